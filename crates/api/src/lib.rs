@@ -22,13 +22,16 @@ use utoipa::OpenApi;
 mod tests;
 
 mod error;
+mod metrics;
 mod models;
 mod rate_limit;
 
 use error::{ApiError, ErrorCode, Json};
+use metrics::{Metrics, Outcome};
 use models::{
-    ArticleDto, ErrorResponse, EvaluateRequest, EvaluateResponse, ExtractRequest, ExtractResponse, HealthResponse,
-    MarkdownOptionsDto, ReadabilityOptionsDto, ReadableOptionsDto, TransformRequest, TransformResponse,
+    ArticleDto, ArticleLinkDto, ErrorResponse, EvaluateRequest, EvaluateResponse, ExtractRequest, ExtractResponse,
+    ExtractionStatsDto, HealthResponse, MarkdownOptionsDto, ReadabilityOptionsDto, ReadableOptionsDto, TimingsDto,
+    TransformRequest, TransformResponse,
 };
 use rate_limit::{RateLimitConfig, RateLimitDecision, RateLimiter};
 
@@ -50,14 +53,14 @@ impl Limit {
         }
     }
 
-    fn from_env_usize(self) -> usize {
+    fn env_usize(self) -> usize {
         env::var(self.env_var())
             .ok()
             .and_then(|value| value.parse().ok())
             .unwrap_or_else(|| self.into())
     }
 
-    fn from_env_u64(self) -> u64 {
+    fn env_u64(self) -> u64 {
         env::var(self.env_var())
             .ok()
             .and_then(|value| value.parse().ok())
@@ -101,6 +104,7 @@ struct AppState {
     client: reqwest::Client,
     config: Config,
     rate_limiter: Option<RateLimiter>,
+    metrics: Option<Metrics>,
 }
 
 impl AppState {
@@ -121,7 +125,9 @@ impl AppState {
             None
         };
 
-        Self { client, config, rate_limiter }
+        let metrics = config.metrics_enabled.then(Metrics::default);
+
+        Self { client, config, rate_limiter, metrics }
     }
 
     async fn fetch_url(&self, url: &str) -> Result<FetchedDocument, ApiError> {
@@ -165,18 +171,7 @@ impl AppState {
             }
 
             ensure_html_content_type(response.headers())?;
-            let bytes = response.bytes().await.map_err(|err| {
-                tracing::warn!(url = %url, error = %err, "failed to read upstream body");
-                ApiError::fetch_failed(err)
-            })?;
-
-            if bytes.len() > self.config.max_fetch_bytes {
-                return Err(ApiError::new(
-                    StatusCode::PAYLOAD_TOO_LARGE,
-                    ErrorCode::DocumentTooLarge,
-                    "fetched document is too large",
-                ));
-            }
+            let bytes = read_capped(response, self.config.max_fetch_bytes, &url).await?;
 
             tracing::info!(url = %url, bytes = bytes.len(), "fetched document");
 
@@ -193,20 +188,50 @@ struct FetchedDocument {
     html: String,
 }
 
+/// Read a response body chunk by chunk, refusing it as soon as it, or its
+/// declared `Content-Length`, passes `limit` bytes.
+async fn read_capped(mut response: reqwest::Response, limit: usize, url: &Url) -> Result<Vec<u8>, ApiError> {
+    if response.content_length().is_some_and(|length| length > limit as u64) {
+        return Err(document_too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|err| {
+        tracing::warn!(url = %url, error = %err, "failed to read upstream body");
+        ApiError::fetch_failed(err)
+    })? {
+        if bytes.len() + chunk.len() > limit {
+            return Err(document_too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn document_too_large() -> ApiError {
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::DocumentTooLarge,
+        "fetched document is too large",
+    )
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(healthz, extract, evaluate, transform),
     components(schemas(
         ArticleDto,
+        ArticleLinkDto,
         ErrorResponse,
         EvaluateRequest,
         EvaluateResponse,
         ExtractRequest,
         ExtractResponse,
+        ExtractionStatsDto,
         HealthResponse,
         MarkdownOptionsDto,
         ReadabilityOptionsDto,
         ReadableOptionsDto,
+        TimingsDto,
         TransformRequest,
         TransformResponse
     ))
@@ -222,6 +247,7 @@ pub struct Config {
     request_timeout_secs: u64,
     allowed_origins: Vec<String>,
     allow_private_network: bool,
+    metrics_enabled: bool,
     rate_limit: RateLimitConfig,
 }
 
@@ -229,15 +255,16 @@ impl Config {
     pub fn from_env() -> Self {
         Self {
             port: Limit::env_u16("PORT", 3000),
-            max_body_bytes: Limit::MaxBodyBytes.from_env_usize(),
-            max_fetch_bytes: Limit::MaxFetchBytes.from_env_usize(),
-            redirect_limit: Limit::Redirect.from_env_usize(),
-            request_timeout_secs: Limit::RequestTimeoutSecs.from_env_u64(),
+            max_body_bytes: Limit::MaxBodyBytes.env_usize(),
+            max_fetch_bytes: Limit::MaxFetchBytes.env_usize(),
+            redirect_limit: Limit::Redirect.env_usize(),
+            request_timeout_secs: Limit::RequestTimeoutSecs.env_u64(),
             allowed_origins: env::var("LECTITO_ALLOWED_ORIGINS")
                 .ok()
                 .map(|value| split_csv(&value))
                 .unwrap_or_default(),
             allow_private_network: Limit::env_bool("LECTITO_ALLOW_PRIVATE_NETWORK", false),
+            metrics_enabled: Limit::env_bool("LECTITO_METRICS_ENABLED", false),
             rate_limit: RateLimitConfig {
                 enabled: Limit::env_bool("LECTITO_RATE_LIMIT_ENABLED", false),
                 redis_url: env::var("LECTITO_REDIS_URL").unwrap_or_else(|_| "redis://lectito-redis:6379".to_owned()),
@@ -252,12 +279,17 @@ pub async fn app(config: Config) -> Router {
     let state = AppState::new(config.clone()).await;
     let timeout = Duration::from_secs(config.request_timeout_secs);
 
-    Router::new()
+    let mut router = Router::new()
         .route("/healthz", get(healthz))
         .route("/openapi.json", get(openapi))
         .route("/v1/extract", post(extract))
         .route("/v1/evaluate", post(evaluate))
-        .route("/v1/transform", post(transform))
+        .route("/v1/transform", post(transform));
+    if config.metrics_enabled {
+        router = router.route("/metrics", get(metrics));
+    }
+
+    router
         .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit))
         .layer(TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, timeout))
         .layer(RequestBodyLimitLayer::new(config.max_body_bytes))
@@ -320,21 +352,37 @@ async fn extract(
     let started = Instant::now();
     let diagnostics = request.diagnostics;
     let options = request.options.unwrap_or_default().into_options();
-    let fetched = state.fetch_url(&request.url).await?;
+    let fetched = state.fetch_url(&request.url).await.inspect_err(|_| {
+        if let Some(metrics) = &state.metrics {
+            metrics.record_error();
+        }
+    })?;
+    let fetch_time = started.elapsed();
     let report = lectito::extract_with_diagnostics(&fetched.html, Some(fetched.final_url.as_str()), &options).map_err(
         |err| {
             tracing::warn!(url = %fetched.final_url, error = %err, "extraction failed");
+            if let Some(metrics) = &state.metrics {
+                metrics.record_error();
+            }
             ApiError::core(ErrorCode::ExtractFailed, err)
         },
     )?;
+    if let Some(metrics) = &state.metrics {
+        let outcome = if report.article.is_some() { Outcome::Article } else { Outcome::NoArticle };
+        metrics.record_extraction(fetch_time, &report.timings, outcome);
+    }
 
     let content_length = report.article.as_ref().map(|a| a.length).unwrap_or(0);
+    let timings = TimingsDto::new(fetch_time, &report.timings);
     let article = report.article.map(ArticleDto::from);
 
     tracing::info!(
         url = %fetched.final_url,
         found_article = article.is_some(),
         content_length,
+        fetch_ms = timings.fetch_ms,
+        parse_ms = timings.parse_ms,
+        extract_ms = timings.extract_ms,
         elapsed_ms = started.elapsed().as_millis(),
         "extract complete"
     );
@@ -342,6 +390,7 @@ async fn extract(
     Ok(axum::Json(ExtractResponse {
         article,
         diagnostics: diagnostics.then(|| serde_json::to_value(report.diagnostics).unwrap_or_default()),
+        timings,
         elapsed_ms: started.elapsed().as_millis(),
     }))
 }
@@ -464,6 +513,16 @@ async fn normalize_errors(req: Request, next: Next) -> Response {
     }
 }
 
+/// Prometheus metrics, routed only when `LECTITO_METRICS_ENABLED` is set.
+async fn metrics(State(state): State<AppState>) -> Response {
+    let body = state.metrics.as_ref().map(Metrics::render).unwrap_or_default();
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
 async fn openapi() -> axum::Json<utoipa::openapi::OpenApi> {
     axum::Json(ApiDoc::openapi())
 }
@@ -0,0 +1,121 @@
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lectito::Timings;
+
+/// Upper bounds, in seconds, of the stage latency histogram buckets.
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+const STAGES: [&str; 4] = ["fetch", "parse", "extract", "postprocess"];
+
+/// Result label of `lectito_extract_requests_total`.
+#[derive(Clone, Copy)]
+pub enum Outcome {
+    Article,
+    NoArticle,
+    Error,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Article => "article",
+            Self::NoArticle => "no_article",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Extraction latency and outcome counters served at `/metrics` in the
+/// Prometheus text format.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    stages: [Histogram; 4],
+    outcomes: [u64; 3],
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+impl Metrics {
+    /// Record a finished extraction and the time spent in each stage.
+    pub fn record_extraction(&self, fetch: Duration, timings: &Timings, outcome: Outcome) {
+        let mut registry = self.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let durations = [fetch, timings.parse, timings.extract, timings.postprocess];
+        for (histogram, duration) in registry.stages.iter_mut().zip(durations) {
+            histogram.observe(duration);
+        }
+        registry.outcomes[outcome as usize] += 1;
+    }
+
+    /// Count an extract request that failed before producing a result.
+    pub fn record_error(&self) {
+        let mut registry = self.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        registry.outcomes[Outcome::Error as usize] += 1;
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut output = String::new();
+        output.push_str("# HELP lectito_extract_stage_seconds Time spent in each extraction stage.\n");
+        output.push_str("# TYPE lectito_extract_stage_seconds histogram\n");
+        for (stage, histogram) in STAGES.iter().zip(&registry.stages) {
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(
+                    output,
+                    r#"lectito_extract_stage_seconds_bucket{{stage="{stage}",le="{bound}"}} {count}"#
+                );
+            }
+            let _ = writeln!(
+                output,
+                r#"lectito_extract_stage_seconds_bucket{{stage="{stage}",le="+Inf"}} {}"#,
+                histogram.count
+            );
+            let _ = writeln!(
+                output,
+                r#"lectito_extract_stage_seconds_sum{{stage="{stage}"}} {}"#,
+                histogram.sum
+            );
+            let _ = writeln!(
+                output,
+                r#"lectito_extract_stage_seconds_count{{stage="{stage}"}} {}"#,
+                histogram.count
+            );
+        }
+        output.push_str("# HELP lectito_extract_requests_total Extract requests by result.\n");
+        output.push_str("# TYPE lectito_extract_requests_total counter\n");
+        for outcome in [Outcome::Article, Outcome::NoArticle, Outcome::Error] {
+            let _ = writeln!(
+                output,
+                r#"lectito_extract_requests_total{{result="{}"}} {}"#,
+                outcome.label(),
+                registry.outcomes[outcome as usize]
+            );
+        }
+        output
+    }
+}
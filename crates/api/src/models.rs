@@ -1,4 +1,9 @@
-use lectito::{MarkdownOptions, MediaRetention, ReadabilityOptions, ReadableOptions};
+use std::time::Duration;
+
+use lectito::{
+    ExtractionStrategy, FootnoteMode, LinkCleanup, LinkKind, LinkStyle, MarkdownOptions, MediaRetention, PatternGroup,
+    ReadabilityOptions, ReadableOptions, Timings, Typography,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -22,6 +27,120 @@ impl From<MediaRetentionDto> for MediaRetention {
     }
 }
 
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum FootnoteModeDto {
+    Markdown,
+    Strip,
+}
+
+impl From<FootnoteModeDto> for FootnoteMode {
+    fn from(value: FootnoteModeDto) -> Self {
+        match value {
+            FootnoteModeDto::Markdown => Self::Markdown,
+            FootnoteModeDto::Strip => Self::Strip,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum LinkStyleDto {
+    Inline,
+    Reference,
+    Footnotes,
+}
+
+impl From<LinkStyleDto> for LinkStyle {
+    fn from(value: LinkStyleDto) -> Self {
+        match value {
+            LinkStyleDto::Inline => Self::Inline,
+            LinkStyleDto::Reference => Self::Reference,
+            LinkStyleDto::Footnotes => Self::Footnotes,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum LinkCleanupDto {
+    None,
+    StripTracking,
+    UnwrapRedirects,
+}
+
+impl From<LinkCleanupDto> for LinkCleanup {
+    fn from(value: LinkCleanupDto) -> Self {
+        match value {
+            LinkCleanupDto::None => Self::None,
+            LinkCleanupDto::StripTracking => Self::StripTracking,
+            LinkCleanupDto::UnwrapRedirects => Self::UnwrapRedirects,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum TypographyDto {
+    Preserve,
+    Smart,
+    Plain,
+}
+
+impl From<TypographyDto> for Typography {
+    fn from(value: TypographyDto) -> Self {
+        match value {
+            TypographyDto::Preserve => Self::Preserve,
+            TypographyDto::Smart => Self::Smart,
+            TypographyDto::Plain => Self::Plain,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum ExtractionStrategyDto {
+    Auto,
+    FullBodyCleanup,
+    LiveBlog,
+    Transcript,
+    Density,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum PatternGroupDto {
+    Positive,
+    Negative,
+    Unlikely,
+    TrailingChrome,
+    DocChrome,
+}
+
+impl From<PatternGroupDto> for PatternGroup {
+    fn from(value: PatternGroupDto) -> Self {
+        match value {
+            PatternGroupDto::Positive => Self::Positive,
+            PatternGroupDto::Negative => Self::Negative,
+            PatternGroupDto::Unlikely => Self::Unlikely,
+            PatternGroupDto::TrailingChrome => Self::TrailingChrome,
+            PatternGroupDto::DocChrome => Self::DocChrome,
+        }
+    }
+}
+
+impl From<ExtractionStrategyDto> for ExtractionStrategy {
+    fn from(value: ExtractionStrategyDto) -> Self {
+        match value {
+            ExtractionStrategyDto::Auto => Self::Auto,
+            ExtractionStrategyDto::FullBodyCleanup => Self::FullBodyCleanup,
+            ExtractionStrategyDto::LiveBlog => Self::LiveBlog,
+            ExtractionStrategyDto::Transcript => Self::Transcript,
+            ExtractionStrategyDto::Density => Self::Density,
+        }
+    }
+}
+
 #[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthResponse {
@@ -44,10 +163,33 @@ pub struct ExtractResponse {
     pub article: Option<ArticleDto>,
     #[schema(value_type = Option<Object>)]
     pub diagnostics: Option<serde_json::Value>,
+    pub timings: TimingsDto,
     #[schema(value_type = u64)]
     pub elapsed_ms: u128,
 }
 
+/// Milliseconds spent in each stage of an extract request.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingsDto {
+    pub fetch_ms: f64,
+    pub parse_ms: f64,
+    pub extract_ms: f64,
+    pub postprocess_ms: f64,
+}
+
+impl TimingsDto {
+    pub fn new(fetch: Duration, timings: &Timings) -> Self {
+        let milliseconds = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        Self {
+            fetch_ms: milliseconds(fetch),
+            parse_ms: milliseconds(timings.parse),
+            extract_ms: milliseconds(timings.extract),
+            postprocess_ms: milliseconds(timings.postprocess),
+        }
+    }
+}
+
 #[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EvaluateRequest {
@@ -93,7 +235,9 @@ pub struct ErrorBody {
 #[serde(rename_all = "camelCase")]
 pub struct ArticleDto {
     title: Option<String>,
+    raw_title: Option<String>,
     byline: Option<String>,
+    author_bio: Option<String>,
     dir: Option<String>,
     lang: Option<String>,
     content: String,
@@ -103,16 +247,79 @@ pub struct ArticleDto {
     excerpt: Option<String>,
     site_name: Option<String>,
     published_time: Option<String>,
+    modified_time: Option<String>,
+    published: Option<String>,
+    modified: Option<String>,
     image: Option<String>,
     domain: Option<String>,
     favicon: Option<String>,
+    canonical_url: Option<String>,
+    amp_url: Option<String>,
+    keywords: Vec<String>,
+    section: Option<String>,
+    is_paywalled: Option<bool>,
+    is_sponsored: Option<bool>,
+    links: Vec<ArticleLinkDto>,
+    stats: ExtractionStatsDto,
+}
+
+/// Sizes and counts from extraction. Stage timings are reported on the
+/// response.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionStatsDto {
+    input_bytes: usize,
+    preprocessed_bytes: usize,
+    candidates_scanned: usize,
+    candidates_scored: usize,
+    removed_elements: usize,
+}
+
+impl From<lectito::ExtractionStats> for ExtractionStatsDto {
+    fn from(stats: lectito::ExtractionStats) -> Self {
+        Self {
+            input_bytes: stats.input_bytes,
+            preprocessed_bytes: stats.preprocessed_bytes,
+            candidates_scanned: stats.candidates_scanned,
+            candidates_scored: stats.candidates_scored,
+            removed_elements: stats.removed_elements,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleLinkDto {
+    text: String,
+    url: String,
+    rel: Vec<String>,
+    kind: LinkKindDto,
+}
+
+#[derive(Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+enum LinkKindDto {
+    Content,
+    Related,
+}
+
+impl From<lectito::ArticleLink> for ArticleLinkDto {
+    fn from(link: lectito::ArticleLink) -> Self {
+        let kind = match link.kind {
+            LinkKind::Related => LinkKindDto::Related,
+            _ => LinkKindDto::Content,
+        };
+        Self { text: link.text, url: link.url, rel: link.rel, kind }
+    }
 }
 
 impl From<lectito::Article> for ArticleDto {
     fn from(article: lectito::Article) -> Self {
         Self {
             title: article.title,
+            raw_title: article.raw_title,
             byline: article.byline,
+            author_bio: article.author_bio,
             dir: article.dir,
             lang: article.lang,
             content: article.content,
@@ -122,9 +329,20 @@ impl From<lectito::Article> for ArticleDto {
             excerpt: article.excerpt,
             site_name: article.site_name,
             published_time: article.published_time,
+            modified_time: article.modified_time,
+            published: article.published.map(|date| date.to_rfc3339()),
+            modified: article.modified.map(|date| date.to_rfc3339()),
             image: article.image,
             domain: article.domain,
             favicon: article.favicon,
+            canonical_url: article.canonical_url,
+            amp_url: article.amp_url,
+            keywords: article.keywords,
+            section: article.section,
+            is_paywalled: article.is_paywalled,
+            is_sponsored: article.is_sponsored,
+            links: article.links.into_iter().map(ArticleLinkDto::from).collect(),
+            stats: article.stats.into(),
         }
     }
 }
@@ -137,12 +355,42 @@ pub struct ReadabilityOptionsDto {
     char_threshold: Option<usize>,
     content_selector: Option<Option<String>>,
     site_profiles: Option<Vec<String>>,
+    site_profile_overrides: Option<Vec<String>>,
+    site_profile_ignores: Option<Vec<String>>,
     mobile_viewport_width: Option<Option<usize>>,
     classes_to_preserve: Option<Vec<String>>,
     keep_classes: Option<bool>,
     disable_json_ld: Option<bool>,
     link_density_modifier: Option<f32>,
     media_retention: Option<MediaRetentionDto>,
+    iframe_allowlist: Option<Vec<String>>,
+    footnotes: Option<FootnoteModeDto>,
+    link_style: Option<LinkStyleDto>,
+    link_cleanup: Option<LinkCleanupDto>,
+    heading_offset: Option<Option<u8>>,
+    normalize_heading_levels: Option<bool>,
+    reading_time_markers: Option<bool>,
+    normalize_text: Option<bool>,
+    typography: Option<TypographyDto>,
+    strategy: Option<ExtractionStrategyDto>,
+    cleanup_dry_run: Option<bool>,
+    keep_hidden: Option<bool>,
+    keep_unlikely_candidates: Option<bool>,
+    keep_iframes: Option<bool>,
+    sibling_threshold: Option<f32>,
+    min_candidate_score: Option<f32>,
+    class_weight: Option<i32>,
+    positive_patterns: Option<Vec<String>>,
+    negative_patterns: Option<Vec<String>>,
+    unlikely_patterns: Option<Vec<String>>,
+    trailing_chrome_patterns: Option<Vec<String>>,
+    doc_chrome_labels: Option<Vec<String>>,
+    replace_pattern_groups: Option<Vec<PatternGroupDto>>,
+    image_target_width: Option<Option<u32>>,
+    max_input_bytes: Option<Option<usize>>,
+    max_candidates: Option<Option<usize>>,
+    max_duration_ms: Option<Option<u64>>,
+    recover_noscript: Option<bool>,
 }
 
 impl ReadabilityOptionsDto {
@@ -163,6 +411,12 @@ impl ReadabilityOptionsDto {
         if let Some(value) = self.site_profiles {
             options.site_profiles = value;
         }
+        if let Some(value) = self.site_profile_overrides {
+            options.site_profile_overrides = value;
+        }
+        if let Some(value) = self.site_profile_ignores {
+            options.site_profile_ignores = value;
+        }
         if let Some(value) = self.mobile_viewport_width {
             options.mobile_viewport_width = value;
         }
@@ -181,6 +435,90 @@ impl ReadabilityOptionsDto {
         if let Some(value) = self.media_retention {
             options.media_retention = value.into();
         }
+        if let Some(value) = self.iframe_allowlist {
+            options.iframe_allowlist = value;
+        }
+        if let Some(value) = self.footnotes {
+            options.footnotes = value.into();
+        }
+        if let Some(value) = self.link_style {
+            options.link_style = value.into();
+        }
+        if let Some(value) = self.link_cleanup {
+            options.link_cleanup = value.into();
+        }
+        if let Some(value) = self.heading_offset {
+            options.heading_offset = value;
+        }
+        if let Some(value) = self.normalize_heading_levels {
+            options.normalize_heading_levels = value;
+        }
+        if let Some(value) = self.reading_time_markers {
+            options.reading_time_markers = value;
+        }
+        if let Some(value) = self.normalize_text {
+            options.normalize_text = value;
+        }
+        if let Some(value) = self.typography {
+            options.typography = value.into();
+        }
+        if let Some(value) = self.strategy {
+            options.strategy = value.into();
+        }
+        if let Some(value) = self.cleanup_dry_run {
+            options.cleanup_dry_run = value;
+        }
+        if let Some(value) = self.keep_hidden {
+            options.keep_hidden = value;
+        }
+        if let Some(value) = self.keep_unlikely_candidates {
+            options.keep_unlikely_candidates = value;
+        }
+        if let Some(value) = self.keep_iframes {
+            options.keep_iframes = value;
+        }
+        if let Some(value) = self.sibling_threshold {
+            options.sibling_threshold = value;
+        }
+        if let Some(value) = self.min_candidate_score {
+            options.min_candidate_score = value;
+        }
+        if let Some(value) = self.class_weight {
+            options.class_weight = value;
+        }
+        if let Some(value) = self.positive_patterns {
+            options.positive_patterns = value;
+        }
+        if let Some(value) = self.negative_patterns {
+            options.negative_patterns = value;
+        }
+        if let Some(value) = self.unlikely_patterns {
+            options.unlikely_patterns = value;
+        }
+        if let Some(value) = self.trailing_chrome_patterns {
+            options.trailing_chrome_patterns = value;
+        }
+        if let Some(value) = self.doc_chrome_labels {
+            options.doc_chrome_labels = value;
+        }
+        if let Some(value) = self.replace_pattern_groups {
+            options.replace_pattern_groups = value.into_iter().map(Into::into).collect();
+        }
+        if let Some(value) = self.image_target_width {
+            options.image_target_width = value;
+        }
+        if let Some(value) = self.max_input_bytes {
+            options.max_input_bytes = value;
+        }
+        if let Some(value) = self.max_candidates {
+            options.max_candidates = value;
+        }
+        if let Some(value) = self.max_duration_ms {
+            options.max_duration = value.map(Duration::from_millis);
+        }
+        if let Some(value) = self.recover_noscript {
+            options.recover_noscript = value;
+        }
         options
     }
 }
@@ -125,13 +125,12 @@ fn caller_ip(headers: &HeaderMap, extensions: &axum::http::Extensions, trust_pro
             return ip.to_string();
         }
 
-        if let Some(forwarded_for) = headers.get("x-forwarded-for").and_then(|value| value.to_str().ok()) {
-            if let Some(ip) = forwarded_for
+        if let Some(forwarded_for) = headers.get("x-forwarded-for").and_then(|value| value.to_str().ok())
+            && let Some(ip) = forwarded_for
                 .split(',')
                 .find_map(|part| part.trim().parse::<IpAddr>().ok())
-            {
-                return ip.to_string();
-            }
+        {
+            return ip.to_string();
         }
     }
 
@@ -43,6 +43,7 @@ fn test_config() -> Config {
         request_timeout_secs: Limit::RequestTimeoutSecs.into(),
         allowed_origins: Vec::new(),
         allow_private_network: true,
+        metrics_enabled: false,
         rate_limit: RateLimitConfig::default(),
     }
 }
@@ -127,6 +128,40 @@ async fn extract_smoke() {
     assert!(body["article"]["markdown"].as_str().unwrap().contains("readability"));
     assert!(body["article"]["content"].as_str().unwrap().contains("<"));
     assert!(body["diagnostics"].is_object());
+    assert!(body["timings"]["extractMs"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn metrics_count_extractions_when_enabled() {
+    let source = html_server().await;
+    let router = app(Config { metrics_enabled: true, ..test_config() }).await;
+    let response = router
+        .clone()
+        .oneshot(json_request(
+            "/v1/extract",
+            json!({ "url": source, "options": { "charThreshold": 20 } }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = router
+        .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("# TYPE lectito_extract_stage_seconds histogram"));
+    assert!(body.contains(r#"lectito_extract_stage_seconds_count{stage="parse"} 1"#));
+    assert!(body.contains(r#"lectito_extract_requests_total{result="article"} 1"#));
+
+    let response = app(test_config())
+        .await
+        .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
@@ -174,6 +209,7 @@ async fn oversized_body_returns_structured_error() {
         request_timeout_secs: Limit::RequestTimeoutSecs.into(),
         allowed_origins: Vec::new(),
         allow_private_network: true,
+        metrics_enabled: false,
         rate_limit: RateLimitConfig::default(),
         port: 0,
     };
@@ -198,3 +234,17 @@ async fn oversized_body_returns_structured_error() {
     let body = body_json(response).await;
     assert_eq!(body["error"]["code"], "document_too_large");
 }
+
+#[tokio::test]
+async fn oversized_fetch_returns_structured_error() {
+    let source = html_server().await;
+    let response = app(Config { max_fetch_bytes: 64, ..test_config() })
+        .await
+        .oneshot(json_request("/v1/extract", json!({ "url": source })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body = body_json(response).await;
+    assert_eq!(body["error"]["code"], "document_too_large");
+}
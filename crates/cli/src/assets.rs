@@ -0,0 +1,454 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use lectito::{Article, Resource};
+use regex::{Captures, Regex};
+use reqwest::blocking::Client;
+use reqwest::header::CONTENT_TYPE;
+use scraper::{Html, Node, Selector};
+
+use crate::fetch::{self, FETCH_TIMEOUT, ReadOptions};
+use crate::utils;
+
+static IMAGE_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("img[src], img[srcset], source[srcset]").expect("valid image selector"));
+
+/// The destination of a Markdown image, `![alt](url`, but not of a link.
+static MARKDOWN_IMAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<prefix>!\[(?:\\.|[^\]\\])*\]\()(?P<url>[^)\s]+)").expect("valid markdown image regex")
+});
+
+/// Where downloaded images end up and how rewritten `src` values point at them.
+#[derive(Debug, PartialEq)]
+pub enum AssetTarget {
+    /// Embed images as `data:` URIs.
+    Inline,
+    /// Write images to `dir` and link them as `link/<file>`.
+    Directory { dir: PathBuf, link: PathBuf },
+}
+
+impl AssetTarget {
+    /// Resolve the target from `--assets-dir` and `--output`.
+    ///
+    /// A relative assets directory is created next to the output file and
+    /// linked relative to it. Without an assets directory, images are inlined.
+    pub fn new(assets_dir: Option<&Path>, output: Option<&Path>) -> Self {
+        let Some(link) = assets_dir else {
+            return Self::Inline;
+        };
+        let dir = match output.and_then(Path::parent) {
+            Some(parent) if link.is_relative() => parent.join(link),
+            _ => link.to_path_buf(),
+        };
+        Self::Directory { dir, link: link.to_path_buf() }
+    }
+}
+
+/// A downloaded image body and its declared content type.
+#[derive(Clone)]
+pub struct Asset {
+    bytes: Vec<u8>,
+    content_type: Option<String>,
+}
+
+impl Asset {
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+}
+
+/// Fetches article images once each, with the user agent and size limit of
+/// `options`, and takes the copies saved in `resources`, the parts of a web
+/// archive input, before downloading.
+pub struct Images<'a> {
+    resources: &'a [Resource],
+    options: &'a ReadOptions,
+    client: Option<Client>,
+    fetched: Vec<(String, Asset)>,
+    failed: BTreeSet<String>,
+}
+
+impl<'a> Images<'a> {
+    pub fn new(resources: &'a [Resource], options: &'a ReadOptions) -> Self {
+        Self { resources, options, client: None, fetched: Vec::new(), failed: BTreeSet::new() }
+    }
+
+    /// Images fetched so far, by URL.
+    pub fn fetched(&self) -> &[(String, Asset)] {
+        &self.fetched
+    }
+
+    /// Fetch every image the article references without rewriting it.
+    /// Failures are logged as warnings.
+    pub fn fetch_all(&mut self, article: &Article) {
+        for url in image_urls(&article.content) {
+            if let Err(error) = self.fetch(&url) {
+                tracing::warn!(url, error = format!("{error:#}"), "skipped image");
+            }
+        }
+    }
+
+    fn fetch(&mut self, url: &str) -> Result<Asset> {
+        if let Some((_, asset)) = self.fetched.iter().find(|(fetched, _)| fetched == url) {
+            return Ok(asset.clone());
+        }
+        if self.failed.contains(url) {
+            anyhow::bail!("already failed");
+        }
+        let asset = match embedded(self.resources, url) {
+            Some(asset) => Ok(asset),
+            None => {
+                let limit = self.options.max_input_bytes;
+                self.client().and_then(|client| download(client, url, limit))
+            }
+        };
+        match asset {
+            Ok(asset) => {
+                self.fetched.push((url.to_string(), asset.clone()));
+                Ok(asset)
+            }
+            Err(error) => {
+                self.failed.insert(url.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    fn client(&mut self) -> Result<&Client> {
+        if self.client.is_none() {
+            let client = Client::builder()
+                .user_agent(self.options.user_agent())
+                .timeout(FETCH_TIMEOUT)
+                .build()
+                .context("failed to build HTTP client for image downloads")?;
+            self.client = Some(client);
+        }
+        Ok(self.client.as_ref().expect("client was just built"))
+    }
+}
+
+/// Download images referenced by the article and rewrite their URLs.
+///
+/// Images that fail to download keep their original URL and are reported on
+/// stderr.
+pub fn archive_images(article: &mut Article, target: &AssetTarget, images: &mut Images<'_>) -> Result<()> {
+    localize_images(article, target, |url| images.fetch(url))
+}
+
+/// The image saved at `url` in a web archive.
+fn embedded(resources: &[Resource], url: &str) -> Option<Asset> {
+    resources
+        .iter()
+        .find(|resource| resource.url == url)
+        .map(|resource| Asset { bytes: resource.data.clone(), content_type: resource.content_type.clone() })
+}
+
+fn localize_images(
+    article: &mut Article, target: &AssetTarget, mut fetch: impl FnMut(&str) -> Result<Asset>,
+) -> Result<()> {
+    let urls = image_urls(&article.content);
+    if urls.is_empty() {
+        return Ok(());
+    }
+    if let AssetTarget::Directory { dir, .. } = target {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let mut local = HashMap::new();
+    for url in urls {
+        match fetch(&url) {
+            Ok(asset) => {
+                local.insert(url.clone(), store(target, &url, &asset)?);
+            }
+            Err(error) => tracing::warn!(url, error = format!("{error:#}"), "skipped image"),
+        }
+    }
+
+    article.content = rewrite_html(&article.content, &local);
+    article.markdown = MARKDOWN_IMAGE
+        .replace_all(&article.markdown, |captures: &Captures<'_>| {
+            let url = &captures["url"];
+            let url = local.get(url).map_or(url, String::as_str);
+            format!("{}{url}", &captures["prefix"])
+        })
+        .into_owned();
+    Ok(())
+}
+
+/// Point `img[src]` and `srcset` candidates at their local copies, leaving
+/// link targets and text alone.
+fn rewrite_html(html: &str, local: &HashMap<String, String>) -> String {
+    let mut document = Html::parse_fragment(html);
+    let ids: Vec<_> = document.select(&IMAGE_SELECTOR).map(|element| element.id()).collect();
+    for id in ids {
+        let Some(mut node) = document.tree.get_mut(id) else {
+            continue;
+        };
+        let Node::Element(element) = node.value() else {
+            continue;
+        };
+        for (name, value) in &mut element.attrs {
+            let rewritten = match &*name.local {
+                "src" => local.get(value.trim()).cloned(),
+                "srcset" => Some(rewrite_srcset(value, local)),
+                _ => None,
+            };
+            if let Some(rewritten) = rewritten {
+                *value = rewritten.as_str().into();
+            }
+        }
+    }
+    document.root_element().inner_html()
+}
+
+fn rewrite_srcset(srcset: &str, local: &HashMap<String, String>) -> String {
+    srcset
+        .split(',')
+        .map(|candidate| {
+            let candidate = candidate.trim();
+            let (url, descriptor) = candidate.split_once(char::is_whitespace).unwrap_or((candidate, ""));
+            match (local.get(url), descriptor.trim()) {
+                (Some(path), "") => path.clone(),
+                (Some(path), descriptor) => format!("{path} {descriptor}"),
+                (None, _) => candidate.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn image_urls(html: &str) -> BTreeSet<String> {
+    let document = Html::parse_fragment(html);
+    let mut urls = BTreeSet::new();
+    for element in document.select(&IMAGE_SELECTOR) {
+        if let Some(src) = element.value().attr("src") {
+            urls.insert(src.trim().to_string());
+        }
+        if let Some(srcset) = element.value().attr("srcset") {
+            urls.extend(
+                srcset
+                    .split(',')
+                    .filter_map(|candidate| candidate.split_whitespace().next())
+                    .map(str::to_string),
+            );
+        }
+    }
+    urls.retain(|url| url.starts_with("http://") || url.starts_with("https://"));
+    urls
+}
+
+/// Download an image, refusing bodies larger than `limit`.
+fn download(client: &Client, url: &str, limit: Option<usize>) -> Result<Asset> {
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("failed to fetch {url}"))?
+        .error_for_status()
+        .with_context(|| format!("failed to fetch {url}"))?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.split(';').next().unwrap_or(value).trim().to_ascii_lowercase());
+    if let Some(kind) = content_type.as_deref()
+        && !kind.starts_with("image/")
+    {
+        anyhow::bail!("unexpected content type {kind}");
+    }
+    let bytes = fetch::read_limited(response, url, limit)?;
+    Ok(Asset { bytes, content_type })
+}
+
+fn store(target: &AssetTarget, url: &str, asset: &Asset) -> Result<String> {
+    let extension = extension(url, asset.content_type.as_deref());
+    match target {
+        AssetTarget::Inline => Ok(format!(
+            "data:{};base64,{}",
+            asset.content_type.as_deref().unwrap_or_else(|| mime_type(extension)),
+            STANDARD.encode(&asset.bytes)
+        )),
+        AssetTarget::Directory { dir, link } => {
+            let name = format!("{:016x}.{extension}", utils::fnv1a64(url.as_bytes()));
+            let path = dir.join(&name);
+            fs::write(&path, &asset.bytes).with_context(|| format!("failed to write {}", path.display()))?;
+            Ok(link.join(name).to_string_lossy().replace('\\', "/"))
+        }
+    }
+}
+
+fn extension(url: &str, content_type: Option<&str>) -> &'static str {
+    match content_type {
+        Some("image/png") => return "png",
+        Some("image/jpeg" | "image/jpg") => return "jpg",
+        Some("image/gif") => return "gif",
+        Some("image/webp") => return "webp",
+        Some("image/avif") => return "avif",
+        Some("image/svg+xml") => return "svg",
+        _ => {}
+    }
+
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let suffix = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, suffix)| suffix.to_ascii_lowercase());
+    match suffix.as_deref() {
+        Some("png") => "png",
+        Some("jpg" | "jpeg") => "jpg",
+        Some("gif") => "gif",
+        Some("webp") => "webp",
+        Some("avif") => "avif",
+        Some("svg") => "svg",
+        _ => "img",
+    }
+}
+
+fn mime_type(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "jpg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article;
+
+    fn png(_: &str) -> Result<Asset> {
+        Ok(Asset { bytes: b"png".to_vec(), content_type: Some("image/png".to_string()) })
+    }
+
+    #[test]
+    fn collects_src_and_srcset_urls() {
+        let urls = image_urls(
+            r#"<p><img src="https://example.com/a.png" srcset="https://example.com/a@2x.png 2x, /rel.png 3x"><img src="data:image/gif;base64,R0lG"></p>"#,
+        );
+
+        assert_eq!(
+            urls.into_iter().collect::<Vec<_>>(),
+            vec!["https://example.com/a.png", "https://example.com/a@2x.png"]
+        );
+    }
+
+    #[test]
+    fn inlines_images_as_data_uris() {
+        let mut article = Article {
+            markdown: "![A](https://example.com/a.png?w=1&h=2)".to_string(),
+            ..article(r#"<img src="https://example.com/a.png?w=1&amp;h=2" alt="A">"#)
+        };
+
+        localize_images(&mut article, &AssetTarget::Inline, png).unwrap();
+
+        assert_eq!(article.content, r#"<img alt="A" src="data:image/png;base64,cG5n">"#);
+        assert_eq!(article.markdown, "![A](data:image/png;base64,cG5n)");
+    }
+
+    #[test]
+    fn writes_images_next_to_output() {
+        let root = std::env::temp_dir().join(format!("lectito-assets-test-{}", std::process::id()));
+        let target = AssetTarget::new(Some(Path::new("images")), Some(&root.join("article.md")));
+        let mut article = Article {
+            markdown: "![](https://example.com/a.png)".to_string(),
+            ..article(r#"<img src="https://example.com/a.png">"#)
+        };
+
+        localize_images(&mut article, &target, png).unwrap();
+
+        let name = format!("{:016x}.png", utils::fnv1a64(b"https://example.com/a.png"));
+        assert_eq!(fs::read(root.join("images").join(&name)).unwrap(), b"png");
+        assert_eq!(article.markdown, format!("![](images/{name})"));
+        assert!(article.content.contains(&format!("src=\"images/{name}\"")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rewrites_images_but_not_links_to_them() {
+        let mut article = Article {
+            markdown: "[https://example.com/a.png](https://example.com/a.png) ![](https://example.com/a.png)"
+                .to_string(),
+            ..article(
+                r#"<a href="https://example.com/a.png">https://example.com/a.png</a><img src="https://example.com/a.png" srcset="https://example.com/a.png 1x, https://example.com/b.png 2x">"#,
+            )
+        };
+
+        localize_images(&mut article, &AssetTarget::Inline, |url| match url.ends_with("a.png") {
+            true => png(url),
+            false => anyhow::bail!("offline"),
+        })
+        .unwrap();
+
+        assert_eq!(
+            article.content,
+            r#"<a href="https://example.com/a.png">https://example.com/a.png</a><img src="data:image/png;base64,cG5n" srcset="data:image/png;base64,cG5n 1x, https://example.com/b.png 2x">"#
+        );
+        assert_eq!(
+            article.markdown,
+            "[https://example.com/a.png](https://example.com/a.png) ![](data:image/png;base64,cG5n)"
+        );
+    }
+
+    #[test]
+    fn keeps_original_url_when_download_fails() {
+        let mut article = Article {
+            markdown: "![](https://example.com/a.png)".to_string(),
+            ..article(r#"<img src="https://example.com/a.png">"#)
+        };
+
+        localize_images(&mut article, &AssetTarget::Inline, |_| anyhow::bail!("offline")).unwrap();
+
+        assert_eq!(article.markdown, "![](https://example.com/a.png)");
+    }
+
+    #[test]
+    fn guesses_extension_from_content_type_then_url() {
+        assert_eq!(extension("https://example.com/a", Some("image/webp")), "webp");
+        assert_eq!(extension("https://example.com/a.JPEG?x=1", None), "jpg");
+        assert_eq!(extension("https://example.com/a", None), "img");
+    }
+
+    #[test]
+    fn refuses_images_over_the_size_limit() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let address = listener.local_addr().expect("test server address");
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept test request");
+            let mut head = Vec::new();
+            let mut byte = [0];
+            while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).is_ok_and(|read| read == 1) {
+                head.push(byte[0]);
+            }
+            let body = [0_u8; 64];
+            let response =
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 64\r\nConnection: close\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes());
+            let _ = stream.write_all(&body);
+        });
+
+        let options = ReadOptions { max_input_bytes: Some(16), ..Default::default() };
+        let mut images = Images::new(&[], &options);
+        let error = images.fetch(&format!("http://{address}/tide.png")).err().unwrap();
+        server.join().expect("join test server");
+        assert!(
+            format!("{error:#}").contains("larger than --max-input-bytes 16"),
+            "{error:#}"
+        );
+    }
+}
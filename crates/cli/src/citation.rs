@@ -0,0 +1,315 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use lectito::Article;
+use reqwest::Url;
+use serde_json::json;
+
+use crate::cli::MetadataFormat;
+
+/// Citation for the article, accessed today.
+pub fn render(article: &Article, source: Option<&str>, format: MetadataFormat, pretty: bool) -> Result<String> {
+    let citation = Citation::new(article, source, today());
+    match format {
+        MetadataFormat::Bibtex => Ok(citation.bibtex()),
+        MetadataFormat::Csl => {
+            let record = json!([citation.csl()]);
+            match pretty {
+                true => serde_json::to_string_pretty(&record).context("failed to serialize CSL-JSON"),
+                false => serde_json::to_string(&record).context("failed to serialize CSL-JSON"),
+            }
+        }
+    }
+}
+
+/// A calendar date as year, month, and day.
+type Date = (i64, u32, u32);
+
+struct Citation<'a> {
+    article: &'a Article,
+    authors: Vec<Name>,
+    url: Option<String>,
+    issued: Option<Date>,
+    accessed: Date,
+}
+
+/// A person split into given and family names, or an organization kept whole.
+enum Name {
+    Person { given: String, family: String },
+    Literal(String),
+}
+
+impl<'a> Citation<'a> {
+    fn new(article: &'a Article, source: Option<&str>, accessed: Date) -> Self {
+        let authors = article.byline.as_deref().map(split_authors).unwrap_or_default();
+        let url = article
+            .canonical_url
+            .as_deref()
+            .or(source)
+            .filter(|url| Url::parse(url).is_ok())
+            .map(str::to_string);
+        let issued = article
+            .published
+            .and_then(|published| parse_date(&published.format("%Y-%m-%d").to_string()));
+        Self { article, authors, url, issued, accessed }
+    }
+
+    /// A biblatex `@online` entry.
+    fn bibtex(&self) -> String {
+        let mut fields = Vec::new();
+        if !self.authors.is_empty() {
+            let authors: Vec<String> = self
+                .authors
+                .iter()
+                .map(|name| match name {
+                    Name::Person { given, family } => format!("{} {}", bibtex_escape(given), bibtex_escape(family)),
+                    Name::Literal(name) => format!("{{{}}}", bibtex_escape(name)),
+                })
+                .collect();
+            fields.push(("author", authors.join(" and ")));
+        }
+        if let Some(title) = non_empty(self.article.title.as_deref()) {
+            // Double braces keep the title's capitalization.
+            fields.push(("title", format!("{{{}}}", bibtex_escape(title))));
+        }
+        if let Some(date) = self.issued {
+            fields.push(("date", format_date(date)));
+        }
+        if let Some(site_name) = non_empty(self.article.site_name.as_deref()) {
+            fields.push(("organization", bibtex_escape(site_name)));
+        }
+        if let Some(url) = &self.url {
+            fields.push(("url", url.replace(['{', '}'], "")));
+        }
+        fields.push(("urldate", format_date(self.accessed)));
+        if let Some(lang) = non_empty(self.article.lang.as_deref()) {
+            fields.push(("langid", bibtex_escape(lang)));
+        }
+
+        let mut entry = format!("@online{{{},\n", self.key());
+        for (name, value) in fields {
+            entry.push_str(&format!("  {name} = {{{value}}},\n"));
+        }
+        entry.push('}');
+        entry
+    }
+
+    /// A CSL-JSON `webpage` item.
+    fn csl(&self) -> serde_json::Value {
+        let mut item = serde_json::Map::new();
+        item.insert("id".to_string(), json!(self.key()));
+        item.insert("type".to_string(), json!("webpage"));
+        if let Some(title) = non_empty(self.article.title.as_deref()) {
+            item.insert("title".to_string(), json!(title));
+        }
+        if !self.authors.is_empty() {
+            let authors: Vec<_> = self
+                .authors
+                .iter()
+                .map(|name| match name {
+                    Name::Person { given, family } => json!({ "given": given, "family": family }),
+                    Name::Literal(name) => json!({ "literal": name }),
+                })
+                .collect();
+            item.insert("author".to_string(), json!(authors));
+        }
+        if let Some(date) = self.issued {
+            item.insert("issued".to_string(), csl_date(date));
+        }
+        if let Some(site_name) = non_empty(self.article.site_name.as_deref()) {
+            item.insert("container-title".to_string(), json!(site_name));
+        }
+        if let Some(url) = &self.url {
+            item.insert("URL".to_string(), json!(url));
+        }
+        item.insert("accessed".to_string(), csl_date(self.accessed));
+        if let Some(excerpt) = non_empty(self.article.excerpt.as_deref()) {
+            item.insert("abstract".to_string(), json!(excerpt));
+        }
+        if let Some(lang) = non_empty(self.article.lang.as_deref()) {
+            item.insert("language".to_string(), json!(lang));
+        }
+        serde_json::Value::Object(item)
+    }
+
+    /// Citation key from the first author's family name, the year, and the
+    /// first significant title word, such as `roe2026tides`.
+    fn key(&self) -> String {
+        let author = self.authors.first().map(|name| match name {
+            Name::Person { family, .. } => family.as_str(),
+            Name::Literal(name) => name.as_str(),
+        });
+        let host = self
+            .url
+            .as_deref()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(|host| host.trim_start_matches("www.").to_string()));
+        let lead = author
+            .map(str::to_string)
+            .or_else(|| host.and_then(|host| host.split('.').next().map(str::to_string)))
+            .unwrap_or_default();
+        let word = self
+            .article
+            .title
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(key_part)
+            .find(|word| word.len() > 3)
+            .unwrap_or_default();
+        let year = self.issued.map(|(year, _, _)| year.to_string()).unwrap_or_default();
+        let key = format!("{}{year}{word}", key_part(&lead));
+        if key.is_empty() { "online".to_string() } else { key }
+    }
+}
+
+/// Authors from a byline such as "Jane Roe and John Doe" or "Jane Roe, John
+/// Doe". Single words are treated as organizations.
+fn split_authors(byline: &str) -> Vec<Name> {
+    byline
+        .split([',', ';', '&'])
+        .flat_map(|part| part.split(" and "))
+        .map(|name| name.split_whitespace().collect::<Vec<_>>())
+        .filter(|words| !words.is_empty())
+        .map(|words| match words.split_last() {
+            Some((family, given)) if !given.is_empty() => {
+                Name::Person { given: given.join(" "), family: family.to_string() }
+            }
+            _ => Name::Literal(words.join(" ")),
+        })
+        .collect()
+}
+
+fn key_part(text: &str) -> String {
+    text.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn bibtex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.split_whitespace().collect::<Vec<_>>().join(" ").chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '{' | '}' | '\\' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn parse_date(date: &str) -> Option<Date> {
+    let mut parts = date.splitn(3, '-');
+    Some((
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+    ))
+}
+
+fn format_date((year, month, day): Date) -> String {
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn csl_date((year, month, day): Date) -> serde_json::Value {
+    json!({ "date-parts": [[year, month, day]] })
+}
+
+/// Today's UTC date.
+fn today() -> Date {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    civil_date((seconds / 86_400) as i64)
+}
+
+/// Proleptic Gregorian date for a count of days since 1970-01-01.
+pub fn civil_date(days: i64) -> Date {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// A news article with every field a citation reads.
+    fn cited() -> Article {
+        Article {
+            title: Some("On Tides & Currents".to_string()),
+            byline: Some("Jane Roe and Reuters".to_string()),
+            excerpt: Some("How the moon moves the sea.".to_string()),
+            site_name: Some("Example News".to_string()),
+            published_time: Some("2026-03-01T09:00:00Z".to_string()),
+            published: "2026-03-01T09:00:00+00:00".parse().ok(),
+            canonical_url: Some("https://example.com/tides".to_string()),
+            ..testing::article("")
+        }
+    }
+
+    #[test]
+    fn renders_bibtex_online_entry() {
+        let article = cited();
+        let citation = Citation::new(&article, None, (2026, 10, 16));
+
+        assert_eq!(
+            citation.bibtex(),
+            "@online{roe2026tides,\n  \
+             author = {Jane Roe and {Reuters}},\n  \
+             title = {{On Tides \\& Currents}},\n  \
+             date = {2026-03-01},\n  \
+             organization = {Example News},\n  \
+             url = {https://example.com/tides},\n  \
+             urldate = {2026-10-16},\n  \
+             langid = {en},\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn renders_csl_json_item() {
+        let article = cited();
+        let citation = Citation::new(&article, Some("https://example.com/tides?ref=feed"), (2026, 10, 16));
+
+        assert_eq!(
+            citation.csl(),
+            json!({
+                "id": "roe2026tides",
+                "type": "webpage",
+                "title": "On Tides & Currents",
+                "author": [{ "given": "Jane", "family": "Roe" }, { "literal": "Reuters" }],
+                "issued": { "date-parts": [[2026, 3, 1]] },
+                "container-title": "Example News",
+                "URL": "https://example.com/tides",
+                "accessed": { "date-parts": [[2026, 10, 16]] },
+                "abstract": "How the moon moves the sea.",
+                "language": "en"
+            })
+        );
+    }
+
+    #[test]
+    fn converts_days_to_civil_dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(20_742), (2026, 10, 16));
+    }
+}
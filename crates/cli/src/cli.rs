@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand, ValueEnum, builder::BoolishValueParser};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum, builder::BoolishValueParser};
 
-use lectito::MediaRetention;
+use lectito::{
+    ExtractionStrategy, FootnoteMode, LinkCleanup, LinkStyle, MediaRetention, PatternGroup, Preset, Typography,
+};
 
 /// Extract readable article content from URLs, AT URIs, files, or stdin.
 #[derive(Debug, Parser)]
@@ -16,6 +18,12 @@ pub struct Cli {
     #[command(flatten)]
     pub extract: ExtractArgs,
 
+    /// Log fetching and extraction steps to stderr. Repeat for more detail.
+    ///
+    /// -v logs debug events and -vv trace events. RUST_LOG overrides both.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -25,24 +33,77 @@ pub enum Commands {
     /// Check whether a document looks readable without extracting it.
     Readable(ReadableArgs),
     /// Print metadata, selected root, cleanup counts, and scoring details.
-    Inspect(InspectArgs),
+    Inspect(Box<InspectArgs>),
     /// Work with llms.txt files and LLM context bundles.
     Llms(LlmsArgs),
+    /// Print version, features, defaults, and site rules as JSON.
+    Info(InfoArgs),
+    /// Check bundled site rules and sync FiveFilters site configs.
+    Siteconfig(SiteconfigArgs),
+    /// Read an article in an interactive terminal pager.
+    Read(ReadArgs),
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum OutputFormat {
     /// Print the full article structure as JSON.
     Json,
+    /// Print one compact JSON object per article on a single line, for JSON
+    /// Lines pipelines.
+    Ndjson,
     /// Print cleaned article HTML.
     Html,
     /// Print Markdown. This is the default format.
     Markdown,
     /// Print extracted plain text.
     Text,
+    /// Print Gemini gemtext.
+    Gmi,
+    /// Print a standalone LaTeX document.
+    Latex,
+    /// Print the article styled for reading in a terminal, through `$PAGER`
+    /// when stdout is a terminal.
+    Term,
     /// Write a generated PDF file.
     #[cfg(feature = "pdf")]
     Pdf,
+    /// Write an EPUB book to --output.
+    Epub,
+}
+
+/// How `--format pdf` produces the PDF.
+#[cfg(feature = "pdf")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PdfEngine {
+    /// Render the article Markdown with the built-in PDF writer.
+    Builtin,
+    /// Typeset the LaTeX output with the `tectonic` command.
+    Tectonic,
+}
+
+/// Article content included in `--format ndjson` records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum NdjsonContent {
+    /// Cleaned article HTML, as `content`.
+    Html,
+    /// Markdown, as `markdown`.
+    Markdown,
+    /// Plain text, as `text_content`.
+    Text,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportProfile {
+    /// Write an Obsidian note with YAML frontmatter and local attachments.
+    Obsidian,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum MetadataFormat {
+    /// A biblatex `@online` entry.
+    Bibtex,
+    /// A CSL-JSON array with one `webpage` item.
+    Csl,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -67,21 +128,78 @@ pub struct ExtractArgs {
     #[arg(long)]
     pub base_url: Option<String>,
 
-    /// Output format: markdown, html, text, json, or pdf.
-    #[arg(long, value_enum, default_value = "markdown")]
+    /// Output format: markdown, html, text, gmi, latex, term, json, ndjson,
+    /// epub, or pdf.
+    ///
+    /// Without this option, an --output extension such as .html or .epub
+    /// names the format.
+    #[arg(short, long, value_enum, env = "LECTITO_FORMAT", default_value = "markdown")]
     pub format: OutputFormat,
 
-    /// Pretty-print JSON output.
-    #[arg(long)]
+    /// Content to include in ndjson records, comma-separated: html, markdown,
+    /// or text. Metadata is always included.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMATS",
+        value_delimiter = ',',
+        default_value = "markdown"
+    )]
+    pub ndjson_content: Vec<NdjsonContent>,
+
+    /// TOML or JSON file with fetch, extract, and output settings.
+    ///
+    /// Flags passed on the command line override the file. Without this
+    /// option, `config.toml` in the user config directory is read if it
+    /// exists.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Start from tuned extraction options: news, docs, forum, or minimal.
+    ///
+    /// Flags passed on the command line override the preset. In a config file,
+    /// set `preset` instead.
+    #[arg(long, value_name = "NAME", conflicts_with = "config")]
+    pub preset: Option<Preset>,
+
+    /// Print the JSON Schema for json and ndjson output, then exit.
+    #[arg(long, conflicts_with_all = ["input", "stdin"])]
+    pub json_schema: bool,
+
+    /// PDF engine: builtin, or tectonic to typeset the LaTeX output.
+    ///
+    /// `tectonic` must be on `PATH`.
+    #[cfg(feature = "pdf")]
+    #[arg(long, value_enum, default_value = "builtin")]
+    pub pdf_engine: PdfEngine,
+
+    /// Pretty-print JSON output. `--pretty=false` turns off a `pretty`
+    /// setting from the config file.
+    #[arg(
+        long,
+        default_value_t = false,
+        default_missing_value = "true",
+        num_args = 0..=1,
+        require_equals = true,
+        value_parser = BoolishValueParser::new()
+    )]
     pub pretty: bool,
 
     /// Write article output to a file instead of stdout.
     ///
-    /// PDF output always writes a file. Without this option, the file is named
-    /// from the generated PDF content hash.
+    /// EPUB output needs this option. PDF output always writes a file; without
+    /// this option, the file is named from the generated PDF content hash.
     #[arg(short, long, value_name = "PATH")]
     pub output: Option<PathBuf>,
 
+    /// Append to --output instead of replacing it.
+    ///
+    /// The file is locked while the article is written, so parallel runs can
+    /// share one digest file. Entries are separated by a blank line, except
+    /// ndjson records, which take one line each.
+    #[arg(long, requires = "output", conflicts_with_all = ["split_by", "profile"])]
+    pub append: bool,
+
     /// Include TOML frontmatter in Markdown output.
     #[arg(
         long,
@@ -97,8 +215,13 @@ pub struct ExtractArgs {
     #[arg(long)]
     pub inspect: bool,
 
+    /// Print time spent fetching, parsing, extracting, post-processing, and
+    /// formatting to stderr.
+    #[arg(long)]
+    pub timings: bool,
+
     /// Maximum seconds to spend on full extraction before exit code 3.
-    #[arg(long, default_value_t = 30)]
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
     pub timeout: u64,
 
     /// Stop parsing after this many elements.
@@ -113,6 +236,70 @@ pub struct ExtractArgs {
     #[arg(long, default_value_t = 5)]
     pub nb_top_candidates: usize,
 
+    /// Share of the top candidate's score a sibling needs to be merged in.
+    #[arg(long, default_value_t = 0.2)]
+    pub sibling_threshold: f32,
+
+    /// Ignore candidates scoring below this before link-density scaling.
+    #[arg(long, default_value_t = 0.0)]
+    pub min_candidate_score: f32,
+
+    /// Points added or subtracted for positive or negative class/id matches.
+    #[arg(long, default_value_t = 25, allow_negative_numbers = true)]
+    pub class_weight: i32,
+
+    /// Extra class/id substring treated as a positive signal. May be repeated.
+    #[arg(long = "positive-pattern", value_name = "PATTERN")]
+    pub positive_patterns: Vec<String>,
+
+    /// Extra class/id substring treated as a negative signal. May be repeated.
+    #[arg(long = "negative-pattern", value_name = "PATTERN")]
+    pub negative_patterns: Vec<String>,
+
+    /// Extra class/id substring marking page chrome to strip before scoring.
+    /// May be repeated.
+    #[arg(long = "unlikely-pattern", value_name = "PATTERN")]
+    pub unlikely_patterns: Vec<String>,
+
+    /// Extra class/id substring marking trailing blocks, such as newsletter
+    /// or related-story boxes, to trim from the end of the article. May be
+    /// repeated.
+    #[arg(long = "trailing-chrome-pattern", value_name = "PATTERN")]
+    pub trailing_chrome_patterns: Vec<String>,
+
+    /// Extra documentation-control label, such as "Edit this page", whose
+    /// buttons and links are removed. May be repeated.
+    #[arg(long = "doc-chrome-label", value_name = "LABEL")]
+    pub doc_chrome_labels: Vec<String>,
+
+    /// Drop a pattern group's built-in patterns so only the added ones match:
+    /// positive, negative, unlikely, trailing-chrome, or doc-chrome. May be
+    /// repeated.
+    #[arg(long = "replace-patterns", value_name = "GROUP")]
+    pub replace_pattern_groups: Vec<PatternGroup>,
+
+    /// Fit <picture> and srcset images to this display width, keeping one
+    /// URL per image.
+    #[arg(long, value_name = "PX")]
+    pub image_target_width: Option<u32>,
+
+    /// Stop extraction with an error after this many milliseconds.
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_duration_ms: Option<u64>,
+
+    /// Refuse input larger than this many bytes, when fetching and before
+    /// parsing.
+    #[arg(long, value_name = "BYTES")]
+    pub max_input_bytes: Option<usize>,
+
+    /// Stop with an error when scoring finds more candidate roots than this.
+    #[arg(long, value_name = "N")]
+    pub max_candidates: Option<usize>,
+
+    /// Added to link density before conditional cleanup compares it.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    pub link_density_modifier: f32,
+
     /// CSS selector for a known article root.
     #[arg(long)]
     pub content_selector: Option<String>,
@@ -121,6 +308,16 @@ pub struct ExtractArgs {
     #[arg(long = "site-profile", value_name = "PATH")]
     pub profiles: Vec<PathBuf>,
 
+    /// Override a directive of the matched site profile for this run, such as
+    /// `prune=no` or `remove=.promo`. May be repeated.
+    #[arg(long = "siteconfig-set", value_name = "DIRECTIVE=VALUE")]
+    pub siteconfig_set: Vec<String>,
+
+    /// Disable a directive of the matched site profile for this run, such as
+    /// `remove_id_or_class`. May be repeated.
+    #[arg(long = "siteconfig-ignore", value_name = "DIRECTIVE")]
+    pub siteconfig_ignore: Vec<String>,
+
     /// Viewport width used when applying mobile recovery rules.
     #[arg(long)]
     pub mobile_viewport_width: Option<usize>,
@@ -137,6 +334,67 @@ pub struct ExtractArgs {
     #[arg(long = "media", default_value_t = MediaRetention::Article)]
     pub media: MediaRetention,
 
+    /// Keep iframes from this host and its subdomains. May be repeated.
+    #[arg(long = "iframe-allow", value_name = "HOST")]
+    pub iframe_allow: Vec<String>,
+
+    /// Footnote handling: markdown or strip.
+    #[arg(long, default_value_t = FootnoteMode::Markdown)]
+    pub footnotes: FootnoteMode,
+
+    /// Markdown link style: inline, reference, or footnotes.
+    #[arg(long = "link-style", default_value_t = LinkStyle::Inline)]
+    pub link_style: LinkStyle,
+
+    /// Link cleanup: none, strip-tracking, or unwrap-redirects.
+    #[arg(long = "link-cleanup", default_value_t = LinkCleanup::None)]
+    pub link_cleanup: LinkCleanup,
+
+    /// Start Markdown headings this many levels below an H1 title.
+    #[arg(long = "heading-offset", value_name = "LEVELS", value_parser = clap::value_parser!(u8).range(0..=5))]
+    pub heading_offset: Option<u8>,
+
+    /// Close skipped heading levels in Markdown output.
+    #[arg(long = "normalize-heading-levels")]
+    pub normalize_heading_levels: bool,
+
+    /// Mark Markdown headings with the reading time before them.
+    #[arg(long = "reading-time-markers")]
+    pub reading_time_markers: bool,
+
+    /// Decode stray entities, apply Unicode NFC, and drop zero-width characters.
+    #[arg(long = "normalize-text")]
+    pub normalize_text: bool,
+
+    /// Quotes and dashes: preserve, smart, or plain.
+    #[arg(long, default_value_t = Typography::Preserve)]
+    pub typography: Typography,
+
+    /// Root selection strategy: auto, full-body-cleanup, live-blog, transcript, or density.
+    #[arg(long, default_value_t = ExtractionStrategy::Auto)]
+    pub strategy: ExtractionStrategy,
+
+    /// Annotate elements cleanup would remove instead of removing them.
+    #[arg(long = "cleanup-dry-run")]
+    pub cleanup_dry_run: bool,
+
+    /// Keep elements hidden with `hidden`, `aria-hidden`, or inline styles.
+    #[arg(long = "keep-hidden")]
+    pub keep_hidden: bool,
+
+    /// Do not strip sidebar-, comment-, or footer-like elements before scoring.
+    #[arg(long = "no-remove-unlikely")]
+    pub no_remove_unlikely: bool,
+
+    /// Keep every iframe, not just video and --iframe-allow hosts.
+    #[arg(long = "keep-iframes")]
+    pub keep_iframes: bool,
+
+    /// Swap lazy placeholder images for their <noscript> fallbacks and keep
+    /// fallback text.
+    #[arg(long = "recover-noscript")]
+    pub recover_noscript: bool,
+
     /// Preserve class attributes in extracted HTML.
     #[arg(long = "keep-classes")]
     pub keep: bool,
@@ -144,6 +402,77 @@ pub struct ExtractArgs {
     /// Class name to preserve in extracted HTML. May be repeated.
     #[arg(long = "preserve-class", value_name = "CLASS")]
     pub preserve: Vec<String>,
+
+    /// Follow up to N next-page links and append each page to the article.
+    ///
+    /// Next pages are found from site profiles, rel="next" links, "Next"
+    /// links, and page numbers in URLs. Needs a URL input or --base-url.
+    #[arg(long, value_name = "N")]
+    pub follow_pages: Option<usize>,
+
+    /// Download images referenced by the article and rewrite their URLs.
+    ///
+    /// Images are inlined as data URIs unless --assets-dir is set. EPUB
+    /// output stores them inside the book. --max-input-bytes caps each image.
+    #[arg(long)]
+    pub download_images: bool,
+
+    /// Directory for downloaded images, relative to the output file.
+    #[arg(long, value_name = "DIR", requires = "download_images")]
+    pub assets_dir: Option<PathBuf>,
+
+    /// Also write the fetched pages and their images to a WARC file.
+    ///
+    /// Keeps the original HTML alongside the extracted article for personal
+    /// archiving. Needs a URL input or --base-url.
+    #[arg(long, value_name = "FILE")]
+    pub warc: Option<PathBuf>,
+
+    /// Prepend an extractive summary of N sentences to the article.
+    #[arg(long, value_name = "N")]
+    pub summary: Option<usize>,
+
+    /// Extract N keyphrases from the article text.
+    ///
+    /// JSON output gains a keyphrases field. Pages that declare no keywords
+    /// use the keyphrases as keywords and frontmatter tags.
+    #[arg(long, value_name = "N")]
+    pub keyphrases: Option<usize>,
+
+    /// Write one file per section, cutting at headings of LEVEL such as `h2`.
+    ///
+    /// Files are named from --output with the section number, as in
+    /// `article-2.md`, and link to the sections before and after them.
+    #[arg(
+        long = "split-by",
+        value_name = "LEVEL",
+        value_parser = parse_heading_level,
+        requires = "output",
+        conflicts_with_all = ["profile", "metadata_format"]
+    )]
+    pub split_by: Option<u8>,
+
+    /// Export the article for a note-taking app.
+    ///
+    /// Implies Markdown output and image downloads. The note is named from
+    /// the article title and written to the current directory, or into
+    /// --output when it is a directory.
+    #[arg(long, value_enum, value_name = "PROFILE")]
+    pub profile: Option<ExportProfile>,
+
+    /// Render the article through a Tera template file instead of --format.
+    ///
+    /// The template sees the article JSON fields plus source, references,
+    /// and stats.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["format", "profile"])]
+    pub template: Option<PathBuf>,
+
+    /// Print a citation for the article after its content.
+    ///
+    /// With --output, the article is written to that file and only the
+    /// citation goes to stdout.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub metadata_format: Option<MetadataFormat>,
 }
 
 /// Check whether a document is probably readable.
@@ -172,7 +501,7 @@ pub struct ReadableArgs {
     pub pretty: bool,
 
     /// Maximum seconds to spend on readability checking before exit code 3.
-    #[arg(long, default_value_t = 30)]
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
     pub timeout: u64,
 
     /// Minimum text length for a block to count toward readability.
@@ -184,6 +513,139 @@ pub struct ReadableArgs {
     pub min_score: f32,
 }
 
+/// Default location of the published site profile bundle.
+pub const SITECONFIG_SOURCE: &str =
+    "https://raw.githubusercontent.com/stormlightlabs/lectito/main/crates/core/src/rules/conf/";
+
+/// Default source for `lectito siteconfig update`: the FiveFilters site config repository.
+pub const FTR_SITE_CONFIG_SOURCE: &str = "https://github.com/fivefilters/ftr-site-config.git";
+
+/// Check bundled site rules and sync FiveFilters site configs.
+#[derive(Debug, Args)]
+pub struct SiteconfigArgs {
+    #[command(subcommand)]
+    pub command: SiteconfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SiteconfigCommands {
+    /// Print the bundled site rules version, optionally checking for updates.
+    Version(SiteconfigVersionArgs),
+    /// Download the published site profiles into a directory.
+    Fetch(SiteconfigFetchArgs),
+    /// Download or update site rules in the user config directory. Extraction
+    /// loads them ahead of the bundled profiles. Exits with 2 when the update
+    /// fails and the rules from the last update are kept.
+    Update(SiteconfigUpdateArgs),
+    /// Fetch each profile's test URLs and check that the profile extracts
+    /// them. Exits with 1 when a check fails.
+    Test(SiteconfigTestArgs),
+    /// Check TOML site profiles for syntax errors, unknown keys, selectors
+    /// that do not compile, and rules that never apply. Exits with 1 when a
+    /// profile has an error.
+    Lint(SiteconfigLintArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SiteconfigVersionArgs {
+    /// Compare with the published bundle. Exits with 1 when the bundled rules are stale.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Bundle URL or local directory containing index.txt and the profiles.
+    #[arg(long, value_name = "URL_OR_DIR", default_value = SITECONFIG_SOURCE)]
+    pub source: String,
+
+    /// Print JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SiteconfigFetchArgs {
+    /// Directory to write the profiles to. Pass each file with --site-profile.
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: PathBuf,
+
+    /// Bundle URL or local directory containing index.txt and the profiles.
+    #[arg(long, value_name = "URL_OR_DIR", default_value = SITECONFIG_SOURCE)]
+    pub source: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SiteconfigUpdateArgs {
+    /// Git repository of FiveFilters site configs, directory of site configs,
+    /// or bundle URL or directory containing index.txt and TOML profiles.
+    #[arg(long, value_name = "URL_OR_DIR", default_value = FTR_SITE_CONFIG_SOURCE)]
+    pub source: String,
+
+    /// Git tag, branch, or commit to check out. Defaults to the remote's default branch.
+    #[arg(long, value_name = "REV")]
+    pub rev: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SiteconfigTestArgs {
+    /// Only check test URLs on this host and its subdomains.
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub host: Option<String>,
+
+    /// Check every profile that lists test URLs.
+    #[arg(long)]
+    pub all: bool,
+
+    /// TOML site profile or FiveFilters .txt site config to check instead of
+    /// the synced rules. May be repeated.
+    #[arg(long = "site-profile", value_name = "PATH")]
+    pub profiles: Vec<PathBuf>,
+
+    /// Print JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SiteconfigLintArgs {
+    /// TOML site profiles, or directories whose .toml files are linted.
+    #[arg(required = true, value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+
+    /// Print JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Open an article in the interactive reader.
+#[derive(Debug, Args)]
+pub struct ReadArgs {
+    /// URL, AT URI, HTML file path, or '-' for stdin.
+    pub input: String,
+
+    /// Base URL for files or stdin, used to resolve relative links.
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Maximum seconds to spend on full extraction before exit code 3.
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
+    pub timeout: u64,
+
+    /// Directory for articles saved from the reader.
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub save_dir: PathBuf,
+}
+
+/// Print a machine-readable capability report.
+#[derive(Debug, Args)]
+pub struct InfoArgs {
+    /// TOML site profile to validate and count. May be repeated.
+    #[arg(long = "site-profile", value_name = "PATH")]
+    pub profiles: Vec<PathBuf>,
+
+    /// Pretty-print JSON output.
+    #[arg(long)]
+    pub pretty: bool,
+}
+
 /// Print extraction metadata and scoring details.
 #[derive(Debug, Args)]
 pub struct InspectArgs {
@@ -207,9 +669,24 @@ pub struct InspectArgs {
     pub pretty: bool,
 
     /// Maximum seconds to spend on full extraction before exit code 3.
-    #[arg(long, default_value_t = 30)]
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
     pub timeout: u64,
 
+    /// TOML or JSON file with fetch, extract, and output settings.
+    ///
+    /// Flags passed on the command line override the file. Without this
+    /// option, `config.toml` in the user config directory is read if it
+    /// exists.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Start from tuned extraction options: news, docs, forum, or minimal.
+    ///
+    /// Flags passed on the command line override the preset. In a config file,
+    /// set `preset` instead.
+    #[arg(long, value_name = "NAME", conflicts_with = "config")]
+    pub preset: Option<Preset>,
+
     /// Stop parsing after this many elements.
     #[arg(long)]
     pub max_elems_to_parse: Option<usize>,
@@ -222,6 +699,70 @@ pub struct InspectArgs {
     #[arg(long, default_value_t = 5)]
     pub nb_top_candidates: usize,
 
+    /// Share of the top candidate's score a sibling needs to be merged in.
+    #[arg(long, default_value_t = 0.2)]
+    pub sibling_threshold: f32,
+
+    /// Ignore candidates scoring below this before link-density scaling.
+    #[arg(long, default_value_t = 0.0)]
+    pub min_candidate_score: f32,
+
+    /// Points added or subtracted for positive or negative class/id matches.
+    #[arg(long, default_value_t = 25, allow_negative_numbers = true)]
+    pub class_weight: i32,
+
+    /// Extra class/id substring treated as a positive signal. May be repeated.
+    #[arg(long = "positive-pattern", value_name = "PATTERN")]
+    pub positive_patterns: Vec<String>,
+
+    /// Extra class/id substring treated as a negative signal. May be repeated.
+    #[arg(long = "negative-pattern", value_name = "PATTERN")]
+    pub negative_patterns: Vec<String>,
+
+    /// Extra class/id substring marking page chrome to strip before scoring.
+    /// May be repeated.
+    #[arg(long = "unlikely-pattern", value_name = "PATTERN")]
+    pub unlikely_patterns: Vec<String>,
+
+    /// Extra class/id substring marking trailing blocks, such as newsletter
+    /// or related-story boxes, to trim from the end of the article. May be
+    /// repeated.
+    #[arg(long = "trailing-chrome-pattern", value_name = "PATTERN")]
+    pub trailing_chrome_patterns: Vec<String>,
+
+    /// Extra documentation-control label, such as "Edit this page", whose
+    /// buttons and links are removed. May be repeated.
+    #[arg(long = "doc-chrome-label", value_name = "LABEL")]
+    pub doc_chrome_labels: Vec<String>,
+
+    /// Drop a pattern group's built-in patterns so only the added ones match:
+    /// positive, negative, unlikely, trailing-chrome, or doc-chrome. May be
+    /// repeated.
+    #[arg(long = "replace-patterns", value_name = "GROUP")]
+    pub replace_pattern_groups: Vec<PatternGroup>,
+
+    /// Fit <picture> and srcset images to this display width, keeping one
+    /// URL per image.
+    #[arg(long, value_name = "PX")]
+    pub image_target_width: Option<u32>,
+
+    /// Stop extraction with an error after this many milliseconds.
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_duration_ms: Option<u64>,
+
+    /// Refuse input larger than this many bytes, when fetching and before
+    /// parsing.
+    #[arg(long, value_name = "BYTES")]
+    pub max_input_bytes: Option<usize>,
+
+    /// Stop with an error when scoring finds more candidate roots than this.
+    #[arg(long, value_name = "N")]
+    pub max_candidates: Option<usize>,
+
+    /// Added to link density before conditional cleanup compares it.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    pub link_density_modifier: f32,
+
     /// CSS selector for a known article root.
     #[arg(long)]
     pub content_selector: Option<String>,
@@ -230,6 +771,16 @@ pub struct InspectArgs {
     #[arg(long = "site-profile", value_name = "PATH")]
     pub profiles: Vec<PathBuf>,
 
+    /// Override a directive of the matched site profile for this run, such as
+    /// `prune=no` or `remove=.promo`. May be repeated.
+    #[arg(long = "siteconfig-set", value_name = "DIRECTIVE=VALUE")]
+    pub siteconfig_set: Vec<String>,
+
+    /// Disable a directive of the matched site profile for this run, such as
+    /// `remove_id_or_class`. May be repeated.
+    #[arg(long = "siteconfig-ignore", value_name = "DIRECTIVE")]
+    pub siteconfig_ignore: Vec<String>,
+
     /// Viewport width used when applying mobile recovery rules.
     #[arg(long)]
     pub mobile_viewport_width: Option<usize>,
@@ -242,6 +793,67 @@ pub struct InspectArgs {
     #[arg(long = "media", default_value_t = MediaRetention::Article)]
     pub media: MediaRetention,
 
+    /// Keep iframes from this host and its subdomains. May be repeated.
+    #[arg(long = "iframe-allow", value_name = "HOST")]
+    pub iframe_allow: Vec<String>,
+
+    /// Footnote handling: markdown or strip.
+    #[arg(long, default_value_t = FootnoteMode::Markdown)]
+    pub footnotes: FootnoteMode,
+
+    /// Markdown link style: inline, reference, or footnotes.
+    #[arg(long = "link-style", default_value_t = LinkStyle::Inline)]
+    pub link_style: LinkStyle,
+
+    /// Link cleanup: none, strip-tracking, or unwrap-redirects.
+    #[arg(long = "link-cleanup", default_value_t = LinkCleanup::None)]
+    pub link_cleanup: LinkCleanup,
+
+    /// Start Markdown headings this many levels below an H1 title.
+    #[arg(long = "heading-offset", value_name = "LEVELS", value_parser = clap::value_parser!(u8).range(0..=5))]
+    pub heading_offset: Option<u8>,
+
+    /// Close skipped heading levels in Markdown output.
+    #[arg(long = "normalize-heading-levels")]
+    pub normalize_heading_levels: bool,
+
+    /// Mark Markdown headings with the reading time before them.
+    #[arg(long = "reading-time-markers")]
+    pub reading_time_markers: bool,
+
+    /// Decode stray entities, apply Unicode NFC, and drop zero-width characters.
+    #[arg(long = "normalize-text")]
+    pub normalize_text: bool,
+
+    /// Quotes and dashes: preserve, smart, or plain.
+    #[arg(long, default_value_t = Typography::Preserve)]
+    pub typography: Typography,
+
+    /// Root selection strategy: auto, full-body-cleanup, live-blog, transcript, or density.
+    #[arg(long, default_value_t = ExtractionStrategy::Auto)]
+    pub strategy: ExtractionStrategy,
+
+    /// Annotate elements cleanup would remove instead of removing them.
+    #[arg(long = "cleanup-dry-run")]
+    pub cleanup_dry_run: bool,
+
+    /// Keep elements hidden with `hidden`, `aria-hidden`, or inline styles.
+    #[arg(long = "keep-hidden")]
+    pub keep_hidden: bool,
+
+    /// Do not strip sidebar-, comment-, or footer-like elements before scoring.
+    #[arg(long = "no-remove-unlikely")]
+    pub no_remove_unlikely: bool,
+
+    /// Keep every iframe, not just video and --iframe-allow hosts.
+    #[arg(long = "keep-iframes")]
+    pub keep_iframes: bool,
+
+    /// Swap lazy placeholder images for their <noscript> fallbacks and keep
+    /// fallback text.
+    #[arg(long = "recover-noscript")]
+    pub recover_noscript: bool,
+
     /// Preserve class attributes in extracted HTML.
     #[arg(long = "keep-classes")]
     pub keep: bool,
@@ -304,7 +916,7 @@ pub struct LlmsExpandArgs {
     pub max_links: usize,
 
     /// Maximum seconds to spend extracting each HTML resource.
-    #[arg(long, default_value_t = 30)]
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
     pub timeout: u64,
 
     /// Write output to a file instead of stdout.
@@ -366,9 +978,13 @@ pub struct LlmsGenerateArgs {
     pub max_depth: usize,
 
     /// Maximum seconds to spend extracting each HTML page.
-    #[arg(long, default_value_t = 30)]
+    #[arg(long, env = "LECTITO_TIMEOUT", default_value_t = 30)]
     pub timeout: u64,
 
+    /// Leave out pages a site profile marks as sponsored content.
+    #[arg(long)]
+    pub skip_sponsored: bool,
+
     /// Write output to a file instead of stdout.
     #[arg(short, long, value_name = "PATH")]
     pub output: Option<PathBuf>,
@@ -378,6 +994,15 @@ pub struct LlmsGenerateArgs {
     pub full_output: Option<PathBuf>,
 }
 
+/// Heading level from `h1` through `h6`, or a bare digit.
+fn parse_heading_level(value: &str) -> Result<u8, String> {
+    let digit = value.trim().trim_start_matches(['h', 'H']);
+    match digit.parse::<u8>() {
+        Ok(level @ 1..=6) => Ok(level),
+        _ => Err(format!("expected a heading level from h1 to h6, got `{value}`")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -405,12 +1030,25 @@ mod tests {
         assert!(matches!(cli.extract.format, OutputFormat::Markdown));
     }
 
+    #[test]
+    fn gmi_format_parses_with_short_flag() {
+        let cli = Cli::try_parse_from(["lectito", "article.html", "-f", "gmi"]).expect("root args should parse");
+        assert!(matches!(cli.extract.format, OutputFormat::Gmi));
+    }
+
+    #[test]
+    fn term_format_parses_with_short_flag() {
+        let cli = Cli::try_parse_from(["lectito", "article.html", "-f", "term"]).expect("root args should parse");
+        assert!(matches!(cli.extract.format, OutputFormat::Term));
+    }
+
     #[cfg(feature = "pdf")]
     #[test]
     fn pdf_format_parses_when_enabled() {
         let cli = Cli::try_parse_from(["lectito", "article.html", "--format", "pdf"])
             .expect("PDF format should parse when the pdf feature is enabled");
         assert!(matches!(cli.extract.format, OutputFormat::Pdf));
+        assert!(matches!(cli.extract.pdf_engine, PdfEngine::Builtin));
     }
 
     #[test]
@@ -433,6 +1071,90 @@ mod tests {
         assert!(!cli.extract.frontmatter);
     }
 
+    #[test]
+    fn summary_takes_sentence_count() {
+        let cli = Cli::try_parse_from(["lectito", "article.html", "--summary", "3"]).expect("summary should parse");
+        assert_eq!(cli.extract.summary, Some(3));
+        assert_eq!(cli.extract.keyphrases, None);
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--summary", "many"]).is_err());
+    }
+
+    #[test]
+    fn follow_pages_takes_page_count() {
+        let cli = Cli::try_parse_from(["lectito", "https://example.com/story", "--follow-pages", "4"])
+            .expect("follow pages should parse");
+        assert_eq!(cli.extract.follow_pages, Some(4));
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--follow-pages", "all"]).is_err());
+    }
+
+    #[test]
+    fn template_replaces_output_format() {
+        let cli =
+            Cli::try_parse_from(["lectito", "article.html", "--template", "note.tera"]).expect("template should parse");
+        assert_eq!(cli.extract.template, Some(PathBuf::from("note.tera")));
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--template", "note.tera", "-f", "html"]).is_err());
+    }
+
+    #[test]
+    fn split_by_takes_heading_level_and_requires_output() {
+        let cli = Cli::try_parse_from(["lectito", "article.html", "--split-by", "h2", "-o", "article.md"])
+            .expect("split flags should parse");
+        assert_eq!(cli.extract.split_by, Some(2));
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--split-by", "h2"]).is_err());
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--split-by", "h7", "-o", "article.md"]).is_err());
+    }
+
+    #[test]
+    fn ndjson_content_takes_comma_separated_formats() {
+        let cli = Cli::try_parse_from([
+            "lectito",
+            "article.html",
+            "-f",
+            "ndjson",
+            "--ndjson-content",
+            "text,html",
+        ])
+        .expect("ndjson flags should parse");
+        assert!(matches!(cli.extract.format, OutputFormat::Ndjson));
+        assert_eq!(cli.extract.ndjson_content, [NdjsonContent::Text, NdjsonContent::Html]);
+
+        let cli = Cli::try_parse_from(["lectito", "article.html"]).expect("defaults should parse");
+        assert_eq!(cli.extract.ndjson_content, [NdjsonContent::Markdown]);
+    }
+
+    #[test]
+    fn json_schema_needs_no_input() {
+        let cli = Cli::try_parse_from(["lectito", "--json-schema"]).expect("json schema flag should parse");
+        assert!(cli.extract.json_schema);
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--json-schema"]).is_err());
+    }
+
+    #[test]
+    fn preset_parses_and_conflicts_with_config() {
+        let cli = Cli::try_parse_from(["lectito", "docs.html", "--preset", "docs"]).expect("preset should parse");
+        assert_eq!(cli.extract.preset, Some(Preset::Docs));
+        assert!(Cli::try_parse_from(["lectito", "docs.html", "--preset", "blog"]).is_err());
+        assert!(Cli::try_parse_from(["lectito", "docs.html", "--preset", "docs", "--config", "lectito.toml"]).is_err());
+    }
+
+    #[test]
+    fn append_requires_output() {
+        let cli = Cli::try_parse_from(["lectito", "article.html", "--append", "-o", "digest.md"])
+            .expect("append flags should parse");
+        assert!(cli.extract.append);
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--append"]).is_err());
+    }
+
+    #[test]
+    fn assets_dir_requires_download_images() {
+        assert!(Cli::try_parse_from(["lectito", "article.html", "--assets-dir", "images"]).is_err());
+
+        let cli = Cli::try_parse_from(["lectito", "article.html", "--download-images", "--assets-dir", "images"])
+            .expect("asset flags should parse");
+        assert!(cli.extract.download_images);
+        assert_eq!(cli.extract.assets_dir, Some(PathBuf::from("images")));
+    }
+
     #[test]
     fn readable_accepts_timeout() {
         match Cli::try_parse_from(["lectito", "readable", "article.html", "--timeout", "7"])
@@ -444,6 +1166,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn info_subcommand_parses_site_profiles() {
+        match Cli::try_parse_from([
+            "lectito",
+            "info",
+            "--site-profile",
+            "a.toml",
+            "--site-profile",
+            "b.toml",
+        ])
+        .expect("info command should parse")
+        .command
+        {
+            Some(Commands::Info(args)) => {
+                assert_eq!(args.profiles, [PathBuf::from("a.toml"), PathBuf::from("b.toml")]);
+                assert!(!args.pretty);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
     #[test]
     fn llms_subcommand_parses() {
         match Cli::try_parse_from(["lectito", "llms", "expand", "https://example.com", "--include-optional"])
@@ -591,4 +1334,19 @@ mod tests {
             other => panic!("unexpected command: {other:?}"),
         }
     }
+
+    #[test]
+    fn read_subcommand_parses() {
+        match Cli::try_parse_from(["lectito", "read", "https://example.com/tides", "--save-dir", "saved"])
+            .expect("read command should parse")
+            .command
+        {
+            Some(Commands::Read(args)) => {
+                assert_eq!(args.input, "https://example.com/tides");
+                assert_eq!(args.save_dir, PathBuf::from("saved"));
+                assert_eq!(args.timeout, 30);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
 }
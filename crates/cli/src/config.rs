@@ -0,0 +1,134 @@
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use lectito::ReadabilityOptions;
+
+/// Extract flags and the `ReadabilityOptions` fields they set.
+const EXTRACT_FLAGS: &[(&str, &str)] = &[
+    ("max_elems_to_parse", "max_elems_to_parse"),
+    ("nb_top_candidates", "nb_top_candidates"),
+    ("char_threshold", "char_threshold"),
+    ("content_selector", "content_selector"),
+    ("profiles", "site_profiles"),
+    ("siteconfig_set", "site_profile_overrides"),
+    ("siteconfig_ignore", "site_profile_ignores"),
+    ("mobile_viewport_width", "mobile_viewport_width"),
+    ("preserve", "classes_to_preserve"),
+    ("keep", "keep_classes"),
+    ("disable_json_ld", "disable_json_ld"),
+    ("link_density_modifier", "link_density_modifier"),
+    ("media", "media_retention"),
+    ("iframe_allow", "iframe_allowlist"),
+    ("footnotes", "footnotes"),
+    ("link_style", "link_style"),
+    ("link_cleanup", "link_cleanup"),
+    ("heading_offset", "heading_offset"),
+    ("normalize_heading_levels", "normalize_heading_levels"),
+    ("reading_time_markers", "reading_time_markers"),
+    ("normalize_text", "normalize_text"),
+    ("typography", "typography"),
+    ("strategy", "strategy"),
+    ("cleanup_dry_run", "cleanup_dry_run"),
+    ("keep_hidden", "keep_hidden"),
+    ("no_remove_unlikely", "keep_unlikely_candidates"),
+    ("keep_iframes", "keep_iframes"),
+    ("sibling_threshold", "sibling_threshold"),
+    ("min_candidate_score", "min_candidate_score"),
+    ("class_weight", "class_weight"),
+    ("positive_patterns", "positive_patterns"),
+    ("negative_patterns", "negative_patterns"),
+    ("unlikely_patterns", "unlikely_patterns"),
+    ("trailing_chrome_patterns", "trailing_chrome_patterns"),
+    ("doc_chrome_labels", "doc_chrome_labels"),
+    ("replace_pattern_groups", "replace_pattern_groups"),
+    ("image_target_width", "image_target_width"),
+    ("max_input_bytes", "max_input_bytes"),
+    ("max_candidates", "max_candidates"),
+    ("max_duration_ms", "max_duration_ms"),
+    ("recover_noscript", "recover_noscript"),
+];
+
+/// Lectito's user config directory: `$LECTITO_CONFIG_DIR` when set, otherwise
+/// `lectito` under `$XDG_CONFIG_HOME` or `~/.config`, or under `%APPDATA%` on
+/// Windows.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("LECTITO_CONFIG_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|base| base.join("lectito"))
+}
+
+/// Whether `id` was passed on the command line or through its `LECTITO_*`
+/// environment variable rather than left at its default.
+pub fn explicit(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// Start from the config file's or preset's extraction options and take each
+/// field the command line set explicitly from `flags`.
+pub fn merge_extract(
+    base: &ReadabilityOptions, flags: ReadabilityOptions, explicit: impl Fn(&str) -> bool,
+) -> Result<ReadabilityOptions> {
+    let mut merged = serde_json::to_value(base).context("failed to merge config")?;
+    let flags = serde_json::to_value(flags).context("failed to merge config")?;
+    for (id, field) in EXTRACT_FLAGS {
+        if explicit(id) {
+            merged[*field] = flags[*field].clone();
+        }
+    }
+    serde_json::from_value(merged).context("failed to merge config")
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use lectito::LinkStyle;
+
+    use super::*;
+    use crate::cli::Cli;
+
+    #[test]
+    fn command_line_flags_override_config() {
+        let config = ReadabilityOptions { char_threshold: 100, link_style: LinkStyle::Footnotes, ..Default::default() };
+        let flags = ReadabilityOptions { char_threshold: 500, keep_hidden: true, ..Default::default() };
+
+        let merged = merge_extract(&config, flags, |id| id == "keep_hidden").unwrap();
+
+        assert_eq!(
+            merged,
+            ReadabilityOptions {
+                char_threshold: 100,
+                link_style: LinkStyle::Footnotes,
+                keep_hidden: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn extract_flags_cover_every_option() {
+        let command = Cli::command();
+        for (id, _) in EXTRACT_FLAGS {
+            assert!(command.get_arguments().any(|arg| arg.get_id() == *id), "no --{id} flag");
+        }
+        let options = serde_json::to_value(ReadabilityOptions::default()).unwrap();
+        let fields: Vec<&str> = EXTRACT_FLAGS.iter().map(|(_, field)| *field).collect();
+        for field in options.as_object().unwrap().keys() {
+            assert!(fields.contains(&field.as_str()), "no flag sets {field}");
+        }
+    }
+}
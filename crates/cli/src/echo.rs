@@ -1,21 +1,50 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use lectito::markdown_with_toml_frontmatter;
-use lectito::{Article, ExtractionDiagnostics, ExtractionReport};
+use lectito::{Article, ExtractionDiagnostics, ExtractionReport, SiteRuleDiagnostic, SiteRuleMatch, Timings};
 
 use anyhow::{Context, Result};
 use owo_colors::OwoColorize;
 
-use crate::cli::{DiagnosticFormat, OutputFormat};
+use crate::cli::{DiagnosticFormat, NdjsonContent, OutputFormat};
 
 pub struct RenderOptions<'a> {
     format: OutputFormat,
     pretty: bool,
     source: Option<&'a str>,
     frontmatter: bool,
+    keyphrases: Option<usize>,
+    color: bool,
+    ndjson_content: &'a [NdjsonContent],
 }
 
 impl<'a> RenderOptions<'a> {
     pub fn new(format: OutputFormat, pretty: bool, source: Option<&'a str>, frontmatter: bool) -> Self {
-        Self { format, pretty, source, frontmatter }
+        Self {
+            format,
+            pretty,
+            source,
+            frontmatter,
+            keyphrases: None,
+            color: false,
+            ndjson_content: &[NdjsonContent::Markdown],
+        }
+    }
+
+    pub fn with_keyphrases(mut self, keyphrases: Option<usize>) -> Self {
+        self.keyphrases = keyphrases;
+        self
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_ndjson_content(mut self, content: &'a [NdjsonContent]) -> Self {
+        self.ndjson_content = content;
+        self
     }
 }
 
@@ -24,29 +53,40 @@ pub struct InspectOptions<'a> {
     json: bool,
     pretty: bool,
     atproto_warnings: &'a [String],
+    site_profiles: &'a [PathBuf],
 }
 
 impl<'a> InspectOptions<'a> {
     pub fn new(pretty: bool, source: Option<&'a str>, json: bool) -> Self {
-        Self { pretty, source, json, atproto_warnings: &[] }
+        Self { pretty, source, json, atproto_warnings: &[], site_profiles: &[] }
     }
 
     pub fn with_atproto_warnings(mut self, warnings: &'a [String]) -> Self {
         self.atproto_warnings = warnings;
         self
     }
+
+    /// Paths of the `--site-profile` files, in the order they were loaded.
+    pub fn with_site_profiles(mut self, paths: &'a [PathBuf]) -> Self {
+        self.site_profiles = paths;
+        self
+    }
 }
 
 pub fn diagnostics_with_atproto_warnings(
     diagnostics: &ExtractionDiagnostics, format: DiagnosticFormat, color: bool, atproto_warnings: &[String],
+    site_profiles: &[PathBuf],
 ) -> Result<()> {
     match format {
         DiagnosticFormat::Json => {
-            if !atproto_warnings.is_empty() {
-                let value = serde_json::json!({
-                    "atproto_warnings": atproto_warnings,
-                    "extraction": diagnostics,
-                });
+            if !atproto_warnings.is_empty() || !site_profiles.is_empty() {
+                let mut value = serde_json::json!({ "extraction": diagnostics });
+                if !atproto_warnings.is_empty() {
+                    value["atproto_warnings"] = serde_json::json!(atproto_warnings);
+                }
+                if !site_profiles.is_empty() {
+                    value["site_profiles"] = serde_json::json!(profile_paths(site_profiles));
+                }
                 eprintln!(
                     "{}",
                     serde_json::to_string_pretty(&value).context("failed to serialize diagnostics")?
@@ -77,6 +117,19 @@ pub fn diagnostics_with_atproto_warnings(
                     eprintln!("  {warning}");
                 }
             }
+            for warning in &diagnostics.warnings {
+                eprintln!(
+                    "{} {warning}",
+                    style("warning:", color, |value| value.yellow().to_string())
+                );
+            }
+            if !site_profiles.is_empty() {
+                eprintln!(
+                    "{} {}",
+                    style("site profiles:", color, |value| value.bold().to_string()),
+                    profile_paths(site_profiles).join(", ")
+                );
+            }
             if let Some(selector) = &diagnostics.content_selector {
                 let status = if selector.matched {
                     style("matched", color, |value| value.green().to_string())
@@ -110,6 +163,9 @@ pub fn diagnostics_with_atproto_warnings(
                         reason
                     );
                 }
+                for (label, value) in site_rule_provenance(site_rule, site_profiles) {
+                    eprintln!("  {} {value}", style(label, color, |value| value.bold().to_string()));
+                }
             }
             for attempt in &diagnostics.attempts {
                 let marker = if Some(attempt.index) == diagnostics.selected_attempt {
@@ -138,14 +194,29 @@ pub fn diagnostics_with_atproto_warnings(
                         root.link_density
                     );
                 }
-                if attempt.recovery.shadow_roots_flattened > 0 || attempt.recovery.mobile_rules_applied > 0 {
+                if attempt.recovery.shadow_roots_flattened > 0
+                    || attempt.recovery.mobile_rules_applied > 0
+                    || attempt.recovery.payloads_decoded > 0
+                    || attempt.recovery.galleries_flattened > 0
+                {
                     eprintln!(
-                        "  {} shadow_roots={}, mobile_rules={}",
+                        "  {} shadow_roots={}, mobile_rules={}, payloads={}, galleries={}",
                         style("recovery:", color, |value| value.bold().to_string()),
                         attempt.recovery.shadow_roots_flattened,
-                        attempt.recovery.mobile_rules_applied
+                        attempt.recovery.mobile_rules_applied,
+                        attempt.recovery.payloads_decoded,
+                        attempt.recovery.galleries_flattened
                     );
                 }
+                if !attempt.preprocess.is_empty() {
+                    eprintln!("  {}", style("preprocess:", color, |value| value.bold().to_string()));
+                    for rule in &attempt.preprocess {
+                        eprintln!(
+                            "    {} text -{} elements -{}",
+                            rule.rule, rule.removed_text_len, rule.removed_elements
+                        );
+                    }
+                }
                 if !attempt.entry_points.is_empty() {
                     eprintln!("  {}", style("entry points:", color, |value| value.bold().to_string()));
                     for candidate in attempt.entry_points.iter().take(3) {
@@ -180,6 +251,18 @@ pub fn diagnostics_with_atproto_warnings(
                         cleanup.element_count_after,
                         cleanup.removed_elements
                     );
+                    for rule in &cleanup.rules {
+                        eprintln!(
+                            "    {} text -{} elements -{}",
+                            rule.rule, rule.removed_text_len, rule.removed_elements
+                        );
+                    }
+                    for removal in &cleanup.dry_run {
+                        eprintln!(
+                            "    would remove {} by {} (text {})",
+                            removal.selector, removal.rule, removal.text_len
+                        );
+                    }
                 }
             }
         }
@@ -190,9 +273,16 @@ pub fn diagnostics_with_atproto_warnings(
 
 pub fn render_article(article: Option<&Article>, opts: RenderOptions) -> Result<String> {
     match opts.format {
-        OutputFormat::Json => match opts.pretty {
-            true => serde_json::to_string_pretty(&article).context("failed to serialize JSON"),
-            false => serde_json::to_string(&article).context("failed to serialize JSON"),
+        OutputFormat::Json => {
+            let article = article_json(article, opts.keyphrases)?;
+            match opts.pretty {
+                true => serde_json::to_string_pretty(&article).context("failed to serialize JSON"),
+                false => serde_json::to_string(&article).context("failed to serialize JSON"),
+            }
+        }
+        OutputFormat::Ndjson => match article {
+            Some(article) => ndjson_record(article, &opts),
+            None => Ok(String::new()),
         },
         OutputFormat::Html => match article {
             Some(article) => Ok(article.content.clone()),
@@ -211,11 +301,119 @@ pub fn render_article(article: Option<&Article>, opts: RenderOptions) -> Result<
             Some(article) => Ok(article.text_content.clone()),
             None => Ok(String::new()),
         },
+        OutputFormat::Gmi => match article {
+            Some(article) => Ok(crate::gemtext::render(article, opts.source)),
+            None => Ok(String::new()),
+        },
+        OutputFormat::Latex => match article {
+            Some(article) => Ok(crate::latex::render(article, opts.source)),
+            None => Ok(String::new()),
+        },
+        OutputFormat::Term => match article {
+            Some(article) => Ok(crate::term::render(
+                article,
+                opts.source,
+                crate::term::width(),
+                opts.color,
+            )),
+            None => Ok(String::new()),
+        },
         #[cfg(feature = "pdf")]
         OutputFormat::Pdf => anyhow::bail!("PDF output is rendered as bytes"),
+        OutputFormat::Epub => anyhow::bail!("EPUB output is written with Article::save_from"),
     }
 }
 
+/// One NDJSON line: article metadata, the source URL, and the selected content.
+fn ndjson_record(article: &Article, opts: &RenderOptions) -> Result<String> {
+    let mut value = article_json(Some(article), opts.keyphrases)?;
+    if let Some(object) = value.as_object_mut() {
+        for (content, field) in [
+            (NdjsonContent::Html, "content"),
+            (NdjsonContent::Markdown, "markdown"),
+            (NdjsonContent::Text, "text_content"),
+        ] {
+            if !opts.ndjson_content.contains(&content) {
+                object.remove(field);
+            }
+        }
+        if let Some(source) = opts.source {
+            object.insert("source".to_string(), serde_json::json!(source));
+        }
+    }
+    serde_json::to_string(&value).context("failed to serialize JSON")
+}
+
+/// JSON Schema for `--format json` and `--format ndjson` output: the article
+/// schema plus the fields [`article_json`] and [`ndjson_record`] add. JSON
+/// output is `null` when no article was extracted.
+pub fn json_schema() -> serde_json::Value {
+    let mut schema = Article::json_schema();
+    schema["type"] = serde_json::json!(["object", "null"]);
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.insert(
+            "keyphrases".to_string(),
+            serde_json::json!({
+                "description": "Ranked keyphrases, present with `--keyphrases`.",
+                "type": "array",
+                "items": { "type": "string" },
+            }),
+        );
+        properties.insert(
+            "source".to_string(),
+            serde_json::json!({
+                "description": "Input URL, present in ndjson records.",
+                "type": "string",
+            }),
+        );
+        #[cfg(feature = "textstats")]
+        {
+            let mut metrics = lectito::ReadabilityMetrics::json_schema();
+            if let Some(metrics) = metrics.as_object_mut() {
+                metrics.remove("$schema");
+            }
+            properties.insert("readability_metrics".to_string(), metrics);
+        }
+    }
+    schema
+}
+
+/// Article JSON plus computed fields that are not part of `Article`.
+pub fn article_json(article: Option<&Article>, keyphrases: Option<usize>) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(article).context("failed to serialize JSON")?;
+    if let (Some(article), Some(count), Some(object)) = (article, keyphrases, value.as_object_mut()) {
+        object.insert("keyphrases".to_string(), serde_json::json!(article.keyphrases(count)));
+    }
+    #[cfg(feature = "textstats")]
+    if let (Some(article), Some(object)) = (article, value.as_object_mut()) {
+        let metrics = serde_json::to_value(article.readability_metrics()).context("failed to serialize JSON")?;
+        object.insert("readability_metrics".to_string(), metrics);
+    }
+    Ok(value)
+}
+
+/// One stderr line with the time spent in each stage of a run.
+pub fn timings(fetch: Duration, extraction: &Timings, format: Duration) -> String {
+    let stages = [
+        ("fetch", fetch),
+        ("parse", extraction.parse),
+        ("extract", extraction.extract),
+        ("postprocess", extraction.postprocess),
+        ("format", format),
+    ];
+    let stages: Vec<String> = stages
+        .iter()
+        .map(|(stage, time)| format!("{stage} {:.1}ms", time.as_secs_f64() * 1000.0))
+        .collect();
+    format!("lectito: timings: {}", stages.join(", "))
+}
+
+pub fn json(value: &serde_json::Value, pretty: bool) -> Result<()> {
+    let output = if pretty { serde_json::to_string_pretty(value) } else { serde_json::to_string(value) };
+    println!("{}", output.context("failed to serialize JSON")?);
+    Ok(())
+}
+
 pub fn readable(readable: bool, json: bool, pretty: bool) -> Result<()> {
     if json {
         let value = serde_json::json!({ "readable": readable });
@@ -241,6 +439,7 @@ pub fn inspect(report: &ExtractionReport, opts: InspectOptions) -> Result<String
             "article": report.article,
             "diagnostics": report.diagnostics,
             "atproto_warnings": opts.atproto_warnings,
+            "site_profiles": profile_paths(opts.site_profiles),
         });
         if opts.pretty {
             return serde_json::to_string_pretty(&value).context("failed to serialize inspect JSON");
@@ -257,6 +456,12 @@ pub fn inspect(report: &ExtractionReport, opts: InspectOptions) -> Result<String
         lines.push("atproto warnings:".to_string());
         lines.extend(opts.atproto_warnings.iter().map(|warning| format!("  {warning}")));
     }
+    if !opts.site_profiles.is_empty() {
+        lines.push(format!(
+            "site profiles: {}",
+            profile_paths(opts.site_profiles).join(", ")
+        ));
+    }
 
     match &report.article {
         Some(article) => {
@@ -272,12 +477,25 @@ pub fn inspect(report: &ExtractionReport, opts: InspectOptions) -> Result<String
             if let Some(published_time) = &article.published_time {
                 lines.push(format!("published: {published_time}"));
             }
+            if let Some(paywalled) = article.is_paywalled {
+                lines.push(format!("paywalled: {paywalled}"));
+            }
+            if let Some(sponsored) = article.is_sponsored {
+                lines.push(format!("sponsored: {sponsored}"));
+            }
             lines.push(format!("text chars: {}", article.text_content.chars().count()));
             lines.push(format!("content html bytes: {}", article.content.len()));
         }
         None => lines.push("article: none".to_string()),
     }
 
+    lines.extend(
+        report
+            .diagnostics
+            .warnings
+            .iter()
+            .map(|warning| format!("warning: {warning}")),
+    );
     lines.push(format!("attempts: {}", report.diagnostics.attempts.len()));
     if let Some(index) = report.diagnostics.selected_attempt {
         lines.push(format!("selected attempt: {index}"));
@@ -296,6 +514,11 @@ pub fn inspect(report: &ExtractionReport, opts: InspectOptions) -> Result<String
             site_rule.source,
             if site_rule.accepted { "accepted" } else { "fallback" }
         ));
+        lines.extend(
+            site_rule_provenance(site_rule, opts.site_profiles)
+                .into_iter()
+                .map(|(label, value)| format!("  {label} {value}")),
+        );
     }
     if let Some(attempt) = report.diagnostics.selected_attempt.and_then(|index| {
         report
@@ -338,6 +561,45 @@ pub fn inspect(report: &ExtractionReport, opts: InspectOptions) -> Result<String
     Ok(lines.join("\n"))
 }
 
+fn profile_paths(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter().map(|path| path.display().to_string()).collect()
+}
+
+/// Where the matched profile came from, which matching profiles it shadowed,
+/// the per-run overrides applied to it, and the conditions the page met.
+fn site_rule_provenance(site_rule: &SiteRuleDiagnostic, site_profiles: &[PathBuf]) -> Vec<(&'static str, String)> {
+    let mut lines = vec![("from:", profile_origin(&site_rule.matched_by, site_profiles))];
+    if !site_rule.shadowed.is_empty() {
+        let shadowed = site_rule
+            .shadowed
+            .iter()
+            .map(|rule| format!("{} ({})", rule.name, profile_origin(&rule.matched_by, site_profiles)))
+            .collect::<Vec<_>>();
+        lines.push(("shadowed:", shadowed.join(", ")));
+    }
+    if !site_rule.overrides.is_empty() {
+        lines.push(("overrides:", site_rule.overrides.join(", ")));
+    }
+    if !site_rule.conditions.is_empty() {
+        lines.push(("conditions:", site_rule.conditions.join(", ")));
+    }
+    lines
+}
+
+fn profile_origin(matched_by: &SiteRuleMatch, site_profiles: &[PathBuf]) -> String {
+    let origin = match (matched_by.user_index, matched_by.file.as_deref()) {
+        (Some(index), _) => site_profiles
+            .get(index)
+            .map_or_else(|| format!("site_profiles[{index}]"), |path| path.display().to_string()),
+        (None, Some(file)) => format!("bundled {file}"),
+        (None, None) => "built-in extractor".to_string(),
+    };
+    match &matched_by.fingerprint {
+        Some(fingerprint) => format!("{origin} via {fingerprint}"),
+        None => origin,
+    }
+}
+
 fn style(value: &str, color: bool, apply: impl FnOnce(&str) -> String) -> String {
     if color { apply(value) } else { value.to_string() }
 }
@@ -1,10 +1,12 @@
 use anyhow::Context;
+use reqwest::blocking::{Client, Response};
 use reqwest::header::{
     ACCEPT, ACCEPT_LANGUAGE, CACHE_CONTROL, CONTENT_TYPE, HeaderMap, HeaderValue, LAST_MODIFIED, LOCATION, REFERER,
 };
 use reqwest::redirect::Policy;
-use reqwest::{StatusCode, Url, blocking::Client};
+use reqwest::{StatusCode, Url};
 use scraper::{Html, Selector};
+use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
 use std::process;
@@ -17,6 +19,54 @@ pub const CURL_USER_AGENT: &str = "curl/8.7.1";
 pub const MAX_REDIRECTS: usize = 10;
 pub const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
 
+/// How inputs are read.
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    /// Refuse fetched pages, files, and stdin input larger than this.
+    pub max_input_bytes: Option<usize>,
+    /// Send this instead of the browser user agent.
+    pub user_agent: Option<String>,
+}
+
+impl ReadOptions {
+    /// The configured user agent, or the browser one.
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(USER_AGENT)
+    }
+}
+
+/// Read at most `limit` bytes from `reader`, failing when there is more.
+pub(crate) fn read_limited(reader: impl Read, source: &str, limit: Option<usize>) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit.map_or(u64::MAX, |limit| limit as u64 + 1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("failed to read {source}"))?;
+    if let Some(limit) = limit
+        && bytes.len() > limit
+    {
+        anyhow::bail!("{source} is larger than --max-input-bytes {limit}");
+    }
+    Ok(bytes)
+}
+
+/// Read a local file, rejecting it by its size before reading when it is
+/// over `limit`.
+fn read_file(path: &Path, limit: Option<usize>) -> anyhow::Result<Vec<u8>> {
+    let source = path.display().to_string();
+    let file = File::open(path).with_context(|| format!("failed to read {source}"))?;
+    if let (Some(limit), Ok(metadata)) = (limit, file.metadata())
+        && metadata.is_file()
+        && metadata.len() > limit as u64
+    {
+        anyhow::bail!(
+            "{source} is {} bytes, larger than --max-input-bytes {limit}",
+            metadata.len()
+        );
+    }
+    read_limited(file, &source, limit)
+}
+
 #[derive(Clone, Copy)]
 enum FetchProfile {
     Browser,
@@ -24,9 +74,9 @@ enum FetchProfile {
 }
 
 impl FetchProfile {
-    fn user_agent(self) -> &'static str {
+    fn user_agent(self, options: &ReadOptions) -> &str {
         match self {
-            Self::Browser => USER_AGENT,
+            Self::Browser => options.user_agent(),
             Self::Curl => CURL_USER_AGENT,
         }
     }
@@ -59,11 +109,36 @@ pub struct InputDocument {
     content_type: Option<String>,
     last_modified: Option<String>,
     atproto_warnings: Vec<String>,
+    resources: Vec<lectito::Resource>,
+    original_html: Option<String>,
 }
 
 impl InputDocument {
     fn new(html: String, base_url: Option<String>, content_type: Option<String>, lastmod: Option<String>) -> Self {
-        Self { html, base_url, content_type, last_modified: lastmod, atproto_warnings: Vec::new() }
+        Self {
+            html,
+            base_url,
+            content_type,
+            last_modified: lastmod,
+            atproto_warnings: Vec::new(),
+            resources: Vec::new(),
+            original_html: None,
+        }
+    }
+
+    /// Read a local file or stdin. MHTML and Safari web archives are unpacked:
+    /// the main page becomes the HTML, the saved URL the base URL unless
+    /// `base_url` is given, and the other parts its resources.
+    fn from_bytes(bytes: Vec<u8>, base_url: Option<&str>, source: &str) -> anyhow::Result<InputDocument> {
+        if lectito::Document::is_archive(&bytes) {
+            let document = lectito::Document::parse(&bytes).with_context(|| format!("failed to unpack {source}"))?;
+            let base_url = base_url.map(str::to_string).or(document.url);
+            let mut input = InputDocument::new(document.html, base_url, None, None);
+            input.resources = document.resources;
+            return Ok(input);
+        }
+        let html = lectito::Document::parse_bytes(&bytes, None).html;
+        Ok(InputDocument::new(html, base_url.map(str::to_string), None, None))
     }
 
     fn with_atproto_warnings(mut self, warnings: Vec<String>) -> Self {
@@ -91,15 +166,26 @@ impl InputDocument {
         &self.atproto_warnings
     }
 
-    pub fn read_src(input: Option<&str>, read_stdin: bool, base_url: Option<&str>) -> anyhow::Result<InputDocument> {
+    /// The page as fetched, before a Standard.site record replaced it.
+    pub fn original_html(&self) -> &str {
+        self.original_html.as_deref().unwrap_or(&self.html)
+    }
+
+    /// Files saved with the page when the input was a web archive.
+    pub fn resources(&self) -> &[lectito::Resource] {
+        &self.resources
+    }
+
+    pub fn read_src(
+        input: Option<&str>, read_stdin: bool, base_url: Option<&str>, options: &ReadOptions,
+    ) -> anyhow::Result<InputDocument> {
         if read_stdin && input.is_some_and(|value| value != "-") {
             anyhow::bail!("cannot combine --stdin with an input path or URL");
         }
 
         if read_stdin || input == Some("-") {
-            let mut html = String::new();
-            io::stdin().read_to_string(&mut html).context("failed to read stdin")?;
-            return Ok(InputDocument::new(html, base_url.map(str::to_string), None, None));
+            let bytes = read_limited(io::stdin(), "stdin", options.max_input_bytes)?;
+            return Self::from_bytes(bytes, base_url, "stdin");
         }
 
         let Some(input) = input else {
@@ -110,48 +196,52 @@ impl InputDocument {
             if base_url.is_some() {
                 anyhow::bail!("cannot combine --base-url with a URL input");
             }
-            return Self::read(None, false, Some(input));
+            return Self::read(None, false, Some(input), options);
         }
 
         if input.starts_with("at://") {
             if base_url.is_some() {
                 anyhow::bail!("cannot combine --base-url with an AT URI input");
             }
-            return Self::atproto(input);
+            return Self::atproto(input, options);
         }
 
         let path = Path::new(input);
-        let html = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
-        Ok(InputDocument::new(html, base_url.map(str::to_string), None, None))
+        let bytes = read_file(path, options.max_input_bytes)?;
+        Self::from_bytes(bytes, base_url, &path.display().to_string())
     }
 
-    pub fn read(path: Option<&Path>, read_stdin: bool, url: Option<&str>) -> anyhow::Result<InputDocument> {
+    pub fn read(
+        path: Option<&Path>, read_stdin: bool, url: Option<&str>, options: &ReadOptions,
+    ) -> anyhow::Result<InputDocument> {
         if read_stdin && path.is_some() {
             anyhow::bail!("cannot combine --stdin with a file path");
         }
 
         if read_stdin {
-            let mut html = String::new();
-            io::stdin().read_to_string(&mut html).context("failed to read stdin")?;
-            return Ok(InputDocument::new(html, url.map(str::to_string), None, None));
+            let bytes = read_limited(io::stdin(), "stdin", options.max_input_bytes)?;
+            return Self::from_bytes(bytes, url, "stdin");
         }
 
         if let Some(path) = path {
-            let html = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
-            return Ok(InputDocument::new(html, url.map(str::to_string), None, None));
+            let bytes = read_file(path, options.max_input_bytes)?;
+            return Self::from_bytes(bytes, url, &path.display().to_string());
         }
 
         if let Some(url) = url {
-            return Self::profile(url, FetchProfile::Browser).or_else(|error| {
+            let _span = tracing::debug_span!("fetch", url).entered();
+            return Self::profile(url, FetchProfile::Browser, options).or_else(|error| {
                 let message = format!("{error:?}");
 
                 if message.contains("403 Forbidden") || message.contains("429 Too Many Requests") {
-                    Self::profile(url, FetchProfile::Curl).or_else(|err| {
+                    tracing::debug!(error = %message, "browser request blocked; retrying with curl user agent");
+                    Self::profile(url, FetchProfile::Curl, options).or_else(|err| {
                         let curl_err_message = format!("{err:?}");
                         if curl_err_message.contains("403 Forbidden")
                             || curl_err_message.contains("429 Too Many Requests")
                         {
-                            Self::curl(url)
+                            tracing::debug!(error = %curl_err_message, "request blocked again; falling back to curl");
+                            Self::curl(url, options)
                         } else {
                             Err(err)
                         }
@@ -165,9 +255,9 @@ impl InputDocument {
         anyhow::bail!("pass either --stdin, a file path, or --url without a file path")
     }
 
-    fn profile(url: &str, profile: FetchProfile) -> anyhow::Result<InputDocument> {
+    fn profile(url: &str, profile: FetchProfile, options: &ReadOptions) -> anyhow::Result<InputDocument> {
         let client = Client::builder()
-            .user_agent(profile.user_agent())
+            .user_agent(profile.user_agent(options))
             .default_headers(profile.headers())
             .redirect(Policy::none())
             .timeout(FETCH_TIMEOUT)
@@ -204,6 +294,7 @@ impl InputDocument {
                 current_url = current_url
                     .join(location)
                     .with_context(|| format!("failed to resolve redirect from {current_url} to {location}"))?;
+                tracing::debug!(to = %current_url, hop = redirect_count + 1, "following redirect");
                 continue;
             }
 
@@ -220,33 +311,42 @@ impl InputDocument {
                 .get(LAST_MODIFIED)
                 .and_then(|value| value.to_str().ok())
                 .map(str::to_string);
-            let html = response
-                .text()
-                .with_context(|| format!("failed to read response body for {current_url}"))?;
+            let html = read_body(response, content_type.as_deref(), &current_url, options.max_input_bytes)?;
 
-            if let Some(redirect_url) = html_redirect_target(&html, &current_url) {
+            let frame_url = lectito::main_frame_url(&html, Some(current_url.as_str()))
+                .and_then(|frame_url| Url::parse(&frame_url).ok())
+                .filter(|frame_url| matches!(frame_url.scheme(), "http" | "https"));
+            if let Some(redirect_url) = html_redirect_target(&html, &current_url).or(frame_url) {
                 if redirect_count == MAX_REDIRECTS {
                     anyhow::bail!("too many redirects while fetching {url}");
                 }
 
+                tracing::debug!(to = %redirect_url, hop = redirect_count + 1, "following meta refresh or frame");
                 current_url = redirect_url;
                 continue;
             }
-
-            let (html, atproto_warnings) = standard_site_html(&client, &html, Some(current_url.as_str()))
-                .map(|render| (render.html, render.warnings))
-                .unwrap_or((html, Vec::new()));
-
-            return Ok(
-                InputDocument::new(html, Some(current_url.to_string()), content_type, last_modified)
-                    .with_atproto_warnings(atproto_warnings),
-            );
+            tracing::debug!(url = %current_url, bytes = html.len(), content_type, "fetched document");
+
+            let Some(render) = standard_site_html(&client, &html, Some(current_url.as_str())) else {
+                return Ok(InputDocument::new(
+                    html,
+                    Some(current_url.to_string()),
+                    content_type,
+                    last_modified,
+                ));
+            };
+            let mut document =
+                InputDocument::new(render.html, Some(current_url.to_string()), content_type, last_modified)
+                    .with_atproto_warnings(render.warnings);
+            tracing::debug!("using Standard.site record content");
+            document.original_html = Some(html);
+            return Ok(document);
         }
 
         unreachable!("redirect loop exits by returning a response or bailing at the redirect limit")
     }
 
-    fn curl(url: &str) -> anyhow::Result<InputDocument> {
+    fn curl(url: &str, options: &ReadOptions) -> anyhow::Result<InputDocument> {
         let marker = "\nLECTITO_EFFECTIVE_URL:";
         let headers_path = {
             let nanos = SystemTime::now()
@@ -255,7 +355,11 @@ impl InputDocument {
                 .unwrap_or_default();
             std::env::temp_dir().join(format!("lectito-curl-headers-{}-{nanos}", std::process::id()))
         };
-        let output = process::Command::new("curl")
+        let mut command = process::Command::new("curl");
+        if let Some(limit) = options.max_input_bytes {
+            command.args(["--max-filesize", &limit.to_string()]);
+        }
+        let output = command
             .args(["-sS", "-L", "--fail", "--compressed", "--max-time", "20", "-D"])
             .arg(&headers_path)
             .args([
@@ -277,10 +381,16 @@ impl InputDocument {
             .ok()
             .and_then(|headers| final_header_value(&headers, "last-modified"));
         let _ = std::fs::remove_file(&headers_path);
-        let output = String::from_utf8(output.stdout).context("curl fallback returned non-UTF-8 body")?;
-        let Some((html, metadata)) = output.rsplit_once(marker) else {
+        let Some(split) = output
+            .stdout
+            .windows(marker.len())
+            .rposition(|window| window == marker.as_bytes())
+        else {
             anyhow::bail!("curl fallback output did not include final URL for {url}");
         };
+        let (body, metadata) = output.stdout.split_at(split);
+        let metadata =
+            std::str::from_utf8(&metadata[marker.len()..]).context("curl fallback returned non-UTF-8 metadata")?;
         let (effective_url, content_type) = metadata
             .split_once("\nLECTITO_CONTENT_TYPE:")
             .map(|(url, content_type)| {
@@ -290,17 +400,19 @@ impl InputDocument {
             })
             .unwrap_or((metadata.trim(), None));
 
+        let html = lectito::Document::parse_bytes(body, content_type.as_deref()).html;
+
         Ok(InputDocument::new(
-            html.to_string(),
+            html,
             Some(effective_url.to_string()),
             content_type,
             last_modified,
         ))
     }
 
-    fn atproto(at_uri: &str) -> anyhow::Result<InputDocument> {
+    fn atproto(at_uri: &str, options: &ReadOptions) -> anyhow::Result<InputDocument> {
         let client = Client::builder()
-            .user_agent(USER_AGENT)
+            .user_agent(options.user_agent())
             .timeout(FETCH_TIMEOUT)
             .build()
             .with_context(|| format!("failed to build ATProto client for {at_uri}"))?;
@@ -320,6 +432,21 @@ impl InputDocument {
     }
 }
 
+/// Read a response body up to the input limit, rejecting it early when
+/// `Content-Length` is already too large, and decode it by its byte-order
+/// mark, the charset in `content_type`, or its `<meta>` charset.
+fn read_body(
+    response: Response, content_type: Option<&str>, url: &Url, limit: Option<usize>,
+) -> anyhow::Result<String> {
+    if let (Some(limit), Some(length)) = (limit, response.content_length())
+        && length > limit as u64
+    {
+        anyhow::bail!("{url} is {length} bytes, larger than --max-input-bytes {limit}");
+    }
+    let bytes = read_limited(response, &format!("response body for {url}"), limit)?;
+    Ok(lectito::Document::parse_bytes(&bytes, content_type).html)
+}
+
 fn standard_site_html(client: &Client, html: &str, source_url: Option<&str>) -> Option<atproto::StandardSiteRender> {
     let at_uri = atproto::standard_site_link(html)?;
     let atproto = AtprotoClient::new(client.clone());
@@ -409,6 +536,39 @@ mod tests {
     use std::net::TcpListener;
     use std::thread;
 
+    /// Read a request's head so the client sees its response rather than a
+    /// reset connection, and return it.
+    fn read_request(stream: &mut impl Read) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0];
+        while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).is_ok_and(|read| read == 1) {
+            head.push(byte[0]);
+        }
+        String::from_utf8_lossy(&head).into_owned()
+    }
+
+    #[test]
+    fn unpacks_web_archive_input() {
+        let mhtml = "Snapshot-Content-Location: https://example.com/tides\r\n\
+            Content-Type: multipart/related; boundary=\"page\"\r\n\r\n\
+            --page\r\nContent-Type: text/html\r\n\r\n<p>The tide comes in.</p>\r\n\
+            --page\r\nContent-Type: image/png\r\nContent-Location: https://example.com/tide.png\r\n\r\nPNG\r\n\
+            --page--\r\n";
+
+        let document = InputDocument::from_bytes(mhtml.as_bytes().to_vec(), None, "tides.mhtml").unwrap();
+        assert_eq!(document.html(), "<p>The tide comes in.</p>");
+        assert_eq!(document.base_url(), Some("https://example.com/tides"));
+        assert_eq!(document.resources()[0].url, "https://example.com/tide.png");
+
+        let document =
+            InputDocument::from_bytes(mhtml.as_bytes().to_vec(), Some("https://mirror.example"), "stdin").unwrap();
+        assert_eq!(document.base_url(), Some("https://mirror.example"));
+
+        let document = InputDocument::from_bytes(b"<p>Plain</p>".to_vec(), None, "stdin").unwrap();
+        assert_eq!(document.html(), "<p>Plain</p>");
+        assert!(document.resources().is_empty());
+    }
+
     #[test]
     fn detects_small_meta_refresh_redirect_page() {
         let html = r#"<!doctype html>
@@ -439,6 +599,7 @@ mod tests {
         let address = listener.local_addr().expect("test server address");
         let server = thread::spawn(move || {
             let (mut stream, _) = listener.accept().expect("accept test request");
+            read_request(&mut stream);
             let body = "# Hello\n";
             let response = format!(
                 "HTTP/1.1 200 OK\r\nContent-Type: text/markdown; charset=utf-8\r\nLast-Modified: Wed, 01 May 2024 10:00:00 GMT\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
@@ -449,7 +610,8 @@ mod tests {
         });
 
         let url = format!("http://{address}/doc.md");
-        let document = InputDocument::read_src(Some(&url), false, None).expect("read test URL");
+        let document =
+            InputDocument::read_src(Some(&url), false, None, &ReadOptions::default()).expect("read test URL");
 
         server.join().expect("join test server");
         assert_eq!(document.content_type(), Some("text/markdown; charset=utf-8"));
@@ -457,6 +619,61 @@ mod tests {
         assert_eq!(document.html(), "# Hello\n");
     }
 
+    #[test]
+    fn sends_the_configured_user_agent() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let address = listener.local_addr().expect("test server address");
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept test request");
+            let head = read_request(&mut stream);
+            let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+            stream.write_all(response.as_bytes()).expect("write response");
+            head
+        });
+
+        let url = format!("http://{address}/");
+        let options = ReadOptions { user_agent: Some("HarbourBot/1.0".to_string()), ..Default::default() };
+        InputDocument::read_src(Some(&url), false, None, &options).expect("read test URL");
+
+        let head = server.join().expect("join test server").to_ascii_lowercase();
+        assert!(head.contains("user-agent: harbourbot/1.0\r\n"), "{head}");
+        assert_eq!(ReadOptions::default().user_agent(), USER_AGENT);
+    }
+
+    #[test]
+    fn rejects_inputs_over_the_size_limit() {
+        let limited = ReadOptions { max_input_bytes: Some(8), ..Default::default() };
+        let path = std::env::temp_dir().join(format!("lectito-limit-test-{}.html", std::process::id()));
+        std::fs::write(&path, "<p>The tide comes in.</p>").unwrap();
+        let file = InputDocument::read(Some(&path), false, None, &limited).err().unwrap();
+        let small = InputDocument::read(
+            Some(&path),
+            false,
+            None,
+            &ReadOptions { max_input_bytes: Some(64), ..Default::default() },
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            format!("{file:#}").contains("larger than --max-input-bytes 8"),
+            "{file:#}"
+        );
+        assert_eq!(small.unwrap().html(), "<p>The tide comes in.</p>");
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let address = listener.local_addr().expect("test server address");
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept test request");
+            read_request(&mut stream);
+            let response =
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 1000000\r\nConnection: close\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes());
+        });
+        let url = format!("http://{address}/big.html");
+        let fetched = InputDocument::read(None, false, Some(&url), &limited).err().unwrap();
+        server.join().expect("join test server");
+        assert!(format!("{fetched:#}").contains("1000000 bytes"), "{fetched:#}");
+    }
+
     #[test]
     fn final_header_value_uses_final_response_block() {
         let headers = "\
@@ -0,0 +1,293 @@
+use lectito::Article;
+use reqwest::Url;
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+
+use crate::html::{self, Walker, collapse};
+
+/// Line prefixes that gemtext gives a meaning to.
+const LINE_MARKERS: &[&str] = &["=>", "```", "#", "* ", ">"];
+
+/// Article as Gemini gemtext.
+///
+/// Gemtext has no inline markup, so each paragraph becomes one text line and
+/// its links and images follow it as `=>` link lines. Headings deeper than
+/// three levels become `###`, and code blocks and tables become preformatted
+/// blocks.
+pub fn render(article: &Article, source: Option<&str>) -> String {
+    let base = source.and_then(|source| Url::parse(source).ok());
+    let mut writer = Writer { base: base.as_ref(), blocks: Vec::new(), title: None };
+    if let Some(title) = article.title.as_deref().map(collapse).filter(|title| !title.is_empty()) {
+        writer.blocks.push(vec![format!("# {title}")]);
+        writer.title = Some(title);
+    }
+    let document = Html::parse_fragment(&article.content);
+    writer.children(document.root_element());
+
+    writer
+        .blocks
+        .iter()
+        .map(|block| block.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+struct Writer<'a> {
+    base: Option<&'a Url>,
+    blocks: Vec<Vec<String>>,
+    /// The title heading, until the article's own copy of it is skipped.
+    title: Option<String>,
+}
+
+#[derive(Default)]
+struct Paragraph {
+    text: String,
+    links: Vec<String>,
+}
+
+impl Walker for Writer<'_> {
+    type Inline = Paragraph;
+
+    fn text(&self, text: &str, paragraph: &mut Paragraph) {
+        paragraph.text.push_str(text);
+    }
+
+    fn block(&mut self, element: ElementRef<'_>) {
+        match element.value().name() {
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let paragraph = self.collect(element);
+                let text = collapse(&paragraph.text);
+                if text.is_empty() {
+                    return;
+                }
+                if self.title.take().is_some_and(|title| title == text) && self.blocks.len() == 1 {
+                    return;
+                }
+                let level = name[1..].parse::<usize>().unwrap_or(3).min(3);
+                self.push(vec![format!("{} {text}", "#".repeat(level))], paragraph.links);
+            }
+            "ul" | "ol" => {
+                let mut lines = Vec::new();
+                let mut links = Vec::new();
+                self.list(element, &mut lines, &mut links);
+                self.push(lines, links);
+            }
+            "pre" => {
+                let alt = element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .flat_map(|element| element.value().classes())
+                    .find_map(|class| class.strip_prefix("language-"))
+                    .unwrap_or_default();
+                let text = element.text().collect::<String>();
+                let mut lines = vec![format!("```{alt}")];
+                lines.extend(text.trim_end().lines().map(|line| {
+                    if line.starts_with("```") { format!(" {line}") } else { line.to_string() }
+                }));
+                lines.push("```".to_string());
+                self.push(lines, Vec::new());
+            }
+            "table" => self.table(element),
+            "blockquote" => {
+                let mut quote = Writer { base: self.base, blocks: Vec::new(), title: None };
+                quote.children(element);
+                let mut lines = Vec::new();
+                for block in quote.blocks {
+                    if !lines.is_empty() {
+                        lines.push(">".to_string());
+                    }
+                    let preformatted = block.first().is_some_and(|line| line.starts_with("```"));
+                    lines.extend(
+                        block
+                            .into_iter()
+                            .map(|line| match preformatted || line.starts_with("=>") {
+                                true => line,
+                                false => format!("> {line}"),
+                            }),
+                    );
+                }
+                self.push(lines, Vec::new());
+            }
+            "hr" => {}
+            _ => self.children(element),
+        }
+    }
+
+    fn inline(&self, element: ElementRef<'_>, paragraph: &mut Paragraph) {
+        match element.value().name() {
+            "br" => paragraph.text.push('\n'),
+            "img" => {
+                if let Some(url) = element
+                    .value()
+                    .attr("src")
+                    .and_then(|src| html::resolve(self.base, src))
+                {
+                    let alt = collapse(element.value().attr("alt").unwrap_or_default());
+                    paragraph.links.push(link_line(&url, &alt));
+                }
+            }
+            name => {
+                let start = paragraph.text.len();
+                let inner = self.collect(element);
+                paragraph.text.push_str(&inner.text);
+                paragraph.links.extend(inner.links);
+                if name == "a"
+                    && let Some(url) = element
+                        .value()
+                        .attr("href")
+                        .and_then(|href| html::resolve(self.base, href))
+                {
+                    let label = collapse(&paragraph.text[start..]);
+                    paragraph.links.push(link_line(&url, &label));
+                }
+            }
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: Paragraph) {
+        let lines: Vec<String> = paragraph
+            .text
+            .split('\n')
+            .map(collapse)
+            .filter(|line| !line.is_empty())
+            .map(
+                |line| match LINE_MARKERS.iter().any(|marker| line.starts_with(marker)) {
+                    true => format!(" {line}"),
+                    false => line,
+                },
+            )
+            .collect();
+        self.push(lines, paragraph.links);
+    }
+}
+
+impl Writer<'_> {
+    fn list(&mut self, list: ElementRef<'_>, lines: &mut Vec<String>, links: &mut Vec<String>) {
+        for item in list.children().filter_map(ElementRef::wrap) {
+            let mut paragraph = Paragraph::default();
+            let mut nested = Vec::new();
+            for child in item.children() {
+                match child.value() {
+                    Node::Text(text) => self.text(text, &mut paragraph),
+                    Node::Element(element) if matches!(element.name(), "ul" | "ol") => {
+                        nested.extend(ElementRef::wrap(child));
+                    }
+                    Node::Element(_) => {
+                        if let Some(child) = ElementRef::wrap(child) {
+                            self.element(child, &mut paragraph);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let text = collapse(&paragraph.text);
+            if !text.is_empty() {
+                lines.push(format!("* {text}"));
+            }
+            links.extend(paragraph.links);
+            for nested in nested {
+                self.list(nested, lines, links);
+            }
+        }
+    }
+
+    fn table(&mut self, table: ElementRef<'_>) {
+        let mut lines = vec!["```".to_string()];
+        let mut links = Vec::new();
+        for row in table
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|element| element.value().name() == "tr")
+        {
+            let cells: Vec<String> = row
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .map(|cell| {
+                    let paragraph = self.collect(cell);
+                    links.extend(paragraph.links);
+                    collapse(&paragraph.text)
+                })
+                .collect();
+            if cells.iter().any(|cell| !cell.is_empty()) {
+                lines.push(cells.join(" | "));
+            }
+        }
+        if lines.len() > 1 {
+            lines.push("```".to_string());
+        } else {
+            lines.clear();
+        }
+        self.push(lines, links);
+    }
+
+    /// Add a block followed by its link lines. Links after a preformatted
+    /// block go in a block of their own.
+    fn push(&mut self, mut lines: Vec<String>, links: Vec<String>) {
+        let mut link_lines: Vec<String> = Vec::new();
+        for link in links {
+            if !link_lines.contains(&link) {
+                link_lines.push(link);
+            }
+        }
+        if lines.first().is_some_and(|line| line.starts_with("```")) {
+            self.blocks.push(lines);
+            lines = Vec::new();
+        }
+        lines.extend(link_lines);
+        if !lines.is_empty() {
+            self.blocks.push(lines);
+        }
+    }
+}
+
+fn link_line(url: &str, label: &str) -> String {
+    match label.is_empty() || label == url {
+        true => format!("=> {}", url.replace(' ', "%20")),
+        false => format!("=> {} {label}", url.replace(' ', "%20")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article;
+
+    #[test]
+    fn renders_line_oriented_gemtext() {
+        let article = article(
+            r##"<h1>On Tides</h1>
+            <p>The moon <a href="/moon">pulls</a> the sea.<br>See <a href="#note">the note</a>.</p>
+            <h4>Deep <em>water</em></h4>
+            <ul><li>Spring <a href="https://example.org/spring">tides</a><ul><li>Neap</li></ul></li></ul>
+            <figure><img src="chart.png" alt="Tide chart"><figcaption>Heights by hour</figcaption></figure>
+            <blockquote><p>Time and tide.</p></blockquote>
+            <pre><code class="language-sh">```
+tide --now</code></pre>
+            <table><tr><th>Port</th><th>High</th></tr><tr><td>Dover</td><td>06:12</td></tr></table>"##,
+        );
+
+        assert_eq!(
+            render(&article, Some("https://example.com/posts/tides")),
+            "# On Tides\n\n\
+             The moon pulls the sea.\n\
+             See the note.\n\
+             => https://example.com/moon pulls\n\n\
+             ### Deep water\n\n\
+             * Spring tides\n\
+             * Neap\n\
+             => https://example.org/spring tides\n\n\
+             => https://example.com/posts/chart.png Tide chart\n\n\
+             Heights by hour\n\n\
+             > Time and tide.\n\n\
+             ```sh\n \
+             ```\n\
+             tide --now\n\
+             ```\n\n\
+             ```\n\
+             Port | High\n\
+             Dover | 06:12\n\
+             ```"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use reqwest::Url;
+use scraper::ElementRef;
+use scraper::node::Node;
+
+/// Elements that start a new block instead of flowing into the surrounding
+/// paragraph.
+pub const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "caption",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "ul",
+];
+
+/// Walk over article HTML for the text renderers.
+///
+/// A writer gathers text and inline elements into `Inline` until the next
+/// block element, then hands the gathered run to `paragraph` before the block
+/// goes to `block`. Scripts, styles, and templates are skipped.
+pub trait Walker {
+    /// Inline content gathered for one paragraph.
+    type Inline: Default;
+
+    fn text(&self, text: &str, inline: &mut Self::Inline);
+
+    fn inline(&self, element: ElementRef<'_>, inline: &mut Self::Inline);
+
+    fn block(&mut self, element: ElementRef<'_>);
+
+    fn paragraph(&mut self, inline: Self::Inline);
+
+    fn is_block(&self, name: &str) -> bool {
+        BLOCK_ELEMENTS.contains(&name)
+    }
+
+    /// Write the children of `element` as paragraphs and blocks.
+    fn children(&mut self, element: ElementRef<'_>) {
+        let mut inline = Self::Inline::default();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text, &mut inline),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    if self.is_block(child.value().name()) {
+                        self.paragraph(std::mem::take(&mut inline));
+                        self.block(child);
+                    } else {
+                        self.element(child, &mut inline);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.paragraph(inline);
+    }
+
+    /// Add the children of `element` to `inline`, blocks included.
+    fn inlines(&self, element: ElementRef<'_>, inline: &mut Self::Inline) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text, inline),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child, inline);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Children of `element` gathered as one run of inline content.
+    fn collect(&self, element: ElementRef<'_>) -> Self::Inline {
+        let mut inline = Self::Inline::default();
+        self.inlines(element, &mut inline);
+        inline
+    }
+
+    /// Add an element to `inline` unless it is a script, style, or template.
+    fn element(&self, element: ElementRef<'_>, inline: &mut Self::Inline) {
+        if !matches!(element.value().name(), "script" | "style" | "template") {
+            self.inline(element, inline);
+        }
+    }
+}
+
+/// Absolute URL for a link target. Same-page fragments are dropped since
+/// heading anchors are not carried over.
+pub fn resolve(base: Option<&Url>, url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with('#') {
+        return None;
+    }
+    match base {
+        Some(base) => base.join(url).ok().map(String::from),
+        None => Some(url.to_string()),
+    }
+}
+
+pub fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,440 @@
+use lectito::Article;
+use reqwest::Url;
+use scraper::{ElementRef, Html};
+
+use crate::html::{self, BLOCK_ELEMENTS, Walker, collapse};
+
+/// Article as a standalone LaTeX document using the `article` class.
+///
+/// Title, byline, date, and keywords go in the preamble and PDF metadata.
+/// Code blocks are `verbatim`, and images with a local path become
+/// `graphicx` figures. Remote images cannot be included by LaTeX, so they are
+/// listed by URL; download them first with `--download-images --assets-dir`.
+pub fn render(article: &Article, source: Option<&str>) -> String {
+    let base = source.and_then(|source| Url::parse(source).ok());
+    let title = article.title.as_deref().map(collapse).filter(|title| !title.is_empty());
+    let mut writer = Writer { base: base.as_ref(), body: String::new(), title: title.clone() };
+    let document = Html::parse_fragment(&article.content);
+    writer.children(document.root_element());
+
+    let mut latex = String::from(PREAMBLE);
+    let metadata = [
+        ("pdftitle", title.as_deref()),
+        ("pdfauthor", article.byline.as_deref()),
+        ("pdfsubject", article.excerpt.as_deref()),
+    ];
+    let keywords = article.keywords.join(", ");
+    let metadata: Vec<String> = metadata
+        .into_iter()
+        .chain([("pdfkeywords", Some(keywords.as_str()))])
+        .filter_map(|(key, value)| {
+            let value = collapse(value?);
+            (!value.is_empty()).then(|| format!("  {key}={{{}}}", escape(&value)))
+        })
+        .collect();
+    if !metadata.is_empty() {
+        latex.push_str(&format!("\\hypersetup{{\n{}\n}}\n", metadata.join(",\n")));
+    }
+    if let Some(title) = &title {
+        latex.push_str(&format!("\\title{{{}}}\n", escape(title)));
+        latex.push_str(&format!(
+            "\\author{{{}}}\n",
+            article
+                .byline
+                .as_deref()
+                .map(collapse)
+                .map(|byline| escape(&byline))
+                .unwrap_or_default()
+        ));
+        latex.push_str(&format!(
+            "\\date{{{}}}\n",
+            article.published_time.as_deref().map(escape).unwrap_or_default()
+        ));
+    }
+    latex.push_str("\n\\begin{document}\n");
+    if title.is_some() {
+        latex.push_str("\\maketitle\n");
+    }
+    if let Some(source) = source.filter(|source| source.contains("://")) {
+        latex.push_str(&format!("\\noindent Source: \\url{{{}}}\n", escape_url(source)));
+    }
+    latex.push('\n');
+    latex.push_str(writer.body.trim());
+    latex.push_str("\n\n\\end{document}\n");
+    latex
+}
+
+const PREAMBLE: &str = r"\documentclass{article}
+\usepackage{iftex}
+\ifPDFTeX
+  \usepackage[utf8]{inputenc}
+  \usepackage[T1]{fontenc}
+\else
+  \usepackage{fontspec}
+\fi
+\usepackage{graphicx}
+\usepackage[hidelinks]{hyperref}
+\setkeys{Gin}{width=\linewidth,height=0.8\textheight,keepaspectratio}
+";
+
+struct Writer<'a> {
+    base: Option<&'a Url>,
+    body: String,
+    /// The document title, until the article's own copy of it is skipped.
+    title: Option<String>,
+}
+
+impl Walker for Writer<'_> {
+    type Inline = String;
+
+    fn text(&self, text: &str, inline: &mut String) {
+        inline.push_str(&escape(text));
+    }
+
+    fn is_block(&self, name: &str) -> bool {
+        BLOCK_ELEMENTS.contains(&name) || name == "img"
+    }
+
+    fn block(&mut self, element: ElementRef<'_>) {
+        match element.value().name() {
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let text = collapse(&element.text().collect::<String>());
+                if text.is_empty() {
+                    return;
+                }
+                if self.title.take().is_some_and(|title| title == text) && self.body.is_empty() {
+                    return;
+                }
+                let command = match name {
+                    "h1" | "h2" => "section",
+                    "h3" => "subsection",
+                    "h4" => "subsubsection",
+                    _ => "paragraph",
+                };
+                let text = collapse(&self.collect(element));
+                self.push(&format!("\\{command}*{{{text}}}"));
+            }
+            "p" | "address" | "caption" | "summary" => {
+                let text = self.collect(element);
+                self.paragraph(text);
+            }
+            kind @ ("ul" | "ol") => {
+                let environment = if kind == "ul" { "itemize" } else { "enumerate" };
+                let items: Vec<String> = element
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|item| item.value().name() == "li")
+                    .map(|item| {
+                        let mut writer = self.nested();
+                        writer.children(item);
+                        format!("\\item {}", writer.body.trim())
+                    })
+                    .collect();
+                if !items.is_empty() {
+                    self.push(&format!(
+                        "\\begin{{{environment}}}\n{}\n\\end{{{environment}}}",
+                        items.join("\n")
+                    ));
+                }
+            }
+            "dl" => {
+                let mut items = Vec::new();
+                for child in element.children().filter_map(ElementRef::wrap) {
+                    match child.value().name() {
+                        "dt" => items.push(format!("\\item[{}]", collapse(&self.collect(child)))),
+                        "dd" => {
+                            let mut writer = self.nested();
+                            writer.children(child);
+                            items.push(writer.body.trim().to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                if !items.is_empty() {
+                    self.push(&format!(
+                        "\\begin{{description}}\n{}\n\\end{{description}}",
+                        items.join("\n")
+                    ));
+                }
+            }
+            "blockquote" => {
+                let mut writer = self.nested();
+                writer.children(element);
+                if !writer.body.trim().is_empty() {
+                    self.push(&format!("\\begin{{quote}}\n{}\n\\end{{quote}}", writer.body.trim()));
+                }
+            }
+            "pre" => {
+                let text = element
+                    .text()
+                    .collect::<String>()
+                    .replace("\\end{verbatim}", "\\end {verbatim}");
+                let text = text.trim_matches('\n').trim_end();
+                if !text.is_empty() {
+                    self.push(&format!("\\begin{{verbatim}}\n{text}\n\\end{{verbatim}}"));
+                }
+            }
+            "figure" => self.figure(element),
+            "img" => self.image(element, None),
+            "table" => self.table(element),
+            "hr" => self.push("\\noindent\\rule{\\linewidth}{0.4pt}"),
+            _ => self.children(element),
+        }
+    }
+
+    fn inline(&self, element: ElementRef<'_>, text: &mut String) {
+        let wrap = |command: &str| {
+            let inner = self.collect(element);
+            match inner.trim().is_empty() {
+                true => inner,
+                false => format!("\\{command}{{{inner}}}"),
+            }
+        };
+        let markup = match element.value().name() {
+            "br" => "\\newline\n".to_string(),
+            "img" => {
+                let alt = collapse(element.value().attr("alt").unwrap_or_default());
+                if alt.is_empty() { String::new() } else { format!("[{}]", escape(&alt)) }
+            }
+            "em" | "i" | "cite" | "dfn" | "var" => wrap("emph"),
+            "strong" | "b" => wrap("textbf"),
+            "code" | "kbd" | "samp" | "tt" => wrap("texttt"),
+            "sup" => wrap("textsuperscript"),
+            "sub" => wrap("textsubscript"),
+            "s" | "del" | "strike" => self.collect(element),
+            "a" => {
+                let label = self.collect(element);
+                match element.value().attr("href").and_then(|href| self.resolve(href)) {
+                    Some(url) if !label.trim().is_empty() => format!("\\href{{{}}}{{{label}}}", escape_url(&url)),
+                    Some(url) => format!("\\url{{{}}}", escape_url(&url)),
+                    None => label,
+                }
+            }
+            _ => self.collect(element),
+        };
+        text.push_str(&markup);
+    }
+
+    fn paragraph(&mut self, text: String) {
+        let text = text
+            .lines()
+            .map(collapse)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = text
+            .trim_end_matches("\\newline")
+            .trim_start_matches("\\newline")
+            .trim();
+        if !text.is_empty() {
+            self.push(text);
+        }
+    }
+}
+
+impl Writer<'_> {
+    fn figure(&mut self, figure: ElementRef<'_>) {
+        let caption = figure
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "figcaption")
+            .map(|caption| collapse(&self.collect(caption)))
+            .filter(|caption| !caption.is_empty());
+        let images: Vec<_> = figure
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|element| element.value().name() == "img")
+            .collect();
+        if images.is_empty() {
+            for child in figure.children().filter_map(ElementRef::wrap) {
+                if child.value().name() != "figcaption" {
+                    self.block(child);
+                }
+            }
+            if let Some(caption) = caption {
+                self.paragraph(caption);
+            }
+            return;
+        }
+        for image in images {
+            self.image(image, caption.as_deref());
+        }
+    }
+
+    fn image(&mut self, image: ElementRef<'_>, caption: Option<&str>) {
+        let alt = collapse(image.value().attr("alt").unwrap_or_default());
+        let caption = caption.map(str::to_string).unwrap_or_else(|| escape(&alt));
+        let Some(src) = image.value().attr("src").and_then(|src| self.resolve(src)) else {
+            if !caption.is_empty() {
+                self.paragraph(caption);
+            }
+            return;
+        };
+        if src.contains("://") || src.starts_with("data:") {
+            let link = match src.starts_with("data:") {
+                true => String::new(),
+                false => format!("\\url{{{}}}", escape_url(&src)),
+            };
+            let text = [caption.as_str(), link.as_str()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(": ");
+            self.paragraph(format!("[Image: {text}]"));
+            return;
+        }
+        let mut figure = format!(
+            "\\begin{{figure}}[htbp]\n\\centering\n\\includegraphics{{{}}}\n",
+            graphics_path(&src)
+        );
+        if !caption.is_empty() {
+            figure.push_str(&format!("\\caption*{{{caption}}}\n"));
+        }
+        figure.push_str("\\end{figure}");
+        self.push(&figure);
+    }
+
+    fn table(&mut self, table: ElementRef<'_>) {
+        let rows: Vec<Vec<String>> = table
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|element| element.value().name() == "tr")
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .map(|cell| collapse(&self.collect(cell)))
+                    .collect()
+            })
+            .filter(|cells: &Vec<String>| !cells.is_empty())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+        if columns == 0 {
+            return;
+        }
+        let mut tabular = format!("\\begin{{tabular}}{{{}}}\n\\hline\n", "l".repeat(columns));
+        for row in rows {
+            tabular.push_str(&format!("{} \\\\\n", row.join(" & ")));
+        }
+        tabular.push_str("\\hline\n\\end{tabular}");
+        self.push(&format!("\\begin{{center}}\n{tabular}\n\\end{{center}}"));
+    }
+
+    fn push(&mut self, block: &str) {
+        if !self.body.is_empty() {
+            self.body.push_str("\n\n");
+        }
+        self.body.push_str(block);
+    }
+
+    fn nested(&self) -> Self {
+        Writer { base: self.base, body: String::new(), title: None }
+    }
+
+    /// Absolute URL for a link target. Data URIs and local image paths are
+    /// kept as they are.
+    fn resolve(&self, url: &str) -> Option<String> {
+        let base = self
+            .base
+            .filter(|_| !url.trim().starts_with("data:") && !is_local_path(url.trim()));
+        html::resolve(base, url)
+    }
+}
+
+/// Relative paths written by `--download-images --assets-dir` stay relative
+/// to the output file instead of being joined onto the page URL.
+fn is_local_path(url: &str) -> bool {
+    !url.contains("://") && !url.starts_with('/')
+}
+
+/// `\includegraphics` reads the path verbatim apart from braces, and needs
+/// them around names with extra dots or spaces.
+fn graphics_path(path: &str) -> String {
+    let path = path.replace(['{', '}'], "");
+    match path.contains(' ') || path.matches('.').count() > 1 {
+        true => match path.rsplit_once('.') {
+            Some((stem, extension)) => format!("{{{stem}}}.{extension}"),
+            None => path,
+        },
+        false => path,
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\u{a0}' => escaped.push('~'),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `\url` and `\href` take their argument nearly verbatim, but `%`, `#`, and
+/// braces still need escaping.
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "%5C")
+        .replace('%', "\\%")
+        .replace('#', "\\#")
+        .replace('{', "%7B")
+        .replace('}', "%7D")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article;
+
+    #[test]
+    fn renders_article_document() {
+        let article = Article {
+            title: Some("Costs & Tides".to_string()),
+            byline: Some("Jane Roe".to_string()),
+            published_time: Some("2026-03-01".to_string()),
+            keywords: vec!["ocean".to_string()],
+            ..article(
+                r#"<h1>Costs &amp; Tides</h1>
+                <p>Prices rose 5% for <em>all</em> <a href="/ports#east">ports</a>.</p>
+                <h3>Code</h3>
+                <pre><code>let x = a_b & c; // \end{verbatim}</code></pre>
+                <figure><img src="images/chart.png" alt="Chart"><figcaption>Tide heights</figcaption></figure>
+                <ul><li>One</li><li><code>two_three</code></li></ul>"#,
+            )
+        };
+        let latex = render(&article, Some("https://example.com/post"));
+
+        assert!(latex.starts_with("\\documentclass{article}\n"));
+        assert!(latex.contains("  pdftitle={Costs \\& Tides},\n  pdfauthor={Jane Roe},\n  pdfkeywords={ocean}\n"));
+        assert!(latex.contains("\\title{Costs \\& Tides}\n\\author{Jane Roe}\n\\date{2026-03-01}\n"));
+        assert!(latex.contains("\\maketitle\n\\noindent Source: \\url{https://example.com/post}\n"));
+        assert!(!latex.contains("\\section*{Costs"));
+        assert!(latex.contains(
+            "Prices rose 5\\% for \\emph{all} \\href{https://example.com/ports\\#east}{ports}.\n\n\\subsection*{Code}"
+        ));
+        assert!(latex.contains("\\begin{verbatim}\nlet x = a_b & c; // \\end {verbatim}\n\\end{verbatim}"));
+        assert!(latex.contains(
+            "\\begin{figure}[htbp]\n\\centering\n\\includegraphics{images/chart.png}\n\\caption*{Tide heights}\n\\end{figure}"
+        ));
+        assert!(latex.contains("\\begin{itemize}\n\\item One\n\\item \\texttt{two\\_three}\n\\end{itemize}"));
+        assert!(latex.ends_with("\\end{document}\n"));
+    }
+
+    #[test]
+    fn lists_remote_images_by_url() {
+        let latex = render(
+            &article(r#"<p>Intro</p><img src="https://cdn.example.com/a.png" alt="Map">"#),
+            None,
+        );
+
+        assert!(latex.contains("[Image: Map: \\url{https://cdn.example.com/a.png}]"));
+        assert!(!latex.contains("\\includegraphics"));
+    }
+}
@@ -7,14 +7,15 @@ use std::process::ExitCode;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use lectito::ReadabilityOptions;
+use clap::ArgMatches;
+use lectito::LectitoOptions;
 use reqwest::Url;
 use scraper::{Html, Selector};
 use serde::Serialize;
 use sitemap::reader::{SiteMapEntity, SiteMapReader};
 
 use crate::cli::{LlmsArgs, LlmsCommands, LlmsExpandArgs, LlmsFetchArgs, LlmsGenerateArgs, LlmsParseArgs};
-use crate::{echo, fetch};
+use crate::{config, echo, fetch, settings};
 
 #[derive(Debug, Serialize)]
 pub struct LlmsDocument {
@@ -128,12 +129,18 @@ impl FetchThrottle {
 struct RobotsCache {
     user_agent: String,
     ignore: bool,
+    fetch_options: fetch::ReadOptions,
     origins: HashMap<String, Option<RobotsRules>>,
 }
 
 impl RobotsCache {
-    fn new(user_agent: &str, ignore: bool) -> Self {
-        Self { user_agent: user_agent.to_string(), ignore, origins: HashMap::new() }
+    fn new(user_agent: &str, ignore: bool, fetch_options: &fetch::ReadOptions) -> Self {
+        Self {
+            user_agent: user_agent.to_string(),
+            ignore,
+            fetch_options: fetch_options.clone(),
+            origins: HashMap::new(),
+        }
     }
 
     fn allowed(&mut self, target: &str) -> bool {
@@ -145,7 +152,7 @@ impl RobotsCache {
         };
 
         if !self.origins.contains_key(&origin) {
-            let rules = read_robots_txt(&origin)
+            let rules = read_robots_txt(&origin, &self.fetch_options)
                 .ok()
                 .and_then(|text| RobotsRules::parse(&text, &self.user_agent));
             self.origins.insert(origin.clone(), rules);
@@ -236,12 +243,30 @@ impl RobotsGroup {
     }
 }
 
-pub fn run(args: LlmsArgs) -> Result<ExitCode> {
+/// Run an `llms` subcommand with the user settings file: its fetch options
+/// and timeout, and its extraction options for each page that is extracted.
+pub fn run(args: LlmsArgs, matches: &ArgMatches) -> Result<ExitCode> {
+    let settings = settings::load(None)?;
+    let settings = settings.as_ref();
+    let fetch_options = &settings::read_options(settings);
+    let explicit_timeout = matches
+        .subcommand()
+        .is_some_and(|(_, matches)| config::explicit(matches, "timeout"));
     match args.command {
-        LlmsCommands::Fetch(args) => run_fetch(args),
-        LlmsCommands::Parse(args) => run_parse(args),
-        LlmsCommands::Expand(args) => run_expand(args),
-        LlmsCommands::Generate(args) => run_generate(args),
+        LlmsCommands::Fetch(args) => run_fetch(args, fetch_options),
+        LlmsCommands::Parse(args) => run_parse(args, fetch_options),
+        LlmsCommands::Expand(mut args) => {
+            if let Some(settings) = settings {
+                settings::apply_timeout(&mut args.timeout, settings, explicit_timeout);
+            }
+            run_expand(args, fetch_options, settings)
+        }
+        LlmsCommands::Generate(mut args) => {
+            if let Some(settings) = settings {
+                settings::apply_timeout(&mut args.timeout, settings, explicit_timeout);
+            }
+            run_generate(args, fetch_options, settings)
+        }
     }
 }
 
@@ -304,14 +329,14 @@ pub fn parse_llms_txt(text: &str) -> Result<LlmsDocument> {
     Ok(LlmsDocument { title, summary: non_empty_join(summary), details: non_empty_join(details), sections })
 }
 
-fn run_fetch(args: LlmsFetchArgs) -> Result<ExitCode> {
-    let source = read_llms_source(&args.input)?;
+fn run_fetch(args: LlmsFetchArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let source = read_llms_source(&args.input, fetch_options)?;
     write_output(args.output.as_ref(), &source.text)?;
     Ok(ExitCode::SUCCESS)
 }
 
-fn run_parse(args: LlmsParseArgs) -> Result<ExitCode> {
-    let source = read_llms_source(&args.input)?;
+fn run_parse(args: LlmsParseArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let source = read_llms_source(&args.input, fetch_options)?;
     let document = parse_llms_txt(&source.text)?;
     let output = if args.pretty {
         serde_json::to_string_pretty(&document).context("failed to serialize llms.txt JSON")?
@@ -322,8 +347,10 @@ fn run_parse(args: LlmsParseArgs) -> Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
-fn run_expand(args: LlmsExpandArgs) -> Result<ExitCode> {
-    let source = read_llms_source(&args.input)?;
+fn run_expand(
+    args: LlmsExpandArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<ExitCode> {
+    let source = read_llms_source(&args.input, fetch_options)?;
     let document = parse_llms_txt(&source.text)?;
     let links = selected_links(&document, args.include_optional, args.max_links);
     let mut output = String::new();
@@ -343,8 +370,8 @@ fn run_expand(args: LlmsExpandArgs) -> Result<ExitCode> {
 
     for link in links {
         let resolved = resolve_link(&link.url, source.base.as_deref())?;
-        let content =
-            read_resource_md(&resolved, args.timeout).with_context(|| format!("failed to expand {}", link.url))?;
+        let content = read_resource_md(&resolved, args.timeout, fetch_options, settings)
+            .with_context(|| format!("failed to expand {}", link.url))?;
 
         output.push_str("---\n\n");
         output.push_str("# Source: ");
@@ -366,7 +393,9 @@ fn run_expand(args: LlmsExpandArgs) -> Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
-fn run_generate(args: LlmsGenerateArgs) -> Result<ExitCode> {
+fn run_generate(
+    args: LlmsGenerateArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<ExitCode> {
     if args.max_pages == 0 {
         anyhow::bail!("--max-pages must be greater than zero");
     }
@@ -378,7 +407,7 @@ fn run_generate(args: LlmsGenerateArgs) -> Result<ExitCode> {
         anyhow::bail!("--robots-agent must not be empty");
     }
 
-    let entries = generate_entries(&args)?;
+    let entries = generate_entries(&args, fetch_options, settings)?;
     let title = args
         .title
         .clone()
@@ -398,19 +427,23 @@ fn run_generate(args: LlmsGenerateArgs) -> Result<ExitCode> {
     Ok(if entries.is_empty() { ExitCode::from(1) } else { ExitCode::SUCCESS })
 }
 
-fn generate_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
+fn generate_entries(
+    args: &LlmsGenerateArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<Vec<CrawledEntry>> {
     match (args.input.as_deref(), args.sitemap.as_deref(), args.discover_sitemap) {
         (Some(_), Some(_), _) => anyhow::bail!("pass either a crawl seed or --sitemap, not both"),
         (_, Some(_), true) => anyhow::bail!("cannot combine --sitemap with --discover"),
-        (Some(input), None, true) => discovered_sitemap_entries(input, args),
-        (Some(_), None, false) => crawl_entries(args),
-        (None, Some(sitemap), false) => sitemap_entries(sitemap, args),
+        (Some(input), None, true) => discovered_sitemap_entries(input, args, fetch_options, settings),
+        (Some(_), None, false) => crawl_entries(args, fetch_options, settings),
+        (None, Some(sitemap), false) => sitemap_entries(sitemap, args, fetch_options, settings),
         (None, None, true) => anyhow::bail!("pass a seed URL when using --discover"),
         (None, None, false) => anyhow::bail!("pass a seed URL/path or --sitemap"),
     }
 }
 
-fn crawl_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
+fn crawl_entries(
+    args: &LlmsGenerateArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<Vec<CrawledEntry>> {
     let input = args.input.as_deref().context("missing crawl seed")?;
     let seed = if input.starts_with("http://") || input.starts_with("https://") {
         let mut url = Url::parse(input).with_context(|| format!("invalid URL: {input}"))?;
@@ -424,7 +457,7 @@ fn crawl_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
     let mut entries = Vec::new();
     let mut throttle = FetchThrottle::new(args.delay_ms);
     let filters = GenerateFilters::new(args)?;
-    let mut robots = RobotsCache::new(&args.robots_user_agent, args.ignore_robots);
+    let mut robots = RobotsCache::new(&args.robots_user_agent, args.ignore_robots, fetch_options);
 
     while let Some(item) = queue.pop_front() {
         if seen.len() >= args.max_pages {
@@ -440,7 +473,7 @@ fn crawl_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
         }
 
         throttle.wait();
-        let page = match read_crawl_page(&item.target) {
+        let page = match read_crawl_page(&item.target, fetch_options) {
             Ok(page) => page,
             Err(error) => {
                 eprintln!("lectito: skipping {}: {error:#}", item.target);
@@ -448,7 +481,7 @@ fn crawl_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
             }
         };
 
-        if let Some(entry) = crawled_entry(&page, args.timeout, entries.len(), item.sitemap_lastmod.as_deref())? {
+        if let Some(entry) = crawled_entry(&page, args, settings, entries.len(), item.sitemap_lastmod.as_deref())? {
             entries.push(entry);
         }
 
@@ -466,22 +499,34 @@ fn crawl_entries(args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
     Ok(ranked_entries(entries))
 }
 
-fn sitemap_entries(input: &str, args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
-    let urls = sitemap_urls_from_inputs(vec![input.to_string()], args.max_sitemaps, args.max_pages)?;
-    entries_from_urls(urls, args)
+fn sitemap_entries(
+    input: &str, args: &LlmsGenerateArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<Vec<CrawledEntry>> {
+    let urls = sitemap_urls_from_inputs(
+        vec![input.to_string()],
+        args.max_sitemaps,
+        args.max_pages,
+        fetch_options,
+    )?;
+    entries_from_urls(urls, args, fetch_options, settings)
 }
 
-fn discovered_sitemap_entries(input: &str, args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
-    let sitemaps = discover_sitemaps(input)?;
-    let urls = sitemap_urls_from_inputs(sitemaps, args.max_sitemaps, args.max_pages)?;
-    entries_from_urls(urls, args)
+fn discovered_sitemap_entries(
+    input: &str, args: &LlmsGenerateArgs, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<Vec<CrawledEntry>> {
+    let sitemaps = discover_sitemaps(input, fetch_options)?;
+    let urls = sitemap_urls_from_inputs(sitemaps, args.max_sitemaps, args.max_pages, fetch_options)?;
+    entries_from_urls(urls, args, fetch_options, settings)
 }
 
-fn entries_from_urls(urls: Vec<SitemapUrl>, args: &LlmsGenerateArgs) -> Result<Vec<CrawledEntry>> {
+fn entries_from_urls(
+    urls: Vec<SitemapUrl>, args: &LlmsGenerateArgs, fetch_options: &fetch::ReadOptions,
+    settings: Option<&LectitoOptions>,
+) -> Result<Vec<CrawledEntry>> {
     let mut entries = Vec::new();
     let mut throttle = FetchThrottle::new(args.delay_ms);
     let filters = GenerateFilters::new(args)?;
-    let mut robots = RobotsCache::new(&args.robots_user_agent, args.ignore_robots);
+    let mut robots = RobotsCache::new(&args.robots_user_agent, args.ignore_robots, fetch_options);
 
     for candidate in urls.into_iter().take(args.max_pages) {
         if !passes_filters(&candidate.url, &filters) {
@@ -500,7 +545,7 @@ fn entries_from_urls(urls: Vec<SitemapUrl>, args: &LlmsGenerateArgs) -> Result<V
         }
 
         throttle.wait();
-        let page = match read_crawl_page(&candidate.url) {
+        let page = match read_crawl_page(&candidate.url, fetch_options) {
             Ok(page) => page,
             Err(error) => {
                 eprintln!("lectito: skipping {}: {error:#}", candidate.url);
@@ -508,7 +553,7 @@ fn entries_from_urls(urls: Vec<SitemapUrl>, args: &LlmsGenerateArgs) -> Result<V
             }
         };
 
-        if let Some(entry) = crawled_entry(&page, args.timeout, entries.len(), candidate.lastmod.as_deref())? {
+        if let Some(entry) = crawled_entry(&page, args, settings, entries.len(), candidate.lastmod.as_deref())? {
             entries.push(entry);
         }
     }
@@ -642,9 +687,9 @@ fn robots_origin(target: &str) -> Option<String> {
     ))
 }
 
-fn read_robots_txt(origin: &str) -> Result<String> {
+fn read_robots_txt(origin: &str, fetch_options: &fetch::ReadOptions) -> Result<String> {
     let url = format!("{}/robots.txt", origin.trim_end_matches('/'));
-    let document = fetch::InputDocument::read_src(Some(&url), false, None)?;
+    let document = fetch::InputDocument::read_src(Some(&url), false, None, fetch_options)?;
     Ok(document.html().to_string())
 }
 
@@ -691,7 +736,9 @@ fn wildcard_match(pattern: &str, value: &str) -> bool {
     p == pattern.len()
 }
 
-fn sitemap_urls_from_inputs(inputs: Vec<String>, max_sitemaps: usize, max_urls: usize) -> Result<Vec<SitemapUrl>> {
+fn sitemap_urls_from_inputs(
+    inputs: Vec<String>, max_sitemaps: usize, max_urls: usize, fetch_options: &fetch::ReadOptions,
+) -> Result<Vec<SitemapUrl>> {
     let mut sitemap_queue = inputs
         .into_iter()
         .map(|input| {
@@ -711,7 +758,8 @@ fn sitemap_urls_from_inputs(inputs: Vec<String>, max_sitemaps: usize, max_urls:
             continue;
         }
 
-        let source = read_text_source(&sitemap).with_context(|| format!("failed to read sitemap {sitemap}"))?;
+        let source =
+            read_text_source(&sitemap, fetch_options).with_context(|| format!("failed to read sitemap {sitemap}"))?;
         for entity in SiteMapReader::new(source.text.as_bytes()) {
             match entity {
                 SiteMapEntity::Url(entry) => {
@@ -749,9 +797,9 @@ fn sitemap_urls_from_inputs(inputs: Vec<String>, max_sitemaps: usize, max_urls:
     Ok(urls)
 }
 
-fn discover_sitemaps(input: &str) -> Result<Vec<String>> {
+fn discover_sitemaps(input: &str, fetch_options: &fetch::ReadOptions) -> Result<Vec<String>> {
     let origin = robots_origin(input).ok_or_else(|| anyhow::anyhow!("--discover requires an HTTP URL seed"))?;
-    let discovered = read_robots_txt(&origin)
+    let discovered = read_robots_txt(&origin, fetch_options)
         .map(|text| sitemap_locations_from_robots(&text, &origin))
         .unwrap_or_default();
     if !discovered.is_empty() {
@@ -788,9 +836,9 @@ fn sitemap_locations_from_robots(text: &str, origin: &str) -> Vec<String> {
     locations
 }
 
-fn read_text_source(input: &str) -> Result<LlmsSource> {
+fn read_text_source(input: &str, fetch_options: &fetch::ReadOptions) -> Result<LlmsSource> {
     if input.starts_with("http://") || input.starts_with("https://") {
-        let document = fetch::InputDocument::read_src(Some(input), false, None)?;
+        let document = fetch::InputDocument::read_src(Some(input), false, None, fetch_options)?;
         return Ok(LlmsSource { text: document.html().to_string(), base: document.base_url().map(str::to_string) });
     }
 
@@ -810,9 +858,9 @@ fn sitemap_origin(input: &str) -> Option<String> {
     ))
 }
 
-fn read_crawl_page(input: &str) -> Result<CrawlPage> {
+fn read_crawl_page(input: &str, fetch_options: &fetch::ReadOptions) -> Result<CrawlPage> {
     if input.starts_with("http://") || input.starts_with("https://") {
-        let document = fetch::InputDocument::read_src(Some(input), false, None)?;
+        let document = fetch::InputDocument::read_src(Some(input), false, None, fetch_options)?;
         let base_url = document.base_url().map(str::to_string);
         let id = base_url.clone().unwrap_or_else(|| input.to_string());
         let last_modified = document.last_modified().map(str::to_string);
@@ -834,16 +882,21 @@ fn read_crawl_page(input: &str) -> Result<CrawlPage> {
 }
 
 fn crawled_entry(
-    page: &CrawlPage, timeout: u64, source_index: usize, sitemap_lastmod: Option<&str>,
+    page: &CrawlPage, args: &LlmsGenerateArgs, settings: Option<&LectitoOptions>, source_index: usize,
+    sitemap_lastmod: Option<&str>,
 ) -> Result<Option<CrawledEntry>> {
-    let options = ReadabilityOptions::default();
-    let Some(report) = super::extract_with_timeout(&page.html, page.base_url.as_deref(), options, timeout)? else {
+    let options = settings::extract_base(settings, None, page.base_url.as_deref()).unwrap_or_default();
+    let Some(report) = super::extract_with_timeout(&page.html, page.base_url.as_deref(), options, args.timeout)? else {
         eprintln!("lectito: extraction timed out for {}", page.id);
         return Ok(None);
     };
     let Some(article) = report.article else {
         return Ok(None);
     };
+    if args.skip_sponsored && article.is_sponsored == Some(true) {
+        eprintln!("lectito: skipping {}: sponsored content", page.id);
+        return Ok(None);
+    }
 
     let title = article
         .title
@@ -1152,7 +1205,7 @@ fn selected_links(document: &LlmsDocument, include_optional: bool, max_links: us
         .collect()
 }
 
-fn read_llms_source(input: &str) -> Result<LlmsSource> {
+fn read_llms_source(input: &str, fetch_options: &fetch::ReadOptions) -> Result<LlmsSource> {
     if input == "-" {
         let mut text = String::new();
         io::stdin().read_to_string(&mut text).context("failed to read stdin")?;
@@ -1161,7 +1214,7 @@ fn read_llms_source(input: &str) -> Result<LlmsSource> {
 
     if input.starts_with("http://") || input.starts_with("https://") {
         let url = llms_url(input)?;
-        let document = fetch::InputDocument::read_src(Some(url.as_str()), false, None)?;
+        let document = fetch::InputDocument::read_src(Some(url.as_str()), false, None, fetch_options)?;
         return Ok(LlmsSource { text: document.html().to_string(), base: Some(url.to_string()) });
     }
 
@@ -1180,24 +1233,28 @@ fn llms_url(input: &str) -> Result<Url> {
     Ok(url)
 }
 
-fn read_resource_md(input: &str, timeout: u64) -> Result<String> {
+fn read_resource_md(
+    input: &str, timeout: u64, fetch_options: &fetch::ReadOptions, settings: Option<&LectitoOptions>,
+) -> Result<String> {
     if input.starts_with("http://") || input.starts_with("https://") {
-        let document = fetch::InputDocument::read_src(Some(input), false, None)?;
+        let document = fetch::InputDocument::read_src(Some(input), false, None, fetch_options)?;
         if looks_like_md(input, document.content_type(), document.html()) {
             return Ok(document.html().to_string());
         }
-        return extract_resource_md(document.html(), document.base_url(), timeout);
+        return extract_resource_md(document.html(), document.base_url(), timeout, settings);
     }
 
     let text = fs::read_to_string(input).with_context(|| format!("failed to read {input}"))?;
     if looks_like_md(input, None, &text) {
         return Ok(text);
     }
-    extract_resource_md(&text, None, timeout)
+    extract_resource_md(&text, None, timeout, settings)
 }
 
-fn extract_resource_md(html: &str, base_url: Option<&str>, timeout: u64) -> Result<String> {
-    let options = ReadabilityOptions::default();
+fn extract_resource_md(
+    html: &str, base_url: Option<&str>, timeout: u64, settings: Option<&LectitoOptions>,
+) -> Result<String> {
+    let options = settings::extract_base(settings, None, base_url).unwrap_or_default();
     let Some(report) = super::extract_with_timeout(html, base_url, options, timeout)? else {
         anyhow::bail!("extraction timed out after {timeout}s");
     };
@@ -1502,8 +1559,13 @@ Use Markdown outputs for agent context.
         )
         .expect("write sitemap");
 
-        let urls = sitemap_urls_from_inputs(vec![sitemap.to_str().expect("utf-8 path").to_string()], 5, 10)
-            .expect("parse sitemap");
+        let urls = sitemap_urls_from_inputs(
+            vec![sitemap.to_str().expect("utf-8 path").to_string()],
+            5,
+            10,
+            &fetch::ReadOptions::default(),
+        )
+        .expect("parse sitemap");
 
         assert_eq!(urls[0].url, "https://example.com/docs/a.html");
         assert_eq!(urls[0].lastmod.as_deref(), Some("2024-05-01T00:00:00+00:00"));
@@ -1572,7 +1634,8 @@ Sitemap: https://example.com/sitemap.xml
             stream.write_all(response.as_bytes()).expect("write response");
         });
 
-        let sitemaps = discover_sitemaps(&format!("http://{address}/docs/")).expect("discover sitemaps");
+        let sitemaps = discover_sitemaps(&format!("http://{address}/docs/"), &fetch::ReadOptions::default())
+            .expect("discover sitemaps");
 
         server.join().expect("join test server");
         assert_eq!(sitemaps, vec![format!("http://{address}/sitemap.xml")]);
@@ -1603,7 +1666,7 @@ Disallow: /tmp$
 
     #[test]
     fn robots_ignore_allows_remote_targets() {
-        let mut robots = RobotsCache::new("Lectito", true);
+        let mut robots = RobotsCache::new("Lectito", true, &fetch::ReadOptions::default());
 
         assert!(robots.allowed("https://example.com/private/page"));
     }
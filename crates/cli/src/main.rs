@@ -1,14 +1,14 @@
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use cli::{Cli, Commands, ExtractArgs, InspectArgs, ReadableArgs};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, ValueEnum};
+use cli::{Cli, Commands, ExportProfile, ExtractArgs, InfoArgs, InspectArgs, OutputFormat, ReadArgs, ReadableArgs};
 
 use lectito::ExtractionReport;
 use lectito::{ReadabilityOptions, ReadableOptions};
@@ -16,24 +16,45 @@ use lectito::{extract_with_diagnostics, is_probably_readable};
 
 use crate::echo::InspectOptions;
 
+mod assets;
 mod atproto;
+mod citation;
 mod cli;
+mod config;
 mod echo;
 mod fetch;
+mod gemtext;
+mod html;
+mod latex;
 mod llms;
+mod obsidian;
 #[cfg(feature = "pdf")]
 mod pdf;
+mod reader;
+mod settings;
+mod siteconfig;
+mod split;
+mod template;
+mod term;
+#[cfg(test)]
+mod testing;
 mod utils;
+mod warc;
 
 fn main() -> ExitCode {
-    let parsed = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let parsed = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
     let color = color_enabled();
+    init_logging(parsed.verbose);
 
     let res = match parsed.command {
         Some(Commands::Readable(args)) => run_readable(args),
-        Some(Commands::Inspect(args)) => run_inspect(args),
-        Some(Commands::Llms(args)) => llms::run(args),
-        None => run_extract(parsed.extract, color),
+        Some(Commands::Inspect(args)) => run_inspect(*args, matches.subcommand_matches("inspect").unwrap_or(&matches)),
+        Some(Commands::Llms(args)) => llms::run(args, matches.subcommand_matches("llms").unwrap_or(&matches)),
+        Some(Commands::Info(args)) => run_info(args),
+        Some(Commands::Siteconfig(args)) => siteconfig::run(args, &settings::env_read_options()),
+        Some(Commands::Read(args)) => run_read(args, matches.subcommand_matches("read").unwrap_or(&matches)),
+        None => run_extract(parsed.extract, &matches, color),
     };
 
     match res {
@@ -45,33 +66,194 @@ fn main() -> ExitCode {
     }
 }
 
-fn run_extract(args: ExtractArgs, color: bool) -> Result<ExitCode> {
-    let input = fetch::InputDocument::read_src(args.input.as_deref(), args.stdin, args.base_url.as_deref())?;
+/// Send `tracing` events to stderr. `RUST_LOG` takes precedence over
+/// `--verbose`; without either, only warnings are logged.
+fn init_logging(verbose: u8) {
+    let filter = match (std::env::var("RUST_LOG"), verbose) {
+        (Ok(filter), _) => tracing_subscriber::EnvFilter::new(filter),
+        (Err(_), 0) => tracing_subscriber::EnvFilter::new("lectito=warn"),
+        (Err(_), 1) => tracing_subscriber::EnvFilter::new("lectito=debug"),
+        (Err(_), _) => tracing_subscriber::EnvFilter::new("lectito=trace"),
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(color_enabled() && io::stderr().is_terminal())
+        .init();
+}
+
+fn run_extract(mut args: ExtractArgs, matches: &ArgMatches, color: bool) -> Result<ExitCode> {
+    if args.json_schema {
+        echo::json(&echo::json_schema(), true)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let settings = settings::load(args.config.as_deref())?;
+    if let Some(settings) = &settings {
+        settings::apply_extract_args(&mut args, settings, |id| config::explicit(matches, id))?;
+    }
+    let fetch_options = fetch::ReadOptions {
+        max_input_bytes: args
+            .max_input_bytes
+            .or(settings.as_ref().and_then(|settings| settings.extract.max_input_bytes)),
+        user_agent: settings::user_agent(settings.as_ref()),
+    };
+    let fetch_started = Instant::now();
+    let input = fetch::InputDocument::read_src(
+        args.input.as_deref(),
+        args.stdin,
+        args.base_url.as_deref(),
+        &fetch_options,
+    )?;
+    let fetch_time = fetch_started.elapsed();
     let options = ReadabilityOptions {
         max_elems_to_parse: args.max_elems_to_parse,
         nb_top_candidates: args.nb_top_candidates,
         char_threshold: args.char_threshold,
         content_selector: args.content_selector,
         site_profiles: read_site_profiles(&args.profiles)?,
+        site_profile_overrides: args.siteconfig_set,
+        site_profile_ignores: args.siteconfig_ignore,
         mobile_viewport_width: args.mobile_viewport_width.or(Some(480)),
         classes_to_preserve: args.preserve,
         keep_classes: args.keep,
         disable_json_ld: args.disable_json_ld,
-        link_density_modifier: 0.0,
+        link_density_modifier: args.link_density_modifier,
         media_retention: args.media,
+        iframe_allowlist: args.iframe_allow,
+        footnotes: args.footnotes,
+        link_style: args.link_style,
+        link_cleanup: args.link_cleanup,
+        heading_offset: args.heading_offset,
+        normalize_heading_levels: args.normalize_heading_levels,
+        reading_time_markers: args.reading_time_markers,
+        normalize_text: args.normalize_text,
+        typography: args.typography,
+        strategy: args.strategy,
+        cleanup_dry_run: args.cleanup_dry_run,
+        keep_hidden: args.keep_hidden,
+        keep_unlikely_candidates: args.no_remove_unlikely,
+        keep_iframes: args.keep_iframes,
+        sibling_threshold: args.sibling_threshold,
+        min_candidate_score: args.min_candidate_score,
+        class_weight: args.class_weight,
+        positive_patterns: args.positive_patterns,
+        negative_patterns: args.negative_patterns,
+        unlikely_patterns: args.unlikely_patterns,
+        trailing_chrome_patterns: args.trailing_chrome_patterns,
+        doc_chrome_labels: args.doc_chrome_labels,
+        replace_pattern_groups: args.replace_pattern_groups,
+        image_target_width: args.image_target_width,
+        max_input_bytes: args.max_input_bytes,
+        max_candidates: args.max_candidates,
+        max_duration: args.max_duration_ms.map(Duration::from_millis),
+        recover_noscript: args.recover_noscript,
     };
-    let Some(report) = extract_with_timeout(input.html(), input.base_url(), options, args.timeout)? else {
+    let base = settings::extract_base(settings.as_ref(), args.preset, input.base_url());
+    let mut options = match &base {
+        Some(base) => config::merge_extract(base, options, |id| config::explicit(matches, id))?,
+        None => options,
+    };
+    options
+        .site_profiles
+        .extend(siteconfig::synced_profiles_for(input.base_url())?);
+    let Some(mut report) = extract_with_timeout(input.html(), input.base_url(), options.clone(), args.timeout)? else {
         eprintln!("lectito: extraction timed out after {}s", args.timeout);
         return Ok(ExitCode::from(3));
     };
+    let mut followed = Vec::new();
+    if let Some(pages) = args.follow_pages
+        && let Some(article) = report.article.as_mut()
+    {
+        followed = follow_pages(article, &input, &options, &fetch_options, pages, args.timeout)?;
+    }
+
+    let note_path = match (args.profile, report.article.as_ref()) {
+        (Some(ExportProfile::Obsidian), Some(article)) => {
+            Some(obsidian::note_path(args.output.as_deref(), article.title.as_deref()))
+        }
+        _ => None,
+    };
+    let format = match args.profile {
+        Some(_) => OutputFormat::Markdown,
+        None if !config::explicit(matches, "format") => {
+            args.output.as_deref().and_then(format_from_path).unwrap_or(args.format)
+        }
+        None => args.format,
+    };
+
+    let mut images = assets::Images::new(input.resources(), &fetch_options);
+    if (args.download_images || note_path.is_some())
+        && let Some(article) = report.article.as_mut()
+    {
+        let assets_dir = match note_path {
+            Some(_) => Some(
+                args.assets_dir
+                    .as_deref()
+                    .unwrap_or(Path::new(obsidian::ATTACHMENTS_DIR)),
+            ),
+            None => args.assets_dir.as_deref(),
+        };
+        // EPUB books carry their images, so they are inlined for the writer
+        // to store in the archive.
+        let target = match format {
+            OutputFormat::Epub => assets::AssetTarget::Inline,
+            _ => assets::AssetTarget::new(assets_dir, note_path.as_deref().or(args.output.as_deref())),
+        };
+        assets::archive_images(article, &target, &mut images)?;
+    }
+    if let Some(path) = &args.warc {
+        if let Some(article) = report.article.as_ref() {
+            images.fetch_all(article);
+        }
+        let pages: Vec<&fetch::InputDocument> = std::iter::once(&input).chain(&followed).collect();
+        warc::write(path, &pages, images.fetched(), report.article.as_ref())?;
+    }
+
+    if let Some(count) = args.keyphrases
+        && let Some(article) = report.article.as_mut()
+        && article.keywords.is_empty()
+    {
+        article.keywords = article.keyphrases(count);
+    }
+    if let Some(sentences) = args.summary
+        && let Some(article) = report.article.as_mut()
+    {
+        prepend_summary(article, sentences);
+    }
+
+    let format_started = Instant::now();
+    if matches!(format, OutputFormat::Epub) {
+        anyhow::ensure!(args.output.is_some(), "EPUB output needs --output");
+        anyhow::ensure!(args.split_by.is_none(), "--split-by does not support EPUB output");
+        anyhow::ensure!(!args.append, "--append does not support EPUB output");
+    }
+    #[cfg(feature = "pdf")]
+    if args.split_by.is_some() && matches!(format, cli::OutputFormat::Pdf) {
+        anyhow::bail!("--split-by does not support PDF output");
+    }
+    #[cfg(feature = "pdf")]
+    if args.append && matches!(format, cli::OutputFormat::Pdf) {
+        anyhow::bail!("--append does not support PDF output");
+    }
 
     #[cfg(feature = "pdf")]
-    let wrote_article = if matches!(args.format, cli::OutputFormat::Pdf) {
+    let wrote_article = if matches!(format, cli::OutputFormat::Pdf) {
         match report.article.as_ref() {
             Some(article) => {
-                let output = pdf::markdown_to_pdf(&article.markdown).context("failed to render PDF")?;
-                let path = pdf_output_path(args.output.as_ref(), &output);
-                fs::write(&path, output).with_context(|| format!("failed to write {}", path.display()))?;
+                let path = match args.pdf_engine {
+                    cli::PdfEngine::Builtin => {
+                        let output = pdf::markdown_to_pdf(&article.markdown).context("failed to render PDF")?;
+                        let path = pdf_output_path(args.output.as_ref(), &output);
+                        fs::write(&path, output).with_context(|| format!("failed to write {}", path.display()))?;
+                        path
+                    }
+                    cli::PdfEngine::Tectonic => {
+                        let latex = latex::render(article, input.base_url());
+                        let path = pdf_output_path(args.output.as_ref(), latex.as_bytes());
+                        pdf::latex_to_pdf(&latex, &path)?;
+                        path
+                    }
+                };
                 println!("PDF written to {}", path.display());
             }
             None => eprintln!("lectito: no article extracted; no PDF written"),
@@ -84,23 +266,78 @@ fn run_extract(args: ExtractArgs, color: bool) -> Result<ExitCode> {
     #[cfg(not(feature = "pdf"))]
     let wrote_article = false;
 
-    if !wrote_article {
-        let output = echo::render_article(
-            report.article.as_ref(),
-            echo::RenderOptions::new(args.format, args.pretty, input.base_url(), args.frontmatter),
-        )?;
+    if let (Some(path), Some(article)) = (&note_path, report.article.as_ref()) {
+        let note = obsidian::render(article, input.base_url());
+        fs::write(path, note).with_context(|| format!("failed to write {}", path.display()))?;
+        println!("Note written to {}", path.display());
+    } else if let (Some(level), Some(path), Some(article)) = (args.split_by, &args.output, report.article.as_ref()) {
+        let paths = split::write_sections(article, level, path, |section| match &args.template {
+            Some(template) => template::render(template, section, input.base_url(), args.keyphrases),
+            None => echo::render_article(
+                Some(section),
+                echo::RenderOptions::new(format, args.pretty, input.base_url(), args.frontmatter)
+                    .with_keyphrases(args.keyphrases)
+                    .with_ndjson_content(&args.ndjson_content),
+            ),
+        })?;
+        for path in paths {
+            println!("Section written to {}", path.display());
+        }
+    } else if let Some(path) = args.output.as_ref().filter(|_| args.template.is_none() && !args.append)
+        && let Some(name) = saved_format(format)
+        && !wrote_article
+    {
+        match report.article.as_ref() {
+            Some(article) => article.save_from(
+                path,
+                &lectito::OutputOptions {
+                    format: name.to_string(),
+                    frontmatter: args.frontmatter,
+                    pretty: args.pretty,
+                },
+                input.base_url(),
+            )?,
+            None if matches!(format, OutputFormat::Epub) => eprintln!("lectito: no article extracted; no EPUB written"),
+            None => fs::write(path, "").with_context(|| format!("failed to write {}", path.display()))?,
+        }
+    } else if !wrote_article {
+        let output = match (&args.template, report.article.as_ref()) {
+            (Some(path), Some(article)) => template::render(path, article, input.base_url(), args.keyphrases)?,
+            (Some(_), None) => String::new(),
+            (None, article) => echo::render_article(
+                article,
+                echo::RenderOptions::new(format, args.pretty, input.base_url(), args.frontmatter)
+                    .with_keyphrases(args.keyphrases)
+                    .with_color(color && args.output.is_none())
+                    .with_ndjson_content(&args.ndjson_content),
+            )?,
+        };
 
         match args.output.as_ref() {
+            Some(path) if args.append => {
+                utils::append_locked(path, &output, !matches!(format, OutputFormat::Ndjson))?;
+            }
             Some(path) => {
                 fs::write(path, output).with_context(|| format!("failed to write {}", path.display()))?;
             }
-            None => {
-                if !output.is_empty() {
-                    println!("{output}");
-                }
-            }
+            None if output.is_empty() => {}
+            None if matches!(format, OutputFormat::Term) => term::page(&output)?,
+            None => println!("{output}"),
         }
     }
+    if let (Some(metadata_format), Some(article)) = (args.metadata_format, report.article.as_ref()) {
+        println!(
+            "{}",
+            citation::render(article, input.base_url(), metadata_format, args.pretty)?
+        );
+    }
+    if args.timings {
+        io::stdout().flush().context("failed to flush article output")?;
+        eprintln!(
+            "{}",
+            echo::timings(fetch_time, &report.timings, format_started.elapsed())
+        );
+    }
 
     if args.inspect {
         io::stdout().flush().context("failed to flush article output")?;
@@ -108,20 +345,33 @@ fn run_extract(args: ExtractArgs, color: bool) -> Result<ExitCode> {
             "{}",
             echo::inspect(
                 &report,
-                InspectOptions::new(false, input.base_url(), false).with_atproto_warnings(input.atproto_warnings())
+                InspectOptions::new(false, input.base_url(), false)
+                    .with_atproto_warnings(input.atproto_warnings())
+                    .with_site_profiles(&args.profiles)
             )?
         );
     }
     if let Some(format) = args.diagnostic_format {
         io::stdout().flush().context("failed to flush article output")?;
-        echo::diagnostics_with_atproto_warnings(&report.diagnostics, format, color, input.atproto_warnings())?;
+        echo::diagnostics_with_atproto_warnings(
+            &report.diagnostics,
+            format,
+            color,
+            input.atproto_warnings(),
+            &args.profiles,
+        )?;
     }
 
     Ok(if report.article.is_some() { ExitCode::SUCCESS } else { ExitCode::from(1) })
 }
 
 fn run_readable(args: ReadableArgs) -> Result<ExitCode> {
-    let input = fetch::InputDocument::read_src(args.input.as_deref(), args.stdin, args.base_url.as_deref())?;
+    let input = fetch::InputDocument::read_src(
+        args.input.as_deref(),
+        args.stdin,
+        args.base_url.as_deref(),
+        &settings::env_read_options(),
+    )?;
     let options = ReadableOptions { min_content_length: args.min_len, min_score: args.min_score };
     let Some(readable) = readable_with_timeout(input.html(), options, args.timeout)? else {
         eprintln!("lectito: readability check timed out after {}s", args.timeout);
@@ -131,21 +381,181 @@ fn run_readable(args: ReadableArgs) -> Result<ExitCode> {
     Ok(if readable { ExitCode::SUCCESS } else { ExitCode::from(1) })
 }
 
-fn run_inspect(args: InspectArgs) -> Result<ExitCode> {
-    let input = fetch::InputDocument::read_src(args.input.as_deref(), args.stdin, args.base_url.as_deref())?;
+/// Append up to `limit` following pages of a paginated article. A page that
+/// fails to fetch or extract ends the article there, with a note on stderr.
+fn follow_pages(
+    article: &mut lectito::Article, input: &fetch::InputDocument, options: &ReadabilityOptions,
+    fetch_options: &fetch::ReadOptions, limit: usize, timeout: u64,
+) -> Result<Vec<fetch::InputDocument>> {
+    let Some(base_url) = input.base_url() else {
+        anyhow::bail!("--follow-pages needs a URL input or --base-url");
+    };
+    let mut html = input.html().to_string();
+    let mut seen = vec![base_url.to_string()];
+    let mut pages = Vec::new();
+
+    for _ in 0..limit {
+        let current = seen.last().cloned().unwrap_or_default();
+        let Some(next) = lectito::find_next_page(&html, &current, options)? else {
+            break;
+        };
+        if seen.contains(&next) {
+            break;
+        }
+        let page = match fetch::InputDocument::read(None, false, Some(&next), fetch_options) {
+            Ok(page) => page,
+            Err(error) => {
+                eprintln!("lectito: stopped following pages at {next}: {error:#}");
+                break;
+            }
+        };
+        let page_url = page.base_url().unwrap_or(&next).to_string();
+        let page_article = match extract_with_timeout(page.html(), Some(&page_url), options.clone(), timeout)? {
+            Some(report) => report.article,
+            None => {
+                eprintln!("lectito: stopped following pages at {next}: extraction timed out after {timeout}s");
+                break;
+            }
+        };
+        let Some(page_article) = page_article else {
+            eprintln!("lectito: stopped following pages at {next}: no article extracted");
+            break;
+        };
+        article.content = format!("{}\n{}", article.content, page_article.content);
+        article.markdown = format!("{}\n\n{}", article.markdown.trim_end(), page_article.markdown);
+        article.text_content = format!("{}\n\n{}", article.text_content.trim_end(), page_article.text_content);
+        article.length += page_article.length;
+        if page_url != next {
+            seen.push(next);
+        }
+        html = page.html().to_string();
+        seen.push(page_url);
+        pages.push(page);
+    }
+    Ok(pages)
+}
+
+/// Add the summary ahead of the article body in every output view.
+fn prepend_summary(article: &mut lectito::Article, sentences: usize) {
+    let summary = article.summary(sentences);
+    if summary.is_empty() {
+        return;
+    }
+    article.content = format!(
+        "<blockquote class=\"lectito-summary\"><p>{}</p></blockquote>\n{}",
+        lectito::escape_html(&summary),
+        article.content
+    );
+    article.markdown = format!("> **Summary:** {summary}\n\n{}", article.markdown);
+    article.text_content = format!("Summary: {summary}\n\n{}", article.text_content);
+}
+
+fn run_info(args: InfoArgs) -> Result<ExitCode> {
+    let mut user_profiles = Vec::new();
+    for (path, source) in args.profiles.iter().zip(read_site_profiles(&args.profiles)?) {
+        let name = lectito::validate_site_profile(&source).with_context(|| path.display().to_string())?;
+        user_profiles.push(serde_json::json!({ "path": path.display().to_string(), "name": name }));
+    }
+    let bundled = lectito::bundled_site_rules();
+    let mut features = lectito::capabilities().features();
+    if cfg!(feature = "pdf") {
+        features.push("pdf");
+    }
+    features.sort_unstable();
+    let formats: Vec<String> = OutputFormat::value_variants()
+        .iter()
+        .filter_map(|format| format.to_possible_value().map(|value| value.get_name().to_string()))
+        .collect();
+
+    let value = serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "features": features,
+        "output_formats": formats,
+        "defaults": {
+            "readability": ReadabilityOptions::default(),
+            "readable": ReadableOptions::default(),
+        },
+        "site_rules": {
+            "bundled": bundled,
+            "bundled_count": bundled.len(),
+            "bundled_version": lectito::bundled_site_rules_version(),
+            "user": user_profiles,
+            "user_count": user_profiles.len(),
+        },
+    });
+    echo::json(&value, args.pretty)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_inspect(mut args: InspectArgs, matches: &ArgMatches) -> Result<ExitCode> {
+    let settings = settings::load(args.config.as_deref())?;
+    if let Some(settings) = &settings {
+        settings::apply_inspect_args(&mut args, settings, |id| config::explicit(matches, id));
+    }
+    let fetch_options = fetch::ReadOptions {
+        max_input_bytes: args
+            .max_input_bytes
+            .or(settings.as_ref().and_then(|settings| settings.extract.max_input_bytes)),
+        user_agent: settings::user_agent(settings.as_ref()),
+    };
+    let input = fetch::InputDocument::read_src(
+        args.input.as_deref(),
+        args.stdin,
+        args.base_url.as_deref(),
+        &fetch_options,
+    )?;
     let options = ReadabilityOptions {
         max_elems_to_parse: args.max_elems_to_parse,
         nb_top_candidates: args.nb_top_candidates,
         char_threshold: args.char_threshold,
         content_selector: args.content_selector,
         site_profiles: read_site_profiles(&args.profiles)?,
+        site_profile_overrides: args.siteconfig_set,
+        site_profile_ignores: args.siteconfig_ignore,
         mobile_viewport_width: args.mobile_viewport_width.or(Some(480)),
         classes_to_preserve: args.preserve,
         keep_classes: args.keep,
         disable_json_ld: args.disable_json_ld,
-        link_density_modifier: 0.0,
+        link_density_modifier: args.link_density_modifier,
         media_retention: args.media,
+        iframe_allowlist: args.iframe_allow,
+        footnotes: args.footnotes,
+        link_style: args.link_style,
+        link_cleanup: args.link_cleanup,
+        heading_offset: args.heading_offset,
+        normalize_heading_levels: args.normalize_heading_levels,
+        reading_time_markers: args.reading_time_markers,
+        normalize_text: args.normalize_text,
+        typography: args.typography,
+        strategy: args.strategy,
+        cleanup_dry_run: args.cleanup_dry_run,
+        keep_hidden: args.keep_hidden,
+        keep_unlikely_candidates: args.no_remove_unlikely,
+        keep_iframes: args.keep_iframes,
+        sibling_threshold: args.sibling_threshold,
+        min_candidate_score: args.min_candidate_score,
+        class_weight: args.class_weight,
+        positive_patterns: args.positive_patterns,
+        negative_patterns: args.negative_patterns,
+        unlikely_patterns: args.unlikely_patterns,
+        trailing_chrome_patterns: args.trailing_chrome_patterns,
+        doc_chrome_labels: args.doc_chrome_labels,
+        replace_pattern_groups: args.replace_pattern_groups,
+        image_target_width: args.image_target_width,
+        max_input_bytes: args.max_input_bytes,
+        max_candidates: args.max_candidates,
+        max_duration: args.max_duration_ms.map(Duration::from_millis),
+        recover_noscript: args.recover_noscript,
     };
+    let base = settings::extract_base(settings.as_ref(), args.preset, input.base_url());
+    let mut options = match &base {
+        Some(base) => config::merge_extract(base, options, |id| config::explicit(matches, id))?,
+        None => options,
+    };
+    options
+        .site_profiles
+        .extend(siteconfig::synced_profiles_for(input.base_url())?);
     let Some(report) = extract_with_timeout(input.html(), input.base_url(), options, args.timeout)? else {
         eprintln!("lectito: extraction timed out after {}s", args.timeout);
         return Ok(ExitCode::from(3));
@@ -156,26 +566,71 @@ fn run_inspect(args: InspectArgs) -> Result<ExitCode> {
             &report,
             InspectOptions::new(args.pretty, input.base_url(), args.json)
                 .with_atproto_warnings(input.atproto_warnings())
+                .with_site_profiles(&args.profiles)
         )?
     );
     Ok(if report.article.is_some() { ExitCode::SUCCESS } else { ExitCode::from(1) })
 }
 
+fn run_read(mut args: ReadArgs, matches: &ArgMatches) -> Result<ExitCode> {
+    let settings = settings::load(None)?;
+    if let Some(settings) = &settings {
+        settings::apply_timeout(&mut args.timeout, settings, config::explicit(matches, "timeout"));
+    }
+    let input = fetch::InputDocument::read_src(
+        Some(&args.input),
+        false,
+        args.base_url.as_deref(),
+        &settings::read_options(settings.as_ref()),
+    )?;
+    let options = settings::extract_base(settings.as_ref(), None, input.base_url()).unwrap_or_default();
+    let Some(report) = extract_with_timeout(input.html(), input.base_url(), options, args.timeout)? else {
+        eprintln!("lectito: extraction timed out after {}s", args.timeout);
+        return Ok(ExitCode::from(3));
+    };
+    let Some(article) = report.article else {
+        eprintln!("lectito: no article extracted");
+        return Ok(ExitCode::from(1));
+    };
+    reader::run(&article, input.base_url(), &args.save_dir)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The format an `--output` extension names, used when `--format` is not set.
+fn format_from_path(path: &Path) -> Option<OutputFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "md" | "markdown" => OutputFormat::Markdown,
+        "html" | "htm" => OutputFormat::Html,
+        "txt" => OutputFormat::Text,
+        "json" => OutputFormat::Json,
+        "ndjson" | "jsonl" => OutputFormat::Ndjson,
+        "gmi" => OutputFormat::Gmi,
+        "tex" => OutputFormat::Latex,
+        "epub" => OutputFormat::Epub,
+        #[cfg(feature = "pdf")]
+        "pdf" => OutputFormat::Pdf,
+        _ => return None,
+    })
+}
+
+/// The library format name for formats [`lectito::Article::save_from`]
+/// writes the same way the CLI renders them.
+fn saved_format(format: OutputFormat) -> Option<&'static str> {
+    match format {
+        OutputFormat::Markdown => Some("markdown"),
+        OutputFormat::Html => Some("html"),
+        OutputFormat::Text => Some("text"),
+        OutputFormat::Epub => Some("epub"),
+        _ => None,
+    }
+}
+
 #[cfg(feature = "pdf")]
 fn pdf_output_path(explicit: Option<&PathBuf>, pdf: &[u8]) -> PathBuf {
     explicit
         .cloned()
-        .unwrap_or_else(|| PathBuf::from(format!("{:016x}.pdf", fnv1a64(pdf))))
-}
-
-#[cfg(feature = "pdf")]
-fn fnv1a64(bytes: &[u8]) -> u64 {
-    let mut hash = 0xcbf29ce484222325;
-    for byte in bytes {
-        hash ^= u64::from(*byte);
-        hash = hash.wrapping_mul(0x100000001b3);
-    }
-    hash
+        .unwrap_or_else(|| PathBuf::from(format!("{:016x}.pdf", utils::fnv1a64(pdf))))
 }
 
 fn extract_with_timeout(
@@ -220,8 +675,10 @@ fn read_site_profiles(paths: &[PathBuf]) -> Result<Vec<String>> {
         .collect()
 }
 
+/// `LECTITO_COLOR` forces color on or off; otherwise color is on unless
+/// `NO_COLOR` is set.
 fn color_enabled() -> bool {
-    std::env::var_os("NO_COLOR").is_none()
+    settings::env_color().unwrap_or_else(|| std::env::var_os("NO_COLOR").is_none())
 }
 
 #[cfg(test)]
@@ -254,6 +711,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn output_extension_names_the_format() {
+        let directory = std::env::temp_dir().join(format!("lectito-output-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let page = directory.join("tides.html");
+        fs::write(
+            &page,
+            "<html><head><title>On Tides</title></head><body><article><p>The tide comes in twice a day, \
+             pulled by the moon and, more weakly, by the sun. Harbour pilots plan every crossing around \
+             it, and the almanac prints each high water to the minute.</p></article></body></html>",
+        )
+        .unwrap();
+        let run = |extra: &[&str]| {
+            let page = page.to_str().unwrap();
+            let matches = Cli::command()
+                .try_get_matches_from(["lectito", page, "--char-threshold", "0"].iter().chain(extra))
+                .unwrap();
+            let args = Cli::from_arg_matches(&matches).unwrap().extract;
+            run_extract(args, &matches, false).unwrap()
+        };
+
+        let epub = directory.join("tides.epub");
+        assert_eq!(run(&["-o", epub.to_str().unwrap()]), ExitCode::SUCCESS);
+        assert!(fs::read(&epub).unwrap().starts_with(b"PK\x03\x04"));
+        let text = directory.join("tides.txt");
+        run(&["-o", text.to_str().unwrap()]);
+        assert!(fs::read_to_string(&text).unwrap().starts_with("The tide comes in"));
+        let forced = directory.join("forced.txt");
+        run(&["-o", forced.to_str().unwrap(), "--format", "html"]);
+        assert!(fs::read_to_string(&forced).unwrap().contains("<p>"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
     #[cfg(feature = "pdf")]
     #[test]
     fn pdf_output_path_defaults_to_hash_filename() {
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use lectito::Article;
+use regex::{Captures, Regex};
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+use crate::utils::file_stem;
+
+/// Folder for downloaded images, relative to the note.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?P<bang>!?)\[(?P<text>(?:\\.|[^\]\\])*)\]\((?P<url>[^)\s]+)(?:\s+"[^"]*")?\)"#)
+        .expect("valid markdown link regex")
+});
+
+/// Markdown note with YAML frontmatter for an Obsidian vault.
+///
+/// Links to headings on the same page become `[[#Heading]]` links, and links
+/// to other anchors on the page keep only their text, since Obsidian cannot
+/// resolve them.
+pub fn render(article: &Article, source: Option<&str>) -> String {
+    let mut note = String::from("---\n");
+    push_field(&mut note, "title", article.title.as_deref());
+    push_list(&mut note, "aliases", &aliases(article));
+    push_list(&mut note, "tags", &tags(&article.keywords));
+    push_field(&mut note, "source", source);
+    push_field(&mut note, "author", article.byline.as_deref());
+    push_field(&mut note, "published", article.published_time.as_deref());
+    push_field(&mut note, "site", article.site_name.as_deref());
+    push_field(&mut note, "description", article.excerpt.as_deref());
+    note.push_str("---\n\n");
+    note.push_str(&convert_anchors(&article.markdown, &article.content, source));
+    note
+}
+
+/// Where to write the note: inside `output` when it is a directory, at
+/// `output` when it names a file, or in the current directory otherwise.
+pub fn note_path(output: Option<&Path>, title: Option<&str>) -> PathBuf {
+    let name = format!("{}.md", file_stem(title));
+    match output {
+        Some(output) if output.is_dir() => output.join(name),
+        Some(output) => output.to_path_buf(),
+        None => PathBuf::from(name),
+    }
+}
+
+/// Other names the note can be linked by: the page's raw title and, when the
+/// file name had to drop characters, the title itself.
+fn aliases(article: &Article) -> Vec<String> {
+    let title = non_empty(article.title.as_deref());
+    let mut aliases = Vec::new();
+    if let Some(title) = title
+        && file_stem(Some(title)) != title
+    {
+        aliases.push(title.to_string());
+    }
+    if let Some(raw_title) = non_empty(article.raw_title.as_deref())
+        && Some(raw_title) != title
+        && !aliases.iter().any(|alias| alias == raw_title)
+    {
+        aliases.push(raw_title.to_string());
+    }
+    aliases
+}
+
+/// Obsidian tags allow letters, digits, `_`, `-`, and `/`, and cannot be only
+/// digits.
+fn tags(keywords: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for keyword in keywords {
+        let tag: String = keyword
+            .trim()
+            .trim_start_matches('#')
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+            .flat_map(char::to_lowercase)
+            .collect();
+        if tag.chars().any(|c| !c.is_ascii_digit()) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn convert_anchors(markdown: &str, content: &str, source: Option<&str>) -> String {
+    let headings = heading_ids(content);
+    let page = source.and_then(|source| Url::parse(source).ok());
+    MARKDOWN_LINK
+        .replace_all(markdown, |captures: &Captures<'_>| {
+            let Some(fragment) = (captures["bang"].is_empty())
+                .then(|| internal_fragment(&captures["url"], page.as_ref()))
+                .flatten()
+            else {
+                return captures[0].to_string();
+            };
+            let text = &captures["text"];
+            match headings.get(&fragment) {
+                Some(heading) if heading == text => format!("[[#{heading}]]"),
+                Some(heading) => format!("[[#{heading}|{}]]", text.replace('|', "\\|")),
+                None => text.to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// The fragment of `url` when it points elsewhere on the same page.
+fn internal_fragment(url: &str, page: Option<&Url>) -> Option<String> {
+    if let Some(fragment) = url.strip_prefix('#') {
+        return Some(fragment.to_string());
+    }
+    let mut url = Url::parse(url).ok()?;
+    let fragment = url.fragment()?.to_string();
+    url.set_fragment(None);
+    let mut page = page?.clone();
+    page.set_fragment(None);
+    (url == page).then_some(fragment)
+}
+
+/// Heading text by `id`, cleaned of the characters Obsidian heading links
+/// cannot contain.
+fn heading_ids(content: &str) -> HashMap<String, String> {
+    let document = Html::parse_fragment(content);
+    let selector = Selector::parse("h1[id], h2[id], h3[id], h4[id], h5[id], h6[id]").expect("valid heading selector");
+    document
+        .select(&selector)
+        .filter_map(|heading| {
+            let text: String = heading
+                .text()
+                .collect::<String>()
+                .chars()
+                .filter(|c| !"#|^[]".contains(*c))
+                .collect();
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let id = heading.value().attr("id")?;
+            (!text.is_empty()).then(|| (id.to_string(), text))
+        })
+        .collect()
+}
+
+fn push_field(note: &mut String, key: &str, value: Option<&str>) {
+    if let Some(value) = non_empty(value) {
+        note.push_str(&format!("{key}: {}\n", yaml_string(value)));
+    }
+}
+
+fn push_list(note: &mut String, key: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+    note.push_str(&format!("{key}:\n"));
+    for value in values {
+        note.push_str(&format!("  - {}\n", yaml_string(value)));
+    }
+}
+
+/// JSON strings are valid YAML double-quoted scalars.
+fn yaml_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn renders_yaml_frontmatter_and_heading_links() {
+        let article = Article {
+            title: Some("Notes: On Tides".to_string()),
+            raw_title: Some("Notes: On Tides / Waves?".to_string()),
+            byline: Some("Jane Roe".to_string()),
+            content: r#"<p>Intro</p><h2 id="how-tides-work">How [Tides] Work</h2>"#.to_string(),
+            markdown: "See [how they work](https://example.com/post#how-tides-work), [nowhere](#missing), \
+                       and [elsewhere](https://example.com/x#frag).\n\n## How \\[Tides\\] Work\n"
+                .to_string(),
+            keywords: vec!["Ocean Science".to_string(), "#tides".to_string(), "2026".to_string()],
+            ..Default::default()
+        };
+
+        let note = render(&article, Some("https://example.com/post"));
+
+        assert_eq!(
+            note,
+            "---\n\
+             title: \"Notes: On Tides\"\n\
+             aliases:\n  - \"Notes: On Tides\"\n  - \"Notes: On Tides / Waves?\"\n\
+             tags:\n  - \"ocean-science\"\n  - \"tides\"\n\
+             source: \"https://example.com/post\"\n\
+             author: \"Jane Roe\"\n\
+             ---\n\n\
+             See [[#How Tides Work|how they work]], nowhere, and [elsewhere](https://example.com/x#frag).\n\n\
+             ## How \\[Tides\\] Work\n"
+        );
+    }
+
+    #[test]
+    fn names_notes_from_sanitized_titles() {
+        assert_eq!(
+            note_path(None, Some("Notes: On Tides / Waves?")),
+            Path::new("Notes On Tides Waves.md")
+        );
+        assert_eq!(note_path(None, Some("  ..  ")), Path::new("Untitled.md"));
+        assert_eq!(note_path(None, Some("con")), Path::new("con_.md"));
+        assert_eq!(
+            note_path(Some(Path::new("note.md")), Some("Title")),
+            Path::new("note.md")
+        );
+        assert_eq!(
+            note_path(Some(&std::env::temp_dir()), Some("Title")),
+            std::env::temp_dir().join("Title.md")
+        );
+    }
+}
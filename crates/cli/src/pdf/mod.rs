@@ -1,11 +1,16 @@
-//! Markdown-to-PDF rendering for the optional `pdf` CLI feature.
+//! PDF rendering for the optional `pdf` CLI feature.
 
 mod layout;
 mod parser;
 mod renderer;
 mod styler;
 
+use std::fs;
 use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
 
 pub fn markdown_to_pdf(markdown: &str) -> io::Result<Vec<u8>> {
     let nodes = parser::parse_markdown(markdown);
@@ -14,6 +19,48 @@ pub fn markdown_to_pdf(markdown: &str) -> io::Result<Vec<u8>> {
     renderer::render_to_pdf(&pages)
 }
 
+/// Typeset a LaTeX document with `tectonic`, writing the PDF to `output`.
+///
+/// The source is written next to the output so relative image paths from
+/// `--assets-dir` resolve, and removed afterwards.
+pub fn latex_to_pdf(latex: &str, output: &Path) -> Result<()> {
+    let dir = match output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let stem = output.file_stem().context("PDF output path has no file name")?;
+    let with_extension = |extension: &str| {
+        let mut name = stem.to_os_string();
+        name.push(extension);
+        dir.join(name)
+    };
+    let source = with_extension(".tex");
+    let rendered = with_extension(".pdf");
+    fs::write(&source, latex).with_context(|| format!("failed to write {}", source.display()))?;
+
+    let result = Command::new("tectonic")
+        .arg("--chatter")
+        .arg("minimal")
+        .arg("--outdir")
+        .arg(dir)
+        .arg(&source)
+        .output();
+    let _ = fs::remove_file(&source);
+    let result = match result {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            anyhow::bail!("tectonic was not found on PATH; install it or use --pdf-engine builtin")
+        }
+        result => result.context("failed to run tectonic")?,
+    };
+    if !result.status.success() {
+        anyhow::bail!("tectonic failed: {}", String::from_utf8_lossy(&result.stderr).trim());
+    }
+    if rendered != output {
+        fs::rename(&rendered, output).with_context(|| format!("failed to write {}", output.display()))?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
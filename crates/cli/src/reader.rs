@@ -0,0 +1,481 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use lectito::Article;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout as Split, Rect};
+use ratatui::style::{Color, Modifier, Style as TuiStyle};
+use ratatui::text::{Line as TuiLine, Span as TuiSpan};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+use crate::term::{self, Layout, Token};
+use crate::utils;
+
+/// Width of the contents sidebar, border included.
+const CONTENTS_WIDTH: u16 = 32;
+
+const HELP: &str = "q quit · ↑↓ scroll · n/p section · t contents · l links · m save Markdown · e save EPUB";
+
+/// Show the article in a full-screen reader until the user quits.
+///
+/// Saved files are written to `save_dir`, named from the article title.
+pub fn run(article: &Article, source: Option<&str>, save_dir: &Path) -> Result<()> {
+    let mut reader = Reader::new(article, source, save_dir, term::width());
+    let mut terminal = ratatui::init();
+    let result = reader.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Article,
+    Contents,
+    Links,
+}
+
+struct Reader<'a> {
+    article: &'a Article,
+    source: Option<&'a str>,
+    save_dir: &'a Path,
+    layout: Layout,
+    /// Width the layout was wrapped to.
+    width: usize,
+    /// First article line on screen.
+    scroll: usize,
+    /// Article lines that fit on screen, from the last draw.
+    height: usize,
+    show_contents: bool,
+    focus: Focus,
+    contents: ListState,
+    links: Vec<(String, String)>,
+    link_list: ListState,
+    status: Option<String>,
+    quit: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(article: &'a Article, source: Option<&'a str>, save_dir: &'a Path, width: usize) -> Self {
+        let layout = term::layout(article, source, width);
+        let show_contents = layout.headings.len() > 1;
+        Reader {
+            article,
+            source,
+            save_dir,
+            layout,
+            width,
+            scroll: 0,
+            height: 1,
+            show_contents,
+            focus: Focus::Article,
+            contents: ListState::default(),
+            links: links(article, source),
+            link_list: ListState::default().with_selected(Some(0)),
+            status: None,
+            quit: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .context("failed to draw the reader")?;
+            if let Event::Key(key) = event::read().context("failed to read terminal input")?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, status] = Split::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let article_area = match self.show_contents {
+            true => {
+                let [contents, article] =
+                    Split::horizontal([Constraint::Length(CONTENTS_WIDTH), Constraint::Min(1)]).areas(body);
+                self.draw_contents(frame, contents);
+                article
+            }
+            false => body,
+        };
+        self.draw_article(frame, article_area);
+
+        let position = match self.layout.lines.len().saturating_sub(self.height) {
+            0 => 100,
+            end => self.scroll.min(end) * 100 / end,
+        };
+        let message = self.status.as_deref().unwrap_or(HELP);
+        frame.render_widget(
+            Paragraph::new(format!("{position:>3}% │ {message}")).style(TuiStyle::new().add_modifier(Modifier::DIM)),
+            status,
+        );
+
+        if self.focus == Focus::Links {
+            self.draw_links(frame, body);
+        }
+    }
+
+    fn draw_article(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::new().padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        let width = usize::from(inner.width).min(term::MAX_WIDTH);
+        if width != self.width {
+            self.relayout(width);
+        }
+        self.height = usize::from(inner.height).max(1);
+        self.scroll = self.scroll.min(self.max_scroll());
+
+        let lines: Vec<TuiLine> = self
+            .layout
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.height)
+            .map(|line| {
+                TuiLine::from(
+                    line.iter()
+                        .map(|span| TuiSpan::styled(span.text.clone(), style(span.style)))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_contents(&mut self, frame: &mut Frame, area: Rect) {
+        let top = self
+            .layout
+            .headings
+            .iter()
+            .map(|heading| heading.level)
+            .min()
+            .unwrap_or(1);
+        let items: Vec<ListItem> = self
+            .layout
+            .headings
+            .iter()
+            .map(|heading| {
+                let indent = "  ".repeat(usize::from(heading.level.saturating_sub(top)));
+                ListItem::new(format!("{indent}{}", heading.title))
+            })
+            .collect();
+        if self.focus != Focus::Contents {
+            self.contents.select(self.current_heading());
+        }
+        let highlight = match self.focus {
+            Focus::Contents => TuiStyle::new().add_modifier(Modifier::REVERSED),
+            _ => TuiStyle::new().add_modifier(Modifier::BOLD),
+        };
+        let list = List::new(items)
+            .block(Block::new().borders(Borders::RIGHT).title(" Contents "))
+            .highlight_style(highlight);
+        frame.render_stateful_widget(list, area, &mut self.contents);
+    }
+
+    fn draw_links(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, middle, _] =
+            Split::vertical([Constraint::Fill(1), Constraint::Percentage(60), Constraint::Fill(1)]).areas(area);
+        let [_, popup, _] =
+            Split::horizontal([Constraint::Fill(1), Constraint::Percentage(80), Constraint::Fill(1)]).areas(middle);
+        let items: Vec<ListItem> = self
+            .links
+            .iter()
+            .map(|(text, url)| {
+                ListItem::new(TuiLine::from(vec![
+                    TuiSpan::raw(format!("{text} ")),
+                    TuiSpan::styled(url.clone(), TuiStyle::new().add_modifier(Modifier::DIM)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Links · Enter opens · Esc closes "))
+            .highlight_style(TuiStyle::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(list, popup, &mut self.link_list);
+    }
+
+    fn handle(&mut self, key: KeyEvent) {
+        self.status = None;
+        match self.focus {
+            Focus::Article => self.handle_article(key),
+            Focus::Contents => self.handle_contents(key),
+            Focus::Links => self.handle_links(key),
+        }
+    }
+
+    fn handle_article(&mut self, key: KeyEvent) {
+        let page = self.height.saturating_sub(2).max(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_to(self.scroll + 1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_to(self.scroll.saturating_sub(1)),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_to(self.scroll + page),
+            KeyCode::PageUp | KeyCode::Char('b') => self.scroll_to(self.scroll.saturating_sub(page)),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll_to(0),
+            KeyCode::End | KeyCode::Char('G') => self.scroll_to(self.max_scroll()),
+            KeyCode::Char('n') => {
+                if let Some(heading) = self.layout.headings.iter().find(|heading| heading.line > self.scroll) {
+                    self.scroll_to(heading.line);
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some(heading) = self
+                    .layout
+                    .headings
+                    .iter()
+                    .rev()
+                    .find(|heading| heading.line < self.scroll)
+                {
+                    self.scroll_to(heading.line);
+                }
+            }
+            KeyCode::Char('t') => {
+                self.show_contents = !self.show_contents;
+            }
+            KeyCode::Tab if self.show_contents => {
+                self.contents.select(self.current_heading());
+                self.focus = Focus::Contents;
+            }
+            KeyCode::Char('l') => match self.links.is_empty() {
+                true => self.status = Some("No links in this article".to_string()),
+                false => self.focus = Focus::Links,
+            },
+            KeyCode::Char('m') => self.save("md"),
+            KeyCode::Char('e') => self.save("epub"),
+            _ => {}
+        }
+    }
+
+    fn handle_contents(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.contents.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.contents.select_previous(),
+            KeyCode::Enter => {
+                if let Some(heading) = self
+                    .contents
+                    .selected()
+                    .and_then(|index| self.layout.headings.get(index))
+                {
+                    self.scroll_to(heading.line);
+                }
+                self.focus = Focus::Article;
+            }
+            KeyCode::Tab | KeyCode::Esc => self.focus = Focus::Article,
+            KeyCode::Char('q') => self.quit = true,
+            _ => {}
+        }
+    }
+
+    fn handle_links(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.link_list.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.link_list.select_previous(),
+            KeyCode::Enter => {
+                if let Some((_, url)) = self.link_list.selected().and_then(|index| self.links.get(index)) {
+                    self.status = Some(match open_url(url) {
+                        Ok(()) => format!("Opened {url}"),
+                        Err(error) => format!("Could not open {url}: {error:#}"),
+                    });
+                }
+                self.focus = Focus::Article;
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('l') => self.focus = Focus::Article,
+            _ => {}
+        }
+    }
+
+    fn scroll_to(&mut self, line: usize) {
+        self.scroll = line.min(self.max_scroll());
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.layout.lines.len().saturating_sub(self.height)
+    }
+
+    /// Index of the last heading at or above the top of the screen.
+    fn current_heading(&self) -> Option<usize> {
+        self.layout
+            .headings
+            .iter()
+            .rposition(|heading| heading.line <= self.scroll)
+    }
+
+    /// Wrap to a new width, keeping the same share of the article scrolled.
+    fn relayout(&mut self, width: usize) {
+        let previous = self.layout.lines.len().max(1);
+        self.layout = term::layout(self.article, self.source, width);
+        self.scroll = self.scroll * self.layout.lines.len() / previous;
+        self.width = width;
+    }
+
+    fn save(&mut self, extension: &str) {
+        let path = self.save_dir.join(format!(
+            "{}.{extension}",
+            utils::file_stem(self.article.title.as_deref())
+        ));
+        self.status = Some(match self.article.save(&path) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(error) => error.to_string(),
+        });
+    }
+}
+
+/// URL schemes the reader hands to the desktop. Anything else, such as
+/// `file:`, `javascript:`, or an app's custom protocol, is never opened.
+const OPENABLE_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// `href` resolved against `base` when it is an absolute URL with an
+/// openable scheme.
+fn openable_url(href: &str, base: Option<&Url>) -> Option<Url> {
+    let url = match base {
+        Some(base) => base.join(href).ok()?,
+        None => Url::parse(href).ok()?,
+    };
+    OPENABLE_SCHEMES.contains(&url.scheme()).then_some(url)
+}
+
+/// The article's links as text and absolute URL, first use of each URL only.
+/// Links the reader would not open are left out.
+fn links(article: &Article, source: Option<&str>) -> Vec<(String, String)> {
+    let base = source.and_then(|source| Url::parse(source).ok());
+    let Ok(selector) = Selector::parse("a[href]") else {
+        return Vec::new();
+    };
+    let document = Html::parse_fragment(&article.content);
+    let mut links: Vec<(String, String)> = Vec::new();
+    for link in document.select(&selector) {
+        let href = link.value().attr("href").unwrap_or_default().trim();
+        if href.is_empty() || href.starts_with('#') {
+            continue;
+        }
+        let Some(url) = openable_url(href, base.as_ref()).map(String::from) else {
+            continue;
+        };
+        if links.iter().any(|(_, known)| *known == url) {
+            continue;
+        }
+        let text = link.text().collect::<Vec<_>>().join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        links.push((if text.is_empty() { url.clone() } else { text }, url));
+    }
+    links
+}
+
+/// Open an http(s) or mailto URL with the desktop's default handler. The
+/// URL is passed as a single argument, never through a shell.
+fn open_url(url: &str) -> Result<()> {
+    let url = openable_url(url, None).context("refusing to open a link that is not http(s) or mailto")?;
+    let program = match std::env::consts::OS {
+        "macos" => "open",
+        "windows" => "explorer",
+        _ => "xdg-open",
+    };
+    Command::new(program)
+        .arg(url.as_str())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("failed to start the browser")?;
+    Ok(())
+}
+
+fn style(style: term::Style) -> TuiStyle {
+    let mut tui = TuiStyle::new();
+    if style.bold {
+        tui = tui.add_modifier(Modifier::BOLD);
+    }
+    if style.italic {
+        tui = tui.add_modifier(Modifier::ITALIC);
+    }
+    if style.underline {
+        tui = tui.add_modifier(Modifier::UNDERLINED);
+    }
+    if style.dim {
+        tui = tui.add_modifier(Modifier::DIM);
+    }
+    match style.token {
+        Some(Token::Keyword) => tui.fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        Some(Token::String) => tui.fg(Color::Green),
+        Some(Token::Number) => tui.fg(Color::Yellow),
+        Some(Token::Comment) => tui.add_modifier(Modifier::DIM),
+        None if style.code => tui.fg(Color::Cyan),
+        None => tui,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article;
+
+    fn press(reader: &mut Reader, code: KeyCode) {
+        reader.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn jumps_between_sections_and_lists_links() {
+        let paragraph = "<p>The tide rises and falls.</p>".repeat(20);
+        let article = article(&format!(
+            r##"<h2>Spring</h2>{paragraph}<p><a href="/moon">Moon</a> and <a href="#top">top</a></p>
+            <h2>Neap</h2>{paragraph}<p><a href="https://example.org/">Elsewhere</a> <a href="/moon">again</a></p>"##
+        ));
+        let mut reader = Reader::new(&article, Some("https://example.com/tides"), Path::new("."), 60);
+        reader.height = 10;
+
+        let lines: Vec<usize> = reader.layout.headings.iter().map(|heading| heading.line).collect();
+        assert_eq!(lines.len(), 3);
+        press(&mut reader, KeyCode::Char('n'));
+        assert_eq!(reader.scroll, lines[1]);
+        press(&mut reader, KeyCode::Char('n'));
+        assert_eq!(reader.scroll, lines[2]);
+        press(&mut reader, KeyCode::Char('p'));
+        assert_eq!(reader.scroll, lines[1]);
+
+        press(&mut reader, KeyCode::Tab);
+        press(&mut reader, KeyCode::Down);
+        press(&mut reader, KeyCode::Enter);
+        assert_eq!(reader.focus, Focus::Article);
+        assert_eq!(reader.scroll, lines[2]);
+
+        assert_eq!(
+            reader.links,
+            [
+                ("Moon".to_string(), "https://example.com/moon".to_string()),
+                ("Elsewhere".to_string(), "https://example.org/".to_string()),
+            ]
+        );
+        press(&mut reader, KeyCode::Char('l'));
+        assert_eq!(reader.focus, Focus::Links);
+        press(&mut reader, KeyCode::Esc);
+        press(&mut reader, KeyCode::Char('q'));
+        assert!(reader.quit);
+    }
+
+    #[test]
+    fn lists_and_opens_only_web_and_mail_links() {
+        let article = article(
+            r#"<p><a href="file:///etc/passwd">passwd</a> <a href="javascript:alert(1)">script</a>
+            <a href="vscode://open?file=x">editor</a> <a href="mailto:tides@example.com">mail</a>
+            <a href="/search?q=a&amp;calc">search</a></p>"#,
+        );
+        assert_eq!(
+            links(&article, Some("https://example.com/tides")),
+            [
+                ("mail".to_string(), "mailto:tides@example.com".to_string()),
+                ("search".to_string(), "https://example.com/search?q=a&calc".to_string()),
+            ]
+        );
+        assert!(links(&article, None).iter().all(|(_, url)| url.starts_with("mailto:")));
+        assert!(open_url("file:///etc/passwd").is_err());
+        assert!(open_url("javascript:alert(1)").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use lectito::{LectitoOptions, Preset, ReadabilityOptions};
+
+use crate::cli::{ExtractArgs, InspectArgs, OutputFormat};
+use crate::{config, fetch};
+
+/// File name of the settings file in the user config directory.
+pub const USER_CONFIG_FILE: &str = "config.toml";
+
+/// Environment variable that replaces the settings file's `fetch.user_agent`.
+/// `LECTITO_TIMEOUT` and `LECTITO_FORMAT` are read by clap with their flags,
+/// and `LECTITO_CONFIG_DIR` by [`config::config_dir`].
+pub const USER_AGENT_ENV: &str = "LECTITO_USER_AGENT";
+
+/// Environment variable that forces color output on or off.
+pub const COLOR_ENV: &str = "LECTITO_COLOR";
+
+/// `config.toml` in Lectito's user config directory, whether or not it exists.
+pub fn user_config_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join(USER_CONFIG_FILE))
+}
+
+/// Settings for a run: the `--config` file when one is given, otherwise the
+/// user config file if it exists.
+pub fn load(path: Option<&Path>) -> Result<Option<LectitoOptions>> {
+    match path {
+        Some(path) => read(path).map(Some),
+        None => user_config_path()
+            .filter(|path| path.is_file())
+            .map(|path| read(&path))
+            .transpose(),
+    }
+}
+
+/// Read a settings file: JSON when the extension is `.json`, TOML otherwise.
+fn read(path: &Path) -> Result<LectitoOptions> {
+    let source = fs::read_to_string(path).with_context(|| format!("failed to read config {}", path.display()))?;
+    let options = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => LectitoOptions::from_json(&source),
+        _ => LectitoOptions::from_toml(&source),
+    };
+    options.with_context(|| format!("invalid config {}", path.display()))
+}
+
+/// The user agent to send: `LECTITO_USER_AGENT` when it is set, otherwise
+/// `fetch.user_agent` from `settings`.
+pub fn user_agent(settings: Option<&LectitoOptions>) -> Option<String> {
+    env::var(USER_AGENT_ENV)
+        .ok()
+        .filter(|agent| !agent.trim().is_empty())
+        .or_else(|| settings.and_then(|settings| settings.fetch.user_agent.clone()))
+}
+
+/// Read options from `settings`: its input size limit and [`user_agent`].
+pub fn read_options(settings: Option<&LectitoOptions>) -> fetch::ReadOptions {
+    fetch::ReadOptions {
+        max_input_bytes: settings.and_then(|settings| settings.extract.max_input_bytes),
+        user_agent: user_agent(settings),
+    }
+}
+
+/// Read options for commands that take no settings file: only
+/// `LECTITO_USER_AGENT` applies.
+pub fn env_read_options() -> fetch::ReadOptions {
+    read_options(None)
+}
+
+/// Color choice forced by `LECTITO_COLOR`, if any.
+pub fn env_color() -> Option<bool> {
+    parse_color(&env::var(COLOR_ENV).ok()?)
+}
+
+/// `always` and `never`, or a boolean such as `true`, `off`, or `0`. `auto`
+/// and unrecognized values force nothing.
+fn parse_color(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "always" | "true" | "yes" | "on" | "1" => Some(true),
+        "never" | "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Apply the `fetch` and `output` sections of `settings` to `args`, keeping
+/// every value `explicit` says came from the command line.
+pub fn apply_extract_args(
+    args: &mut ExtractArgs, settings: &LectitoOptions, explicit: impl Fn(&str) -> bool,
+) -> Result<()> {
+    if !explicit("format") {
+        args.format = OutputFormat::from_str(&settings.output.format, true)
+            .map_err(|error| anyhow::anyhow!("invalid output format in config: {error}"))?;
+    }
+    if !explicit("frontmatter") {
+        args.frontmatter = settings.output.frontmatter;
+    }
+    apply_timeout(&mut args.timeout, settings, explicit("timeout"));
+    if !explicit("pretty") {
+        args.pretty = settings.output.pretty;
+    }
+    args.follow_pages = args.follow_pages.or(settings.fetch.follow_pages);
+    Ok(())
+}
+
+/// Apply the `fetch.timeout` and `output.pretty` settings to `inspect`,
+/// keeping every value `explicit` says came from the command line.
+pub fn apply_inspect_args(args: &mut InspectArgs, settings: &LectitoOptions, explicit: impl Fn(&str) -> bool) {
+    apply_timeout(&mut args.timeout, settings, explicit("timeout"));
+    if !explicit("pretty") {
+        args.pretty = settings.output.pretty;
+    }
+}
+
+/// Replace `timeout` with the `fetch.timeout` setting unless it was passed
+/// explicitly.
+pub fn apply_timeout(timeout: &mut u64, settings: &LectitoOptions, explicit: bool) {
+    if !explicit && let Some(setting) = settings.fetch.timeout {
+        *timeout = setting;
+    }
+}
+
+/// Extraction options the command-line flags are merged onto: `--preset`
+/// when given, otherwise the settings' options for the page at `url`, which
+/// take the first matching `[[routes]]` entry.
+pub fn extract_base(
+    settings: Option<&LectitoOptions>, preset: Option<Preset>, url: Option<&str>,
+) -> Option<ReadabilityOptions> {
+    match (preset, settings) {
+        (Some(preset), _) => Some(ReadabilityOptions::preset(preset)),
+        (None, Some(settings)) => Some(settings.extract_for(url).clone()),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+    use lectito::MediaRetention;
+
+    use super::*;
+    use crate::cli::{Cli, Commands};
+
+    const SETTINGS: &str = r#"
+        [fetch]
+        timeout = 10
+
+        [extract]
+        char_threshold = 200
+
+        [output]
+        format = "text"
+        frontmatter = false
+
+        [[routes]]
+        urls = ["news.example.com"]
+        extract = { char_threshold = 100, unlikely_patterns = ["promo"], media_retention = "none" }
+    "#;
+
+    fn extract_args(argv: &[&str]) -> (ExtractArgs, clap::ArgMatches) {
+        let matches = Cli::command().get_matches_from(argv);
+        let cli = Cli::from_arg_matches(&matches).unwrap();
+        (cli.extract, matches)
+    }
+
+    #[test]
+    fn settings_fill_in_flags_left_at_their_defaults() {
+        let settings = LectitoOptions::from_toml(SETTINGS).unwrap();
+        let (mut args, matches) = extract_args(&["lectito", "page.html", "--format", "json"]);
+
+        apply_extract_args(&mut args, &settings, |id| config::explicit(&matches, id)).unwrap();
+
+        assert!(matches!(args.format, OutputFormat::Json));
+        assert!(!args.frontmatter);
+        assert_eq!(args.timeout, 10);
+    }
+
+    #[test]
+    fn pretty_setting_yields_to_an_explicit_flag() {
+        let settings = LectitoOptions::from_toml("[output]\npretty = true\n").unwrap();
+        let pretty = |argv: &[&str]| {
+            let (mut args, matches) = extract_args(argv);
+            apply_extract_args(&mut args, &settings, |id| config::explicit(&matches, id)).unwrap();
+            args.pretty
+        };
+
+        assert!(pretty(&["lectito", "page.html"]));
+        assert!(pretty(&["lectito", "page.html", "--pretty"]));
+        assert!(!pretty(&["lectito", "page.html", "--pretty=false"]));
+    }
+
+    #[test]
+    fn inspect_takes_timeout_and_pretty_from_settings() {
+        let settings = LectitoOptions::from_toml("[fetch]\ntimeout = 10\n\n[output]\npretty = true\n").unwrap();
+        let inspect = |argv: &[&str]| {
+            let matches = Cli::command().get_matches_from(argv);
+            let Some(Commands::Inspect(mut args)) = Cli::from_arg_matches(&matches).unwrap().command else {
+                panic!("expected inspect");
+            };
+            let matches = matches.subcommand_matches("inspect").unwrap();
+            apply_inspect_args(&mut args, &settings, |id| config::explicit(matches, id));
+            (args.timeout, args.pretty)
+        };
+
+        assert_eq!(inspect(&["lectito", "inspect", "page.html"]), (10, true));
+        assert_eq!(
+            inspect(&["lectito", "inspect", "page.html", "--timeout", "5"]),
+            (5, true)
+        );
+    }
+
+    #[test]
+    fn routes_override_extraction_defaults_per_site() {
+        let settings = LectitoOptions::from_toml(SETTINGS).unwrap();
+
+        let base = extract_base(Some(&settings), None, Some("https://news.example.com/story")).unwrap();
+        assert_eq!(base.char_threshold, 100);
+        assert_eq!(base.unlikely_patterns, ["promo"]);
+        assert_eq!(base.media_retention, MediaRetention::None);
+
+        let base = extract_base(Some(&settings), None, Some("https://blog.example.org/post")).unwrap();
+        assert_eq!(base.char_threshold, 200);
+        assert!(base.unlikely_patterns.is_empty());
+
+        let base = extract_base(
+            Some(&settings),
+            Some(Preset::Docs),
+            Some("https://news.example.com/story"),
+        )
+        .unwrap();
+        assert_eq!(base, ReadabilityOptions::preset(Preset::Docs));
+    }
+
+    #[test]
+    fn environment_sits_between_settings_and_flags() {
+        let settings = LectitoOptions::from_toml(SETTINGS).unwrap();
+        // Writing the process environment races the tests that read it, so
+        // `timeout` is pointed at variables the test run already has: Cargo
+        // sets the package version for tests, and the other is never set.
+        let layered = |env: &'static str, argv: &[&str]| {
+            let matches = Cli::command()
+                .mut_arg("timeout", |arg| arg.env(env))
+                .get_matches_from(argv);
+            let mut args = Cli::from_arg_matches(&matches).unwrap().extract;
+            apply_extract_args(&mut args, &settings, |id| config::explicit(&matches, id)).unwrap();
+            args.timeout
+        };
+        let set = "CARGO_PKG_VERSION_PATCH";
+        let from_env: u64 = env!("CARGO_PKG_VERSION_PATCH").parse().unwrap();
+
+        assert_eq!(layered("LECTITO_TEST_UNSET_TIMEOUT", &["lectito", "page.html"]), 10);
+        assert_eq!(layered(set, &["lectito", "page.html"]), from_env);
+        assert_eq!(layered(set, &["lectito", "page.html", "--timeout", "5"]), 5);
+    }
+
+    #[test]
+    fn color_env_accepts_modes_and_booleans() {
+        assert_eq!(parse_color("always"), Some(true));
+        assert_eq!(parse_color("OFF"), Some(false));
+        assert_eq!(parse_color("0"), Some(false));
+        assert_eq!(parse_color("auto"), None);
+        assert_eq!(parse_color("sometimes"), None);
+    }
+}
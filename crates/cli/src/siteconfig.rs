@@ -0,0 +1,714 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+
+use crate::cli::{
+    SiteconfigArgs, SiteconfigCommands, SiteconfigFetchArgs, SiteconfigLintArgs, SiteconfigTestArgs,
+    SiteconfigUpdateArgs, SiteconfigVersionArgs,
+};
+use crate::{config, echo, fetch};
+
+const INDEX_FILE: &str = "index.txt";
+
+/// Synced profile names by host, so a run reads only the profiles for its page.
+const HOSTS_FILE: &str = "hosts.txt";
+/// Config subdirectory holding the profiles written by `siteconfig update`.
+const SITE_RULES_DIR: &str = "site-rules";
+/// Config subdirectory holding the git checkout `siteconfig update` converts.
+const CHECKOUT_DIR: &str = "site-config-source";
+
+/// Site profile files read from a published bundle.
+struct Bundle {
+    profiles: Vec<(String, String)>,
+    /// FiveFilters directives left out while converting, once per line.
+    skipped: Vec<String>,
+}
+
+impl Bundle {
+    fn read(source: &str, fetch_options: &fetch::ReadOptions) -> Result<Self> {
+        let index = read_file(source, INDEX_FILE, fetch_options)?;
+        let profiles = lectito::site_rules_index(&index)
+            .into_iter()
+            .map(|name| {
+                if name.contains(['/', '\\']) || name == ".." {
+                    anyhow::bail!("invalid profile name in {INDEX_FILE}: {name}");
+                }
+                Ok((name.to_string(), read_file(source, name, fetch_options)?))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { profiles, skipped: Vec::new() })
+    }
+
+    /// Convert a directory of FiveFilters `<host>.txt` site configs, skipping
+    /// files that do not convert, such as configs without a `body`. Configs in
+    /// subdirectories are keyed by path, so `example.com/blog/*.txt` applies
+    /// to `/blog/` pages.
+    fn read_ftr(dir: &Path) -> Result<Self> {
+        let mut profiles = Vec::new();
+        let mut skipped = Vec::new();
+        for (name, path) in ftr_files(dir, "")? {
+            let Some(stem) = name.strip_suffix(".txt") else {
+                continue;
+            };
+            let source = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            if let Ok(conversion) = lectito::convert_ftr_site_config(&name, &String::from_utf8_lossy(&source)) {
+                // Bundle file names are flat and portable.
+                let file_name = stem.replace('/', "_").replace('*', "wildcard");
+                profiles.push((format!("{file_name}.toml"), conversion.profile));
+                skipped.extend(conversion.skipped);
+            }
+        }
+        if profiles.is_empty() {
+            anyhow::bail!("no site configs with a body directive in {}", dir.display());
+        }
+        profiles.sort();
+        Ok(Self { profiles, skipped })
+    }
+
+    fn version(&self) -> String {
+        lectito::site_rules_version(
+            self.profiles
+                .iter()
+                .map(|(name, source)| (name.as_str(), source.as_str())),
+        )
+    }
+
+    /// Write the profiles, an index whose `header` comments describe them,
+    /// and a host index. Profiles with fingerprints are indexed under `*`.
+    fn write(&self, dir: &Path, header: &str) -> Result<()> {
+        let mut hosts = String::new();
+        for (name, source) in &self.profiles {
+            lectito::validate_site_profile(source).with_context(|| format!("profile {name} is invalid"))?;
+            match lectito::site_profile_hosts(source)? {
+                Some(profile_hosts) => {
+                    for host in profile_hosts {
+                        hosts.push_str(&format!("{host} {name}\n"));
+                    }
+                }
+                None => hosts.push_str(&format!("* {name}\n")),
+            }
+        }
+
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        for (name, source) in &self.profiles {
+            let path = dir.join(name);
+            fs::write(&path, source).with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        let hosts_path = dir.join(HOSTS_FILE);
+        fs::write(&hosts_path, hosts).with_context(|| format!("failed to write {}", hosts_path.display()))?;
+        let index: String = self.profiles.iter().map(|(name, _)| format!("{name}\n")).collect();
+        let index_path = dir.join(INDEX_FILE);
+        fs::write(&index_path, format!("{header}{index}"))
+            .with_context(|| format!("failed to write {}", index_path.display()))
+    }
+}
+
+pub fn run(args: SiteconfigArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    match args.command {
+        SiteconfigCommands::Version(args) => run_version(args, fetch_options),
+        SiteconfigCommands::Fetch(args) => run_fetch(args, fetch_options),
+        SiteconfigCommands::Update(args) => run_update(args, fetch_options),
+        SiteconfigCommands::Test(args) => run_test(args, fetch_options),
+        SiteconfigCommands::Lint(args) => run_lint(args),
+    }
+}
+
+fn run_version(args: SiteconfigVersionArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let bundled = lectito::bundled_site_rules_version();
+    let published = if args.check { Some(Bundle::read(&args.source, fetch_options)?) } else { None };
+    let published_version = published.as_ref().map(Bundle::version);
+    let stale = published_version.as_ref().map(|version| *version != bundled);
+    let synced =
+        config::config_dir().and_then(|dir| fs::read_to_string(dir.join(SITE_RULES_DIR).join(INDEX_FILE)).ok());
+    let synced_field = |field: &str| synced.as_deref().and_then(|index| index_header(index, field));
+
+    if args.json {
+        let value = serde_json::json!({
+            "lectito": env!("CARGO_PKG_VERSION"),
+            "bundled": bundled,
+            "published": published_version,
+            "source": args.check.then_some(&args.source),
+            "stale": stale,
+            "synced": synced.as_deref().map(|index| serde_json::json!({
+                "source": synced_field("source"),
+                "revision": synced_field("revision"),
+                "profiles": lectito::site_rules_index(index).len(),
+            })),
+        });
+        echo::json(&value, true)?;
+    } else {
+        println!("bundled site rules {bundled} (lectito {})", env!("CARGO_PKG_VERSION"));
+        if let Some(index) = &synced {
+            println!(
+                "synced site rules {} from {} ({} profiles)",
+                synced_field("revision").unwrap_or("unknown"),
+                synced_field("source").unwrap_or("unknown"),
+                lectito::site_rules_index(index).len()
+            );
+        }
+        if let (Some(bundle), Some(version)) = (&published, &published_version) {
+            println!("published site rules {version} ({} profiles)", bundle.profiles.len());
+        }
+        match stale {
+            Some(true) => println!(
+                "bundled rules are stale; run `lectito siteconfig fetch --output-dir <DIR>` and pass the files with --site-profile"
+            ),
+            Some(false) => println!("bundled rules are up to date"),
+            None => {}
+        }
+    }
+
+    Ok(if stale == Some(true) { ExitCode::from(1) } else { ExitCode::SUCCESS })
+}
+
+fn run_fetch(args: SiteconfigFetchArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let bundle = Bundle::read(&args.source, fetch_options)?;
+    bundle.write(&args.output_dir, "")?;
+    for (name, _) in &bundle.profiles {
+        println!("{}", args.output_dir.join(name).display());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_update(args: SiteconfigUpdateArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let dir = config::config_dir().context("no config directory; set HOME or XDG_CONFIG_HOME")?;
+    let rules_dir = dir.join(SITE_RULES_DIR);
+    let (bundle, revision) = match read_update_source(&args, &dir.join(CHECKOUT_DIR), fetch_options) {
+        Ok(update) => update,
+        Err(error) if rules_dir.join(INDEX_FILE).exists() => {
+            tracing::warn!(
+                error = format!("{error:#}"),
+                "site config update failed, keeping the rules from the last update"
+            );
+            return Ok(ExitCode::from(2));
+        }
+        Err(error) => return Err(error),
+    };
+
+    // Write beside the current rules and swap, so a failed write keeps them.
+    let staging = dir.join(format!("{SITE_RULES_DIR}.new"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).with_context(|| format!("failed to remove {}", staging.display()))?;
+    }
+    bundle.write(
+        &staging,
+        &format!("# source: {}\n# revision: {revision}\n", args.source),
+    )?;
+    swap_dir(&staging, &rules_dir)?;
+
+    println!(
+        "synced {} site profiles at {revision} into {}",
+        bundle.profiles.len(),
+        rules_dir.display()
+    );
+    if !bundle.skipped.is_empty() {
+        println!(
+            "skipped {} unsupported directives: {}",
+            bundle.skipped.len(),
+            directive_counts(&bundle.skipped)
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Replace `target` with `staging`. The old directory is renamed aside
+/// first and put back if the swap fails, so the previous rules survive.
+fn swap_dir(staging: &Path, target: &Path) -> Result<()> {
+    let previous = target.with_extension("old");
+    if previous.exists() {
+        fs::remove_dir_all(&previous).with_context(|| format!("failed to remove {}", previous.display()))?;
+    }
+    let had_previous = target.exists();
+    if had_previous {
+        fs::rename(target, &previous).with_context(|| format!("failed to move {} aside", target.display()))?;
+    }
+    if let Err(error) = fs::rename(staging, target) {
+        if had_previous {
+            let _ = fs::rename(&previous, target);
+        }
+        return Err(error).with_context(|| format!("failed to write {}", target.display()));
+    }
+    if had_previous {
+        fs::remove_dir_all(&previous).with_context(|| format!("failed to remove {}", previous.display()))?;
+    }
+    Ok(())
+}
+
+/// Directive names with how often each appears, most frequent first, such as
+/// `http_header (12), single_page_link (3)`.
+fn directive_counts(names: &[String]) -> String {
+    let mut counts = BTreeMap::<&str, usize>::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(_, left), (_, right)| right.cmp(left));
+    counts
+        .iter()
+        .map(|(name, count)| format!("{name} ({count})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn run_test(args: SiteconfigTestArgs, fetch_options: &fetch::ReadOptions) -> Result<ExitCode> {
+    let profiles = if args.profiles.is_empty() {
+        synced_profiles()?
+    } else {
+        args.profiles
+            .iter()
+            .map(|path| read_profile(path))
+            .collect::<Result<_>>()?
+    };
+
+    let options = lectito::ReadabilityOptions::default();
+    let mut results = Vec::new();
+    for profile in &profiles {
+        let name = lectito::validate_site_profile(profile)?;
+        for url in lectito::site_profile_test_urls(profile)? {
+            if args.host.as_deref().is_some_and(|host| !url_on_host(&url, host)) {
+                continue;
+            }
+            let result = match fetch::InputDocument::read_src(Some(&url), false, None, fetch_options) {
+                Ok(document) => {
+                    let page_url = document.base_url().unwrap_or(&url);
+                    let check = lectito::check_site_profile(profile, page_url, document.html(), &options)?;
+                    let mut value = serde_json::to_value(&check).context("failed to serialize check")?;
+                    value["passed"] = check.passed().into();
+                    value
+                }
+                Err(error) => serde_json::json!({
+                    "name": name,
+                    "url": url,
+                    "passed": false,
+                    "failures": [format!("fetch failed: {error:#}")],
+                }),
+            };
+            results.push(result);
+        }
+    }
+    if results.is_empty() {
+        anyhow::bail!("no test URLs in the selected site profiles");
+    }
+
+    let failed = results.iter().filter(|result| result["passed"] != true).count();
+    if args.json {
+        echo::json(&serde_json::Value::Array(results), true)?;
+    } else {
+        for result in &results {
+            let (name, url) = (
+                result["name"].as_str().unwrap_or_default(),
+                result["url"].as_str().unwrap_or_default(),
+            );
+            if result["passed"] == true {
+                println!(
+                    "pass {name} {url}: text {}, without profile {}",
+                    result["text_len"], result["baseline_text_len"]
+                );
+            } else {
+                let failures: Vec<_> = result["failures"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|failure| failure.as_str())
+                    .collect();
+                println!("FAIL {name} {url}: {}", failures.join("; "));
+            }
+        }
+        println!("{} of {} checks passed", results.len() - failed, results.len());
+    }
+
+    Ok(if failed > 0 { ExitCode::from(1) } else { ExitCode::SUCCESS })
+}
+
+fn run_lint(args: SiteconfigLintArgs) -> Result<ExitCode> {
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if path.is_dir() {
+            files.extend(toml_files(path)?);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut results = Vec::new();
+    for file in &files {
+        let source = fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))?;
+        results.push((file, lectito::lint_site_profile(&source)));
+    }
+    let lints = results.iter().flat_map(|(_, lints)| lints);
+    let errors = lints
+        .clone()
+        .filter(|lint| lint.severity == lectito::LintSeverity::Error)
+        .count();
+    let warnings = lints.count() - errors;
+
+    if args.json {
+        let results = results
+            .iter()
+            .map(|(file, lints)| serde_json::json!({ "path": file.display().to_string(), "lints": lints }))
+            .collect();
+        echo::json(&serde_json::Value::Array(results), true)?;
+    } else {
+        for (file, lints) in &results {
+            for lint in lints {
+                let location = match lint.line {
+                    Some(line) => format!("{}:{line}", file.display()),
+                    None => file.display().to_string(),
+                };
+                let severity = match lint.severity {
+                    lectito::LintSeverity::Error => "error",
+                    _ => "warning",
+                };
+                println!("{location}: {severity}: {}", lint.message);
+            }
+        }
+        println!(
+            "checked {} profiles, errors: {errors}, warnings: {warnings}",
+            results.len()
+        );
+    }
+
+    Ok(if errors > 0 { ExitCode::from(1) } else { ExitCode::SUCCESS })
+}
+
+/// The `.toml` files directly inside `dir`, sorted by name.
+fn toml_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        if path.extension().is_some_and(|extension| extension == "toml") {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("no .toml site profiles in {}", dir.display());
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Files under `dir` with their `/`-separated paths relative to it, skipping
+/// hidden directories such as `.git`.
+fn ftr_files(dir: &Path, prefix: &str) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !path.is_dir() {
+            files.push((format!("{prefix}{name}"), path));
+        } else if !name.starts_with('.') {
+            files.extend(ftr_files(&path, &format!("{prefix}{name}/"))?);
+        }
+    }
+    Ok(files)
+}
+
+/// Read a TOML profile, converting FiveFilters `.txt` site configs.
+fn read_profile(path: &Path) -> Result<String> {
+    let source = fs::read_to_string(path).with_context(|| format!("failed to read site profile {}", path.display()))?;
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name.ends_with(".txt") => {
+            lectito::site_profile_from_ftr(name, &source).with_context(|| path.display().to_string())
+        }
+        _ => Ok(source),
+    }
+}
+
+fn url_on_host(url: &str, host: &str) -> bool {
+    let host = host.trim_start_matches("www.");
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.host_str()
+                .map(|url_host| url_host.trim_start_matches("www.").to_string())
+        })
+        .is_some_and(|url_host| url_host == host || url_host.ends_with(&format!(".{host}")))
+}
+
+/// Profiles written by `lectito siteconfig update`, or none before the first update.
+pub fn synced_profiles() -> Result<Vec<String>> {
+    let Some(dir) = config::config_dir().map(|dir| dir.join(SITE_RULES_DIR)) else {
+        return Ok(Vec::new());
+    };
+    read_synced(&dir, |_| true)
+}
+
+/// Synced profiles that can apply to `url`: those indexed under its host or
+/// a parent domain, and those matched by fingerprints on any host.
+pub fn synced_profiles_for(url: Option<&str>) -> Result<Vec<String>> {
+    let Some(dir) = config::config_dir().map(|dir| dir.join(SITE_RULES_DIR)) else {
+        return Ok(Vec::new());
+    };
+    let host = url
+        .and_then(|url| Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(|host| host.trim_start_matches("www.").to_string()));
+    profiles_for_host(&dir, host.as_deref())
+}
+
+fn profiles_for_host(dir: &Path, host: Option<&str>) -> Result<Vec<String>> {
+    // Rules synced before the host index existed are read in full.
+    let Ok(hosts) = fs::read_to_string(dir.join(HOSTS_FILE)) else {
+        return read_synced(dir, |_| true);
+    };
+    let mut keys = vec!["*"];
+    let mut rest = host.unwrap_or_default();
+    while !rest.is_empty() {
+        keys.push(rest);
+        rest = rest.split_once('.').map_or("", |(_, parent)| parent);
+    }
+    let names: BTreeSet<&str> = hosts
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(key, _)| keys.contains(key))
+        .map(|(_, name)| name)
+        .collect();
+    read_synced(dir, |name| names.contains(name))
+}
+
+/// Profiles listed in the synced index that `wanted` accepts, in index order.
+fn read_synced(dir: &Path, wanted: impl Fn(&str) -> bool) -> Result<Vec<String>> {
+    let Ok(index) = fs::read_to_string(dir.join(INDEX_FILE)) else {
+        return Ok(Vec::new());
+    };
+    lectito::site_rules_index(&index)
+        .into_iter()
+        .filter(|name| wanted(name))
+        .map(|name| {
+            let path = dir.join(name);
+            fs::read_to_string(&path).with_context(|| format!("failed to read synced site profile {}", path.display()))
+        })
+        .collect()
+}
+
+/// Read the profiles for `siteconfig update` and the revision they came from.
+fn read_update_source(
+    args: &SiteconfigUpdateArgs, checkout: &Path, fetch_options: &fetch::ReadOptions,
+) -> Result<(Bundle, String)> {
+    let source = args.source.as_str();
+    if is_git_source(source) {
+        let revision = git_checkout(source, args.rev.as_deref(), checkout)?;
+        return Ok((Bundle::read_ftr(checkout)?, revision));
+    }
+    if args.rev.is_some() {
+        anyhow::bail!("--rev needs a git source, not {source}");
+    }
+
+    let local = Path::new(source);
+    let bundle = if local.is_dir() && !local.join(INDEX_FILE).exists() {
+        Bundle::read_ftr(local)?
+    } else {
+        Bundle::read(source, fetch_options)?
+    };
+    let version = bundle.version();
+    Ok((bundle, version))
+}
+
+fn is_git_source(source: &str) -> bool {
+    source.ends_with(".git")
+        || ["git@", "git://", "ssh://"]
+            .iter()
+            .any(|prefix| source.starts_with(prefix))
+        || Path::new(source).join(".git").exists()
+}
+
+/// Shallow-fetch `rev` of the repository at `source` into `checkout` and
+/// return the commit checked out.
+fn git_checkout(source: &str, rev: Option<&str>, checkout: &Path) -> Result<String> {
+    if !checkout.join(".git").exists() {
+        fs::create_dir_all(checkout).with_context(|| format!("failed to create {}", checkout.display()))?;
+        git(checkout, &["init", "--quiet"])?;
+    }
+    git(
+        checkout,
+        &["fetch", "--quiet", "--depth", "1", source, rev.unwrap_or("HEAD")],
+    )?;
+    git(checkout, &["checkout", "--quiet", "--force", "FETCH_HEAD"])?;
+    Ok(git(checkout, &["rev-parse", "HEAD"])?.trim().to_string())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Value of a `# field: value` comment in a bundle index.
+fn index_header<'a>(index: &'a str, field: &str) -> Option<&'a str> {
+    index.lines().find_map(|line| {
+        line.strip_prefix('#')?
+            .trim()
+            .strip_prefix(field)?
+            .strip_prefix(':')
+            .map(str::trim)
+    })
+}
+
+/// Read `name` from a bundle URL or local directory.
+fn read_file(source: &str, name: &str, fetch_options: &fetch::ReadOptions) -> Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let base = format!("{}/", source.trim_end_matches('/'));
+        let url = Url::parse(&base)
+            .and_then(|base| base.join(name))
+            .with_context(|| format!("invalid site config source {source}"))?;
+        let document = fetch::InputDocument::read_src(Some(url.as_str()), false, None, fetch_options)?;
+        return Ok(document.html().to_string());
+    }
+
+    let path = Path::new(source).join(name);
+    fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Bundle, directive_counts, index_header, profiles_for_host, swap_dir, url_on_host};
+    use crate::fetch;
+
+    #[test]
+    fn reads_bundle_from_directory_index() {
+        let dir = std::env::temp_dir().join(format!("lectito-siteconfig-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let profile = "name = \"example\"\nhosts = [\"example.com\"]\ncontent_roots = [\"//article\"]\n";
+        fs::write(dir.join("index.txt"), "# bundled profiles\nexample.com.toml\n").unwrap();
+        fs::write(dir.join("example.com.toml"), profile).unwrap();
+
+        let bundle = Bundle::read(dir.to_str().unwrap(), &fetch::ReadOptions::default()).unwrap();
+        assert_eq!(bundle.profiles.len(), 1);
+        assert_eq!(
+            bundle.version(),
+            lectito::site_rules_version([("example.com.toml", profile)])
+        );
+
+        fs::write(dir.join("index.txt"), "../secrets.toml\n").unwrap();
+        assert!(Bundle::read(dir.to_str().unwrap(), &fetch::ReadOptions::default()).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn converts_fivefilters_directory_into_bundle() {
+        let dir = std::env::temp_dir().join(format!("lectito-siteconfig-ftr-test-{}", std::process::id()));
+        let output = dir.join("site-rules");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("example.com.txt"),
+            "body: //article\nstrip: //aside\nhttp_header(user-agent): x\ntidy: no\nhttp_header(cookie): y\n",
+        )
+        .unwrap();
+        fs::write(dir.join("strip-only.org.txt"), "strip: //aside\n").unwrap();
+        fs::write(dir.join("README.md"), "# site configs\n").unwrap();
+
+        let bundle = Bundle::read_ftr(&dir).unwrap();
+        assert_eq!(bundle.profiles.len(), 1);
+        assert_eq!(bundle.profiles[0].0, "example.com.toml");
+        assert_eq!(directive_counts(&bundle.skipped), "http_header (2), tidy (1)");
+
+        bundle.write(&output, "# source: test\n# revision: abc123\n").unwrap();
+        let index = fs::read_to_string(output.join("index.txt")).unwrap();
+        assert_eq!(index_header(&index, "revision"), Some("abc123"));
+        assert_eq!(lectito::site_rules_index(&index), ["example.com.toml"]);
+        assert_eq!(
+            Bundle::read(output.to_str().unwrap(), &fetch::ReadOptions::default())
+                .unwrap()
+                .version(),
+            bundle.version()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn converts_path_keyed_fivefilters_configs() {
+        let dir = std::env::temp_dir().join(format!("lectito-siteconfig-path-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("example.com/blog")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("example.com/blog/*.txt"), "body: //main\n").unwrap();
+        fs::write(dir.join("*.substack.com.txt"), "body: //article\n").unwrap();
+        fs::write(dir.join(".git/config.txt"), "body: //article\n").unwrap();
+
+        let bundle = Bundle::read_ftr(&dir).unwrap();
+        let names: Vec<_> = bundle.profiles.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["example.com_blog_wildcard.toml", "wildcard.substack.com.toml"]);
+        assert!(bundle.profiles[0].1.contains("path_prefixes = [\"/blog/*\"]"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn loads_synced_profiles_by_host() {
+        let dir = std::env::temp_dir().join(format!("lectito-siteconfig-hosts-test-{}", std::process::id()));
+        let profile = |hosts: &str| format!("{hosts}\ncontent_roots = [\"//article\"]\n");
+        let bundle = Bundle {
+            profiles: vec![
+                ("example.com.toml".to_string(), profile("hosts = [\"www.example.com\"]")),
+                (
+                    "ghost.toml".to_string(),
+                    profile("fingerprints = [\"meta[name=generator]\"]"),
+                ),
+                ("substack.toml".to_string(), profile("hosts = [\"*.substack.com\"]")),
+            ],
+            skipped: Vec::new(),
+        };
+        bundle.write(&dir, "").unwrap();
+        let loaded = |host| profiles_for_host(&dir, host).unwrap().len();
+
+        assert_eq!(loaded(Some("blog.example.com")), 2);
+        assert_eq!(loaded(Some("tides.substack.com")), 2);
+        assert_eq!(loaded(Some("example.org")), 1);
+        assert_eq!(loaded(None), 1);
+        fs::remove_file(dir.join("hosts.txt")).unwrap();
+        assert_eq!(loaded(Some("example.org")), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_previous_rules_when_the_swap_fails() {
+        let dir = std::env::temp_dir().join(format!("lectito-siteconfig-swap-test-{}", std::process::id()));
+        let (staging, rules) = (dir.join("site-rules.new"), dir.join("site-rules"));
+        fs::create_dir_all(&rules).unwrap();
+        fs::write(rules.join("index.txt"), "old\n").unwrap();
+
+        assert!(swap_dir(&staging, &rules).is_err());
+        assert_eq!(fs::read_to_string(rules.join("index.txt")).unwrap(), "old\n");
+
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join("index.txt"), "new\n").unwrap();
+        swap_dir(&staging, &rules).unwrap();
+        assert_eq!(fs::read_to_string(rules.join("index.txt")).unwrap(), "new\n");
+        assert!(!staging.exists() && !dir.join("site-rules.old").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn filters_test_urls_by_host() {
+        assert!(url_on_host("https://www.nytimes.com/2024/story.html", "nytimes.com"));
+        assert!(url_on_host("https://cooking.nytimes.com/recipe", "www.nytimes.com"));
+        assert!(!url_on_host("https://notnytimes.com/story", "nytimes.com"));
+        assert!(!url_on_host("not a url", "nytimes.com"));
+    }
+}
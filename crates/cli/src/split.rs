@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lectito::{Article, escape_html};
+
+/// A neighboring section and the file it is written to.
+type Neighbor<'a> = (&'a Article, &'a Path);
+
+/// Write each section of `article`, cut at headings of `level`, to its own
+/// file named from `output`, and return the paths in order.
+///
+/// Every section gets links to the sections before and after it. `render`
+/// turns a section into file contents, so sections share the frontmatter and
+/// format of a whole-article run.
+pub fn write_sections(
+    article: &Article, level: u8, output: &Path, render: impl Fn(&Article) -> Result<String>,
+) -> Result<Vec<PathBuf>> {
+    let sections = article.split_at(level);
+    let paths = section_paths(output, sections.len());
+    for (index, section) in sections.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| (&sections[previous], paths[previous].as_path()));
+        let next = sections.get(index + 1).zip(paths.get(index + 1).map(PathBuf::as_path));
+        let mut section = section.clone();
+        add_navigation(&mut section, previous, next);
+        let rendered = render(&section)?;
+        fs::write(&paths[index], rendered).with_context(|| format!("failed to write {}", paths[index].display()))?;
+    }
+    Ok(paths)
+}
+
+/// `article.md` becomes `article-1.md`, `article-2.md`, and so on, padded so
+/// the names sort in order. A single section keeps the output path.
+fn section_paths(output: &Path, count: usize) -> Vec<PathBuf> {
+    if count == 1 {
+        return vec![output.to_path_buf()];
+    }
+    let stem = output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "article".to_string());
+    let extension = output
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let digits = count.to_string().len();
+    (1..=count)
+        .map(|number| output.with_file_name(format!("{stem}-{number:0digits$}{extension}")))
+        .collect()
+}
+
+/// Append previous and next links to the Markdown, HTML, and text of a section.
+fn add_navigation(section: &mut Article, previous: Option<Neighbor>, next: Option<Neighbor>) {
+    let links: Vec<(String, String)> = [("Previous", previous), ("Next", next)]
+        .into_iter()
+        .filter_map(|(label, neighbor)| {
+            let (article, path) = neighbor?;
+            let file = path.file_name()?.to_string_lossy().into_owned();
+            let title = article.title.as_deref().unwrap_or("Untitled");
+            Some((format!("{label}: {title}"), file))
+        })
+        .collect();
+    if links.is_empty() {
+        return;
+    }
+
+    let markdown: Vec<String> = links
+        .iter()
+        .map(|(label, file)| {
+            let label = label.replace('[', "\\[").replace(']', "\\]");
+            match file.contains(' ') {
+                true => format!("[{label}](<{file}>)"),
+                false => format!("[{label}]({file})"),
+            }
+        })
+        .collect();
+    section.markdown = format!("{}\n\n---\n\n{}", section.markdown.trim_end(), markdown.join(" · "));
+
+    let html: Vec<String> = links
+        .iter()
+        .map(|(label, file)| format!(r#"<a href="{}">{}</a>"#, escape_html(file), escape_html(label)))
+        .collect();
+    let nav = format!("<nav>{}</nav>", html.join(" · "));
+    match section.content.strip_suffix("</div>") {
+        Some(content) => section.content = format!("{content}{nav}</div>"),
+        None => section.content.push_str(&nav),
+    }
+
+    let text: Vec<String> = links.iter().map(|(label, file)| format!("{label} ({file})")).collect();
+    section.text_content = format!("{}\n\n{}", section.text_content.trim_end(), text.join("\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_section_files_and_links_neighbors() {
+        let paths = section_paths(Path::new("out/tides.md"), 12);
+        assert_eq!(paths[0], Path::new("out/tides-01.md"));
+        assert_eq!(paths[11], Path::new("out/tides-12.md"));
+        assert_eq!(section_paths(Path::new("tides.md"), 1), [PathBuf::from("tides.md")]);
+
+        let neighbor = Article { title: Some("Spring [tides]".to_string()), ..section("") };
+        let mut middle = section("Middle.");
+        add_navigation(
+            &mut middle,
+            Some((&neighbor, Path::new("out/tides-01.md"))),
+            Some((&neighbor, Path::new("out/tides 03.md"))),
+        );
+
+        assert_eq!(
+            middle.markdown,
+            "Middle.\n\n---\n\n[Previous: Spring \\[tides\\]](tides-01.md) · [Next: Spring \\[tides\\]](<tides 03.md>)"
+        );
+        assert!(
+            middle
+                .content
+                .ends_with(r#"<nav><a href="tides-01.md">Previous: Spring [tides]</a> · <a href="tides 03.md">Next: Spring [tides]</a></nav></div>"#)
+        );
+        assert!(
+            middle
+                .text_content
+                .ends_with("Previous: Spring [tides] (tides-01.md)\nNext: Spring [tides] (tides 03.md)")
+        );
+    }
+
+    fn section(text: &str) -> Article {
+        Article {
+            content: format!(r#"<div id="readability-page-1" class="page"><p>{text}</p></div>"#),
+            markdown: text.to_string(),
+            text_content: text.to_string(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use lectito::Article;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde_json::json;
+
+use crate::echo;
+
+/// Words read per minute for the `reading_minutes` estimate.
+const WORDS_PER_MINUTE: usize = 230;
+
+/// Article rendered through a user-supplied Tera template.
+///
+/// The template sees the article JSON fields at the top level, including
+/// `content` (HTML), `markdown`, and `text_content`, plus `source`,
+/// `references` (the article's links), and `stats`.
+pub fn render(path: &Path, article: &Article, source: Option<&str>, keyphrases: Option<usize>) -> Result<String> {
+    let template =
+        std::fs::read_to_string(path).with_context(|| format!("failed to read template {}", path.display()))?;
+    let name = path.display().to_string();
+    let mut tera = tera::Tera::default();
+    tera.add_raw_template(&name, &template)
+        .with_context(|| format!("failed to parse template {name}"))?;
+    let context =
+        tera::Context::from_value(context(article, source, keyphrases)?).context("failed to build template context")?;
+    tera.render(&name, &context)
+        .with_context(|| format!("failed to render template {name}"))
+}
+
+fn context(article: &Article, source: Option<&str>, keyphrases: Option<usize>) -> Result<serde_json::Value> {
+    let mut value = echo::article_json(Some(article), keyphrases)?;
+    let words = article.text_content.split_whitespace().count();
+    if let Some(object) = value.as_object_mut() {
+        object.insert("source".to_string(), json!(source));
+        object.insert("references".to_string(), json!(references(article, source)));
+        object.insert(
+            "stats".to_string(),
+            json!({
+                "words": words,
+                "characters": article.length,
+                "reading_minutes": words.div_ceil(WORDS_PER_MINUTE),
+            }),
+        );
+    }
+    Ok(value)
+}
+
+/// Links in the article body, resolved against the source URL, in order and
+/// without repeats. Same-page fragments are skipped.
+fn references(article: &Article, source: Option<&str>) -> Vec<serde_json::Value> {
+    let base = source.and_then(|source| Url::parse(source).ok());
+    let document = Html::parse_fragment(&article.content);
+    let selector = Selector::parse("a[href]").expect("link selector should parse");
+    let mut seen = Vec::new();
+    let mut references = Vec::new();
+    for link in document.select(&selector) {
+        let href = link.value().attr("href").unwrap_or_default().trim();
+        if href.is_empty() || href.starts_with('#') {
+            continue;
+        }
+        let url = match &base {
+            Some(base) => base.join(href).map(String::from).unwrap_or_else(|_| href.to_string()),
+            None => href.to_string(),
+        };
+        if seen.contains(&url) {
+            continue;
+        }
+        let text = link.text().collect::<Vec<_>>().join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        references.push(json!({ "text": text, "url": url }));
+        seen.push(url);
+    }
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_template_with_article_context() {
+        let article: Article = serde_json::from_value(json!({
+            "title": "On Tides",
+            "raw_title": null,
+            "byline": "Jane Roe",
+            "dir": null,
+            "lang": "en",
+            "content": r##"<p>The <a href="/moon">moon</a> pulls the <a href="#sea">sea</a>. <a href="/moon">Again</a>.</p>"##,
+            "markdown": "The [moon](/moon) pulls the sea.",
+            "text_content": "The moon pulls the sea. Again.",
+            "length": 30,
+            "excerpt": null,
+            "site_name": null,
+            "published_time": null,
+            "modified_time": null,
+            "published": null,
+            "modified": null,
+            "image": null,
+            "domain": null,
+            "favicon": null,
+            "canonical_url": null,
+            "amp_url": null,
+            "keywords": [],
+            "section": null,
+            "is_paywalled": null
+        }))
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("lectito-template-{}.tera", std::process::id()));
+        std::fs::write(
+            &path,
+            "{{ title }} by {{ byline }} <{{ source }}>\n\
+             {{ stats.words }} words, {{ stats.reading_minutes }} min\n\
+             {{ markdown }}\n\
+             {% for ref in references %}- {{ ref.url }}{% endfor %}",
+        )
+        .unwrap();
+
+        let output = render(&path, &article, Some("https://example.com/posts/tides"), None);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            output.unwrap(),
+            "On Tides by Jane Roe <https://example.com/posts/tides>\n\
+             6 words, 1 min\n\
+             The [moon](/moon) pulls the sea.\n\
+             - https://example.com/moon"
+        );
+    }
+}
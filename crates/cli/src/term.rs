@@ -0,0 +1,729 @@
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use lectito::Article;
+use owo_colors::{OwoColorize, Style as Ansi};
+use reqwest::Url;
+use scraper::{ElementRef, Html};
+use unicode_width::UnicodeWidthStr;
+
+use crate::html::{self, Walker, collapse};
+
+/// Line width used when `COLUMNS` is unset.
+const DEFAULT_WIDTH: usize = 80;
+
+/// Longest line the reader wraps to, however wide the terminal is.
+pub const MAX_WIDTH: usize = 100;
+
+/// Narrowest width the reader wraps to.
+const MIN_WIDTH: usize = 20;
+
+/// Keywords highlighted in code blocks, shared across common languages.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "do",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "null",
+    "package",
+    "pub",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "true",
+    "True",
+    "False",
+    "try",
+    "type",
+    "use",
+    "var",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+/// A run of text in one style.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    fn new(text: &str, style: Style) -> Self {
+        Self { text: text.to_string(), style }
+    }
+}
+
+/// One output line as styled runs.
+pub type Line = Vec<Span>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub dim: bool,
+    pub code: bool,
+    /// Syntax class of text in a code block.
+    pub token: Option<Token>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+/// A heading of the laid-out article and the line it starts on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heading {
+    pub level: u8,
+    pub title: String,
+    pub line: usize,
+}
+
+/// An article wrapped into styled lines.
+pub struct Layout {
+    pub lines: Vec<Line>,
+    /// The title and top-level headings, in order.
+    pub headings: Vec<Heading>,
+}
+
+/// Article styled for reading in a terminal.
+///
+/// Paragraphs wrap to `width` columns, lists hang their items, and block
+/// quotes get a rule down their left side. With `color`, headings and
+/// emphasis use ANSI styles, link URLs follow their text dimmed, and code
+/// blocks get keyword, string, number, and comment highlighting.
+pub fn render(article: &Article, source: Option<&str>, width: usize, color: bool) -> String {
+    layout(article, source, width)
+        .lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|span| paint(&span.text, span.style, color))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Article wrapped to `width` columns, as `render` prints it but unpainted.
+pub fn layout(article: &Article, source: Option<&str>, width: usize) -> Layout {
+    let base = source.and_then(|source| Url::parse(source).ok());
+    let mut writer = Writer::new(base.as_ref(), width.max(MIN_WIDTH));
+    if let Some(title) = article.title.as_deref().map(collapse).filter(|title| !title.is_empty()) {
+        let style = Style { bold: true, underline: true, ..Style::default() };
+        let mut block = writer.wrap(&[Span::new(&title, style)]);
+        let details: Vec<&str> = [article.byline.as_deref(), article.site_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|detail| !detail.is_empty())
+            .collect();
+        if !details.is_empty() {
+            block.extend(writer.wrap(&[Span::new(&details.join(" · "), DIM)]));
+        }
+        writer.headings.push((0, 1, title.clone()));
+        writer.blocks.push(block);
+        writer.title = Some(title);
+    }
+    let document = Html::parse_fragment(&article.content);
+    writer.children(document.root_element());
+
+    let mut lines = Vec::new();
+    let mut starts = Vec::new();
+    for block in writer.blocks {
+        if !lines.is_empty() {
+            lines.push(Line::new());
+        }
+        starts.push(lines.len());
+        lines.extend(block);
+    }
+    let headings = writer
+        .headings
+        .into_iter()
+        .map(|(block, level, title)| Heading { level, title, line: starts[block] })
+        .collect();
+    Layout { lines, headings }
+}
+
+/// Width to wrap to: the terminal's width when stdout is a terminal, then
+/// `COLUMNS`, kept between 20 and 100 columns.
+pub fn width() -> usize {
+    let terminal = match std::io::stdout().is_terminal() {
+        true => ratatui::crossterm::terminal::size()
+            .ok()
+            .map(|(columns, _)| usize::from(columns)),
+        false => None,
+    };
+    terminal
+        .filter(|columns| *columns > 0)
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|columns| columns.trim().parse().ok())
+        })
+        .unwrap_or(DEFAULT_WIDTH)
+        .clamp(MIN_WIDTH, MAX_WIDTH)
+}
+
+/// Print through `$PAGER` (`less` by default) when stdout is a terminal, and
+/// straight to stdout otherwise or when the pager cannot start.
+pub fn page(output: &str) -> Result<()> {
+    if std::io::stdout().is_terminal() {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| "less".to_string());
+        let mut parts = pager.split_whitespace();
+        if let Some(program) = parts.next() {
+            let mut command = Command::new(program);
+            command.args(parts).stdin(Stdio::piped());
+            // Like git, let less pass colors through and exit on short output.
+            if std::env::var_os("LESS").is_none() {
+                command.env("LESS", "FRX");
+            }
+            if let Ok(mut child) = command.spawn() {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Quitting the pager early closes the pipe, which is fine.
+                    let _ = writeln!(stdin, "{output}");
+                }
+                child.wait().context("failed to wait for pager")?;
+                return Ok(());
+            }
+        }
+    }
+    println!("{output}");
+    Ok(())
+}
+
+/// Dimmed text for secondary details such as link URLs and rules.
+const DIM: Style = Style { bold: false, italic: false, underline: false, dim: true, code: false, token: None };
+
+struct Writer<'a> {
+    base: Option<&'a Url>,
+    width: usize,
+    blocks: Vec<Vec<Line>>,
+    /// Block index, level, and text of each heading.
+    headings: Vec<(usize, u8, String)>,
+    /// The title heading, until the article's own copy of it is skipped.
+    title: Option<String>,
+}
+
+/// Styled runs of a paragraph, and the style for the text added next.
+#[derive(Default)]
+struct Styled {
+    spans: Vec<Span>,
+    style: Style,
+}
+
+enum Word {
+    Text(Vec<(String, Style)>),
+    Break,
+}
+
+impl<'a> Writer<'a> {
+    fn new(base: Option<&'a Url>, width: usize) -> Self {
+        Writer { base, width, blocks: Vec::new(), headings: Vec::new(), title: None }
+    }
+
+    fn nested(&self, width: usize) -> Self {
+        Writer::new(self.base, width.max(MIN_WIDTH / 2))
+    }
+
+    /// List items with their marker on the first line and the rest hanging
+    /// under the item text.
+    fn list(&self, list: ElementRef<'_>) -> Vec<Line> {
+        let ordered = list.value().name() == "ol";
+        let start = list
+            .value()
+            .attr("start")
+            .and_then(|start| start.parse::<usize>().ok())
+            .unwrap_or(1);
+        let items: Vec<_> = list
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|item| item.value().name() == "li")
+            .collect();
+        let marker_width = match ordered {
+            true => format!("{}. ", start + items.len().saturating_sub(1)).width(),
+            false => 2,
+        };
+
+        let mut lines = Vec::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let mut content = self.nested(self.width.saturating_sub(marker_width));
+            content.children(item);
+            let marker = match ordered {
+                true => format!("{:<marker_width$}", format!("{}.", start + index)),
+                false => "• ".to_string(),
+            };
+            for (line_index, line) in content.blocks.concat().into_iter().enumerate() {
+                match line_index {
+                    0 => lines.push(prefixed(Span::new(&marker, Style::default()), line)),
+                    _ if line.is_empty() => lines.push(line),
+                    _ => lines.push(prefixed(Span::new(&" ".repeat(marker_width), Style::default()), line)),
+                }
+            }
+        }
+        lines
+    }
+
+    fn table(&mut self, table: ElementRef<'_>) {
+        let mut lines = Vec::new();
+        for row in table
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|element| element.value().name() == "tr")
+        {
+            let cells: Vec<Span> = row
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .map(|cell| {
+                    let style = Style { bold: cell.value().name() == "th", ..Style::default() };
+                    let spans = self.styled(cell, style);
+                    Span::new(
+                        &collapse(&spans.iter().map(|span| span.text.as_str()).collect::<String>()),
+                        style,
+                    )
+                })
+                .collect();
+            if cells.iter().any(|cell| !cell.text.is_empty()) {
+                let mut line = Line::new();
+                for (index, cell) in cells.into_iter().enumerate() {
+                    if index > 0 {
+                        line.push(Span::new(" │ ", DIM));
+                    }
+                    line.push(cell);
+                }
+                lines.push(line);
+            }
+        }
+        self.push(lines);
+    }
+
+    /// Children of `element` as runs in `style` and the styles nested in it.
+    fn styled(&self, element: ElementRef<'_>, style: Style) -> Vec<Span> {
+        let mut styled = Styled { spans: Vec::new(), style };
+        self.inlines(element, &mut styled);
+        styled.spans
+    }
+
+    /// Lines of styled text no wider than the writer. Words longer than a
+    /// line get a line of their own.
+    fn wrap(&self, spans: &[Span]) -> Vec<Line> {
+        let mut words = Vec::new();
+        let mut word = Vec::new();
+        for span in spans {
+            for (index, piece) in span.text.split('\n').enumerate() {
+                if index > 0 {
+                    end_word(&mut word, &mut words);
+                    words.push(Word::Break);
+                }
+                let mut fragment = String::new();
+                for c in piece.chars() {
+                    if c.is_whitespace() {
+                        if !fragment.is_empty() {
+                            word.push((std::mem::take(&mut fragment), span.style));
+                        }
+                        end_word(&mut word, &mut words);
+                    } else {
+                        fragment.push(c);
+                    }
+                }
+                if !fragment.is_empty() {
+                    word.push((fragment, span.style));
+                }
+            }
+        }
+        end_word(&mut word, &mut words);
+
+        let mut lines = Vec::new();
+        let mut line = Line::new();
+        let mut line_width = 0;
+        for word in words {
+            match word {
+                Word::Break => {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                Word::Text(pieces) => {
+                    let width: usize = pieces.iter().map(|(text, _)| text.width()).sum();
+                    if line_width > 0 && line_width + 1 + width > self.width {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0;
+                    }
+                    if line_width > 0 {
+                        // Keep underlines and other runs unbroken between
+                        // words of the same style.
+                        let style = match (line.last(), pieces.first()) {
+                            (Some(last), Some((_, next))) if last.style == *next => *next,
+                            _ => Style::default(),
+                        };
+                        push_span(&mut line, " ", style);
+                        line_width += 1;
+                    }
+                    for (text, style) in pieces {
+                        push_span(&mut line, &text, style);
+                    }
+                    line_width += width;
+                }
+            }
+        }
+        lines.push(line);
+        while lines.first().is_some_and(Vec::is_empty) {
+            lines.remove(0);
+        }
+        while lines.last().is_some_and(Vec::is_empty) {
+            lines.pop();
+        }
+        lines
+    }
+
+    fn push(&mut self, lines: Vec<Line>) {
+        if !lines.is_empty() {
+            self.blocks.push(lines);
+        }
+    }
+}
+
+impl Walker for Writer<'_> {
+    type Inline = Styled;
+
+    fn text(&self, text: &str, styled: &mut Styled) {
+        styled.spans.push(Span::new(text, styled.style));
+    }
+
+    fn block(&mut self, element: ElementRef<'_>) {
+        match element.value().name() {
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let style = Style { bold: true, underline: matches!(name, "h1" | "h2"), ..Style::default() };
+                let spans = self.styled(element, style);
+                let text = collapse(&spans.iter().map(|span| span.text.as_str()).collect::<String>());
+                if text.is_empty() {
+                    return;
+                }
+                if self.title.take().is_some_and(|title| title == text) && self.blocks.len() == 1 {
+                    return;
+                }
+                let lines = self.wrap(&spans);
+                let level = name[1..].parse().unwrap_or(6);
+                self.headings.push((self.blocks.len(), level, text));
+                self.push(lines);
+            }
+            "ul" | "ol" => {
+                let lines = self.list(element);
+                self.push(lines);
+            }
+            "pre" => {
+                let language = element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .flat_map(|element| element.value().classes())
+                    .find_map(|class| class.strip_prefix("language-"))
+                    .unwrap_or_default();
+                let text = element.text().collect::<String>();
+                let lines = text
+                    .trim_end()
+                    .lines()
+                    .map(|line| {
+                        let mut spans = vec![Span::new("    ", Style::default())];
+                        spans.extend(highlight(line, language));
+                        spans
+                    })
+                    .collect();
+                self.push(lines);
+            }
+            "table" => self.table(element),
+            "blockquote" => {
+                let mut quote = self.nested(self.width.saturating_sub(2));
+                quote.children(element);
+                let lines = quote
+                    .blocks
+                    .join(&Line::new())
+                    .into_iter()
+                    .map(|line| match line.is_empty() {
+                        true => vec![Span::new("│", DIM)],
+                        false => prefixed(Span::new("│ ", DIM), line),
+                    })
+                    .collect();
+                self.push(lines);
+            }
+            "figcaption" => {
+                let spans = self.styled(element, Style { italic: true, ..DIM });
+                let lines = self.wrap(&spans);
+                self.push(lines);
+            }
+            "hr" => {
+                let rule = "─".repeat(self.width.min(40));
+                self.push(vec![vec![Span::new(&rule, DIM)]]);
+            }
+            _ => self.children(element),
+        }
+    }
+
+    fn inline(&self, element: ElementRef<'_>, styled: &mut Styled) {
+        let style = styled.style;
+        match element.value().name() {
+            "br" => styled.spans.push(Span::new("\n", style)),
+            "img" => {
+                let alt = collapse(element.value().attr("alt").unwrap_or_default());
+                let label = match alt.is_empty() {
+                    true => "[image]".to_string(),
+                    false => format!("[image: {alt}]"),
+                };
+                styled.spans.push(Span::new(&label, Style { dim: true, ..style }));
+            }
+            name => {
+                styled.style = match name {
+                    "strong" | "b" => Style { bold: true, ..style },
+                    "em" | "i" | "cite" => Style { italic: true, ..style },
+                    "code" | "kbd" | "samp" | "tt" => Style { code: true, ..style },
+                    "u" | "ins" => Style { underline: true, ..style },
+                    _ => style,
+                };
+                let start = styled.spans.len();
+                self.inlines(element, styled);
+                styled.style = style;
+                let label = collapse(
+                    &styled.spans[start..]
+                        .iter()
+                        .map(|span| span.text.as_str())
+                        .collect::<String>(),
+                );
+                if name == "a"
+                    && let Some(url) = element
+                        .value()
+                        .attr("href")
+                        .and_then(|href| html::resolve(self.base, href))
+                    && url != label
+                {
+                    styled.spans.push(Span::new(&format!(" ({url})"), DIM));
+                }
+            }
+        }
+    }
+
+    fn paragraph(&mut self, styled: Styled) {
+        let lines = self.wrap(&styled.spans);
+        self.push(lines);
+    }
+}
+
+fn end_word(word: &mut Vec<(String, Style)>, words: &mut Vec<Word>) {
+    if !word.is_empty() {
+        words.push(Word::Text(std::mem::take(word)));
+    }
+}
+
+/// Add text to a line, merging it into the last run when the style matches.
+fn push_span(line: &mut Line, text: &str, style: Style) {
+    match line.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => line.push(Span::new(text, style)),
+    }
+}
+
+fn prefixed(prefix: Span, line: Line) -> Line {
+    let mut prefixed = vec![prefix];
+    prefixed.extend(line);
+    prefixed
+}
+
+fn paint(text: &str, style: Style, color: bool) -> String {
+    if !color || style == Style::default() {
+        return text.to_string();
+    }
+    let mut ansi = Ansi::new();
+    if style.bold {
+        ansi = ansi.bold();
+    }
+    if style.italic {
+        ansi = ansi.italic();
+    }
+    if style.underline {
+        ansi = ansi.underline();
+    }
+    if style.dim {
+        ansi = ansi.dimmed();
+    }
+    ansi = match style.token {
+        Some(Token::Keyword) => ansi.magenta().bold(),
+        Some(Token::String) => ansi.green(),
+        Some(Token::Number) => ansi.yellow(),
+        Some(Token::Comment) => ansi.dimmed(),
+        None if style.code => ansi.cyan(),
+        None => ansi,
+    };
+    text.style(ansi).to_string()
+}
+
+/// A line of code split into keyword, string, number, and comment runs.
+///
+/// The highlighting is lexical and line by line, so it suits most languages
+/// without knowing any of them well.
+fn highlight(line: &str, language: &str) -> Line {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Line::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        let after_space = index == 0 || chars[index - 1].is_whitespace();
+        let (end, token) =
+            if (c == '/' && next == Some('/')) || (c == '#' && after_space && language != "c" && language != "cpp") {
+                (chars.len(), Some(Token::Comment))
+            } else if let Some(end) = matches!(c, '"' | '\'' | '`')
+                .then(|| closing_quote(&chars, index, language))
+                .flatten()
+            {
+                (end + 1, Some(Token::String))
+            } else if c.is_ascii_digit() && (index == 0 || !is_identifier(chars[index - 1])) {
+                let end = chars[index..]
+                    .iter()
+                    .position(|&c| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+                    .map_or(chars.len(), |offset| index + offset);
+                (end, Some(Token::Number))
+            } else if c.is_alphabetic() || c == '_' {
+                let end = chars[index..]
+                    .iter()
+                    .position(|&c| !is_identifier(c))
+                    .map_or(chars.len(), |offset| index + offset);
+                let word: String = chars[index..end].iter().collect();
+                (end, KEYWORDS.contains(&word.as_str()).then_some(Token::Keyword))
+            } else {
+                (index + 1, None)
+            };
+        let text: String = chars[index..end].iter().collect();
+        push_span(&mut spans, &text, Style { token, ..Style::default() });
+        index = end;
+    }
+    spans
+}
+
+/// Index of the quote closing the string that opens at `start`. In Rust a
+/// single quote is a character literal only when it closes right away, since
+/// lifetimes never close.
+fn closing_quote(chars: &[char], start: usize, language: &str) -> Option<usize> {
+    let quote = chars[start];
+    let mut index = start + 1;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 2,
+            c if c == quote => {
+                return match quote == '\'' && language == "rust" && index - start > 3 {
+                    true => None,
+                    false => Some(index),
+                };
+            }
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::article;
+
+    #[test]
+    fn wraps_blocks_to_the_terminal_width() {
+        let article = article(
+            r##"<h1>On Tides</h1>
+            <p>The moon <a href="/moon">pulls</a> the sea twice a day, <em>every</em> day.<br>See <a href="#note">the note</a>.</p>
+            <h2>Deep water</h2>
+            <ul><li>Spring tides come with the <b>full</b> moon<ul><li>Neap</li></ul></li></ul>
+            <ol start="9"><li>Nine</li><li>Ten</li></ol>
+            <blockquote><p>Time and tide.</p></blockquote>
+            <pre><code class="language-sh">tide --now</code></pre>
+            <p><img src="chart.png" alt="Tide chart"></p>"##,
+        );
+
+        assert_eq!(
+            render(&article, Some("https://example.com/posts/tides"), 30, false),
+            "On Tides\n\
+             Ada Tern\n\n\
+             The moon pulls\n\
+             (https://example.com/moon) the\n\
+             sea twice a day, every day.\n\
+             See the note.\n\n\
+             Deep water\n\n\
+             • Spring tides come with the\n  \
+             full moon\n  \
+             • Neap\n\n\
+             9.  Nine\n\
+             10. Ten\n\n\
+             │ Time and tide.\n\n    \
+             tide --now\n\n\
+             [image: Tide chart]"
+        );
+    }
+
+    #[test]
+    fn styles_text_and_highlights_code() {
+        let article = article(
+            r#"<p>A <strong>bold</strong> <a href="https://example.org">link</a>.</p>
+            <pre><code class="language-rust">fn main() { let s = "hi"; } // done</code></pre>"#,
+        );
+
+        let output = render(&article, None, 80, true);
+
+        assert!(output.contains(&"bold".bold().to_string()));
+        assert!(output.contains(&"(https://example.org)".dimmed().to_string()));
+        assert!(output.contains(&"fn".style(Ansi::new().magenta().bold()).to_string()));
+        assert!(output.contains(&"\"hi\"".style(Ansi::new().green()).to_string()));
+        assert!(output.contains(&"// done".style(Ansi::new().dimmed()).to_string()));
+    }
+}
@@ -0,0 +1,13 @@
+use lectito::Article;
+
+/// Article titled "On Tides" by Ada Tern with the given content. Tests set
+/// any other fields they need with struct update syntax.
+pub fn article(content: &str) -> Article {
+    Article {
+        title: Some("On Tides".to_string()),
+        byline: Some("Ada Tern".to_string()),
+        lang: Some("en".to_string()),
+        content: content.to_string(),
+        ..Default::default()
+    }
+}
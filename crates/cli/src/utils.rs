@@ -1,5 +1,43 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
 use anyhow::{Context, Result};
 
+/// Long titles make unwieldy paths; Obsidian shows the title alias anyway.
+const MAX_FILE_STEM_CHARS: usize = 120;
+
+/// File name for a title, without characters that file systems or Obsidian
+/// vaults reject.
+pub fn file_stem(title: Option<&str>) -> String {
+    let cleaned: String = title
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_control() || r#"\/:*?"<>|#^[]"#.contains(c) { ' ' } else { c })
+        .collect();
+    let mut stem: String = cleaned
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_FILE_STEM_CHARS)
+        .collect();
+    stem = stem.trim_end_matches(['.', ' ']).trim_start_matches('.').to_string();
+    if stem.is_empty() {
+        return "Untitled".to_string();
+    }
+    let reserved = ["CON", "PRN", "AUX", "NUL"].contains(&stem.to_ascii_uppercase().as_str())
+        || (stem.len() == 4
+            && stem
+                .get(..3)
+                .is_some_and(|prefix| ["COM", "LPT"].contains(&prefix.to_ascii_uppercase().as_str()))
+            && stem.as_bytes()[3].is_ascii_digit());
+    if reserved {
+        stem.push('_');
+    }
+    stem
+}
+
 pub fn decode_percentage(value: &str) -> Result<String> {
     let bytes = value.as_bytes();
     let mut output = Vec::with_capacity(bytes.len());
@@ -29,3 +67,68 @@ pub fn hex_value(byte: u8) -> Result<u8> {
         _ => anyhow::bail!("invalid percent escape"),
     }
 }
+
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Append `output` and a newline to the file at `path`, creating it if needed.
+///
+/// The write holds an exclusive advisory lock on the file, so concurrent runs
+/// appending to the same file each add whole entries. With `blank_line`,
+/// entries after the first are separated by a blank line.
+pub fn append_locked(path: &Path, output: &str, blank_line: bool) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    file.lock()
+        .with_context(|| format!("failed to lock {}", path.display()))?;
+    let separator = match blank_line && file.metadata()?.len() > 0 {
+        true => "\n",
+        false => "",
+    };
+    file.write_all(format!("{separator}{output}\n").as_bytes())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_stem_truncates_long_titles() {
+        assert_eq!(file_stem(Some(&"x".repeat(300))).len(), MAX_FILE_STEM_CHARS);
+    }
+
+    #[test]
+    fn append_locked_keeps_concurrent_entries_whole() {
+        let path = std::env::temp_dir().join(format!("lectito-append-test-{}.md", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let entries: Vec<String> = (0..8)
+            .map(|index| format!("# Entry {index}\n\n{}", "text ".repeat(2000)))
+            .collect();
+
+        std::thread::scope(|scope| {
+            for entry in &entries {
+                let path = &path;
+                scope.spawn(move || append_locked(path, entry, true).unwrap());
+            }
+        });
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.matches("# Entry").count(), entries.len());
+        for entry in &entries {
+            assert!(written.contains(&format!("{entry}\n")));
+        }
+    }
+}
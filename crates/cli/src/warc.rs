@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use lectito::Article;
+
+use crate::assets::Asset;
+use crate::citation::civil_date;
+use crate::fetch::InputDocument;
+use crate::utils;
+
+/// Write the fetched `pages` and `images` to a WARC 1.1 file at `path`, with
+/// the extracted Markdown as a conversion of the first page.
+///
+/// Bodies are stored as `resource` records: reqwest decodes compressed
+/// responses, so the original HTTP headers would not match the stored bytes.
+pub fn write(
+    path: &Path, pages: &[&InputDocument], images: &[(String, Asset)], article: Option<&Article>,
+) -> Result<()> {
+    if pages.iter().any(|page| page.base_url().is_none()) {
+        anyhow::bail!("--warc needs a URL input or --base-url");
+    }
+    let file = File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut warc = Warc::new(BufWriter::new(file));
+    warc.write_records(pages, images, article)
+        .and_then(|()| warc.out.flush())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+struct Warc<W> {
+    out: W,
+    date: String,
+    records: u64,
+}
+
+impl<W: Write> Warc<W> {
+    fn new(out: W) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self { out, date: warc_date(seconds), records: 0 }
+    }
+
+    fn write_records(
+        &mut self, pages: &[&InputDocument], images: &[(String, Asset)], article: Option<&Article>,
+    ) -> std::io::Result<()> {
+        let fields = format!(
+            "software: lectito-cli/{}\r\nformat: WARC File Format 1.1\r\n",
+            env!("CARGO_PKG_VERSION")
+        );
+        self.record("warcinfo", &[], "application/warc-fields", fields.as_bytes())?;
+
+        let mut first_page = None;
+        for page in pages {
+            let url = page.base_url().unwrap_or_default();
+            let content_type = page.content_type().unwrap_or("text/html");
+            let id = self.record(
+                "resource",
+                &[("WARC-Target-URI", url)],
+                content_type,
+                page.original_html().as_bytes(),
+            )?;
+            first_page.get_or_insert((url, id));
+        }
+        for (url, asset) in images {
+            let content_type = asset.content_type().unwrap_or("application/octet-stream");
+            self.record("resource", &[("WARC-Target-URI", url)], content_type, asset.bytes())?;
+        }
+        if let (Some(article), Some((url, id))) = (article, first_page) {
+            self.record(
+                "conversion",
+                &[("WARC-Target-URI", url), ("WARC-Refers-To", &id)],
+                "text/markdown; charset=utf-8",
+                article.markdown.as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write one record and return its `WARC-Record-ID`.
+    fn record(
+        &mut self, kind: &str, fields: &[(&str, &str)], content_type: &str, block: &[u8],
+    ) -> std::io::Result<String> {
+        self.records += 1;
+        let id = self.record_id(kind, block);
+        write!(
+            self.out,
+            "WARC/1.1\r\nWARC-Type: {kind}\r\nWARC-Record-ID: {id}\r\nWARC-Date: {}\r\n",
+            self.date
+        )?;
+        for (name, value) in fields {
+            write!(self.out, "{name}: {value}\r\n")?;
+        }
+        write!(
+            self.out,
+            "Content-Type: {content_type}\r\nContent-Length: {}\r\n\r\n",
+            block.len()
+        )?;
+        self.out.write_all(block)?;
+        self.out.write_all(b"\r\n\r\n")?;
+        Ok(id)
+    }
+
+    /// A version 4 style UUID URN, unique within the file and unlikely to
+    /// repeat across runs.
+    fn record_id(&self, kind: &str, block: &[u8]) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        let seed = format!("{kind}:{}:{nanos}:{}", self.records, std::process::id());
+        let high = utils::fnv1a64(seed.as_bytes()) & 0xffff_ffff_ffff_0fff | 0x4000;
+        let low = utils::fnv1a64(block) ^ utils::fnv1a64(format!("{seed}:low").as_bytes());
+        let low = low & 0x3fff_ffff_ffff_ffff | 0x8000_0000_0000_0000;
+        format!(
+            "<urn:uuid:{:08x}-{:04x}-{:04x}-{:04x}-{:012x}>",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        )
+    }
+}
+
+/// `seconds` since the Unix epoch as a WARC date, `2026-10-16T09:30:00Z`.
+fn warc_date(seconds: u64) -> String {
+    let (year, month, day) = civil_date((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_resource_records() {
+        let mut warc = Warc::new(Vec::new());
+        warc.date = warc_date(1_792_143_000);
+
+        let id = warc
+            .record(
+                "resource",
+                &[("WARC-Target-URI", "https://example.com/tides")],
+                "text/html",
+                b"<p>Tides</p>",
+            )
+            .unwrap();
+        let output = String::from_utf8(warc.out).unwrap();
+
+        assert!(id.starts_with("<urn:uuid:") && id.ends_with('>'));
+        assert_eq!(id.len(), "<urn:uuid:>".len() + 36);
+        assert_eq!(&id[24..25], "4");
+        assert_eq!(
+            output,
+            format!(
+                "WARC/1.1\r\nWARC-Type: resource\r\nWARC-Record-ID: {id}\r\nWARC-Date: 2026-10-16T09:30:00Z\r\n\
+                 WARC-Target-URI: https://example.com/tides\r\nContent-Type: text/html\r\nContent-Length: 12\r\n\r\n\
+                 <p>Tides</p>\r\n\r\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Batch throughput over the fixture corpus: full extraction, which tests
+//! every element's class and id against the unlikely-candidate and
+//! class-weight patterns, and the readability check, which runs the
+//! unlikely-candidate test on every paragraph-like node.
+//!
+//! Run with `cargo bench -p lectito --bench corpus`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use lectito::{ReadabilityOptions, ReadableOptions};
+
+fn corpus(c: &mut Criterion) {
+    let fixtures = lectito_fixtures::load_all().expect("fixture corpus");
+    let bytes: usize = fixtures.iter().map(|fixture| fixture.source.len()).sum();
+    let extract = ReadabilityOptions::default();
+    let readable = ReadableOptions::default();
+
+    let mut group = c.benchmark_group("corpus");
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.sample_size(10);
+    group.bench_function("extract", |bench| {
+        bench.iter(|| {
+            for fixture in &fixtures {
+                let _ = black_box(lectito::extract(&fixture.source, None, &extract));
+            }
+        })
+    });
+    group.bench_function("is_probably_readable", |bench| {
+        bench.iter(|| {
+            for fixture in &fixtures {
+                let _ = black_box(lectito::is_probably_readable(&fixture.source, &readable));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, corpus);
+criterion_main!(benches);
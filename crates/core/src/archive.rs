@@ -0,0 +1,532 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+use super::error::{Error, Result};
+use super::regexes::RegexPattern;
+
+/// Bytes searched for a `<meta>` charset declaration, as browsers do.
+const META_PRESCAN_BYTES: usize = 1024;
+
+/// A page saved as a single file, unpacked into its HTML and the resources
+/// saved with it.
+///
+/// Chrome and Edge write MHTML ("Save page as", "Webpage, Single File");
+/// Safari writes `.webarchive`, which needs the `webarchive` feature. Extract
+/// from [`Document::html`] with [`Document::url`] as the base URL, and look up
+/// images in [`Document::resources`] instead of fetching them.
+///
+/// ```
+/// let mhtml = "MIME-Version: 1.0\r\n\
+///     Content-Type: multipart/related; boundary=\"page\"\r\n\r\n\
+///     --page\r\n\
+///     Content-Type: text/html; charset=utf-8\r\n\
+///     Content-Location: https://example.com/tides\r\n\r\n\
+///     <article><p>The tide comes in.</p></article>\r\n\
+///     --page--\r\n";
+/// let document = lectito::Document::parse_mhtml(mhtml.as_bytes())?;
+/// assert_eq!(document.url.as_deref(), Some("https://example.com/tides"));
+/// let options = lectito::ReadabilityOptions { char_threshold: 0, ..Default::default() };
+/// let article = lectito::extract(&document.html, document.url.as_deref(), &options)?.unwrap();
+/// assert_eq!(article.text_content, "The tide comes in.");
+/// # Ok::<(), lectito::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Document {
+    /// The main HTML part, decoded to UTF-8.
+    pub html: String,
+    /// URL the page was saved from, when the archive records it.
+    pub url: Option<String>,
+    /// Every other part, such as images, stylesheets, and frames.
+    pub resources: Vec<Resource>,
+}
+
+/// A file saved inside a [`Document`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Resource {
+    /// URL the resource was loaded from, or `cid:<id>` for MHTML parts
+    /// named only by `Content-ID`.
+    pub url: String,
+    /// MIME type, such as `image/png`, when the archive records it.
+    pub content_type: Option<String>,
+    /// The decoded bytes.
+    pub data: Vec<u8>,
+}
+
+impl Document {
+    /// Unpack MHTML or a Safari web archive, whichever `bytes` holds.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        match is_plist(bytes) {
+            true => Self::parse_webarchive(bytes),
+            false => Self::parse_mhtml(bytes),
+        }
+    }
+
+    /// Decode a plain HTML page from raw bytes, such as an HTTP body or a file
+    /// in a legacy encoding.
+    ///
+    /// The encoding comes from a byte-order mark, then `hint`, then a
+    /// `<meta charset>` or `<meta http-equiv="Content-Type">` tag in the first
+    /// 1024 bytes. Without any of these the bytes are read as UTF-8 when they
+    /// are valid UTF-8 and as windows-1252 otherwise. `hint` is a charset label
+    /// such as `shift_jis` or a whole `Content-Type` value; unknown labels are
+    /// ignored. Malformed sequences become U+FFFD. Archives go through
+    /// [`Document::parse`] instead.
+    ///
+    /// ```
+    /// let bytes = b"<meta charset=\"iso-8859-1\"><p>Caf\xe9</p>";
+    /// let document = lectito::Document::parse_bytes(bytes, None);
+    /// assert_eq!(document.html, "<meta charset=\"iso-8859-1\"><p>Caf\u{e9}</p>");
+    /// ```
+    pub fn parse_bytes(bytes: &[u8], hint: Option<&str>) -> Self {
+        Self { html: decode_text(bytes, hint), ..Default::default() }
+    }
+
+    /// Whether `bytes` look like MHTML or a Safari web archive rather than
+    /// plain HTML.
+    pub fn is_archive(bytes: &[u8]) -> bool {
+        if is_plist(bytes) {
+            return true;
+        }
+        let (headers, _) = split_headers(bytes);
+        header(&headers, "content-type")
+            .is_some_and(|content_type| content_type.to_ascii_lowercase().starts_with("multipart/related"))
+    }
+
+    /// Unpack an MHTML file. The first `text/html` part is the page; its
+    /// `Content-Location`, or the `Snapshot-Content-Location` Chrome writes,
+    /// is the page URL. Base64 and quoted-printable parts are decoded.
+    pub fn parse_mhtml(bytes: &[u8]) -> Result<Self> {
+        let (headers, body) = split_headers(bytes);
+        let content_type = header(&headers, "content-type").unwrap_or_default();
+        let parts = match parameter(content_type, "boundary") {
+            Some(boundary) if content_type.to_ascii_lowercase().starts_with("multipart/") => {
+                split_multipart(body, &boundary)
+                    .into_iter()
+                    .map(|part| {
+                        let (headers, body) = split_headers(part);
+                        Part::new(headers, body)
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            _ => vec![Part::new(headers.clone(), body)?],
+        };
+
+        let main = parts
+            .iter()
+            .position(|part| part.mime_type() == "text/html")
+            .ok_or_else(|| Error::Archive("MHTML has no text/html part".to_string()))?;
+        let html = decode_text(&parts[main].data, parts[main].charset());
+        let url = header(&headers, "snapshot-content-location")
+            .map(str::to_string)
+            .or_else(|| parts[main].url());
+        let resources = parts
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| *index != main)
+            .filter_map(|(_, part)| {
+                Some(Resource {
+                    url: part.url()?,
+                    content_type: header(&part.headers, "content-type").map(str::to_string),
+                    data: part.data,
+                })
+            })
+            .collect();
+        Ok(Self { html, url, resources })
+    }
+
+    /// Unpack a Safari `.webarchive`. Subresources of frames are included
+    /// with the page's own.
+    #[cfg(feature = "webarchive")]
+    pub fn parse_webarchive(bytes: &[u8]) -> Result<Self> {
+        let archive = plist::Value::from_reader(std::io::Cursor::new(bytes))
+            .map_err(|error| Error::Archive(error.to_string()))?;
+        let archive = archive
+            .as_dictionary()
+            .ok_or_else(|| Error::Archive("web archive is not a dictionary".to_string()))?;
+        let main = archive
+            .get("WebMainResource")
+            .and_then(plist::Value::as_dictionary)
+            .ok_or_else(|| Error::Archive("web archive has no WebMainResource".to_string()))?;
+        let data = main
+            .get("WebResourceData")
+            .and_then(plist::Value::as_data)
+            .unwrap_or_default();
+        let charset = main
+            .get("WebResourceTextEncodingName")
+            .and_then(plist::Value::as_string);
+        let url = main
+            .get("WebResourceURL")
+            .and_then(plist::Value::as_string)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string);
+        let mut resources = Vec::new();
+        collect_web_subresources(archive, &mut resources);
+        Ok(Self { html: decode_text(data, charset), url, resources })
+    }
+
+    /// Fails with [`Error::FeatureDisabled`]; Safari web archives need the
+    /// `webarchive` feature.
+    #[cfg(not(feature = "webarchive"))]
+    pub fn parse_webarchive(_bytes: &[u8]) -> Result<Self> {
+        Err(Error::FeatureDisabled("webarchive"))
+    }
+
+    /// The saved resource loaded from `url`.
+    pub fn resource(&self, url: &str) -> Option<&Resource> {
+        self.resources.iter().find(|resource| resource.url == url)
+    }
+}
+
+fn is_plist(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"bplist00")
+        || (bytes.starts_with(b"<?xml") && bytes.windows(6).take(512).any(|window| window == b"<plist"))
+}
+
+#[cfg(feature = "webarchive")]
+fn collect_web_subresources(archive: &plist::Dictionary, resources: &mut Vec<Resource>) {
+    let subresources = archive.get("WebSubresources").and_then(plist::Value::as_array);
+    for resource in subresources
+        .into_iter()
+        .flatten()
+        .filter_map(plist::Value::as_dictionary)
+    {
+        let url = resource.get("WebResourceURL").and_then(plist::Value::as_string);
+        let data = resource.get("WebResourceData").and_then(plist::Value::as_data);
+        if let (Some(url), Some(data)) = (url, data) {
+            resources.push(Resource {
+                url: url.to_string(),
+                content_type: resource
+                    .get("WebResourceMIMEType")
+                    .and_then(plist::Value::as_string)
+                    .map(str::to_string),
+                data: data.to_vec(),
+            });
+        }
+    }
+    let frames = archive.get("WebSubframeArchives").and_then(plist::Value::as_array);
+    for frame in frames.into_iter().flatten().filter_map(plist::Value::as_dictionary) {
+        collect_web_subresources(frame, resources);
+    }
+}
+
+/// One MIME part, with its body already decoded.
+struct Part {
+    headers: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+impl Part {
+    fn new(headers: Vec<(String, String)>, body: &[u8]) -> Result<Self> {
+        let data = match header(&headers, "content-transfer-encoding")
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("base64") => {
+                let encoded: Vec<u8> = body
+                    .iter()
+                    .copied()
+                    .filter(|byte| !byte.is_ascii_whitespace())
+                    .collect();
+                STANDARD
+                    .decode(encoded)
+                    .map_err(|error| Error::Archive(format!("invalid base64 part: {error}")))?
+            }
+            Some("quoted-printable") => decode_quoted_printable(body),
+            _ => body.to_vec(),
+        };
+        Ok(Self { headers, data })
+    }
+
+    fn mime_type(&self) -> String {
+        let content_type = header(&self.headers, "content-type").unwrap_or_default();
+        content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    }
+
+    fn charset(&self) -> Option<&str> {
+        header(&self.headers, "content-type").and_then(|content_type| {
+            content_type
+                .split(';')
+                .skip(1)
+                .filter_map(|parameter| parameter.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+                .map(|(_, value)| value.trim().trim_matches('"'))
+        })
+    }
+
+    fn url(&self) -> Option<String> {
+        header(&self.headers, "content-location")
+            .map(str::to_string)
+            .or_else(|| {
+                header(&self.headers, "content-id")
+                    .map(|id| format!("cid:{}", id.trim_start_matches('<').trim_end_matches('>')))
+            })
+    }
+}
+
+/// Split a header block, with folded lines joined, from the body after the
+/// first blank line. Header names are lowercased.
+fn split_headers(bytes: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let (line, next) = match rest.iter().position(|byte| *byte == b'\n') {
+            Some(end) => (&rest[..end], &rest[end + 1..]),
+            None => (rest, &rest[rest.len()..]),
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            return (headers, next);
+        }
+        let line = String::from_utf8_lossy(line);
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        } else {
+            // Not a header block at all.
+            return (Vec::new(), bytes);
+        }
+        rest = next;
+    }
+    (headers, rest)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.as_str())
+        .filter(|value| !value.is_empty())
+}
+
+fn parameter(content_type: &str, name: &str) -> Option<String> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+}
+
+/// The parts between `--boundary` lines, up to the closing `--boundary--`.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+    let mut starts = Vec::new();
+    let mut index = 0;
+    while let Some(found) = body[index..]
+        .windows(delimiter.len())
+        .position(|window| window == delimiter)
+    {
+        let start = index + found;
+        if start == 0 || body[start - 1] == b'\n' {
+            starts.push(start);
+        }
+        index = start + delimiter.len();
+    }
+
+    let mut parts = Vec::new();
+    for (position, &start) in starts.iter().enumerate() {
+        let after = start + delimiter.len();
+        if body[after..].starts_with(b"--") {
+            break;
+        }
+        let Some(line_end) = body[after..].iter().position(|byte| *byte == b'\n') else {
+            break;
+        };
+        let content_start = after + line_end + 1;
+        let end = starts
+            .get(position + 1)
+            .copied()
+            .unwrap_or(body.len())
+            .max(content_start);
+        let part = &body[content_start..end];
+        let part = part.strip_suffix(b"\n").unwrap_or(part);
+        parts.push(part.strip_suffix(b"\r").unwrap_or(part));
+    }
+    parts
+}
+
+fn decode_quoted_printable(body: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(body.len());
+    let mut index = 0;
+    while index < body.len() {
+        if body[index] != b'=' {
+            decoded.push(body[index]);
+            index += 1;
+            continue;
+        }
+        let rest = &body[index + 1..];
+        if rest.starts_with(b"\r\n") {
+            index += 3;
+        } else if rest.starts_with(b"\n") {
+            index += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(b'=');
+            index += 1;
+        }
+    }
+    decoded
+}
+
+/// Decode `data` by its byte-order mark, the `charset` label or
+/// `Content-Type` value, its `<meta>` declaration, or as UTF-8 falling back
+/// to windows-1252, in that order.
+fn decode_text(data: &[u8], charset: Option<&str>) -> String {
+    let encoding = Encoding::for_bom(data)
+        .map(|(encoding, _)| encoding)
+        .or_else(|| charset.and_then(encoding_for))
+        .or_else(|| meta_charset(data))
+        .unwrap_or(if std::str::from_utf8(data).is_ok() { UTF_8 } else { WINDOWS_1252 });
+    encoding.decode(data).0.into_owned()
+}
+
+/// The encoding a charset label or `Content-Type` value names.
+fn encoding_for(charset: &str) -> Option<&'static Encoding> {
+    let label = match parameter(charset, "charset") {
+        Some(label) => label,
+        None if charset.contains(';') || charset.contains('/') => return None,
+        None => charset.to_string(),
+    };
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// The encoding declared by a `<meta>` tag near the start of `data`. A page
+/// that declares UTF-16 in ASCII markup is really UTF-8.
+fn meta_charset(data: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&data[..data.len().min(META_PRESCAN_BYTES)]);
+    let label = RegexPattern::MetaCharset.to_regex().captures(&head)?.get(1)?;
+    Encoding::for_label(label.as_str().as_bytes()).map(Encoding::output_encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MHTML: &str = "From: <Saved by Blink>\r\n\
+        Snapshot-Content-Location: https://example.com/tides\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/related;\r\n\
+        \ttype=\"text/html\";\r\n\
+        \tboundary=\"----MultipartBoundary--tide\"\r\n\
+        \r\n\
+        ------MultipartBoundary--tide\r\n\
+        Content-Type: text/html\r\n\
+        Content-ID: <frame-1@mhtml.blink>\r\n\
+        Content-Transfer-Encoding: quoted-printable\r\n\
+        Content-Location: https://example.com/tides\r\n\
+        \r\n\
+        <html><body><p class=3D\"lede\">The tide comes in twice a day, pulled by th=\r\n\
+        e moon =E2=80=94 and the sun.</p><img src=3D\"https://example.com/tide.png\"></body></html>\r\n\
+        ------MultipartBoundary--tide\r\n\
+        Content-Type: image/png\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        Content-Location: https://example.com/tide.png\r\n\
+        \r\n\
+        iVBORw0K\r\n\
+        Ggo=\r\n\
+        ------MultipartBoundary--tide--\r\n";
+
+    #[test]
+    fn unpacks_mhtml_parts() {
+        assert!(Document::is_archive(MHTML.as_bytes()));
+        assert!(!Document::is_archive(b"<!doctype html><p>Plain page</p>"));
+
+        let document = Document::parse(MHTML.as_bytes()).unwrap();
+        assert_eq!(document.url.as_deref(), Some("https://example.com/tides"));
+        assert_eq!(
+            document.html,
+            "<html><body><p class=\"lede\">The tide comes in twice a day, pulled by the moon \u{2014} and the sun.</p>\
+             <img src=\"https://example.com/tide.png\"></body></html>"
+        );
+        let image = document.resource("https://example.com/tide.png").unwrap();
+        assert_eq!(image.content_type.as_deref(), Some("image/png"));
+        assert_eq!(image.data, b"\x89PNG\r\n\x1a\n");
+
+        assert!(matches!(
+            Document::parse_mhtml(b"MIME-Version: 1.0\r\nContent-Type: image/png\r\n\r\nPNG"),
+            Err(Error::Archive(_))
+        ));
+    }
+
+    #[cfg(feature = "webarchive")]
+    #[test]
+    fn unpacks_safari_webarchives() {
+        let resource = |url: &str, mime: &str, data: &[u8]| {
+            plist::Value::Dictionary(plist::Dictionary::from_iter([
+                ("WebResourceURL", plist::Value::String(url.to_string())),
+                ("WebResourceMIMEType", plist::Value::String(mime.to_string())),
+                ("WebResourceData", plist::Value::Data(data.to_vec())),
+            ]))
+        };
+        let archive = plist::Value::Dictionary(plist::Dictionary::from_iter([
+            (
+                "WebMainResource",
+                resource(
+                    "https://example.com/tides",
+                    "text/html",
+                    "<p>Tides \u{2014} daily.</p>".as_bytes(),
+                ),
+            ),
+            (
+                "WebSubresources",
+                plist::Value::Array(vec![resource("https://example.com/tide.png", "image/png", b"PNG")]),
+            ),
+        ]));
+        let mut bytes = Vec::new();
+        archive.to_writer_binary(&mut bytes).unwrap();
+
+        assert!(Document::is_archive(&bytes));
+        let document = Document::parse(&bytes).unwrap();
+        assert_eq!(document.html, "<p>Tides \u{2014} daily.</p>");
+        assert_eq!(document.url.as_deref(), Some("https://example.com/tides"));
+        assert_eq!(document.resource("https://example.com/tide.png").unwrap().data, b"PNG");
+    }
+
+    #[test]
+    fn decodes_plain_html_bytes() {
+        let latin1 = b"<p>Caf\xe9</p>";
+        assert_eq!(Document::parse_bytes(latin1, None).html, "<p>Caf\u{e9}</p>");
+        assert_eq!(
+            Document::parse_bytes("<p>Café</p>".as_bytes(), None).html,
+            "<p>Café</p>"
+        );
+        assert_eq!(
+            Document::parse_bytes(b"\xef\xbb\xbf<p>Caf\xc3\xa9</p>", Some("iso-8859-1")).html,
+            "<p>Café</p>"
+        );
+
+        let shift_jis = b"<p>\x93\xfa\x96\x7b</p>";
+        assert_eq!(Document::parse_bytes(shift_jis, Some("shift_jis")).html, "<p>日本</p>");
+        assert_eq!(
+            Document::parse_bytes(shift_jis, Some("text/html; charset=\"Shift_JIS\"")).html,
+            "<p>日本</p>"
+        );
+
+        let gbk = b"<head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=gb2312\"></head><p>\xd6\xd0\xce\xc4</p>";
+        let document = Document::parse_bytes(gbk, Some("text/html"));
+        assert!(document.html.ends_with("<p>中文</p>"));
+        assert_eq!(document.url, None);
+
+        let utf16 = b"<meta charset=\"utf-16\"><p>Caf\xc3\xa9</p>";
+        assert!(Document::parse_bytes(utf16, None).html.ends_with("<p>Café</p>"));
+    }
+}
@@ -0,0 +1,435 @@
+use std::time::Duration;
+
+use super::config::{
+    ExtractionStrategy, FootnoteMode, LinkCleanup, LinkStyle, MediaRetention, PatternGroup, ReadabilityOptions,
+    Typography,
+};
+use super::error::{Error, Result};
+
+impl ReadabilityOptions {
+    /// Start building options from the defaults.
+    ///
+    /// ```
+    /// use lectito::{MediaRetention, ReadabilityOptions};
+    ///
+    /// let options = ReadabilityOptions::builder()
+    ///     .char_threshold(200)
+    ///     .content_selector("main article")
+    ///     .media_retention(MediaRetention::All)
+    ///     .build()?;
+    /// assert_eq!(options.char_threshold, 200);
+    /// # Ok::<(), lectito::Error>(())
+    /// ```
+    pub fn builder() -> ReadabilityOptionsBuilder {
+        ReadabilityOptionsBuilder::default()
+    }
+
+    /// Check that numeric options are in range and `content_selector` parses.
+    ///
+    /// Extraction runs this first, so out-of-range options fail with
+    /// [`Error::InvalidOption`] instead of quietly skewing scores.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_elems_to_parse == Some(0) {
+            return Err(Error::invalid_option("max_elems_to_parse", "must be at least 1"));
+        }
+        if self.nb_top_candidates == 0 {
+            return Err(Error::invalid_option("nb_top_candidates", "must be at least 1"));
+        }
+        if let Some(selector) = &self.content_selector
+            && kuchiki::Selectors::compile(selector).is_err()
+        {
+            return Err(Error::invalid_option(
+                "content_selector",
+                format!("`{selector}` is not a valid CSS selector"),
+            ));
+        }
+        if self.mobile_viewport_width == Some(0) {
+            return Err(Error::invalid_option("mobile_viewport_width", "must be at least 1"));
+        }
+        if self.image_target_width == Some(0) {
+            return Err(Error::invalid_option("image_target_width", "must be at least 1"));
+        }
+        if self.max_input_bytes == Some(0) {
+            return Err(Error::invalid_option("max_input_bytes", "must be at least 1"));
+        }
+        if self.max_candidates == Some(0) {
+            return Err(Error::invalid_option("max_candidates", "must be at least 1"));
+        }
+        if self.max_duration == Some(Duration::ZERO) {
+            return Err(Error::invalid_option("max_duration", "must be greater than zero"));
+        }
+        check_range("link_density_modifier", self.link_density_modifier, -1.0, 1.0)?;
+        if let Some(offset) = self.heading_offset
+            && offset > 5
+        {
+            return Err(Error::invalid_option(
+                "heading_offset",
+                format!("must be at most 5, since Markdown stops at H6, got {offset}"),
+            ));
+        }
+        check_range("sibling_threshold", self.sibling_threshold, 0.0, 1.0)?;
+        if !self.min_candidate_score.is_finite() {
+            return Err(Error::invalid_option("min_candidate_score", "must be a finite number"));
+        }
+        Ok(())
+    }
+}
+
+fn check_range(option: &str, value: f32, min: f32, max: f32) -> Result<()> {
+    match (min..=max).contains(&value) {
+        true => Ok(()),
+        false => Err(Error::invalid_option(
+            option,
+            format!("must be between {min} and {max}, got {value}"),
+        )),
+    }
+}
+
+/// Builder for [`ReadabilityOptions`].
+///
+/// Each setter documents the field it sets. List setters add one value per
+/// call. Unset fields keep their defaults, and new options arrive as new
+/// setters, so builder code keeps compiling across minor releases.
+/// [`build`](Self::build) validates the result.
+#[derive(Clone, Debug, Default)]
+pub struct ReadabilityOptionsBuilder {
+    options: ReadabilityOptions,
+}
+
+impl ReadabilityOptionsBuilder {
+    /// Reject documents above this many elements. See
+    /// [`ReadabilityOptions::max_elems_to_parse`].
+    pub fn max_elems_to_parse(mut self, limit: usize) -> Self {
+        self.options.max_elems_to_parse = Some(limit);
+        self
+    }
+
+    /// See [`ReadabilityOptions::nb_top_candidates`].
+    pub fn nb_top_candidates(mut self, count: usize) -> Self {
+        self.options.nb_top_candidates = count;
+        self
+    }
+
+    /// See [`ReadabilityOptions::char_threshold`].
+    pub fn char_threshold(mut self, chars: usize) -> Self {
+        self.options.char_threshold = chars;
+        self
+    }
+
+    /// See [`ReadabilityOptions::content_selector`].
+    pub fn content_selector(mut self, selector: impl Into<String>) -> Self {
+        self.options.content_selector = Some(selector.into());
+        self
+    }
+
+    /// Add a TOML site profile. See [`ReadabilityOptions::site_profiles`].
+    pub fn site_profile(mut self, profile: impl Into<String>) -> Self {
+        self.options.site_profiles.push(profile.into());
+        self
+    }
+
+    /// Add a `directive=value` assignment. See
+    /// [`ReadabilityOptions::site_profile_overrides`].
+    pub fn site_profile_override(mut self, assignment: impl Into<String>) -> Self {
+        self.options.site_profile_overrides.push(assignment.into());
+        self
+    }
+
+    /// Disable a site profile directive. See
+    /// [`ReadabilityOptions::site_profile_ignores`].
+    pub fn site_profile_ignore(mut self, directive: impl Into<String>) -> Self {
+        self.options.site_profile_ignores.push(directive.into());
+        self
+    }
+
+    /// `None` turns mobile CSS recovery off. See
+    /// [`ReadabilityOptions::mobile_viewport_width`].
+    pub fn mobile_viewport_width(mut self, width: Option<usize>) -> Self {
+        self.options.mobile_viewport_width = width;
+        self
+    }
+
+    /// Add a class name to keep. See [`ReadabilityOptions::classes_to_preserve`].
+    pub fn preserve_class(mut self, class: impl Into<String>) -> Self {
+        self.options.classes_to_preserve.push(class.into());
+        self
+    }
+
+    /// See [`ReadabilityOptions::keep_classes`].
+    pub fn keep_classes(mut self, keep: bool) -> Self {
+        self.options.keep_classes = keep;
+        self
+    }
+
+    /// See [`ReadabilityOptions::disable_json_ld`].
+    pub fn disable_json_ld(mut self, disable: bool) -> Self {
+        self.options.disable_json_ld = disable;
+        self
+    }
+
+    /// See [`ReadabilityOptions::link_density_modifier`].
+    pub fn link_density_modifier(mut self, modifier: f32) -> Self {
+        self.options.link_density_modifier = modifier;
+        self
+    }
+
+    /// See [`ReadabilityOptions::media_retention`].
+    pub fn media_retention(mut self, retention: MediaRetention) -> Self {
+        self.options.media_retention = retention;
+        self
+    }
+
+    /// Add an iframe host to keep. See [`ReadabilityOptions::iframe_allowlist`].
+    pub fn allow_iframe_host(mut self, host: impl Into<String>) -> Self {
+        self.options.iframe_allowlist.push(host.into());
+        self
+    }
+
+    /// See [`ReadabilityOptions::footnotes`].
+    pub fn footnotes(mut self, mode: FootnoteMode) -> Self {
+        self.options.footnotes = mode;
+        self
+    }
+
+    /// See [`ReadabilityOptions::link_style`].
+    pub fn link_style(mut self, style: LinkStyle) -> Self {
+        self.options.link_style = style;
+        self
+    }
+
+    /// See [`ReadabilityOptions::link_cleanup`].
+    pub fn link_cleanup(mut self, cleanup: LinkCleanup) -> Self {
+        self.options.link_cleanup = cleanup;
+        self
+    }
+
+    /// See [`ReadabilityOptions::heading_offset`].
+    pub fn heading_offset(mut self, offset: u8) -> Self {
+        self.options.heading_offset = Some(offset);
+        self
+    }
+
+    /// See [`ReadabilityOptions::normalize_heading_levels`].
+    pub fn normalize_heading_levels(mut self, normalize: bool) -> Self {
+        self.options.normalize_heading_levels = normalize;
+        self
+    }
+
+    /// See [`ReadabilityOptions::reading_time_markers`].
+    pub fn reading_time_markers(mut self, markers: bool) -> Self {
+        self.options.reading_time_markers = markers;
+        self
+    }
+
+    /// See [`ReadabilityOptions::normalize_text`].
+    pub fn normalize_text(mut self, normalize: bool) -> Self {
+        self.options.normalize_text = normalize;
+        self
+    }
+
+    /// See [`ReadabilityOptions::typography`].
+    pub fn typography(mut self, typography: Typography) -> Self {
+        self.options.typography = typography;
+        self
+    }
+
+    /// See [`ReadabilityOptions::strategy`].
+    pub fn strategy(mut self, strategy: ExtractionStrategy) -> Self {
+        self.options.strategy = strategy;
+        self
+    }
+
+    /// See [`ReadabilityOptions::cleanup_dry_run`].
+    pub fn cleanup_dry_run(mut self, dry_run: bool) -> Self {
+        self.options.cleanup_dry_run = dry_run;
+        self
+    }
+
+    /// See [`ReadabilityOptions::keep_hidden`].
+    pub fn keep_hidden(mut self, keep: bool) -> Self {
+        self.options.keep_hidden = keep;
+        self
+    }
+
+    /// See [`ReadabilityOptions::keep_unlikely_candidates`].
+    pub fn keep_unlikely_candidates(mut self, keep: bool) -> Self {
+        self.options.keep_unlikely_candidates = keep;
+        self
+    }
+
+    /// See [`ReadabilityOptions::keep_iframes`].
+    pub fn keep_iframes(mut self, keep: bool) -> Self {
+        self.options.keep_iframes = keep;
+        self
+    }
+
+    /// See [`ReadabilityOptions::sibling_threshold`].
+    pub fn sibling_threshold(mut self, threshold: f32) -> Self {
+        self.options.sibling_threshold = threshold;
+        self
+    }
+
+    /// See [`ReadabilityOptions::min_candidate_score`].
+    pub fn min_candidate_score(mut self, score: f32) -> Self {
+        self.options.min_candidate_score = score;
+        self
+    }
+
+    /// See [`ReadabilityOptions::class_weight`].
+    pub fn class_weight(mut self, weight: i32) -> Self {
+        self.options.class_weight = weight;
+        self
+    }
+
+    /// Add a positive class or id substring. See
+    /// [`ReadabilityOptions::positive_patterns`].
+    pub fn positive_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.options.positive_patterns.push(pattern.into());
+        self
+    }
+
+    /// Add a negative class or id substring. See
+    /// [`ReadabilityOptions::negative_patterns`].
+    pub fn negative_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.options.negative_patterns.push(pattern.into());
+        self
+    }
+
+    /// Add a class or id substring that marks page chrome. See
+    /// [`ReadabilityOptions::unlikely_patterns`].
+    pub fn unlikely_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.options.unlikely_patterns.push(pattern.into());
+        self
+    }
+
+    /// Add a class, id, role, or label substring that marks trailing chrome.
+    /// See [`ReadabilityOptions::trailing_chrome_patterns`].
+    pub fn trailing_chrome_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.options.trailing_chrome_patterns.push(pattern.into());
+        self
+    }
+
+    /// Add a documentation-page control label to remove. See
+    /// [`ReadabilityOptions::doc_chrome_labels`].
+    pub fn doc_chrome_label(mut self, label: impl Into<String>) -> Self {
+        self.options.doc_chrome_labels.push(label.into());
+        self
+    }
+
+    /// Drop the built-in patterns of `group`. See
+    /// [`ReadabilityOptions::replace_pattern_groups`].
+    pub fn replace_pattern_group(mut self, group: PatternGroup) -> Self {
+        self.options.replace_pattern_groups.push(group);
+        self
+    }
+
+    /// `None` leaves responsive images as they are. See
+    /// [`ReadabilityOptions::image_target_width`].
+    pub fn image_target_width(mut self, width: Option<u32>) -> Self {
+        self.options.image_target_width = width;
+        self
+    }
+
+    /// `None` accepts input of any size. See
+    /// [`ReadabilityOptions::max_input_bytes`].
+    pub fn max_input_bytes(mut self, bytes: Option<usize>) -> Self {
+        self.options.max_input_bytes = bytes;
+        self
+    }
+
+    /// `None` scores every candidate. See
+    /// [`ReadabilityOptions::max_candidates`].
+    pub fn max_candidates(mut self, count: Option<usize>) -> Self {
+        self.options.max_candidates = count;
+        self
+    }
+
+    /// `None` lets extraction run to completion. See
+    /// [`ReadabilityOptions::max_duration`].
+    pub fn max_duration(mut self, duration: Option<Duration>) -> Self {
+        self.options.max_duration = duration;
+        self
+    }
+
+    /// See [`ReadabilityOptions::recover_noscript`].
+    pub fn recover_noscript(mut self, recover: bool) -> Self {
+        self.options.recover_noscript = recover;
+        self
+    }
+
+    /// Finish building, failing with [`Error::InvalidOption`] when
+    /// [`ReadabilityOptions::validate`] rejects the options.
+    pub fn build(self) -> Result<ReadabilityOptions> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
+
+impl From<ReadabilityOptions> for ReadabilityOptionsBuilder {
+    /// Continue building from existing options.
+    fn from(options: ReadabilityOptions) -> Self {
+        Self { options }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_sets_fields_and_keeps_defaults() {
+        let options = ReadabilityOptions::builder()
+            .char_threshold(0)
+            .site_profile("name = \"a\"")
+            .site_profile("name = \"b\"")
+            .mobile_viewport_width(None)
+            .heading_offset(1)
+            .strategy(ExtractionStrategy::LiveBlog)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            options,
+            ReadabilityOptions {
+                char_threshold: 0,
+                site_profiles: vec!["name = \"a\"".to_string(), "name = \"b\"".to_string()],
+                mobile_viewport_width: None,
+                heading_offset: Some(1),
+                strategy: ExtractionStrategy::LiveBlog,
+                ..Default::default()
+            }
+        );
+        let rebuilt = ReadabilityOptionsBuilder::from(options.clone())
+            .keep_hidden(true)
+            .build()
+            .unwrap();
+        assert_eq!(rebuilt, ReadabilityOptions { keep_hidden: true, ..options });
+    }
+
+    #[test]
+    fn build_rejects_out_of_range_options() {
+        let message = |builder: ReadabilityOptionsBuilder| builder.build().unwrap_err().to_string();
+
+        assert_eq!(
+            message(ReadabilityOptions::builder().sibling_threshold(1.5)),
+            "invalid option sibling_threshold: must be between 0 and 1, got 1.5"
+        );
+        assert_eq!(
+            message(ReadabilityOptions::builder().link_density_modifier(f32::NAN)),
+            "invalid option link_density_modifier: must be between -1 and 1, got NaN"
+        );
+        assert_eq!(
+            message(ReadabilityOptions::builder().content_selector("main >")),
+            "invalid option content_selector: `main >` is not a valid CSS selector"
+        );
+        assert!(matches!(
+            ReadabilityOptions::builder().nb_top_candidates(0).build(),
+            Err(Error::InvalidOption { option, .. }) if option == "nb_top_candidates"
+        ));
+        assert!(ReadabilityOptions::builder().heading_offset(6).build().is_err());
+        assert!(ReadabilityOptions::default().validate().is_ok());
+
+        let options = ReadabilityOptions { sibling_threshold: -0.1, ..Default::default() };
+        assert!(crate::extract("<p>Text</p>", None, &options).is_err());
+    }
+}
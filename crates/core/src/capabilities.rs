@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+use super::error::{Error, Result};
+
+/// Optional features this crate can be built with.
+const FEATURES: &[(&str, bool)] = &[
+    ("bundled-configs", cfg!(feature = "bundled-configs")),
+    ("schema", cfg!(feature = "schema")),
+    ("textstats", cfg!(feature = "textstats")),
+    ("tokio", cfg!(feature = "tokio")),
+    ("webarchive", cfg!(feature = "webarchive")),
+];
+
+/// What this build of Lectito can do, for applications that adapt their UI
+/// to the compiled feature set.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Curated news-site profiles are compiled in.
+    pub bundled_configs: bool,
+    /// `Article::json_schema` is available.
+    pub schema: bool,
+    /// `Article::readability_metrics` is available.
+    pub textstats: bool,
+    /// `Article::write_to_async` is available.
+    pub tokio: bool,
+    /// [`crate::Document::parse_webarchive`] reads Safari web archives.
+    pub webarchive: bool,
+    /// Formats [`crate::Article::save_with`] writes.
+    pub output_formats: &'static [&'static str],
+}
+
+/// The feature set this build of Lectito was compiled with.
+///
+/// ```
+/// let capabilities = lectito::capabilities();
+/// if capabilities.require("textstats").is_err() {
+///     // Hide the readability score.
+/// }
+/// assert!(capabilities.output_formats.contains(&"epub"));
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        bundled_configs: cfg!(feature = "bundled-configs"),
+        schema: cfg!(feature = "schema"),
+        textstats: cfg!(feature = "textstats"),
+        tokio: cfg!(feature = "tokio"),
+        webarchive: cfg!(feature = "webarchive"),
+        output_formats: &["markdown", "html", "text", "json", "epub"],
+    }
+}
+
+impl Capabilities {
+    /// Names of the enabled optional features.
+    pub fn features(&self) -> Vec<&'static str> {
+        FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Fail with [`Error::FeatureDisabled`] unless `feature` was compiled
+    /// in. Unknown feature names fail with [`Error::Config`].
+    pub fn require(&self, feature: &str) -> Result<()> {
+        match FEATURES.iter().find(|(name, _)| *name == feature) {
+            Some((_, true)) => Ok(()),
+            Some((name, false)) => Err(Error::FeatureDisabled(name)),
+            None => Err(Error::Config(format!("unknown feature {feature}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_compiled_features() {
+        let capabilities = capabilities();
+        assert_eq!(capabilities.textstats, cfg!(feature = "textstats"));
+        assert_eq!(capabilities.features().contains(&"schema"), cfg!(feature = "schema"));
+        match cfg!(feature = "textstats") {
+            true => assert!(capabilities.require("textstats").is_ok()),
+            false => assert!(matches!(
+                capabilities.require("textstats"),
+                Err(Error::FeatureDisabled("textstats"))
+            )),
+        }
+        assert!(matches!(capabilities.require("fetch"), Err(Error::Config(_))));
+    }
+}
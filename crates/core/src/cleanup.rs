@@ -1,53 +1,164 @@
+use std::collections::HashMap;
+
 use kuchiki::NodeRef;
+use kuchiki::iter::NodeIterator;
 use url::Url;
 
-use super::config::{ExtractFlags, MediaRetention, ReadabilityOptions};
+use super::config::{ExtractFlags, FootnoteMode, MediaRetention, PatternGroup, ReadabilityOptions};
+use super::diagnostics::{
+    DRY_RUN_MARKER, DRY_RUN_REMOVED, DryRunRemovalDiagnostic, RemovalTracker, RuleRemovalDiagnostic,
+};
+use super::extract::node_selector;
 use super::metadata::Metadata;
-use super::patterns::{DEFAULT_CLASSES_TO_PRESERVE, DEPRECATED_SIZE_ATTRIBUTE_ELEMS, PRESENTATIONAL_ATTRIBUTES};
+use super::patterns::{
+    AUTHOR_BIO_SELECTOR, DEFAULT_CLASSES_TO_PRESERVE, DEPRECATED_SIZE_ATTRIBUTE_ELEMS, MAX_AUTHOR_BIO_CHARS,
+    MAX_DATELINE_OFFSET, PRESENTATIONAL_ATTRIBUTES,
+};
 use super::regexes::RegexPattern;
 use super::scoring::{class_weight, link_density};
-use super::{dom, markdown};
+use super::{dates, dom, markdown, patterns, tracking};
 
 pub fn cleanup_article(
     nodes: &[NodeRef], opts: &ReadabilityOptions, flags: ExtractFlags, base_url: Option<&Url>, metadata: &Metadata,
-) {
+) -> (Vec<RuleRemovalDiagnostic>, Vec<DryRunRemovalDiagnostic>) {
+    if opts.cleanup_dry_run {
+        return dry_run_cleanup_article(nodes, opts, flags, base_url, metadata);
+    }
+
+    let mut tracker = RemovalTracker::default();
     for node in nodes {
         clean_styles(node);
         clean_unsafe_attrs(node);
         fix_lazy_images(node);
-        dom::remove_matching(
-            node,
-            "script, style, noscript, base, form, fieldset, footer, link, aside, nav, #toc, .toc, #jump-to-nav, .mw-jump, #siteSub, #contentSub",
-        );
-        apply_media_retention(node, opts.media_retention);
-        clean_embeds(node, opts.media_retention);
-        remove_media_player_chrome(node);
-        remove_gallery_chrome(node);
-        remove_app_doc_controls(node);
-        remove_rustdoc_controls(node);
-        remove_mdn_chrome(node);
-        remove_share_nodes(node);
-        remove_trailing_page_chrome(node);
-        clean_headers(node, metadata.title.as_deref(), flags);
-        clean_leading_article_metadata(node, metadata);
-        markdown::code::normalize_code_markup(node);
-        if flags.clean_conditionally {
-            clean_conditionally(node, opts, flags);
-        }
-        remove_empty_blocks(node);
+        if let Some(width) = opts.image_target_width {
+            markdown::media::select_responsive_images(node, width);
+        }
+        run_removal_rules(node, opts, flags, metadata, &mut tracker);
         fix_relative_urls(node, base_url);
+        tracking::clean_links(node, opts.link_cleanup);
         if !opts.keep_classes {
             clean_classes(node, opts);
         }
     }
+    (tracker.finish(), Vec::new())
 }
 
-pub fn remove_trailing_chrome_roots(roots: Vec<NodeRef>) -> Vec<NodeRef> {
+/// Run the removal rules against a copy of each root's document and annotate
+/// the elements they would remove instead of removing them.
+fn dry_run_cleanup_article(
+    nodes: &[NodeRef], opts: &ReadabilityOptions, flags: ExtractFlags, base_url: Option<&Url>, metadata: &Metadata,
+) -> (Vec<RuleRemovalDiagnostic>, Vec<DryRunRemovalDiagnostic>) {
+    let mut marked = HashMap::new();
+    for node in nodes {
+        clean_styles(node);
+        clean_unsafe_attrs(node);
+        fix_lazy_images(node);
+        if let Some(width) = opts.image_target_width {
+            markdown::media::select_responsive_images(node, width);
+        }
+        for element in node.inclusive_descendants().elements() {
+            let marker = marked.len().to_string();
+            element.attributes.borrow_mut().insert(DRY_RUN_MARKER, marker.clone());
+            marked.insert(marker, element.as_node().clone());
+        }
+    }
+
+    // Copy whole documents so rules that look above the root behave the same.
+    let mut documents: Vec<(usize, NodeRef)> = Vec::new();
+    let mut tracker = RemovalTracker::recording();
+    for node in nodes {
+        let document = node.inclusive_ancestors().last().unwrap_or_else(|| node.clone());
+        let copy = match documents.iter().find(|(id, _)| *id == dom::node_id(&document)) {
+            Some((_, copy)) => copy.clone(),
+            None => {
+                let copy = dom::deep_clone(&document);
+                documents.push((dom::node_id(&document), copy.clone()));
+                copy
+            }
+        };
+        let Some(marker) = dom::attr(node, DRY_RUN_MARKER) else {
+            continue;
+        };
+        let Some(copy_root) = copy
+            .inclusive_descendants()
+            .find(|candidate| dom::attr(candidate, DRY_RUN_MARKER).as_deref() == Some(marker.as_str()))
+        else {
+            continue;
+        };
+        run_removal_rules(&copy_root, opts, flags, metadata, &mut tracker);
+    }
+
+    let (rules, removed) = tracker.finish_recording();
+    let removals = removed
+        .into_iter()
+        .filter_map(|(marker, rule)| {
+            let element = marked.get(&marker)?;
+            dom::set_attr(element, DRY_RUN_REMOVED, &rule);
+            Some(DryRunRemovalDiagnostic {
+                selector: node_selector(element),
+                text_len: element.text_contents().chars().count(),
+                rule,
+            })
+        })
+        .collect();
+
+    for node in nodes {
+        for element in node.inclusive_descendants().elements() {
+            element.attributes.borrow_mut().remove(DRY_RUN_MARKER);
+        }
+        fix_relative_urls(node, base_url);
+        tracking::clean_links(node, opts.link_cleanup);
+        if !opts.keep_classes {
+            clean_classes(node, opts);
+        }
+    }
+    (rules, removals)
+}
+
+/// Cleanup steps that remove content, tracked by rule name.
+fn run_removal_rules(
+    node: &NodeRef, opts: &ReadabilityOptions, flags: ExtractFlags, metadata: &Metadata, tracker: &mut RemovalTracker,
+) {
+    tracker.track("page-chrome-tags", node, || {
+        dom::remove_matching(
+            node,
+            "script, style, noscript, base, form, fieldset, footer, link, aside, nav, #toc, .toc, #jump-to-nav, .mw-jump, #siteSub, #contentSub",
+        )
+    });
+    tracker.track("media-retention", node, || {
+        apply_media_retention(node, opts.media_retention)
+    });
+    tracker.track("embeds", node, || clean_embeds(node, opts));
+    tracker.track("media-player-chrome", node, || remove_media_player_chrome(node));
+    tracker.track("gallery-chrome", node, || remove_gallery_chrome(node));
+    tracker.track("app-doc-controls", node, || remove_app_doc_controls(node, opts));
+    tracker.track("rustdoc-controls", node, || remove_rustdoc_controls(node));
+    tracker.track("mdn-chrome", node, || remove_mdn_chrome(node));
+    tracker.track("share", node, || remove_share_nodes(node));
+    tracker.track("trailing-chrome", node, || remove_trailing_page_chrome(node, opts));
+    tracker.track("author-bio", node, || remove_author_bio(node));
+    tracker.track("headers", node, || {
+        clean_headers(node, metadata.title.as_deref(), opts, flags)
+    });
+    tracker.track("leading-metadata", node, || {
+        clean_leading_article_metadata(node, metadata)
+    });
+    tracker.track("code-chrome", node, || markdown::code::normalize_code_markup(node));
+    if flags.clean_conditionally {
+        tracker.track("conditional", node, || clean_conditionally(node, opts, flags));
+    }
+    if matches!(opts.footnotes, FootnoteMode::Strip) {
+        tracker.track("footnotes", node, || markdown::footnotes::strip_footnotes(node));
+    }
+    tracker.track("empty-blocks", node, || remove_empty_blocks(node));
+}
+
+pub fn remove_trailing_chrome_roots(roots: Vec<NodeRef>, opts: &ReadabilityOptions) -> Vec<NodeRef> {
     let mut retained = Vec::with_capacity(roots.len());
     let mut trimming = true;
 
     for root in roots.into_iter().rev() {
-        if trimming && is_trailing_page_chrome(&root) {
+        if trimming && is_trailing_page_chrome(&root, opts) {
             continue;
         }
         if is_footnote_or_reference_block(&root) || has_meaningful_article_content(&root) {
@@ -88,20 +199,37 @@ fn apply_media_retention(root: &NodeRef, retention: MediaRetention) {
     }
 }
 
-fn clean_embeds(root: &NodeRef, retention: MediaRetention) {
-    if matches!(retention, MediaRetention::None) {
+fn clean_embeds(root: &NodeRef, opts: &ReadabilityOptions) {
+    if matches!(opts.media_retention, MediaRetention::None) {
         return;
     }
 
     for node in dom::select_nodes(root, "object, embed, iframe") {
-        let keep = dom::attrs(&node).values().any(|value| allowed_video(value));
+        let is_iframe = dom::node_name(&node) == "iframe";
+        let keep = dom::attrs(&node).values().any(|value| allowed_video(value))
+            || (is_iframe && opts.keep_iframes)
+            || (is_iframe
+                && dom::attr(&node, "src").is_some_and(|src| allowlisted_iframe(&src, &opts.iframe_allowlist)));
         if !keep {
             node.detach();
         }
     }
 }
 
-fn allowed_video(value: &str) -> bool {
+fn allowlisted_iframe(src: &str, allowlist: &[String]) -> bool {
+    let Some(host) = Url::parse(src.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+    else {
+        return false;
+    };
+    allowlist.iter().any(|pattern| {
+        let pattern = pattern.trim().trim_start_matches("*.").to_ascii_lowercase();
+        !pattern.is_empty() && (host == pattern || host.ends_with(&format!(".{pattern}")))
+    })
+}
+
+pub fn allowed_video(value: &str) -> bool {
     let value = value.to_ascii_lowercase();
     [
         "youtube.com",
@@ -145,7 +273,7 @@ fn is_gallery_chrome(node: &NodeRef) -> bool {
         return false;
     }
 
-    let item_count = dom::select_nodes(node, "[data-gallery-item], [data-gallery-legend], .slide").len();
+    let item_count = dom::select_iter(node, "[data-gallery-item], [data-gallery-legend], .slide").count();
     if item_count >= 3 {
         return true;
     }
@@ -218,9 +346,19 @@ fn remove_share_nodes(root: &NodeRef) {
     }
 }
 
-fn remove_app_doc_controls(root: &NodeRef) {
+/// Remove "About the author" boxes; their text is kept as
+/// [`Metadata::author_bio`].
+fn remove_author_bio(root: &NodeRef) {
+    for node in dom::select_nodes(root, AUTHOR_BIO_SELECTOR) {
+        if dom::inner_text(&node).chars().count() <= MAX_AUTHOR_BIO_CHARS {
+            node.detach();
+        }
+    }
+}
+
+fn remove_app_doc_controls(root: &NodeRef, opts: &ReadabilityOptions) {
     for node in dom::select_nodes(root, "button, [role='button']") {
-        if is_doc_control_button(&node) {
+        if is_doc_control_button(&node, opts) {
             node.detach();
         }
     }
@@ -232,22 +370,17 @@ fn remove_app_doc_controls(root: &NodeRef) {
     }
 }
 
-fn is_doc_control_button(node: &NodeRef) -> bool {
+fn is_doc_control_button(node: &NodeRef, opts: &ReadabilityOptions) -> bool {
     let label = dom::attr(node, "aria-label")
         .or_else(|| dom::attr(node, "title"))
         .unwrap_or_else(|| dom::inner_text(node));
-    let label = super::patterns::normalize_spaces(&label).to_ascii_lowercase();
-    matches!(
-        label.as_str(),
-        "copy" | "copy code" | "copied" | "copied!" | "copy page" | "copy page contents"
-    )
+    let label = patterns::normalize_spaces(&label);
+    patterns::matches_group(PatternGroup::DocChrome, label.trim(), opts)
 }
 
 fn is_orphan_doc_tablist(node: &NodeRef) -> bool {
     let text_len = dom::inner_text(node).chars().count();
-    text_len <= 120
-        && dom::select_nodes(node, "pre, code").is_empty()
-        && !dom::select_nodes(node, "[role='tab'], button").is_empty()
+    text_len <= 120 && !dom::exists(node, "pre, code") && dom::exists(node, "[role='tab'], button")
 }
 
 fn remove_rustdoc_controls(root: &NodeRef) {
@@ -285,7 +418,7 @@ fn remove_mdn_chrome(root: &NodeRef) {
     }
 }
 
-fn remove_trailing_page_chrome(root: &NodeRef) {
+fn remove_trailing_page_chrome(root: &NodeRef, opts: &ReadabilityOptions) {
     let mut trimming = true;
     for child in root
         .children()
@@ -297,7 +430,7 @@ fn remove_trailing_page_chrome(root: &NodeRef) {
         if !trimming {
             break;
         }
-        if is_trailing_page_chrome(&child) {
+        if is_trailing_page_chrome(&child, opts) {
             child.detach();
         } else if is_footnote_or_reference_block(&child) || has_meaningful_article_content(&child) {
             trimming = false;
@@ -306,12 +439,12 @@ fn remove_trailing_page_chrome(root: &NodeRef) {
 
     for child in root.children().filter(|node| node.as_element().is_some()) {
         if !is_footnote_or_reference_block(&child) {
-            remove_trailing_page_chrome(&child);
+            remove_trailing_page_chrome(&child, opts);
         }
     }
 }
 
-fn is_trailing_page_chrome(node: &NodeRef) -> bool {
+fn is_trailing_page_chrome(node: &NodeRef, opts: &ReadabilityOptions) -> bool {
     if is_footnote_or_reference_block(node) {
         return false;
     }
@@ -325,7 +458,7 @@ fn is_trailing_page_chrome(node: &NodeRef) -> bool {
     }
 
     let attrs = trailing_signal_attrs(node);
-    if RegexPattern::TrailingChromeAttrs.to_regex().is_match(&attrs) {
+    if patterns::matches_group(PatternGroup::TrailingChrome, &attrs, opts) {
         return true;
     }
 
@@ -348,7 +481,7 @@ fn is_trailing_page_chrome(node: &NodeRef) -> bool {
         return true;
     }
 
-    let link_count = dom::select_nodes(node, "a").len();
+    let link_count = dom::select_iter(node, "a").count();
     if !is_list_card_body(node)
         && !matches!(tag.as_str(), "ul" | "ol" | "li")
         && link_count >= 3
@@ -358,7 +491,7 @@ fn is_trailing_page_chrome(node: &NodeRef) -> bool {
         return true;
     }
 
-    let input_count = dom::select_nodes(node, "input, button, select, textarea").len();
+    let input_count = dom::select_iter(node, "input, button, select, textarea").count();
     input_count > 0 && text_len < 700 && looks_like_signup_text(&text)
 }
 
@@ -368,7 +501,7 @@ fn looks_like_related_card_list(node: &NodeRef, text: &str) -> bool {
         return true;
     }
 
-    if dom::node_name(node) != "section" || dom::select_nodes(node, "h3").len() < 3 {
+    if dom::node_name(node) != "section" || dom::select_iter(node, "h3").count() < 3 {
         return false;
     }
 
@@ -380,12 +513,11 @@ fn looks_like_related_card_list(node: &NodeRef, text: &str) -> bool {
 fn is_article_continuation(node: &NodeRef) -> bool {
     let attrs = trailing_signal_attrs(node);
     (attrs.contains("read-more") || attrs.contains("continue-reading"))
-        && dom::select_nodes(
+        && dom::select_iter(
             node,
             "#read-more-content, .read-more-content, .continue-reading-content",
         )
-        .into_iter()
-        .any(|content| dom::select_nodes(&content, "p").len() >= 3)
+        .any(|content| dom::select_iter(&content, "p").count() >= 3)
 }
 
 fn is_footnote_or_reference_block(node: &NodeRef) -> bool {
@@ -400,19 +532,17 @@ fn is_footnote_or_reference_block(node: &NodeRef) -> bool {
         return true;
     }
 
-    dom::select_nodes(node, "h1, h2, h3, h4, h5, h6")
-        .first()
-        .is_some_and(|heading| {
-            RegexPattern::FootnoteReferenceText
-                .to_regex()
-                .is_match(&dom::inner_text(heading))
-        })
+    dom::select_first(node, "h1, h2, h3, h4, h5, h6").is_some_and(|heading| {
+        RegexPattern::FootnoteReferenceText
+            .to_regex()
+            .is_match(&dom::inner_text(&heading))
+    })
 }
 
 fn has_meaningful_article_content(node: &NodeRef) -> bool {
     let tag = dom::node_name(node);
     if matches!(tag.as_str(), "p" | "pre" | "blockquote" | "table" | "figure") {
-        return !dom::inner_text(node).is_empty() || !dom::select_nodes(node, "img, picture, video, audio").is_empty();
+        return !dom::inner_text(node).is_empty() || dom::exists(node, "img, picture, video, audio");
     }
 
     let text = dom::inner_text(node);
@@ -420,7 +550,7 @@ fn has_meaningful_article_content(node: &NodeRef) -> bool {
         return true;
     }
 
-    !dom::select_nodes(node, "p, pre, blockquote, table, figure, img, picture, video, audio").is_empty()
+    dom::exists(node, "p, pre, blockquote, table, figure, img, picture, video, audio")
 }
 
 fn trailing_signal_attrs(node: &NodeRef) -> String {
@@ -445,9 +575,9 @@ fn looks_like_signup_text(text: &str) -> bool {
         .any(|needle| lower.contains(needle))
 }
 
-fn clean_headers(root: &NodeRef, article_title: Option<&str>, flags: ExtractFlags) {
+fn clean_headers(root: &NodeRef, article_title: Option<&str>, options: &ReadabilityOptions, flags: ExtractFlags) {
     for node in dom::select_nodes(root, "h1, h2") {
-        let low_weight = class_weight(&node, flags) < 0;
+        let low_weight = class_weight(&node, flags, options) < 0;
         let duplicates_title = article_title
             .map(|title| text_similarity(title, &dom::inner_text(&node)) > 0.75)
             .unwrap_or(false);
@@ -480,6 +610,9 @@ fn clean_leading_article_metadata(root: &NodeRef, metadata: &Metadata) {
         }
     }
 
+    remove_metadata_bylines(root, metadata);
+    remove_metadata_datelines(root, metadata);
+
     trim_leading_metadata_siblings(root, metadata);
     for child in root.children().filter(|node| node.as_element().is_some()) {
         if is_prose_container(&child) {
@@ -491,6 +624,91 @@ fn clean_leading_article_metadata(root: &NodeRef, metadata: &Metadata) {
     }
 }
 
+/// Remove author markers anywhere in the body when their block only repeats
+/// the extracted byline, e.g. `<p>By <a rel="author">Jane Doe</a></p>`.
+fn remove_metadata_bylines(root: &NodeRef, metadata: &Metadata) {
+    let Some(byline) = metadata.byline.as_deref().filter(|byline| !byline.trim().is_empty()) else {
+        return;
+    };
+    let max_len = byline.chars().count() + 40;
+    let root_id = dom::node_id(root);
+
+    for marker in dom::select_nodes(
+        root,
+        r#"[rel~="author"], [itemprop~="author"], .byline, .dateline, .author-name"#,
+    ) {
+        if dom::node_id(&marker) == root_id || marker.parent().is_none() {
+            continue;
+        }
+        let mut block = marker;
+        while let Some(parent) = block.parent() {
+            if dom::node_id(&parent) == root_id || dom::inner_text(&parent).chars().count() > max_len {
+                break;
+            }
+            block = parent;
+        }
+        let text = dom::inner_text(&block);
+        if text.chars().count() <= max_len && matches_metadata_value(&text, Some(byline)) {
+            block.detach();
+        }
+    }
+}
+
+/// Remove a dateline before the article text when it repeats the published
+/// date, e.g. `<p><em>Published March 1, 2026</em></p>` or a `<time>` whose
+/// `datetime` falls on that day. Dates inside a sentence are kept.
+fn remove_metadata_datelines(root: &NodeRef, metadata: &Metadata) {
+    let Some(published) = metadata.published.map(|published| published.date_naive()) else {
+        return;
+    };
+    let root_id = dom::node_id(root);
+    let mut leading_text = 0;
+    let mut datelines: Vec<NodeRef> = Vec::new();
+
+    for node in root.descendants() {
+        if let Some(text) = node.as_text() {
+            leading_text += text.borrow().trim().chars().count();
+            if leading_text > MAX_DATELINE_OFFSET {
+                break;
+            }
+            continue;
+        }
+        if node.as_element().is_none() || dom::node_id(&node) == root_id {
+            continue;
+        }
+        let text = dom::inner_text(&node);
+        if text.chars().count() > 80 {
+            continue;
+        }
+        let date = dom::attr(&node, "datetime")
+            .and_then(|datetime| dates::parse_date(&datetime))
+            .or_else(|| dates::parse_date(&text));
+        if date.is_none_or(|date| date.date_naive() != published) {
+            continue;
+        }
+        let mut block = node;
+        while let Some(parent) = block.parent() {
+            if dom::node_id(&parent) == root_id || dom::inner_text(&parent) != text {
+                break;
+            }
+            block = parent;
+        }
+        let in_sentence = block
+            .parent()
+            .is_some_and(|parent| dom::node_id(&parent) != root_id && is_prose_container(&parent));
+        if !in_sentence
+            && !datelines
+                .iter()
+                .any(|dateline| dom::node_id(dateline) == dom::node_id(&block))
+        {
+            datelines.push(block);
+        }
+    }
+    for dateline in datelines {
+        dateline.detach();
+    }
+}
+
 fn is_prose_container(node: &NodeRef) -> bool {
     matches!(
         dom::node_name(node).as_str(),
@@ -545,10 +763,8 @@ fn looks_like_article_header(node: &NodeRef, metadata: &Metadata) -> bool {
     if is_leading_date_node(node) {
         return true;
     }
-    !dom::select_nodes(node, "h1, h2, time, address, figure, img, picture").is_empty()
-        && dom::select_nodes(node, "p")
-            .into_iter()
-            .all(|paragraph| dom::inner_text(&paragraph).chars().count() < 120)
+    dom::exists(node, "h1, h2, time, address, figure, img, picture")
+        && dom::select_iter(node, "p").all(|paragraph| dom::inner_text(&paragraph).chars().count() < 120)
 }
 
 fn is_leading_metadata_node(node: &NodeRef, metadata: &Metadata) -> bool {
@@ -606,7 +822,7 @@ fn is_hero_media_node(node: &NodeRef) -> bool {
         return false;
     }
     let text_len = dom::inner_text(node).chars().count();
-    text_len < 240 && !dom::select_nodes(node, "img, picture, source").is_empty()
+    text_len < 240 && dom::exists(node, "img, picture, source")
 }
 
 fn is_byline_node(node: &NodeRef, metadata: &Metadata) -> bool {
@@ -628,7 +844,7 @@ fn is_byline_node(node: &NodeRef, metadata: &Metadata) -> bool {
 }
 
 fn contains_published_time(node: &NodeRef, metadata: &Metadata) -> bool {
-    if !dom::select_nodes(node, "time").is_empty() {
+    if dom::exists(node, "time") {
         return true;
     }
     let text = dom::inner_text(node);
@@ -716,13 +932,13 @@ fn clean_conditionally(root: &NodeRef, options: &ReadabilityOptions, flags: Extr
             continue;
         }
 
-        let weight = class_weight(&node, flags);
+        let weight = class_weight(&node, flags, options);
         let density = link_density(&node) + options.link_density_modifier as f64;
-        let p_count = dom::select_nodes(&node, "p").len();
-        let img_count = dom::select_nodes(&node, "img").len();
-        let li_count = dom::select_nodes(&node, "li").len().saturating_sub(100);
-        let input_count = dom::select_nodes(&node, "input").len();
-        let embed_count = dom::select_nodes(&node, "object, embed, iframe").len();
+        let p_count = dom::select_iter(&node, "p").count();
+        let img_count = dom::select_iter(&node, "img").count();
+        let li_count = dom::select_iter(&node, "li").count().saturating_sub(100);
+        let input_count = dom::select_iter(&node, "input").count();
+        let embed_count = dom::select_iter(&node, "object, embed, iframe").count();
         let comma_count = RegexPattern::Comma.to_regex().find_iter(&text).count();
         let is_list = is_list_like(&node);
         let is_article_media = match options.media_retention {
@@ -759,16 +975,14 @@ fn is_list_card_body(node: &NodeRef) -> bool {
         return false;
     };
 
-    dom::node_name(&parent) == "li"
-        && !dom::select_nodes(node, "p").is_empty()
-        && !dom::select_nodes(node, "a").is_empty()
+    dom::node_name(&parent) == "li" && dom::exists(node, "p") && dom::exists(node, "a")
 }
 
 fn has_media_descendant(node: &NodeRef) -> bool {
     matches!(
         dom::node_name(node).as_str(),
         "figure" | "picture" | "img" | "video" | "audio" | "iframe"
-    ) || !dom::select_nodes(node, "img, picture, video, audio, iframe").is_empty()
+    ) || dom::exists(node, "img, picture, video, audio, iframe")
 }
 
 fn is_article_media_container(node: &NodeRef) -> bool {
@@ -779,7 +993,7 @@ fn is_article_media_container(node: &NodeRef) -> bool {
         return true;
     }
 
-    if dom::select_nodes(node, "img, picture, video, audio, iframe").is_empty() {
+    if !dom::exists(node, "img, picture, video, audio, iframe") {
         return false;
     }
 
@@ -806,8 +1020,7 @@ fn is_list_like(node: &NodeRef) -> bool {
         return false;
     }
 
-    let list_len: usize = dom::select_nodes(node, "ul, ol")
-        .into_iter()
+    let list_len: usize = dom::select_iter(node, "ul, ol")
         .map(|list| dom::inner_text(&list).chars().count())
         .sum();
     (list_len as f64 / text_len as f64) > 0.9
@@ -822,16 +1035,13 @@ fn is_data_table(node: &NodeRef) -> bool {
         return false;
     }
 
-    if dom::attr(node, "summary").is_some()
-        || !dom::select_nodes(node, "caption, col, colgroup, tfoot, thead, th").is_empty()
-    {
+    if dom::attr(node, "summary").is_some() || dom::exists(node, "caption, col, colgroup, tfoot, thead, th") {
         return true;
     }
 
-    let rows = dom::select_nodes(node, "tr").len();
-    let columns = dom::select_nodes(node, "tr")
-        .into_iter()
-        .map(|row| dom::select_nodes(&row, "td, th").len())
+    let rows = dom::select_iter(node, "tr").count();
+    let columns = dom::select_iter(node, "tr")
+        .map(|row| dom::select_iter(&row, "td, th").count())
         .max()
         .unwrap_or(0);
     rows >= 2 && columns >= 2 && rows.saturating_mul(columns) >= 10
@@ -842,7 +1052,7 @@ fn remove_empty_blocks(root: &NodeRef) {
         if dom::node_id(&node) == dom::node_id(root) {
             continue;
         }
-        let has_media = !dom::select_nodes(&node, "img, iframe, video, audio, object, embed").is_empty();
+        let has_media = dom::exists(&node, "img, iframe, video, audio, object, embed");
         if !has_media && dom::inner_text(&node).trim().is_empty() {
             node.detach();
         }
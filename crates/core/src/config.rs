@@ -1,11 +1,16 @@
 use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
+use super::regexes::RegexPattern;
+
 /// Controls how much media remains in extracted article HTML and Markdown.
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
 pub enum MediaRetention {
     /// Remove media from extracted content.
     None,
@@ -52,12 +57,397 @@ impl FromStr for MediaRetention {
     }
 }
 
+/// Controls how footnote references and footnote lists are handled.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum FootnoteMode {
+    /// Keep footnotes and emit Markdown footnote syntax (`[^1]`).
+    #[default]
+    Markdown,
+    /// Remove reference markers and footnote lists from extracted content.
+    Strip,
+}
+
+impl FootnoteMode {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Strip => "strip",
+        }
+    }
+}
+
+impl fmt::Display for FootnoteMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FootnoteMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "markdown" => Ok(Self::Markdown),
+            "strip" => Ok(Self::Strip),
+            other => Err(format!("invalid footnote mode '{other}' (expected markdown or strip)")),
+        }
+    }
+}
+
+/// Controls how links are written in the article Markdown.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum LinkStyle {
+    /// Inline links such as `[text](https://example.com)`.
+    #[default]
+    Inline,
+    /// Reference links such as `[text][1]`, with definitions at the end.
+    Reference,
+    /// Link text followed by a numbered footnote holding the URL. Only
+    /// external `http` and `https` links are converted.
+    Footnotes,
+}
+
+impl LinkStyle {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Inline => "inline",
+            Self::Reference => "reference",
+            Self::Footnotes => "footnotes",
+        }
+    }
+}
+
+impl fmt::Display for LinkStyle {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LinkStyle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "inline" => Ok(Self::Inline),
+            "reference" => Ok(Self::Reference),
+            "footnotes" | "footnote" => Ok(Self::Footnotes),
+            other => Err(format!(
+                "invalid link style '{other}' (expected inline, reference, or footnotes)"
+            )),
+        }
+    }
+}
+
+/// Controls how tracking junk is removed from links in the article.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum LinkCleanup {
+    /// Leave links as they are.
+    #[default]
+    None,
+    /// Remove `utm_*`, `fbclid`, `gclid`, and other tracking query parameters.
+    StripTracking,
+    /// Also replace links through known redirectors, such as
+    /// `l.facebook.com` and Google's `/url`, with their destination.
+    UnwrapRedirects,
+}
+
+impl LinkCleanup {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::StripTracking => "strip-tracking",
+            Self::UnwrapRedirects => "unwrap-redirects",
+        }
+    }
+}
+
+impl fmt::Display for LinkCleanup {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LinkCleanup {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "strip-tracking" | "strip" => Ok(Self::StripTracking),
+            "unwrap-redirects" | "unwrap" => Ok(Self::UnwrapRedirects),
+            other => Err(format!(
+                "invalid link cleanup '{other}' (expected none, strip-tracking, or unwrap-redirects)"
+            )),
+        }
+    }
+}
+
+/// Controls quotes, dashes, and ellipses in extracted text.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Typography {
+    /// Leave punctuation as the page wrote it.
+    #[default]
+    Preserve,
+    /// Curl straight quotes and turn `--` and `...` into `—` and `…`.
+    Smart,
+    /// Straighten curly quotes and spell dashes and ellipses in ASCII.
+    Plain,
+}
+
+impl Typography {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Preserve => "preserve",
+            Self::Smart => "smart",
+            Self::Plain => "plain",
+        }
+    }
+}
+
+impl fmt::Display for Typography {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Typography {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "preserve" => Ok(Self::Preserve),
+            "smart" => Ok(Self::Smart),
+            "plain" | "ascii" => Ok(Self::Plain),
+            other => Err(format!(
+                "invalid typography '{other}' (expected preserve, smart, or plain)"
+            )),
+        }
+    }
+}
+
+/// Selects how the article root is found.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ExtractionStrategy {
+    /// Score candidate roots, falling back to whole-body cleanup when nothing passes.
+    #[default]
+    Auto,
+    /// Skip candidate selection and clean the whole `<body>`.
+    FullBodyCleanup,
+    /// Extract every update of a live blog in page order with its timestamp,
+    /// even when the page does not mark itself as a live blog.
+    LiveBlog,
+    /// Extract the transcript of a video or podcast page, even when the page
+    /// has other prose.
+    Transcript,
+    /// Skip candidate scoring and keep the longest run of text-dense blocks
+    /// with few links, Boilerpipe style. Suits forums and documentation pages
+    /// whose markup scores poorly.
+    Density,
+}
+
+impl ExtractionStrategy {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::FullBodyCleanup => "full-body-cleanup",
+            Self::LiveBlog => "live-blog",
+            Self::Transcript => "transcript",
+            Self::Density => "density",
+        }
+    }
+}
+
+impl fmt::Display for ExtractionStrategy {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExtractionStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "full-body-cleanup" | "full-body" => Ok(Self::FullBodyCleanup),
+            "live-blog" | "liveblog" => Ok(Self::LiveBlog),
+            "transcript" => Ok(Self::Transcript),
+            "density" | "text-density" => Ok(Self::Density),
+            other => Err(format!(
+                "invalid extraction strategy '{other}' (expected auto, full-body-cleanup, live-blog, transcript, or density)"
+            )),
+        }
+    }
+}
+
+/// A named group of built-in patterns that decides what extraction keeps.
+///
+/// Each group has a built-in regular expression, [`PatternGroup::builtin`].
+/// [`ReadabilityOptions`] adds patterns to a group through the field
+/// [`PatternGroup::extra`] reads, and drops the built-ins of the groups in
+/// [`ReadabilityOptions::replace_pattern_groups`], so only the added patterns
+/// match.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum PatternGroup {
+    /// Class or id names that raise a candidate's score.
+    Positive,
+    /// Class or id names that lower a candidate's score.
+    Negative,
+    /// Class or id names of page chrome stripped before scoring. The built-in
+    /// patterns spare an element whose class or id also looks like content.
+    Unlikely,
+    /// Class, id, role, or label of blocks such as newsletters and
+    /// recommendations, trimmed from the end of the article.
+    TrailingChrome,
+    /// Labels of documentation-page controls, such as "Copy code" buttons,
+    /// removed from the article.
+    DocChrome,
+}
+
+impl PatternGroup {
+    /// Every group, in a stable order.
+    pub const ALL: [Self; 5] = [
+        Self::Positive,
+        Self::Negative,
+        Self::Unlikely,
+        Self::TrailingChrome,
+        Self::DocChrome,
+    ];
+
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Negative => "negative",
+            Self::Unlikely => "unlikely",
+            Self::TrailingChrome => "trailing-chrome",
+            Self::DocChrome => "doc-chrome",
+        }
+    }
+
+    /// The group's built-in regular expression.
+    pub fn builtin(self) -> &'static str {
+        self.regex().to_regex().as_str()
+    }
+
+    /// The patterns `options` adds to the group. They are case-insensitive
+    /// substrings, except for [`PatternGroup::DocChrome`], whose patterns are
+    /// whole control labels.
+    pub fn extra(self, options: &ReadabilityOptions) -> &[String] {
+        match self {
+            Self::Positive => &options.positive_patterns,
+            Self::Negative => &options.negative_patterns,
+            Self::Unlikely => &options.unlikely_patterns,
+            Self::TrailingChrome => &options.trailing_chrome_patterns,
+            Self::DocChrome => &options.doc_chrome_labels,
+        }
+    }
+
+    pub(crate) fn regex(self) -> RegexPattern {
+        match self {
+            Self::Positive => RegexPattern::Positive,
+            Self::Negative => RegexPattern::Negative,
+            Self::Unlikely => RegexPattern::UnlikelyCandidates,
+            Self::TrailingChrome => RegexPattern::TrailingChromeAttrs,
+            Self::DocChrome => RegexPattern::DocChromeLabel,
+        }
+    }
+}
+
+impl fmt::Display for PatternGroup {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PatternGroup {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|group| group.as_str() == value).ok_or_else(|| {
+            format!(
+                "invalid pattern group '{value}' (expected positive, negative, unlikely, trailing-chrome, or doc-chrome)"
+            )
+        })
+    }
+}
+
+/// Named starting points for [`ReadabilityOptions`], tuned for a kind of page.
+///
+/// See [`ReadabilityOptions::preset`] for what each preset changes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Preset {
+    /// News and magazine articles with related-story rails and signup promos.
+    News,
+    /// Documentation and reference pages: short, link-heavy, split into sections.
+    Docs,
+    /// Forum threads and discussion pages made of many short posts.
+    Forum,
+    /// Text-first output without media, footnotes, or inline links.
+    Minimal,
+}
+
+impl Preset {
+    /// Returns the stable string form used by CLI flags and serialized options.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::News => "news",
+            Self::Docs => "docs",
+            Self::Forum => "forum",
+            Self::Minimal => "minimal",
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "news" => Ok(Self::News),
+            "docs" => Ok(Self::Docs),
+            "forum" => Ok(Self::Forum),
+            "minimal" => Ok(Self::Minimal),
+            other => Err(format!(
+                "invalid preset '{other}' (expected news, docs, forum, or minimal)"
+            )),
+        }
+    }
+}
+
 /// Options for full article extraction.
 ///
 /// Defaults are intended for article pages.
 ///
 /// Set only the fields that solve a specific input problem.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct ReadabilityOptions {
     /// Reject documents above this element count before extraction work starts.
     pub max_elems_to_parse: Option<usize>,
@@ -71,8 +461,21 @@ pub struct ReadabilityOptions {
     /// handling, URL rewriting, Markdown conversion, and diagnostics still run.
     pub content_selector: Option<String>,
     /// TOML site profiles used as URL-scoped extraction hints.
+    ///
+    /// Each source is parsed once per process and shared between threads.
     #[serde(default)]
     pub site_profiles: Vec<String>,
+    /// `directive=value` assignments applied to the matched site profile for
+    /// this run, such as `prune=no` or `remove=.promo`.
+    ///
+    /// Repeating a list directive collects every value.
+    #[serde(default)]
+    pub site_profile_overrides: Vec<String>,
+    /// Site profile directives to disable for this run, such as
+    /// `remove_id_or_class`. Lists are cleared and switches revert to their
+    /// defaults.
+    #[serde(default)]
+    pub site_profile_ignores: Vec<String>,
     /// Viewport width used to recover content hidden behind mobile CSS rules.
     pub mobile_viewport_width: Option<usize>,
     /// Class names kept during cleanup when `keep_classes` is false.
@@ -86,6 +489,118 @@ pub struct ReadabilityOptions {
     /// Controls whether images, figures, and embeds survive cleanup.
     #[serde(default)]
     pub media_retention: MediaRetention,
+    /// Extra iframe hosts kept during cleanup, alongside the built-in video hosts.
+    ///
+    /// `example.com` matches that host and its subdomains.
+    #[serde(default)]
+    pub iframe_allowlist: Vec<String>,
+    /// Controls whether footnotes survive cleanup.
+    #[serde(default)]
+    pub footnotes: FootnoteMode,
+    /// Controls how links are written in the article Markdown.
+    #[serde(default)]
+    pub link_style: LinkStyle,
+    /// Controls whether tracking parameters and redirectors are removed from
+    /// links in the article.
+    #[serde(default)]
+    pub link_cleanup: LinkCleanup,
+    /// Levels below an H1 title at which the Markdown's highest heading
+    /// starts. `Some(1)` makes it an H2; `None` keeps the source levels.
+    #[serde(default)]
+    pub heading_offset: Option<u8>,
+    /// Close skipped heading levels in the Markdown, so an H2 is followed by
+    /// an H3 rather than an H4.
+    #[serde(default)]
+    pub normalize_heading_levels: bool,
+    /// Mark each Markdown heading with the reading time before it, such as
+    /// `*~12 min in*`.
+    #[serde(default)]
+    pub reading_time_markers: bool,
+    /// Clean article text: decode leftover HTML entities, apply Unicode NFC
+    /// normalization, and drop zero-width spaces and soft hyphens.
+    #[serde(default)]
+    pub normalize_text: bool,
+    /// Controls quotes, dashes, and ellipses in article text. Code is left alone.
+    #[serde(default)]
+    pub typography: Typography,
+    /// Controls how the article root is found.
+    #[serde(default)]
+    pub strategy: ExtractionStrategy,
+    /// Annotate elements cleanup would remove instead of removing them.
+    ///
+    /// Affected elements get a `data-lectito-removed="<rule>"` attribute and
+    /// are listed in the cleanup diagnostics.
+    #[serde(default)]
+    pub cleanup_dry_run: bool,
+    /// Keep elements hidden with `hidden`, `aria-hidden`, or inline styles.
+    #[serde(default)]
+    pub keep_hidden: bool,
+    /// Keep elements whose class or id looks like page chrome, such as
+    /// `sidebar` or `comment`, instead of stripping them before scoring.
+    #[serde(default)]
+    pub keep_unlikely_candidates: bool,
+    /// Keep every iframe during embed cleanup, not just video and allowlisted hosts.
+    #[serde(default)]
+    pub keep_iframes: bool,
+    /// Share of the top candidate's score a sibling needs to be merged into
+    /// the article. The cutoff never drops below 10 points.
+    #[serde(default = "default_sibling_threshold")]
+    pub sibling_threshold: f32,
+    /// Candidates scoring below this, before link-density scaling, are
+    /// ignored. When none remain, the body is scored instead.
+    #[serde(default)]
+    pub min_candidate_score: f32,
+    /// Points added for a positive class or id match and subtracted for a
+    /// negative one.
+    #[serde(default = "default_class_weight")]
+    pub class_weight: i32,
+    /// Extra class or id substrings treated as positive signals.
+    #[serde(default)]
+    pub positive_patterns: Vec<String>,
+    /// Extra class or id substrings treated as negative signals.
+    #[serde(default)]
+    pub negative_patterns: Vec<String>,
+    /// Extra class or id substrings that mark page chrome to strip before
+    /// scoring, alongside the built-in unlikely-candidate patterns.
+    #[serde(default)]
+    pub unlikely_patterns: Vec<String>,
+    /// Extra class, id, role, or label substrings that mark blocks to trim
+    /// from the end of the article, such as a site's feedback widget.
+    #[serde(default)]
+    pub trailing_chrome_patterns: Vec<String>,
+    /// Extra labels of documentation-page controls to remove, such as
+    /// "Edit this page". Matched against the whole label, ignoring case.
+    #[serde(default)]
+    pub doc_chrome_labels: Vec<String>,
+    /// Pattern groups whose built-in patterns are dropped, leaving only the
+    /// patterns these options add. A replaced group with no added patterns
+    /// matches nothing.
+    #[serde(default)]
+    pub replace_pattern_groups: Vec<PatternGroup>,
+    /// Display width, in pixels, to fit `<picture>` and `srcset` images to.
+    /// When set, each keeps only its narrowest candidate at least this wide,
+    /// or its widest, in `src`. `None` leaves responsive images as they are.
+    #[serde(default)]
+    pub image_target_width: Option<u32>,
+    /// Reject inputs above this many bytes before parsing.
+    #[serde(default)]
+    pub max_input_bytes: Option<usize>,
+    /// Stop with [`crate::Error::MaxCandidatesExceeded`] when candidate
+    /// scoring finds more roots than this in one attempt.
+    #[serde(default)]
+    pub max_candidates: Option<usize>,
+    /// Wall-clock budget for one extraction. When it runs out, extraction
+    /// stops at the next stage or scoring batch with
+    /// [`crate::Error::BudgetExceeded`]. Serialized as `max_duration_ms`.
+    /// HTML parsing itself is not interrupted; pair this with
+    /// `max_elems_to_parse` for hostile input.
+    #[serde(default, rename = "max_duration_ms", with = "optional_milliseconds")]
+    pub max_duration: Option<Duration>,
+    /// Recover `<noscript>` fallbacks instead of only unwrapping images. A
+    /// fallback image replaces the lone placeholder image just before it,
+    /// and fallback text that is not an "enable JavaScript" notice is kept.
+    #[serde(default)]
+    pub recover_noscript: bool,
 }
 
 impl Default for ReadabilityOptions {
@@ -96,14 +611,69 @@ impl Default for ReadabilityOptions {
             char_threshold: 500,
             content_selector: None,
             site_profiles: Vec::new(),
+            site_profile_overrides: Vec::new(),
+            site_profile_ignores: Vec::new(),
             mobile_viewport_width: Some(480),
             classes_to_preserve: Vec::new(),
             keep_classes: false,
             disable_json_ld: false,
             link_density_modifier: 0.0,
             media_retention: MediaRetention::Article,
+            iframe_allowlist: Vec::new(),
+            footnotes: FootnoteMode::Markdown,
+            link_style: LinkStyle::Inline,
+            link_cleanup: LinkCleanup::None,
+            heading_offset: None,
+            normalize_heading_levels: false,
+            reading_time_markers: false,
+            normalize_text: false,
+            typography: Typography::Preserve,
+            strategy: ExtractionStrategy::Auto,
+            cleanup_dry_run: false,
+            keep_hidden: false,
+            keep_unlikely_candidates: false,
+            keep_iframes: false,
+            sibling_threshold: default_sibling_threshold(),
+            min_candidate_score: 0.0,
+            class_weight: default_class_weight(),
+            positive_patterns: Vec::new(),
+            negative_patterns: Vec::new(),
+            unlikely_patterns: Vec::new(),
+            trailing_chrome_patterns: Vec::new(),
+            doc_chrome_labels: Vec::new(),
+            replace_pattern_groups: Vec::new(),
+            image_target_width: None,
+            max_input_bytes: None,
+            max_candidates: None,
+            max_duration: None,
+            recover_noscript: false,
+        }
+    }
+}
+
+mod optional_milliseconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => serializer.serialize_some(&(duration.as_millis() as u64)),
+            None => serializer.serialize_none(),
         }
     }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
+fn default_sibling_threshold() -> f32 {
+    0.2
+}
+
+fn default_class_weight() -> i32 {
+    25
 }
 
 /// Options for the quick readability check.
@@ -111,6 +681,7 @@ impl Default for ReadabilityOptions {
 /// These options affect [`crate::is_probably_readable`] only. They do not
 /// change full extraction.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct ReadableOptions {
     /// Minimum text length for a block to count toward readability.
     pub min_content_length: usize,
@@ -126,6 +697,7 @@ impl Default for ReadableOptions {
 
 /// Options for Markdown-to-HTML rendering.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct MarkdownOptions {
     /// Enable GitHub Flavored Markdown extensions.
     pub gfm: bool,
@@ -144,12 +716,19 @@ impl Default for MarkdownOptions {
 }
 
 /// Extracted article content and metadata.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Article {
     /// Best title found in metadata or content.
     pub title: Option<String>,
+    /// Title as published, before separator and site-name cleanup.
+    pub raw_title: Option<String>,
     /// Author or byline when available.
     pub byline: Option<String>,
+    /// Text of the article's "About the author" box, or the author's
+    /// schema.org `description`. The box is removed from `content`.
+    #[serde(default)]
+    pub author_bio: Option<String>,
     /// Text direction from document metadata.
     pub dir: Option<String>,
     /// Language from document metadata.
@@ -168,12 +747,75 @@ pub struct Article {
     pub site_name: Option<String>,
     /// Published timestamp from metadata when available.
     pub published_time: Option<String>,
+    /// Last-modified timestamp from metadata when available.
+    pub modified_time: Option<String>,
+    /// `published_time` parsed into a date with its UTC offset.
+    #[serde(default, with = "crate::dates::rfc3339")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>", extend("format" = "date-time")))]
+    pub published: Option<DateTime<FixedOffset>>,
+    /// `modified_time` parsed into a date with its UTC offset.
+    #[serde(default, with = "crate::dates::rfc3339")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<String>", extend("format" = "date-time")))]
+    pub modified: Option<DateTime<FixedOffset>>,
     /// Lead image URL from metadata when available.
     pub image: Option<String>,
     /// Source domain inferred from the base URL.
     pub domain: Option<String>,
     /// Favicon URL from document metadata when available.
     pub favicon: Option<String>,
+    /// Absolute URL from `<link rel="canonical">`.
+    pub canonical_url: Option<String>,
+    /// Absolute URL from `<link rel="amphtml">`.
+    pub amp_url: Option<String>,
+    /// Keywords and tags from metadata, in source order.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Publication section, such as `Technology`.
+    pub section: Option<String>,
+    /// `Some(true)` when the page shows paywall signals, `Some(false)` when
+    /// structured data marks it free, and `None` when nothing says either way.
+    #[serde(default)]
+    pub is_paywalled: Option<bool>,
+    /// `Some(true)` when a `native_ad_clue` of the matched site profile
+    /// matches the page, `Some(false)` when the profile has clues and none
+    /// match, and `None` when no profile says either way.
+    #[serde(default)]
+    pub is_sponsored: Option<bool>,
+    /// Outbound links in the article, then links from the page's related
+    /// articles modules, without duplicate URLs.
+    #[serde(default)]
+    pub links: Vec<ArticleLink>,
+    /// Input and intermediate sizes, candidate counts, and stage timings.
+    #[serde(default)]
+    pub stats: crate::ExtractionStats,
+}
+
+/// A link found in or around the article.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ArticleLink {
+    /// Link text, or the `title` attribute when the link has none.
+    pub text: String,
+    /// Absolute `http` or `https` URL.
+    pub url: String,
+    /// Lowercased `rel` values, such as `nofollow` or `sponsored`.
+    #[serde(default)]
+    pub rel: Vec<String>,
+    /// Where the link was found.
+    pub kind: LinkKind,
+}
+
+/// Where an [`ArticleLink`] was found.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum LinkKind {
+    /// In the extracted article content.
+    Content,
+    /// In a related articles, read-more, or recommendation module.
+    Related,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
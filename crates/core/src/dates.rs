@@ -0,0 +1,143 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+
+use super::regexes::RegexPattern;
+
+const OFFSET_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S %z",
+];
+
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%B %d, %Y %H:%M",
+    "%B %d, %Y %I:%M %p",
+    "%d %B %Y %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%Y%m%d",
+    "%B %d, %Y",
+    "%B %d %Y",
+    "%b. %d, %Y",
+    "%d %B %Y",
+    "%d %B, %Y",
+    "%d %b. %Y",
+    "%A, %B %d, %Y",
+    "%A %d %B %Y",
+];
+
+/// Parse a published or modified date from metadata or page text.
+///
+/// Accepts RFC 3339/ISO 8601, RFC 2822, and common human formats such as
+/// `Published on May 1st, 2026`. Values without an offset are read as UTC.
+pub fn parse_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date);
+    }
+
+    let relaxed = RegexPattern::DateNoise
+        .to_regex()
+        .replace_all(value, |captures: &regex::Captures<'_>| {
+            captures
+                .get(3)
+                .map_or(String::new(), |digit| digit.as_str().to_string())
+        })
+        .replace(" at ", " ")
+        .trim_end_matches(['.', ','])
+        .trim()
+        .to_string();
+
+    OFFSET_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(&relaxed, format).ok())
+        .or_else(|| {
+            NAIVE_DATETIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&relaxed, format).ok())
+                .or_else(|| {
+                    DATE_FORMATS
+                        .iter()
+                        .find_map(|format| NaiveDate::parse_from_str(&relaxed, format).ok())
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|date| date.and_utc().fixed_offset())
+        })
+}
+
+/// Serde support for optional dates as RFC 3339 strings.
+pub mod rfc3339 {
+    use chrono::{DateTime, FixedOffset};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<DateTime<FixedOffset>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(date) => serializer.serialize_some(&date.to_rfc3339()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| DateTime::parse_from_rfc3339(&value).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc3339(value: &str) -> Option<String> {
+        parse_date(value).map(|date| date.to_rfc3339())
+    }
+
+    #[test]
+    fn parses_machine_formats() {
+        assert_eq!(
+            rfc3339("2026-05-01T12:00:00+02:00").as_deref(),
+            Some("2026-05-01T12:00:00+02:00")
+        );
+        assert_eq!(
+            rfc3339("Fri, 01 May 2026 12:00:00 GMT").as_deref(),
+            Some("2026-05-01T12:00:00+00:00")
+        );
+        assert_eq!(
+            rfc3339("2026-05-01T12:00:00+0200").as_deref(),
+            Some("2026-05-01T12:00:00+02:00")
+        );
+        assert_eq!(
+            rfc3339("2026-05-01 08:30").as_deref(),
+            Some("2026-05-01T08:30:00+00:00")
+        );
+        assert_eq!(rfc3339("2026-05-01").as_deref(), Some("2026-05-01T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn parses_human_formats() {
+        assert_eq!(
+            rfc3339("Published on May 1st, 2026").as_deref(),
+            Some("2026-05-01T00:00:00+00:00")
+        );
+        assert_eq!(rfc3339("1 May 2026").as_deref(), Some("2026-05-01T00:00:00+00:00"));
+        assert_eq!(rfc3339("Sep. 3, 2025").as_deref(), Some("2025-09-03T00:00:00+00:00"));
+        assert_eq!(
+            rfc3339("Updated: March 2, 2026 at 4:15 PM").as_deref(),
+            Some("2026-03-02T16:15:00+00:00")
+        );
+        assert_eq!(rfc3339("last week"), None);
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+
+use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+
+use super::dom;
+
+/// Elements that hold a text block when no other block element is inside them.
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "pre",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "dt",
+    "dd",
+    "td",
+    "th",
+    "figcaption",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "nav",
+    "aside",
+];
+
+const HEADING_TAGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Column width used to wrap block text into lines for text density.
+const LINE_WIDTH: usize = 80;
+
+/// Blocks with a larger share of words inside links are boilerplate.
+const MAX_LINK_DENSITY: f64 = 0.333;
+
+/// A preceding block this link-heavy needs a dense successor to keep content.
+const MAX_PREVIOUS_LINK_DENSITY: f64 = 0.555;
+
+/// A run of content blocks may cross this many boilerplate blocks without
+/// links, such as a short subheading or a photo credit.
+const MAX_GAP: usize = 1;
+
+struct Block {
+    node: NodeRef,
+    words: usize,
+    link_density: f64,
+    text_density: f64,
+    heading: bool,
+    content: bool,
+}
+
+impl Block {
+    fn new(node: NodeRef) -> Option<Self> {
+        let text = dom::inner_text(&node);
+        let words = text.split_whitespace().count();
+        if words == 0 {
+            return None;
+        }
+        let link_words: usize = dom::select_iter(&node, "a")
+            .map(|link| dom::inner_text(&link).split_whitespace().count())
+            .sum();
+        Some(Self {
+            heading: HEADING_TAGS.contains(&dom::node_name(&node).as_str()),
+            node,
+            words,
+            link_density: link_words.min(words) as f64 / words as f64,
+            text_density: text_density(&text),
+            content: false,
+        })
+    }
+}
+
+/// An `<article>` holding the page's main text, found by text density
+/// instead of candidate scoring.
+///
+/// The page is split into text blocks: the innermost block elements that
+/// hold text. Each block is classified as content or boilerplate from its
+/// link density and the text density of it and its neighbours, following
+/// Kohlschütter et al.'s "Boilerplate Detection using Shallow Text
+/// Features" (the model behind Boilerpipe). The longest run of content
+/// blocks, counted in words, is the article; a heading just before the run
+/// is kept as its title. List items and table cells bring their list or
+/// table along so the markup stays valid.
+pub fn density_root(document: &NodeRef) -> Option<NodeRef> {
+    let body = dom::select_first(document, "body")?;
+    let mut blocks: Vec<Block> = body
+        .inclusive_descendants()
+        .filter(is_leaf_block)
+        .filter_map(Block::new)
+        .collect();
+    classify(&mut blocks);
+    let run = longest_run(&blocks)?;
+
+    let mut nodes: Vec<NodeRef> = Vec::new();
+    let mut seen = HashSet::new();
+    for block in &blocks[run] {
+        let node = output_node(&block.node);
+        if seen.insert(dom::node_id(&node)) {
+            nodes.push(node);
+        }
+    }
+    let ids: HashSet<usize> = nodes.iter().map(dom::node_id).collect();
+    nodes.retain(|node| !node.ancestors().any(|ancestor| ids.contains(&dom::node_id(&ancestor))));
+
+    let page = kuchiki::parse_html().one("<html><body><article></article></body></html>");
+    let root = dom::select_first(&page, "article")?;
+    for node in nodes {
+        root.append(node);
+    }
+    Some(root)
+}
+
+fn is_leaf_block(node: &NodeRef) -> bool {
+    node.as_element().is_some()
+        && BLOCK_TAGS.contains(&dom::node_name(node).as_str())
+        && !node
+            .descendants()
+            .any(|inner| inner.as_element().is_some() && BLOCK_TAGS.contains(&dom::node_name(&inner).as_str()))
+}
+
+/// Words per line when the text is wrapped at [`LINE_WIDTH`] columns. The
+/// last line is left out unless it is the only one, since it is usually
+/// short.
+fn text_density(text: &str) -> f64 {
+    let mut lines = vec![0usize];
+    let mut column = 0;
+    for word in text.split_whitespace() {
+        let width = word.chars().count();
+        if column > 0 && column + 1 + width > LINE_WIDTH {
+            lines.push(0);
+            column = 0;
+        }
+        column += width + usize::from(column > 0);
+        if let Some(line) = lines.last_mut() {
+            *line += 1;
+        }
+    }
+    match lines.split_last() {
+        Some((last, [])) => *last as f64,
+        Some((_, full)) => full.iter().sum::<usize>() as f64 / full.len() as f64,
+        None => 0.0,
+    }
+}
+
+/// Boilerpipe's density rules: a block is content when its links are few
+/// and it, or the blocks around it, are dense with text.
+fn classify(blocks: &mut [Block]) {
+    for index in 0..blocks.len() {
+        let previous = index.checked_sub(1).map(|previous| &blocks[previous]);
+        let next = blocks.get(index + 1);
+        let (previous_links, previous_density) =
+            previous.map_or((0.0, 0.0), |block| (block.link_density, block.text_density));
+        let next_density = next.map_or(0.0, |block| block.text_density);
+        let block = &blocks[index];
+
+        let content = if block.link_density > MAX_LINK_DENSITY {
+            false
+        } else if previous_links > MAX_PREVIOUS_LINK_DENSITY {
+            next_density > 11.0
+        } else if block.text_density <= 9.0 {
+            next_density > 10.0 || previous_density > 4.0
+        } else {
+            next_density > 0.0
+        };
+        blocks[index].content = content;
+    }
+}
+
+/// The index range of the run of content blocks with the most words,
+/// including a heading just before it.
+fn longest_run(blocks: &[Block]) -> Option<std::ops::Range<usize>> {
+    let mut best: Option<(std::ops::Range<usize>, usize)> = None;
+    let mut index = 0;
+    while index < blocks.len() {
+        if !blocks[index].content {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let mut end = index + 1;
+        let mut words = blocks[index].words;
+        let mut cursor = end;
+        while cursor < blocks.len() {
+            let gap_end = (cursor..blocks.len().min(cursor + MAX_GAP + 1)).find(|&next| blocks[next].content);
+            let Some(next) = gap_end else {
+                break;
+            };
+            if blocks[cursor..next].iter().any(|block| block.link_density > 0.0) {
+                break;
+            }
+            words += blocks[next].words;
+            end = next + 1;
+            cursor = end;
+        }
+        if best.as_ref().is_none_or(|(_, best_words)| words > *best_words) {
+            let start = match start.checked_sub(1) {
+                Some(previous) if blocks[previous].heading && blocks[previous].link_density == 0.0 => previous,
+                _ => start,
+            };
+            best = Some((start..end, words));
+        }
+        index = end;
+    }
+    best.map(|(run, _)| run)
+}
+
+/// The element a block is copied out as: its list for list items, its
+/// table for cells, and otherwise the block itself.
+fn output_node(block: &NodeRef) -> NodeRef {
+    let container = match dom::node_name(block).as_str() {
+        "li" => &["ul", "ol"][..],
+        "dt" | "dd" => &["dl"][..],
+        "td" | "th" => &["table"][..],
+        _ => return block.clone(),
+    };
+    block
+        .ancestors()
+        .find(|ancestor| container.contains(&dom::node_name(ancestor).as_str()))
+        .filter(|ancestor| !dom::exists(ancestor, "table table"))
+        .unwrap_or_else(|| block.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROSE: &str = "The tide comes in twice a day, pulled by the moon and, to a lesser degree, the sun. \
+        Fishermen along the coast have timed their work to it for centuries, and the harbour still fills and \
+        empties on a schedule printed in the local paper every morning.";
+
+    #[test]
+    fn keeps_the_densest_run_of_text_blocks() {
+        let html = format!(
+            r#"<html><body>
+            <div class="menu"><a href="/">Home</a> <a href="/news">News</a> <a href="/about">About us</a></div>
+            <div class="story">
+              <h1>On Tides</h1>
+              <p>{PROSE}</p>
+              <p>{PROSE}</p>
+              <ul><li>{PROSE}</li><li>{PROSE}</li></ul>
+              <p>{PROSE}</p>
+            </div>
+            <div class="related"><a href="/a">Another story about the sea</a> <a href="/b">Harbour fees rise</a></div>
+            <div class="footer">Copyright 2026</div>
+            </body></html>"#
+        );
+        let document = kuchiki::parse_html().one(html);
+        let root = density_root(&document).unwrap();
+        let text = dom::inner_text(&root);
+
+        assert!(text.starts_with("On Tides"));
+        assert_eq!(text.matches("The tide comes in").count(), 5);
+        assert_eq!(dom::select_iter(&root, "ul > li").count(), 2);
+        assert!(!text.contains("Home"));
+        assert!(!text.contains("Harbour fees"));
+        assert!(!text.contains("Copyright"));
+    }
+
+    #[test]
+    fn text_density_ignores_the_last_wrapped_line() {
+        assert_eq!(text_density(""), 0.0);
+        assert_eq!(text_density("three short words"), 3.0);
+        let long = "word ".repeat(20);
+        assert_eq!(text_density(&long), 16.0);
+    }
+}
@@ -1,7 +1,13 @@
-use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use kuchiki::NodeRef;
+use kuchiki::iter::NodeIterator;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Details about how extraction selected, cleaned, and accepted article roots.
 #[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct ExtractionDiagnostics {
     /// Result of applying a caller-provided `content_selector`.
     pub content_selector: Option<ContentSelectorDiagnostic>,
@@ -13,11 +19,14 @@ pub struct ExtractionDiagnostics {
     pub selected_attempt: Option<usize>,
     /// Final extraction outcome.
     pub outcome: ExtractionOutcome,
+    /// Informational notes about the result that do not change the outcome.
+    pub warnings: Vec<String>,
 }
 
 /// Final status for an extraction report.
 #[derive(Clone, Debug, Default, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ExtractionOutcome {
     /// Extraction met the configured acceptance thresholds.
     Accepted,
@@ -30,15 +39,146 @@ pub enum ExtractionOutcome {
 
 /// Article output plus diagnostics for the decisions made during extraction.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct ExtractionReport {
     /// Extracted article, if any content was accepted.
     pub article: Option<crate::Article>,
     /// Root selection, cleanup, and fallback diagnostics.
     pub diagnostics: ExtractionDiagnostics,
+    /// Time spent in each extraction stage.
+    pub timings: Timings,
+}
+
+impl ExtractionReport {
+    pub(crate) fn new(article: Option<crate::Article>, diagnostics: ExtractionDiagnostics) -> Self {
+        Self { article, diagnostics, timings: Timings::default() }
+    }
+}
+
+/// Wall-clock time spent in each extraction stage.
+///
+/// Serialized as milliseconds (`parse_ms`, `extract_ms`, `postprocess_ms`).
+/// On `wasm32`, where no monotonic clock is available, every stage reads
+/// zero.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Timings {
+    /// Snapshot recovery, HTML parsing, and metadata extraction.
+    #[serde(
+        rename = "parse_ms",
+        serialize_with = "milliseconds",
+        deserialize_with = "from_milliseconds"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+    pub parse: Duration,
+    /// Root selection, scoring, cleanup, and conversion to Markdown.
+    #[serde(
+        rename = "extract_ms",
+        serialize_with = "milliseconds",
+        deserialize_with = "from_milliseconds"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+    pub extract: Duration,
+    /// Heading, reading-time, link-style, and paywall post-processing.
+    #[serde(
+        rename = "postprocess_ms",
+        serialize_with = "milliseconds",
+        deserialize_with = "from_milliseconds"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+    pub postprocess: Duration,
+}
+
+impl Timings {
+    /// Sum of every stage.
+    pub fn total(&self) -> Duration {
+        self.parse + self.extract + self.postprocess
+    }
+}
+
+fn milliseconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+fn from_milliseconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let milliseconds = f64::deserialize(deserializer)?;
+    Ok(Duration::try_from_secs_f64(milliseconds / 1000.0).unwrap_or_default())
+}
+
+/// Sizes, counts, and stage timings from extracting an article, for
+/// applications that report extraction cost.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ExtractionStats {
+    /// Bytes of HTML passed to extraction.
+    pub input_bytes: usize,
+    /// Bytes of HTML after snapshot recovery, less the script text removed
+    /// before the extraction attempts run.
+    pub preprocessed_bytes: usize,
+    /// Elements examined for candidate scoring, summed across attempts.
+    pub candidates_scanned: usize,
+    /// Elements that received a candidate score, summed across attempts.
+    pub candidates_scored: usize,
+    /// Elements removed by the site rule, preprocessing, and cleanup of the
+    /// selected attempt.
+    pub removed_elements: usize,
+    /// Time spent in each stage.
+    pub timings: Timings,
+}
+
+/// Measures elapsed time with `Instant`, except on `wasm32`, where reading
+/// the clock panics and elapsed time is always zero.
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    started: std::time::Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.started.elapsed();
+        #[cfg(target_arch = "wasm32")]
+        return Duration::ZERO;
+    }
+}
+
+/// Wall-clock budget from `ReadabilityOptions::max_duration`, checked
+/// between stages. Never expires on `wasm32`, where elapsed time is zero.
+pub(crate) struct Deadline {
+    started: Stopwatch,
+    limit: Option<Duration>,
+}
+
+impl Deadline {
+    pub(crate) fn start(limit: Option<Duration>) -> Self {
+        Self { started: Stopwatch::start(), limit }
+    }
+
+    /// Fail with [`crate::Error::BudgetExceeded`] once the budget is spent.
+    pub(crate) fn check(&self, stage: &'static str) -> crate::Result<()> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        let elapsed = self.started.elapsed();
+        if elapsed > limit {
+            return Err(crate::Error::budget_exceeded(stage, limit, elapsed));
+        }
+        Ok(())
+    }
 }
 
 /// Diagnostics for a caller-provided content selector.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct ContentSelectorDiagnostic {
     /// CSS selector supplied by the caller.
     pub selector: String,
@@ -50,6 +190,7 @@ pub struct ContentSelectorDiagnostic {
 
 /// Diagnostics for a matched site-specific extraction rule.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct SiteRuleDiagnostic {
     /// Profile or extractor name.
     pub name: String,
@@ -67,11 +208,82 @@ pub struct SiteRuleDiagnostic {
     pub accepted: bool,
     /// Reason generic extraction was used after a weak rule result.
     pub fallback_reason: Option<String>,
+    /// Other profiles that matched the URL but lost to this one, most
+    /// specific first. Profiles are never merged.
+    pub shadowed: Vec<ShadowedSiteRule>,
+    /// Per-run directive changes applied to the profile, in the order they
+    /// ran, such as `ignore remove_id_or_class` or `set prune=no`.
+    pub overrides: Vec<String>,
+    /// `if_page_contains` expressions that matched the page, whose
+    /// conditional rules ran ahead of the profile's own.
+    pub conditions: Vec<String>,
+}
+
+/// A site profile that matched the page URL but was not selected.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ShadowedSiteRule {
+    /// Profile name.
+    pub name: String,
+    /// Where the profile came from and how it matched.
+    pub matched_by: SiteRuleMatch,
+}
+
+/// Outcome of [`crate::check_site_profile`] for one page.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct SiteProfileCheck {
+    /// Profile name.
+    pub name: String,
+    /// Page URL the profile was checked against.
+    pub url: String,
+    /// Whether the profile matched the URL ahead of every other profile.
+    pub matched: bool,
+    /// Text length extracted through the profile's content roots.
+    pub text_len: usize,
+    /// Article text length from extraction without the profile.
+    pub baseline_text_len: usize,
+    /// Title found by the profile's `metadata.title` selectors.
+    pub title: Option<String>,
+    /// Why the check failed. Empty when it passed.
+    pub failures: Vec<String>,
+}
+
+impl SiteProfileCheck {
+    /// Whether the profile produced a body, met `char_threshold`, and found
+    /// a title when it has title selectors.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Problem found by [`crate::lint_site_profile`].
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct SiteProfileLint {
+    /// Whether the profile fails to load or only carries a dead rule.
+    pub severity: LintSeverity,
+    /// One-based source line, when the problem can be traced to one.
+    pub line: Option<usize>,
+    /// What is wrong.
+    pub message: String,
+}
+
+/// How serious a [`SiteProfileLint`] is.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum LintSeverity {
+    /// The profile does not load, or a selector in it never runs.
+    Error,
+    /// The profile loads, but part of it is ignored or never applies.
+    Warning,
 }
 
 /// Source of a site-specific extraction rule.
 #[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum SiteRuleSource {
     /// A TOML profile selected the content.
     DeclarativeProfile,
@@ -79,8 +291,9 @@ pub enum SiteRuleSource {
     CodeExtractor,
 }
 
-/// Host and path rule that matched a page URL.
+/// Host and path rule, or platform fingerprint, that matched a page.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct SiteRuleMatch {
     /// Hostname matched by the rule.
     pub host: String,
@@ -88,10 +301,18 @@ pub struct SiteRuleMatch {
     pub path_prefix: Option<String>,
     /// Whether the matching rule is bundled with Lectito.
     pub bundled: bool,
+    /// File name of a bundled profile, such as `wikipedia.org.toml`.
+    pub file: Option<String>,
+    /// Position of a user profile in `ReadabilityOptions::site_profiles`.
+    pub user_index: Option<usize>,
+    /// `fingerprints` selector found in the page when the profile matched by
+    /// platform rather than by host.
+    pub fingerprint: Option<String>,
 }
 
 /// Diagnostics for one generic extraction attempt.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct AttemptDiagnostic {
     /// Attempt index in retry order.
     pub index: usize,
@@ -109,6 +330,8 @@ pub struct AttemptDiagnostic {
     pub cleanup: Option<CleanupDiagnostic>,
     /// Content recovered from hidden or alternate markup before scoring.
     pub recovery: RecoveryDiagnostic,
+    /// Content removed by each preprocessing rule before scoring.
+    pub preprocess: Vec<RuleRemovalDiagnostic>,
     /// Extracted text length after cleanup.
     pub text_len: usize,
     /// Whether this attempt met acceptance thresholds.
@@ -117,6 +340,7 @@ pub struct AttemptDiagnostic {
 
 /// Generic extraction flags used for an attempt.
 #[derive(Clone, Copy, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct FlagDiagnostic {
     /// Whether unlikely page chrome was stripped before scoring.
     pub strip_unlikely: bool,
@@ -128,6 +352,7 @@ pub struct FlagDiagnostic {
 
 /// Diagnostics for one candidate article root.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct CandidateDiagnostic {
     /// Candidate node summary.
     pub node: NodeDiagnostic,
@@ -140,6 +365,7 @@ pub struct CandidateDiagnostic {
 /// Reason a node was considered as an article root.
 #[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum CandidateSelection {
     /// The node was found by generic readability scoring.
     CandidateScoring,
@@ -149,6 +375,7 @@ pub enum CandidateSelection {
 
 /// Stable summary of a DOM node used in diagnostics.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct NodeDiagnostic {
     /// CSS-like selector for the node.
     pub selector: String,
@@ -166,6 +393,7 @@ pub struct NodeDiagnostic {
 
 /// Cleanup measurements for a selected article root.
 #[derive(Clone, Debug, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct CleanupDiagnostic {
     /// Root selectors cleaned for output.
     pub roots: Vec<String>,
@@ -179,13 +407,171 @@ pub struct CleanupDiagnostic {
     pub element_count_after: usize,
     /// Number of elements removed during cleanup.
     pub removed_elements: usize,
+    /// Content removed by each cleanup rule, in pipeline order.
+    pub rules: Vec<RuleRemovalDiagnostic>,
+    /// Elements cleanup would have removed when `cleanup_dry_run` is enabled.
+    pub dry_run: Vec<DryRunRemovalDiagnostic>,
+}
+
+/// Text and elements removed by one preprocessing or cleanup rule.
+///
+/// Only rules that removed something are reported. Values are summed across
+/// roots when a rule runs more than once.
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct RuleRemovalDiagnostic {
+    /// Stable rule name, such as `unlikely-candidates` or `conditional`.
+    pub rule: String,
+    /// Characters of text removed by the rule.
+    pub removed_text_len: usize,
+    /// Elements removed by the rule.
+    pub removed_elements: usize,
+}
+
+/// An element a cleanup rule would have removed in dry-run mode.
+///
+/// The element is kept in the output and annotated with
+/// `data-lectito-removed="<rule>"`.
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct DryRunRemovalDiagnostic {
+    /// Rule that would have removed the element.
+    pub rule: String,
+    /// Compact selector for the element.
+    pub selector: String,
+    /// Characters of text inside the element.
+    pub text_len: usize,
+}
+
+/// Attribute holding the dry-run marker assigned to each element in a root.
+pub(crate) const DRY_RUN_MARKER: &str = "data-lectito-node";
+/// Attribute naming the rule that would have removed an element in a dry run.
+pub(crate) const DRY_RUN_REMOVED: &str = "data-lectito-removed";
+
+/// Accumulates [`RuleRemovalDiagnostic`] values while rules run.
+#[derive(Default)]
+pub(crate) struct RemovalTracker {
+    rules: Vec<RuleRemovalDiagnostic>,
+    /// Marker and rule for each element removed, when recording a dry run.
+    removed: Option<Vec<(String, String)>>,
+}
+
+impl RemovalTracker {
+    /// Track rules and also record which marked elements each rule removed.
+    pub(crate) fn recording() -> Self {
+        Self { removed: Some(Vec::new()), ..Default::default() }
+    }
+
+    /// Run `rule` against `root` and record what it removed.
+    pub(crate) fn track(&mut self, rule: &str, root: &NodeRef, step: impl FnOnce()) {
+        let (text_before, elements_before) = measure(root);
+        let marked_before = self.removed.is_some().then(|| marked_elements(root));
+        step();
+        let (text_after, elements_after) = measure(root);
+        if let (Some(removed), Some(marked_before)) = (self.removed.as_mut(), marked_before) {
+            for marker in removed_markers(root, marked_before) {
+                removed.push((marker, rule.to_string()));
+            }
+        }
+        self.add(
+            rule,
+            text_before.saturating_sub(text_after),
+            elements_before.saturating_sub(elements_after),
+        );
+    }
+
+    /// Count text and elements removed by `rule`, for passes that measure
+    /// each element they detach instead of the whole tree.
+    pub(crate) fn add(&mut self, rule: &str, removed_text_len: usize, removed_elements: usize) {
+        if removed_text_len == 0 && removed_elements == 0 {
+            return;
+        }
+
+        match self.rules.iter_mut().find(|entry| entry.rule == rule) {
+            Some(entry) => {
+                entry.removed_text_len += removed_text_len;
+                entry.removed_elements += removed_elements;
+            }
+            None => {
+                self.rules
+                    .push(RuleRemovalDiagnostic { rule: rule.to_string(), removed_text_len, removed_elements })
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<RuleRemovalDiagnostic> {
+        self.rules
+    }
+
+    /// Finish a recording tracker, returning the per-rule totals and the
+    /// marker and rule of each removed element.
+    pub(crate) fn finish_recording(self) -> (Vec<RuleRemovalDiagnostic>, Vec<(String, String)>) {
+        (self.rules, self.removed.unwrap_or_default())
+    }
+}
+
+/// Marker and parent marker for each marked element under `root`.
+fn marked_elements(root: &NodeRef) -> Vec<(String, Option<String>)> {
+    root.inclusive_descendants()
+        .elements()
+        .filter_map(|element| {
+            let marker = element.attributes.borrow().get(DRY_RUN_MARKER)?.to_string();
+            let parent = element
+                .as_node()
+                .ancestors()
+                .elements()
+                .find_map(|ancestor| ancestor.attributes.borrow().get(DRY_RUN_MARKER).map(str::to_string));
+            Some((marker, parent))
+        })
+        .collect()
+}
+
+/// Outermost marked elements that vanished without leaving any marked
+/// descendant behind. Unwrapped elements keep their children and are skipped.
+fn removed_markers(root: &NodeRef, marked_before: Vec<(String, Option<String>)>) -> Vec<String> {
+    let surviving: HashSet<String> = marked_elements(root).into_iter().map(|(marker, _)| marker).collect();
+    let parents: HashMap<&str, Option<&str>> = marked_before
+        .iter()
+        .map(|(marker, parent)| (marker.as_str(), parent.as_deref()))
+        .collect();
+
+    let mut kept_content = HashSet::new();
+    for marker in &surviving {
+        let mut current = parents.get(marker.as_str()).copied().flatten();
+        while let Some(parent) = current {
+            if !kept_content.insert(parent) {
+                break;
+            }
+            current = parents.get(parent).copied().flatten();
+        }
+    }
+
+    let vanished = |marker: &str| !surviving.contains(marker) && !kept_content.contains(marker);
+    marked_before
+        .iter()
+        .filter(|(marker, parent)| vanished(marker) && !parent.as_deref().is_some_and(vanished))
+        .map(|(marker, _)| marker.clone())
+        .collect()
+}
+
+/// Text length and element count under `root`, inclusive.
+pub(crate) fn measure(root: &NodeRef) -> (usize, usize) {
+    (
+        root.text_contents().chars().count(),
+        root.inclusive_descendants().elements().count(),
+    )
 }
 
 /// Content recovery performed before scoring.
 #[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[non_exhaustive]
 pub struct RecoveryDiagnostic {
     /// Number of declarative shadow roots flattened into normal markup.
     pub shadow_roots_flattened: usize,
     /// Number of mobile-only style rules applied to recover hidden content.
     pub mobile_rules_applied: usize,
+    /// Number of inline-encoded page payloads decoded before parsing.
+    pub payloads_decoded: usize,
+    /// Number of gallery and slideshow widgets flattened into figures.
+    pub galleries_flattened: usize,
 }
@@ -1,7 +1,10 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use kuchiki::NodeRef;
+use kuchiki::iter::{NodeIterator, Select};
 use kuchiki::traits::TendrilSink;
+use kuchiki::{ElementData, NodeData, NodeRef, Selectors};
 
 use super::patterns;
 
@@ -54,10 +57,57 @@ pub fn remove_matching(root: &NodeRef, selector: &str) {
     }
 }
 
+/// Collect the elements under `root`, inclusive, that match `selector`.
+///
+/// Use this when the loop detaches or moves the matches; otherwise prefer
+/// [`select_iter`], [`select_first`], or [`exists`].
 pub fn select_nodes(root: &NodeRef, selector: &str) -> Vec<NodeRef> {
-    root.select(selector)
-        .map(|nodes| nodes.map(|node| node.as_node().clone()).collect())
-        .unwrap_or_default()
+    select_iter(root, selector).collect()
+}
+
+/// Lazily yield the elements under `root`, inclusive, that match `selector`,
+/// in document order. Invalid selectors match nothing.
+pub fn select_iter(root: &NodeRef, selector: &str) -> impl Iterator<Item = NodeRef> + use<> {
+    compiled_selector(selector)
+        .map(|selectors| Select { iter: root.inclusive_descendants().elements(), selectors })
+        .into_iter()
+        .flatten()
+        .map(|node| node.as_node().clone())
+}
+
+/// The first element under `root`, inclusive, that matches `selector`.
+pub fn select_first(root: &NodeRef, selector: &str) -> Option<NodeRef> {
+    select_iter(root, selector).next()
+}
+
+/// Whether any element under `root`, inclusive, matches `selector`.
+pub fn exists(root: &NodeRef, selector: &str) -> bool {
+    select_first(root, selector).is_some()
+}
+
+thread_local! {
+    static SELECTOR_CACHE: RefCell<HashMap<String, Option<Rc<Selectors>>>> = RefCell::new(HashMap::new());
+}
+
+/// Upper bound on cached selectors; profile-supplied selectors are unbounded.
+const SELECTOR_CACHE_LIMIT: usize = 1024;
+
+/// Compile a selector list once per thread and reuse it across calls.
+///
+/// Invalid selectors are cached as `None` so they are not re-parsed either.
+fn compiled_selector(selector: &str) -> Option<Rc<Selectors>> {
+    SELECTOR_CACHE.with(|cache| {
+        if let Some(compiled) = cache.borrow().get(selector) {
+            return compiled.clone();
+        }
+        let compiled = Selectors::compile(selector).ok().map(Rc::new);
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= SELECTOR_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(selector.to_string(), compiled.clone());
+        compiled
+    })
 }
 
 pub fn node_name(node: &NodeRef) -> String {
@@ -66,6 +116,26 @@ pub fn node_name(node: &NodeRef) -> String {
         .unwrap_or_default()
 }
 
+/// Whether the node is an autonomous custom element such as `<news-article>`.
+///
+/// Hyphenated names reserved by SVG and MathML are excluded.
+pub fn is_custom_element(node: &NodeRef) -> bool {
+    let name = node_name(node);
+    name.starts_with(|ch: char| ch.is_ascii_lowercase())
+        && name.contains('-')
+        && !matches!(
+            name.as_str(),
+            "annotation-xml"
+                | "color-profile"
+                | "font-face"
+                | "font-face-src"
+                | "font-face-uri"
+                | "font-face-format"
+                | "font-face-name"
+                | "missing-glyph"
+        )
+}
+
 pub fn class_id_string(node: &NodeRef) -> String {
     format!(
         "{} {}",
@@ -104,9 +174,16 @@ pub fn remove_attr(node: &NodeRef, name: &str) {
     }
 }
 
+/// A new, detached element with no attributes or children.
+pub fn new_element(tag: &str) -> Option<NodeRef> {
+    let document = kuchiki::parse_html().one(format!("<html><body><{tag}></{tag}></body></html>"));
+    let element = select_first(&document, tag)?;
+    element.detach();
+    Some(element)
+}
+
 pub fn retag_node(node: &NodeRef, tag: &str) -> Option<NodeRef> {
-    let replacement_doc = kuchiki::parse_html().one(format!("<html><body><{tag}></{tag}></body></html>"));
-    let replacement = select_nodes(&replacement_doc, tag).into_iter().next()?;
+    let replacement = new_element(tag)?;
 
     for (name, value) in attrs(node) {
         set_attr(&replacement, &name, &value);
@@ -128,6 +205,83 @@ pub fn replace_with_children(node: &NodeRef) {
     node.detach();
 }
 
+/// Copy `node` and all of its descendants, including `<template>` contents,
+/// into a new, detached tree that shares no nodes with the original.
+pub fn deep_clone(node: &NodeRef) -> NodeRef {
+    let data = match node.data() {
+        NodeData::Element(element) => NodeData::Element(ElementData {
+            name: element.name.clone(),
+            attributes: element.attributes.clone(),
+            template_contents: element.template_contents.as_ref().map(deep_clone),
+        }),
+        data => data.clone(),
+    };
+    let copy = NodeRef::new(data);
+    for child in node.children() {
+        copy.append(deep_clone(&child));
+    }
+    copy
+}
+
 pub fn node_id(node: &NodeRef) -> usize {
     (&**node) as *const _ as usize
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_compiled_selectors() {
+        let root = kuchiki::parse_html().one("<div><p class=\"a\">One</p><p>Two</p></div>");
+
+        assert_eq!(select_nodes(&root, "p.a").len(), 1);
+        assert_eq!(select_nodes(&root, "p").len(), 2);
+        assert!(select_nodes(&root, "p[").is_empty());
+        assert!(Rc::ptr_eq(
+            &compiled_selector("p.a").unwrap(),
+            &compiled_selector("p.a").unwrap()
+        ));
+    }
+
+    #[test]
+    fn selects_lazily_and_in_document_order() {
+        let root = kuchiki::parse_html().one("<div id=\"outer\"><p>One</p><div><p>Two</p></div></div>");
+        let outer = select_first(&root, "#outer").unwrap();
+
+        let texts: Vec<_> = select_iter(&outer, "p").map(|node| inner_text(&node)).collect();
+        assert_eq!(texts, ["One", "Two"]);
+        assert_eq!(
+            select_first(&outer, "div").as_ref().map(node_name).as_deref(),
+            Some("div")
+        );
+        assert!(Rc::ptr_eq(&select_first(&outer, "div").unwrap().0, &outer.0));
+        assert!(exists(&outer, "div p"));
+        assert!(!exists(&outer, "span"));
+        assert!(!exists(&outer, "p["));
+        assert_eq!(select_iter(&root, "p[").count(), 0);
+    }
+
+    #[test]
+    fn deep_clone_shares_nothing_with_the_original() {
+        let original =
+            kuchiki::parse_html().one(r#"<div class="tide"><p>High water</p><template><p>Low</p></template></div>"#);
+        let copy = deep_clone(&original);
+
+        set_attr(&select_first(&copy, "div").unwrap(), "class", "ebb");
+        select_first(&copy, "p").unwrap().detach();
+        let template = |document: &NodeRef| {
+            let node = select_first(document, "template").unwrap();
+            node.as_element().unwrap().template_contents.clone().unwrap()
+        };
+        template(&copy).first_child().unwrap().detach();
+
+        assert_eq!(
+            attr(&select_first(&original, "div").unwrap(), "class").as_deref(),
+            Some("tide")
+        );
+        assert_eq!(inner_text(&select_first(&original, "p").unwrap()), "High water");
+        assert_eq!(template(&original).to_string(), "<p>Low</p>");
+        assert!(select_first(&copy, "div > p").is_none());
+    }
+}
@@ -0,0 +1,520 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+use zip::write::{SimpleFileOptions, StreamWriter};
+use zip::{CompressionMethod, ZipWriter};
+
+use super::config::Article;
+
+/// Elements left out of the book: scripts, embeds, and form controls that
+/// EPUB readers will not run.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "button", "embed", "form", "iframe", "input", "noscript", "object", "script", "select", "style", "template",
+    "textarea",
+];
+
+/// Image types EPUB readers must support, with the extension each is stored
+/// under.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("image/gif", "gif"),
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/svg+xml", "svg"),
+    ("image/webp", "webp"),
+];
+
+/// Elements written as self-closing XHTML tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "hr", "link", "meta", "param", "source", "track", "wbr",
+];
+
+impl Article {
+    /// The article as an EPUB 3 book with a single chapter.
+    ///
+    /// The chapter is the cleaned article HTML rewritten as XHTML, and the
+    /// table of contents lists its `h2` sections. Images embedded as base64
+    /// `data:` URIs, as the CLI's `--download-images` writes them, are stored
+    /// in the book; other images are replaced by their alt text, since EPUB
+    /// readers do not load remote images. `source` is the page URL, used as
+    /// the book identifier.
+    pub fn to_epub(&self, source: Option<&str>) -> Vec<u8> {
+        parts(self, source).flatten().collect()
+    }
+}
+
+/// The EPUB archive one zip entry at a time. The chapter, navigation,
+/// package, and decoded images are built first; only the zip framing is
+/// produced as the entries are taken, so the whole archive is never held as
+/// one buffer.
+pub(crate) fn parts(article: &Article, source: Option<&str>) -> Parts {
+    let title = article
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .unwrap_or("Untitled");
+    let language = article.lang.as_deref().filter(|lang| !lang.is_empty()).unwrap_or("en");
+    let (chapter, sections, images) = chapter(article, title, language);
+
+    // The mimetype entry must come first and be stored uncompressed. The other
+    // entries are stored too, so the build needs no compressor.
+    let mut entries = vec![
+        ("mimetype".to_string(), b"application/epub+zip".to_vec()),
+        ("META-INF/container.xml".to_string(), CONTAINER.as_bytes().to_vec()),
+        (
+            "OEBPS/content.opf".to_string(),
+            package(article, source, title, language, &chapter, &images).into_bytes(),
+        ),
+        (
+            "OEBPS/nav.xhtml".to_string(),
+            navigation(title, language, &sections).into_bytes(),
+        ),
+        ("OEBPS/article.xhtml".to_string(), chapter.into_bytes()),
+    ];
+    entries.extend(
+        images
+            .into_iter()
+            .map(|image| (format!("OEBPS/{}", image.href), image.bytes)),
+    );
+    let pending = Pending::default();
+    Parts { entries: entries.into_iter(), zip: Some(ZipWriter::new_stream(pending.clone())), pending }
+}
+
+/// Iterator over the bytes of an EPUB archive, one entry per item and the
+/// central directory last. Each entry's data is dropped once it is yielded.
+pub(crate) struct Parts {
+    entries: std::vec::IntoIter<(String, Vec<u8>)>,
+    zip: Option<ZipWriter<StreamWriter<Pending>>>,
+    pending: Pending,
+}
+
+impl Iterator for Parts {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        let zip = self.zip.as_mut()?;
+        match self.entries.next() {
+            Some((name, data)) => {
+                let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+                zip.start_file(name, stored).expect("in-memory zip entry");
+                zip.write_all(&data).expect("in-memory zip entry");
+            }
+            None => {
+                self.zip.take()?.finish().expect("in-memory zip archive");
+            }
+        }
+        Some(self.pending.take())
+    }
+}
+
+/// Bytes the zip writer has produced since the last [`Pending::take`].
+#[derive(Clone, Default)]
+struct Pending(Arc<Mutex<Vec<u8>>>);
+
+impl Pending {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl Write for Pending {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// An image decoded from a `data:` URI, stored in the book at `href`.
+struct Image {
+    href: String,
+    media_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+fn package(
+    article: &Article, source: Option<&str>, title: &str, language: &str, chapter: &str, images: &[Image],
+) -> String {
+    let identifier = source
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("urn:lectito:{:016x}", fnv1a64(chapter.as_bytes())));
+    let mut metadata = vec![
+        format!(r#"<dc:identifier id="book-id">{}</dc:identifier>"#, escape(&identifier)),
+        format!("<dc:title>{}</dc:title>", escape(title)),
+        format!("<dc:language>{}</dc:language>", escape(language)),
+    ];
+    let optional = [
+        ("dc:creator", article.byline.clone()),
+        ("dc:publisher", article.site_name.clone()),
+        ("dc:description", article.excerpt.clone()),
+        ("dc:date", article.published.map(|date| date.to_rfc3339())),
+        ("dc:source", source.map(str::to_string)),
+    ];
+    for (tag, value) in optional {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+            metadata.push(format!("<{tag}>{}</{tag}>", escape(value)));
+        }
+    }
+    let modified = match article.modified.or(article.published) {
+        Some(date) => date.naive_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        None => now().format("%Y-%m-%dT00:00:00Z").to_string(),
+    };
+    metadata.push(format!(r#"<meta property="dcterms:modified">{modified}</meta>"#));
+
+    let properties: Vec<&str> = [("<math", "mathml"), ("<svg", "svg")]
+        .into_iter()
+        .filter(|(tag, _)| chapter.contains(tag))
+        .map(|(_, property)| property)
+        .collect();
+    let properties = match properties.is_empty() {
+        true => String::new(),
+        false => format!(r#" properties="{}""#, properties.join(" ")),
+    };
+    let images: String = images
+        .iter()
+        .enumerate()
+        .map(|(index, image)| {
+            format!(
+                r#"
+    <item id="image-{}" href="{}" media-type="{}"/>"#,
+                index + 1,
+                image.href,
+                image.media_type
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{language}">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    {}
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="article" href="article.xhtml" media-type="application/xhtml+xml"{properties}/>{images}
+  </manifest>
+  <spine>
+    <itemref idref="article"/>
+  </spine>
+</package>
+"#,
+        metadata.join("\n    "),
+        language = escape(language),
+    )
+}
+
+fn navigation(title: &str, language: &str, sections: &[(String, String)]) -> String {
+    let items: String = sections
+        .iter()
+        .map(|(id, text)| format!(r#"<li><a href="article.xhtml#{id}">{}</a></li>"#, escape(text)))
+        .collect();
+    let sections = match items.is_empty() {
+        true => String::new(),
+        false => format!("<ol>{items}</ol>"),
+    };
+    document(
+        title,
+        language,
+        &format!(
+            r#"<nav epub:type="toc"><h1>Contents</h1><ol><li><a href="article.xhtml">{}</a>{sections}</li></ol></nav>"#,
+            escape(title)
+        ),
+    )
+}
+
+/// The chapter XHTML, the id and text of each `h2` section, and the images
+/// the chapter shows.
+fn chapter(article: &Article, title: &str, language: &str) -> (String, Vec<(String, String)>, Vec<Image>) {
+    let fragment = Html::parse_fragment(&article.content);
+    let mut writer = Writer::default();
+    writer.children(fragment.root_element());
+
+    let has_title = fragment
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .any(|element| element.value().name() == "h1" && collapse(&element.text().collect::<String>()) == title);
+    let heading = match has_title {
+        true => String::new(),
+        false => format!("<h1>{}</h1>", escape(title)),
+    };
+    (
+        document(title, language, &format!("{heading}{}", writer.output)),
+        writer.sections,
+        writer.images,
+    )
+}
+
+fn document(title: &str, language: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{language}" lang="{language}">
+<head><meta charset="utf-8"/><title>{}</title></head>
+<body>{body}</body>
+</html>
+"#,
+        escape(title),
+        language = escape(language),
+    )
+}
+
+#[derive(Default)]
+struct Writer {
+    output: String,
+    sections: Vec<(String, String)>,
+    images: Vec<Image>,
+    /// The `data:` URI of each image, so repeats share one file.
+    image_sources: Vec<String>,
+}
+
+impl Writer {
+    fn children(&mut self, element: ElementRef<'_>) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.output.push_str(&escape(text)),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef<'_>) {
+        let name = element.value().name();
+        if DROPPED_ELEMENTS.contains(&name) {
+            return;
+        }
+        if name == "img" {
+            let alt = collapse(element.value().attr("alt").unwrap_or_default());
+            match element.value().attr("src").and_then(|src| self.image(src)) {
+                Some(href) => self
+                    .output
+                    .push_str(&format!(r#"<img src="{href}" alt="{}"/>"#, escape(&alt))),
+                None if !alt.is_empty() => self.output.push_str(&format!("[{}]", escape(&alt))),
+                None => {}
+            }
+            return;
+        }
+
+        let mut attributes: Vec<(String, String)> = element
+            .value()
+            .attrs()
+            .filter(|(name, _)| is_xml_name(name) && !name.starts_with("xmlns"))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        match name {
+            "math" => attributes.push(("xmlns".to_string(), "http://www.w3.org/1998/Math/MathML".to_string())),
+            "svg" => attributes.push(("xmlns".to_string(), "http://www.w3.org/2000/svg".to_string())),
+            "h2" => {
+                let id = match attributes.iter().find(|(name, _)| name == "id") {
+                    Some((_, id)) => id.clone(),
+                    None => {
+                        let id = format!("section-{}", self.sections.len() + 1);
+                        attributes.push(("id".to_string(), id.clone()));
+                        id
+                    }
+                };
+                self.sections.push((id, collapse(&element.text().collect::<String>())));
+            }
+            _ => {}
+        }
+
+        self.output.push('<');
+        self.output.push_str(name);
+        for (name, value) in attributes {
+            self.output.push_str(&format!(r#" {name}="{}""#, escape(&value)));
+        }
+        if VOID_ELEMENTS.contains(&name) {
+            self.output.push_str("/>");
+            return;
+        }
+        self.output.push('>');
+        self.children(element);
+        self.output.push_str(&format!("</{name}>"));
+    }
+}
+
+impl Writer {
+    /// The book path of the image a base64 `data:` URI holds, when it is an
+    /// image type EPUB readers support.
+    fn image(&mut self, src: &str) -> Option<String> {
+        let src = src.trim();
+        if let Some(index) = self.image_sources.iter().position(|known| known == src) {
+            return Some(self.images[index].href.clone());
+        }
+        let (header, data) = src.strip_prefix("data:")?.split_once(',')?;
+        let media_type = header.strip_suffix(";base64")?;
+        let (media_type, extension) = IMAGE_TYPES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(media_type))?;
+        let bytes = STANDARD.decode(data.trim()).ok()?;
+        let href = format!("images/image-{}.{extension}", self.images.len() + 1);
+        self.images.push(Image { href: href.clone(), media_type, bytes });
+        self.image_sources.push(src.to_string());
+        Some(href)
+    }
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn is_xml_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The current time, or the Unix epoch on `wasm32`, where reading the clock
+/// panics.
+fn now() -> DateTime<Utc> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return DateTime::<Utc>::from(std::time::SystemTime::now());
+    #[cfg(target_arch = "wasm32")]
+    return DateTime::<Utc>::UNIX_EPOCH;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(content: &str) -> Article {
+        Article {
+            title: Some("On Tides".to_string()),
+            byline: Some("Ada Tern".to_string()),
+            lang: Some("en".to_string()),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Entries of a zip archive, in order.
+    fn entries(bytes: &[u8]) -> Vec<(String, String)> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        (0..archive.len())
+            .map(|index| {
+                let mut file = archive.by_index(index).unwrap();
+                assert_eq!(file.compression(), CompressionMethod::Stored);
+                let mut data = String::new();
+                std::io::Read::read_to_string(&mut file, &mut data).unwrap();
+                (file.name().to_string(), data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn writes_epub_package_with_xhtml_chapter() {
+        let book = article(
+            r#"<div id="readability-page-1" class="page"><p>Sun &amp; moon<br>rise.</p>
+            <h2>Spring</h2><p><img src="https://example.com/a.png" alt="Chart"></p><script>x()</script></div>"#,
+        )
+        .to_epub(Some("https://example.com/tides"));
+
+        let entries = entries(&book);
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "mimetype",
+                "META-INF/container.xml",
+                "OEBPS/content.opf",
+                "OEBPS/nav.xhtml",
+                "OEBPS/article.xhtml"
+            ]
+        );
+        assert_eq!(entries[0].1, "application/epub+zip");
+        assert!(
+            entries[2]
+                .1
+                .contains(r#"<dc:identifier id="book-id">https://example.com/tides</dc:identifier>"#)
+        );
+        assert!(entries[2].1.contains("<dc:creator>Ada Tern</dc:creator>"));
+        assert!(
+            entries[3]
+                .1
+                .contains(r##"<li><a href="article.xhtml#section-1">Spring</a></li>"##)
+        );
+        assert!(entries[4].1.contains(
+            r#"<body><h1>On Tides</h1><div class="page" id="readability-page-1"><p>Sun &amp; moon<br/>rise.</p>"#
+        ));
+        assert!(
+            entries[4]
+                .1
+                .contains(r#"<h2 id="section-1">Spring</h2><p>[Chart]</p></div>"#)
+        );
+        let end = &book[book.len() - 22..];
+        assert!(end.starts_with(&0x0605_4b50_u32.to_le_bytes()));
+        assert_eq!(end[10..12], 5_u16.to_le_bytes());
+
+        // One part per entry, then the central directory.
+        let parts: Vec<_> = parts(&article("<p>Tide</p>"), None).collect();
+        assert_eq!(parts.len(), 6);
+        assert!(parts[0].starts_with(b"PK\x03\x04"));
+        assert!(parts[5][parts[5].len() - 22..].starts_with(&0x0605_4b50_u32.to_le_bytes()));
+    }
+
+    #[test]
+    fn stores_data_uri_images_in_the_book() {
+        let book = article(
+            r#"<p><img src="data:image/png;base64,cG5n" alt="Tide chart"><img src="data:image/png;base64,cG5n">
+            <img src="https://example.com/remote.png" alt="Remote"><img src="data:image/avif;base64,YXZpZg==" alt="Avif"></p>"#,
+        )
+        .to_epub(None);
+
+        let entries = entries(&book);
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[5], ("OEBPS/images/image-1.png".to_string(), "png".to_string()));
+        assert!(
+            entries[2]
+                .1
+                .contains(r#"<item id="image-1" href="images/image-1.png" media-type="image/png"/>"#)
+        );
+        assert!(
+            entries[4].1.contains(
+                r#"<p><img src="images/image-1.png" alt="Tide chart"/><img src="images/image-1.png" alt=""/>"#
+            )
+        );
+        assert!(entries[4].1.contains("[Remote][Avif]</p>"));
+    }
+}
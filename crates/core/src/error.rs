@@ -1,5 +1,12 @@
+use std::time::Duration;
+
+use crate::ExtractionDiagnostics;
+
 /// Error returned by Lectito extraction and conversion functions.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// HTML parsing failed before extraction could start.
     #[error("failed to parse HTML")]
@@ -15,6 +22,33 @@ pub enum Error {
         /// Configured maximum element count.
         limit: usize,
     },
+    /// The input exceeded `ReadabilityOptions::max_input_bytes`.
+    #[error("input is {actual} bytes, exceeding max_input_bytes={limit}")]
+    InputTooLarge {
+        /// Input size in bytes.
+        actual: usize,
+        /// Configured maximum size.
+        limit: usize,
+    },
+    /// Candidate scoring found more roots than
+    /// `ReadabilityOptions::max_candidates`.
+    #[error("candidate scoring exceeded max_candidates={limit}")]
+    MaxCandidatesExceeded {
+        /// Configured maximum candidate count.
+        limit: usize,
+    },
+    /// Extraction ran past `ReadabilityOptions::max_duration` and stopped.
+    #[error("extraction exceeded max_duration of {}ms during {stage}", limit.as_millis())]
+    BudgetExceeded {
+        /// Stage that was running when the budget ran out, such as `scoring`.
+        stage: &'static str,
+        /// Configured budget.
+        limit: Duration,
+        /// Time spent when extraction stopped.
+        elapsed: Duration,
+        /// Diagnostics for the work finished before extraction stopped.
+        diagnostics: Box<ExtractionDiagnostics>,
+    },
     /// A site profile could not be parsed or converted into selectors.
     #[error("invalid site profile {name}: {message}")]
     InvalidSiteProfile {
@@ -26,16 +60,71 @@ pub enum Error {
     /// Article HTML serialization failed after extraction.
     #[error("failed to serialize article HTML")]
     Serialization,
+    /// A `ReadabilityOptions` field is outside its accepted range.
+    #[error("invalid option {option}: {message}")]
+    InvalidOption {
+        /// Field name, such as `sibling_threshold`.
+        option: String,
+        /// What the field accepts and the value it had.
+        message: String,
+    },
+    /// A configuration file could not be parsed or names an unknown setting.
+    #[error("invalid configuration: {0}")]
+    Config(String),
+    /// Article metadata could not be written as TOML frontmatter.
+    #[error("failed to serialize frontmatter: {0}")]
+    Frontmatter(String),
+    /// A custom [`crate::ExtractionStage`] stopped extraction.
+    #[error("extraction stage failed: {0}")]
+    Stage(String),
+    /// An MHTML file or Safari web archive could not be unpacked.
+    #[error("invalid web archive: {0}")]
+    Archive(String),
+    /// The call needs an optional feature this build was compiled without.
+    /// [`crate::capabilities`] lists the features that are available.
+    #[error("this build of lectito was compiled without the `{0}` feature")]
+    FeatureDisabled(&'static str),
+    /// [`crate::Article::write_to`] could not write to its sink.
+    #[error("failed to write output: {0}")]
+    Write(#[source] std::io::Error),
+    /// A saved article could not be written to disk.
+    #[error("failed to write {}: {source}", path.display())]
+    Save {
+        /// Destination path.
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        source: std::io::Error,
+    },
 }
 
 impl Error {
-    pub fn invalid_site_profile(name: impl ToString, message: impl ToString) -> Self {
+    pub(crate) fn invalid_site_profile(name: impl ToString, message: impl ToString) -> Self {
         Self::InvalidSiteProfile { name: name.to_string(), message: message.to_string() }
     }
 
-    pub fn max_elems_exceeded(actual: usize, limit: usize) -> Self {
+    pub(crate) fn invalid_option(option: impl ToString, message: impl ToString) -> Self {
+        Self::InvalidOption { option: option.to_string(), message: message.to_string() }
+    }
+
+    pub(crate) fn max_elems_exceeded(actual: usize, limit: usize) -> Self {
         Self::MaxElemsExceeded { actual, limit }
     }
+
+    pub(crate) fn input_too_large(actual: usize, limit: usize) -> Self {
+        Self::InputTooLarge { actual, limit }
+    }
+
+    pub(crate) fn budget_exceeded(stage: &'static str, limit: Duration, elapsed: Duration) -> Self {
+        Self::BudgetExceeded { stage, limit, elapsed, diagnostics: Box::default() }
+    }
+
+    /// Attach the diagnostics gathered so far to a `BudgetExceeded` error.
+    pub(crate) fn with_partial_diagnostics(mut self, partial: &ExtractionDiagnostics) -> Self {
+        if let Self::BudgetExceeded { diagnostics, .. } = &mut self {
+            **diagnostics = partial.clone();
+        }
+        self
+    }
 }
 
 /// Result type used by Lectito APIs.
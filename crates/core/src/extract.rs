@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use kuchiki::NodeRef;
 use kuchiki::traits::TendrilSink;
 use scraper::Html;
@@ -7,16 +5,23 @@ use url::Url;
 
 use crate::shared;
 
-use super::config::{Article, ExtractFlags, ReadabilityOptions};
+use super::config::{Article, ExtractFlags, ExtractionStrategy, LinkStyle, PatternGroup, ReadabilityOptions};
 use super::diagnostics::{
-    AttemptDiagnostic, CandidateDiagnostic, CandidateSelection, CleanupDiagnostic, ContentSelectorDiagnostic,
-    ExtractionDiagnostics, ExtractionOutcome, ExtractionReport, FlagDiagnostic, NodeDiagnostic, RecoveryDiagnostic,
-    SiteRuleSource,
+    AttemptDiagnostic, CandidateDiagnostic, CandidateSelection, CleanupDiagnostic, ContentSelectorDiagnostic, Deadline,
+    ExtractionDiagnostics, ExtractionOutcome, ExtractionReport, ExtractionStats, FlagDiagnostic, NodeDiagnostic,
+    RecoveryDiagnostic, RemovalTracker, RuleRemovalDiagnostic, SiteRuleSource, Stopwatch, measure,
 };
 use super::error::{Error, Result};
 use super::regexes::RegexPattern;
-use super::{cleanup, dom, json_schema, markdown, metadata, normalize, patterns, recovery, rules, scoring, serialize};
-use super::{metadata::Metadata, scoring::Candidate};
+use super::{
+    cleanup, density, dom, frames, json_schema, links, liveblog, markdown, metadata, normalize, patterns, paywall,
+    pipeline, recovery, rules, scoring, serialize, transcript, typography,
+};
+use super::{
+    metadata::Metadata,
+    pipeline::Stages,
+    scoring::{Candidate, ScoreCache},
+};
 
 const KNOWN_CONTENT_SELECTORS: &[&str] = &[
     "#article-body",
@@ -29,10 +34,13 @@ const KNOWN_CONTENT_SELECTORS: &[&str] = &[
     ".entry-content",
     ".post-content",
     ".content-wrapper",
+    ".h-entry .e-content",
 ];
 const USEFUL_WORD_THRESHOLD: usize = 180;
 const EXTREMELY_SHORT_WORD_THRESHOLD: usize = 80;
 const SUSPICIOUS_SIGNAL_RATIO: usize = 3;
+/// Shortest `<noscript>` text worth keeping as article content.
+const MIN_NOSCRIPT_TEXT_LEN: usize = 80;
 const ENTRY_POINT_SELECTORS: &[&str] = &[
     "article",
     "main",
@@ -67,9 +75,12 @@ impl From<ExtractAttempt> for Article {
             metadata.excerpt = metadata::first_paragraph_excerpt(&attempt.content);
         }
 
+        let links = links::article_links(&attempt.content, metadata.related_links);
         Article {
             title: metadata.title,
+            raw_title: metadata.raw_title,
             byline: metadata.byline,
+            author_bio: metadata.author_bio,
             dir: metadata.dir,
             lang: metadata.lang,
             markdown: markdown::html_to_markdown(&attempt.content),
@@ -79,9 +90,20 @@ impl From<ExtractAttempt> for Article {
             excerpt: metadata.excerpt,
             site_name: metadata.site_name,
             published_time: metadata.published_time,
+            modified_time: metadata.modified_time,
+            published: metadata.published,
+            modified: metadata.modified,
             image: metadata.image,
             domain: metadata.domain,
             favicon: metadata.favicon,
+            canonical_url: metadata.canonical_url,
+            amp_url: metadata.amp_url,
+            keywords: metadata.keywords,
+            section: metadata.section,
+            is_paywalled: metadata.is_paywalled,
+            is_sponsored: metadata.is_sponsored,
+            links,
+            stats: ExtractionStats::default(),
         }
     }
 }
@@ -95,6 +117,7 @@ impl From<Metadata> for ExtractAttempt {
 struct GrabDiagnostics {
     attempt: AttemptDiagnostic,
     content_selector: Option<ContentSelectorDiagnostic>,
+    nodes_scanned: usize,
 }
 
 impl From<ExtractFlags> for FlagDiagnostic {
@@ -107,23 +130,90 @@ impl From<ExtractFlags> for FlagDiagnostic {
     }
 }
 
+impl AttemptDiagnostic {
+    /// An attempt at `index` that has not prepared the page, scored
+    /// candidates, or selected a root yet.
+    fn new(index: usize, flags: ExtractFlags) -> Self {
+        Self {
+            index,
+            flags: flags.into(),
+            candidate_count: 0,
+            candidates: Vec::new(),
+            entry_points: Vec::new(),
+            selected_root: None,
+            cleanup: None,
+            recovery: RecoveryDiagnostic::default(),
+            preprocess: Vec::new(),
+            text_len: 0,
+            accepted: false,
+        }
+    }
+
+    /// Record what recovery and preprocessing did to the page.
+    fn prepared(self, recovery: RecoveryDiagnostic, preprocess: Vec<RuleRemovalDiagnostic>) -> Self {
+        Self { recovery, preprocess, ..self }
+    }
+
+    /// Record the selected root, its cleanup, and the resulting text length.
+    fn selected(
+        self, root: Option<NodeDiagnostic>, cleanup: CleanupDiagnostic, text_len: usize, accepted: bool,
+    ) -> Self {
+        Self { selected_root: root, cleanup: Some(cleanup), text_len, accepted, ..self }
+    }
+}
+
+/// The page parsed once, with its scripts removed. Attempts mutate the page,
+/// so each one takes its own copy of the parsed tree.
+struct SourceDom {
+    parsed: NodeRef,
+    /// Bytes of script text removed from the page.
+    script_bytes: usize,
+}
+
+impl SourceDom {
+    fn new(html: &str) -> Self {
+        let parsed = kuchiki::parse_html().one(html);
+        let script_bytes = strip_scripts(&parsed);
+        Self { parsed, script_bytes }
+    }
+
+    /// A fresh copy of the parsed page.
+    fn copy(&self) -> NodeRef {
+        dom::deep_clone(&self.parsed)
+    }
+}
+
 struct EntryPointCandidate {
     node: NodeRef,
     score: f64,
     diagnostic: CandidateDiagnostic,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 struct AttemptConfig {
     flags: ExtractFlags,
     remove_hidden: bool,
 }
 
+impl AttemptConfig {
+    /// Drop the preprocessing steps the caller opted out of.
+    fn restricted_by(self, options: &ReadabilityOptions) -> Self {
+        Self {
+            flags: ExtractFlags {
+                strip_unlikely: self.flags.strip_unlikely && !options.keep_unlikely_candidates,
+                ..self.flags
+            },
+            remove_hidden: self.remove_hidden && !options.keep_hidden,
+        }
+    }
+}
+
 /// Extract a readable article from an HTML document.
 ///
 /// `base_url` is optional. Pass it when the document contains relative links,
-/// images, or metadata URLs. The function returns `Ok(None)` when the document
-/// parses but no useful article content is found.
+/// images, or metadata URLs; without it, an absolute `<link rel="canonical">`
+/// is used. The function returns `Ok(None)` when the document parses but no
+/// useful article content is found.
 pub fn extract(html: &str, base_url: Option<&str>, options: &ReadabilityOptions) -> Result<Option<Article>> {
     Ok(extract_with_diagnostics(html, base_url, options)?.article)
 }
@@ -142,6 +232,114 @@ pub fn clean_article_html(html: &str, base_url: Option<&str>, options: &Readabil
 pub fn extract_with_diagnostics(
     html: &str, base_url: Option<&str>, options: &ReadabilityOptions,
 ) -> Result<ExtractionReport> {
+    extract_with_stages(html, base_url, options, &[])
+}
+
+/// [`extract_with_diagnostics`] with the custom stages of an
+/// [`crate::Extractor`] run at their hook points.
+pub(crate) fn extract_with_stages(
+    html: &str, base_url: Option<&str>, options: &ReadabilityOptions, stages: Stages<'_>,
+) -> Result<ExtractionReport> {
+    options.validate()?;
+    let normalized = patterns::normalize_extra(options);
+    let options = &*normalized;
+    let _span = tracing::debug_span!("extract", base_url, strategy = %options.strategy).entered();
+    let started = Stopwatch::start();
+    let mut stats = ExtractionStats { input_bytes: html.len(), ..ExtractionStats::default() };
+    let deadline = Deadline::start(options.max_duration);
+    let mut report = extract_report(html, base_url, options, stages, &mut stats, &deadline)?;
+    for stage in stages {
+        stage.before_postprocess(&mut report)?;
+    }
+    stats.timings.extract = started.elapsed().saturating_sub(stats.timings.parse);
+    let postprocess = Stopwatch::start();
+    match report.article.as_mut() {
+        Some(article) => {
+            if options.heading_offset.is_some() || options.normalize_heading_levels {
+                article.markdown = markdown::adjust_heading_levels(
+                    &article.markdown,
+                    options.heading_offset,
+                    options.normalize_heading_levels,
+                );
+            }
+            if options.reading_time_markers {
+                article.markdown = markdown::annotate_reading_time(&article.markdown, markdown::WORDS_PER_MINUTE);
+            }
+            if options.link_style != LinkStyle::Inline {
+                article.markdown = markdown::apply_link_style(&article.markdown, options.link_style);
+            }
+            flag_paywall(article, &mut report.diagnostics);
+            if article.is_sponsored == Some(true) {
+                report
+                    .diagnostics
+                    .warnings
+                    .push("page matches a site profile native_ad_clue; the article is sponsored content".to_string());
+            }
+        }
+        None => {
+            if let Some(frame_url) = frames::main_frame_url(html, base_url) {
+                report
+                    .diagnostics
+                    .warnings
+                    .push(format!("page is a frameset; extract the main frame at {frame_url}"));
+            }
+        }
+    }
+    stats.timings.postprocess = postprocess.elapsed();
+    stats.removed_elements = removed_elements(&report.diagnostics);
+    tracing::debug!(
+        outcome = ?report.diagnostics.outcome,
+        warnings = report.diagnostics.warnings.len(),
+        selected_attempt = report.diagnostics.selected_attempt,
+        text_len = report.article.as_ref().map_or(0, |article| article.length),
+        parse_ms = stats.timings.parse.as_secs_f64() * 1000.0,
+        extract_ms = stats.timings.extract.as_secs_f64() * 1000.0,
+        postprocess_ms = stats.timings.postprocess.as_secs_f64() * 1000.0,
+        "extraction finished"
+    );
+    if let Some(article) = report.article.as_mut() {
+        article.stats = stats;
+    }
+    report.timings = stats.timings;
+    Ok(report)
+}
+
+/// Elements removed on the way to the selected content: by the site rule
+/// when it was accepted, otherwise by preprocessing and cleanup of the
+/// selected attempt.
+fn removed_elements(diagnostics: &ExtractionDiagnostics) -> usize {
+    if let Some(site_rule) = diagnostics.site_rule.as_ref().filter(|site_rule| site_rule.accepted) {
+        return site_rule.removals;
+    }
+    let Some(attempt) = diagnostics
+        .selected_attempt
+        .and_then(|index| diagnostics.attempts.get(index))
+    else {
+        return 0;
+    };
+    let preprocess: usize = attempt.preprocess.iter().map(|rule| rule.removed_elements).sum();
+    preprocess + attempt.cleanup.as_ref().map_or(0, |cleanup| cleanup.removed_elements)
+}
+
+/// Add the truncation signal to page-level paywall signals and warn when any
+/// signal fired.
+fn flag_paywall(article: &mut Article, diagnostics: &mut ExtractionDiagnostics) {
+    if article.is_paywalled.is_none() && paywall::looks_truncated(&article.text_content) {
+        article.is_paywalled = Some(true);
+    }
+    if article.is_paywalled == Some(true) {
+        diagnostics
+            .warnings
+            .push("content appears to be paywalled; the article may be incomplete".to_string());
+    }
+}
+
+fn extract_report(
+    html: &str, base_url: Option<&str>, options: &ReadabilityOptions, stages: Stages<'_>, stats: &mut ExtractionStats,
+    deadline: &Deadline,
+) -> Result<ExtractionReport> {
+    enforce_input_limit(html, options.max_input_bytes)?;
+    let started = Stopwatch::start();
     let (working_html, source_recovery) = recovery::recover_html_snapshot(html);
     let html = working_html.as_str();
     let base_url = base_url
@@ -152,34 +350,99 @@ pub fn extract_with_diagnostics(
     enforce_element_limit(&document, options.max_elems_to_parse)?;
     let base_url = effective_base_url(&document, base_url.as_ref());
 
-    let metadata = metadata::extract_metadata(&document, html, options, base_url.as_ref());
-    let extraction_html = strip_raw_script_blocks(html);
+    let mut metadata = metadata::extract_metadata(&document, html, options, base_url.as_ref());
+    metadata.is_sponsored = rules::native_ad(html, base_url.as_ref(), options)?;
+    stats.timings.parse = started.elapsed();
+    deadline.check("parse")?;
+    let source = SourceDom::new(html);
+    stats.preprocessed_bytes = html.len().saturating_sub(source.script_bytes);
     let mut best_attempt: Option<ExtractAttempt> = None;
     let mut diagnostics = ExtractionDiagnostics::default();
 
+    let fixed_attempt = match options.strategy {
+        ExtractionStrategy::FullBodyCleanup => Some(full_body_attempt as FixedAttempt),
+        ExtractionStrategy::Density => Some(density_attempt as FixedAttempt),
+        _ => None,
+    };
+    if let Some(fixed_attempt) = fixed_attempt
+        && options.content_selector.is_none()
+    {
+        let Some((mut attempt, attempt_diagnostic)) =
+            fixed_attempt(&source, options, stages, 0, base_url.as_ref(), &metadata)?
+        else {
+            return Ok(ExtractionReport::new(None, diagnostics));
+        };
+        tracing::debug!(
+            text_len = attempt.text_len,
+            accepted = attempt_diagnostic.accepted,
+            "extracted with fixed strategy"
+        );
+        attempt.metadata = metadata;
+        diagnostics.selected_attempt = Some(0);
+        diagnostics.outcome = if attempt_diagnostic.accepted {
+            ExtractionOutcome::Accepted
+        } else {
+            ExtractionOutcome::BestAttempt
+        };
+        diagnostics.attempts.push(attempt_diagnostic);
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
+    }
+
+    if options.content_selector.is_none()
+        && liveblog::might_be_live_blog(html, options.strategy)
+        && let Some((mut attempt, attempt_diagnostic)) =
+            live_blog_attempt(html, &source, options, stages, base_url.as_ref(), &metadata)?
+    {
+        tracing::debug!(text_len = attempt.text_len, "extracted live blog");
+        attempt.metadata = metadata;
+        diagnostics.selected_attempt = Some(0);
+        diagnostics.outcome = ExtractionOutcome::Accepted;
+        diagnostics.attempts.push(attempt_diagnostic);
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
+    }
+
+    if options.content_selector.is_none()
+        && matches!(
+            options.strategy,
+            ExtractionStrategy::Auto | ExtractionStrategy::Transcript
+        )
+        && let Some((mut attempt, attempt_diagnostic)) =
+            transcript_attempt(html, &source, options, stages, base_url.as_ref(), &metadata)?
+    {
+        tracing::debug!(text_len = attempt.text_len, "extracted transcript");
+        attempt.metadata = metadata;
+        diagnostics.selected_attempt = Some(0);
+        diagnostics.outcome = ExtractionOutcome::Accepted;
+        diagnostics.attempts.push(attempt_diagnostic);
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
+    }
+
     if options.content_selector.is_none()
         && let Some((mut attempt, attempt_diagnostic)) =
-            known_content_attempt(&extraction_html, options, base_url.as_ref(), &metadata)?
+            known_content_attempt(&document, &source, options, stages, base_url.as_ref(), &metadata)?
     {
+        tracing::debug!(text_len = attempt.text_len, "extracted known content container");
         attempt.metadata = metadata;
         diagnostics.selected_attempt = Some(0);
         diagnostics.outcome = ExtractionOutcome::Accepted;
         diagnostics.attempts.push(attempt_diagnostic);
-        return Ok(ExtractionReport { article: Some(attempt.into()), diagnostics });
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
     }
 
     let schema_text_has_markup = metadata.schema_text.as_deref().is_some_and(schema_text_contains_html);
-    if (schema_text_has_markup || !source_has_rich_article_content(&extraction_html))
+    if (schema_text_has_markup || !source_has_rich_article_content(&document))
         && let Some((mut attempt, attempt_diagnostic)) = schema_text_attempt(&metadata, options, base_url.as_ref())?
     {
+        tracing::debug!(text_len = attempt.text_len, "extracted JSON-LD article body");
         attempt.metadata = metadata;
         diagnostics.selected_attempt = Some(0);
         diagnostics.outcome = ExtractionOutcome::Accepted;
         diagnostics.attempts.push(attempt_diagnostic);
-        return Ok(ExtractionReport { article: Some(attempt.into()), diagnostics });
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
     }
 
-    if let Some(mut rule_extraction) = try_site_rule(html, options, base_url.as_ref(), &metadata)?
+    deadline.check("site rules")?;
+    if let Some(mut rule_extraction) = try_site_rule(&source, options, stages, base_url.as_ref(), &metadata)?
         && rule_extraction.attempt.text_len > 0
     {
         let attempt_metadata = rule_extraction.attempt.metadata.clone();
@@ -195,10 +458,17 @@ pub fn extract_with_diagnostics(
         rule_extraction.diagnostic.accepted =
             matches!(rule_extraction.diagnostic.source, SiteRuleSource::CodeExtractor)
                 || rule_extraction.attempt.text_len >= options.char_threshold;
+        tracing::debug!(
+            rule = %rule_extraction.diagnostic.name,
+            source = ?rule_extraction.diagnostic.source,
+            text_len = rule_extraction.attempt.text_len,
+            accepted = rule_extraction.diagnostic.accepted,
+            "site rule matched"
+        );
         if rule_extraction.diagnostic.accepted {
             diagnostics.site_rule = Some(rule_extraction.diagnostic);
             diagnostics.outcome = ExtractionOutcome::Accepted;
-            return Ok(ExtractionReport { article: Some(rule_extraction.attempt.into()), diagnostics });
+            return Ok(ExtractionReport::new(Some(rule_extraction.attempt.into()), diagnostics));
         }
         rule_extraction.diagnostic.fallback_reason = Some(format!(
             "site rule text_len {} below char_threshold {}",
@@ -208,6 +478,9 @@ pub fn extract_with_diagnostics(
         best_attempt = Some(rule_extraction.attempt);
     }
 
+    // Like Readability.js, relax one flag per retry when an attempt comes up
+    // short: keep unlikely candidates, then ignore class weights, then skip
+    // conditional cleaning, and finally keep hidden elements.
     let attempts = [
         AttemptConfig { flags: ExtractFlags::all(), remove_hidden: true },
         AttemptConfig {
@@ -227,41 +500,51 @@ pub fn extract_with_diagnostics(
             remove_hidden: false,
         },
     ];
+    // Opt-outs can make neighbouring attempts identical; run each one once.
+    let mut attempts: Vec<_> = attempts.iter().map(|config| config.restricted_by(options)).collect();
+    attempts.dedup();
 
     for (index, config) in attempts.into_iter().enumerate() {
-        let dom = kuchiki::parse_html().one(extraction_html.as_ref());
+        let _span = tracing::debug_span!("attempt", index).entered();
+        deadline
+            .check("readability attempt")
+            .map_err(|error| error.with_partial_diagnostics(&diagnostics))?;
+        let dom = source.copy();
         let flags = config.flags;
-        let mut recovery = prep_document_with_visibility(&dom, options, flags, config.remove_hidden);
+        let (mut recovery, preprocess) = prep_document_with_visibility(&dom, options, flags, config.remove_hidden);
+        tracing::trace!(
+            ?flags,
+            remove_hidden = config.remove_hidden,
+            rules = preprocess.len(),
+            "preprocessed document"
+        );
         recovery.shadow_roots_flattened += source_recovery.shadow_roots_flattened;
-
-        let Some((mut attempt, attempt_diagnostic)) = grab_article(
-            &dom,
-            options,
-            flags,
-            index,
-            recovery.clone(),
-            base_url.as_ref(),
-            &metadata,
-        )?
-        else {
-            diagnostics.attempts.push(AttemptDiagnostic {
-                index,
-                flags: flags.into(),
-                candidate_count: 0,
-                candidates: Vec::new(),
-                entry_points: Vec::new(),
-                selected_root: None,
-                cleanup: None,
-                recovery,
-                text_len: 0,
-                accepted: false,
-            });
+        recovery.payloads_decoded += source_recovery.payloads_decoded;
+        pipeline::after_preprocess(stages, &dom)?;
+
+        let grabbed = grab_article(&dom, options, flags, index, base_url.as_ref(), &metadata, deadline)
+            .map_err(|error| error.with_partial_diagnostics(&diagnostics))?;
+        let Some((mut attempt, attempt_diagnostic)) = grabbed else {
+            diagnostics
+                .attempts
+                .push(AttemptDiagnostic::new(index, flags).prepared(recovery, preprocess));
             continue;
         };
+        let mut attempt_diagnostic = attempt_diagnostic;
+        attempt_diagnostic.attempt.recovery = recovery;
+        attempt_diagnostic.attempt.preprocess = preprocess;
+        stats.candidates_scanned += attempt_diagnostic.nodes_scanned;
+        stats.candidates_scored += attempt_diagnostic.attempt.candidate_count;
 
         if diagnostics.content_selector.is_none() {
             diagnostics.content_selector = attempt_diagnostic.content_selector.clone();
         }
+        tracing::debug!(
+            candidates = attempt_diagnostic.attempt.candidate_count,
+            text_len = attempt.text_len,
+            char_threshold = options.char_threshold,
+            "readability attempt finished"
+        );
         diagnostics.attempts.push(attempt_diagnostic.attempt);
         let diagnostic_index = diagnostics.attempts.len() - 1;
 
@@ -278,7 +561,7 @@ pub fn extract_with_diagnostics(
             attempt = json_schema::apply_schema_fallback(html, attempt, &metadata, options, flags, base_url.as_ref())?;
             diagnostics.selected_attempt = Some(diagnostic_index);
             diagnostics.outcome = ExtractionOutcome::Accepted;
-            return Ok(ExtractionReport { article: Some(attempt.into()), diagnostics });
+            return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
         }
 
         if best_attempt
@@ -291,9 +574,34 @@ pub fn extract_with_diagnostics(
         }
     }
 
+    if options.content_selector.is_none()
+        && let Some((mut attempt, attempt_diagnostic)) = full_body_attempt(
+            &source,
+            options,
+            stages,
+            diagnostics.attempts.len(),
+            base_url.as_ref(),
+            &metadata,
+        )?
+        && attempt_diagnostic.accepted
+        && best_attempt
+            .as_ref()
+            .is_none_or(|best| attempt.text_len > best.text_len)
+    {
+        tracing::debug!(
+            text_len = attempt.text_len,
+            "readability attempts fell short; using whole-body cleanup"
+        );
+        attempt.metadata = metadata;
+        diagnostics.selected_attempt = Some(diagnostics.attempts.len());
+        diagnostics.outcome = ExtractionOutcome::Accepted;
+        diagnostics.attempts.push(attempt_diagnostic);
+        return Ok(ExtractionReport::new(Some(attempt.into()), diagnostics));
+    }
+
     let Some(mut attempt) = best_attempt.filter(|attempt| attempt.text_len > 0) else {
         diagnostics.outcome = ExtractionOutcome::NoContent;
-        return Ok(ExtractionReport { article: None, diagnostics });
+        return Ok(ExtractionReport::new(None, diagnostics));
     };
     attempt.metadata = metadata.clone();
     attempt = json_schema::apply_schema_fallback(
@@ -305,52 +613,73 @@ pub fn extract_with_diagnostics(
         base_url.as_ref(),
     )?;
     diagnostics.outcome = ExtractionOutcome::BestAttempt;
-    Ok(ExtractionReport { article: Some(attempt.into()), diagnostics })
+    Ok(ExtractionReport::new(Some(attempt.into()), diagnostics))
 }
 
 pub fn prep_document(document: &NodeRef, options: &ReadabilityOptions, flags: ExtractFlags) -> RecoveryDiagnostic {
-    prep_document_with_visibility(document, options, flags, true)
+    prep_document_with_visibility(document, options, flags, true).0
 }
 
 fn prep_document_with_visibility(
     document: &NodeRef, options: &ReadabilityOptions, flags: ExtractFlags, remove_hidden: bool,
-) -> RecoveryDiagnostic {
+) -> (RecoveryDiagnostic, Vec<RuleRemovalDiagnostic>) {
+    let mut tracker = RemovalTracker::default();
     let recovery = recovery::recover(document, options.mobile_viewport_width);
-    unwrap_noscript_images(document);
-    dom::remove_matching(document, "script, style");
+    unwrap_noscript(document, options.recover_noscript);
+    tracker.track("script-style", document, || {
+        dom::remove_matching(document, "script, style")
+    });
     normalize_markup(document);
 
-    if flags.strip_unlikely {
-        let nodes = dom::select_nodes(document, "*");
-        for node in nodes {
-            if (remove_hidden && !dom::is_kuchiki_visible(&node)) || dom::has_unlikely_role(&node) {
-                node.detach();
-                continue;
-            }
-
-            let tag = dom::node_name(&node);
-            if tag == "body" || tag == "a" {
-                continue;
-            }
+    if remove_hidden || flags.strip_unlikely {
+        remove_hidden_and_unlikely(document, options, remove_hidden, flags.strip_unlikely, &mut tracker);
+    }
+    (recovery, tracker.finish())
+}
 
-            let match_string = dom::class_id_string(&node);
-            if RegexPattern::UnlikelyCandidates.to_regex().is_match(&match_string)
-                && !RegexPattern::MaybeCandidate.to_regex().is_match(&match_string)
-                && !dom::has_ancestor_tag(&node, "table", 3)
-                && !dom::has_ancestor_tag(&node, "code", 3)
-            {
-                node.detach();
-            }
-        }
-    } else {
-        let nodes = dom::select_nodes(document, "*");
-        for node in nodes {
-            if remove_hidden && !dom::is_kuchiki_visible(&node) {
-                node.detach();
-            }
+/// Remove hidden elements and unlikely candidates in one walk, measuring
+/// only what is detached. An element that is both counts as hidden. The walk
+/// does not descend into a removed element, so each node is visited once.
+fn remove_hidden_and_unlikely(
+    document: &NodeRef, options: &ReadabilityOptions, remove_hidden: bool, strip_unlikely: bool,
+    tracker: &mut RemovalTracker,
+) {
+    let mut hidden = (0, 0);
+    let mut unlikely = (0, 0);
+    let mut pending = document.children().rev().collect::<Vec<_>>();
+    while let Some(node) = pending.pop() {
+        if node.as_element().is_none() {
+            continue;
         }
+        let removed = if remove_hidden && !dom::is_kuchiki_visible(&node) {
+            &mut hidden
+        } else if strip_unlikely && is_unlikely_node(&node, options) {
+            &mut unlikely
+        } else {
+            pending.extend(node.children().rev());
+            continue;
+        };
+        let (text_len, elements) = measure(&node);
+        node.detach();
+        removed.0 += text_len;
+        removed.1 += elements;
+    }
+    tracker.add("hidden", hidden.0, hidden.1);
+    tracker.add("unlikely-candidates", unlikely.0, unlikely.1);
+}
+
+fn is_unlikely_node(node: &NodeRef, options: &ReadabilityOptions) -> bool {
+    if dom::has_unlikely_role(node) {
+        return true;
+    }
+    let tag = dom::node_name(node);
+    if tag == "body" || tag == "a" {
+        return false;
     }
-    recovery
+    let match_string = dom::class_id_string(node);
+    patterns::matches_group(PatternGroup::Unlikely, &match_string, options)
+        && !dom::has_ancestor_tag(node, "table", 3)
+        && !dom::has_ancestor_tag(node, "code", 3)
 }
 
 pub fn serialize_roots(
@@ -360,9 +689,10 @@ pub fn serialize_roots(
     let element_count_before = roots.iter().map(element_count).sum();
     let root_selectors = roots.iter().map(node_selector).collect();
 
-    cleanup::cleanup_article(&roots, opts, flags, base_url, metadata);
+    let (rules, dry_run) = cleanup::cleanup_article(&roots, opts, flags, base_url, metadata);
     normalize::normalize_article(&roots, metadata.title.as_deref());
-    let roots = cleanup::remove_trailing_chrome_roots(roots);
+    typography::normalize_text(&roots, opts);
+    let roots = if opts.cleanup_dry_run { roots } else { cleanup::remove_trailing_chrome_roots(roots, opts) };
 
     let mut content = String::from(r#"<div id="readability-page-1" class="page">"#);
     for node in &roots {
@@ -385,7 +715,16 @@ pub fn serialize_roots(
         element_count_before,
         element_count_after,
         removed_elements: element_count_before.saturating_sub(element_count_after),
+        rules,
+        dry_run,
     };
+    tracing::trace!(
+        removed_elements = cleanup.removed_elements,
+        text_len_before,
+        text_len_after = text_len,
+        rules = cleanup.rules.len(),
+        "cleaned article"
+    );
 
     Ok((attempt, cleanup))
 }
@@ -394,8 +733,18 @@ pub fn element_count(node: &NodeRef) -> usize {
     node.descendants().filter(|node| node.as_element().is_some()).count()
 }
 
-fn strip_raw_script_blocks(html: &str) -> String {
-    RegexPattern::RawScript.to_regex().replace_all(html, "").into_owned()
+/// Remove scripts, keeping MathJax TeX blocks as MathML, and return the
+/// bytes of script text removed.
+fn strip_scripts(document: &NodeRef) -> usize {
+    let mut removed = 0;
+    for script in dom::select_nodes(document, "script") {
+        if let Some(math) = markdown::math::tex_script_to_math(&script) {
+            script.insert_before(math);
+        }
+        removed += script.text_contents().len();
+        script.detach();
+    }
+    removed
 }
 
 fn should_retry_short_or_suspicious(
@@ -437,36 +786,37 @@ fn best_content_signal_len(diagnostic: &AttemptDiagnostic) -> usize {
 }
 
 fn known_content_attempt(
-    html: &str, opts: &ReadabilityOptions, base_url: Option<&Url>, metadata: &Metadata,
+    parsed: &Html, source: &SourceDom, opts: &ReadabilityOptions, stages: Stages<'_>, base_url: Option<&Url>,
+    metadata: &Metadata,
 ) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
-    let document = kuchiki::parse_html().one(html);
+    // Only build a mutable DOM when the parsed source has a known container.
+    if !KNOWN_CONTENT_SELECTORS
+        .iter()
+        .any(|selector| parsed.select(&patterns::selector(selector)).next().is_some())
+    {
+        return Ok(None);
+    }
+
+    let document = source.copy();
+    pipeline::after_preprocess(stages, &document)?;
     let flags = ExtractFlags { strip_unlikely: false, weight_classes: false, clean_conditionally: false };
 
     for selector in KNOWN_CONTENT_SELECTORS {
         let roots = dom::select_nodes(&document, selector);
         for root in roots {
             let recovery = recovery::recover(&root, opts.mobile_viewport_width);
-            unwrap_noscript_images(&root);
+            unwrap_noscript(&root, opts.recover_noscript);
             dom::remove_matching(&root, "script, style");
             normalize_markup(&root);
             let selected_root = node_diagnostic(&root);
-            let (attempt, cleanup) = serialize_roots(vec![root], opts, flags, base_url, metadata)?;
+            let (attempt, cleanup) = serialize_roots(with_lead_siblings(vec![root]), opts, flags, base_url, metadata)?;
             if attempt.text_len < opts.char_threshold {
                 continue;
             }
 
-            let diagnostic = AttemptDiagnostic {
-                index: 0,
-                flags: flags.into(),
-                candidate_count: 0,
-                candidates: Vec::new(),
-                entry_points: Vec::new(),
-                selected_root: Some(selected_root),
-                cleanup: Some(cleanup),
-                recovery,
-                text_len: attempt.text_len,
-                accepted: true,
-            };
+            let diagnostic = AttemptDiagnostic::new(0, flags)
+                .prepared(recovery, Vec::new())
+                .selected(Some(selected_root), cleanup, attempt.text_len, true);
             return Ok(Some((attempt, diagnostic)));
         }
     }
@@ -474,6 +824,132 @@ fn known_content_attempt(
     Ok(None)
 }
 
+/// Rebuild a live blog from its updates.
+///
+/// Live blogs are runs of short, timestamped posts that scoring treats as
+/// link-heavy chrome, so the updates are collected directly instead.
+fn live_blog_attempt(
+    html: &str, source: &SourceDom, opts: &ReadabilityOptions, stages: Stages<'_>, base_url: Option<&Url>,
+    metadata: &Metadata,
+) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
+    let document = source.copy();
+    let flags = ExtractFlags { strip_unlikely: false, weight_classes: false, clean_conditionally: false };
+    let (recovery, preprocess) = prep_document_with_visibility(&document, opts, flags, !opts.keep_hidden);
+    pipeline::after_preprocess(stages, &document)?;
+    let Some(root) = liveblog::live_blog_root(html, &document, opts.strategy) else {
+        return Ok(None);
+    };
+
+    let selected_root = node_diagnostic(&root);
+    let (attempt, cleanup) = serialize_roots(vec![root], opts, flags, base_url, metadata)?;
+    if attempt.text_len < opts.char_threshold {
+        return Ok(None);
+    }
+    let diagnostic = AttemptDiagnostic::new(0, flags)
+        .prepared(recovery, preprocess)
+        .selected(Some(selected_root), cleanup, attempt.text_len, true);
+    Ok(Some((attempt, diagnostic)))
+}
+
+/// The transcript of a video or podcast page as the article. Hidden elements
+/// are kept while looking, since transcripts are often collapsed.
+fn transcript_attempt(
+    html: &str, source: &SourceDom, opts: &ReadabilityOptions, stages: Stages<'_>, base_url: Option<&Url>,
+    metadata: &Metadata,
+) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
+    let document = source.copy();
+    let flags = ExtractFlags { strip_unlikely: false, weight_classes: false, clean_conditionally: false };
+    let (recovery, preprocess) = prep_document_with_visibility(&document, opts, flags, false);
+    pipeline::after_preprocess(stages, &document)?;
+    let Some(root) = transcript::transcript_root(html, &document, opts.strategy, opts.char_threshold) else {
+        return Ok(None);
+    };
+
+    let selected_root = node_diagnostic(&root);
+    let (attempt, cleanup) = serialize_roots(vec![root], opts, flags, base_url, metadata)?;
+    if attempt.text_len < opts.char_threshold {
+        return Ok(None);
+    }
+    let diagnostic = AttemptDiagnostic::new(0, flags)
+        .prepared(recovery, preprocess)
+        .selected(Some(selected_root), cleanup, attempt.text_len, true);
+    Ok(Some((attempt, diagnostic)))
+}
+
+/// An attempt that builds its root without candidate scoring.
+type FixedAttempt = fn(
+    &SourceDom,
+    &ReadabilityOptions,
+    Stages<'_>,
+    usize,
+    Option<&Url>,
+    &Metadata,
+) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>>;
+
+/// Keep the longest run of text-dense blocks instead of picking a candidate
+/// root. Forum threads and documentation pages split their text across many
+/// small containers that score poorly one by one.
+fn density_attempt(
+    source: &SourceDom, opts: &ReadabilityOptions, stages: Stages<'_>, index: usize, base_url: Option<&Url>,
+    metadata: &Metadata,
+) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
+    let document = source.copy();
+    let flags = ExtractFlags { strip_unlikely: false, weight_classes: false, clean_conditionally: false };
+    let (recovery, preprocess) = prep_document_with_visibility(&document, opts, flags, !opts.keep_hidden);
+    pipeline::after_preprocess(stages, &document)?;
+    let Some(root) = density::density_root(&document) else {
+        return Ok(None);
+    };
+
+    let selected_root = node_diagnostic(&root);
+    let (attempt, cleanup) = serialize_roots(vec![root], opts, flags, base_url, metadata)?;
+    if attempt.text_len == 0 {
+        return Ok(None);
+    }
+    let accepted = attempt.text_len >= opts.char_threshold;
+    let diagnostic = AttemptDiagnostic::new(index, flags)
+        .prepared(recovery, preprocess)
+        .selected(Some(selected_root), cleanup, attempt.text_len, accepted);
+    Ok(Some((attempt, diagnostic)))
+}
+
+/// Clean the whole `<body>` instead of picking a candidate root.
+///
+/// Personal pages and minimal blogs often have no wrapper element to score, so
+/// the body itself is the article once navigation and page chrome are gone.
+fn full_body_attempt(
+    source: &SourceDom, opts: &ReadabilityOptions, stages: Stages<'_>, index: usize, base_url: Option<&Url>,
+    metadata: &Metadata,
+) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
+    let document = source.copy();
+    let config = AttemptConfig { flags: ExtractFlags::all(), remove_hidden: true }.restricted_by(opts);
+    let flags = config.flags;
+    let (recovery, mut preprocess) = prep_document_with_visibility(&document, opts, flags, config.remove_hidden);
+    let mut tracker = RemovalTracker::default();
+    tracker.track("body-chrome", &document, || {
+        dom::remove_matching(
+            &document,
+            r#"nav, header, footer, aside, form, dialog, [role="banner"], [role="contentinfo"], [role="search"]"#,
+        )
+    });
+    preprocess.extend(tracker.finish());
+    pipeline::after_preprocess(stages, &document)?;
+    let Some(body) = dom::select_first(&document, "body") else {
+        return Ok(None);
+    };
+
+    let selected_root = node_diagnostic(&body);
+    let (attempt, cleanup) = serialize_roots(vec![body], opts, flags, base_url, metadata)?;
+    if attempt.text_len == 0 {
+        return Ok(None);
+    }
+    let accepted = attempt.text_len >= opts.char_threshold;
+    let diagnostic = AttemptDiagnostic::new(index, flags)
+        .prepared(recovery, preprocess)
+        .selected(Some(selected_root), cleanup, attempt.text_len, accepted);
+    Ok(Some((attempt, diagnostic)))
+}
+
 fn schema_text_attempt(
     metadata: &Metadata, opts: &ReadabilityOptions, base_url: Option<&Url>,
 ) -> Result<Option<(ExtractAttempt, AttemptDiagnostic)>> {
@@ -494,7 +970,7 @@ fn schema_text_attempt(
             shared::escape_html(&normalized)
         ))
     };
-    let Some(root) = dom::select_nodes(&document, "article").into_iter().next() else {
+    let Some(root) = dom::select_first(&document, "article") else {
         return Ok(None);
     };
 
@@ -505,18 +981,7 @@ fn schema_text_attempt(
         return Ok(None);
     }
 
-    let diagnostic = AttemptDiagnostic {
-        index: 0,
-        flags: flags.into(),
-        candidate_count: 0,
-        candidates: Vec::new(),
-        entry_points: Vec::new(),
-        selected_root: Some(selected_root),
-        cleanup: Some(cleanup),
-        recovery: RecoveryDiagnostic::default(),
-        text_len: attempt.text_len,
-        accepted: true,
-    };
+    let diagnostic = AttemptDiagnostic::new(0, flags).selected(Some(selected_root), cleanup, attempt.text_len, true);
 
     Ok(Some((attempt, diagnostic)))
 }
@@ -528,16 +993,14 @@ fn schema_text_contains_html(text: &str) -> bool {
         .any(|tag| lower.contains(tag))
 }
 
-fn source_has_rich_article_content(html: &str) -> bool {
-    let document = kuchiki::parse_html().one(html);
-    !dom::select_nodes(
-        &document,
+fn source_has_rich_article_content(document: &Html) -> bool {
+    let selector = patterns::selector(
         "article img, article figure, article picture, article table, article video, article iframe,\
          main img, main figure, main picture, main table, main video, main iframe,\
          [role='main'] img, [role='main'] figure, [role='main'] picture, [role='main'] table,\
          [role='main'] video, [role='main'] iframe",
-    )
-    .is_empty()
+    );
+    document.select(&selector).next().is_some()
 }
 
 fn title_duplicates_site_name(metadata: &Metadata) -> bool {
@@ -547,31 +1010,50 @@ fn title_duplicates_site_name(metadata: &Metadata) -> bool {
 }
 
 fn first_content_heading(content: &str) -> Option<String> {
-    dom::select_nodes(
+    dom::select_iter(
         &kuchiki::parse_html().one(format!("<html><body>{content}</body></html>")),
         "h1, h2, h3",
     )
-    .into_iter()
     .map(|heading| patterns::normalize_spaces(dom::inner_text(&heading).trim()))
     .find(|heading| !heading.is_empty())
 }
 
 fn try_site_rule(
-    html: &str, options: &ReadabilityOptions, base_url: Option<&Url>, metadata: &Metadata,
+    source: &SourceDom, options: &ReadabilityOptions, stages: Stages<'_>, base_url: Option<&Url>, metadata: &Metadata,
 ) -> Result<Option<rules::RuleExtraction>> {
-    let doc = kuchiki::parse_html().one(html);
+    let doc = source.copy();
     prep_document(
         &doc,
         options,
         ExtractFlags { strip_unlikely: false, weight_classes: false, clean_conditionally: false },
     );
+    pipeline::after_preprocess(stages, &doc)?;
     rules::extract_with_site_rule(&doc, base_url, options, metadata)
 }
 
+/// Run only the site rule stage, as [`rules::check_site_profile`] does to
+/// test a profile without the stages that run before it.
+pub(crate) fn site_rule_attempt(
+    html: &str, base_url: &Url, options: &ReadabilityOptions,
+) -> Result<Option<rules::RuleExtraction>> {
+    let (html, _) = recovery::recover_html_snapshot(html);
+    let document = Html::parse_document(&html);
+    let metadata = metadata::extract_metadata(&document, &html, options, Some(base_url));
+    try_site_rule(&SourceDom::new(&html), options, &[], Some(base_url), &metadata)
+}
+
 fn normalize_markup(document: &NodeRef) {
     for font in dom::select_nodes(document, "font") {
         let _ = dom::retag_node(&font, "span");
     }
+    // Netscape-era layout elements are plain containers; as divs they get the
+    // same paragraph conversion and scoring as modern markup.
+    for node in dom::select_nodes(document, "layer, ilayer, nolayer, center, multicol") {
+        let _ = dom::retag_node(&node, "div");
+    }
+
+    markdown::math::normalize_math_markup(document);
+    unwrap_custom_elements(document);
 
     for div in dom::select_nodes(document, "div") {
         if has_single_element_child(&div, "p") && direct_text_is_empty(&div) {
@@ -586,6 +1068,47 @@ fn normalize_markup(document: &NodeRef) {
     }
 }
 
+/// Treat custom elements as transparent containers by retagging them as the
+/// `div` or `p` they stand in for, or as `article` when the name says so.
+/// Innermost elements are handled first so a wrapper of custom paragraphs
+/// sees the converted children. Custom elements that sit beside text, and
+/// MathJax output, are left alone.
+fn unwrap_custom_elements(document: &NodeRef) {
+    let custom: Vec<_> = dom::select_iter(document, "*")
+        .filter(|node| dom::is_custom_element(node) && !dom::node_name(node).starts_with("mjx-"))
+        .collect();
+    for node in custom.into_iter().rev() {
+        if has_child_block_element(&node) {
+            let tag = match is_article_element(&node) {
+                true => "article",
+                false => "div",
+            };
+            let _ = dom::retag_node(&node, tag);
+        } else if !dom::inner_text(&node).is_empty()
+            && node
+                .parent()
+                .is_some_and(|parent| is_flow_container(&parent) && direct_text_is_empty(&parent))
+        {
+            let _ = dom::retag_node(&node, "p");
+        }
+    }
+}
+
+/// Whether a custom element's name marks it as an article wrapper, such as
+/// `<news-article>` or `<story-body>`, and not page chrome like
+/// `<post-comments>`.
+fn is_article_element(node: &NodeRef) -> bool {
+    let name = dom::node_name(node);
+    RegexPattern::ArticleElementName.to_regex().is_match(&name) && !RegexPattern::Negative.to_regex().is_match(&name)
+}
+
+fn is_flow_container(node: &NodeRef) -> bool {
+    matches!(
+        dom::node_name(node).as_str(),
+        "article" | "body" | "div" | "main" | "section"
+    ) || dom::is_custom_element(node)
+}
+
 fn has_single_element_child(node: &NodeRef, tag: &str) -> bool {
     let mut element_children = node.children().filter(|child| child.as_element().is_some());
     let Some(first) = element_children.next() else {
@@ -601,15 +1124,17 @@ fn direct_text_is_empty(node: &NodeRef) -> bool {
 }
 
 fn has_child_block_element(node: &NodeRef) -> bool {
-    !dom::select_nodes(
+    dom::exists(
         node,
         "address, article, aside, blockquote, canvas, dd, div, dl, dt, fieldset, figcaption, figure, footer, form, h1, h2, h3, h4, h5, h6, header, hgroup, hr, li, main, nav, noscript, ol, output, p, pre, section, table, tfoot, ul, video",
     )
-    .is_empty()
 }
 
 fn effective_base_url(document: &Html, base_url: Option<&Url>) -> Option<Url> {
-    let base_url = base_url.cloned()?;
+    // Without a caller URL, an absolute canonical link is the page's own address.
+    let base_url = base_url
+        .cloned()
+        .or_else(|| Url::parse(&metadata::canonical_url(document, None)?).ok())?;
     let selector = patterns::selector("base[href]");
     document
         .select(&selector)
@@ -619,16 +1144,23 @@ fn effective_base_url(document: &Html, base_url: Option<&Url>) -> Option<Url> {
         .or(Some(base_url))
 }
 
-fn unwrap_noscript_images(document: &NodeRef) {
+/// Replace `<noscript>` elements holding images with their content.
+///
+/// With `recover`, a fallback that is a single image replaces the lone
+/// placeholder image just before it instead of sitting beside it, and
+/// fallbacks with enough text are unwrapped too unless they only ask the
+/// reader to enable JavaScript.
+fn unwrap_noscript(document: &NodeRef, recover: bool) {
     for noscript in dom::select_nodes(document, "noscript") {
         let content = unescape_noscript_markup(&noscript.text_contents());
         let lower_content = content.to_ascii_lowercase();
-        if !lower_content.contains("<img") && !lower_content.contains("<picture") {
+        let has_image = lower_content.contains("<img") || lower_content.contains("<picture");
+        if !has_image && !recover {
             continue;
         }
 
         let fragment = kuchiki::parse_html().one(format!("<html><body>{content}</body></html>"));
-        let Some(body) = dom::select_nodes(&fragment, "body").into_iter().next() else {
+        let Some(body) = dom::select_first(&fragment, "body") else {
             continue;
         };
         let children: Vec<_> = body.children().collect();
@@ -636,6 +1168,24 @@ fn unwrap_noscript_images(document: &NodeRef) {
             continue;
         }
 
+        if recover && has_image {
+            if let Some(placeholder) = previous_single_image(&noscript)
+                && is_single_image(&body)
+                && let Some(image) = dom::select_first(&body, "img")
+                && let Some(old_image) = dom::select_first(&placeholder, "img")
+            {
+                keep_placeholder_sources(&old_image, &image);
+                for child in children {
+                    placeholder.insert_before(child);
+                }
+                placeholder.detach();
+                noscript.detach();
+                continue;
+            }
+        } else if recover && !is_noscript_text_fallback(&body) {
+            continue;
+        }
+
         for child in children {
             noscript.insert_before(child);
         }
@@ -643,6 +1193,58 @@ fn unwrap_noscript_images(document: &NodeRef) {
     }
 }
 
+fn is_noscript_text_fallback(body: &NodeRef) -> bool {
+    let text = dom::inner_text(body);
+    text.chars().count() >= MIN_NOSCRIPT_TEXT_LEN && !RegexPattern::NoscriptNotice.to_regex().is_match(&text)
+}
+
+/// The element just before `node`, skipping whitespace, when it holds
+/// nothing but one image.
+fn previous_single_image(node: &NodeRef) -> Option<NodeRef> {
+    let previous = node
+        .preceding_siblings()
+        .find(|sibling| sibling.as_element().is_some() || !sibling.text_contents().trim().is_empty())?;
+    (previous.as_element().is_some() && is_single_image(&previous)).then_some(previous)
+}
+
+/// Whether `node` is an image, or wraps exactly one image with no text.
+fn is_single_image(node: &NodeRef) -> bool {
+    if dom::node_name(node) == "img" {
+        return true;
+    }
+    let mut elements = node.children().filter(|child| child.as_element().is_some());
+    let Some(only) = elements.next() else {
+        return false;
+    };
+    elements.next().is_none() && node.text_contents().trim().is_empty() && is_single_image(&only)
+}
+
+/// Carry the placeholder's image URLs over to the fallback image, renaming
+/// them to `data-old-*` where the fallback already sets that attribute, so
+/// lazy-image repair can still see them.
+fn keep_placeholder_sources(placeholder: &NodeRef, image: &NodeRef) {
+    let (Some(old), Some(new)) = (placeholder.as_element(), image.as_element()) else {
+        return;
+    };
+    let old_attributes = old.attributes.borrow();
+    let mut new_attributes = new.attributes.borrow_mut();
+    for (name, attribute) in old_attributes.map.iter() {
+        let name = name.local.to_string();
+        let value = &attribute.value;
+        if value.is_empty()
+            || !(name == "src" || name == "srcset" || RegexPattern::LazyImageUrl.to_regex().is_match(value))
+            || new_attributes.get(name.as_str()) == Some(value.as_str())
+        {
+            continue;
+        }
+        let name = match new_attributes.contains(name.as_str()) {
+            true => format!("data-old-{name}"),
+            false => name,
+        };
+        new_attributes.insert(name, value.clone());
+    }
+}
+
 fn unescape_noscript_markup(value: &str) -> String {
     value
         .replace("&lt;", "<")
@@ -653,11 +1255,11 @@ fn unescape_noscript_markup(value: &str) -> String {
 }
 
 fn grab_article(
-    doc: &NodeRef, opts: &ReadabilityOptions, flags: ExtractFlags, index: usize, recovery: RecoveryDiagnostic,
-    base_url: Option<&Url>, metadata: &Metadata,
+    doc: &NodeRef, opts: &ReadabilityOptions, flags: ExtractFlags, index: usize, base_url: Option<&Url>,
+    metadata: &Metadata, deadline: &Deadline,
 ) -> Result<Option<(ExtractAttempt, GrabDiagnostics)>> {
     if let Some(selector) = opts.content_selector.as_deref()
-        && let Some(root) = dom::select_nodes(doc, selector).into_iter().next()
+        && let Some(root) = dom::select_first(doc, selector)
     {
         let selector_diagnostic = ContentSelectorDiagnostic {
             selector: selector.to_string(),
@@ -665,28 +1267,28 @@ fn grab_article(
             selected: Some(node_diagnostic(&root)),
         };
         let (attempt, cleanup) = serialize_roots(vec![root], opts, flags, base_url, metadata)?;
-        let attempt_diagnostic = AttemptDiagnostic {
-            index,
-            flags: flags.into(),
-            candidate_count: 0,
-            candidates: Vec::new(),
-            entry_points: Vec::new(),
-            selected_root: selector_diagnostic.selected.clone(),
-            cleanup: Some(cleanup),
-            recovery,
-            text_len: attempt.text_len,
-            accepted: attempt.text_len >= opts.char_threshold,
-        };
+        let accepted = attempt.text_len >= opts.char_threshold;
+        let attempt_diagnostic = AttemptDiagnostic::new(index, flags).selected(
+            selector_diagnostic.selected.clone(),
+            cleanup,
+            attempt.text_len,
+            accepted,
+        );
         return Ok(Some((
             attempt,
-            GrabDiagnostics { attempt: attempt_diagnostic, content_selector: Some(selector_diagnostic) },
+            GrabDiagnostics {
+                attempt: attempt_diagnostic,
+                content_selector: Some(selector_diagnostic),
+                nodes_scanned: 0,
+            },
         )));
     }
 
-    let entry_points = entry_point_candidates(doc);
-    let mut candidates = scoring::score_candidates(doc, flags);
+    let mut cache = ScoreCache::default();
+    let entry_points = entry_point_candidates(doc, opts, &mut cache);
+    let (mut candidates, nodes_scanned) = scoring::score_candidates(doc, flags, opts, deadline, &mut cache)?;
     if candidates.is_empty() {
-        let body = dom::select_nodes(doc, "body").into_iter().next();
+        let body = dom::select_first(doc, "body");
         if let Some(body) = body {
             candidates.push(Candidate { node: body, score: 1.0 });
         }
@@ -720,6 +1322,8 @@ fn grab_article(
     });
 
     candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    // Siblings outside the top candidates still count their own scores.
+    cache.record(&candidates);
 
     let candidate_count = candidates.len();
 
@@ -740,14 +1344,16 @@ fn grab_article(
 
     let top_candidate = candidates[0].node.clone();
     let top_score = candidates[0].score;
+    tracing::trace!(
+        candidate_count,
+        top_score,
+        top_candidate = %node_selector(&top_candidate),
+        "scored candidates"
+    );
     let top_id = dom::node_id(&top_candidate);
-    let score_by_id: HashMap<usize, f64> = candidates
-        .iter()
-        .map(|candidate| (dom::node_id(&candidate.node), candidate.score))
-        .collect();
 
     let parent = top_candidate.parent().unwrap_or_else(|| doc.clone());
-    let sibling_threshold = 10.0_f64.max(top_score * 0.2);
+    let sibling_threshold = 10.0_f64.max(top_score * f64::from(opts.sibling_threshold));
     let top_class = dom::attr(&top_candidate, "class").unwrap_or_default();
 
     let mut included = Vec::new();
@@ -760,7 +1366,7 @@ fn grab_article(
                 content_bonus += top_score * 0.2;
             }
 
-            if score_by_id.get(&dom::node_id(&sibling)).copied().unwrap_or(0.0) + content_bonus >= sibling_threshold {
+            if cache.score(&sibling).unwrap_or(0.0) + content_bonus >= sibling_threshold {
                 append = true;
             } else if dom::node_name(&sibling) == "p" {
                 let density = scoring::link_density(&sibling);
@@ -784,6 +1390,7 @@ fn grab_article(
     if let Some(focused_root) = larger_focused_subtree(&candidates[0].node, included_text_len, opts.char_threshold) {
         included = vec![focused_root];
     }
+    let included = with_lead_siblings(included);
 
     let selected_root = included.first().map(node_diagnostic);
     let (attempt, cleanup) = serialize_roots(included, opts, flags, base_url, metadata)?;
@@ -791,29 +1398,26 @@ fn grab_article(
         .content_selector
         .as_ref()
         .map(|selector| ContentSelectorDiagnostic { selector: selector.clone(), matched: false, selected: None });
+    let accepted = attempt.text_len >= opts.char_threshold;
     let attempt_diagnostic = AttemptDiagnostic {
-        index,
-        flags: flags.into(),
         candidate_count,
         candidates: candidate_diagnostics,
         entry_points: entry_points
             .iter()
             .map(|entry_point| entry_point.diagnostic.clone())
             .collect(),
-        selected_root,
-        cleanup: Some(cleanup),
-        recovery,
-        text_len: attempt.text_len,
-        accepted: attempt.text_len >= opts.char_threshold,
+        ..AttemptDiagnostic::new(index, flags).selected(selected_root, cleanup, attempt.text_len, accepted)
     };
 
     Ok(Some((
         attempt,
-        GrabDiagnostics { attempt: attempt_diagnostic, content_selector },
+        GrabDiagnostics { attempt: attempt_diagnostic, content_selector, nodes_scanned },
     )))
 }
 
-fn entry_point_candidates(document: &NodeRef) -> Vec<EntryPointCandidate> {
+fn entry_point_candidates(
+    document: &NodeRef, options: &ReadabilityOptions, cache: &mut ScoreCache,
+) -> Vec<EntryPointCandidate> {
     let mut candidates = Vec::new();
     let mut seen = std::collections::HashSet::new();
 
@@ -831,7 +1435,7 @@ fn entry_point_candidates(document: &NodeRef) -> Vec<EntryPointCandidate> {
                 continue;
             }
             let score = (text_len as f64 / 25.0) * (1.0 - link_density).max(0.0)
-                + scoring::class_weight(&node, ExtractFlags::all()) as f64;
+                + cache.class_weight(&node, ExtractFlags::all(), options) as f64;
             let diagnostic = CandidateDiagnostic {
                 node: node_diagnostic(&node),
                 score: round_score(score),
@@ -862,11 +1466,68 @@ fn selected_entry_point(entry_points: &[EntryPointCandidate]) -> Option<Candidat
     Some(Candidate { node: selected.node.clone(), score: best.score + 25.0 })
 }
 
-fn larger_focused_subtree(node: &NodeRef, current_text_len: usize, char_threshold: usize) -> Option<NodeRef> {
-    if shared::word_count(&dom::inner_text(node)) >= EXTREMELY_SHORT_WORD_THRESHOLD
-        && !looks_like_narrow_non_article(node)
-    {
-        return None;
+/// Prepend the headline and standfirst that sit just before the first root.
+///
+/// CMS templates often render `<h1>` and the dek as siblings of the body
+/// container, so the selected root alone loses the opening of the article.
+fn with_lead_siblings(roots: Vec<NodeRef>) -> Vec<NodeRef> {
+    let Some(first) = roots.first() else {
+        return roots;
+    };
+    if matches!(dom::node_name(first).as_str(), "body" | "html") {
+        return roots;
+    }
+    // A root that opens with its own headline does not need the one outside it.
+    if first
+        .children()
+        .find(|node| node.as_element().is_some())
+        .is_some_and(|node| matches!(dom::node_name(&node).as_str(), "h1" | "h2"))
+    {
+        return roots;
+    }
+
+    let mut leads: Vec<NodeRef> = first
+        .preceding_siblings()
+        .filter(|node| node.as_element().is_some())
+        .take_while(is_lead_sibling)
+        .take(3)
+        .collect();
+    if leads.is_empty() {
+        return roots;
+    }
+    leads.reverse();
+    leads.extend(roots);
+    leads
+}
+
+fn is_lead_sibling(node: &NodeRef) -> bool {
+    let text_len = dom::inner_text(node).chars().count();
+    if text_len == 0 || scoring::link_density(node) > 0.25 {
+        return false;
+    }
+    let tag = dom::node_name(node);
+    if tag == "h1" {
+        return text_len <= 200;
+    }
+    if !matches!(tag.as_str(), "h2" | "h3" | "p" | "div") || text_len > 500 {
+        return false;
+    }
+    dom::class_id_string(node)
+        .to_ascii_lowercase()
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .any(|token| {
+            matches!(
+                token,
+                "dek" | "deck" | "standfirst" | "subtitle" | "subheadline" | "subhead" | "lede" | "lead" | "summary"
+            )
+        })
+}
+
+fn larger_focused_subtree(node: &NodeRef, current_text_len: usize, char_threshold: usize) -> Option<NodeRef> {
+    if shared::word_count(&dom::inner_text(node)) >= EXTREMELY_SHORT_WORD_THRESHOLD
+        && !looks_like_narrow_non_article(node)
+    {
+        return None;
     }
 
     let min_text_len = char_threshold.max(current_text_len.saturating_mul(2)).max(1);
@@ -911,7 +1572,7 @@ fn node_diagnostic(node: &NodeRef) -> NodeDiagnostic {
     }
 }
 
-fn node_selector(node: &NodeRef) -> String {
+pub(crate) fn node_selector(node: &NodeRef) -> String {
     let tag = dom::node_name(node);
     if tag.is_empty() {
         return "<node>".to_string();
@@ -935,6 +1596,13 @@ fn round_score(value: f64) -> f64 {
     (value * 1000.0).round() / 1000.0
 }
 
+fn enforce_input_limit(html: &str, limit: Option<usize>) -> Result<()> {
+    match limit {
+        Some(limit) if html.len() > limit => Err(Error::input_too_large(html.len(), limit)),
+        _ => Ok(()),
+    }
+}
+
 fn enforce_element_limit(document: &Html, limit: Option<usize>) -> Result<()> {
     match limit {
         Some(limit) => {
@@ -948,6 +1616,10 @@ fn enforce_element_limit(document: &Html, limit: Option<usize>) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use base64::Engine;
+
     use super::*;
     use crate::config::MediaRetention;
     use crate::patterns::normalize_spaces;
@@ -968,6 +1640,241 @@ mod tests {
         assert!(article.length > 25);
     }
 
+    #[test]
+    fn retries_with_relaxed_flags_when_unlikely_stripping_loses_the_article() {
+        let paragraph = "The harbour master keeps a ledger of every tide, noting the height of the water, \
+            the hour it turned, and which boats were able to cross the bar before it fell again.";
+        let html = format!(
+            r#"<html><body>
+            <div class="sidebar-story">
+                <p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p>
+            </div>
+            </body></html>"#
+        );
+
+        let report = extract_with_diagnostics(&html, None, &ReadabilityOptions::default()).unwrap();
+        let article = report.article.unwrap();
+        assert_eq!(report.diagnostics.outcome, ExtractionOutcome::Accepted);
+        assert!(report.diagnostics.attempts.len() > 1);
+        let selected = report.diagnostics.selected_attempt.unwrap();
+        assert!(selected > 0);
+        assert!(!report.diagnostics.attempts[selected].flags.strip_unlikely);
+        assert!(article.text_content.contains("harbour master keeps a ledger"));
+    }
+
+    #[test]
+    fn strips_user_unlikely_patterns_before_scoring() {
+        let paragraph = "The harbour master keeps a ledger of every tide, noting the height of the water, \
+            the hour it turned, and which boats were able to cross the bar before it fell again.";
+        let html = format!(
+            r#"<html><body><div class="harbour-log">
+                <p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p>
+                <div class="promo-well content">
+                    <p>Subscribe for more stories about boats, tides, and the harbour, delivered each week.</p>
+                    <p>Every issue brings charts, almanac notes, and letters from readers along the coast.</p>
+                </div>
+                <p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p>
+            </div></body></html>"#
+        );
+
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(&html, None, &options).unwrap().unwrap();
+        assert!(article.text_content.contains("Subscribe"));
+
+        let options = ReadabilityOptions { unlikely_patterns: vec!["Promo-Well".to_string()], ..options };
+        let article = extract(&html, None, &options).unwrap().unwrap();
+        assert!(!article.text_content.contains("Subscribe"));
+        assert!(article.text_content.contains("harbour master keeps a ledger"));
+    }
+
+    #[test]
+    fn hidden_and_unlikely_walk_matches_separate_passes() {
+        let separate = |html: &str, options: &ReadabilityOptions| {
+            let document = kuchiki::parse_html().one(html);
+            for node in dom::select_nodes(&document, "*") {
+                if !dom::is_kuchiki_visible(&node) {
+                    node.detach();
+                }
+            }
+            for node in dom::select_nodes(&document, "*") {
+                if is_unlikely_node(&node, options) {
+                    node.detach();
+                }
+            }
+            document.to_string()
+        };
+        let walked = |html: &str, options: &ReadabilityOptions| {
+            let document = kuchiki::parse_html().one(html);
+            remove_hidden_and_unlikely(&document, options, true, true, &mut RemovalTracker::default());
+            document.to_string()
+        };
+
+        let options = ReadabilityOptions { unlikely_patterns: vec!["promo".to_string()], ..Default::default() };
+        let nested = r#"<html><body>
+            <div class="sidebar"><p hidden>Hidden in chrome</p><div class="promo">Promo in chrome</div></div>
+            <div hidden><div class="comment">Chrome in hidden</div></div>
+            <table><tr><td><div class="sidebar">Kept inside a table</div></td></tr></table>
+            <article><p>Body</p><div role="dialog"><p style="display:none">Both</p></div><a class="share">Link</a></article>
+        </body></html>"#;
+        assert_eq!(walked(nested, &options), separate(nested, &options));
+
+        for name in [
+            "wikipedia",
+            "cnet",
+            "dropbox-blog",
+            "hidden-nodes",
+            "remove-aria-hidden",
+            "visibility-hidden",
+        ] {
+            let fixture = lectito_fixtures::load_fixture(name).unwrap();
+            assert_eq!(
+                walked(&fixture.source, &options),
+                separate(&fixture.source, &options),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn pattern_groups_can_be_extended_or_replaced() {
+        let paragraph = "The harbour master keeps a ledger of every tide, noting the height of the water, \
+            the hour it turned, and which boats were able to cross the bar before it fell again.";
+        let html = format!(
+            r#"<html><body><article>
+                <p>{paragraph}</p><p>{paragraph}</p>
+                <button>Copy page</button><button>Edit this page</button>
+                <p>{paragraph}</p>
+                <div class="tide-almanac"><h4>Tide tables</h4><p>Get the harbour letter in your inbox each week.</p></div>
+            </article></body></html>"#
+        );
+        let text = |options: ReadabilityOptions| {
+            let options = ReadabilityOptions { char_threshold: 0, ..options };
+            extract(&html, None, &options).unwrap().unwrap().text_content
+        };
+
+        let builtin = text(ReadabilityOptions::default());
+        assert!(!builtin.contains("Copy page"));
+        assert!(builtin.contains("Edit this page"));
+        assert!(builtin.contains("harbour letter"));
+
+        let extended = text(ReadabilityOptions {
+            doc_chrome_labels: vec!["edit this page".to_string()],
+            trailing_chrome_patterns: vec!["tide-almanac".to_string()],
+            ..Default::default()
+        });
+        assert!(!extended.contains("Copy page"));
+        assert!(!extended.contains("Edit this page"));
+        assert!(!extended.contains("harbour letter"));
+
+        let replaced = text(ReadabilityOptions {
+            doc_chrome_labels: vec!["Edit this page".to_string()],
+            replace_pattern_groups: vec![PatternGroup::DocChrome],
+            ..Default::default()
+        });
+        assert!(replaced.contains("Copy page"));
+        assert!(!replaced.contains("Edit this page"));
+    }
+
+    #[test]
+    fn moves_author_bio_box_into_metadata() {
+        let paragraph = "The harbour master keeps a ledger of every tide, noting the height of the water, \
+            the hour it turned, and which boats were able to cross the bar before it fell again.";
+        let html = format!(
+            r#"<html><body><article>
+                <p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p>
+                <p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p>
+                <div class="post-author-bio">
+                    <h3>About the author</h3>
+                    <p>Mara Quill has reported on the harbour for twenty years.</p>
+                </div>
+            </article></body></html>"#
+        );
+
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(&html, None, &options).unwrap().unwrap();
+        assert_eq!(
+            article.author_bio.as_deref(),
+            Some("Mara Quill has reported on the harbour for twenty years.")
+        );
+        assert!(!article.text_content.contains("Mara Quill"));
+        assert!(article.text_content.contains("harbour master keeps a ledger"));
+
+        let json_ld = r#"<script type="application/ld+json">{"@type": "NewsArticle",
+            "author": {"@type": "Person", "name": "Mara Quill", "description": "Harbour correspondent."}}</script>"#;
+        let html = html.replacen("<body>", &format!("<head>{json_ld}</head><body>"), 1);
+        let article = extract(&html, None, &options).unwrap().unwrap();
+        assert_eq!(article.author_bio.as_deref(), Some("Harbour correspondent."));
+    }
+
+    #[test]
+    fn scores_article_body_wrapped_in_custom_elements() {
+        let paragraph = "Custom element paragraphs carry the story, with commas, detail, and enough words to score.";
+        let html = format!(
+            r#"<html><body>
+                <div class="promo"><p>Subscribe for more stories like this one.</p></div>
+                <news-story><story-body>
+                    <story-paragraph>{paragraph}</story-paragraph>
+                    <story-paragraph>{paragraph}</story-paragraph>
+                    <story-paragraph>Inline <relative-time>yesterday</relative-time> markers stay inline, too.</story-paragraph>
+                </story-body></news-story>
+            </body></html>"#
+        );
+
+        let article = extract(
+            &html,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(
+            article.text_content.contains("Custom element paragraphs"),
+            "{}",
+            article.content
+        );
+        assert!(
+            article.text_content.contains("Inline yesterday markers"),
+            "{}",
+            article.content
+        );
+        assert!(!article.text_content.contains("Subscribe"), "{}", article.content);
+        assert!(!article.content.contains("<story-paragraph"), "{}", article.content);
+    }
+
+    #[test]
+    fn preserves_mathjax_scripts_and_katex_as_mathml() {
+        let html = r#"<html><body><article>
+            <p>This article explains the quadratic formula, with enough punctuation and prose to be extracted as readable content.</p>
+            <p>Inline <script type="math/tex">a \ne 0</script> and display <script type="math/tex; mode=display">x = \frac{-b}{2a}</script> math.</p>
+            <p>KaTeX <span class="katex"><span class="katex-mathml"><math><semantics><mrow><mi>E</mi></mrow><annotation encoding="application/x-tex">E=mc^2</annotation></semantics></math></span><span class="katex-html" aria-hidden="true">E=mc2</span></span> here.</p>
+        </article></body></html>"#;
+
+        let article = extract(
+            html,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(
+            article.content.contains(r#"<math alttext="a \ne 0">"#),
+            "{}",
+            article.content
+        );
+        assert!(article.content.contains(r#"display="block""#), "{}", article.content);
+        assert!(!article.content.contains("katex-html"), "{}", article.content);
+        assert!(!article.text_content.contains("E=mc2"), "{}", article.text_content);
+        assert!(article.markdown.contains("$a \\ne 0$"), "{}", article.markdown);
+        assert!(
+            article.markdown.contains("$$\nx = \\frac{-b}{2a}\n$$"),
+            "{}",
+            article.markdown
+        );
+        assert!(article.markdown.contains("$E=mc^2$"), "{}", article.markdown);
+    }
+
     #[test]
     fn accepts_long_json_ld_article_body_before_candidate_scoring() {
         let schema_text = "This article body comes from JSON-LD before the generic scoring path runs. ".repeat(20);
@@ -1164,6 +2071,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn recovers_noscript_fallbacks_when_asked() {
+        let html = r#"<html><body><article>
+            <p>Enough text, with punctuation, to choose the article body for this regression.</p>
+            <figure><img class="lazy" src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-src="/photo.jpg"></figure>
+            <noscript><img src="/photo.jpg" alt="Harbour at dawn"></noscript>
+            <noscript><p>The rest of the story is rendered by a script, so this copy waits here for readers without it.</p></noscript>
+            <noscript>Please enable JavaScript to view the comments powered by our comment service.</noscript>
+            </article></body></html>"#;
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+
+        let article = extract(html, Some("https://example.com/story"), &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(article.content.matches("<img").count(), 2);
+        assert!(!article.text_content.contains("rest of the story"));
+
+        let options = ReadabilityOptions { recover_noscript: true, ..options };
+        let article = extract(html, Some("https://example.com/story"), &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(article.content.matches("<img").count(), 1);
+        assert!(article.content.contains(r#"src="https://example.com/photo.jpg""#));
+        assert!(article.content.contains(r#"alt="Harbour at dawn""#));
+        assert!(
+            article
+                .text_content
+                .contains("rest of the story is rendered by a script")
+        );
+        assert!(!article.text_content.contains("enable JavaScript"));
+    }
+
     #[test]
     fn repairs_noscript_images_and_scores_br_divs() {
         let noscript_article = extract(
@@ -1294,6 +2233,36 @@ mod tests {
         assert!(article.content.contains("Api::29"), "{}", article.content);
     }
 
+    #[test]
+    fn keeps_headline_and_dek_outside_the_body_container() {
+        let article = extract(
+            r#"<html><head><title>Quiet Rivers Return | Example News</title></head><body>
+                <div class="layout">
+                    <h1>Quiet Rivers Return</h1>
+                    <h2 class="article-dek">How a valley brought its rivers back after decades of decline</h2>
+                    <div class="article-content">
+                        <p>The river moved slowly through the valley, carrying sediment and stories from the mountains above, and the town depended on it.</p>
+                        <p>Fishermen, millers, and farmers all shared the water, negotiating rights and seasons in long meetings by the old bridge.</p>
+                    </div>
+                </div>
+                <div class="sidebar"><a href="/a">Other stories</a></div>
+            </body></html>"#,
+            Some("https://example.com/rivers"),
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(
+            article
+                .markdown
+                .starts_with("# Quiet Rivers Return\n\n## How a valley brought its rivers back"),
+            "{}",
+            article.markdown
+        );
+        assert!(!article.text_content.contains("Other stories"));
+    }
+
     #[test]
     fn uses_content_heading_when_metadata_title_is_site_name() {
         let article = extract(
@@ -1341,24 +2310,245 @@ mod tests {
     }
 
     #[test]
-    fn none_media_retention_removes_body_figures() {
-        let html = r#"
-            <html><body><article>
-                <p>This article explains a diagram, with enough punctuation and prose to be extracted as readable content.</p>
-                <div class="figure"><img src="curve.png"></div>
-                <p>The paragraph after the figure continues the argument and proves the figure sits inside the article body.</p>
-            </article></body></html>
-        "#;
-
-        let article = extract(
-            html,
-            Some("https://example.com/articles/story.html"),
-            &ReadabilityOptions { char_threshold: 0, media_retention: MediaRetention::None, ..Default::default() },
+    fn none_media_retention_removes_body_figures() {
+        let html = r#"
+            <html><body><article>
+                <p>This article explains a diagram, with enough punctuation and prose to be extracted as readable content.</p>
+                <div class="figure"><img src="curve.png"></div>
+                <p>The paragraph after the figure continues the argument and proves the figure sits inside the article body.</p>
+            </article></body></html>
+        "#;
+
+        let article = extract(
+            html,
+            Some("https://example.com/articles/story.html"),
+            &ReadabilityOptions { char_threshold: 0, media_retention: MediaRetention::None, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(!article.content.contains("<img"), "{}", article.content);
+    }
+
+    #[test]
+    fn iframe_allowlist_keeps_configured_embeds() {
+        let html = r#"
+            <html><body><article>
+                <p>This article embeds an interactive chart, with enough punctuation and prose to be extracted as readable content.</p>
+                <iframe src="https://charts.datawrapper.de/abc123/" title="Turnout chart"></iframe>
+                <iframe src="https://ads.example.net/slot"></iframe>
+                <p>The paragraph after the chart continues the argument and proves the embed sits inside the article body.</p>
+            </article></body></html>
+        "#;
+        let options = ReadabilityOptions {
+            char_threshold: 0,
+            iframe_allowlist: vec!["datawrapper.de".to_string()],
+            ..Default::default()
+        };
+
+        let article = extract(html, None, &options).unwrap().unwrap();
+        assert!(article.content.contains("charts.datawrapper.de"), "{}", article.content);
+        assert!(!article.content.contains("ads.example.net"), "{}", article.content);
+        assert!(
+            article
+                .markdown
+                .contains("[Turnout chart](https://charts.datawrapper.de/abc123/)"),
+            "{}",
+            article.markdown
+        );
+
+        let article = extract(
+            html,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(!article.content.contains("<iframe"), "{}", article.content);
+    }
+
+    #[test]
+    fn preprocess_opt_outs_keep_hidden_and_unlikely_content() {
+        let html = r#"
+            <html><body><article>
+                <p>This article body has enough prose, punctuation, and detail to be selected by normal scoring.</p>
+                <p hidden>Hidden errata paragraph that a reader asked to keep in the output.</p>
+                <div class="sidebar-note"><p>Sidebar note paragraph, long enough to keep, with commas, and more words.</p></div>
+                <iframe src="https://ads.example.net/slot"></iframe>
+                <p>A second paragraph keeps the article comfortably above the empty threshold.</p>
+            </article></body></html>
+        "#;
+
+        let report = extract_with_diagnostics(
+            html,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap();
+        let first = &report.diagnostics.attempts[0];
+        assert!(first.flags.strip_unlikely);
+        assert!(first.preprocess.iter().any(|rule| rule.rule == "hidden"));
+
+        let options = ReadabilityOptions {
+            char_threshold: 0,
+            keep_hidden: true,
+            keep_unlikely_candidates: true,
+            keep_iframes: true,
+            ..Default::default()
+        };
+        let report = extract_with_diagnostics(html, None, &options).unwrap();
+        assert!(report.diagnostics.attempts.iter().all(
+            |attempt| !attempt.flags.strip_unlikely && attempt.preprocess.iter().all(|rule| rule.rule != "hidden")
+        ));
+        let article = report.article.unwrap();
+        assert!(
+            article.text_content.contains("Hidden errata"),
+            "{}",
+            article.text_content
+        );
+        assert!(
+            article.text_content.contains("Sidebar note"),
+            "{}",
+            article.text_content
+        );
+        assert!(article.content.contains("ads.example.net"), "{}", article.content);
+    }
+
+    #[test]
+    fn full_body_cleanup_keeps_unwrapped_personal_page_text() {
+        let html = r#"
+            <html><head><title>Notes</title></head><body>
+                <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+                <h1>Notes on gardening</h1>
+                <p>I planted tomatoes early this year, and the cold snap in April taught me to wait.</p>
+                <div><p>The beans did better, climbing the fence within a few weeks of sowing.</p></div>
+                <div><p>Next spring I will start seedlings indoors and move them out in May.</p></div>
+                <footer>Copyright 2026 Jo Example</footer>
+            </body></html>
+        "#;
+
+        let report = extract_with_diagnostics(
+            html,
+            None,
+            &ReadabilityOptions {
+                char_threshold: 0,
+                strategy: ExtractionStrategy::FullBodyCleanup,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let article = report.article.unwrap();
+
+        assert_eq!(report.diagnostics.attempts.len(), 1);
+        assert_eq!(
+            report.diagnostics.attempts[0]
+                .selected_root
+                .as_ref()
+                .map(|root| root.tag.as_str()),
+            Some("body")
+        );
+        assert!(article.text_content.contains("tomatoes early"));
+        assert!(article.text_content.contains("beans did better"));
+        assert!(article.text_content.contains("seedlings indoors"));
+        assert!(!article.text_content.contains("About"));
+        assert!(!article.text_content.contains("Copyright"));
+    }
+
+    #[test]
+    fn auto_falls_back_to_full_body_cleanup_not_density() {
+        let post = "Replying to the thread above: the tide tables in the local paper were off by an hour all week.";
+        let html = format!(
+            r#"<html><body>
+                <h1>Tides</h1>
+                <p>Short intro to the thread.</p>
+                <table><tr><td>{post}</td></tr><tr><td>{post}</td></tr><tr><td>{post}</td></tr></table>
+                <div><a href="/a">Older thread</a></div>
+                <ul><li>{post}</li><li>{post}</li></ul>
+            </body></html>"#
+        );
+        let extract_as = |strategy| {
+            let options = ReadabilityOptions { char_threshold: 300, strategy, ..Default::default() };
+            extract_with_diagnostics(&html, None, &options).unwrap()
+        };
+
+        // The density result is accepted here and longer than every scored
+        // attempt, but Auto only falls back to whole-body cleanup.
+        let density = extract_as(ExtractionStrategy::Density);
+        assert!(density.diagnostics.attempts[0].accepted);
+        let auto = extract_as(ExtractionStrategy::Auto);
+        let full_body = extract_as(ExtractionStrategy::FullBodyCleanup);
+        assert_eq!(auto.article.unwrap().content, full_body.article.unwrap().content);
+        assert_ne!(
+            auto.diagnostics.attempts.last().unwrap().text_len,
+            density.diagnostics.attempts[0].text_len
+        );
+    }
+
+    #[test]
+    fn reports_text_removed_by_each_rule() {
+        let report = extract_with_diagnostics(
+            r#"
+            <html><body>
+                <div hidden>Hidden promotional copy that should never be counted as article text.</div>
+                <article>
+                    <p>This article body has enough prose, punctuation, and detail to be selected by normal scoring.</p>
+                    <nav><a href="/a">Previous</a> <a href="/b">Next</a></nav>
+                    <p>A second paragraph keeps the article comfortably above the empty threshold.</p>
+                </article>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap();
+
+        let attempt = &report.diagnostics.attempts[0];
+        let hidden = attempt.preprocess.iter().find(|rule| rule.rule == "hidden").unwrap();
+        assert!(hidden.removed_text_len >= 60, "{hidden:?}");
+        assert_eq!(hidden.removed_elements, 1);
+
+        let cleanup = attempt.cleanup.as_ref().unwrap();
+        let chrome = cleanup
+            .rules
+            .iter()
+            .find(|rule| rule.rule == "page-chrome-tags")
+            .unwrap();
+        assert_eq!(chrome.removed_text_len, "Previous Next".len());
+        assert_eq!(chrome.removed_elements, 3);
+    }
+
+    #[test]
+    fn cleanup_dry_run_marks_removals_without_removing() {
+        let report = extract_with_diagnostics(
+            r#"
+            <html><body>
+                <article>
+                    <p>This article body has enough prose, punctuation, and detail to be selected by normal scoring.</p>
+                    <nav class="pager"><a href="/a">Previous</a> <a href="/b">Next</a></nav>
+                    <p>A second paragraph keeps the article comfortably above the empty threshold.</p>
+                </article>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, cleanup_dry_run: true, ..Default::default() },
         )
-        .unwrap()
         .unwrap();
 
-        assert!(!article.content.contains("<img"), "{}", article.content);
+        let article = report.article.unwrap();
+        assert!(article.text_content.contains("Previous Next"));
+        assert!(
+            article
+                .content
+                .contains(r#"<nav data-lectito-removed="page-chrome-tags">"#)
+        );
+        assert!(!article.content.contains("data-lectito-node"));
+
+        let attempt = &report.diagnostics.attempts[report.diagnostics.selected_attempt.unwrap()];
+        let removals = &attempt.cleanup.as_ref().unwrap().dry_run;
+        assert_eq!(removals.len(), 1, "{removals:?}");
+        assert_eq!(removals[0].rule, "page-chrome-tags");
+        assert_eq!(removals[0].selector, "nav.pager");
+        assert_eq!(removals[0].text_len, "Previous Next".len());
     }
 
     #[test]
@@ -1724,6 +2914,363 @@ mod tests {
         assert_eq!(article.favicon.as_deref(), Some("https://www.example.com/icon.png"));
     }
 
+    #[test]
+    fn falls_back_to_canonical_url_for_relative_links() {
+        let article = extract(
+            r#"
+            <html><head>
+                <title>Canonical Story</title>
+                <LINK REL="Canonical" HREF="https://example.com/posts/canonical-story">
+                <link rel="amphtml" href="/posts/canonical-story/amp">
+            </head><body>
+                <article><p>This article links to <a href="related">a related post</a> with enough text to extract.</p></article>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            article.canonical_url.as_deref(),
+            Some("https://example.com/posts/canonical-story")
+        );
+        assert_eq!(
+            article.amp_url.as_deref(),
+            Some("https://example.com/posts/canonical-story/amp")
+        );
+        assert!(
+            article.content.contains(r#"href="https://example.com/posts/related""#),
+            "{}",
+            article.content
+        );
+        assert_eq!(article.domain.as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn writes_keywords_and_section_to_frontmatter() {
+        let article = extract(
+            r#"
+            <html><head>
+                <meta name="keywords" content="rust, parsing">
+                <meta property="article:section" content="Engineering">
+            </head><body>
+                <article><p>This article has enough text and punctuation to be extracted as the main content.</p></article>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        let markdown = markdown::markdown_with_toml_frontmatter(&article, None).unwrap();
+        assert!(markdown.contains("section = \"Engineering\"\n"), "{markdown}");
+        assert!(markdown.contains("tags = [\"rust\", \"parsing\"]\n"), "{markdown}");
+    }
+
+    #[test]
+    fn flags_truncated_teasers_as_paywalled() {
+        let html = r#"
+            <html><body>
+                <article><p>The council met late into the night to debate the new transit plan, and members said…</p></article>
+            </body></html>
+        "#;
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let report = extract_with_diagnostics(html, None, &options).unwrap();
+
+        assert_eq!(report.article.unwrap().is_paywalled, Some(true));
+        assert_eq!(report.diagnostics.warnings.len(), 1);
+
+        let open = html.replace("said…", "said yes.");
+        let report = extract_with_diagnostics(&open, None, &options).unwrap();
+        assert_eq!(report.article.unwrap().is_paywalled, None);
+        assert!(report.diagnostics.warnings.is_empty());
+    }
+
+    #[test]
+    fn reports_stage_timings_in_milliseconds() {
+        let html = "<article><p>The tide comes in twice a day, pulled by the moon.</p></article>";
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let report = extract_with_diagnostics(html, None, &options).unwrap();
+
+        let timings = report.timings;
+        assert!(timings.parse > std::time::Duration::ZERO);
+        assert!(timings.extract > std::time::Duration::ZERO);
+        assert_eq!(timings.total(), timings.parse + timings.extract + timings.postprocess);
+        let json = serde_json::to_value(report).unwrap();
+        assert_eq!(
+            json["timings"]["parse_ms"].as_f64().unwrap(),
+            timings.parse.as_secs_f64() * 1000.0
+        );
+        assert!(json["timings"]["postprocess_ms"].is_f64());
+    }
+
+    #[test]
+    fn enforces_input_size_and_candidate_limits() {
+        let paragraph = "<p>The tide comes in twice a day, pulled by the moon, and goes out again by evening.</p>";
+        let html = format!(
+            "<section><div>{}</div></section>",
+            format!("<div>{paragraph}</div>").repeat(6)
+        );
+
+        let options = ReadabilityOptions { max_input_bytes: Some(100), ..Default::default() };
+        match extract(&html, None, &options) {
+            Err(Error::InputTooLarge { actual, limit }) => assert_eq!((actual, limit), (html.len(), 100)),
+            other => panic!("expected InputTooLarge, got {other:?}"),
+        }
+
+        let options = ReadabilityOptions { max_candidates: Some(3), char_threshold: 0, ..Default::default() };
+        assert!(matches!(
+            extract(&html, None, &options),
+            Err(Error::MaxCandidatesExceeded { limit: 3 })
+        ));
+
+        let options = ReadabilityOptions {
+            max_input_bytes: Some(html.len()),
+            max_candidates: Some(100),
+            char_threshold: 0,
+            ..Default::default()
+        };
+        assert!(extract(&html, None, &options).unwrap().is_some());
+    }
+
+    #[test]
+    fn stops_when_max_duration_runs_out() {
+        let html = format!(
+            "<div id=\"story\">{}</div>",
+            "<div><p>Nested, padded, and slow.</p>".repeat(50)
+        );
+        let options = ReadabilityOptions { max_duration: Some(Duration::from_nanos(1)), ..Default::default() };
+
+        match extract_with_diagnostics(&html, None, &options) {
+            Err(Error::BudgetExceeded { stage, limit, elapsed, .. }) => {
+                assert_eq!(stage, "parse");
+                assert_eq!(limit, Duration::from_nanos(1));
+                assert!(elapsed > limit);
+            }
+            other => panic!("expected BudgetExceeded, got {other:?}"),
+        }
+
+        let options = ReadabilityOptions { max_duration: Some(Duration::from_secs(60)), ..Default::default() };
+        assert!(extract_with_diagnostics(&html, None, &options).is_ok());
+        let options = ReadabilityOptions { max_duration: Some(Duration::ZERO), ..Default::default() };
+        assert!(matches!(
+            extract_with_diagnostics(&html, None, &options),
+            Err(Error::InvalidOption { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_extraction_stats_on_the_article() {
+        let paragraph = "<p>The tide comes in twice a day, pulled by the moon, and goes out again by evening.</p>";
+        let html = format!(
+            "<html><body><script>var tracking = true;</script><div class=\"sidebar\"><a href=\"/a\">Share</a></div>\
+             <div id=\"story\">{}<form><button>Subscribe</button></form></div></body></html>",
+            paragraph.repeat(8)
+        );
+        let options = ReadabilityOptions { char_threshold: 200, ..Default::default() };
+        let report = extract_with_diagnostics(&html, None, &options).unwrap();
+        let article = report.article.unwrap();
+
+        let stats = article.stats;
+        assert_eq!(stats.input_bytes, html.len());
+        assert!(stats.preprocessed_bytes < stats.input_bytes);
+        assert!(stats.candidates_scanned >= 8);
+        assert!(stats.candidates_scored > 0);
+        assert!(stats.removed_elements > 0);
+        assert_eq!(stats.timings, report.timings);
+
+        let json = serde_json::to_value(&article).unwrap();
+        assert_eq!(json["stats"]["input_bytes"], html.len());
+        let parsed: Article = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.stats.candidates_scored, stats.candidates_scored);
+    }
+
+    #[test]
+    fn extracts_legacy_layers_and_warns_on_framesets() {
+        let paragraph = "An old homepage paragraph, written long ago, with enough words and commas to score. ";
+        let html = format!(
+            r#"<html><body><center><font size="2">My Homepage</font></center>
+            <layer id="story"><font face="Times">{}</font></layer></body></html>"#,
+            paragraph.repeat(4)
+        );
+        let article = extract(
+            &html,
+            None,
+            &ReadabilityOptions { char_threshold: 200, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(!article.content.contains("<layer"), "{}", article.content);
+        assert!(article.text_content.contains("An old homepage paragraph"));
+
+        let frameset = r#"<html><frameset cols="150,*"><frame name="nav" src="nav.html"><frame src="body.html"></frameset></html>"#;
+        let report =
+            extract_with_diagnostics(frameset, Some("https://example.com/"), &ReadabilityOptions::default()).unwrap();
+        assert!(report.article.is_none());
+        assert_eq!(
+            report.diagnostics.warnings,
+            ["page is a frameset; extract the main frame at https://example.com/body.html"]
+        );
+    }
+
+    #[test]
+    fn extracts_xhtml_with_self_closing_tags_and_cdata() {
+        let paragraph = "An XHTML paragraph, served as XML, with enough words and commas to score. ";
+        let html = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">
+            <html data-build="a>b" xmlns="http://www.w3.org/1999/xhtml"><head><title>XHTML</title>
+            <script data-note="1 > 0" type="text/javascript">//<![CDATA[
+            var ready = 1 < 2;
+            //]]></script></head>
+            <body><div class="nav"><a name="top"/><div class="clear" title="a > b"/></div>
+            <article><p>{}</p><p><![CDATA[Raw <text> kept verbatim.]]></p><br/></article></body></html>"#,
+            paragraph.repeat(4)
+        );
+        let article = extract(
+            &html,
+            None,
+            &ReadabilityOptions { char_threshold: 200, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(article.text_content.contains("An XHTML paragraph"));
+        assert!(
+            article.text_content.contains("Raw <text> kept verbatim."),
+            "{}",
+            article.text_content
+        );
+        assert!(!article.content.contains("CDATA"));
+        assert!(!article.text_content.contains("var ready"));
+
+        let normalized = recovery::normalize_xhtml(&html);
+        assert!(normalized.starts_with("<!DOCTYPE html PUBLIC"), "{normalized}");
+        assert!(normalized.contains(r#"<div class="clear" title="a &gt; b"></div></div>"#));
+        assert!(normalized.contains("<p>Raw &lt;text&gt; kept verbatim.</p>"));
+        assert!(normalized.contains("\n            var ready = 1 < 2;\n            "));
+    }
+
+    #[test]
+    fn ignores_output_and_stray_bytes_before_the_doctype() {
+        let paragraph = "A page paragraph, printed after a warning, with enough words and commas to score. ";
+        let options = ReadabilityOptions { char_threshold: 200, ..Default::default() };
+        let warning = "<br />\n<b>Warning</b>:  session_start(): Cannot send session cookie in <b>/var/www/index.php</b> on line <b>3</b><br />\n";
+        let page = format!(
+            "<!DOCTYPE html><html lang=\"en\"><head><title>Real \u{8}Title</title></head><body><p>{}</p></body></html>",
+            paragraph.repeat(4)
+        );
+
+        for html in [format!("{warning}{page}"), format!("\u{FEFF}\u{FEFF}  \u{1}\n{page}")] {
+            let article = extract(&html, None, &options).unwrap().unwrap();
+            assert_eq!(article.title.as_deref(), Some("Real Title"));
+            assert_eq!(article.lang.as_deref(), Some("en"));
+            assert!(!article.text_content.contains("Warning"), "{}", article.text_content);
+            assert!(!article.text_content.contains('\u{FEFF}'));
+        }
+
+        let fragment = format!(
+            "<p>{}</p><script>document.write('<html>')</script>",
+            paragraph.repeat(4)
+        );
+        let article = extract(&fragment, None, &options).unwrap().unwrap();
+        assert!(article.text_content.contains("A page paragraph"));
+    }
+
+    #[test]
+    fn decodes_inline_base64_pages_before_scoring() {
+        let paragraph = "A saved page paragraph, stored as base64, with enough words and commas to score. ";
+        let page = format!(
+            "<html><head><title>Saved Page</title></head><body><article><p>{}</p></article></body></html>",
+            paragraph.repeat(4)
+        );
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&page);
+        let wrapped: String = encoded
+            .as_bytes()
+            .chunks(76)
+            .map(|line| format!("{}\n", std::str::from_utf8(line).unwrap()))
+            .collect();
+        let options = ReadabilityOptions { char_threshold: 200, ..Default::default() };
+
+        for html in [
+            format!("<html><body>\n{wrapped}</body></html>"),
+            format!(r#"<html><body><iframe src="data:text/html;base64,{encoded}"></iframe></body></html>"#),
+            format!("data:text/html;charset=utf-8;base64,{encoded}"),
+        ] {
+            let report = extract_with_diagnostics(&html, None, &options).unwrap();
+            let article = report.article.expect("decoded article");
+            assert_eq!(article.title.as_deref(), Some("Saved Page"));
+            assert!(article.text_content.contains("A saved page paragraph"));
+            assert_eq!(report.diagnostics.attempts[0].recovery.payloads_decoded, 1);
+        }
+
+        let plain = format!("<html><body><p>{}</p></body></html>", paragraph.repeat(4));
+        let report = extract_with_diagnostics(&plain, None, &options).unwrap();
+        assert_eq!(report.diagnostics.attempts[0].recovery.payloads_decoded, 0);
+    }
+
+    #[test]
+    fn removes_nested_byline_matching_metadata() {
+        let article = extract(
+            r#"
+            <html><head>
+                <title>Quiet Rivers</title>
+                <meta name="author" content="Jane Doe">
+            </head><body>
+                <article>
+                    <h1>Quiet Rivers</h1>
+                    <div class="entry-body">
+                        <p>By <a rel="author" href="/u/jane">Jane Doe</a></p>
+                        <p>The river moved slowly through the valley, carrying sediment and stories from the mountains above, and the town depended on it for nearly everything it had built.</p>
+                        <p>Fishermen, millers, and farmers all shared the water, negotiating rights and seasons in long meetings that sometimes lasted well past midnight.</p>
+                        <p>Jane Doe wrote about this for years and still thinks about it.</p>
+                    </div>
+                </article>
+            </body></html>
+            "#,
+            Some("https://example.com/rivers"),
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert!(!article.markdown.contains("By [Jane Doe]"));
+        assert!(article.text_content.contains("Jane Doe wrote about this"));
+    }
+
+    #[test]
+    fn removes_leading_dateline_matching_published_date() {
+        let article = extract(
+            r#"
+            <html><head>
+                <title>Quiet Rivers</title>
+                <meta property="article:published_time" content="2026-03-01T09:00:00Z">
+            </head><body>
+                <article>
+                    <h1>Quiet Rivers</h1>
+                    <div class="entry-body">
+                        <p><em>Published March 1st, 2026</em></p>
+                        <p>The river moved slowly through the valley, carrying sediment and stories from the mountains above, and the town depended on it for nearly everything it had built.</p>
+                        <p>Fishermen, millers, and farmers all shared the water, negotiating rights and seasons in long meetings that sometimes lasted well past midnight.</p>
+                        <p>The council met again on March 1, 2026 to settle the spring rights.</p>
+                    </div>
+                </article>
+            </body></html>
+            "#,
+            Some("https://example.com/rivers"),
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(article.published_time.as_deref(), Some("2026-03-01T09:00:00Z"));
+        assert!(!article.text_content.contains("Published March"));
+        assert!(article.text_content.contains("met again on March 1, 2026"));
+    }
+
     #[test]
     fn prefers_specific_heading_over_generic_site_title_and_cleans_header() {
         let article = extract(
@@ -2080,6 +3627,52 @@ mod tests {
         assert!(!article.text_content.contains("Mortgage offer"));
     }
 
+    #[test]
+    fn footnote_mode_renders_or_strips_footnotes() {
+        let html = r##"
+            <html><head><title>Cited Story</title></head><body>
+                <article>
+                    <h1>Cited Story</h1>
+                    <p>The first claim about the subject is notable, with enough prose to be extracted as content.<sup class="reference"><a href="#cite_note-1">[1]</a></sup></p>
+                    <p>More article text follows here, describing the history and context of the subject in detail.</p>
+                    <h2>References</h2>
+                    <ol class="references">
+                        <li id="cite_note-1"><span class="reference-text">Smith, <i>A Book</i>, 2001.</span></li>
+                    </ol>
+                </article>
+            </body></html>
+            "##;
+
+        let rendered = extract(
+            html,
+            Some("https://example.com/cited"),
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(
+            rendered
+                .markdown
+                .contains("notable, with enough prose to be extracted as content.[^1]")
+        );
+        assert!(rendered.markdown.contains("[^1]:"));
+        assert!(rendered.markdown.contains("Smith, *A Book*, 2001."));
+        assert!(!rendered.markdown.contains("## References"));
+
+        let stripped = extract(
+            html,
+            Some("https://example.com/cited"),
+            &ReadabilityOptions { char_threshold: 0, footnotes: crate::FootnoteMode::Strip, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(!stripped.markdown.contains("[^1]"));
+        assert!(!stripped.markdown.contains("[1]"));
+        assert!(!stripped.text_content.contains("Smith"));
+        assert!(!stripped.text_content.contains("References"));
+        assert!(stripped.text_content.contains("More article text follows"));
+    }
+
     #[test]
     fn removes_newsletter_category_and_next_article_tail_sections() {
         let article = extract(
@@ -2160,6 +3753,103 @@ mod tests {
         let shadow_article = shadow.article.unwrap();
         assert!(shadow_article.text_content.contains("shadow article"));
         assert!(shadow.diagnostics.attempts[0].recovery.shadow_roots_flattened > 0);
+
+        let nested = extract_with_diagnostics(
+            r#"
+            <html><body>
+                <x-story data-note="a > b">
+                    <template shadowrootmode="open">
+                        <x-body>
+                            <template shadowrootmode="open">
+                                <article><p>This nested shadow article has enough text and punctuation to survive flattening.</p></article>
+                            </template>
+                        </x-body>
+                        <p>This closing paragraph follows the inner template and belongs to the outer shadow root.</p>
+                    </template>
+                </x-story>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap();
+        let nested_article = nested.article.unwrap();
+        assert!(nested_article.text_content.contains("nested shadow article"));
+        assert!(nested_article.text_content.contains("closing paragraph"));
+        assert!(!nested_article.content.contains("<template"));
+        assert_eq!(nested.diagnostics.attempts[0].recovery.shadow_roots_flattened, 2);
+    }
+
+    #[test]
+    fn fills_shadow_slots_and_treats_article_elements_as_entry_points() {
+        let slotted = extract(
+            r#"
+            <html><body>
+                <x-card>
+                    <template shadowrootmode="open">
+                        <h2><slot name="headline">Untitled</slot></h2>
+                        <div class="body"><slot></slot></div>
+                        <p class="credit"><slot name="credit">Photograph by the news desk.</slot></p>
+                    </template>
+                    <span slot="headline">Spring tides</span>
+                    <p>Spring tides arrive twice a month, when the sun and moon line up, and they run higher than usual.</p>
+                    <p>Neap tides follow a week later, with the smallest range between high and low water of the month.</p>
+                </x-card>
+            </body></html>
+            "#,
+            None,
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap()
+        .unwrap();
+        let text = &slotted.text_content;
+        assert!(!text.contains("Untitled"));
+        assert!(text.find("Spring tides arrive").unwrap() < text.find("Neap tides").unwrap());
+        assert!(text.find("Neap tides").unwrap() < text.find("Photograph by the news desk").unwrap());
+        assert!(!slotted.content.contains("<slot"));
+
+        let document = kuchiki::parse_html().one(
+            "<news-article><p>One</p><p>Two</p></news-article><post-comments><p>Nice</p><p>Agreed</p></post-comments>",
+        );
+        normalize_markup(&document);
+        assert_eq!(dom::select_iter(&document, "article").count(), 1);
+        assert!(dom::exists(&document, "article > p"));
+    }
+
+    #[test]
+    fn flattens_gallery_slides_into_figures() {
+        let report = extract_with_diagnostics(
+            r#"
+            <html><body><article>
+                <h1>Fifteen photos of the coast</h1>
+                <p>The coast changes with every tide, and these photographs follow it from dawn, through the long bright noon, until the evening light.</p>
+                <div class="swiper gallery">
+                    <div class="swiper-wrapper">
+                        <div class="swiper-slide swiper-slide-duplicate"><img src="/c.jpg"></div>
+                        <div class="swiper-slide"><img src="/a.jpg" alt="Dawn"><p class="caption">Dawn at the harbour.</p></div>
+                        <div class="swiper-slide" aria-hidden="true" style="display: none">
+                            <img data-src="/b.jpg" class="lazy" alt="Noon"><div class="slide-credit">Noon on the cliffs.</div>
+                        </div>
+                        <div class="swiper-slide" hidden><img src="/c.jpg" alt="Dusk"></div>
+                    </div>
+                    <div class="gallery-thumbs"><div class="swiper-slide"><img src="/a-thumb.jpg"></div></div>
+                    <button class="swiper-button-next">Next</button>
+                </div>
+                <p>Each of these photographs was taken from the same headland across a single day in early spring.</p>
+            </article></body></html>
+            "#,
+            Some("https://example.com/coast"),
+            &ReadabilityOptions { char_threshold: 0, ..Default::default() },
+        )
+        .unwrap();
+        let article = report.article.unwrap();
+
+        assert_eq!(report.diagnostics.attempts[0].recovery.galleries_flattened, 1);
+        assert_eq!(article.content.matches("<figure>").count(), 3);
+        assert!(article.content.contains("https://example.com/b.jpg"));
+        assert!(article.text_content.contains("Dawn at the harbour."));
+        assert!(article.text_content.contains("Noon on the cliffs."));
+        assert!(!article.content.contains("a-thumb.jpg"));
     }
 
     #[test]
@@ -0,0 +1,103 @@
+use scraper::{ElementRef, Html};
+use url::Url;
+
+use super::patterns;
+use super::regexes::RegexPattern;
+
+/// URL of the frame that most likely holds the article on a `<frameset>`
+/// page.
+///
+/// Frames named like content (`main`, `content`, `body`) win over navigation
+/// frames, then the frame given the most space by its frameset. Relative
+/// sources need `base_url`; returns `None` for pages without frames.
+pub fn main_frame_url(html: &str, base_url: Option<&str>) -> Option<String> {
+    let document = Html::parse_document(html);
+    let src = main_frame_src(&document)?;
+    let url = match base_url.and_then(|base_url| Url::parse(base_url).ok()) {
+        Some(base_url) => base_url.join(&src).ok()?,
+        None => Url::parse(&src).ok()?,
+    };
+    Some(url.to_string())
+}
+
+fn main_frame_src(document: &Html) -> Option<String> {
+    document
+        .select(&patterns::selector("frameset > frame[src]"))
+        .filter(|frame| !frame.value().attr("src").unwrap_or_default().trim().is_empty())
+        .max_by_key(|frame| (name_score(frame), declared_size(frame)))
+        .and_then(|frame| frame.value().attr("src"))
+        .map(|src| src.trim().to_string())
+}
+
+fn name_score(frame: &ElementRef<'_>) -> i32 {
+    let value = frame.value();
+    let names = [value.attr("name"), value.attr("id"), value.attr("src")]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if RegexPattern::MainFrame.to_regex().is_match(&names) {
+        1
+    } else if RegexPattern::NavigationFrame.to_regex().is_match(&names) {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Space the parent frameset gives this frame through `cols` or `rows`.
+/// Relative sizes (`*`) beat percentages, which beat pixel widths.
+fn declared_size(frame: &ElementRef<'_>) -> u32 {
+    let Some(frameset) = frame.parent().and_then(ElementRef::wrap) else {
+        return 0;
+    };
+    let sizes = frameset
+        .value()
+        .attr("cols")
+        .or_else(|| frameset.value().attr("rows"))
+        .unwrap_or_default();
+    let position = frameset
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| matches!(child.value().name(), "frame" | "frameset"))
+        .position(|child| child.id() == frame.id());
+    let Some(size) = position.and_then(|position| sizes.split(',').nth(position)) else {
+        return 0;
+    };
+
+    let size = size.trim();
+    if let Some(weight) = size.strip_suffix('*') {
+        1_000_000 * weight.trim().parse::<u32>().unwrap_or(1)
+    } else if let Some(percent) = size.strip_suffix('%') {
+        10_000 * percent.trim().parse::<u32>().unwrap_or(0)
+    } else {
+        size.parse().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::main_frame_url;
+
+    #[test]
+    fn picks_the_content_frame() {
+        let html = r#"<html><frameset cols="180,*"><frame name="nav" src="nav.html"><frame src="page.html"></frameset></html>"#;
+        assert_eq!(
+            main_frame_url(html, Some("https://example.com/site/")).as_deref(),
+            Some("https://example.com/site/page.html")
+        );
+
+        let named = r#"<frameset rows="80%,20%"><frame name="menu" src="menu.html"><frame name="main" src="story.html"></frameset>"#;
+        assert_eq!(
+            main_frame_url(named, Some("https://example.com/")).as_deref(),
+            Some("https://example.com/story.html")
+        );
+    }
+
+    #[test]
+    fn needs_frames_and_a_base_for_relative_sources() {
+        let html = r#"<frameset cols="*"><frame src="page.html"></frameset>"#;
+        assert!(main_frame_url(html, None).is_none());
+        assert!(main_frame_url("<p>No frames here.</p>", Some("https://example.com/")).is_none());
+    }
+}
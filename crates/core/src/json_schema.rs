@@ -7,26 +7,37 @@ use url::Url;
 use super::config::{ExtractFlags, ReadabilityOptions};
 use super::error::Result;
 use super::extract::{ExtractAttempt, element_count, prep_document, serialize_roots};
-use super::metadata::{Metadata, clean_metadata_value, decode_html_entities, normalize_byline};
+use super::metadata::{Metadata, clean_metadata_value, decode_html_entities, normalize_byline, split_keywords};
 use super::regexes::RegexPattern;
 use super::{dom, patterns, shared};
 
 pub fn extract_json_ld(html: &str) -> Metadata {
-    let document = Html::parse_document(html);
-    let script_selector = patterns::selector(r#"script[type="application/ld+json"]"#);
-
-    for script in document.select(&script_selector) {
-        let content = script.text().collect::<String>();
-        let content = content.trim().trim_start_matches("<![CDATA[").trim_end_matches("]]>");
-        let Ok(value) = serde_json::from_str::<Value>(content) else {
-            continue;
-        };
-        if let Some(article) = find_json_ld_article(&value) {
-            return metadata_from_json_ld(article);
-        }
+    let blocks = json_ld_blocks(html);
+    let mut articles = Vec::new();
+    for block in &blocks {
+        collect_json_ld_articles(block, &mut articles);
     }
+    // Earliest node wins among equally ranked candidates.
+    articles
+        .into_iter()
+        .enumerate()
+        .max_by_key(|(index, article)| (json_ld_article_rank(article), std::cmp::Reverse(*index)))
+        .map(|(_, article)| metadata_from_json_ld(article))
+        .unwrap_or_default()
+}
 
-    Metadata::default()
+/// Every JSON-LD block in the document that parses as JSON.
+pub fn json_ld_blocks(html: &str) -> Vec<Value> {
+    let document = Html::parse_document(html);
+    let script_selector = patterns::selector(r#"script[type="application/ld+json"]"#);
+    document
+        .select(&script_selector)
+        .filter_map(|script| {
+            let content = script.text().collect::<String>();
+            let content = content.trim().trim_start_matches("<![CDATA[").trim_end_matches("]]>");
+            serde_json::from_str::<Value>(content).ok()
+        })
+        .collect()
 }
 
 pub fn apply_schema_fallback(
@@ -73,7 +84,7 @@ pub fn apply_schema_fallback(
 
     let escaped = shared::escape_html(schema_text);
     let document = kuchiki::parse_html().one(format!("<html><body><article><p>{escaped}</p></article></body></html>"));
-    let Some(root) = dom::select_nodes(&document, "article").into_iter().next() else {
+    let Some(root) = dom::select_first(&document, "article") else {
         return Ok(attempt);
     };
 
@@ -108,36 +119,64 @@ fn contains_rich_content(content: &str) -> bool {
         .any(|tag| content.to_ascii_lowercase().contains(tag))
 }
 
-fn find_json_ld_article(value: &Value) -> Option<&Value> {
+/// Collect article-like nodes from a JSON-LD block, flattening `@graph`
+/// arrays and `mainEntity` wrappers such as `WebPage`.
+fn collect_json_ld_articles<'a>(value: &'a Value, articles: &mut Vec<&'a Value>) {
     match value {
-        Value::Array(items) => items.iter().find_map(find_json_ld_article),
+        Value::Array(items) => {
+            for item in items {
+                collect_json_ld_articles(item, articles);
+            }
+        }
         Value::Object(map) => {
-            if let Some(graph) = map.get("@graph").and_then(Value::as_array)
-                && let Some(article) = graph.iter().find_map(find_json_ld_article)
-            {
-                return Some(article);
+            if let Some(graph) = map.get("@graph") {
+                collect_json_ld_articles(graph, articles);
+            }
+            if map.get("@type").is_some_and(json_ld_type_is_article) {
+                articles.push(value);
+            } else if let Some(entity) = map.get("mainEntity") {
+                collect_json_ld_articles(entity, articles);
             }
-
-            if map.get("@type").is_some_and(json_ld_type_is_article) { Some(value) } else { None }
         }
-        _ => None,
+        _ => {}
     }
 }
 
-fn json_ld_type_is_article(value: &Value) -> bool {
+/// Prefer true article types over other matched types such as `Report`, then
+/// nodes that carry a headline or body.
+fn json_ld_article_rank(value: &Value) -> (u8, bool) {
+    let is_article = json_ld_types(value.get("@type"))
+        .iter()
+        .any(|kind| kind.ends_with("Article") || *kind == "BlogPosting");
+    let has_content = ["headline", "articleBody", "text"]
+        .iter()
+        .any(|field| string_field(value, field).is_some());
+    (u8::from(is_article), has_content)
+}
+
+pub fn json_ld_types(value: Option<&Value>) -> Vec<&str> {
     match value {
-        Value::String(kind) => RegexPattern::JsonLdArticleType
-            .to_regex()
-            .is_match(kind.trim_start_matches("https://schema.org/")),
-        Value::Array(kinds) => kinds.iter().any(json_ld_type_is_article),
-        _ => false,
+        Some(Value::String(kind)) => vec![
+            kind.trim_start_matches("https://schema.org/")
+                .trim_start_matches("http://schema.org/"),
+        ],
+        Some(Value::Array(kinds)) => kinds.iter().flat_map(|kind| json_ld_types(Some(kind))).collect(),
+        _ => Vec::new(),
     }
 }
 
+fn json_ld_type_is_article(value: &Value) -> bool {
+    json_ld_types(Some(value))
+        .iter()
+        .any(|kind| RegexPattern::JsonLdArticleType.to_regex().is_match(kind))
+}
+
 fn metadata_from_json_ld(value: &Value) -> Metadata {
     Metadata {
         title: string_field(value, "name").or_else(|| string_field(value, "headline")),
+        raw_title: None,
         byline: byline_from_json_ld(value.get("author")),
+        author_bio: author_bio_from_json_ld(value.get("author")),
         excerpt: string_field(value, "description"),
         site_name: value
             .get("publisher")
@@ -148,12 +187,22 @@ fn metadata_from_json_ld(value: &Value) -> Metadata {
                     .and_then(|is_part_of| string_field(is_part_of, "name"))
             }),
         published_time: string_field(value, "datePublished"),
+        modified_time: string_field(value, "dateModified"),
+        published: None,
+        modified: None,
         image: image_from_json_ld(value.get("image")),
         domain: None,
         favicon: None,
+        canonical_url: None,
+        amp_url: None,
+        keywords: keywords_from_json_ld(value.get("keywords")),
+        section: first_string(value.get("articleSection")),
+        is_paywalled: paywalled_from_json_ld(value),
+        is_sponsored: None,
         schema_text: string_field(value, "articleBody").or_else(|| string_field(value, "text")),
         lang: None,
         dir: None,
+        related_links: Vec::new(),
     }
 }
 
@@ -173,6 +222,14 @@ fn byline_from_json_ld(value: Option<&Value>) -> Option<String> {
     normalize_byline(&raw)
 }
 
+/// The `description` of the first author given as a `Person` object.
+fn author_bio_from_json_ld(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Array(authors) => authors.iter().find_map(|author| string_field(author, "description")),
+        author => string_field(author, "description"),
+    }
+}
+
 fn image_from_json_ld(value: Option<&Value>) -> Option<String> {
     match value? {
         Value::String(image) => clean_metadata_value(image),
@@ -182,7 +239,50 @@ fn image_from_json_ld(value: Option<&Value>) -> Option<String> {
     }
 }
 
-fn string_field(value: &Value, field: &str) -> Option<String> {
+fn keywords_from_json_ld(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(keywords)) => split_keywords(keywords),
+        Some(Value::Array(keywords)) => {
+            let joined: Vec<_> = keywords.iter().filter_map(Value::as_str).collect();
+            split_keywords(&joined.join(","))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `isAccessibleForFree` on the article or any of its `hasPart` sections,
+/// inverted. A gated section marks the whole article as paywalled.
+fn paywalled_from_json_ld(value: &Value) -> Option<bool> {
+    let parts = match value.get("hasPart") {
+        Some(Value::Array(parts)) => parts.iter().collect(),
+        Some(part) => vec![part],
+        None => Vec::new(),
+    };
+    if parts.iter().any(|part| accessible_for_free(part) == Some(false)) {
+        return Some(true);
+    }
+    accessible_for_free(value).map(|free| !free)
+}
+
+fn accessible_for_free(value: &Value) -> Option<bool> {
+    match value.get("isAccessibleForFree")? {
+        Value::Bool(free) => Some(*free),
+        Value::String(free) if free.trim().eq_ignore_ascii_case("true") => Some(true),
+        Value::String(free) if free.trim().eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// A string, or the first string in an array.
+fn first_string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(value) => clean_metadata_value(value),
+        Value::Array(values) => values.iter().find_map(|value| first_string(Some(value))),
+        _ => None,
+    }
+}
+
+pub fn string_field(value: &Value, field: &str) -> Option<String> {
     value
         .get(field)?
         .as_str()
@@ -213,9 +313,30 @@ fn normalized_match_text(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::extract_json_ld;
     use crate::config::ReadabilityOptions;
     use crate::extract::extract;
 
+    #[test]
+    fn picks_article_across_blocks_graphs_and_main_entity() {
+        let html = r#"
+            <html><head>
+                <script type="application/ld+json">{"@type": "WebSite", "name": "Example"}</script>
+                <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+                    {"@type": "BreadcrumbList", "itemListElement": []},
+                    {"@type": "Report", "name": "Annual filing"},
+                    {"@type": "WebPage", "mainEntity": {"@type": ["NewsArticle"], "headline": "Graph Story", "dateModified": "2026-05-02"}}
+                ]}</script>
+                <script type="application/ld+json">{"@type": "BlogPosting", "headline": "Later Post"}</script>
+            </head><body></body></html>
+        "#;
+
+        let metadata = extract_json_ld(html);
+
+        assert_eq!(metadata.title.as_deref(), Some("Graph Story"));
+        assert_eq!(metadata.modified_time.as_deref(), Some("2026-05-02"));
+    }
+
     #[test]
     fn schema_text_fallback_uses_smallest_matching_subtree() {
         let schema_text = "This is the target post content with enough words to trigger schema text fallback. It includes several sentences so it is clearly better than the short article summary. More detail appears here to make the target post the right extraction root.";
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use super::config::Article;
+use super::patterns::STOP_WORDS;
+
+/// Longer runs between stop words are usually clauses, not phrases.
+const MAX_PHRASE_WORDS: usize = 3;
+
+impl Article {
+    /// The `n` highest-scoring keyphrases in `text_content`, best first.
+    ///
+    /// Unlike `keywords`, which comes from page metadata, these are computed
+    /// from the article text.
+    pub fn keyphrases(&self, n: usize) -> Vec<String> {
+        keyphrases(&self.text_content, n)
+    }
+}
+
+/// Extract keyphrases from plain text with RAKE.
+///
+/// Candidate phrases are runs of words between stop words and punctuation.
+/// Each word scores its co-occurrence degree over its frequency, and a phrase
+/// scores the sum of its words. Phrases are lowercased and deduplicated; ties
+/// keep the earlier phrase.
+pub fn keyphrases(text: &str, n: usize) -> Vec<String> {
+    let phrases = candidate_phrases(text);
+
+    let mut frequency = HashMap::<&str, f64>::new();
+    let mut degree = HashMap::<&str, f64>::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word.as_str()).or_default() += 1.0;
+            *degree.entry(word.as_str()).or_default() += phrase.len() as f64;
+        }
+    }
+
+    let mut scored: Vec<(String, f64)> = Vec::new();
+    for phrase in &phrases {
+        let key = phrase.join(" ");
+        if scored.iter().any(|(existing, _)| *existing == key) {
+            continue;
+        }
+        let score = phrase
+            .iter()
+            .map(|word| degree[word.as_str()] / frequency[word.as_str()])
+            .sum();
+        scored.push((key, score));
+    }
+    // Stable sort keeps earlier phrases ahead on ties.
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(n).map(|(phrase, _)| phrase).collect()
+}
+
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    for fragment in text.split(|c: char| !(c.is_alphanumeric() || c.is_whitespace() || matches!(c, '\'' | '’' | '-')))
+    {
+        let mut phrase = Vec::new();
+        for word in fragment.split_whitespace() {
+            let word = word.trim_matches(['\'', '’', '-']).to_lowercase();
+            if word.is_empty() || STOP_WORDS.contains(&word.as_str()) || !word.chars().any(char::is_alphabetic) {
+                push_phrase(&mut phrases, std::mem::take(&mut phrase));
+            } else {
+                phrase.push(word);
+            }
+        }
+        push_phrase(&mut phrases, phrase);
+    }
+    phrases
+}
+
+fn push_phrase(phrases: &mut Vec<Vec<String>>, phrase: Vec<String>) {
+    if !phrase.is_empty() && phrase.len() <= MAX_PHRASE_WORDS {
+        phrases.push(phrase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::keyphrases;
+
+    #[test]
+    fn ranks_multi_word_phrases_between_stop_words() {
+        let text = "Compatibility of systems of linear constraints over the set of natural numbers. \
+                    Criteria of compatibility of a system of linear Diophantine equations are considered. \
+                    Upper bounds for components of a minimal set of solutions are given.";
+
+        let phrases = keyphrases(text, 3);
+
+        assert_eq!(
+            phrases,
+            ["linear diophantine equations", "linear constraints", "natural numbers"]
+        );
+        assert!(keyphrases(text, 0).is_empty());
+        assert!(keyphrases("the and of", 5).is_empty());
+    }
+}
@@ -20,33 +20,106 @@
 //! # Ok::<(), lectito::Error>(())
 //! # }
 //! ```
+//!
+//! # API surface
+//!
+//! Everything public is re-exported from the crate root; the modules are
+//! private.
+//!
+//! - Extraction: [`extract`], [`extract_with_diagnostics`],
+//!   [`clean_article_html`], [`is_probably_readable`] and its streaming
+//!   variant [`is_probably_readable_fast`], and [`find_next_page`].
+//!   [`Document`] unpacks pages saved as MHTML or Safari web archives.
+//!   [`Extractor`] hooks custom [`ExtractionStage`]s into extraction.
+//! - Options: [`ReadabilityOptions`], built with struct update syntax over
+//!   `Default` or a [`Preset`], or with [`ReadabilityOptions::builder`], plus
+//!   [`ReadableOptions`] and [`MarkdownOptions`]. [`LectitoOptions`] gathers
+//!   fetch, extraction, and output settings for configuration files.
+//! - Output: [`Article`] and its methods, including [`Article::save`], and the
+//!   [`ExtractionReport`] diagnostics types.
+//! - Helpers: Markdown conversion, text utilities, and site rule tools.
+//!   [`capabilities`] reports the optional features this build includes.
+//!
+//! Diagnostics types, [`Error`], and the option enums are `#[non_exhaustive]`,
+//! so new fields and variants can arrive in minor releases. Read their fields
+//! freely, but add a wildcard arm when matching on the enums.
 
+mod archive;
+mod builder;
+mod capabilities;
 mod cleanup;
 mod config;
+mod dates;
+mod density;
 mod diagnostics;
 mod dom;
+mod epub;
 mod error;
 mod extract;
+mod frames;
 mod json_schema;
+mod keyphrases;
+mod links;
+mod liveblog;
 mod markdown;
 mod metadata;
+mod microformats;
 mod normalize;
+mod options;
+mod pagination;
 mod patterns;
+mod paywall;
+mod pipeline;
+mod presets;
 mod readable;
 mod recovery;
 mod regexes;
 mod rules;
+mod save;
+#[cfg(feature = "schema")]
+mod schema;
 mod scoring;
 mod serialize;
 mod shared;
+mod split;
+mod summarize;
+#[cfg(feature = "textstats")]
+mod textstats;
+mod tracking;
+mod transcript;
+mod typography;
 
-pub use config::{Article, MarkdownOptions, MediaRetention, ReadabilityOptions, ReadableOptions};
+pub use archive::{Document, Resource};
+pub use builder::ReadabilityOptionsBuilder;
+pub use capabilities::{Capabilities, capabilities};
+pub use config::{
+    Article, ArticleLink, ExtractionStrategy, FootnoteMode, LinkCleanup, LinkKind, LinkStyle, MarkdownOptions,
+    MediaRetention, PatternGroup, Preset, ReadabilityOptions, ReadableOptions, Typography,
+};
 pub use diagnostics::{
     AttemptDiagnostic, CandidateDiagnostic, CandidateSelection, CleanupDiagnostic, ContentSelectorDiagnostic,
-    ExtractionDiagnostics, ExtractionOutcome, ExtractionReport, FlagDiagnostic, NodeDiagnostic, RecoveryDiagnostic,
+    DryRunRemovalDiagnostic, ExtractionDiagnostics, ExtractionOutcome, ExtractionReport, ExtractionStats,
+    FlagDiagnostic, LintSeverity, NodeDiagnostic, RecoveryDiagnostic, RuleRemovalDiagnostic, ShadowedSiteRule,
+    SiteProfileCheck, SiteProfileLint, SiteRuleDiagnostic, SiteRuleMatch, SiteRuleSource, Timings,
 };
 pub use error::{Error, Result};
 pub use extract::{clean_article_html, extract, extract_with_diagnostics};
-pub use markdown::{html_to_markdown, markdown_to_html, markdown_with_toml_frontmatter};
-pub use readable::is_probably_readable;
+pub use frames::main_frame_url;
+pub use keyphrases::keyphrases;
+pub use markdown::{
+    adjust_heading_levels, annotate_reading_time, apply_link_style, html_to_markdown, markdown_to_html,
+    markdown_with_toml_frontmatter,
+};
+pub use options::{FetchOptions, LectitoOptions, OutputOptions, Route};
+pub use pagination::find_next_page;
+pub use pipeline::{ExtractionStage, Extractor, Page, RemoveElements};
+pub use readable::{ReadableSignals, is_probably_readable, is_probably_readable_fast, readable_signals};
+pub use rules::{
+    FtrConversion, SiteProfileBuilder, bundled_site_rules, bundled_site_rules_version, check_site_profile,
+    convert_ftr_site_config, lint_site_profile, site_profile_from_ftr, site_profile_hosts, site_profile_test_urls,
+    site_rules_index, site_rules_version, validate_site_profile,
+};
 pub use shared::escape_html;
+pub use summarize::summarize;
+#[cfg(feature = "textstats")]
+pub use textstats::{ReadabilityMetrics, readability_metrics};
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use scraper::{ElementRef, Html};
+use url::Url;
+
+use super::config::{ArticleLink, LinkKind};
+use super::patterns;
+
+/// Related modules with more text than this are page sections, not link lists.
+const MAX_RELATED_MODULE_CHARS: usize = 3000;
+
+/// Links in the related articles modules of `document`.
+pub fn related_links(document: &Html, base_url: Option<&Url>) -> Vec<ArticleLink> {
+    let selector = patterns::selector(patterns::RELATED_LINKS_SELECTOR);
+    let link_selector = patterns::selector("a[href]");
+    document
+        .select(&selector)
+        .filter(|module| !matches!(module.value().name(), "html" | "body"))
+        .filter(|module| module.text().map(|text| text.chars().count()).sum::<usize>() <= MAX_RELATED_MODULE_CHARS)
+        .flat_map(|module| module.select(&link_selector))
+        .filter_map(|link| article_link(link, base_url, LinkKind::Related))
+        .collect()
+}
+
+/// The links in the article `content`, then the `related` links, keeping the
+/// first link to each URL.
+pub fn article_links(content: &str, related: Vec<ArticleLink>) -> Vec<ArticleLink> {
+    let fragment = Html::parse_fragment(content);
+    let link_selector = patterns::selector("a[href]");
+    let content_links: Vec<ArticleLink> = fragment
+        .select(&link_selector)
+        .filter_map(|link| article_link(link, None, LinkKind::Content))
+        .collect();
+    let mut seen = HashSet::new();
+    content_links
+        .into_iter()
+        .chain(related)
+        .filter(|link| seen.insert(link.url.clone()))
+        .collect()
+}
+
+/// Content links were made absolute during cleanup, so only related links
+/// need `base_url`. Links that do not resolve to `http` or `https` are
+/// skipped.
+fn article_link(element: ElementRef<'_>, base_url: Option<&Url>, kind: LinkKind) -> Option<ArticleLink> {
+    let href = element.value().attr("href")?.trim();
+    let url = match base_url {
+        Some(base_url) => base_url.join(href).ok()?,
+        None => Url::parse(href).ok()?,
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let text = patterns::normalize_spaces(element.text().collect::<String>().trim());
+    let text = match text.is_empty() {
+        true => element
+            .value()
+            .attr("title")
+            .map(|title| patterns::normalize_spaces(title.trim()))
+            .unwrap_or_default(),
+        false => text,
+    };
+    let rel = element
+        .value()
+        .attr("rel")
+        .map(|rel| rel.split_whitespace().map(str::to_ascii_lowercase).collect())
+        .unwrap_or_default();
+    Some(ArticleLink { text, url: url.into(), rel, kind })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadabilityOptions, extract};
+
+    #[test]
+    fn lists_content_links_before_related_modules() {
+        let paragraph = "The harbour master keeps a ledger of every tide, noting the height of the water, \
+            the hour it turned, and which boats were able to cross the bar before it fell again.";
+        let html = format!(
+            r##"<html><body>
+            <article>
+                <p>{paragraph} See the <a href="/charts/2026">tide charts</a>.</p>
+                <p>{paragraph} Data from the <a href="https://tides.example.org/" rel="nofollow External">tide office</a>.</p>
+                <p>{paragraph} <a href="#notes">Notes</a> <a href="mailto:desk@example.com">Write to us</a>.</p>
+            </article>
+            <div class="related-stories">
+                <a href="/harbour-fees">Harbour fees rise</a>
+                <a href="/charts/2026">Tide charts</a>
+                <a href="/lighthouse" title="The old lighthouse"><img src="lighthouse.jpg"></a>
+            </div>
+            </body></html>"##
+        );
+
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(&html, Some("https://news.example.com/tides"), &options)
+            .unwrap()
+            .unwrap();
+        let links: Vec<(&str, &str, LinkKind)> = article
+            .links
+            .iter()
+            .map(|link| (link.text.as_str(), link.url.as_str(), link.kind))
+            .collect();
+
+        assert_eq!(
+            links,
+            [
+                ("tide charts", "https://news.example.com/charts/2026", LinkKind::Content),
+                ("tide office", "https://tides.example.org/", LinkKind::Content),
+                ("Notes", "https://news.example.com/tides#notes", LinkKind::Content),
+                (
+                    "Harbour fees rise",
+                    "https://news.example.com/harbour-fees",
+                    LinkKind::Related
+                ),
+                (
+                    "The old lighthouse",
+                    "https://news.example.com/lighthouse",
+                    LinkKind::Related
+                ),
+            ]
+        );
+        assert_eq!(article.links[1].rel, ["nofollow", "external"]);
+    }
+}
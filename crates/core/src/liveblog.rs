@@ -0,0 +1,293 @@
+use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+use serde_json::Value;
+
+use super::config::ExtractionStrategy;
+use super::regexes::RegexPattern;
+use super::{dates, dom, json_schema, scoring, shared};
+
+/// Fewest JSON-LD `liveBlogUpdate` entries that make a page a live blog.
+const MIN_SCHEMA_UPDATES: usize = 2;
+
+/// Fewest timestamped sibling posts that make a container a live blog.
+const MIN_POST_BLOCKS: usize = 3;
+
+/// Shortest post text, not counting its timestamp.
+const MIN_POST_TEXT: usize = 40;
+
+/// Longest text a timestamp element may hold.
+const MAX_TIMESTAMP_TEXT: usize = 60;
+
+struct Update {
+    time: Option<String>,
+    headline: Option<String>,
+    body: NodeRef,
+}
+
+/// Whether a page is worth the live-blog pass. Auto extraction only looks
+/// for posts on pages that mention a live blog somewhere in their markup.
+pub fn might_be_live_blog(html: &str, strategy: ExtractionStrategy) -> bool {
+    strategy == ExtractionStrategy::LiveBlog || RegexPattern::LiveBlogMarker.to_regex().is_match(html)
+}
+
+/// An `<article>` holding every update of a live blog in page order, each
+/// in a `<section>` under an `<h3>` with its timestamp and headline.
+///
+/// Updates come from JSON-LD `LiveBlogPosting.liveBlogUpdate` when it has
+/// them, and otherwise from the largest run of sibling blocks that each carry
+/// a timestamp. `html` is the source with its scripts; `document` is the
+/// prepared page. With the auto strategy the post run must be marked as a
+/// live blog by its class or id.
+pub fn live_blog_root(html: &str, document: &NodeRef, strategy: ExtractionStrategy) -> Option<NodeRef> {
+    let mut updates = schema_updates(html);
+    if updates.len() < MIN_SCHEMA_UPDATES {
+        updates = post_block_updates(document, strategy == ExtractionStrategy::Auto);
+    }
+    if updates.is_empty() {
+        return None;
+    }
+
+    let page = kuchiki::parse_html().one("<html><body><article></article></body></html>");
+    let root = dom::select_first(&page, "article")?;
+    for update in updates {
+        let section = dom::new_element("section")?;
+        let label = match (update.time, update.headline) {
+            (Some(time), Some(headline)) => format!("{time} — {headline}"),
+            (Some(label), None) | (None, Some(label)) => label,
+            (None, None) => String::new(),
+        };
+        if !label.is_empty() {
+            let heading = dom::new_element("h3")?;
+            heading.append(NodeRef::new_text(label));
+            section.append(heading);
+        }
+        for child in update.body.children().collect::<Vec<_>>() {
+            section.append(child);
+        }
+        root.append(section);
+    }
+    Some(root)
+}
+
+fn schema_updates(html: &str) -> Vec<Update> {
+    if !html.contains("LiveBlogPosting") {
+        return Vec::new();
+    }
+    let blocks = json_schema::json_ld_blocks(html);
+    let mut postings = Vec::new();
+    for block in &blocks {
+        collect_live_blog_postings(block, &mut postings);
+    }
+    postings
+        .into_iter()
+        .map(|posting| match posting.get("liveBlogUpdate") {
+            Some(Value::Array(updates)) => updates.iter().filter_map(schema_update).collect(),
+            Some(update) => schema_update(update).into_iter().collect(),
+            None => Vec::new(),
+        })
+        .max_by_key(Vec::len)
+        .unwrap_or_default()
+}
+
+fn collect_live_blog_postings<'a>(value: &'a Value, postings: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_live_blog_postings(item, postings);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(graph) = map.get("@graph") {
+                collect_live_blog_postings(graph, postings);
+            }
+            if json_schema::json_ld_types(map.get("@type")).contains(&"LiveBlogPosting") {
+                postings.push(value);
+            } else if let Some(entity) = map.get("mainEntity") {
+                collect_live_blog_postings(entity, postings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn schema_update(value: &Value) -> Option<Update> {
+    let text = json_schema::string_field(value, "articleBody").or_else(|| json_schema::string_field(value, "text"))?;
+    let markup = if text.contains('<') {
+        text
+    } else {
+        text.split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| format!("<p>{}</p>", shared::escape_html(paragraph)))
+            .collect()
+    };
+    let fragment = kuchiki::parse_html().one(format!("<html><body><div>{markup}</div></body></html>"));
+    let body = dom::select_first(&fragment, "body > div")?;
+    let time = ["datePublished", "dateCreated", "dateModified"]
+        .iter()
+        .find_map(|field| json_schema::string_field(value, field))
+        .map(|time| match dates::parse_date(&time) {
+            Some(date) => date.format("%Y-%m-%d %H:%M %:z").to_string(),
+            None => time,
+        });
+    Some(Update { time, headline: json_schema::string_field(value, "headline"), body })
+}
+
+/// Updates from the container with the most timestamped child posts.
+fn post_block_updates(document: &NodeRef, require_marker: bool) -> Vec<Update> {
+    let mut best: Vec<NodeRef> = Vec::new();
+    for container in dom::select_nodes(document, "body, main, article, section, div, ol, ul") {
+        if is_unlikely(&container) {
+            continue;
+        }
+        let children: Vec<NodeRef> = container
+            .children()
+            .filter(|node| node.as_element().is_some())
+            .collect();
+        let posts: Vec<NodeRef> = children.iter().filter(|child| is_post_block(child)).cloned().collect();
+        // Ads and promos may sit between posts, but most children are posts.
+        if posts.len() < MIN_POST_BLOCKS || posts.len() * 2 < children.len() || posts.len() <= best.len() {
+            continue;
+        }
+        if require_marker && !std::iter::once(&container).chain(&posts).any(is_marked_live) {
+            continue;
+        }
+        best = posts;
+    }
+    best.iter().map(post_update).collect()
+}
+
+fn is_post_block(node: &NodeRef) -> bool {
+    let Some(timestamp) = timestamp(node) else {
+        return false;
+    };
+    let text_len = dom::inner_text(node).chars().count();
+    let timestamp_len = dom::inner_text(&timestamp).chars().count();
+    !is_unlikely(node) && text_len.saturating_sub(timestamp_len) >= MIN_POST_TEXT && scoring::link_density(node) < 0.5
+}
+
+fn is_unlikely(node: &NodeRef) -> bool {
+    RegexPattern::UnlikelyCandidates
+        .to_regex()
+        .is_match(&dom::class_id_string(node))
+}
+
+fn is_marked_live(node: &NodeRef) -> bool {
+    RegexPattern::LiveBlogMarker
+        .to_regex()
+        .is_match(&dom::class_id_string(node))
+}
+
+/// The post's `<time>`, or a short element named like a timestamp.
+fn timestamp(post: &NodeRef) -> Option<NodeRef> {
+    dom::select_first(post, "time").or_else(|| {
+        dom::select_iter(post, "[class], [id]").find(|node| {
+            node != post
+                && RegexPattern::LiveBlogTimestamp
+                    .to_regex()
+                    .is_match(&dom::class_id_string(node))
+                && (1..=MAX_TIMESTAMP_TEXT).contains(&dom::inner_text(node).chars().count())
+        })
+    })
+}
+
+fn post_update(post: &NodeRef) -> Update {
+    let body = dom::deep_clone(post);
+    let time = timestamp(&body).and_then(|timestamp| {
+        let text = dom::inner_text(&timestamp);
+        timestamp.detach();
+        match text.is_empty() {
+            true => dom::attr(&timestamp, "datetime"),
+            false => Some(text),
+        }
+    });
+    let headline = dom::select_first(&body, "h1, h2, h3, h4")
+        .map(|heading| {
+            heading.detach();
+            dom::inner_text(&heading)
+        })
+        .filter(|headline| !headline.is_empty());
+    Update { time, headline, body }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadabilityOptions, extract};
+
+    fn posts(class: &str) -> String {
+        let posts: String = [
+            ("10:42", "Polls close", "Polling stations have closed across the country and counting has begun."),
+            ("10:15", "Turnout is high", "Officials say turnout is the highest in two decades, with long queues."),
+            ("09:50", "Queues at dawn", "Voters were lining up before sunrise at schools and community halls."),
+        ]
+        .iter()
+        .map(|(time, headline, text)| {
+            format!(
+                r#"<div class="post"><time datetime="2026-10-16T{time}:00Z">{time}</time><h2>{headline}</h2><p>{text}</p></div>"#
+            )
+        })
+        .collect();
+        format!(
+            r#"<html><body><nav><a href="/">Home</a></nav><h1>Election day</h1><div class="{class}">{posts}</div></body></html>"#
+        )
+    }
+
+    fn options(strategy: ExtractionStrategy) -> ReadabilityOptions {
+        ReadabilityOptions { strategy, char_threshold: 100, ..Default::default() }
+    }
+
+    #[test]
+    fn extracts_timestamped_posts_in_page_order() {
+        let article = extract(&posts("live-blog"), None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+
+        assert!(article.markdown.contains("### 10:42 — Polls close"));
+        let positions: Vec<usize> = ["10:42 — Polls close", "10:15 — Turnout", "09:50 — Queues"]
+            .iter()
+            .map(|label| article.text_content.find(label).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(article.text_content.contains("counting has begun"));
+        assert!(!article.text_content.contains("Home"));
+    }
+
+    #[test]
+    fn auto_strategy_needs_a_live_blog_marker() {
+        let html = posts("timeline");
+
+        let auto = extract(&html, None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+        let live = extract(&html, None, &options(ExtractionStrategy::LiveBlog))
+            .unwrap()
+            .unwrap();
+
+        assert!(!auto.markdown.contains("### 10:42"));
+        assert!(live.markdown.contains("### 10:42 — Polls close"));
+    }
+
+    #[test]
+    fn reads_json_ld_live_blog_updates() {
+        let html = r#"<html><head><script type="application/ld+json">{
+            "@context": "https://schema.org",
+            "@type": "LiveBlogPosting",
+            "headline": "Storm updates",
+            "liveBlogUpdate": [
+                {"@type": "BlogPosting", "headline": "Roads closed", "datePublished": "2026-10-16T08:30:00+01:00",
+                 "articleBody": "The coast road is closed after flooding near the harbour wall this morning."},
+                {"@type": "BlogPosting", "datePublished": "2026-10-16T07:00:00+01:00",
+                 "articleBody": "Heavy rain arrived overnight.\n\nThe river is expected to peak by noon."}
+            ]
+        }</script></head><body><p>Loading updates…</p></body></html>"#;
+
+        let article = extract(html, None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+
+        assert!(article.markdown.contains("### 2026-10-16 08:30 +01:00 — Roads closed"));
+        assert!(article.markdown.contains("### 2026-10-16 07:00 +01:00"));
+        assert!(article.text_content.contains("The river is expected to peak by noon."));
+    }
+}
@@ -1,12 +1,19 @@
 pub mod code;
-mod footnotes;
+pub mod footnotes;
 mod frontmatter;
-mod math;
-mod media;
+mod headings;
+mod links;
+pub mod math;
+pub mod media;
+mod reading_time;
 mod tables;
 
 use comrak::options::{Extension, Parse};
 pub use frontmatter::markdown_with_toml_frontmatter;
+pub(crate) use frontmatter::toml_frontmatter;
+pub use headings::adjust_heading_levels;
+pub use links::apply_link_style;
+pub use reading_time::{WORDS_PER_MINUTE, annotate_reading_time};
 
 use comrak::markdown_to_html as comrak_markdown_to_html;
 use comrak::{Arena, Options};
@@ -176,10 +183,7 @@ impl RenderContext {
 /// populates [`crate::Article::markdown`].
 pub fn html_to_markdown(html: &str) -> String {
     let document = kuchiki::parse_html().one(format!("<html><body>{html}</body></html>"));
-    let body = dom::select_nodes(&document, "body")
-        .into_iter()
-        .next()
-        .unwrap_or(document);
+    let body = dom::select_first(&document, "body").unwrap_or(document);
     let footnotes = footnotes::FootnoteContext::extract(&body);
     let mut output = RenderContext::default().render_children(&body);
     output.push_str(&footnotes.render_defs());
@@ -290,7 +294,18 @@ fn is_heading_permalink(node: &NodeRef, label: &str) -> bool {
 
 fn fmt_with_comrak(markdown: &str) -> String {
     let arena = Arena::new();
-    let opts = Options {
+    let opts = format_options();
+    let root = parse_document(&arena, markdown, &opts);
+    let mut output = String::new();
+    match format_commonmark(root, &opts, &mut output) {
+        Err(_) => markdown.to_string(),
+        Ok(_) => output.trim().to_string(),
+    }
+}
+
+/// Parse and format options for the Markdown produced from article HTML.
+fn format_options() -> Options<'static> {
+    Options {
         extension: Extension {
             footnotes: true,
             math_dollars: true,
@@ -300,13 +315,6 @@ fn fmt_with_comrak(markdown: &str) -> String {
         },
         parse: Parse { leave_footnote_definitions: true, ..Default::default() },
         ..Default::default()
-    };
-
-    let root = parse_document(&arena, markdown, &opts);
-    let mut output = String::new();
-    match format_commonmark(root, &opts, &mut output) {
-        Err(_) => markdown.to_string(),
-        Ok(_) => output.trim().to_string(),
     }
 }
 
@@ -24,7 +24,7 @@ pub(super) fn render_code_container(node: &NodeRef, ctx: RenderContext) -> Optio
         return None;
     }
 
-    if let Some(pre) = dom::select_nodes(node, "pre").into_iter().next() {
+    if let Some(pre) = dom::select_first(node, "pre") {
         return Some(render_code_block(&pre, ctx));
     }
 
@@ -50,8 +50,7 @@ fn code_text(node: &NodeRef) -> String {
 }
 
 fn code_line_nodes(root: &NodeRef) -> Vec<NodeRef> {
-    dom::select_nodes(root, "*")
-        .into_iter()
+    dom::select_iter(root, "*")
         .filter(is_code_line_node)
         .filter(|node| !has_code_line_ancestor(node, root))
         .collect()
@@ -204,7 +203,7 @@ pub(super) fn is_highlighter_chrome(node: &NodeRef) -> bool {
         "cm-gutters",
         "gutter-wrapper",
     ];
-    if class.contains("code-toolbar") && dom::select_nodes(node, "pre").is_empty() {
+    if class.contains("code-toolbar") && !dom::exists(node, "pre") {
         return true;
     }
     if chrome.iter().any(|needle| {
@@ -259,7 +258,7 @@ fn normalize_code_tables(root: &NodeRef) {
 
 fn normalize_standalone_code_containers(root: &NodeRef) {
     for node in dom::select_nodes(root, "div") {
-        if dom::select_nodes(&node, "pre").is_empty() && is_standalone_code_container(&node) {
+        if !dom::exists(&node, "pre") && is_standalone_code_container(&node) {
             let code = CodeBlock { language: get_lang_id(&node), text: code_text(&node) };
             if !code.text.trim().is_empty() {
                 replace_with_pre(&node, code.language.as_deref(), &code.text);
@@ -270,7 +269,7 @@ fn normalize_standalone_code_containers(root: &NodeRef) {
 
 fn normalize_pre_blocks(root: &NodeRef) {
     for pre in dom::select_nodes(root, "pre") {
-        let code_node = dom::select_nodes(&pre, "code").into_iter().next();
+        let code_node = dom::select_first(&pre, "code");
         let language = get_lang_id(&pre)
             .or_else(|| code_node.as_ref().and_then(get_lang_id))
             .or_else(|| ancestor_lang_id(&pre));
@@ -290,7 +289,7 @@ impl CodeBlock {
     }
 
     fn from_node(node: &NodeRef) -> Self {
-        let code_node = dom::select_nodes(node, "code").into_iter().next();
+        let code_node = dom::select_first(node, "code");
         Self::new(
             get_lang_id(node)
                 .or_else(|| code_node.as_ref().and_then(get_lang_id))
@@ -303,20 +302,16 @@ impl CodeBlock {
         if !(has_class_token(node, "highlighttable")
             || has_class_token(node, "rouge-table")
             || has_class_token(node, "highlight")
-            || !dom::select_nodes(node, "td.linenos, td.rouge-gutter, td.gutter, td.code, td.rouge-code").is_empty())
+            || dom::exists(node, "td.linenos, td.rouge-gutter, td.gutter, td.code, td.rouge-code"))
         {
             return None;
         }
 
-        let code_cell = dom::select_nodes(node, "td.code, td.rouge-code")
-            .into_iter()
-            .next()
-            .or_else(|| {
-                dom::select_nodes(node, "td")
-                    .into_iter()
-                    .filter(|cell| !is_line_number_node(cell))
-                    .max_by_key(|cell| code_text(cell).trim().len())
-            })?;
+        let code_cell = dom::select_first(node, "td.code, td.rouge-code").or_else(|| {
+            dom::select_iter(node, "td")
+                .filter(|cell| !is_line_number_node(cell))
+                .max_by_key(|cell| code_text(cell).trim().len())
+        })?;
         let text = code_text(&code_cell);
         (!text.trim().is_empty())
             .then(|| CodeBlock { language: get_lang_id(node).or_else(|| get_lang_id(&code_cell)), text })
@@ -351,10 +346,10 @@ impl CodeBlock {
 
 fn replace_with_pre(node: &NodeRef, language: Option<&str>, text: &str) {
     let fragment = kuchiki::parse_html().one("<html><body><pre><code></code></pre></body></html>");
-    let Some(pre) = dom::select_nodes(&fragment, "pre").into_iter().next() else {
+    let Some(pre) = dom::select_first(&fragment, "pre") else {
         return;
     };
-    let Some(code) = dom::select_nodes(&pre, "code").into_iter().next() else {
+    let Some(code) = dom::select_first(&pre, "code") else {
         return;
     };
     if let Some(language) = language {
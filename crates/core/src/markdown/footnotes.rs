@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use kuchiki::NodeRef;
 
@@ -18,10 +18,7 @@ pub struct FootnoteContext {
 
 impl FootnoteContext {
     pub fn extract(root: &NodeRef) -> Self {
-        let definition_nodes: Vec<NodeRef> = dom::select_nodes(root, "[id]")
-            .into_iter()
-            .filter(is_definition_node)
-            .collect();
+        let definition_nodes: Vec<NodeRef> = dom::select_iter(root, "[id]").filter(is_definition_node).collect();
         if definition_nodes.is_empty() {
             return Self::default();
         }
@@ -53,6 +50,7 @@ impl FootnoteContext {
         }
 
         rewrite_references(root, &target_labels);
+        remove_empty_note_headings(root);
 
         Self { definitions }
     }
@@ -71,6 +69,76 @@ impl FootnoteContext {
     }
 }
 
+/// Remove footnote reference markers, footnote definitions, and the headings
+/// and lists left empty by their removal.
+pub fn strip_footnotes(root: &NodeRef) {
+    let definition_nodes: Vec<NodeRef> = dom::select_iter(root, "[id]").filter(is_definition_node).collect();
+    if definition_nodes.is_empty() {
+        return;
+    }
+
+    let ids: HashSet<String> = definition_nodes
+        .iter()
+        .filter_map(|node| dom::attr(node, "id"))
+        .collect();
+    for node in definition_nodes {
+        node.detach();
+    }
+
+    for anchor in dom::select_nodes(root, "a[href]") {
+        let Some(href) = dom::attr(&anchor, "href") else {
+            continue;
+        };
+        if href_fragment(&href).is_some_and(|target| ids.contains(target)) {
+            reference_wrapper(&anchor).unwrap_or(anchor).detach();
+        }
+    }
+
+    for list in dom::select_nodes(root, "ol, ul") {
+        if !dom::exists(&list, "li") {
+            list.detach();
+        }
+    }
+    remove_empty_note_headings(root);
+}
+
+/// Drop "References"-style headings whose section no longer has any text.
+fn remove_empty_note_headings(root: &NodeRef) {
+    for heading in dom::select_nodes(root, "h1, h2, h3, h4, h5, h6") {
+        let title = dom::inner_text(&heading).to_ascii_lowercase();
+        if !matches!(
+            title.trim_end_matches(':').trim(),
+            "references" | "notes" | "footnotes" | "endnotes" | "citations" | "notes and references"
+        ) {
+            continue;
+        }
+
+        let mut section_is_empty = true;
+        let mut sibling = heading.next_sibling();
+        while let Some(current) = sibling {
+            if matches!(
+                dom::node_name(&current).as_str(),
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            ) {
+                break;
+            }
+            if !dom::inner_text(&current).is_empty() {
+                section_is_empty = false;
+                break;
+            }
+            sibling = current.next_sibling();
+        }
+        if section_is_empty {
+            heading.detach();
+        }
+    }
+}
+
+fn href_fragment(href: &str) -> Option<&str> {
+    href.strip_prefix('#')
+        .or_else(|| href.split_once('#').map(|(_, fragment)| fragment))
+}
+
 fn is_definition_node(node: &NodeRef) -> bool {
     let tag = dom::node_name(node);
     if matches!(tag.as_str(), "a" | "sup") {
@@ -166,14 +234,10 @@ fn strip_leading_marker(value: &str) -> String {
 }
 
 fn rewrite_references(root: &NodeRef, target_labels: &HashMap<String, String>) {
-    let refs: Vec<_> = dom::select_nodes(root, "a[href]")
-        .into_iter()
+    let refs: Vec<_> = dom::select_iter(root, "a[href]")
         .filter_map(|anchor| {
             let href = dom::attr(&anchor, "href")?;
-            let target = href
-                .strip_prefix('#')
-                .or_else(|| href.split_once('#').map(|(_, fragment)| fragment))?;
-            let label = target_labels.get(target)?;
+            let label = target_labels.get(href_fragment(&href)?)?;
             Some((anchor, label.clone()))
         })
         .collect();
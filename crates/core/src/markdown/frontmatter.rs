@@ -1,16 +1,23 @@
 use serde::Serialize;
 
-use crate::Article;
+use crate::{Article, Error, Result};
 
 /// Format an article as Markdown with TOML frontmatter.
 ///
 /// The frontmatter includes available metadata from [`Article`] plus the
 /// optional source URL.
-pub fn markdown_with_toml_frontmatter(article: &Article, source: Option<&str>) -> Result<String, toml::ser::Error> {
+pub fn markdown_with_toml_frontmatter(article: &Article, source: Option<&str>) -> Result<String> {
+    Ok(format!("{}{}", toml_frontmatter(article, source)?, article.markdown))
+}
+
+/// The `+++` frontmatter block and blank line that precede the Markdown.
+pub(crate) fn toml_frontmatter(article: &Article, source: Option<&str>) -> Result<String> {
     let frontmatter = Frontmatter {
         title: non_empty(article.title.as_deref()),
         author: non_empty(article.byline.as_deref()),
         site: non_empty(article.site_name.as_deref()),
+        section: non_empty(article.section.as_deref()),
+        tags: article.keywords.iter().map(String::as_str).collect(),
         published: non_empty(article.published_time.as_deref()),
         source: non_empty(source),
         domain: non_empty(article.domain.as_deref()),
@@ -21,8 +28,8 @@ pub fn markdown_with_toml_frontmatter(article: &Article, source: Option<&str>) -
         dir: non_empty(article.dir.as_deref()),
         length: article.length,
     };
-    let metadata = toml::to_string(&frontmatter)?;
-    Ok(format!("+++\n{}+++\n\n{}", metadata, article.markdown))
+    let metadata = toml::to_string(&frontmatter).map_err(|error| Error::Frontmatter(error.to_string()))?;
+    Ok(format!("+++\n{metadata}+++\n\n"))
 }
 
 #[derive(Serialize)]
@@ -34,6 +41,10 @@ struct Frontmatter<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     site: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    section: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     published: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     source: Option<&'a str>,
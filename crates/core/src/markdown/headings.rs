@@ -0,0 +1,97 @@
+use comrak::nodes::NodeValue;
+use comrak::{Arena, format_commonmark, parse_document};
+
+/// Move the headings of formatted Markdown to new levels.
+///
+/// With `offset`, every heading shifts so the highest one sits that many
+/// levels below an H1 title: `Some(1)` makes it an H2. With `normalize`,
+/// skipped levels are closed so each heading is at most one level below the
+/// heading it falls under. Levels stay between 1 and 6.
+pub fn adjust_heading_levels(markdown: &str, offset: Option<u8>, normalize: bool) -> String {
+    let arena = Arena::new();
+    let opts = super::format_options();
+    let root = parse_document(&arena, markdown, &opts);
+    let headings: Vec<_> = root
+        .descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Heading(_)))
+        .collect();
+    let levels: Vec<u8> = headings
+        .iter()
+        .filter_map(|node| match &node.data.borrow().value {
+            NodeValue::Heading(heading) => Some(heading.level),
+            _ => None,
+        })
+        .collect();
+    let Some(&top) = levels.iter().min() else {
+        return markdown.to_string();
+    };
+
+    let base = offset.map_or(top, |offset| offset.saturating_add(1).min(6));
+    let shifted: Vec<u8> = levels
+        .iter()
+        .map(|&level| (i16::from(level) - i16::from(top) + i16::from(base)).clamp(1, 6) as u8)
+        .collect();
+    let adjusted = match normalize {
+        true => contiguous_levels(&shifted, base),
+        false => shifted,
+    };
+    if adjusted == levels {
+        return markdown.to_string();
+    }
+
+    for (node, level) in headings.iter().zip(adjusted) {
+        if let NodeValue::Heading(heading) = &mut node.data.borrow_mut().value {
+            heading.level = level;
+            heading.setext = false;
+        }
+    }
+    let mut output = String::new();
+    match format_commonmark(root, &opts, &mut output) {
+        Err(_) => markdown.to_string(),
+        Ok(_) => output.trim().to_string(),
+    }
+}
+
+/// Levels with no gaps under their parent heading. A heading's parent is the
+/// nearest earlier heading with a lower original level.
+fn contiguous_levels(levels: &[u8], base: u8) -> Vec<u8> {
+    let mut parents: Vec<(u8, u8)> = Vec::new();
+    levels
+        .iter()
+        .map(|&level| {
+            while parents.last().is_some_and(|&(original, _)| original >= level) {
+                parents.pop();
+            }
+            let adjusted = parents.last().map_or(base, |&(_, parent)| (parent + 1).min(6));
+            parents.push((level, adjusted));
+            adjusted
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "### Background\n\nText.\n\n##### Detail\n\nMore.\n\n### Results\n\n#### Numbers";
+
+    #[test]
+    fn offsets_headings_below_the_title() {
+        assert_eq!(
+            adjust_heading_levels(MARKDOWN, Some(1), false),
+            "## Background\n\nText.\n\n#### Detail\n\nMore.\n\n## Results\n\n### Numbers"
+        );
+    }
+
+    #[test]
+    fn closes_skipped_heading_levels() {
+        assert_eq!(
+            adjust_heading_levels(MARKDOWN, Some(1), true),
+            "## Background\n\nText.\n\n### Detail\n\nMore.\n\n## Results\n\n### Numbers"
+        );
+        assert_eq!(
+            adjust_heading_levels(MARKDOWN, None, true),
+            "### Background\n\nText.\n\n#### Detail\n\nMore.\n\n### Results\n\n#### Numbers"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{Arena, escape_commonmark_link_destination, format_commonmark, parse_document};
+
+use crate::LinkStyle;
+
+struct Target {
+    url: String,
+    title: String,
+    label: String,
+}
+
+/// Rewrite the inline links of formatted Markdown in another style.
+///
+/// Reference links keep their text and point at a numbered definition;
+/// footnote links keep only their text followed by a footnote marker. Both
+/// collect their definitions at the end, and links to the same target share
+/// a number. Autolinks, whose text is already the URL, stay as they are.
+pub fn apply_link_style(markdown: &str, style: LinkStyle) -> String {
+    if style == LinkStyle::Inline {
+        return markdown.to_string();
+    }
+
+    let arena = Arena::new();
+    let opts = super::format_options();
+    let root = parse_document(&arena, markdown, &opts);
+    let taken: Vec<String> = root
+        .descendants()
+        .filter_map(|node| match &node.data.borrow().value {
+            NodeValue::FootnoteDefinition(definition) => Some(definition.name.clone()),
+            _ => None,
+        })
+        .collect();
+    let links: Vec<_> = root
+        .descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Link(_)))
+        .collect();
+
+    let mut targets: Vec<Target> = Vec::new();
+    let mut next_number = 1;
+    for link in links {
+        let NodeValue::Link(ref target) = link.data.borrow().value.clone() else {
+            continue;
+        };
+        if is_autolink(link, &target.url) || (style == LinkStyle::Footnotes && !is_external(&target.url)) {
+            continue;
+        }
+        let label = match targets
+            .iter()
+            .find(|known| known.url == target.url && known.title == target.title)
+        {
+            Some(known) => known.label.clone(),
+            None => {
+                while style == LinkStyle::Footnotes && taken.contains(&next_number.to_string()) {
+                    next_number += 1;
+                }
+                let label = next_number.to_string();
+                next_number += 1;
+                targets.push(Target { url: target.url.clone(), title: target.title.clone(), label: label.clone() });
+                label
+            }
+        };
+
+        if style == LinkStyle::Reference {
+            link.insert_before(raw(&arena, "[".to_string()));
+        }
+        for child in link.children().collect::<Vec<_>>() {
+            link.insert_before(child);
+        }
+        let marker = match style {
+            LinkStyle::Footnotes => format!("[^{label}]"),
+            _ => format!("][{label}]"),
+        };
+        link.insert_before(raw(&arena, marker));
+        link.detach();
+    }
+
+    if targets.is_empty() {
+        return markdown.to_string();
+    }
+    let mut output = String::new();
+    if format_commonmark(root, &opts, &mut output).is_err() {
+        return markdown.to_string();
+    }
+    let mut output = output.trim().to_string();
+    output.push_str("\n\n");
+    let definitions: Vec<String> = targets
+        .iter()
+        .map(|target| {
+            let url = destination(&target.url);
+            match (style, target.title.is_empty()) {
+                (LinkStyle::Footnotes, _) => format!("[^{}]: {url}", target.label),
+                (_, true) => format!("[{}]: {url}", target.label),
+                (_, false) => format!("[{}]: {url} \"{}\"", target.label, target.title.replace('"', "\\\"")),
+            }
+        })
+        .collect();
+    let separator = if style == LinkStyle::Footnotes { "\n\n" } else { "\n" };
+    output.push_str(&definitions.join(separator));
+    output
+}
+
+fn raw<'a>(arena: &'a Arena<'a>, literal: String) -> &'a AstNode<'a> {
+    arena.alloc(NodeValue::HtmlInline(literal).into())
+}
+
+/// A URL as a definition destination, in angle brackets only when it needs them.
+fn destination(url: &str) -> String {
+    if url.is_empty() || url.contains(|c: char| c.is_whitespace() || c.is_control() || "<>".contains(c)) {
+        escape_commonmark_link_destination(url)
+    } else {
+        url.to_string()
+    }
+}
+
+fn is_external(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn is_autolink<'a>(link: &'a AstNode<'a>, url: &str) -> bool {
+    let mut text = String::new();
+    for node in link.descendants() {
+        match &node.data.borrow().value {
+            NodeValue::Text(value) => text.push_str(value),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            _ => {}
+        }
+    }
+    text == url || format!("mailto:{text}") == url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "See [the docs](https://example.com/docs \"Docs\") and [the FAQ](/faq).\n\n\
+                            Read [the docs](https://example.com/docs \"Docs\") again, or \
+                            <https://example.com>.[^1]\n\n[^1]: A note.";
+
+    #[test]
+    fn writes_reference_links_with_shared_definitions() {
+        assert_eq!(
+            apply_link_style(MARKDOWN, LinkStyle::Reference),
+            "See [the docs][1] and [the FAQ][2].\n\n\
+             Read [the docs][1] again, or <https://example.com>.[^1]\n\n\
+             [^1]:\n    A note.\n\n\
+             [1]: https://example.com/docs \"Docs\"\n\
+             [2]: /faq"
+        );
+    }
+
+    #[test]
+    fn turns_external_links_into_footnotes() {
+        assert_eq!(
+            apply_link_style(MARKDOWN, LinkStyle::Footnotes),
+            "See the docs[^2] and [the FAQ](/faq).\n\n\
+             Read the docs[^2] again, or <https://example.com>.[^1]\n\n\
+             [^1]:\n    A note.\n\n\
+             [^2]: https://example.com/docs"
+        );
+    }
+}
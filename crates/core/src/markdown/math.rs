@@ -1,7 +1,50 @@
 use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
 
 use super::{RenderContext, render_children};
-use crate::{dom, patterns};
+use crate::{dom, patterns, shared};
+
+/// Convert a MathJax `<script type="math/tex">` element into a detached
+/// MathML element that carries the TeX source in `alttext`.
+///
+/// Returns `None` for scripts that do not hold TeX.
+pub fn tex_script_to_math(script: &NodeRef) -> Option<NodeRef> {
+    let kind = dom::attr(script, "type")?.to_ascii_lowercase();
+    if !kind.contains("math/tex") && !kind.contains("math/latex") {
+        return None;
+    }
+    let body = script.text_contents();
+    let latex = strip_math_delimiters(&body);
+    if latex.is_empty() {
+        return None;
+    }
+
+    let latex = shared::escape_html(latex);
+    let display = if kind.contains("mode=display") { r#" display="block""# } else { "" };
+    let document = kuchiki::parse_html().one(format!(
+        r#"<html><body><math{display} alttext="{latex}"><mtext>{latex}</mtext></math></body></html>"#
+    ));
+    let math = dom::select_first(&document, "math")?;
+    math.detach();
+    Some(math)
+}
+
+/// Replace rendered KaTeX and MathJax markup with the MathML it already
+/// carries, dropping the visual-only HTML copy.
+pub fn normalize_math_markup(root: &NodeRef) {
+    for node in dom::select_nodes(root, ".katex, mjx-container") {
+        let Some(math) = dom::select_first(&node, "math") else {
+            continue;
+        };
+        if dom::node_name(&node) == "mjx-container"
+            && matches!(dom::attr(&node, "display").as_deref(), Some("true" | "block"))
+        {
+            dom::set_attr(&math, "display", "block");
+        }
+        node.insert_before(math);
+        node.detach();
+    }
+}
 
 pub(super) fn render_math(node: &NodeRef, _ctx: RenderContext) -> Option<String> {
     let latex = latex_for_node(node)?;
@@ -18,11 +61,10 @@ pub(super) fn render_math(node: &NodeRef, _ctx: RenderContext) -> Option<String>
 }
 
 pub(super) fn has_math(node: &NodeRef) -> bool {
-    !dom::select_nodes(
+    dom::exists(
         node,
         "math, mjx-container, .katex, .math, .mwe-math-element, img[alttext], img[data-latex], span[data-latex], script[type]",
     )
-    .is_empty()
 }
 
 fn latex_for_node(node: &NodeRef) -> Option<String> {
@@ -45,20 +87,18 @@ fn latex_from_attrs(node: &NodeRef) -> Option<String> {
 }
 
 fn latex_from_katex_annotation(node: &NodeRef) -> Option<String> {
-    dom::select_nodes(node, "annotation")
-        .into_iter()
-        .find_map(|annotation| {
-            let encoding = dom::attr(&annotation, "encoding")
-                .unwrap_or_default()
-                .to_ascii_lowercase();
-            if encoding.contains("tex") || encoding.contains("latex") {
-                let text = annotation.text_contents();
-                let latex = strip_math_delimiters(text.trim());
-                if latex.is_empty() { None } else { Some(latex.to_string()) }
-            } else {
-                None
-            }
-        })
+    dom::select_iter(node, "annotation").find_map(|annotation| {
+        let encoding = dom::attr(&annotation, "encoding")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if encoding.contains("tex") || encoding.contains("latex") {
+            let text = annotation.text_contents();
+            let latex = strip_math_delimiters(text.trim());
+            if latex.is_empty() { None } else { Some(latex.to_string()) }
+        } else {
+            None
+        }
+    })
 }
 
 fn latex_from_script(node: &NodeRef) -> Option<String> {
@@ -75,9 +115,7 @@ fn latex_from_script(node: &NodeRef) -> Option<String> {
 }
 
 fn latex_from_math_descendant(node: &NodeRef) -> Option<String> {
-    dom::select_nodes(node, "math")
-        .into_iter()
-        .find_map(|math| latex_from_mathml(&math))
+    dom::select_iter(node, "math").find_map(|math| latex_from_mathml(&math))
 }
 
 fn latex_from_mathml(node: &NodeRef) -> Option<String> {
@@ -106,8 +144,7 @@ fn is_display_math(node: &NodeRef) -> bool {
         return true;
     }
 
-    dom::select_nodes(node, "math, mjx-container")
-        .into_iter()
+    dom::select_iter(node, "math, mjx-container")
         .any(|child| dom::node_id(&child) != dom::node_id(node) && is_display_math(&child))
 }
 
@@ -250,8 +287,7 @@ fn undersuperscript(node: &NodeRef) -> String {
 }
 
 fn render_table_mathml(node: &NodeRef) -> String {
-    dom::select_nodes(node, "mtr, mlabeledtr")
-        .into_iter()
+    dom::select_iter(node, "mtr, mlabeledtr")
         .map(|row| {
             row.children()
                 .filter(|child| dom::node_name(child) == "mtd")
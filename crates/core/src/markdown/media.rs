@@ -5,6 +5,9 @@ use url::Url;
 use super::{RenderContext, block, inline_children};
 use crate::{dom, patterns};
 
+/// Attributes holding a single image URL, lazy-loading ones first.
+const IMAGE_URL_ATTRS: [&str; 5] = ["data-src", "data-original", "data-lazy-src", "data-url", "src"];
+
 #[derive(Debug, Clone)]
 struct ImageCandidate {
     url: String,
@@ -18,22 +21,57 @@ pub(super) fn render_image(node: &NodeRef) -> String {
 }
 
 pub(super) fn render_picture(node: &NodeRef) -> String {
-    let img = dom::select_nodes(node, "img").into_iter().next();
+    let img = dom::select_first(node, "img");
+    let candidates = picture_candidates(node, img.as_ref());
+    render_image_from_candidates(img.as_ref().unwrap_or(node), candidates)
+}
+
+/// Point each `<picture>` and `srcset` image at the one candidate that best
+/// fits `target_width` pixels: the narrowest at least that wide, or the
+/// widest when none is. A density descriptor counts as a multiple of the
+/// target. The chosen URL is written to `src`, and the other candidates,
+/// `sizes`, and the picture's `<source>` elements are removed.
+pub fn select_responsive_images(root: &NodeRef, target_width: u32) {
+    for picture in dom::select_nodes(root, "picture") {
+        let Some(img) = dom::select_first(&picture, "img") else {
+            continue;
+        };
+        if let Some(url) = target_image_url(picture_candidates(&picture, Some(&img)), target_width) {
+            use_image_url(&img, &url);
+            for source in dom::select_nodes(&picture, "source") {
+                source.detach();
+            }
+        }
+    }
+    for img in dom::select_nodes(root, "img[srcset], img[data-srcset]") {
+        if let Some(url) = target_image_url(image_candidates(&img, 0), target_width) {
+            use_image_url(&img, &url);
+        }
+    }
+}
+
+fn use_image_url(img: &NodeRef, url: &str) {
+    for attr in IMAGE_URL_ATTRS.iter().chain(["srcset", "data-srcset", "sizes"].iter()) {
+        dom::remove_attr(img, attr);
+    }
+    dom::set_attr(img, "src", url);
+}
+
+fn picture_candidates(picture: &NodeRef, img: Option<&NodeRef>) -> Vec<ImageCandidate> {
     let mut candidates = Vec::new();
     let mut order = 0;
-    for source in dom::select_nodes(node, "source") {
+    for source in dom::select_nodes(picture, "source") {
         candidates.extend(srcset_candidates(&source, order));
         order = candidates.len();
     }
-    if let Some(img) = img.as_ref() {
+    if let Some(img) = img {
         candidates.extend(image_candidates(img, order));
     }
-    render_image_from_candidates(img.as_ref().unwrap_or(node), candidates)
+    candidates
 }
 
 pub(super) fn render_figure(node: &NodeRef, ctx: RenderContext) -> Option<String> {
-    let media = dom::select_nodes(node, "picture, img, iframe, video, audio, object, embed, blockquote")
-        .into_iter()
+    let media = dom::select_iter(node, "picture, img, iframe, video, audio, object, embed, blockquote")
         .filter(|candidate| dom::node_id(candidate) != dom::node_id(node))
         .filter(|candidate| {
             dom::node_name(candidate) != "img"
@@ -54,8 +92,7 @@ pub(super) fn render_figure(node: &NodeRef, ctx: RenderContext) -> Option<String
     }
 
     let mut output = media.into_iter().next().unwrap();
-    if let Some(caption) = dom::select_nodes(node, "figcaption")
-        .into_iter()
+    if let Some(caption) = dom::select_iter(node, "figcaption")
         .map(|caption| inline_children(&caption, ctx))
         .find(|caption| !caption.is_empty())
     {
@@ -73,8 +110,7 @@ pub(super) fn render_embed(node: &NodeRef) -> Option<String> {
         if !class.contains("twitter-tweet") && !class.contains("x-tweet") {
             embed_url = None
         } else {
-            embed_url = dom::select_nodes(node, "a")
-                .into_iter()
+            embed_url = dom::select_iter(node, "a")
                 .filter_map(|link| dom::attr(&link, "href"))
                 .find_map(|href| normalize_embed_url(&href))
         }
@@ -88,7 +124,27 @@ pub(super) fn render_embed(node: &NodeRef) -> Option<String> {
         }
     }
 
-    embed_url.map(|url| format!("[Video]({})", escape_commonmark_link_destination(&url)))
+    match embed_url {
+        Some(url) => Some(format!("[Video]({})", escape_commonmark_link_destination(&url))),
+        None => render_iframe_link(node),
+    }
+}
+
+/// Render an iframe that survived cleanup but is not a known video as a link.
+fn render_iframe_link(node: &NodeRef) -> Option<String> {
+    if dom::node_name(node) != "iframe" {
+        return None;
+    }
+    let src = dom::attr(node, "src").filter(|src| src.starts_with("http://") || src.starts_with("https://"))?;
+    let label = dom::attr(node, "title")
+        .map(|title| patterns::normalize_spaces(title.trim()))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| "Embedded content".to_string());
+    Some(format!(
+        "[{}]({})",
+        escape_commonmark_inline(&label),
+        escape_commonmark_link_destination(&src)
+    ))
 }
 
 fn render_image_from_candidates(node: &NodeRef, candidates: Vec<ImageCandidate>) -> String {
@@ -113,7 +169,7 @@ fn render_image_from_candidates(node: &NodeRef, candidates: Vec<ImageCandidate>)
 fn image_candidates(node: &NodeRef, start_order: usize) -> Vec<ImageCandidate> {
     let mut candidates = srcset_candidates(node, start_order);
     let mut order = start_order + candidates.len();
-    for attr in ["data-src", "data-original", "data-lazy-src", "data-url", "src"] {
+    for attr in IMAGE_URL_ATTRS {
         if let Some(url) = dom::attr(node, attr).filter(|url| !url.trim().is_empty()) {
             candidates.push(ImageCandidate { url, width: None, density: None, order });
             order += 1;
@@ -190,17 +246,19 @@ fn parse_density_descriptor(value: &str) -> Option<f32> {
     value.strip_suffix('x')?.parse::<f32>().ok()
 }
 
+fn is_placeholder(candidate: &ImageCandidate) -> bool {
+    let trimmed = candidate.url.trim().to_ascii_lowercase();
+    trimmed.is_empty()
+        || trimmed.starts_with("data:")
+        || trimmed == "#"
+        || trimmed == "about:blank"
+        || trimmed.contains("placeholder")
+}
+
 fn best_image_url(candidates: Vec<ImageCandidate>) -> Option<String> {
     candidates
         .into_iter()
-        .filter(|candidate| {
-            let trimmed = candidate.url.trim().to_ascii_lowercase();
-            !(trimmed.is_empty()
-                || trimmed.starts_with("data:")
-                || trimmed == "#"
-                || trimmed == "about:blank"
-                || trimmed.contains("placeholder"))
-        })
+        .filter(|candidate| !is_placeholder(candidate))
         .max_by(|a, b| {
             image_score(a)
                 .total_cmp(&image_score(b))
@@ -209,6 +267,38 @@ fn best_image_url(candidates: Vec<ImageCandidate>) -> Option<String> {
         .map(|candidate| candidate.url)
 }
 
+/// The candidate closest to `target_width` without being narrower, or the
+/// widest one. Falls back to [`best_image_url`] when no candidate has a
+/// descriptor.
+fn target_image_url(candidates: Vec<ImageCandidate>, target_width: u32) -> Option<String> {
+    let target = target_width as f32;
+    let width = |candidate: &ImageCandidate| {
+        candidate
+            .width
+            .map(|width| width as f32)
+            .or(candidate.density.map(|density| density * target))
+    };
+    let candidates: Vec<ImageCandidate> = candidates
+        .into_iter()
+        .filter(|candidate| !is_placeholder(candidate))
+        .collect();
+    let sized = || {
+        candidates
+            .iter()
+            .filter_map(|candidate| Some((width(candidate)?, candidate)))
+    };
+    sized()
+        .filter(|(width, _)| *width >= target)
+        .min_by(|(a, a_candidate), (b, b_candidate)| a.total_cmp(b).then(a_candidate.order.cmp(&b_candidate.order)))
+        .or_else(|| {
+            sized().max_by(|(a, a_candidate), (b, b_candidate)| {
+                a.total_cmp(b).then(b_candidate.order.cmp(&a_candidate.order))
+            })
+        })
+        .map(|(_, candidate)| candidate.url.clone())
+        .or_else(|| best_image_url(candidates))
+}
+
 fn image_score(candidate: &ImageCandidate) -> f32 {
     if let Some(width) = candidate.width {
         width as f32
@@ -292,4 +382,33 @@ mod tests {
         assert_eq!(candidates.len(), 3);
         assert_eq!(super::best_image_url(candidates).as_deref(), Some("large.png"));
     }
+
+    #[test]
+    fn selects_the_narrowest_candidate_covering_the_target_width() {
+        use kuchiki::traits::TendrilSink;
+
+        let document = kuchiki::parse_html().one(
+            r#"<picture>
+              <source type="image/webp" srcset="tide-480.webp 480w, tide-960.webp 960w, tide-1920.webp 1920w">
+              <img src="tide-placeholder.jpg" data-src="tide-small.jpg" sizes="100vw" alt="Low tide">
+            </picture>
+            <img id="density" src="chart.png" srcset="chart.png 1x, chart@2x.png 2x">
+            <img id="narrow" srcset="map-320.png 320w, map-640.png 640w">"#,
+        );
+        super::select_responsive_images(&document, 800);
+
+        let picture = crate::dom::select_nodes(&document, "picture").remove(0);
+        assert!(!crate::dom::exists(&picture, "source"));
+        let img = crate::dom::select_nodes(&picture, "img").remove(0);
+        assert_eq!(crate::dom::attr(&img, "src").as_deref(), Some("tide-960.webp"));
+        assert_eq!(crate::dom::attr(&img, "data-src"), None);
+        assert_eq!(crate::dom::attr(&img, "sizes"), None);
+        assert_eq!(crate::dom::attr(&img, "alt").as_deref(), Some("Low tide"));
+
+        let density = crate::dom::select_nodes(&document, "#density").remove(0);
+        assert_eq!(crate::dom::attr(&density, "src").as_deref(), Some("chart.png"));
+        assert_eq!(crate::dom::attr(&density, "srcset"), None);
+        let narrow = crate::dom::select_nodes(&document, "#narrow").remove(0);
+        assert_eq!(crate::dom::attr(&narrow, "src").as_deref(), Some("map-640.png"));
+    }
 }
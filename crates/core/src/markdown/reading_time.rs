@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{Arena, format_commonmark, parse_document};
+
+/// Words read per minute for the markers added by the `reading_time_markers`
+/// option.
+pub const WORDS_PER_MINUTE: usize = 230;
+
+/// Mark each heading of formatted Markdown with the reading time before it,
+/// such as `*~12 min in*`.
+///
+/// Times count the words of everything above the heading, rounded to the
+/// nearest minute. Headings less than half a minute in get no marker.
+pub fn annotate_reading_time(markdown: &str, words_per_minute: usize) -> String {
+    let arena = Arena::new();
+    let opts = super::format_options();
+    let root = parse_document(&arena, markdown, &opts);
+    let words_per_minute = words_per_minute.max(1);
+
+    let mut words = 0;
+    let mut marked = false;
+    for block in root.children().collect::<Vec<_>>() {
+        let minutes = (words + words_per_minute / 2) / words_per_minute;
+        if matches!(block.data.borrow().value, NodeValue::Heading(_)) && minutes > 0 {
+            let paragraph = arena.alloc(NodeValue::Paragraph.into());
+            let emphasis = arena.alloc(NodeValue::Emph.into());
+            emphasis.append(arena.alloc(NodeValue::Text(Cow::Owned(format!("~{minutes} min in"))).into()));
+            paragraph.append(emphasis);
+            block.insert_before(paragraph);
+            marked = true;
+        }
+        words += word_count(block);
+    }
+    if !marked {
+        return markdown.to_string();
+    }
+
+    let mut output = String::new();
+    match format_commonmark(root, &opts, &mut output) {
+        Err(_) => markdown.to_string(),
+        Ok(_) => output.trim().to_string(),
+    }
+}
+
+fn word_count<'a>(block: &'a AstNode<'a>) -> usize {
+    block
+        .descendants()
+        .map(|node| match &node.data.borrow().value {
+            NodeValue::Text(text) => text.split_whitespace().count(),
+            NodeValue::Code(code) => code.literal.split_whitespace().count(),
+            NodeValue::CodeBlock(code) => code.literal.split_whitespace().count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_sections_with_cumulative_reading_time() {
+        let section = "word ".repeat(300);
+        let markdown = format!("## One\n\n{section}\n\n## Two\n\n{section}\n\n## Three\n\nEnd.");
+
+        let annotated = annotate_reading_time(&markdown, 200);
+
+        assert!(annotated.starts_with("## One\n\n"));
+        assert!(annotated.contains("*~2 min in*\n\n## Two"));
+        assert!(annotated.contains("*~3 min in*\n\n## Three"));
+        assert_eq!(
+            annotate_reading_time("## One\n\nShort.\n\n## Two", 200),
+            "## One\n\nShort.\n\n## Two"
+        );
+    }
+}
@@ -85,9 +85,7 @@ fn separator_row(width: usize) -> String {
 }
 
 fn has_spanning_cell(node: &NodeRef) -> bool {
-    dom::select_nodes(node, "td, th")
-        .into_iter()
-        .any(|cell| span_value(&cell, "rowspan") > 1 || span_value(&cell, "colspan") > 1)
+    dom::select_iter(node, "td, th").any(|cell| span_value(&cell, "rowspan") > 1 || span_value(&cell, "colspan") > 1)
 }
 
 fn span_value(node: &NodeRef, attr: &str) -> usize {
@@ -111,15 +109,12 @@ fn is_layout_table(node: &NodeRef, rows: &[Vec<String>]) -> bool {
         return true;
     }
 
-    if dom::select_nodes(node, "table")
-        .into_iter()
-        .any(|table| dom::node_id(&table) != dom::node_id(node))
-    {
+    if dom::select_iter(node, "table").any(|table| dom::node_id(&table) != dom::node_id(node)) {
         return true;
     }
 
-    let has_header_signal = dom::attr(node, "summary").is_some()
-        || !dom::select_nodes(node, "caption, col, colgroup, tfoot, thead, th").is_empty();
+    let has_header_signal =
+        dom::attr(node, "summary").is_some() || dom::exists(node, "caption, col, colgroup, tfoot, thead, th");
     if has_header_signal {
         return false;
     }
@@ -141,12 +136,11 @@ fn has_data_density(rows: &[Vec<String>]) -> bool {
 }
 
 fn has_layout_cell_content(node: &NodeRef) -> bool {
-    dom::select_nodes(node, "td, th").into_iter().any(|cell| {
-        !dom::select_nodes(
+    dom::select_iter(node, "td, th").any(|cell| {
+        dom::exists(
             &cell,
             "p, div, section, article, header, footer, ul, ol, table, form, figure",
         )
-        .is_empty()
     })
 }
 
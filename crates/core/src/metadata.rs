@@ -2,31 +2,48 @@ use std::collections::HashMap;
 
 use crate::shared;
 
+use chrono::{DateTime, FixedOffset};
 use scraper::Html;
 use url::Url;
 
-use super::config::ReadabilityOptions;
+use super::config::{ArticleLink, ReadabilityOptions};
 use super::regexes::RegexPattern;
-use super::{json_schema, patterns};
+use super::{dates, json_schema, links, microformats, patterns, paywall};
 
 #[derive(Clone, Debug, Default)]
 pub struct Metadata {
     pub title: Option<String>,
+    pub raw_title: Option<String>,
     pub byline: Option<String>,
+    pub author_bio: Option<String>,
     pub excerpt: Option<String>,
     pub site_name: Option<String>,
     pub published_time: Option<String>,
+    pub modified_time: Option<String>,
+    pub published: Option<DateTime<FixedOffset>>,
+    pub modified: Option<DateTime<FixedOffset>>,
     pub image: Option<String>,
     pub domain: Option<String>,
     pub favicon: Option<String>,
+    pub canonical_url: Option<String>,
+    pub amp_url: Option<String>,
+    pub keywords: Vec<String>,
+    pub section: Option<String>,
+    pub is_paywalled: Option<bool>,
+    pub is_sponsored: Option<bool>,
     pub schema_text: Option<String>,
     pub lang: Option<String>,
     pub dir: Option<String>,
+    pub related_links: Vec<ArticleLink>,
 }
 
 pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOptions, base_url: Option<&Url>) -> Metadata {
     let mut metadata = if options.disable_json_ld { Metadata::default() } else { json_schema::extract_json_ld(html) };
+    // Microformats fill gaps left by structured data and meta tags.
+    let mut h_entry = microformats::extract_h_entry(document);
     let mut values = HashMap::<String, String>::new();
+    // `article:tag` repeats once per tag, so it cannot share the single-value map.
+    let mut article_tags = Vec::new();
     let meta_selector = patterns::selector("meta");
 
     for element in document.select(&meta_selector) {
@@ -42,7 +59,9 @@ pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOption
         if let Some(property) = element.value().attr("property") {
             for name in property.split_whitespace() {
                 let name = name.to_lowercase();
-                if meta_property_key(&name) {
+                if name == "article:tag" {
+                    article_tags.push(decode_html_entities(content));
+                } else if meta_property_key(&name) {
                     values.insert(name, decode_html_entities(content));
                 }
             }
@@ -59,26 +78,32 @@ pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOption
     metadata.site_name = metadata
         .site_name
         .or_else(|| first_value(&values, &["og:site_name", "application-name"]));
-    metadata.title = metadata
-        .title
-        .or_else(|| {
-            first_value(
-                &values,
-                &[
-                    "dc:title",
-                    "dcterm:title",
-                    "og:title",
-                    "weibo:article:title",
-                    "weibo:webpage:title",
-                    "title",
-                    "twitter:title",
-                    "parsely-title",
-                    "sailthru:title",
-                ],
-            )
-        })
-        .and_then(|title| prefer_specific_headline(document, metadata.site_name.as_deref(), &title))
-        .or_else(|| article_title(document));
+    let raw_title = metadata.title.take().or_else(|| {
+        first_value(
+            &values,
+            &[
+                "dc:title",
+                "dcterm:title",
+                "og:title",
+                "weibo:article:title",
+                "weibo:webpage:title",
+                "title",
+                "twitter:title",
+                "parsely-title",
+                "sailthru:title",
+            ],
+        )
+        .or_else(|| h_entry.title.take())
+    });
+    metadata.title = raw_title
+        .as_deref()
+        .and_then(|title| prefer_specific_headline(document, metadata.site_name.as_deref(), title))
+        .or_else(|| article_title(document))
+        .map(|title| strip_site_suffix(document, metadata.site_name.as_deref(), &title));
+    metadata.raw_title = raw_title
+        .map(|title| patterns::normalize_spaces(title.trim()))
+        .or_else(|| document_title(document))
+        .filter(|title| !title.is_empty());
     metadata.byline = metadata
         .byline
         .or_else(|| {
@@ -98,7 +123,9 @@ pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOption
             )
             .and_then(|value| normalize_byline(&value))
         })
+        .or_else(|| h_entry.byline.take())
         .or_else(|| byline_from_document(document));
+    metadata.author_bio = metadata.author_bio.or_else(|| author_bio_from_document(document));
     metadata.excerpt = metadata.excerpt.or_else(|| {
         first_value(
             &values,
@@ -113,16 +140,24 @@ pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOption
                 "sailthru:description",
             ],
         )
+        .or_else(|| h_entry.excerpt.take())
     });
     metadata.published_time = metadata
         .published_time
-        .or_else(|| first_value(&values, &["article:published_time", "parsely-pub-date", "publishdate"]));
-    metadata.published_time = metadata
-        .published_time
+        .or_else(|| first_value(&values, &["article:published_time", "parsely-pub-date", "publishdate"]))
+        .or_else(|| h_entry.published_time.take())
         .or_else(|| published_time_from_document(document));
+    metadata.modified_time = metadata
+        .modified_time
+        .or_else(|| first_value(&values, &["article:modified_time", "og:updated_time"]))
+        .or_else(|| h_entry.modified_time.take())
+        .or_else(|| modified_time_from_document(document));
+    metadata.published = metadata.published_time.as_deref().and_then(dates::parse_date);
+    metadata.modified = metadata.modified_time.as_deref().and_then(dates::parse_date);
     metadata.image = metadata
         .image
         .or_else(|| first_value(&values, &["og:image", "twitter:image", "sailthru:image:full", "image"]))
+        .or_else(|| h_entry.image.take())
         .and_then(|image| absolutize_url(&image, base_url));
     metadata.favicon = metadata
         .favicon
@@ -137,20 +172,35 @@ pub fn extract_metadata(document: &Html, html: &str, options: &ReadabilityOption
         })
         .or_else(|| base_url.and_then(|_| absolutize_url("/favicon.ico", base_url)));
     metadata.favicon = metadata.favicon.and_then(|favicon| absolutize_url(&favicon, base_url));
+    if metadata.keywords.is_empty() {
+        metadata.keywords = split_keywords(&article_tags.join(","));
+    }
+    if metadata.keywords.is_empty() {
+        metadata.keywords = first_value(&values, &["keywords", "news_keywords"])
+            .map(|value| split_keywords(&value))
+            .unwrap_or_default();
+    }
+    metadata.section = metadata
+        .section
+        .or_else(|| first_value(&values, &["article:section"]))
+        .and_then(|section| clean_metadata_value(&section));
+    // Structured data is authoritative; DOM markers only fill in when it is silent.
+    metadata.is_paywalled = metadata
+        .is_paywalled
+        .or_else(|| paywall::has_paywall_markers(document).then_some(true));
+    metadata.related_links = links::related_links(document, base_url);
+    metadata.canonical_url = metadata.canonical_url.or_else(|| canonical_url(document, base_url));
+    metadata.amp_url = metadata
+        .amp_url
+        .or_else(|| link_href(document, "amphtml"))
+        .and_then(|url| absolutize_url(&url, base_url));
     metadata.domain = metadata
         .domain
         .or_else(|| {
-            let selector = patterns::selector(r#"link[rel="canonical"]"#);
-            let canonical_url = document
-                .select(&selector)
-                .find_map(|link| link.value().attr("href").and_then(clean_metadata_value));
-
-            canonical_url.and_then(|url| {
-                Url::parse(&url)
-                    .ok()?
-                    .host_str()
-                    .map(|value| value.strip_prefix("www.").unwrap_or(value).to_string())
-            })
+            Url::parse(metadata.canonical_url.as_deref()?)
+                .ok()?
+                .host_str()
+                .map(|value| value.strip_prefix("www.").unwrap_or(value).to_string())
         })
         .or_else(|| {
             base_url.and_then(|url| {
@@ -254,8 +304,11 @@ fn meta_property_key(name: &str) -> bool {
                 | "image"
                 | "image:favicon"
                 | "published_time"
+                | "modified_time"
+                | "updated_time"
                 | "title"
                 | "site_name"
+                | "section"
         )
 }
 
@@ -284,17 +337,21 @@ fn meta_name_key(name: &str) -> bool {
             | "description"
             | "title"
             | "site_name"
+            | "keywords"
+            | "news_keywords"
     )
 }
 
-fn article_title(document: &Html) -> Option<String> {
+fn document_title(document: &Html) -> Option<String> {
     let title_selector = patterns::selector("title");
-    let title = document
+    document
         .select(&title_selector)
         .next()
-        .map(|title| title.text().collect::<String>())
-        .unwrap_or_default();
-    let original = patterns::normalize_spaces(title.trim());
+        .map(|title| patterns::normalize_spaces(title.text().collect::<String>().trim()))
+}
+
+fn article_title(document: &Html) -> Option<String> {
+    let original = document_title(document).unwrap_or_default();
     if original.is_empty() {
         return Some(String::new());
     }
@@ -378,6 +435,53 @@ fn specific_heading(document: &Html) -> Option<String> {
         .find(|heading| !heading.is_empty())
 }
 
+/// Drop trailing site-name segments such as "Story | Site Name – Blog".
+///
+/// A title that starts with the page heading followed by a separator collapses
+/// to that heading; otherwise everything from the first segment equal to the
+/// site name onwards is removed.
+fn strip_site_suffix(document: &Html, site_name: Option<&str>, title: &str) -> String {
+    let title = patterns::normalize_spaces(title.trim());
+    let separators = separator_spans(&title);
+    if separators.is_empty() {
+        return title;
+    }
+
+    if let Some(heading) = specific_heading(document).filter(|heading| shared::word_count(heading) >= 2)
+        && separators
+            .iter()
+            .any(|(start, _)| title[..*start].trim().eq_ignore_ascii_case(&heading))
+    {
+        return heading;
+    }
+
+    let Some(site_name) = site_name.map(str::trim).filter(|site_name| !site_name.is_empty()) else {
+        return title;
+    };
+    for (index, (start, end)) in separators.iter().enumerate() {
+        let segment_end = separators.get(index + 1).map_or(title.len(), |(next, _)| *next);
+        if title[*end..segment_end].trim().eq_ignore_ascii_case(site_name) && !title[..*start].trim().is_empty() {
+            return title[..*start].trim().to_string();
+        }
+    }
+    title
+}
+
+/// Byte ranges of every title separator, in document order.
+fn separator_spans(title: &str) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = patterns::TITLE_SEPARATORS
+        .iter()
+        .flat_map(|separator| {
+            title
+                .match_indices(separator)
+                .map(|(index, separator)| (index, index + separator.len()))
+        })
+        .collect();
+    spans.sort_unstable();
+    spans.dedup_by(|next, previous| next.0 < previous.1);
+    spans
+}
+
 fn last_separator(title: &str) -> Option<(&'static str, usize)> {
     patterns::TITLE_SEPARATORS
         .iter()
@@ -409,6 +513,25 @@ fn byline_from_document(document: &Html) -> Option<String> {
     None
 }
 
+/// Text of the first "About the author" box, without its heading.
+fn author_bio_from_document(document: &Html) -> Option<String> {
+    let selector = patterns::selector(patterns::AUTHOR_BIO_SELECTOR);
+    let paragraph_selector = patterns::selector("p");
+    document.select(&selector).find_map(|element| {
+        let paragraphs: Vec<String> = element
+            .select(&paragraph_selector)
+            .map(|paragraph| paragraph.text().collect::<String>())
+            .collect();
+        let text = match paragraphs.is_empty() {
+            true => element.text().collect::<String>(),
+            false => paragraphs.join(" "),
+        };
+        let text = patterns::normalize_spaces(text.trim());
+        let text = RegexPattern::AuthorBioHeading.to_regex().replace(&text, "");
+        (!text.is_empty() && text.chars().count() <= patterns::MAX_AUTHOR_BIO_CHARS).then(|| text.into_owned())
+    })
+}
+
 fn byline_from_selector(document: &Html, selector: &str) -> Option<String> {
     let selector = patterns::selector(selector);
     for element in document.select(&selector) {
@@ -537,6 +660,44 @@ fn published_time_from_document(document: &Html) -> Option<String> {
     })
 }
 
+fn modified_time_from_document(document: &Html) -> Option<String> {
+    let selector = patterns::selector(
+        r#"[itemprop*="dateModified"][datetime], [itemprop*="dateModified"][content], [property="article:modified_time"][content]"#,
+    );
+    document.select(&selector).find_map(|element| {
+        element
+            .value()
+            .attr("datetime")
+            .or_else(|| element.value().attr("content"))
+            .and_then(clean_metadata_value)
+    })
+}
+
+/// `<link rel="canonical">` resolved against `base_url`. Relative hrefs are
+/// dropped when there is no base URL.
+pub fn canonical_url(document: &Html, base_url: Option<&Url>) -> Option<String> {
+    link_href(document, "canonical").and_then(|url| absolutize_url(&url, base_url))
+}
+
+fn link_href(document: &Html, rel: &str) -> Option<String> {
+    let selector = patterns::selector(&format!(r#"link[rel~="{rel}" i][href]"#));
+    document
+        .select(&selector)
+        .find_map(|link| link.value().attr("href").and_then(clean_metadata_value))
+}
+
+/// Split comma- or semicolon-separated keywords, dropping blanks and
+/// case-insensitive repeats.
+pub fn split_keywords(value: &str) -> Vec<String> {
+    let mut keywords = Vec::<String>::new();
+    for keyword in value.split([',', ';']).filter_map(clean_metadata_value) {
+        if !keywords.iter().any(|existing| existing.eq_ignore_ascii_case(&keyword)) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
 fn absolutize_url(value: &str, base_url: Option<&Url>) -> Option<String> {
     let value = clean_metadata_value(value)?;
     if let Ok(url) = Url::parse(&value) {
@@ -617,4 +778,170 @@ mod tests {
 
         assert_eq!(metadata.title.as_deref(), Some("Responsive images"));
     }
+
+    #[test]
+    fn strips_site_name_suffixes_and_keeps_raw_title() {
+        let html = r#"
+            <html><head>
+                <meta property="og:site_name" content="Site Name">
+                <meta property="og:title" content="Measuring River Flow | Site Name – Blog">
+            </head><body><article><p>No heading here.</p></article></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.title.as_deref(), Some("Measuring River Flow"));
+        assert_eq!(
+            metadata.raw_title.as_deref(),
+            Some("Measuring River Flow | Site Name – Blog")
+        );
+    }
+
+    #[test]
+    fn collapses_title_to_matching_heading() {
+        let html = r#"
+            <html><head>
+                <title>Measuring   River Flow :: Field Notes :: Archive</title>
+            </head><body><article><h1>Measuring River Flow</h1></article></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.title.as_deref(), Some("Measuring River Flow"));
+        assert_eq!(
+            metadata.raw_title.as_deref(),
+            Some("Measuring River Flow :: Field Notes :: Archive")
+        );
+    }
+
+    #[test]
+    fn parses_published_and_modified_dates() {
+        let html = r#"
+            <html><head>
+                <meta property="article:published_time" content="2026-05-01T12:00:00+02:00">
+                <meta property="article:modified_time" content="Tue, 05 May 2026 09:30:00 GMT">
+            </head><body><article><h1>Dated</h1></article></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.modified_time.as_deref(), Some("Tue, 05 May 2026 09:30:00 GMT"));
+        assert_eq!(
+            metadata.published.map(|date| date.to_rfc3339()).as_deref(),
+            Some("2026-05-01T12:00:00+02:00")
+        );
+        assert_eq!(
+            metadata.modified.map(|date| date.to_rfc3339()).as_deref(),
+            Some("2026-05-05T09:30:00+00:00")
+        );
+    }
+
+    #[test]
+    fn reads_json_ld_date_modified() {
+        let html = r#"
+            <html><head><script type="application/ld+json">
+                {"@type": "NewsArticle", "headline": "Dated", "datePublished": "2026-05-01", "dateModified": "2026-05-02T08:00:00Z"}
+            </script></head><body></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.modified_time.as_deref(), Some("2026-05-02T08:00:00Z"));
+        assert_eq!(
+            metadata.published.map(|date| date.to_rfc3339()).as_deref(),
+            Some("2026-05-01T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn fills_missing_fields_from_h_entry() {
+        let html = r#"
+            <html><head>
+                <title>Notebook</title>
+                <meta name="description" content="Meta description wins.">
+            </head><body>
+                <article class="h-entry">
+                    <h1 class="p-name">Fixing a Bicycle Hub</h1>
+                    <p>By <a class="p-author h-card" href="/">Sam Ortiz</a>
+                    on <time class="dt-published" datetime="2026-02-10">Feb 10</time></p>
+                    <p class="p-summary">Summary from the entry.</p>
+                    <div class="e-content"><p>Hub notes.</p></div>
+                </article>
+            </body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.title.as_deref(), Some("Fixing a Bicycle Hub"));
+        assert_eq!(metadata.byline.as_deref(), Some("Sam Ortiz"));
+        assert_eq!(metadata.published_time.as_deref(), Some("2026-02-10"));
+        assert_eq!(metadata.excerpt.as_deref(), Some("Meta description wins."));
+    }
+
+    #[test]
+    fn reads_keywords_and_section_by_source_priority() {
+        let html = r#"
+            <html><head>
+                <meta property="article:section" content="Science">
+                <meta property="article:tag" content="Space">
+                <meta property="article:tag" content="Rockets, space">
+                <meta name="keywords" content="ignored, because, tags, exist">
+            </head><body></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.keywords, ["Space", "Rockets"]);
+        assert_eq!(metadata.section.as_deref(), Some("Science"));
+
+        let html = r#"
+            <html><head>
+                <script type="application/ld+json">
+                    {"@type": "NewsArticle", "headline": "Tagged", "keywords": ["Ocean", "Tides"], "articleSection": ["Earth", "News"]}
+                </script>
+                <meta property="article:section" content="Science">
+                <meta name="keywords" content="fallback; words">
+            </head><body></body></html>
+        "#;
+        let document = Html::parse_document(html);
+        let metadata = extract_metadata(&document, html, &ReadabilityOptions::default(), None);
+
+        assert_eq!(metadata.keywords, ["Ocean", "Tides"]);
+        assert_eq!(metadata.section.as_deref(), Some("Earth"));
+    }
+
+    #[test]
+    fn reads_paywall_from_json_ld_before_dom_markers() {
+        let paywall = r#"<div class="paywall-prompt">Subscribe to keep reading</div>"#;
+        let parse = |head: &str| {
+            let html = format!("<html><head>{head}</head><body>{paywall}</body></html>");
+            extract_metadata(
+                &Html::parse_document(&html),
+                &html,
+                &ReadabilityOptions::default(),
+                None,
+            )
+            .is_paywalled
+        };
+
+        assert_eq!(parse(""), Some(true));
+        assert_eq!(
+            parse(
+                r#"<script type="application/ld+json">{"@type": "NewsArticle", "isAccessibleForFree": "True"}</script>"#
+            ),
+            Some(false)
+        );
+        assert_eq!(
+            parse(
+                r#"<script type="application/ld+json">{"@type": "NewsArticle", "isAccessibleForFree": true,
+                    "hasPart": {"@type": "WebPageElement", "isAccessibleForFree": false, "cssSelector": ".locked"}}</script>"#
+            ),
+            Some(true)
+        );
+        let html = "<html><body><p>Open.</p></body></html>";
+        assert_eq!(
+            extract_metadata(&Html::parse_document(html), html, &ReadabilityOptions::default(), None).is_paywalled,
+            None
+        );
+    }
 }
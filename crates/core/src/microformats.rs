@@ -0,0 +1,131 @@
+use scraper::{ElementRef, Html};
+
+use super::metadata::{Metadata, clean_metadata_value, normalize_byline};
+use super::patterns;
+
+/// Longest `p-name` accepted as a title. Notes often put the whole post in
+/// `p-name`, which is content rather than a headline.
+const MAX_NAME_LEN: usize = 200;
+
+/// Read metadata from the page's `h-entry`.
+///
+/// Pages with more than one top-level entry, such as `h-feed` indexes, return
+/// empty metadata because no single entry describes the page.
+pub fn extract_h_entry(document: &Html) -> Metadata {
+    let selector = patterns::selector(".h-entry");
+    let mut entries = document.select(&selector).filter(|entry| {
+        !entry
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|ancestor| has_class(&ancestor, "h-entry"))
+    });
+    let (Some(entry), None) = (entries.next(), entries.next()) else {
+        return Metadata::default();
+    };
+
+    Metadata {
+        title: property(entry, "p-name")
+            .filter(|name| !has_class(name, "e-content"))
+            .and_then(text_value)
+            .filter(|name| name.chars().count() <= MAX_NAME_LEN),
+        byline: property(entry, "p-author").and_then(author_value),
+        excerpt: property(entry, "p-summary").and_then(text_value),
+        published_time: property(entry, "dt-published").and_then(datetime_value),
+        modified_time: property(entry, "dt-updated").and_then(datetime_value),
+        image: property(entry, "u-featured")
+            .or_else(|| property(entry, "u-photo"))
+            .and_then(url_value),
+        ..Default::default()
+    }
+}
+
+/// First element carrying `class` that belongs to `entry` rather than to a
+/// nested microformat such as the author's `h-card`.
+fn property<'a>(entry: ElementRef<'a>, class: &str) -> Option<ElementRef<'a>> {
+    let selector = patterns::selector(&format!(".{class}"));
+    entry.select(&selector).find(|element| {
+        element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(is_microformat_root)
+            .is_some_and(|root| root.id() == entry.id())
+    })
+}
+
+fn is_microformat_root(element: &ElementRef<'_>) -> bool {
+    element.value().classes().any(|class| class.starts_with("h-"))
+}
+
+fn has_class(element: &ElementRef<'_>, class: &str) -> bool {
+    element.value().classes().any(|candidate| candidate == class)
+}
+
+fn text_value(element: ElementRef<'_>) -> Option<String> {
+    clean_metadata_value(&element.text().collect::<String>())
+}
+
+fn author_value(element: ElementRef<'_>) -> Option<String> {
+    let name = if has_class(&element, "h-card") { property(element, "p-name").and_then(text_value) } else { None };
+    normalize_byline(&name.or_else(|| text_value(element))?)
+}
+
+fn datetime_value(element: ElementRef<'_>) -> Option<String> {
+    let value = element.value();
+    value
+        .attr("datetime")
+        .or_else(|| value.attr("content"))
+        .or_else(|| value.attr("value"))
+        .or_else(|| (value.name() == "abbr").then(|| value.attr("title")).flatten())
+        .and_then(clean_metadata_value)
+        .or_else(|| text_value(element))
+}
+
+fn url_value(element: ElementRef<'_>) -> Option<String> {
+    let value = element.value();
+    value
+        .attr("src")
+        .or_else(|| value.attr("href"))
+        .or_else(|| value.attr("data"))
+        .and_then(clean_metadata_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::extract_h_entry;
+
+    #[test]
+    fn reads_entry_properties_but_not_nested_card_names() {
+        let document = Html::parse_document(
+            r#"
+            <html><body>
+                <article class="h-entry">
+                    <h1 class="p-name">Building a Tiny Garden Shed</h1>
+                    <a class="p-author h-card" href="/"><img class="u-photo" src="/me.jpg"><span class="p-name">Robin Vale</span></a>
+                    <time class="dt-published" datetime="2026-03-04T09:30:00+01:00">March 4</time>
+                    <p class="p-summary">Notes from a weekend of framing.</p>
+                    <div class="e-content"><img class="u-photo" src="/shed.jpg"><p>Body.</p></div>
+                </article>
+            </body></html>
+            "#,
+        );
+
+        let metadata = extract_h_entry(&document);
+
+        assert_eq!(metadata.title.as_deref(), Some("Building a Tiny Garden Shed"));
+        assert_eq!(metadata.byline.as_deref(), Some("Robin Vale"));
+        assert_eq!(metadata.published_time.as_deref(), Some("2026-03-04T09:30:00+01:00"));
+        assert_eq!(metadata.excerpt.as_deref(), Some("Notes from a weekend of framing."));
+        assert_eq!(metadata.image.as_deref(), Some("/shed.jpg"));
+    }
+
+    #[test]
+    fn ignores_feeds_with_several_entries() {
+        let document = Html::parse_document(
+            r#"<div class="h-feed"><div class="h-entry"><span class="p-name">One</span></div><div class="h-entry"><span class="p-name">Two</span></div></div>"#,
+        );
+
+        assert!(extract_h_entry(&document).title.is_none());
+    }
+}
@@ -32,7 +32,7 @@ fn remove_duplicate_title_heading(root: &NodeRef, title: Option<&str>) {
     let Some(title) = title else {
         return;
     };
-    let Some(heading) = dom::select_nodes(root, "h1").into_iter().next() else {
+    let Some(heading) = dom::select_first(root, "h1") else {
         return;
     };
     if normalized_text(&dom::inner_text(&heading)) == normalized_text(title) {
@@ -42,14 +42,14 @@ fn remove_duplicate_title_heading(root: &NodeRef, title: Option<&str>) {
 
 fn normalize_code_blocks(root: &NodeRef) {
     for pre in dom::select_nodes(root, "pre") {
-        if !dom::select_nodes(&pre, "code").is_empty() {
+        if dom::exists(&pre, "code") {
             continue;
         }
         let fragment = kuchiki::parse_html().one("<html><body><pre><code></code></pre></body></html>");
-        let Some(new_pre) = dom::select_nodes(&fragment, "pre").into_iter().next() else {
+        let Some(new_pre) = dom::select_first(&fragment, "pre") else {
             continue;
         };
-        let Some(code) = dom::select_nodes(&new_pre, "code").into_iter().next() else {
+        let Some(code) = dom::select_first(&new_pre, "code") else {
             continue;
         };
         while let Some(child) = pre.first_child() {
@@ -111,7 +111,7 @@ fn remove_empty_wrappers(root: &NodeRef) {
         if dom::node_id(&node) == dom::node_id(root) {
             continue;
         }
-        let has_media = !dom::select_nodes(&node, "img, iframe, video, audio, object, embed, source").is_empty();
+        let has_media = dom::exists(&node, "img, iframe, video, audio, object, embed, source");
         if !has_media && dom::inner_text(&node).is_empty() {
             node.detach();
         }
@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use super::config::{Article, Preset, ReadabilityOptions, ReadableOptions};
+use super::error::{Error, Result};
+use super::markdown::markdown_with_toml_frontmatter;
+use super::rules::host_matches;
+
+/// Fetch, extraction, and output settings in one struct.
+///
+/// It deserializes from TOML or JSON, so one configuration file can drive the
+/// CLI's `--config` and a library caller the same way. Every section and field
+/// is optional and falls back to its default. Unknown sections and fields in
+/// `fetch` and `output` are rejected so typos do not pass silently. A top-level
+/// `preset` starts `extract` from [`ReadabilityOptions::preset`] instead of the
+/// defaults, and the `extract` fields in the file apply on top. `[[routes]]`
+/// give pages whose URL matches a pattern their own preset and `extract`
+/// fields; see [`Route`].
+///
+/// ```
+/// use lectito::{LectitoOptions, LinkStyle};
+///
+/// let options = LectitoOptions::from_toml(
+///     r#"
+///     [extract]
+///     char_threshold = 200
+///     link_style = "footnotes"
+///
+///     [output]
+///     frontmatter = false
+///     "#,
+/// )?;
+/// assert_eq!(options.extract.link_style, LinkStyle::Footnotes);
+/// # Ok::<(), lectito::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LectitoOptions {
+    /// Preset the extraction options started from, if any.
+    pub preset: Option<Preset>,
+    /// Settings for fetching pages, used by callers that fetch.
+    pub fetch: FetchOptions,
+    /// Full extraction options.
+    pub extract: ReadabilityOptions,
+    /// Options for the quick readability check.
+    pub readable: ReadableOptions,
+    /// How the article is written out.
+    pub output: OutputOptions,
+    /// Per-URL extraction options, checked in order by
+    /// [`LectitoOptions::extract_for`].
+    pub routes: Vec<Route>,
+}
+
+/// Extraction options for pages whose URL matches one of `urls`.
+///
+/// In a file, a route's `extract` starts from its `preset`, or the file's
+/// `preset` when it has none. The file's `extract` fields apply next and the
+/// route's own `extract` fields last:
+///
+/// ```toml
+/// [[routes]]
+/// urls = ["*.readthedocs.io", "docs.rs"]
+/// preset = "docs"
+///
+/// [[routes]]
+/// urls = ["news.ycombinator.com/item"]
+/// preset = "forum"
+/// extract = { char_threshold = 100 }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Route {
+    /// URL patterns: a host, optionally prefixed with `*.` to take its
+    /// subdomains too and followed by a path prefix, such as
+    /// `*.readthedocs.io` or `example.com/docs`. A leading `www.` is ignored.
+    pub urls: Vec<String>,
+    /// Preset the route's extraction options started from, if any.
+    pub preset: Option<Preset>,
+    /// Extraction options for matching pages.
+    pub extract: ReadabilityOptions,
+}
+
+impl Route {
+    /// Whether `url` matches one of the route's patterns.
+    pub fn matches(&self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        let Some(host) = url.host_str().map(|host| host.trim_start_matches("www.")) else {
+            return false;
+        };
+        self.urls.iter().any(|pattern| {
+            let (pattern_host, path) = match pattern.trim().split_once('/') {
+                Some((pattern_host, path)) => (pattern_host, path.trim_end_matches('/')),
+                None => (pattern.trim(), ""),
+            };
+            let matches_host = match pattern_host.strip_prefix("*.") {
+                Some(domain) => host_matches(domain, true, host),
+                None => host_matches(pattern_host, false, host),
+            };
+            matches_host
+                && url
+                    .path()
+                    .strip_prefix('/')
+                    .and_then(|url_path| url_path.strip_prefix(path))
+                    .is_some_and(|rest| path.is_empty() || rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+/// Page fetching settings.
+///
+/// Lectito does not fetch pages itself. The CLI applies these, and library
+/// callers can apply them to their own HTTP client.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FetchOptions {
+    /// `User-Agent` header for page requests. `None` keeps the fetcher's own.
+    pub user_agent: Option<String>,
+    /// Follow up to this many next-page links found by
+    /// [`crate::find_next_page`] and append each page to the article.
+    pub follow_pages: Option<usize>,
+    /// Seconds the CLI spends extracting a page before giving up, as its
+    /// `--timeout` flag does.
+    pub timeout: Option<u64>,
+}
+
+/// Output settings.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputOptions {
+    /// Output format name. [`LectitoOptions::render`] writes `markdown`,
+    /// `html`, `text`, and `json`, [`Article::save_with`] adds `epub`, and the
+    /// CLI accepts every `--format` value.
+    pub format: String,
+    /// Include TOML frontmatter in Markdown output.
+    pub frontmatter: bool,
+    /// Pretty-print JSON output.
+    pub pretty: bool,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self { format: "markdown".to_string(), frontmatter: true, pretty: false }
+    }
+}
+
+impl LectitoOptions {
+    /// Parse options from TOML and validate the extraction options.
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let value = toml::from_str(source).map_err(|error| Error::Config(error.message().to_string()))?;
+        Self::from_value(value)
+    }
+
+    /// Parse options from JSON and validate the extraction options.
+    pub fn from_json(source: &str) -> Result<Self> {
+        let value = serde_json::from_str(source).map_err(|error| Error::Config(error.to_string()))?;
+        Self::from_value(value)
+    }
+
+    /// Extraction options for a page: those of the first route matching
+    /// `url`, or `extract` when none does or there is no URL.
+    pub fn extract_for(&self, url: Option<&str>) -> &ReadabilityOptions {
+        url.and_then(|url| self.routes.iter().find(|route| route.matches(url)))
+            .map_or(&self.extract, |route| &route.extract)
+    }
+
+    /// Resolve presets into `extract` for the file and each route, then
+    /// deserialize and validate.
+    fn from_value(mut value: Value) -> Result<Self> {
+        if value.is_object() {
+            let preset = value.get("preset").cloned();
+            let fields = value.get("extract").cloned();
+            value["extract"] = resolve_extract(preset.as_ref(), [fields.as_ref()])?;
+            for route in value
+                .get_mut("routes")
+                .and_then(Value::as_array_mut)
+                .into_iter()
+                .flatten()
+                .filter(|route| route.is_object())
+            {
+                let route_preset = route.get("preset").or(preset.as_ref()).cloned();
+                let route_fields = route.get("extract").cloned();
+                route["extract"] = resolve_extract(route_preset.as_ref(), [fields.as_ref(), route_fields.as_ref()])?;
+            }
+        }
+        let options: Self = serde_json::from_value(value).map_err(|error| Error::Config(error.to_string()))?;
+        options.extract.validate()?;
+        for route in &options.routes {
+            route.extract.validate()?;
+        }
+        Ok(options)
+    }
+
+    /// Write `article` in the configured output format.
+    ///
+    /// `source` is the page URL recorded in Markdown frontmatter. Formats other
+    /// than `markdown`, `html`, `text`, and `json` are CLI renderers and fail
+    /// with [`Error::Config`].
+    pub fn render(&self, article: &Article, source: Option<&str>) -> Result<String> {
+        render(article, &self.output, source)
+    }
+}
+
+/// Start from the preset's options, or the defaults, and apply each table of
+/// `extract` fields in turn.
+fn resolve_extract<'a>(preset: Option<&Value>, layers: impl IntoIterator<Item = Option<&'a Value>>) -> Result<Value> {
+    let options = match preset {
+        Some(preset) => ReadabilityOptions::preset(
+            serde_json::from_value(preset.clone()).map_err(|error| Error::Config(format!("preset: {error}")))?,
+        ),
+        None => ReadabilityOptions::default(),
+    };
+    let mut extract = serde_json::to_value(options).map_err(|error| Error::Config(error.to_string()))?;
+    for layer in layers.into_iter().flatten() {
+        let fields = layer
+            .as_object()
+            .ok_or_else(|| Error::Config("extract must be a table".to_string()))?;
+        for (field, setting) in fields {
+            extract[field] = setting.clone();
+        }
+    }
+    Ok(extract)
+}
+
+pub(crate) fn render(article: &Article, output: &OutputOptions, source: Option<&str>) -> Result<String> {
+    match output.format.as_str() {
+        "markdown" if output.frontmatter => markdown_with_toml_frontmatter(article, source),
+        "markdown" => Ok(article.markdown.clone()),
+        "html" => Ok(article.content.clone()),
+        "text" => Ok(article.text_content.clone()),
+        "json" => match output.pretty {
+            true => serde_json::to_string_pretty(article),
+            false => serde_json::to_string(article),
+        }
+        .map_err(|error| Error::Config(error.to_string())),
+        other => Err(Error::Config(format!(
+            "output format {other} is not available here (expected markdown, html, text, or json)"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExtractionStrategy, extract};
+
+    #[test]
+    fn parses_partial_toml_and_json_with_defaults() {
+        let options = LectitoOptions::from_toml(
+            r#"
+            [fetch]
+            follow_pages = 3
+
+            [extract]
+            char_threshold = 0
+            strategy = "live-blog"
+
+            [output]
+            format = "text"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(options.fetch.follow_pages, Some(3));
+        assert_eq!(options.extract.char_threshold, 0);
+        assert_eq!(options.extract.strategy, ExtractionStrategy::LiveBlog);
+        assert_eq!(
+            options.extract.nb_top_candidates,
+            ReadabilityOptions::default().nb_top_candidates
+        );
+        assert!(options.output.frontmatter);
+        assert_eq!(
+            LectitoOptions::from_json(r#"{"fetch": {"follow_pages": 3}, "extract": {"char_threshold": 0, "strategy": "live-blog"}, "output": {"format": "text"}}"#).unwrap(),
+            options
+        );
+
+        let html = "<article><p>The tide comes in.</p></article>";
+        let article = extract(html, None, &options.extract).unwrap().unwrap();
+        assert_eq!(options.render(&article, None).unwrap(), article.text_content);
+
+        assert!(matches!(
+            LectitoOptions::from_toml("[output]\nfromat = \"html\""),
+            Err(Error::Config(message)) if message.contains("fromat")
+        ));
+        assert!(matches!(
+            LectitoOptions::from_toml("[extract]\nsibling_threshold = 2.0"),
+            Err(Error::InvalidOption { .. })
+        ));
+
+        let docs = LectitoOptions::from_toml("preset = \"docs\"\n[extract]\nchar_threshold = 300").unwrap();
+        assert_eq!(docs.preset, Some(Preset::Docs));
+        assert_eq!(
+            docs.extract,
+            ReadabilityOptions { char_threshold: 300, ..ReadabilityOptions::preset(Preset::Docs) }
+        );
+        assert!(matches!(
+            LectitoOptions::from_json(r#"{"preset": "blog"}"#),
+            Err(Error::Config(_))
+        ));
+
+        let gemtext = LectitoOptions {
+            output: OutputOptions { format: "gmi".to_string(), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(gemtext.render(&article, None).is_err());
+    }
+
+    #[test]
+    fn routes_pick_extraction_options_by_url() {
+        let options = LectitoOptions::from_toml(
+            r#"
+            [extract]
+            keep_hidden = true
+
+            [[routes]]
+            urls = ["*.readthedocs.io", "docs.rs"]
+            preset = "docs"
+
+            [[routes]]
+            urls = ["news.ycombinator.com/item"]
+            preset = "forum"
+            extract = { char_threshold = 100 }
+            "#,
+        )
+        .unwrap();
+
+        let docs = options.extract_for(Some("https://pip.readthedocs.io/en/stable/"));
+        assert_eq!(
+            docs,
+            &ReadabilityOptions { keep_hidden: true, ..ReadabilityOptions::preset(Preset::Docs) }
+        );
+        assert_eq!(options.extract_for(Some("https://readthedocs.io/")), docs);
+        assert_eq!(options.extract_for(Some("https://www.docs.rs/serde")), docs);
+        assert_eq!(
+            options.extract_for(Some("https://news.ycombinator.com/item?id=1")),
+            &ReadabilityOptions { keep_hidden: true, char_threshold: 100, ..ReadabilityOptions::preset(Preset::Forum) }
+        );
+        assert_eq!(
+            options.extract_for(Some("https://news.ycombinator.com/items")),
+            &options.extract
+        );
+        assert_eq!(options.extract_for(Some("https://example.com/")), &options.extract);
+        assert_eq!(options.extract_for(None), &options.extract);
+        assert_eq!(
+            options.extract,
+            ReadabilityOptions { keep_hidden: true, ..Default::default() }
+        );
+
+        assert!(matches!(
+            LectitoOptions::from_toml("[[routes]]\nurls = [\"a.com\"]\nextract = { sibling_threshold = 2.0 }"),
+            Err(Error::InvalidOption { .. })
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+use url::Url;
+
+use super::config::ReadabilityOptions;
+use super::error::{Error, Result};
+use super::regexes::RegexPattern;
+use super::{dom, recovery, rules};
+
+/// How far up a link may sit inside a pagination container.
+const MAX_CONTAINER_DEPTH: usize = 5;
+
+/// Query parameters that carry a page number, as in `?page=2`. Slideshows
+/// that put each slide on its own page number them the same way.
+const PAGE_PARAMS: &[&str] = &["page", "p", "pg", "paged", "pagina", "seite", "slide"];
+
+/// Path segments that precede a page number, as in `/page/2/`.
+const PAGE_SEGMENTS: &[&str] = &["page", "slide"];
+
+/// URL of the next page of a multi-page article.
+///
+/// When the site profile matching `base_url` has `next_page` selectors, only
+/// those are used, so a profile can also say a page has no next page. Other
+/// pages fall back to `rel="next"` links, then to links labelled like
+/// "Next page" or marked `next` inside pagination controls, and last to a
+/// link whose URL is this one with the page number raised by one, such as
+/// `?page=3` from `?page=2` or `/page/2/` from the first page. Only links to
+/// another page on the same host count. Returns `None` on the last page.
+///
+/// This lets callers that run their own fetch loop follow pagination without
+/// extracting every page first.
+pub fn find_next_page(html: &str, base_url: &str, options: &ReadabilityOptions) -> Result<Option<String>> {
+    let base = Url::parse(base_url).map_err(|_| Error::InvalidBaseUrl(base_url.to_string()))?;
+    let (html, _) = recovery::normalize_source(html);
+    let document = kuchiki::parse_html().one(html.as_ref());
+
+    let links = match rules::profile_next_page_links(&document, &base, options)? {
+        Some(links) => links,
+        None => heuristic_links(&document, &base),
+    };
+    Ok(links
+        .iter()
+        .find_map(|link| next_page_url(link, &base))
+        .map(String::from))
+}
+
+fn heuristic_links(document: &NodeRef, base: &Url) -> Vec<String> {
+    let rel_next = dom::select_iter(document, "link[rel][href], a[rel][href]").filter(|node| {
+        dom::attr(node, "rel").is_some_and(|rel| rel.split_whitespace().any(|rel| rel.eq_ignore_ascii_case("next")))
+    });
+    let links = dom::select_nodes(document, "a[href]");
+    let labelled = links.iter().filter(|link| is_next_link(link)).cloned();
+    let numbered = links.iter().filter(|link| is_numbered_next(link, base)).cloned();
+    rel_next
+        .chain(labelled)
+        .chain(numbered)
+        .filter_map(|node| dom::attr(&node, "href"))
+        .collect()
+}
+
+/// A link to this URL with the page number one higher. Pages without a
+/// number count as page one.
+fn is_numbered_next(link: &NodeRef, base: &Url) -> bool {
+    let Some(url) = dom::attr(link, "href").and_then(|href| base.join(&href).ok()) else {
+        return false;
+    };
+    let (stem, current) = page_position(base);
+    match page_position(&url) {
+        (link_stem, Some(page)) => link_stem == stem && page == current.unwrap_or(1) + 1,
+        _ => false,
+    }
+}
+
+/// A URL without its page number, and the page number if it has one. The
+/// number is read from a page query parameter or a `/page/N` or `/slide/N`
+/// path suffix.
+fn page_position(url: &Url) -> (String, Option<u32>) {
+    let mut stem = url.clone();
+    stem.set_fragment(None);
+
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let numbered = pairs.iter().position(|(key, value)| {
+        PAGE_PARAMS.contains(&key.to_ascii_lowercase().as_str()) && value.parse::<u32>().is_ok()
+    });
+    let mut page = None;
+    if let Some(index) = numbered {
+        page = pairs[index].1.parse().ok();
+        let rest = pairs
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| *position != index)
+            .map(|(_, pair)| pair);
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(rest)
+            .finish();
+        stem.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+
+    let path = url.path().trim_end_matches('/').to_string();
+    let mut path = path.as_str();
+    if page.is_none()
+        && let Some((prefix, number)) = path.rsplit_once('/')
+        && let Some((prefix, segment)) = prefix.rsplit_once('/')
+        && PAGE_SEGMENTS.contains(&segment)
+        && let Ok(number) = number.parse()
+    {
+        page = Some(number);
+        path = prefix;
+    }
+    let path = path.trim_end_matches('/').to_string();
+    stem.set_path(&path);
+    (stem.to_string(), page)
+}
+
+/// A link reading "Next", "Next page", or similar, or one marked `next` or
+/// showing only an arrow inside pagination controls. Links to the next
+/// article or post are not pages of this one.
+fn is_next_link(link: &NodeRef) -> bool {
+    let text = dom::inner_text(link);
+    let label = text.trim_matches(|c: char| c.is_whitespace() || "›»→>".contains(c));
+    if RegexPattern::NextArticleText.to_regex().is_match(&text) {
+        return false;
+    }
+    if RegexPattern::NextPageText.to_regex().is_match(label) {
+        return true;
+    }
+    let marked_next = [
+        dom::attr(link, "class"),
+        dom::attr(link, "id"),
+        dom::attr(link, "aria-label"),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|value| RegexPattern::NextPageMarker.to_regex().is_match(&value));
+    (marked_next || (label.is_empty() && !text.trim().is_empty())) && in_pagination(link)
+}
+
+fn in_pagination(link: &NodeRef) -> bool {
+    link.ancestors()
+        .filter(|node| node.as_element().is_some())
+        .take(MAX_CONTAINER_DEPTH)
+        .any(|node| {
+            RegexPattern::PaginationContainer
+                .to_regex()
+                .is_match(&dom::class_id_string(&node))
+        })
+}
+
+/// Resolve a candidate link, keeping it only if it is another page on the
+/// same host.
+fn next_page_url(link: &str, base: &Url) -> Option<Url> {
+    if link.starts_with('#') {
+        return None;
+    }
+    let mut url = base.join(link).ok()?;
+    url.set_fragment(None);
+    let mut current = base.clone();
+    current.set_fragment(None);
+    let same_host = url.host_str().map(|host| host.trim_start_matches("www."))
+        == current.host_str().map(|host| host.trim_start_matches("www."));
+    (matches!(url.scheme(), "http" | "https") && same_host && url != current).then_some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next_page(html: &str, options: &ReadabilityOptions) -> Option<String> {
+        find_next_page(html, "https://example.com/story?page=1", options).unwrap()
+    }
+
+    #[test]
+    fn finds_next_page_links() {
+        let options = ReadabilityOptions::default();
+
+        assert_eq!(
+            next_page(
+                r#"<head><link rel="next" href="/story?page=2"></head><body><p>Text</p></body>"#,
+                &options
+            )
+            .as_deref(),
+            Some("https://example.com/story?page=2")
+        );
+        assert_eq!(
+            next_page(
+                r#"<p>Text</p><a href="/elsewhere">Next article</a><a href="?page=2">Next page »</a>"#,
+                &options
+            )
+            .as_deref(),
+            Some("https://example.com/story?page=2")
+        );
+        assert_eq!(
+            next_page(
+                r#"<div class="pagination"><a href="?page=1">1</a><a class="next" href="?page=2">›</a></div>"#,
+                &options
+            )
+            .as_deref(),
+            Some("https://example.com/story?page=2")
+        );
+        assert_eq!(
+            next_page(
+                r##"<a href="https://other.example/story?page=2">Next</a><a href="#comments">Next</a>"##,
+                &options
+            ),
+            None
+        );
+        assert_eq!(
+            next_page(
+                r#"<nav class="post-navigation"><a class="next" href="/b">Next post</a></nav>"#,
+                &options
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_links_to_the_following_page_number() {
+        let options = ReadabilityOptions::default();
+        let pages = r#"<p>Text</p><a href="/story?page=3">3</a><a href="/story?page=2">2</a>"#;
+
+        assert_eq!(
+            next_page(pages, &options).as_deref(),
+            Some("https://example.com/story?page=2")
+        );
+        assert_eq!(
+            find_next_page(
+                r#"<a href="/story?ref=nav&amp;page=3">3</a>"#,
+                "https://example.com/story?page=2&ref=nav",
+                &options
+            )
+            .unwrap()
+            .as_deref(),
+            Some("https://example.com/story?ref=nav&page=3")
+        );
+        assert_eq!(
+            find_next_page(
+                r#"<a href="/2026/tides/page/3/">3</a><a href="/2026/tides/page/2/">2</a>"#,
+                "https://example.com/2026/tides/",
+                &options
+            )
+            .unwrap()
+            .as_deref(),
+            Some("https://example.com/2026/tides/page/2/")
+        );
+        assert_eq!(
+            find_next_page(
+                r#"<a href="?slide=3">3</a>"#,
+                "https://example.com/gallery?slide=2",
+                &options
+            )
+            .unwrap()
+            .as_deref(),
+            Some("https://example.com/gallery?slide=3")
+        );
+        assert_eq!(
+            find_next_page(
+                r#"<a href="/story?page=2">Older</a><a href="/other?page=2">2</a>"#,
+                "https://example.com/story?page=2",
+                &options
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn site_profile_next_page_selectors_replace_heuristics() {
+        let profile = r#"
+hosts = ["example.com"]
+content_roots = ["article"]
+next_page_link = ["//a[@class='more']/@href"]
+"#;
+        let options = ReadabilityOptions { site_profiles: vec![profile.to_string()], ..Default::default() };
+
+        assert_eq!(
+            next_page(
+                r#"<a rel="next" href="/wrong">Next</a><a class="more" href="/story?page=2">More</a>"#,
+                &options
+            )
+            .as_deref(),
+            Some("https://example.com/story?page=2")
+        );
+        assert_eq!(next_page(r#"<a rel="next" href="/wrong">Next</a>"#, &options), None);
+    }
+}
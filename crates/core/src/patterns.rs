@@ -1,4 +1,9 @@
-use super::regexes::RegexPattern;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::config::{PatternGroup, ReadabilityOptions};
+use super::regexes::{self, RegexPattern};
 
 pub const TAGS_TO_SCORE: &[&str] = &["section", "h2", "h3", "h4", "h5", "h6", "p", "td", "pre"];
 
@@ -6,7 +11,21 @@ pub const DEPRECATED_SIZE_ATTRIBUTE_ELEMS: &[&str] = &["table", "th", "td", "hr"
 
 pub const DEFAULT_CLASSES_TO_PRESERVE: &[&str] = &["page"];
 
-pub const TITLE_SEPARATORS: &[&str] = &[" | ", " - ", " – ", " — ", " \\ ", " / ", " > ", " » "];
+/// English function words that carry little meaning on their own, used by
+/// summaries and keyphrase extraction.
+pub const STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "also", "am", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "being", "below", "between", "both", "but", "by", "can", "could", "did", "do",
+    "does", "doing", "down", "during", "each", "few", "for", "from", "further", "had", "has", "have", "having", "he",
+    "her", "here", "hers", "him", "his", "how", "i", "if", "in", "into", "is", "it", "its", "just", "me", "more",
+    "most", "my", "no", "nor", "not", "now", "of", "off", "on", "once", "one", "only", "or", "other", "our", "out",
+    "over", "own", "said", "same", "she", "should", "so", "some", "such", "than", "that", "the", "their", "them",
+    "then", "there", "these", "they", "this", "those", "through", "to", "too", "under", "until", "up", "us", "very",
+    "was", "we", "were", "what", "when", "where", "which", "while", "who", "whom", "why", "will", "with", "would",
+    "you", "your",
+];
+
+pub const TITLE_SEPARATORS: &[&str] = &[" | ", " - ", " – ", " — ", " :: ", " \\ ", " / ", " > ", " » "];
 
 pub const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
     "align",
@@ -23,6 +42,18 @@ pub const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
     "vspace",
 ];
 
+/// "About the author" boxes and schema.org `Person` descriptions.
+pub const AUTHOR_BIO_SELECTOR: &str = r#"[class*="author-bio"], [class*="author_bio"], [class*="authorbio"], [class*="about-author"], [class*="about-the-author"], [class*="author-box"], [class*="bio-box"], [id*="author-bio"], [id*="about-author"], [id*="about-the-author"], [itemtype*="schema.org/Person"] [itemprop="description"]"#;
+
+/// Related articles, read-more, and recommendation modules.
+pub const RELATED_LINKS_SELECTOR: &str = r#"[class*="related"], [id*="related"], [class*="more-stories"], [class*="recommended"], [id*="recommended"], [class*="read-more"], [class*="also-read"], [class*="further-reading"], [class*="more-from"]"#;
+
+/// Author bio boxes longer than this are more likely article sections.
+pub const MAX_AUTHOR_BIO_CHARS: usize = 1200;
+
+/// Datelines after this much article text are part of the article.
+pub const MAX_DATELINE_OFFSET: usize = 200;
+
 pub fn normalize_spaces(text: &str) -> String {
     RegexPattern::NormalizeWhitespace
         .to_regex()
@@ -40,6 +71,100 @@ pub fn has_display_none(style: Option<&str>) -> bool {
         })
 }
 
+/// Whether `value` matches `group`: its built-in patterns, unless `options`
+/// replaces them, or a pattern `options` adds to it.
+pub fn matches_group(group: PatternGroup, value: &str, options: &ReadabilityOptions) -> bool {
+    let builtin = !options.replace_pattern_groups.contains(&group)
+        && match group {
+            PatternGroup::Unlikely => regexes::is_unlikely_candidate(value),
+            _ => group.regex().to_regex().is_match(value),
+        };
+    builtin || matches_extra(group, value, options)
+}
+
+/// Case-insensitive match against the patterns `options` adds to `group`:
+/// whole labels for doc chrome, substrings otherwise. The patterns must
+/// already be normalized by [`normalize_extra`].
+pub fn matches_extra(group: PatternGroup, value: &str, options: &ReadabilityOptions) -> bool {
+    let patterns = group.extra(options);
+    if patterns.is_empty() {
+        return false;
+    }
+    let value = value.trim().to_lowercase();
+    patterns.iter().any(|pattern| match group {
+        PatternGroup::DocChrome => value == *pattern,
+        _ => value.contains(pattern.as_str()),
+    })
+}
+
+/// `options` with the patterns added to each group trimmed and lowercased,
+/// and blank ones dropped, so matching does not redo it for every element.
+/// Extraction runs this once up front; options that are already normalized
+/// are borrowed.
+pub fn normalize_extra(options: &ReadabilityOptions) -> Cow<'_, ReadabilityOptions> {
+    let normalized = |pattern: &String| !pattern.is_empty() && *pattern == pattern.trim().to_lowercase();
+    if PatternGroup::ALL
+        .iter()
+        .all(|group| group.extra(options).iter().all(normalized))
+    {
+        return Cow::Borrowed(options);
+    }
+    let mut options = options.clone();
+    for patterns in [
+        &mut options.positive_patterns,
+        &mut options.negative_patterns,
+        &mut options.unlikely_patterns,
+        &mut options.trailing_chrome_patterns,
+        &mut options.doc_chrome_labels,
+    ] {
+        *patterns = patterns
+            .iter()
+            .map(|pattern| pattern.trim().to_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+    }
+    Cow::Owned(options)
+}
+
+thread_local! {
+    static SELECTOR_CACHE: RefCell<HashMap<String, scraper::Selector>> = RefCell::new(HashMap::new());
+}
+
+/// Parse an internal selector, reusing the compiled form on later calls.
+///
+/// Compiled selectors are reference counted, so the returned clone is cheap.
 pub fn selector(pattern: &str) -> scraper::Selector {
-    scraper::Selector::parse(pattern).expect("internal selector should parse")
+    SELECTOR_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .entry(pattern.to_string())
+            .or_insert_with(|| scraper::Selector::parse(pattern).expect("internal selector should parse"))
+            .clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_extra_patterns_once() {
+        let options = ReadabilityOptions {
+            negative_patterns: vec![" Promo-Well ".to_string(), "  ".to_string()],
+            doc_chrome_labels: vec!["Edit this page".to_string()],
+            ..Default::default()
+        };
+
+        let normalized = normalize_extra(&options);
+        assert!(matches!(normalized, Cow::Owned(_)));
+        assert_eq!(normalized.negative_patterns, ["promo-well"]);
+        assert!(matches_extra(PatternGroup::Negative, "sidebar PROMO-WELL", &normalized));
+        assert!(matches_extra(PatternGroup::DocChrome, " edit this PAGE ", &normalized));
+        assert!(!matches_extra(
+            PatternGroup::DocChrome,
+            "edit this page later",
+            &normalized
+        ));
+        assert!(matches!(normalize_extra(&normalized), Cow::Borrowed(_)));
+    }
 }
@@ -0,0 +1,51 @@
+use scraper::Html;
+
+use super::patterns;
+
+/// Class, id, and attribute markers left by common paywall and metering
+/// scripts.
+const PAYWALL_SELECTORS: &str = r#"
+    [class*="paywall" i], [id*="paywall" i], [data-paywall],
+    [class*="regwall" i], [id*="regwall" i],
+    .tp-modal, .tp-container-inner, #piano-offer, .piano-offer,
+    .subscriber-only, .subscribers-only, .premium-gate, .meteredContent
+"#;
+
+/// Endings that mark a teaser cut off before the article ends.
+const TRUNCATION_MARKERS: &[&str] = &["…", "...", "[…]", "[...]"];
+
+/// Whether the page carries DOM markers from a paywall or registration wall.
+pub fn has_paywall_markers(document: &Html) -> bool {
+    document.select(&patterns::selector(PAYWALL_SELECTORS)).next().is_some()
+}
+
+/// Whether extracted text stops mid-thought, as teaser excerpts behind a
+/// paywall usually do.
+pub fn looks_truncated(text: &str) -> bool {
+    let text = text.trim_end();
+    TRUNCATION_MARKERS.iter().any(|marker| text.ends_with(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::{has_paywall_markers, looks_truncated};
+
+    #[test]
+    fn detects_paywall_markers() {
+        let gated =
+            Html::parse_document(r#"<article><p>Teaser.</p><div class="article-Paywall-prompt"></div></article>"#);
+        let open = Html::parse_document(r#"<article><p>Full story.</p><div class="share-tools"></div></article>"#);
+
+        assert!(has_paywall_markers(&gated));
+        assert!(!has_paywall_markers(&open));
+    }
+
+    #[test]
+    fn detects_ellipsis_endings() {
+        assert!(looks_truncated("The council voted to approve the plan, but members…\n"));
+        assert!(looks_truncated("Officials said the bridge would reopen [...]"));
+        assert!(!looks_truncated("The bridge reopened on Monday."));
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+
+use kuchiki::NodeRef;
+
+use super::config::{Article, ReadabilityOptions};
+use super::diagnostics::ExtractionReport;
+use super::error::{Error, Result};
+use super::{dom, extract, serialize};
+
+/// The custom stages of one extraction, in the order they were added.
+pub(crate) type Stages<'a> = &'a [Box<dyn ExtractionStage>];
+
+/// A custom step run inside extraction by an [`Extractor`].
+///
+/// A stage hooks in at up to four points, in this order:
+///
+/// - `before` sees the page HTML before Lectito parses it.
+/// - `after_preprocess` sees each parsed copy of the page once Lectito has
+///   removed scripts, hidden elements, and other noise, and before it scores
+///   candidates or picks the content root. Remove markup such as a site's
+///   cookie banner here.
+/// - `before_postprocess` sees the report before Markdown post-processing
+///   adjusts headings, links, and the reading time.
+/// - `after` sees the finished report.
+///
+/// Every hook does nothing by default, so a stage implements only the ones it
+/// needs. Return [`Error::Stage`] to stop extraction.
+pub trait ExtractionStage: Send + Sync {
+    /// Rewrite the page HTML before extraction.
+    fn before(&self, html: String, _base_url: Option<&str>) -> Result<String> {
+        Ok(html)
+    }
+
+    /// Edit a preprocessed page before scoring. Extraction may parse the page
+    /// more than once, trying stricter attempts first, and this runs on every
+    /// copy, so it must give the same result each time.
+    fn after_preprocess(&self, _page: &Page<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Adjust the extraction result before Markdown post-processing.
+    fn before_postprocess(&self, _report: &mut ExtractionReport) -> Result<()> {
+        Ok(())
+    }
+
+    /// Adjust the extraction result. `after` stages that change
+    /// `article.content` also update `markdown` and `text_content` if they
+    /// need them to agree.
+    fn after(&self, _report: &mut ExtractionReport) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A parsed, preprocessed copy of the page, as
+/// [`ExtractionStage::after_preprocess`] sees it.
+pub struct Page<'a> {
+    root: &'a NodeRef,
+}
+
+impl Page<'_> {
+    /// Remove every element matching `selector` and return how many were
+    /// removed. Fails with [`Error::InvalidOption`] when the selector does not
+    /// parse.
+    pub fn remove(&self, selector: &str) -> Result<usize> {
+        let nodes = self.select(selector)?;
+        for node in &nodes {
+            node.detach();
+        }
+        Ok(nodes.len())
+    }
+
+    /// Count the elements matching `selector`.
+    pub fn count(&self, selector: &str) -> Result<usize> {
+        Ok(self.select(selector)?.len())
+    }
+
+    /// The page's visible text.
+    pub fn text(&self) -> String {
+        dom::inner_text(self.root)
+    }
+
+    /// The page serialized back to HTML.
+    pub fn html(&self) -> Result<String> {
+        serialize::serialize_node(self.root)
+    }
+
+    fn select(&self, selector: &str) -> Result<Vec<NodeRef>> {
+        validate_selector(selector)?;
+        Ok(dom::select_nodes(self.root, selector))
+    }
+}
+
+impl fmt::Debug for Page<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("Page").finish_non_exhaustive()
+    }
+}
+
+/// Run every stage's `after_preprocess` hook on `document`.
+pub(crate) fn after_preprocess(stages: Stages<'_>, document: &NodeRef) -> Result<()> {
+    let page = Page { root: document };
+    stages.iter().try_for_each(|stage| stage.after_preprocess(&page))
+}
+
+fn validate_selector(selector: &str) -> Result<()> {
+    match kuchiki::Selectors::compile(selector) {
+        Ok(_) => Ok(()),
+        Err(()) => Err(Error::invalid_option(
+            "selector",
+            format!("`{selector}` is not a valid CSS selector"),
+        )),
+    }
+}
+
+/// Extraction with custom stages hooked into the built-in pipeline.
+///
+/// At each hook point, stages run in the order they were added. See
+/// [`ExtractionStage`] for the hook points. Stages live here rather than on
+/// [`ReadabilityOptions`], which stays plain data that can be cloned,
+/// compared, and serialized.
+///
+/// ```
+/// use lectito::{Extractor, ReadabilityOptions, RemoveElements};
+///
+/// let extractor = Extractor::new(ReadabilityOptions { char_threshold: 0, ..Default::default() })
+///     .stage(RemoveElements::new("#cookie-banner")?)
+///     .stage_after(|article| article.keywords.retain(|keyword| keyword != "sponsored"));
+/// let html = r#"<div id="cookie-banner">We use cookies.</div><article><p>The tide comes in.</p></article>"#;
+/// let article = extractor.extract(html, None)?.unwrap();
+/// assert!(!article.text_content.contains("cookies"));
+/// # Ok::<(), lectito::Error>(())
+/// ```
+#[derive(Default)]
+pub struct Extractor {
+    options: ReadabilityOptions,
+    stages: Vec<Box<dyn ExtractionStage>>,
+}
+
+impl Extractor {
+    /// An extractor using `options` and no custom stages.
+    pub fn new(options: ReadabilityOptions) -> Self {
+        Self { options, stages: Vec::new() }
+    }
+
+    /// Add a stage.
+    pub fn stage(mut self, stage: impl ExtractionStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Add a stage that rewrites the page HTML before extraction.
+    pub fn stage_before(self, stage: impl Fn(String) -> String + Send + Sync + 'static) -> Self {
+        self.stage(Before(stage))
+    }
+
+    /// Add a stage that adjusts the extracted article. It is skipped when no
+    /// article was found.
+    pub fn stage_after(self, stage: impl Fn(&mut Article) + Send + Sync + 'static) -> Self {
+        self.stage(After(stage))
+    }
+
+    /// The options extraction runs with.
+    pub fn options(&self) -> &ReadabilityOptions {
+        &self.options
+    }
+
+    /// Run the stages and extraction, like [`crate::extract`].
+    pub fn extract(&self, html: &str, base_url: Option<&str>) -> Result<Option<Article>> {
+        Ok(self.extract_with_diagnostics(html, base_url)?.article)
+    }
+
+    /// Run the stages and extraction, like [`crate::extract_with_diagnostics`].
+    pub fn extract_with_diagnostics(&self, html: &str, base_url: Option<&str>) -> Result<ExtractionReport> {
+        let mut html = html.to_string();
+        for stage in &self.stages {
+            html = stage.before(html, base_url)?;
+        }
+        let mut report = extract::extract_with_stages(&html, base_url, &self.options, &self.stages)?;
+        for stage in &self.stages {
+            stage.after(&mut report)?;
+        }
+        Ok(report)
+    }
+}
+
+impl fmt::Debug for Extractor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Extractor")
+            .field("options", &self.options)
+            .field("stages", &self.stages.len())
+            .finish()
+    }
+}
+
+/// A stage that removes every element matching a CSS selector from the
+/// preprocessed page, before scoring.
+#[derive(Clone, Debug)]
+pub struct RemoveElements {
+    selector: String,
+}
+
+impl RemoveElements {
+    /// Remove elements matching `selector`. Fails with [`Error::InvalidOption`]
+    /// when the selector does not parse.
+    pub fn new(selector: impl Into<String>) -> Result<Self> {
+        let selector = selector.into();
+        validate_selector(&selector)?;
+        Ok(Self { selector })
+    }
+}
+
+impl ExtractionStage for RemoveElements {
+    fn after_preprocess(&self, page: &Page<'_>) -> Result<()> {
+        page.remove(&self.selector).map(drop)
+    }
+}
+
+struct Before<F>(F);
+
+impl<F: Fn(String) -> String + Send + Sync> ExtractionStage for Before<F> {
+    fn before(&self, html: String, _base_url: Option<&str>) -> Result<String> {
+        Ok((self.0)(html))
+    }
+}
+
+struct After<F>(F);
+
+impl<F: Fn(&mut Article) + Send + Sync> ExtractionStage for After<F> {
+    fn after(&self, report: &mut ExtractionReport) -> Result<()> {
+        if let Some(article) = report.article.as_mut() {
+            (self.0)(article);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Reject;
+
+    impl ExtractionStage for Reject {
+        fn after(&self, report: &mut ExtractionReport) -> Result<()> {
+            match report.article.as_ref().is_some_and(|article| article.length < 10) {
+                true => Err(Error::Stage("article too short".to_string())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn stages_run_in_order_around_extraction() {
+        let html = r#"<body><div class="consent">Accept all cookies</div><article><p>The tide comes in twice a day.</p></article></body>"#;
+        let extractor = Extractor::new(ReadabilityOptions { char_threshold: 0, ..Default::default() })
+            .stage(RemoveElements::new(".consent").unwrap())
+            .stage_before(|html| html.replace("twice", "two times"))
+            .stage_after(|article| article.title = Some("Tides".to_string()));
+
+        let article = extractor.extract(html, None).unwrap().unwrap();
+        assert!(!article.content.contains("cookies"));
+        assert!(article.text_content.contains("two times a day"));
+        assert_eq!(article.title.as_deref(), Some("Tides"));
+
+        let strict = Extractor::new(extractor.options().clone()).stage(Reject);
+        assert!(matches!(
+            strict.extract("<article><p>Short.</p></article>", None),
+            Err(Error::Stage(message)) if message == "article too short"
+        ));
+        assert!(matches!(
+            RemoveElements::new("div >"),
+            Err(Error::InvalidOption { option, .. }) if option == "selector"
+        ));
+    }
+
+    struct Inner;
+
+    impl ExtractionStage for Inner {
+        fn after_preprocess(&self, page: &Page<'_>) -> Result<()> {
+            if page.count("script")? > 0 {
+                return Err(Error::Stage("scripts survived preprocessing".to_string()));
+            }
+            page.remove("aside").map(drop)
+        }
+
+        fn before_postprocess(&self, report: &mut ExtractionReport) -> Result<()> {
+            if let Some(article) = report.article.as_mut() {
+                article.markdown.insert_str(0, "# Tides\n\n");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn inner_hooks_see_the_preprocessed_page_and_the_report_before_postprocessing() {
+        let html = r#"<body><script>track()</script><article><p>The tide comes in twice a day.</p><aside>Related: moon phases</aside></article></body>"#;
+        let options = ReadabilityOptions { char_threshold: 0, heading_offset: Some(1), ..Default::default() };
+        let extractor = Extractor::new(options).stage(Inner);
+
+        let article = extractor.extract(html, None).unwrap().unwrap();
+        assert!(!article.text_content.contains("moon phases"));
+        assert!(article.markdown.starts_with("## Tides\n"), "{}", article.markdown);
+        assert!(matches!(
+            Page { root: &NodeRef::new_text("") }.remove("div >"),
+            Err(Error::InvalidOption { option, .. }) if option == "selector"
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use super::config::{FootnoteMode, LinkStyle, MediaRetention, Preset, ReadabilityOptions, Typography};
+
+impl ReadabilityOptions {
+    /// Options tuned for one kind of page.
+    ///
+    /// Each preset starts from the defaults and changes only what its pages
+    /// need:
+    ///
+    /// - [`Preset::News`] adds negative patterns for newsletter signups,
+    ///   recirculation rails, and most-read lists.
+    /// - [`Preset::Docs`] lowers `char_threshold` to 200, tolerates link-heavy
+    ///   blocks, merges sibling sections more readily, and scores documentation
+    ///   containers up and tables of contents, breadcrumbs, and version
+    ///   switchers down.
+    /// - [`Preset::Forum`] keeps comment and reply containers, lowers
+    ///   `char_threshold` to 200 and `class_weight` to 10, keeps more
+    ///   candidates, merges sibling posts readily, and scores signatures and
+    ///   user cards down.
+    /// - [`Preset::Minimal`] drops media and footnotes, moves links to
+    ///   reference definitions, normalizes whitespace, and uses plain ASCII
+    ///   punctuation.
+    ///
+    /// Change fields afterwards with struct update syntax or
+    /// [`crate::ReadabilityOptionsBuilder::from`].
+    ///
+    /// ```
+    /// use lectito::{Preset, ReadabilityOptions};
+    ///
+    /// let options = ReadabilityOptions { keep_hidden: true, ..ReadabilityOptions::preset(Preset::Docs) };
+    /// assert_eq!(options.char_threshold, 200);
+    /// ```
+    pub fn preset(preset: Preset) -> Self {
+        let patterns = |patterns: &[&str]| patterns.iter().map(|pattern| pattern.to_string()).collect();
+        match preset {
+            Preset::News => Self {
+                negative_patterns: patterns(&[
+                    "newsletter",
+                    "subscribe",
+                    "recirc",
+                    "most-read",
+                    "most-popular",
+                    "trending",
+                ]),
+                ..Default::default()
+            },
+            Preset::Docs => Self {
+                char_threshold: 200,
+                link_density_modifier: 0.2,
+                sibling_threshold: 0.1,
+                positive_patterns: patterns(&["doc", "markdown", "prose", "reference"]),
+                negative_patterns: patterns(&[
+                    "table-of-contents",
+                    "breadcrumb",
+                    "edit-this-page",
+                    "navbar",
+                    "version-switcher",
+                ]),
+                ..Default::default()
+            },
+            Preset::Forum => Self {
+                nb_top_candidates: 10,
+                char_threshold: 200,
+                keep_unlikely_candidates: true,
+                sibling_threshold: 0.05,
+                class_weight: 10,
+                positive_patterns: patterns(&["message", "reply", "topic", "thread"]),
+                negative_patterns: patterns(&["signature", "user-info", "avatar", "vote"]),
+                ..Default::default()
+            },
+            Preset::Minimal => Self {
+                media_retention: MediaRetention::None,
+                footnotes: FootnoteMode::Strip,
+                link_style: LinkStyle::Reference,
+                normalize_text: true,
+                typography: Typography::Plain,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid_and_differ_from_defaults() {
+        for preset in [Preset::News, Preset::Docs, Preset::Forum, Preset::Minimal] {
+            let options = ReadabilityOptions::preset(preset);
+            assert!(options.validate().is_ok(), "{preset} preset is invalid");
+            assert_ne!(
+                options,
+                ReadabilityOptions::default(),
+                "{preset} preset changes nothing"
+            );
+            assert_eq!(preset.as_str().parse::<Preset>(), Ok(preset));
+        }
+
+        let html = "<article><p>First paragraph with enough words to read.</p><figure><img src=\"/a.png\" alt=\"A\"></figure></article>";
+        let options = ReadabilityOptions { char_threshold: 0, ..ReadabilityOptions::preset(Preset::Minimal) };
+        let article = crate::extract(html, Some("https://example.com/"), &options)
+            .unwrap()
+            .unwrap();
+        assert!(!article.content.contains("<img"));
+    }
+}
@@ -1,17 +1,26 @@
+use std::cell::RefCell;
 use std::collections::HashSet;
 
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, CharacterTokens, EndTag, StartTag, Tag, TagToken, Token, TokenSink, TokenSinkResult, Tokenizer,
+    TokenizerOpts,
+};
+use html5ever::{LocalName, local_name};
 use scraper::{ElementRef, Html};
 
-use super::patterns;
-use super::regexes::RegexPattern;
+use super::regexes;
 use super::{config::ReadableOptions, error::Result};
+use super::{patterns, recovery};
 
 /// Return a quick estimate of whether an HTML document contains article text.
 ///
 /// This check does not run full extraction and does not return article content.
 /// Use it to filter batches of pages before calling [`crate::extract`].
 pub fn is_probably_readable(html: &str, options: &ReadableOptions) -> Result<bool> {
-    let document = Html::parse_document(html);
+    let (html, _) = recovery::normalize_source(html);
+    let document = Html::parse_document(&html);
 
     let text_selector = patterns::selector("p, pre, article");
     let br_selector = patterns::selector("div > br");
@@ -50,9 +59,7 @@ pub fn is_probably_readable(html: &str, options: &ReadableOptions) -> Result<boo
             node.value().attr("class").unwrap_or_default(),
             node.value().attr("id").unwrap_or_default()
         );
-        if RegexPattern::UnlikelyCandidates.to_regex().is_match(&match_string)
-            && !RegexPattern::MaybeCandidate.to_regex().is_match(&match_string)
-        {
+        if regexes::is_unlikely_candidate(&match_string) {
             continue;
         }
 
@@ -86,6 +93,333 @@ fn is_node_visible(node: &ElementRef<'_>) -> bool {
         && element.attr("aria-hidden") != Some("true")
 }
 
+/// Bytes handed to the tokenizer between checks for an early answer.
+const STREAM_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Readability signals gathered in one streaming pass over a document.
+///
+/// Returned by [`readable_signals`] so crawlers can apply their own triage
+/// policy on top of the paragraph score.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ReadableSignals {
+    /// Paragraph score, computed as in [`is_probably_readable`].
+    pub score: f32,
+    /// Number of text blocks long enough to add to `score`.
+    pub paragraphs: usize,
+    /// Bytes of trimmed text outside scripts, styles, and other raw-text elements.
+    pub text_bytes: usize,
+    /// Share of the markup that is text, from 0.0 to 1.0.
+    pub text_ratio: f32,
+    /// Whether the page has an `<article>` element or an `og:type` of `article`.
+    pub article: bool,
+}
+
+/// Return a streaming estimate of whether an HTML document contains article text.
+///
+/// This gives the same answer as [`is_probably_readable`] on well-formed pages
+/// at a fraction of the cost. It tokenizes the HTML without building a tree and
+/// stops as soon as the score passes `min_score`. It skips the source repairs
+/// the full check makes, so the two can disagree on broken markup.
+pub fn is_probably_readable_fast(html: &str, options: &ReadableOptions) -> Result<bool> {
+    Ok(stream(html, options, true).readable)
+}
+
+/// Scan a whole HTML document once and report the signals behind the
+/// readability checks.
+pub fn readable_signals(html: &str, options: &ReadableOptions) -> Result<ReadableSignals> {
+    let scan = stream(html, options, false);
+    Ok(ReadableSignals {
+        score: scan.score,
+        paragraphs: scan.paragraphs,
+        text_bytes: scan.text_bytes,
+        text_ratio: scan.text_bytes as f32 / html.len().max(1) as f32,
+        article: scan.article,
+    })
+}
+
+/// Tokenize `html` in chunks, stopping early once it is readable when
+/// `stop_early` is set.
+fn stream(html: &str, options: &ReadableOptions, stop_early: bool) -> ReadableScan {
+    let tokenizer = Tokenizer::new(ReadableSink::new(options), TokenizerOpts::default());
+    let input = BufferQueue::default();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(STREAM_CHUNK_BYTES);
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+
+        input.push_back(StrTendril::from_slice(chunk));
+        let _ = tokenizer.feed(&input);
+        if stop_early && tokenizer.sink.scan.borrow().readable {
+            break;
+        }
+    }
+    tokenizer.end();
+    tokenizer.sink.scan.take()
+}
+
+/// Token sink that scores text blocks as their end tags arrive.
+struct ReadableSink<'a> {
+    options: &'a ReadableOptions,
+    scan: RefCell<ReadableScan>,
+}
+
+#[derive(Default)]
+struct ReadableScan {
+    open: Vec<OpenElement>,
+    /// Elements from this index up have not seen any non-whitespace text yet.
+    untouched_from: usize,
+    /// UTF-16 length of all text seen so far.
+    text_length: usize,
+    /// Value of `text_length` just after the last non-whitespace character.
+    trimmed_end: usize,
+    text_bytes: usize,
+    list_items: usize,
+    paragraphs: usize,
+    in_raw_text: bool,
+    score: f32,
+    article: bool,
+    readable: bool,
+}
+
+struct OpenElement {
+    name: LocalName,
+    /// Whether the element counts as a text block, and passes the same
+    /// visibility, class, and list checks as [`is_probably_readable`].
+    block: Option<bool>,
+    /// Whether the element itself is visible and not an unlikely candidate.
+    eligible: bool,
+    text_start: Option<usize>,
+}
+
+impl<'a> ReadableSink<'a> {
+    fn new(options: &'a ReadableOptions) -> Self {
+        Self { options, scan: RefCell::default() }
+    }
+
+    fn start_tag(&self, scan: &mut ReadableScan, tag: &Tag) -> TokenSinkResult<()> {
+        let name = &tag.name;
+        if closes_paragraph(name)
+            && let Some(index) = scan.open.iter().rposition(|element| element.name == local_name!("p"))
+        {
+            self.close_to(scan, index);
+        }
+        if *name == local_name!("li")
+            && let Some(index) = scan
+                .open
+                .iter()
+                .rposition(|element| matches!(element.name, local_name!("li") | local_name!("ul") | local_name!("ol")))
+            && scan.open[index].name == local_name!("li")
+        {
+            self.close_to(scan, index);
+        }
+
+        match *name {
+            local_name!("article") => scan.article = true,
+            local_name!("meta") => {
+                if attr(tag, "property").is_some_and(|value| value.eq_ignore_ascii_case("og:type"))
+                    && attr(tag, "content").is_some_and(|value| value.trim().eq_ignore_ascii_case("article"))
+                {
+                    scan.article = true;
+                }
+            }
+            local_name!("br") => {
+                if let Some(parent) = scan.open.last_mut()
+                    && parent.name == local_name!("div")
+                {
+                    parent.block = parent.block.or(Some(parent.eligible));
+                }
+            }
+            _ => {}
+        }
+
+        let raw = match *name {
+            local_name!("script") => Some(RawKind::ScriptData),
+            local_name!("style") | local_name!("noscript") | local_name!("iframe") | local_name!("xmp") => {
+                Some(RawKind::Rawtext)
+            }
+            local_name!("title") | local_name!("textarea") => Some(RawKind::Rcdata),
+            _ => None,
+        };
+        if let Some(kind) = raw {
+            scan.in_raw_text = true;
+            return TokenSinkResult::RawData(kind);
+        }
+        if tag.self_closing || is_void(name) {
+            return TokenSinkResult::Continue;
+        }
+
+        let eligible = matches!(
+            *name,
+            local_name!("p") | local_name!("pre") | local_name!("article") | local_name!("div")
+        ) && is_tag_eligible(tag);
+        let block = match *name {
+            local_name!("p") => Some(eligible && scan.list_items == 0),
+            local_name!("pre") | local_name!("article") => Some(eligible),
+            _ => None,
+        };
+        if *name == local_name!("li") {
+            scan.list_items += 1;
+        }
+        scan.open
+            .push(OpenElement { name: name.clone(), block, eligible, text_start: None });
+        TokenSinkResult::Continue
+    }
+
+    fn end_tag(&self, scan: &mut ReadableScan, tag: &Tag) {
+        // Raw text only ends at its own end tag.
+        if scan.in_raw_text {
+            scan.in_raw_text = false;
+            return;
+        }
+        if let Some(index) = scan.open.iter().rposition(|element| element.name == tag.name) {
+            self.close_to(scan, index);
+        }
+    }
+
+    /// Close the element at `index` and everything opened inside it.
+    fn close_to(&self, scan: &mut ReadableScan, index: usize) {
+        while scan.open.len() > index {
+            let Some(element) = scan.open.pop() else { break };
+            if element.name == local_name!("li") {
+                scan.list_items -= 1;
+            }
+            if element.block != Some(true) {
+                continue;
+            }
+
+            let text_length = element
+                .text_start
+                .map_or(0, |start| scan.trimmed_end.saturating_sub(start));
+            if text_length < self.options.min_content_length {
+                continue;
+            }
+            scan.paragraphs += 1;
+            scan.score += ((text_length - self.options.min_content_length) as f32).sqrt();
+            if scan.score > self.options.min_score {
+                scan.readable = true;
+            }
+        }
+        scan.untouched_from = scan.untouched_from.min(scan.open.len());
+    }
+
+    fn text(&self, scan: &mut ReadableScan, text: &str) {
+        // Like `textContent`, block lengths include script and style text.
+        if !scan.in_raw_text {
+            scan.text_bytes += text.trim().len();
+        }
+        for character in text.chars() {
+            if !character.is_whitespace() {
+                let position = scan.text_length;
+                for element in &mut scan.open[scan.untouched_from..] {
+                    element.text_start = Some(position);
+                }
+                scan.untouched_from = scan.open.len();
+                scan.trimmed_end = position + character.len_utf16();
+            }
+            scan.text_length += character.len_utf16();
+        }
+    }
+}
+
+impl TokenSink for ReadableSink<'_> {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let mut scan = self.scan.borrow_mut();
+        match token {
+            TagToken(tag) if tag.kind == StartTag => return self.start_tag(&mut scan, &tag),
+            TagToken(tag) if tag.kind == EndTag => self.end_tag(&mut scan, &tag),
+            CharacterTokens(text) => self.text(&mut scan, &text),
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// Return the value of attribute `name` on `tag`.
+fn attr<'t>(tag: &'t Tag, name: &str) -> Option<&'t str> {
+    tag.attrs
+        .iter()
+        .find(|attribute| &*attribute.name.local == name)
+        .map(|attribute| &*attribute.value)
+}
+
+/// Apply the visibility and unlikely-candidate checks of [`is_probably_readable`] to a start tag.
+fn is_tag_eligible(tag: &Tag) -> bool {
+    let classes = attr(tag, "class").unwrap_or_default();
+    let fallback_image = classes
+        .split_ascii_whitespace()
+        .any(|class| class.eq_ignore_ascii_case("fallback-image"));
+    let visible = attr(tag, "hidden").is_none()
+        && !patterns::has_display_none(attr(tag, "style"))
+        && (fallback_image || attr(tag, "aria-hidden") != Some("true"));
+
+    visible && !regexes::is_unlikely_candidate(&format!("{} {}", classes, attr(tag, "id").unwrap_or_default()))
+}
+
+/// Start tags that implicitly close an open `<p>`.
+fn closes_paragraph(name: &LocalName) -> bool {
+    matches!(
+        *name,
+        local_name!("address")
+            | local_name!("article")
+            | local_name!("aside")
+            | local_name!("blockquote")
+            | local_name!("details")
+            | local_name!("div")
+            | local_name!("dl")
+            | local_name!("fieldset")
+            | local_name!("figcaption")
+            | local_name!("figure")
+            | local_name!("footer")
+            | local_name!("form")
+            | local_name!("h1")
+            | local_name!("h2")
+            | local_name!("h3")
+            | local_name!("h4")
+            | local_name!("h5")
+            | local_name!("h6")
+            | local_name!("header")
+            | local_name!("hgroup")
+            | local_name!("hr")
+            | local_name!("main")
+            | local_name!("menu")
+            | local_name!("nav")
+            | local_name!("ol")
+            | local_name!("p")
+            | local_name!("pre")
+            | local_name!("section")
+            | local_name!("table")
+            | local_name!("ul")
+    )
+}
+
+fn is_void(name: &LocalName) -> bool {
+    matches!(
+        *name,
+        local_name!("area")
+            | local_name!("base")
+            | local_name!("br")
+            | local_name!("col")
+            | local_name!("embed")
+            | local_name!("hr")
+            | local_name!("img")
+            | local_name!("input")
+            | local_name!("link")
+            | local_name!("meta")
+            | local_name!("param")
+            | local_name!("source")
+            | local_name!("track")
+            | local_name!("wbr")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
@@ -159,6 +493,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fast_check_applies_the_same_filters() {
+        let options = ReadableOptions { min_content_length: 0, min_score: 0.0 };
+        let docs = [
+            (r#"<html><p id="main">this paragraph is long enough</p></html>"#, true),
+            (
+                r#"<html><div>this line is long enough<br>and so is this</div></html>"#,
+                true,
+            ),
+            (
+                r#"<html><p>short<script>var padding = "script text counts";</script></p></html>"#,
+                true,
+            ),
+            (r#"<html><p hidden>this paragraph is long enough</p></html>"#, false),
+            (
+                r#"<html><p style="display: none">this paragraph is long enough</p></html>"#,
+                false,
+            ),
+            (
+                r#"<html><p class="comment">this paragraph is long enough</p></html>"#,
+                false,
+            ),
+            (r#"<html><li><p>this paragraph is long enough</p></li></html>"#, false),
+            (r#"<html><div>this div has no line breaks</div></html>"#, false),
+        ];
+
+        for (doc, expected) in docs {
+            assert_eq!(is_probably_readable(doc, &options).unwrap(), expected, "{doc}");
+            assert_eq!(is_probably_readable_fast(doc, &options).unwrap(), expected, "{doc}");
+        }
+    }
+
+    #[test]
+    fn signals_report_blocks_article_and_text_share() {
+        let doc = format!(
+            r#"<html><head><meta property="og:type" content="article"><style>p {{ margin: 0 }}</style></head>
+            <body><p>{}</p><p>{}</p><p>too short</p></body></html>"#,
+            "hello there ".repeat(20),
+            "hello there ".repeat(30),
+        );
+
+        let signals = readable_signals(&doc, &ReadableOptions::default()).unwrap();
+
+        assert!(signals.article);
+        assert_eq!(signals.paragraphs, 2);
+        assert!(signals.score > ReadableOptions::default().min_score);
+        assert_eq!(
+            signals.text_bytes,
+            "hello there ".repeat(50).len() - 2 + "too short".len()
+        );
+        assert!(signals.text_ratio > 0.5 && signals.text_ratio < 1.0);
+        assert!(
+            !readable_signals("<html><p>hi</p></html>", &ReadableOptions::default())
+                .unwrap()
+                .article
+        );
+    }
+
+    #[test]
+    fn fast_check_matches_upstream_fixture_metadata() {
+        let mut mismatches = Vec::new();
+
+        for fixture in lectito_fixtures::load_all().unwrap() {
+            let metadata: ExpectedMetadata = serde_json::from_value(fixture.expected_metadata).unwrap();
+            let actual = is_probably_readable_fast(&fixture.source, &ReadableOptions::default()).unwrap();
+            if actual != metadata.readerable {
+                mismatches.push(fixture.name);
+            }
+        }
+
+        assert!(
+            mismatches.is_empty(),
+            "fast readable fixture mismatches: {mismatches:?}"
+        );
+    }
+
     #[test]
     fn matches_upstream_fixture_metadata() {
         let mut checked = 0;
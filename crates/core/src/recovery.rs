@@ -1,55 +1,546 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use base64::Engine;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, CharacterTokens, CommentToken, Doctype, DoctypeToken, EOFToken, NullCharacterToken, ParseError,
+    StartTag, Tag, TagToken, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use html5ever::{LocalName, local_name};
 use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+use scraper::Html;
 
 use super::diagnostics::RecoveryDiagnostic;
 use super::regexes::RegexPattern;
-use super::{dom, patterns};
+use super::{dom, patterns, serialize, shared};
 
+/// Repair the source and flatten declarative shadow roots into their hosts.
+///
+/// Shadow roots are flattened on the parsed DOM and the page serialized
+/// again, so nested templates and attributes holding `>` survive. Pages
+/// without a shadow root attribute are returned as they were.
 pub fn recover_html_snapshot(html: &str) -> (String, RecoveryDiagnostic) {
+    let (html, payload_decoded) = normalize_source(html);
     let mut flattened = 0;
-    let html = RegexPattern::ShadowTemplateHtml
-        .to_regex()
-        .replace_all(html, |captures: &regex::Captures<'_>| {
-            flattened += 1;
-            captures
-                .name("body")
-                .map(|body| body.as_str())
-                .unwrap_or_default()
-                .to_string()
-        })
-        .into_owned();
+    let html = match RegexPattern::ShadowRootAttribute.to_regex().is_match(&html) {
+        true => {
+            let document = kuchiki::parse_html().one(html.as_ref());
+            flattened = flatten_declarative_shadow_dom(&document);
+            match flattened {
+                0 => html.into_owned(),
+                _ => serialize::serialize_node(&document).unwrap_or_else(|_| html.into_owned()),
+            }
+        }
+        false => html.into_owned(),
+    };
     (
         html,
-        RecoveryDiagnostic { shadow_roots_flattened: flattened, mobile_rules_applied: 0 },
+        RecoveryDiagnostic {
+            shadow_roots_flattened: flattened,
+            mobile_rules_applied: 0,
+            payloads_decoded: usize::from(payload_decoded),
+            galleries_flattened: 0,
+        },
     )
 }
 
+/// Source-level repairs that must happen before any parse.
+///
+/// Strips stray bytes, decodes inline-encoded payloads, rewrites XHTML syntax,
+/// and drops output printed before the document starts. Also reports whether
+/// a payload was decoded.
+pub fn normalize_source(html: &str) -> (Cow<'_, str>, bool) {
+    let html = strip_stray_characters(html);
+    let (html, payload_decoded) = match decode_inline_payload(&html) {
+        Some(decoded) => (Cow::Owned(strip_stray_characters(&decoded).into_owned()), true),
+        None => (html, false),
+    };
+    let html = and_then(html, normalize_xhtml);
+    (and_then(html, strip_leading_garbage), payload_decoded)
+}
+
+fn and_then<'a>(html: Cow<'a, str>, repair: impl FnOnce(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match html {
+        Cow::Borrowed(html) => repair(html),
+        Cow::Owned(html) => Cow::Owned(repair(&html).into_owned()),
+    }
+}
+
+/// Remove byte-order marks, leading whitespace, and control characters the
+/// HTML parser would keep as text.
+fn strip_stray_characters(html: &str) -> Cow<'_, str> {
+    let html = html.trim_start_matches(|c: char| c == '\u{FEFF}' || c.is_whitespace());
+    RegexPattern::StrayCharacters.to_regex().replace_all(html, "")
+}
+
+/// Drop output printed before `<!DOCTYPE>` or `<html>`, such as PHP warnings
+/// or debug dumps. Without this the parser starts the body early, so the
+/// stray text becomes article content and the page parses in quirks mode.
+fn strip_leading_garbage(html: &str) -> Cow<'_, str> {
+    let Some(start) = RegexPattern::DocumentStart.to_regex().find(html) else {
+        return Cow::Borrowed(html);
+    };
+    let prefix = &html[..start.start()];
+    // Conditional comments can hold an alternate `<html>` tag, and a match
+    // after document structure or inside a script is not where the page starts.
+    let is_doctype = start.as_str().starts_with("<!");
+    if prefix.trim().is_empty()
+        || (!is_doctype && prefix.contains("<!--"))
+        || RegexPattern::DocumentStructureTag.to_regex().is_match(prefix)
+    {
+        return Cow::Borrowed(html);
+    }
+    Cow::Borrowed(&html[start.start()..])
+}
+
+/// Base64 runs shorter than this are treated as ordinary text.
+const MIN_INLINE_PAYLOAD_LEN: usize = 64;
+
+const BASE64_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const BASE64_STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, BASE64_CONFIG);
+const BASE64_URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, BASE64_CONFIG);
+
+/// Decode a page that arrives as a single encoded blob.
+///
+/// Handles input that is itself a `data:` URI, a body holding nothing but a
+/// frame, object, or embed pointing at a `data:text/html` URI, and a body whose
+/// only text is base64-encoded markup, as some saved-page formats and email
+/// clients produce. Returns `None` for ordinary pages, so the encoded text is
+/// never scored as content.
+pub fn decode_inline_payload(html: &str) -> Option<String> {
+    let trimmed = html.trim_start_matches('\u{FEFF}').trim();
+    if trimmed
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+    {
+        return decode_data_uri(trimmed);
+    }
+    if !RegexPattern::InlinePayloadHint.to_regex().is_match(html) {
+        return None;
+    }
+
+    let document = Html::parse_document(html);
+    let body = document.select(&patterns::selector("body")).next()?;
+    let text: String = body
+        .text()
+        .flat_map(str::chars)
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if text.is_empty() {
+        return body
+            .select(&patterns::selector("iframe[src], frame[src], embed[src], object[data]"))
+            .filter_map(|element| element.value().attr("src").or_else(|| element.value().attr("data")))
+            .find_map(decode_data_uri);
+    }
+    if text.len() < MIN_INLINE_PAYLOAD_LEN {
+        return None;
+    }
+    decode_base64(&text).filter(|decoded| looks_like_markup(decoded))
+}
+
+/// Decode a `data:` URI whose media type is HTML or XHTML.
+fn decode_data_uri(uri: &str) -> Option<String> {
+    let (header, data) = uri.trim().get(5..)?.split_once(',')?;
+    let mut params = header.split(';').map(str::trim);
+    let media_type = params.next().unwrap_or_default();
+    if !media_type.eq_ignore_ascii_case("text/html") && !media_type.eq_ignore_ascii_case("application/xhtml+xml") {
+        return None;
+    }
+    let data = percent_encoding::percent_decode_str(data).decode_utf8().ok()?;
+    if params.any(|param| param.eq_ignore_ascii_case("base64")) {
+        let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        decode_base64(&data)
+    } else {
+        Some(data.into_owned())
+    }
+}
+
+fn decode_base64(data: &str) -> Option<String> {
+    let bytes = BASE64_STANDARD
+        .decode(data)
+        .or_else(|_| BASE64_URL_SAFE.decode(data))
+        .ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn looks_like_markup(text: &str) -> bool {
+    RegexPattern::MarkupTag.to_regex().is_match(text)
+}
+
+/// Elements that never have content, so `<br/>` means the same in HTML.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param", "source", "track",
+    "wbr",
+];
+
+/// The XHTML namespace, which marks a page written as XML.
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Rewrite XHTML-only syntax that the HTML parser misreads.
+///
+/// Applies to documents with an XML declaration or the XHTML namespace on the
+/// root element. The page is tokenized, reading CDATA sections as text the
+/// way an XML parser does, and written out again: the prolog is dropped,
+/// self-closing non-void tags such as `<div/>` get an explicit end tag instead
+/// of swallowing their siblings, CDATA markers in scripts and styles are
+/// removed, and CDATA sections elsewhere become text.
+pub fn normalize_xhtml(html: &str) -> Cow<'_, str> {
+    if !html.contains("<?xml") && !html.contains(XHTML_NAMESPACE) {
+        return Cow::Borrowed(html);
+    }
+
+    let tokenizer = Tokenizer::new(XhtmlSink::default(), TokenizerOpts::default());
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(html));
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+    let rewrite = tokenizer.sink.rewrite.take();
+    match rewrite.xml_prolog || rewrite.xhtml_namespace {
+        true => Cow::Owned(rewrite.output),
+        false => Cow::Borrowed(html),
+    }
+}
+
+/// Token sink that writes the page back out with XHTML syntax repaired.
+#[derive(Default)]
+struct XhtmlSink {
+    rewrite: RefCell<XhtmlRewrite>,
+}
+
+#[derive(Default)]
+struct XhtmlRewrite {
+    output: String,
+    /// Whether anything other than processing instructions and whitespace
+    /// has been seen, which ends the prolog.
+    started: bool,
+    xml_prolog: bool,
+    xhtml_namespace: bool,
+    /// The open raw-text element, such as `script`, and its text so far. The
+    /// tokenizer splits raw text into runs, so it is written at the end tag.
+    raw_text: Option<(LocalName, String)>,
+    /// Depth of open `svg` and `math` elements, where `<style>` and
+    /// `<script>` hold markup rather than raw text.
+    foreign_depth: usize,
+}
+
+impl XhtmlRewrite {
+    fn start_tag(&mut self, tag: &Tag) -> TokenSinkResult<()> {
+        if tag.name == local_name!("html")
+            && tag
+                .attrs
+                .iter()
+                .any(|attr| &*attr.name.local == "xmlns" && attr.value.trim().eq_ignore_ascii_case(XHTML_NAMESPACE))
+        {
+            self.xhtml_namespace = true;
+        }
+        self.output.push('<');
+        self.output.push_str(&tag.name);
+        for attr in &tag.attrs {
+            self.output.push(' ');
+            self.output.push_str(&attr.name.local);
+            self.output.push_str("=\"");
+            self.output.push_str(&shared::escape_html(&attr.value));
+            self.output.push('"');
+        }
+        self.output.push('>');
+
+        let void = VOID_ELEMENTS.contains(&&*tag.name);
+        if tag.self_closing {
+            if !void {
+                self.output.push_str(&format!("</{}>", tag.name));
+            }
+            return TokenSinkResult::Continue;
+        }
+        if matches!(tag.name, local_name!("svg") | local_name!("math")) {
+            self.foreign_depth += 1;
+        }
+        if self.foreign_depth > 0 || void {
+            return TokenSinkResult::Continue;
+        }
+        let raw = match tag.name {
+            local_name!("script") => RawKind::ScriptData,
+            local_name!("style")
+            | local_name!("noscript")
+            | local_name!("iframe")
+            | local_name!("xmp")
+            | local_name!("noembed")
+            | local_name!("noframes") => RawKind::Rawtext,
+            local_name!("title") | local_name!("textarea") => RawKind::Rcdata,
+            _ => return TokenSinkResult::Continue,
+        };
+        self.raw_text = Some((tag.name.clone(), String::new()));
+        TokenSinkResult::RawData(raw)
+    }
+
+    fn end_tag(&mut self, tag: &Tag) {
+        if matches!(tag.name, local_name!("svg") | local_name!("math")) {
+            self.foreign_depth = self.foreign_depth.saturating_sub(1);
+        }
+        self.finish_raw_text();
+        self.output.push_str(&format!("</{}>", tag.name));
+    }
+
+    fn text(&mut self, text: &str) {
+        match &mut self.raw_text {
+            Some((_, raw)) => raw.push_str(text),
+            None => self.output.push_str(&shared::escape_html(text)),
+        }
+    }
+
+    /// Write the text of the open raw-text element, if any.
+    fn finish_raw_text(&mut self) {
+        let Some((name, text)) = self.raw_text.take() else {
+            return;
+        };
+        match name {
+            local_name!("script") | local_name!("style") => self
+                .output
+                .push_str(&RegexPattern::CdataMarker.to_regex().replace_all(&text, "")),
+            local_name!("title") | local_name!("textarea") => self.output.push_str(&shared::escape_html(&text)),
+            _ => self.output.push_str(&text),
+        }
+    }
+
+    fn doctype(&mut self, doctype: &Doctype) {
+        self.output.push_str("<!DOCTYPE");
+        if let Some(name) = &doctype.name {
+            self.output.push(' ');
+            self.output.push_str(name);
+        }
+        match (&doctype.public_id, &doctype.system_id) {
+            (Some(public), Some(system)) => self.output.push_str(&format!(r#" PUBLIC "{public}" "{system}""#)),
+            (Some(public), None) => self.output.push_str(&format!(r#" PUBLIC "{public}""#)),
+            (None, Some(system)) => self.output.push_str(&format!(r#" SYSTEM "{system}""#)),
+            (None, None) => {}
+        }
+        self.output.push('>');
+    }
+}
+
+impl TokenSink for XhtmlSink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let mut rewrite = self.rewrite.borrow_mut();
+        match token {
+            // Processing instructions arrive as comments starting with `?`.
+            CommentToken(text) if !rewrite.started && text.starts_with('?') => {
+                rewrite.xml_prolog |= text.starts_with("?xml");
+            }
+            CharacterTokens(text)
+                if !rewrite.started
+                    && text
+                        .trim_matches(|c: char| c.is_whitespace() || c == '\u{FEFF}')
+                        .is_empty() => {}
+            ParseError(_) | NullCharacterToken => {}
+            EOFToken => rewrite.finish_raw_text(),
+            token => {
+                rewrite.started = true;
+                match token {
+                    TagToken(tag) if tag.kind == StartTag => return rewrite.start_tag(&tag),
+                    TagToken(tag) => rewrite.end_tag(&tag),
+                    CharacterTokens(text) => rewrite.text(&text),
+                    CommentToken(text) => rewrite.output.push_str(&format!("<!--{text}-->")),
+                    DoctypeToken(doctype) => rewrite.doctype(&doctype),
+                    _ => {}
+                }
+            }
+        }
+        TokenSinkResult::Continue
+    }
+
+    /// Read CDATA sections as text everywhere, as an XML parser would, rather
+    /// than as the bogus comments HTML makes of them outside SVG and MathML.
+    fn adjusted_current_node_present_but_not_in_html_namespace(&self) -> bool {
+        true
+    }
+}
+
 pub fn recover(document: &NodeRef, mobile_viewport_width: Option<usize>) -> RecoveryDiagnostic {
     let mut diagnostic = RecoveryDiagnostic {
         shadow_roots_flattened: flatten_declarative_shadow_dom(document),
         mobile_rules_applied: 0,
+        payloads_decoded: 0,
+        galleries_flattened: 0,
     };
     if let Some(width) = mobile_viewport_width {
         diagnostic.mobile_rules_applied = apply_mobile_display_rules(document, width);
     }
+    diagnostic.galleries_flattened = flatten_galleries(document);
     diagnostic
 }
 
-fn flatten_declarative_shadow_dom(document: &NodeRef) -> usize {
+/// Replace gallery, slideshow, and carousel widgets with one figure per
+/// slide. Sliders hide every slide but the current one, so without this
+/// only the first image survives hidden-element removal.
+fn flatten_galleries(document: &NodeRef) -> usize {
     let mut flattened = 0;
-    for template in dom::select_nodes(document, r#"template[shadowrootmode], template[shadowroot]"#) {
-        let children: Vec<_> = template.children().collect();
-        if children.is_empty() {
+    for gallery in dom::select_nodes(document, "div, section, ul, ol, figure") {
+        let class_id = dom::class_id_string(&gallery);
+        if !RegexPattern::GalleryContainer.to_regex().is_match(&class_id)
+            || RegexPattern::UnlikelyCandidates.to_regex().is_match(&class_id)
+            || RegexPattern::GalleryChrome.to_regex().is_match(&class_id)
+            || !gallery.ancestors().any(|node| node.as_document().is_some())
+        {
+            continue;
+        }
+        let figures: Vec<NodeRef> = gallery_slides(&gallery).iter().filter_map(slide_figure).collect();
+        if figures.len() < 2 {
             continue;
         }
-        for child in children {
-            template.insert_before(child);
+        for figure in figures {
+            gallery.insert_before(figure);
         }
-        template.detach();
+        gallery.detach();
         flattened += 1;
     }
     flattened
 }
 
+/// The outermost slides of a gallery, skipping thumbnail strips, controls,
+/// and the clones infinite carousels add at each end.
+fn gallery_slides(gallery: &NodeRef) -> Vec<NodeRef> {
+    let slides: Vec<NodeRef> = gallery
+        .descendants()
+        .filter(|node| {
+            node.as_element().is_some()
+                && RegexPattern::GallerySlide
+                    .to_regex()
+                    .is_match(&dom::class_id_string(node))
+        })
+        .collect();
+    let slide_ids: Vec<usize> = slides.iter().map(dom::node_id).collect();
+    slides
+        .into_iter()
+        .filter(|slide| {
+            slide
+                .inclusive_ancestors()
+                .take_while(|node| node != gallery)
+                .all(|node| {
+                    let is_nested = node != *slide && slide_ids.contains(&dom::node_id(&node));
+                    !is_nested
+                        && !RegexPattern::GalleryChrome
+                            .to_regex()
+                            .is_match(&dom::class_id_string(&node))
+                })
+        })
+        .collect()
+}
+
+/// A slide as a plain figure holding its first image and its caption.
+fn slide_figure(slide: &NodeRef) -> Option<NodeRef> {
+    let image = dom::select_first(slide, "picture, img")?;
+    let image = dom::deep_clone(&image);
+    for node in image.inclusive_descendants() {
+        for name in ["hidden", "aria-hidden", "style"] {
+            dom::remove_attr(&node, name);
+        }
+    }
+
+    let figure = dom::new_element("figure")?;
+    figure.append(image);
+    let caption = dom::select_iter(slide, "figcaption, *[class], *[id]").find(|node| {
+        (dom::node_name(node) == "figcaption"
+            || RegexPattern::GalleryCaption
+                .to_regex()
+                .is_match(&dom::class_id_string(node)))
+            && !dom::exists(node, "img")
+            && !dom::inner_text(node).is_empty()
+    });
+    if let Some(caption) = caption {
+        let figcaption = dom::new_element("figcaption")?;
+        for child in caption.children() {
+            figcaption.append(dom::deep_clone(&child));
+        }
+        figure.append(figcaption);
+    }
+    Some(figure)
+}
+
+/// Replace each shadow root template with its contents, repeating until
+/// templates nested inside shadow roots are flattened too.
+///
+/// The host's light-DOM children move into the shadow root's `<slot>`s as a
+/// browser would render them. Children no slot takes stay after the shadow
+/// content.
+fn flatten_declarative_shadow_dom(document: &NodeRef) -> usize {
+    let mut flattened = 0;
+    loop {
+        let mut changed = false;
+        for template in dom::select_nodes(document, r#"template[shadowrootmode], template[shadowroot]"#) {
+            // Parsed templates keep their children in a separate fragment.
+            let contents = template
+                .as_element()
+                .and_then(|element| element.template_contents.clone());
+            let children: Vec<_> = contents
+                .iter()
+                .flat_map(NodeRef::children)
+                .chain(template.children())
+                .collect();
+            if children.is_empty() {
+                continue;
+            }
+            let light: Vec<_> = template
+                .parent()
+                .map(|host| host.children().filter(|child| child != &template).collect())
+                .unwrap_or_default();
+            let slots: Vec<_> = children
+                .iter()
+                .flat_map(|child| dom::select_nodes(child, "slot"))
+                .collect();
+            for child in children {
+                template.insert_before(child);
+            }
+            template.detach();
+            assign_slots(slots, light);
+            flattened += 1;
+            changed = true;
+        }
+        if !changed {
+            return flattened;
+        }
+    }
+}
+
+/// Move each light-DOM node into the first slot with its `slot` name, or the
+/// first unnamed slot when it has none. Slots left empty show their
+/// fallback content.
+fn assign_slots(slots: Vec<NodeRef>, mut light: Vec<NodeRef>) {
+    for slot in slots {
+        let name = dom::attr(&slot, "name").unwrap_or_default();
+        let (assigned, rest): (Vec<_>, Vec<_>) = light.into_iter().partition(|node| slot_name(node) == name);
+        light = rest;
+        let has_content = assigned
+            .iter()
+            .any(|node| node.as_element().is_some() || !node.text_contents().trim().is_empty());
+        match has_content {
+            true => {
+                for node in assigned {
+                    slot.insert_before(node);
+                }
+                slot.detach();
+            }
+            false => {
+                light.extend(assigned);
+                dom::replace_with_children(&slot);
+            }
+        }
+    }
+}
+
+/// The slot a light-DOM node is assigned to; text goes to the unnamed slot.
+fn slot_name(node: &NodeRef) -> String {
+    node.as_element()
+        .and_then(|_| dom::attr(node, "slot"))
+        .unwrap_or_default()
+}
+
 fn apply_mobile_display_rules(document: &NodeRef, viewport_width: usize) -> usize {
     let mut applied = 0;
     for style in dom::select_nodes(document, "style") {
@@ -1,10 +1,8 @@
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RegexPattern {
-    /// Removes raw `<script>` blocks before expensive DOM scoring.
-    RawScript,
     /// Matches JSON-LD schema types that can describe article content.
     JsonLdArticleType,
     /// Flags nodes that are usually navigation, ads, comments, or chrome.
@@ -31,6 +29,8 @@ pub enum RegexPattern {
     TrailingChromeAttrs,
     /// Detects trailing chrome by heading or short block text.
     TrailingChromeText,
+    /// Matches the labels of copy buttons and similar documentation-page controls.
+    DocChromeLabel,
     /// Detects footnote/reference sections by class/id/role attributes.
     FootnoteReferenceAttrs,
     /// Detects footnote/reference headings by text.
@@ -43,20 +43,77 @@ pub enum RegexPattern {
     CssRule,
     /// Extracts a `display` value from a CSS declaration block.
     DisplayDecl,
-    /// Finds declarative shadow DOM templates in raw HTML snapshots.
-    ShadowTemplateHtml,
+    /// Detects declarative shadow DOM attributes in raw HTML snapshots.
+    ShadowRootAttribute,
     /// Extracts a stable numeric label from footnote ids.
     FootnoteTrailingNumber,
     /// Removes leading byline words such as `by` or `written by`.
     BylinePrefix,
     /// Removes dates and update text from bylines.
     BylineTrailingDate,
+    /// Removes an "About the author" heading from author bio text.
+    AuthorBioHeading,
+    /// Strips wording and ordinals around human-written dates before parsing.
+    DateNoise,
+    /// Matches CDATA open and close markers, with the comment wrappers used in scripts.
+    CdataMarker,
+    /// Cheap check for a `data:` URI or a long base64 run before decoding.
+    InlinePayloadHint,
+    /// Detects an HTML tag in decoded text.
+    MarkupTag,
+    /// Matches byte-order marks and control characters that are not whitespace.
+    StrayCharacters,
+    /// Matches the `<!DOCTYPE>` or `<html>` tag that starts a document.
+    DocumentStart,
+    /// Matches tags that only appear once a document has started.
+    DocumentStructureTag,
+    /// Matches a bare XPath step such as `div` or `*`.
+    XPathTagStep,
+    /// Matches an XPath step with an attribute test such as `div[@id='main']`,
+    /// with either quote style.
+    XPathAttrEqualsStep,
+    /// Matches an XPath step with a `contains(@attr, '...')` test, with either
+    /// quote style.
+    XPathAttrContainsStep,
+    /// Matches link labels that point to the next page of the same article.
+    NextPageText,
+    /// Matches link text that points to a different article instead of a page.
+    NextArticleText,
+    /// Matches class, id, or label values that mark a next-page link.
+    NextPageMarker,
+    /// Matches class/id names of pagination controls.
+    PaginationContainer,
+    /// Matches class/id names of gallery, slideshow, and carousel widgets.
+    GalleryContainer,
+    /// Matches class/id names of a single gallery slide.
+    GallerySlide,
+    /// Matches class/id names of slide captions and credits.
+    GalleryCaption,
+    /// Matches class/id names of gallery thumbnails, controls, and cloned slides.
+    GalleryChrome,
+    /// Matches markup and class/id names that mark a live blog or its posts.
+    LiveBlogMarker,
+    /// Matches class/id names of a live-blog post timestamp.
+    LiveBlogTimestamp,
+    /// Matches class/id names and labels of a transcript container.
+    TranscriptMarker,
+    /// Matches heading text that introduces a transcript.
+    TranscriptHeading,
+    /// Matches frame names and sources that usually hold page content.
+    MainFrame,
+    /// Matches frame names and sources that usually hold navigation or chrome.
+    NavigationFrame,
+    /// Matches the charset a `<meta charset>` or `<meta http-equiv>` tag declares.
+    MetaCharset,
+    /// Matches `<noscript>` text asking the reader to enable JavaScript.
+    NoscriptNotice,
+    /// Matches custom element names of article wrappers, such as `news-article`.
+    ArticleElementName,
 }
 
 impl RegexPattern {
     pub fn to_regex(self) -> &'static Regex {
         match self {
-            Self::RawScript => &RAW_SCRIPT,
             Self::JsonLdArticleType => &JSON_LD_ARTICLE_TYPE,
             Self::UnlikelyCandidates => &UNLIKELY_CANDIDATES,
             Self::MaybeCandidate => &MAYBE_CANDIDATE,
@@ -70,16 +127,45 @@ impl RegexPattern {
             Self::LazyImageSrcset => &LAZY_IMAGE_SRCSET,
             Self::TrailingChromeAttrs => &TRAILING_CHROME_ATTRS,
             Self::TrailingChromeText => &TRAILING_CHROME_TEXT,
+            Self::DocChromeLabel => &DOC_CHROME_LABEL,
             Self::FootnoteReferenceAttrs => &FOOTNOTE_REFERENCE_ATTRS,
             Self::FootnoteReferenceText => &FOOTNOTE_REFERENCE_TEXT,
             Self::LeadingDateText => &LEADING_DATE_TEXT,
             Self::MobileMediaBlock => &MOBILE_MEDIA_BLOCK,
             Self::CssRule => &CSS_RULE,
             Self::DisplayDecl => &DISPLAY_DECL,
-            Self::ShadowTemplateHtml => &SHADOW_TEMPLATE_HTML,
+            Self::ShadowRootAttribute => &SHADOW_ROOT_ATTRIBUTE,
             Self::FootnoteTrailingNumber => &FOOTNOTE_TRAILING_NUMBER,
             Self::BylinePrefix => &BYLINE_PREFIX,
             Self::BylineTrailingDate => &BYLINE_TRAILING_DATE,
+            Self::AuthorBioHeading => &AUTHOR_BIO_HEADING,
+            Self::DateNoise => &DATE_NOISE,
+            Self::CdataMarker => &CDATA_MARKER,
+            Self::InlinePayloadHint => &INLINE_PAYLOAD_HINT,
+            Self::MarkupTag => &MARKUP_TAG,
+            Self::StrayCharacters => &STRAY_CHARACTERS,
+            Self::DocumentStart => &DOCUMENT_START,
+            Self::DocumentStructureTag => &DOCUMENT_STRUCTURE_TAG,
+            Self::XPathTagStep => &XPATH_TAG_STEP,
+            Self::XPathAttrEqualsStep => &XPATH_ATTR_EQUALS_STEP,
+            Self::XPathAttrContainsStep => &XPATH_ATTR_CONTAINS_STEP,
+            Self::NextPageText => &NEXT_PAGE_TEXT,
+            Self::NextArticleText => &NEXT_ARTICLE_TEXT,
+            Self::NextPageMarker => &NEXT_PAGE_MARKER,
+            Self::PaginationContainer => &PAGINATION_CONTAINER,
+            Self::GalleryContainer => &GALLERY_CONTAINER,
+            Self::GallerySlide => &GALLERY_SLIDE,
+            Self::GalleryCaption => &GALLERY_CAPTION,
+            Self::GalleryChrome => &GALLERY_CHROME,
+            Self::LiveBlogMarker => &LIVE_BLOG_MARKER,
+            Self::LiveBlogTimestamp => &LIVE_BLOG_TIMESTAMP,
+            Self::TranscriptMarker => &TRANSCRIPT_MARKER,
+            Self::TranscriptHeading => &TRANSCRIPT_HEADING,
+            Self::MainFrame => &MAIN_FRAME,
+            Self::NavigationFrame => &NAVIGATION_FRAME,
+            Self::MetaCharset => &META_CHARSET,
+            Self::NoscriptNotice => &NOSCRIPT_NOTICE,
+            Self::ArticleElementName => &ARTICLE_ELEMENT_NAME,
         }
     }
 }
@@ -90,33 +176,60 @@ impl From<RegexPattern> for &'static Regex {
     }
 }
 
-static RAW_SCRIPT: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?is)<script\b[^>]*>.*?</script\s*>").expect("valid script regex"));
+/// Patterns tested together in one pass over the input, for checks that run
+/// on every element.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegexSetPattern {
+    /// [`RegexPattern::Negative`] then [`RegexPattern::Positive`], for class/id weighting.
+    ClassWeight,
+    /// [`RegexPattern::UnlikelyCandidates`] then [`RegexPattern::MaybeCandidate`].
+    UnlikelyCandidates,
+}
+
+impl RegexSetPattern {
+    pub fn to_regex_set(self) -> &'static RegexSet {
+        match self {
+            Self::ClassWeight => &CLASS_WEIGHT,
+            Self::UnlikelyCandidates => &UNLIKELY_OR_MAYBE,
+        }
+    }
+}
+
+/// Whether a class/id string looks like page chrome and not like content.
+pub fn is_unlikely_candidate(match_string: &str) -> bool {
+    let matches = RegexSetPattern::UnlikelyCandidates.to_regex_set().matches(match_string);
+    matches.matched(0) && !matches.matched(1)
+}
+
+static CLASS_WEIGHT: Lazy<RegexSet> =
+    Lazy::new(|| RegexSet::new([NEGATIVE_PATTERN, POSITIVE_PATTERN]).expect("valid class weight regex set"));
+
+static UNLIKELY_OR_MAYBE: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([UNLIKELY_CANDIDATES_PATTERN, MAYBE_CANDIDATE_PATTERN]).expect("valid unlikely-candidates regex set")
+});
 
 static JSON_LD_ARTICLE_TYPE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^Article|AdvertiserContentArticle|NewsArticle|AnalysisNewsArticle|AskPublicNewsArticle|BackgroundNewsArticle|OpinionNewsArticle|ReportageNewsArticle|ReviewNewsArticle|Report|SatiricalArticle|ScholarlyArticle|MedicalScholarlyArticle|SocialMediaPosting|BlogPosting|LiveBlogPosting|DiscussionForumPosting|TechArticle|APIReference$")
+    Regex::new(r"^(?:Article|AdvertiserContentArticle|NewsArticle|AnalysisNewsArticle|AskPublicNewsArticle|BackgroundNewsArticle|OpinionNewsArticle|ReportageNewsArticle|ReviewNewsArticle|Report|SatiricalArticle|ScholarlyArticle|MedicalScholarlyArticle|SocialMediaPosting|BlogPosting|LiveBlogPosting|DiscussionForumPosting|TechArticle|APIReference)$")
         .expect("valid json-ld article type regex")
 });
 
-static UNLIKELY_CANDIDATES: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?i)-ad-|ai2html|banner|breadcrumbs|combx|comment|community|cover-wrap|disqus|extra|footer|gdpr|header|legends|menu|related|remark|replies|rss|shoutbox|sidebar|skyscraper|social|sponsor|supplemental|ad-break|agegate|pagination|pager|popup|yom-remote",
-    )
-    .expect("valid unlikely-candidates regex")
-});
+const UNLIKELY_CANDIDATES_PATTERN: &str = r"(?i)-ad-|ai2html|banner|breadcrumbs|combx|comment|community|cover-wrap|disqus|extra|footer|gdpr|header|legends|menu|related|remark|replies|rss|shoutbox|sidebar|skyscraper|social|sponsor|supplemental|ad-break|agegate|pagination|pager|popup|yom-remote";
 
-static MAYBE_CANDIDATE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?i)and|article|body|column|content|main|mathjax|shadow").expect("valid ok-maybe regex"));
+static UNLIKELY_CANDIDATES: Lazy<Regex> =
+    Lazy::new(|| Regex::new(UNLIKELY_CANDIDATES_PATTERN).expect("valid unlikely-candidates regex"));
 
-static POSITIVE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|text|blog|story")
-        .expect("valid positive regex")
-});
+const MAYBE_CANDIDATE_PATTERN: &str = r"(?i)and|article|body|column|content|main|mathjax|shadow";
 
-static NEGATIVE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|\bcom-|contact|footer|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget")
-        .expect("valid negative regex")
-});
+static MAYBE_CANDIDATE: Lazy<Regex> = Lazy::new(|| Regex::new(MAYBE_CANDIDATE_PATTERN).expect("valid ok-maybe regex"));
+
+const POSITIVE_PATTERN: &str =
+    r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|text|blog|story";
+
+static POSITIVE: Lazy<Regex> = Lazy::new(|| Regex::new(POSITIVE_PATTERN).expect("valid positive regex"));
+
+const NEGATIVE_PATTERN: &str = r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|\bcom-|contact|footer|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget";
+
+static NEGATIVE: Lazy<Regex> = Lazy::new(|| Regex::new(NEGATIVE_PATTERN).expect("valid negative regex"));
 
 static NORMALIZE_WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s{2,}").expect("valid whitespace regex"));
 
@@ -161,6 +274,10 @@ static TRAILING_CHROME_TEXT: Lazy<Regex> = Lazy::new(|| {
     .expect("valid trailing chrome text regex")
 });
 
+static DOC_CHROME_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(copy|copy code|copied!?|copy page|copy page contents)$").expect("valid doc chrome label regex")
+});
+
 static FOOTNOTE_REFERENCE_ATTRS: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)\b(footnotes?|endnotes?|references?|bibliography|citations?)\b")
         .expect("valid footnote reference attribute regex")
@@ -197,10 +314,8 @@ static CSS_RULE: Lazy<Regex> = Lazy::new(|| {
 static DISPLAY_DECL: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?is)display\s*:\s*(?P<display>[a-z-]+)").expect("valid display regex"));
 
-static SHADOW_TEMPLATE_HTML: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"(?is)<template\s+[^>]*(?:shadowrootmode|shadowroot)\s*=\s*["']?[^"'\s>]+["']?[^>]*>(?P<body>.*?)</template>"#)
-        .expect("valid shadow template regex")
-});
+static SHADOW_ROOT_ATTRIBUTE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\sshadowroot(?:mode)?\s*=").expect("valid shadow root attribute regex"));
 
 static FOOTNOTE_TRAILING_NUMBER: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)(?:^|[-_:])(?:fn|ftnt|note|ref)?(\d+)$|(\d+)$").expect("valid footnote label regex"));
@@ -208,6 +323,9 @@ static FOOTNOTE_TRAILING_NUMBER: Lazy<Regex> =
 static BYLINE_PREFIX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^\s*(by|author|authors|written by)\s*:?\s+").expect("valid byline prefix regex"));
 
+static AUTHOR_BIO_HEADING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^\s*about\s+the\s+authors?\s*:?\s*").expect("valid author bio heading regex"));
+
 static BYLINE_TRAILING_DATE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         r"(?ix)
@@ -223,3 +341,119 @@ static BYLINE_TRAILING_DATE: Lazy<Regex> = Lazy::new(|| {
     )
     .expect("valid byline trailing date regex")
 });
+
+static DATE_NOISE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(published|updated|last\s+updated|posted|modified)?\s*(on|:)?\s+|(\d)(st|nd|rd|th)\b")
+        .expect("valid date noise regex")
+});
+
+static CDATA_MARKER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?://|/\*)?[ \t]*(?:<!\[CDATA\[|\]\]>)(?:[ \t]*\*/)?").expect("valid cdata marker regex")
+});
+
+static INLINE_PAYLOAD_HINT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)data:(?:text/html|application/xhtml\+xml)[;,]|[A-Za-z0-9+/_-]{64,}")
+        .expect("valid inline payload hint regex")
+});
+
+static MARKUP_TAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)</?(?:html|head|body|article|main|div|p|h[1-6]|section)\b[^>]*>").expect("valid markup tag regex")
+});
+
+static STRAY_CHARACTERS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\x00-\x08\x0B\x0E-\x1F\x7F\u{FEFF}]").expect("valid stray characters regex"));
+
+static DOCUMENT_START: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)<!doctype\b|<html\b").expect("valid document start regex"));
+
+static DOCUMENT_STRUCTURE_TAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<(?:head|body|title|meta|link|script|style|template|textarea|article|main)\b")
+        .expect("valid document structure tag regex")
+});
+
+static XPATH_TAG_STEP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([A-Za-z][\w-]*|\*)$").expect("valid XPath tag step regex"));
+
+static XPATH_ATTR_EQUALS_STEP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^([A-Za-z][\w-]*|\*)\[@([\w:-]+)\s*=\s*(?:'([^']+)'|"([^"]+)")\]$"#)
+        .expect("valid XPath attribute step regex")
+});
+
+static XPATH_ATTR_CONTAINS_STEP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^([A-Za-z][\w-]*|\*)\[contains\(\s*@([\w:-]+)\s*,\s*(?:'([^']+)'|"([^"]+)")\s*\)\]$"#)
+        .expect("valid XPath contains step regex")
+});
+
+static NEXT_PAGE_TEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:next|next\s+page|nextpage|page\s+suivante|suivante?|weiter|n[äa]chste\s+seite|siguiente|pr[óo]xima|volgende)$")
+        .expect("valid next page text regex")
+});
+
+static NEXT_ARTICLE_TEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:article|story|post|entry|chapter|episode|item)s?\b").expect("valid next article text regex")
+});
+
+static NEXT_PAGE_MARKER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:^|[\s_-])next(?:$|[\s_-])").expect("valid next page marker regex"));
+
+static PAGINATION_CONTAINER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)pagination|pager|paging|page-?nav|page-?links|nav-links|\bpages\b")
+        .expect("valid pagination regex")
+});
+
+static GALLERY_CONTAINER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:gallery|slideshow|carousel|slider|swiper|splide|flickity|glide|slick)")
+        .expect("valid gallery container regex")
+});
+
+static GALLERY_SLIDE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bslide\b|__slide\b|\bgallery-?(?:item|image)\b|\bcarousel-?(?:item|cell)\b")
+        .expect("valid gallery slide regex")
+});
+
+static GALLERY_CAPTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)caption|credit|description|slide-?text|\bdesc\b").expect("valid gallery caption regex")
+});
+
+static GALLERY_CHROME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)thumb|\bnav|pager|pagination|\bdots\b|indicator|cloned|duplicate")
+        .expect("valid gallery chrome regex")
+});
+
+static LIVE_BLOG_MARKER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)LiveBlogPosting|live-?blog|live-?(?:update|post|event|coverage|entry|feed)")
+        .expect("valid live blog marker regex")
+});
+
+static LIVE_BLOG_TIMESTAMP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)timestamp|time(?:$|[\s_-])|posted|date(?:$|[\s_-])").expect("valid live blog timestamp regex")
+});
+
+static TRANSCRIPT_MARKER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)transcri(?:pt|ption)").expect("valid transcript marker regex"));
+
+static TRANSCRIPT_HEADING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:(?:show|hide|read|view)\s+(?:the\s+)?)?(?:full\s+|episode\s+|video\s+|audio\s+)?transcri(?:pt|ption)\s*:?\s*$")
+        .expect("valid transcript heading regex")
+});
+
+static MAIN_FRAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)main|content|body|text|article|story").expect("valid main frame regex"));
+
+static NAVIGATION_FRAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)nav|menu|top|head|foot|banner|left|side|toc|index|logo|title|bottom")
+        .expect("valid navigation frame regex")
+});
+
+static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta\b[^>]*?\bcharset\s*=\s*["']?\s*([a-z0-9_:.\-]+)"#).expect("valid meta charset regex")
+});
+
+static NOSCRIPT_NOTICE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(enable|turn on|activate|allow)\b.{0,40}\bjavascript\b|\bjavascript\b.{0,40}\b(disabled|required|turned off|not enabled|needed)\b|\b(browser|you)\b.{0,40}\b(does not|doesn't|do not|don't) support\b")
+        .expect("valid noscript notice regex")
+});
+
+static ARTICLE_ELEMENT_NAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|-)(?:article|story|entry|post)(?:-|$)").expect("valid article element name regex")
+});
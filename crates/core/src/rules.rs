@@ -1,16 +1,27 @@
+mod builder;
 mod hn;
+mod lint;
 
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+pub use builder::SiteProfileBuilder;
 use hn::HackerNewsExtractor;
 use kuchiki::NodeRef;
-use regex::Regex;
-use serde::Deserialize;
+use kuchiki::traits::TendrilSink;
+pub use lint::lint_site_profile;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use super::config::{ExtractFlags, ReadabilityOptions};
-use super::diagnostics::{SiteRuleDiagnostic, SiteRuleMatch, SiteRuleSource};
+use super::diagnostics::{ShadowedSiteRule, SiteProfileCheck, SiteRuleDiagnostic, SiteRuleMatch, SiteRuleSource};
 use super::error::{Error, Result};
-use super::extract::{ExtractAttempt, element_count};
+use super::extract::{self, ExtractAttempt, element_count};
 use super::metadata::Metadata;
+use super::regexes::RegexPattern;
 use super::{dom, normalize, serialize};
 
 const BUNDLED_PROFILES: &[(&str, &str)] = &[
@@ -25,8 +36,52 @@ const BUNDLED_PROFILES: &[(&str, &str)] = &[
     ("readthedocs.io.toml", include_str!("./rules/conf/readthedocs.io.toml")),
 ];
 
+/// Curated profiles for major news sites, compiled in with the
+/// `bundled-configs` feature.
+#[cfg(feature = "bundled-configs")]
+const NEWS_PROFILES: &[(&str, &str)] = &[
+    ("bbc.co.uk.toml", include_str!("./rules/conf/bbc.co.uk.toml")),
+    ("nytimes.com.toml", include_str!("./rules/conf/nytimes.com.toml")),
+    (
+        "telegraph.co.uk.toml",
+        include_str!("./rules/conf/telegraph.co.uk.toml"),
+    ),
+    (
+        "theguardian.com.toml",
+        include_str!("./rules/conf/theguardian.com.toml"),
+    ),
+    ("theverge.com.toml", include_str!("./rules/conf/theverge.com.toml")),
+    (
+        "washingtonpost.com.toml",
+        include_str!("./rules/conf/washingtonpost.com.toml"),
+    ),
+];
+
+#[cfg(not(feature = "bundled-configs"))]
+const NEWS_PROFILES: &[(&str, &str)] = &[];
+
+/// Presets for publishing platforms, matched by `fingerprints` on pages no
+/// host profile covers.
+const PLATFORM_PROFILES: &[(&str, &str)] = &[
+    ("wordpress.toml", include_str!("./rules/conf/wordpress.toml")),
+    ("ghost.toml", include_str!("./rules/conf/ghost.toml")),
+    ("medium.toml", include_str!("./rules/conf/medium.toml")),
+    ("substack.toml", include_str!("./rules/conf/substack.toml")),
+    ("blogger.toml", include_str!("./rules/conf/blogger.toml")),
+    ("hugo.toml", include_str!("./rules/conf/hugo.toml")),
+    ("docusaurus.toml", include_str!("./rules/conf/docusaurus.toml")),
+];
+
 static HACKER_NEWS_EXTRACTOR: HackerNewsExtractor = HackerNewsExtractor;
 
+/// Parsed profiles shared by every extraction in the process.
+static PROFILE_CACHE: Lazy<ProfileCache> = Lazy::new(ProfileCache::default);
+
+/// User profiles kept in [`PROFILE_CACHE`], enough for a full set of synced
+/// profiles. Past it the least recently used profile is dropped, so callers
+/// that send a new profile with every request cannot grow it without bound.
+const USER_PROFILE_CACHE_LIMIT: usize = 8192;
+
 trait SiteExtractor {
     fn name(&self) -> &'static str;
     fn matches(&self, url: &Url) -> bool;
@@ -46,19 +101,34 @@ pub struct RuleExtraction {
 pub struct SiteProfile {
     name: String,
     hosts: Vec<String>,
+    fingerprints: Vec<String>,
     subdomains: bool,
     path_prefixes: Vec<String>,
     exclude_path_prefixes: Vec<String>,
     content_roots: Vec<String>,
     remove: Vec<String>,
     remove_id_or_class: Vec<String>,
+    dissolve: Vec<String>,
+    wrap_in: Vec<(String, Vec<String>)>,
+    next_page: Vec<String>,
+    native_ad_clue: Vec<String>,
     metadata: MetadataProfile,
     cleanup: CleanupProfile,
     fallback: FallbackProfile,
+    test_urls: Vec<String>,
+    conditionals: Vec<ConditionalRules>,
     bundled: bool,
     specificity: usize,
 }
 
+/// Rules that apply only when the page contains `if_page_contains`. Only the
+/// list directives of `rules` are used.
+#[derive(Clone, Debug, Default)]
+struct ConditionalRules {
+    if_page_contains: String,
+    rules: SiteProfile,
+}
+
 impl From<&SiteProfile> for ExtractFlags {
     fn from(profile: &SiteProfile) -> Self {
         Self { strip_unlikely: false, weight_classes: false, clean_conditionally: profile.cleanup.prune }
@@ -73,6 +143,7 @@ struct MetadataProfile {
     image: Vec<String>,
     site_name: Option<String>,
     title_suffixes: Vec<String>,
+    prefer_config: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -98,6 +169,280 @@ impl Default for FallbackProfile {
     }
 }
 
+/// Directives a `[[conditional]]` block can hold.
+const CONDITIONAL_DIRECTIVES: [Directive; 4] = [
+    Directive::ContentRoots,
+    Directive::Remove,
+    Directive::RemoveIdOrClass,
+    Directive::NextPage,
+];
+
+/// A profile directive that can be overridden or ignored for one run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Directive {
+    ContentRoots,
+    Remove,
+    RemoveIdOrClass,
+    Dissolve,
+    NextPage,
+    NativeAdClue,
+    Title,
+    Author,
+    Date,
+    Image,
+    SiteName,
+    TitleSuffixes,
+    PreferConfig,
+    Cleanup,
+    Prune,
+    GenericOnEmpty,
+}
+
+impl Directive {
+    /// Accepts profile keys, optionally prefixed with their table, and the
+    /// names used by FiveFilters-style site configs.
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let key = ["metadata.", "cleanup.", "fallback."]
+            .iter()
+            .find_map(|table| name.strip_prefix(table))
+            .unwrap_or(name);
+        Some(match key {
+            "content_roots" | "body" => Self::ContentRoots,
+            "remove" | "strip" => Self::Remove,
+            "remove_id_or_class" | "strip_id_or_class" => Self::RemoveIdOrClass,
+            "dissolve" => Self::Dissolve,
+            "next_page" | "next_page_link" => Self::NextPage,
+            "native_ad_clue" => Self::NativeAdClue,
+            "title" => Self::Title,
+            "author" | "byline" => Self::Author,
+            "date" | "published_time" => Self::Date,
+            "image" => Self::Image,
+            "site_name" => Self::SiteName,
+            "title_suffixes" => Self::TitleSuffixes,
+            "prefer_config" | "prefer_config_metadata" => Self::PreferConfig,
+            "enabled" | "cleanup" => Self::Cleanup,
+            "prune" => Self::Prune,
+            "generic_on_empty" | "autodetect_on_failure" => Self::GenericOnEmpty,
+            _ => return None,
+        })
+    }
+
+    fn list(self, profile: &mut SiteProfile) -> Option<&mut Vec<String>> {
+        Some(match self {
+            Self::ContentRoots => &mut profile.content_roots,
+            Self::Remove => &mut profile.remove,
+            Self::RemoveIdOrClass => &mut profile.remove_id_or_class,
+            Self::Dissolve => &mut profile.dissolve,
+            Self::NextPage => &mut profile.next_page,
+            Self::NativeAdClue => &mut profile.native_ad_clue,
+            Self::Title => &mut profile.metadata.title,
+            Self::Author => &mut profile.metadata.author,
+            Self::Date => &mut profile.metadata.date,
+            Self::Image => &mut profile.metadata.image,
+            Self::TitleSuffixes => &mut profile.metadata.title_suffixes,
+            Self::SiteName | Self::PreferConfig | Self::Cleanup | Self::Prune | Self::GenericOnEmpty => return None,
+        })
+    }
+
+    fn is_switch(self) -> bool {
+        matches!(
+            self,
+            Self::PreferConfig | Self::Cleanup | Self::Prune | Self::GenericOnEmpty
+        )
+    }
+
+    /// Switch directives all default to on.
+    fn switch(self, profile: &mut SiteProfile) -> Option<&mut bool> {
+        Some(match self {
+            Self::PreferConfig => &mut profile.metadata.prefer_config,
+            Self::Cleanup => &mut profile.cleanup.enabled,
+            Self::Prune => &mut profile.cleanup.prune,
+            Self::GenericOnEmpty => &mut profile.fallback.generic_on_empty,
+            _ => return None,
+        })
+    }
+}
+
+/// Per-run changes to the matched profile, from
+/// `ReadabilityOptions::site_profile_overrides` and `site_profile_ignores`.
+#[derive(Clone, Debug, Default)]
+struct ProfileOverrides {
+    ignores: Vec<Directive>,
+    sets: Vec<(Directive, String)>,
+    descriptions: Vec<String>,
+}
+
+impl ProfileOverrides {
+    fn parse(opts: &ReadabilityOptions) -> Result<Self> {
+        let invalid = |message: String| Error::invalid_site_profile("overrides", message);
+        let directive = |name: &str| Directive::parse(name).ok_or_else(|| invalid(format!("unknown directive {name}")));
+
+        let ignores = opts
+            .site_profile_ignores
+            .iter()
+            .map(|name| directive(name))
+            .collect::<Result<_>>()?;
+        let sets = opts
+            .site_profile_overrides
+            .iter()
+            .map(|assignment| {
+                let (name, value) = assignment
+                    .split_once('=')
+                    .ok_or_else(|| invalid(format!("expected directive=value, got {assignment}")))?;
+                let directive = directive(name)?;
+                let value = value.trim().to_string();
+                if directive.is_switch() && parse_switch(&value).is_none() {
+                    return Err(invalid(format!("{} expects yes or no, got {value}", name.trim())));
+                }
+                Ok((directive, value))
+            })
+            .collect::<Result<_>>()?;
+        let descriptions = opts
+            .site_profile_ignores
+            .iter()
+            .map(|name| format!("ignore {}", name.trim()))
+            .chain(
+                opts.site_profile_overrides
+                    .iter()
+                    .map(|assignment| format!("set {}", assignment.trim())),
+            )
+            .collect();
+        Ok(Self { ignores, sets, descriptions })
+    }
+
+    fn apply(&self, profile: &mut SiteProfile) {
+        for directive in &self.ignores {
+            clear_conditional_lists(profile, *directive);
+            if let Some(list) = directive.list(profile) {
+                list.clear();
+            } else if let Some(switch) = directive.switch(profile) {
+                *switch = true;
+            } else {
+                profile.metadata.site_name = None;
+            }
+        }
+
+        let mut replaced = Vec::new();
+        for (directive, value) in &self.sets {
+            if !replaced.contains(directive) {
+                clear_conditional_lists(profile, *directive);
+            }
+            if let Some(list) = directive.list(profile) {
+                // The first value replaces the profile's list, conditional
+                // rules included; later ones add to it.
+                if !replaced.contains(directive) {
+                    list.clear();
+                    replaced.push(*directive);
+                }
+                list.push(value.clone());
+            } else if let Some(switch) = directive.switch(profile) {
+                *switch = parse_switch(value).unwrap_or(*switch);
+            } else {
+                profile.metadata.site_name = Some(value.clone());
+            }
+        }
+    }
+}
+
+fn clear_conditional_lists(profile: &mut SiteProfile, directive: Directive) {
+    for conditional in &mut profile.conditionals {
+        if let Some(list) = directive.list(&mut conditional.rules) {
+            list.clear();
+        }
+    }
+}
+
+fn parse_switch(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" | "on" | "1" => Some(true),
+        "no" | "false" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parsed site profiles, so concurrent extractions parse each TOML source
+/// once instead of on every call. Entries are immutable; a run that changes
+/// its profile clones it first.
+#[derive(Debug)]
+struct ProfileCache {
+    bundled: OnceCell<Vec<(&'static str, Arc<SiteProfile>)>>,
+    user: RwLock<HashMap<u64, CachedProfile>>,
+    limit: usize,
+    clock: AtomicU64,
+}
+
+/// A user profile with the source it was parsed from, checked on every hit
+/// in case two sources share a hash.
+#[derive(Debug)]
+struct CachedProfile {
+    name: Box<str>,
+    source: Box<str>,
+    profile: Arc<SiteProfile>,
+    last_used: AtomicU64,
+}
+
+impl Default for ProfileCache {
+    fn default() -> Self {
+        Self::with_limit(USER_PROFILE_CACHE_LIMIT)
+    }
+}
+
+impl ProfileCache {
+    fn with_limit(limit: usize) -> Self {
+        Self { bundled: OnceCell::new(), user: RwLock::default(), limit, clock: AtomicU64::new(0) }
+    }
+
+    /// Bundled profiles with their file names, parsed on first use.
+    fn bundled(&self) -> Result<&[(&'static str, Arc<SiteProfile>)]> {
+        self.bundled
+            .get_or_try_init(|| {
+                bundled_profiles()
+                    .map(|(name, source)| Ok((name, Arc::new(parse_toml_profile(name, source, true)?))))
+                    .collect()
+            })
+            .map(Vec::as_slice)
+    }
+
+    /// The profile parsed from `source`, with `name` as its fallback name.
+    /// Sources that fail to parse are not cached.
+    fn user(&self, name: &str, source: &str) -> Result<Arc<SiteProfile>> {
+        let mut hasher = DefaultHasher::new();
+        (name, source).hash(&mut hasher);
+        let key = hasher.finish();
+        let now = self.clock.fetch_add(1, Ordering::Relaxed);
+        if let Some(cached) = self.user.read().unwrap_or_else(PoisonError::into_inner).get(&key)
+            && *cached.name == *name
+            && *cached.source == *source
+        {
+            cached.last_used.store(now, Ordering::Relaxed);
+            return Ok(Arc::clone(&cached.profile));
+        }
+
+        let profile = Arc::new(parse_toml_profile(name, source, false)?);
+        let mut user = self.user.write().unwrap_or_else(PoisonError::into_inner);
+        if user.len() >= self.limit
+            && !user.contains_key(&key)
+            && let Some(oldest) = user
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used.load(Ordering::Relaxed))
+                .map(|(key, _)| *key)
+        {
+            user.remove(&oldest);
+        }
+        user.insert(
+            key,
+            CachedProfile {
+                name: name.into(),
+                source: source.into(),
+                profile: Arc::clone(&profile),
+                last_used: AtomicU64::new(now),
+            },
+        );
+        Ok(profile)
+    }
+}
+
 #[derive(Clone, Debug)]
 struct SelectorQuery {
     selector: String,
@@ -107,60 +452,100 @@ struct SelectorQuery {
 #[derive(Clone, Debug)]
 struct ProfileMatch {
     profile: SiteProfile,
-    host: String,
-    path_prefix: Option<String>,
+    matched_by: SiteRuleMatch,
+    shadowed: Vec<ShadowedSiteRule>,
+    overrides: Vec<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct TomlSiteProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     hosts: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fingerprints: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
     subdomains: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     path_prefixes: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     exclude_path_prefixes: Vec<String>,
     #[serde(default, alias = "content_roots")]
     content_roots: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     remove: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     remove_id_or_class: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dissolve: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    wrap_in: BTreeMap<String, Vec<String>>,
+    #[serde(default, alias = "next_page_link", skip_serializing_if = "Vec::is_empty")]
+    next_page: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    native_ad_clue: Vec<String>,
+    #[serde(default, alias = "test_url", skip_serializing_if = "Vec::is_empty")]
+    test_urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
     metadata: TomlMetadataProfile,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     cleanup: TomlCleanupProfile,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     fallback: TomlFallbackProfile,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    conditional: Vec<TomlConditionalRules>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+struct TomlConditionalRules {
+    if_page_contains: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    content_roots: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remove: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remove_id_or_class: Vec<String>,
+    #[serde(default, alias = "next_page_link", skip_serializing_if = "Vec::is_empty")]
+    next_page: Vec<String>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 struct TomlMetadataProfile {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     title: Vec<String>,
-    #[serde(default, alias = "byline")]
+    #[serde(default, alias = "byline", skip_serializing_if = "Vec::is_empty")]
     author: Vec<String>,
-    #[serde(default, alias = "published_time")]
+    #[serde(default, alias = "published_time", skip_serializing_if = "Vec::is_empty")]
     date: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     image: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     site_name: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     title_suffixes: Vec<String>,
+    #[serde(alias = "prefer_config_metadata", skip_serializing_if = "Option::is_none")]
+    prefer_config: Option<bool>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 struct TomlCleanupProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
     enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     prune: Option<bool>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 struct TomlFallbackProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
     generic_on_empty: Option<bool>,
 }
 
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
 pub fn extract_with_site_rule(
     doc: &NodeRef, url: Option<&Url>, opts: &ReadabilityOptions, metadata: &Metadata,
 ) -> Result<Option<RuleExtraction>> {
@@ -171,7 +556,7 @@ pub fn extract_with_site_rule(
         return Ok(None);
     }
 
-    if let Some(profile_match) = matching_profile(url, opts)?
+    if let Some(profile_match) = matching_page_profile(url, opts, doc)?
         && let Some(extraction) = extract_with_profile(doc, url, opts, metadata, profile_match)?
     {
         return Ok(Some(extraction));
@@ -189,12 +574,18 @@ pub fn extract_with_site_rule(
                     host: url.host_str().unwrap_or_default().to_string(),
                     path_prefix: None,
                     bundled: true,
+                    file: None,
+                    user_index: None,
+                    fingerprint: None,
                 },
                 roots: Vec::new(),
                 removals: 0,
                 text_len: attempt.text_len,
                 accepted: false,
                 fallback_reason: None,
+                shadowed: Vec::new(),
+                overrides: Vec::new(),
+                conditions: Vec::new(),
             };
             return Ok(Some(RuleExtraction { attempt, flags, diagnostic }));
         }
@@ -206,8 +597,10 @@ pub fn extract_with_site_rule(
 fn extract_with_profile(
     doc: &NodeRef, url: &Url, opts: &ReadabilityOptions, metadata: &Metadata, profile_match: ProfileMatch,
 ) -> Result<Option<RuleExtraction>> {
-    let profile = profile_match.profile;
+    let ProfileMatch { mut profile, matched_by, shadowed, overrides } = profile_match;
+    let conditions = apply_conditionals(doc, &mut profile);
     let removals = apply_removals(doc, &profile);
+    apply_rewrites(doc, &profile);
     let roots = select_first_non_empty(doc, &profile.content_roots);
     if roots.is_empty() {
         if profile.fallback.generic_on_empty {
@@ -219,16 +612,15 @@ fn extract_with_profile(
             diagnostic: SiteRuleDiagnostic {
                 name: profile.name,
                 source: SiteRuleSource::DeclarativeProfile,
-                matched_by: SiteRuleMatch {
-                    host: profile_match.host,
-                    path_prefix: profile_match.path_prefix,
-                    bundled: profile.bundled,
-                },
+                matched_by,
                 roots: Vec::new(),
                 removals,
                 text_len: 0,
                 accepted: false,
                 fallback_reason: Some("profile matched but no content roots matched".to_string()),
+                shadowed,
+                overrides,
+                conditions,
             },
         }));
     }
@@ -242,67 +634,228 @@ fn extract_with_profile(
     let diagnostic = SiteRuleDiagnostic {
         name: profile.name,
         source: SiteRuleSource::DeclarativeProfile,
-        matched_by: SiteRuleMatch {
-            host: profile_match.host,
-            path_prefix: profile_match.path_prefix,
-            bundled: profile.bundled,
-        },
+        matched_by,
         roots: root_selectors,
         removals,
         text_len: attempt.text_len,
         accepted: false,
         fallback_reason: None,
+        shadowed,
+        overrides,
+        conditions,
     };
 
     Ok(Some(RuleExtraction { attempt, flags, diagnostic }))
 }
 
+/// Whether the page is sponsored content according to the matched profile's
+/// `native_ad_clue` selectors.
+///
+/// Returns `None` when no profile matches `url` or the profile has no clues,
+/// `Some(true)` when any clue matches the page, and `Some(false)` otherwise.
+pub(crate) fn native_ad(html: &str, url: Option<&Url>, opts: &ReadabilityOptions) -> Result<Option<bool>> {
+    let Some(url) = url else {
+        return Ok(None);
+    };
+    // Parse the page only once a profile needs it.
+    let doc = std::cell::OnceCell::new();
+    let doc = || doc.get_or_init(|| kuchiki::parse_html().one(html));
+    let profile_match = match matching_profile(url, opts)? {
+        Some(profile_match) => Some(profile_match),
+        None if fingerprinted_clues(opts)? => matching_page_profile(url, opts, doc())?,
+        None => None,
+    };
+    let Some(ProfileMatch { profile, .. }) = profile_match else {
+        return Ok(None);
+    };
+    if profile.native_ad_clue.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        profile
+            .native_ad_clue
+            .iter()
+            .any(|selector| page_contains(doc(), selector)),
+    ))
+}
+
+/// Whether a profile matched by fingerprint could have `native_ad_clue`
+/// selectors, either its own or from a per-run override.
+fn fingerprinted_clues(opts: &ReadabilityOptions) -> Result<bool> {
+    if !opts.site_profile_overrides.is_empty() {
+        return Ok(true);
+    }
+    let has_clues = |profile: &SiteProfile| !profile.fingerprints.is_empty() && !profile.native_ad_clue.is_empty();
+    for (index, source) in opts.site_profiles.iter().enumerate() {
+        if has_clues(&*PROFILE_CACHE.user(&format!("user-profile-{index}"), source)?) {
+            return Ok(true);
+        }
+    }
+    Ok(PROFILE_CACHE.bundled()?.iter().any(|(_, profile)| has_clues(profile)))
+}
+
+/// Links found by the matched profile's `next_page` selectors, in document
+/// order.
+///
+/// Returns `None` when no profile matches `url` or the profile has no
+/// `next_page` selectors, so the caller can fall back to heuristics. Selectors
+/// that target an element rather than an attribute use its `href`.
+pub fn profile_next_page_links(doc: &NodeRef, url: &Url, opts: &ReadabilityOptions) -> Result<Option<Vec<String>>> {
+    let Some(ProfileMatch { mut profile, .. }) = matching_page_profile(url, opts, doc)? else {
+        return Ok(None);
+    };
+    apply_conditionals(doc, &mut profile);
+    if profile.next_page.is_empty() {
+        return Ok(None);
+    }
+    let links = profile
+        .next_page
+        .iter()
+        .filter_map(|selector| selector_to_query(selector))
+        .flat_map(|query| {
+            dom::select_iter(doc, &query.selector)
+                .filter_map(move |node| dom::attr(&node, query.attr.as_deref().unwrap_or("href")))
+        })
+        .map(|link| link.trim().to_string())
+        .filter(|link| !link.is_empty())
+        .collect();
+    Ok(Some(links))
+}
+
 fn apply_metadata_hints(doc: &NodeRef, profile: &SiteProfile, metadata: &mut Metadata) {
+    let prefer_config = profile.metadata.prefer_config;
     if let Some(title) = extract_string(doc, &profile.metadata.title) {
-        metadata.title = Some(strip_title_suffixes(title, &profile.metadata.title_suffixes));
+        let title = strip_title_suffixes(title, &profile.metadata.title_suffixes);
+        apply_metadata_hint(&mut metadata.title, title, prefer_config);
     }
     if let Some(author) = extract_string(doc, &profile.metadata.author) {
-        metadata.byline = Some(author);
+        apply_metadata_hint(&mut metadata.byline, author, prefer_config);
     }
     if let Some(date) = extract_string(doc, &profile.metadata.date) {
-        metadata.published_time = Some(date);
+        apply_metadata_hint(&mut metadata.published_time, date, prefer_config);
     }
     if let Some(image) = extract_string(doc, &profile.metadata.image) {
-        metadata.image = Some(image);
+        apply_metadata_hint(&mut metadata.image, image, prefer_config);
     }
     if let Some(site_name) = &profile.metadata.site_name {
-        metadata.site_name = Some(site_name.clone());
+        apply_metadata_hint(&mut metadata.site_name, site_name.clone(), prefer_config);
         if metadata.byline.is_none() && site_name == "Wikipedia" {
             metadata.byline = Some(site_name.clone());
         }
     }
 }
 
+/// Profile values replace page metadata unless the profile sets
+/// `prefer_config = false`, in which case they only fill gaps.
+fn apply_metadata_hint(field: &mut Option<String>, value: String, prefer_config: bool) {
+    if prefer_config || field.as_deref().is_none_or(|current| current.trim().is_empty()) {
+        *field = Some(value);
+    }
+}
+
 fn matching_profile(url: &Url, opts: &ReadabilityOptions) -> Result<Option<ProfileMatch>> {
     let Some(host) = url.host_str().map(|host| host.trim_start_matches("www.").to_string()) else {
         return Ok(None);
     };
     let path = url.path();
+    let overrides = ProfileOverrides::parse(opts)?;
     let mut matches = Vec::new();
 
+    let matched = |profile: &Arc<SiteProfile>, path_prefix, file: Option<&str>, user_index| SiteRuleMatch {
+        host: host.clone(),
+        path_prefix,
+        bundled: profile.bundled,
+        file: file.map(str::to_string),
+        user_index,
+        fingerprint: None,
+    };
+
     for (index, source) in opts.site_profiles.iter().enumerate() {
-        let mut profile = parse_toml_profile(&format!("user-profile-{index}"), source, false)?;
+        let profile = PROFILE_CACHE.user(&format!("user-profile-{index}"), source)?;
         if let Some(path_prefix) = matching_profile_path(&profile, &host, path) {
-            profile.specificity += 10_000usize.saturating_sub(index);
-            matches.push(ProfileMatch { profile, host: host.clone(), path_prefix });
+            let specificity = profile.specificity + 10_000usize.saturating_sub(index);
+            let matched_by = matched(&profile, path_prefix, None, Some(index));
+            matches.push((specificity, profile, matched_by));
         }
     }
 
-    for (index, (name, source)) in BUNDLED_PROFILES.iter().enumerate() {
-        let mut profile = parse_toml_profile(name, source, true)?;
-        if let Some(path_prefix) = matching_profile_path(&profile, &host, path) {
-            profile.specificity += 1_000usize.saturating_sub(index);
-            matches.push(ProfileMatch { profile, host: host.clone(), path_prefix });
+    for (index, (name, profile)) in PROFILE_CACHE.bundled()?.iter().enumerate() {
+        if let Some(path_prefix) = matching_profile_path(profile, &host, path) {
+            let specificity = profile.specificity + 1_000usize.saturating_sub(index);
+            matches.push((
+                specificity,
+                Arc::clone(profile),
+                matched(profile, path_prefix, Some(name), None),
+            ));
         }
     }
 
-    matches.sort_by_key(|b| std::cmp::Reverse(b.profile.specificity));
-    Ok(matches.into_iter().next())
+    matches.sort_by_key(|(specificity, ..)| std::cmp::Reverse(*specificity));
+    let matches = matches
+        .into_iter()
+        .map(|(_, profile, matched_by)| (profile, matched_by));
+    Ok(first_profile_match(matches, overrides))
+}
+
+/// The profile for `url` as [`matching_profile`] finds it or, when no host
+/// matches, the first profile with a `fingerprints` selector found in `doc`:
+/// user profiles first, then the bundled platform profiles.
+fn matching_page_profile(url: &Url, opts: &ReadabilityOptions, doc: &NodeRef) -> Result<Option<ProfileMatch>> {
+    if let Some(profile_match) = matching_profile(url, opts)? {
+        return Ok(Some(profile_match));
+    }
+    let Some(host) = url.host_str().map(|host| host.trim_start_matches("www.").to_string()) else {
+        return Ok(None);
+    };
+    let overrides = ProfileOverrides::parse(opts)?;
+    let mut profiles = Vec::new();
+    for (index, source) in opts.site_profiles.iter().enumerate() {
+        profiles.push((
+            PROFILE_CACHE.user(&format!("user-profile-{index}"), source)?,
+            None,
+            Some(index),
+        ));
+    }
+    for (name, profile) in PROFILE_CACHE.bundled()? {
+        profiles.push((Arc::clone(profile), Some(*name), None));
+    }
+
+    let matches = profiles.into_iter().filter_map(|(profile, file, user_index)| {
+        let path_prefix = matching_path(&profile, url.path())?;
+        let fingerprint = profile
+            .fingerprints
+            .iter()
+            .find(|fingerprint| page_contains(doc, fingerprint))?
+            .clone();
+        let matched_by = SiteRuleMatch {
+            host: host.clone(),
+            path_prefix,
+            bundled: profile.bundled,
+            file: file.map(str::to_string),
+            user_index,
+            fingerprint: Some(fingerprint),
+        };
+        Some((profile, matched_by))
+    });
+    Ok(first_profile_match(matches, overrides))
+}
+
+/// Apply `overrides` to the first of `matches`, which are in precedence
+/// order, and list the rest as shadowed.
+fn first_profile_match(
+    mut matches: impl Iterator<Item = (Arc<SiteProfile>, SiteRuleMatch)>, overrides: ProfileOverrides,
+) -> Option<ProfileMatch> {
+    let (profile, matched_by) = matches.next()?;
+    let mut profile = SiteProfile::clone(&profile);
+    overrides.apply(&mut profile);
+    Some(ProfileMatch {
+        profile,
+        matched_by,
+        shadowed: matches
+            .map(|(shadowed, matched_by)| ShadowedSiteRule { name: shadowed.name.clone(), matched_by })
+            .collect(),
+        overrides: overrides.descriptions,
+    })
 }
 
 fn matching_profile_path(profile: &SiteProfile, host: &str, path: &str) -> Option<Option<String>> {
@@ -313,10 +866,14 @@ fn matching_profile_path(profile: &SiteProfile, host: &str, path: &str) -> Optio
     {
         return None;
     }
+    matching_path(profile, path)
+}
+
+fn matching_path(profile: &SiteProfile, path: &str) -> Option<Option<String>> {
     if profile
         .exclude_path_prefixes
         .iter()
-        .any(|prefix| path.starts_with(prefix.as_str()))
+        .any(|prefix| path_matches(prefix, path))
     {
         return None;
     }
@@ -326,22 +883,418 @@ fn matching_profile_path(profile: &SiteProfile, host: &str, path: &str) -> Optio
     profile
         .path_prefixes
         .iter()
-        .find(|prefix| path.starts_with(prefix.as_str()))
+        .find(|prefix| path_matches(prefix, path))
         .cloned()
         .map(Some)
 }
 
-fn host_matches(pattern: &str, subdomains: bool, host: &str) -> bool {
+/// Whether `host` is `pattern`, or one of its subdomains when `subdomains` is
+/// set or the pattern starts with `*.`.
+pub(crate) fn host_matches(pattern: &str, subdomains: bool, host: &str) -> bool {
     let pattern = pattern.trim().trim_start_matches("www.");
+    let (pattern, subdomains) = match pattern.strip_prefix("*.") {
+        Some(pattern) => (pattern, true),
+        None => (pattern, subdomains),
+    };
     host == pattern || (subdomains && host.ends_with(&format!(".{pattern}")))
 }
 
+/// Whether `path` starts with `prefix`, where each `*` in the prefix stands
+/// for any run of characters within one path segment, as in `/@*/p/`.
+fn path_matches(prefix: &str, path: &str) -> bool {
+    let Some((head, tail)) = prefix.split_once('*') else {
+        return path.starts_with(prefix);
+    };
+    let Some(rest) = path.strip_prefix(head) else {
+        return false;
+    };
+    let segment_end = rest.find('/').unwrap_or(rest.len());
+    (0..=segment_end)
+        .filter(|&end| rest.is_char_boundary(end))
+        .any(|end| path_matches(tail, &rest[end..]))
+}
+
+/// Fingerprint of the TOML site profiles compiled into the crate.
+pub fn bundled_site_rules_version() -> String {
+    site_rules_version(bundled_profiles())
+}
+
+/// Fingerprint a set of `(file name, TOML source)` site profiles.
+///
+/// The value is order-independent and matches [`bundled_site_rules_version`]
+/// when the files are identical, so a published bundle can be compared with
+/// the compiled one.
+pub fn site_rules_version<'a>(profiles: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut profiles: Vec<_> = profiles.into_iter().collect();
+    profiles.sort();
+    // FNV-1a keeps the fingerprint stable across Rust releases, unlike `DefaultHasher`.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (name, source) in profiles {
+        for byte in name.bytes().chain([0]).chain(source.bytes()).chain([0]) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// File names listed in a site profile bundle manifest.
+pub fn site_rules_index(manifest: &str) -> Vec<&str> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Names of the site profiles and code extractors compiled into the crate.
+pub fn bundled_site_rules() -> Vec<String> {
+    PROFILE_CACHE
+        .bundled()
+        .unwrap_or_default()
+        .iter()
+        .map(|(_, profile)| profile.name.clone())
+        .chain([HACKER_NEWS_EXTRACTOR.name().to_string()])
+        .collect()
+}
+
+/// Parse and validate a TOML site profile, returning its name.
+///
+/// Profiles without a `name` are reported as `user-profile`.
+pub fn validate_site_profile(source: &str) -> Result<String> {
+    parse_toml_profile("user-profile", source, false).map(|profile| profile.name)
+}
+
+/// Convert a FiveFilters full-text-rss site config into a TOML site profile.
+///
+/// `file_name` is the config's file name, such as `example.com.txt`, which
+/// names the host. A leading dot or `*.`, as in `.example.com.txt` or
+/// `*.example.com.txt`, also matches subdomains, and a path after the host, as
+/// in `example.com/blog/*.txt`, becomes a path prefix. `body`, `strip`, `strip_id_or_class`, `strip_image_src`,
+/// `wrap_in(tag)`, `dissolve`, `next_page_link`, `native_ad_clue`, `title`,
+/// `author`, `date`, `prune`, `autodetect_on_failure`, and `test_url` are
+/// converted; other directives are ignored. An `if_page_contains` line makes
+/// the `body`, `strip`, `strip_id_or_class`, or `next_page_link` line before it
+/// conditional.
+/// Configs without a `body` return [`Error::InvalidSiteProfile`].
+/// [`convert_ftr_site_config`] also reports the directives left out.
+pub fn site_profile_from_ftr(file_name: &str, source: &str) -> Result<String> {
+    convert_ftr_site_config(file_name, source).map(|conversion| conversion.profile)
+}
+
+/// A FiveFilters site config converted by [`convert_ftr_site_config`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FtrConversion {
+    /// The TOML site profile.
+    pub profile: String,
+    /// Directives that were not converted, once per line, named without
+    /// arguments: `http_header(user-agent)` is listed as `http_header`.
+    pub skipped: Vec<String>,
+}
+
+/// Convert a FiveFilters site config like [`site_profile_from_ftr`], and list
+/// the directives it ignored.
+pub fn convert_ftr_site_config(file_name: &str, source: &str) -> Result<FtrConversion> {
+    let stem = file_name.strip_suffix(".txt").unwrap_or(file_name);
+    let (host, path_prefix) = match stem.split_once('/') {
+        Some((host, path)) => (host, Some(format!("/{path}"))),
+        None => (stem, None),
+    };
+    let (host, subdomains) = match host.strip_prefix('.').or_else(|| host.strip_prefix("*.")) {
+        Some(host) => (host, true),
+        None => (host, false),
+    };
+    if host.is_empty() || !host.contains('.') || host.contains('*') {
+        return Err(Error::invalid_site_profile(file_name, "file name must be a host"));
+    }
+
+    let mut directives = FtrDirectives::new();
+    let mut conditionals: Vec<(&str, FtrDirectives)> = Vec::new();
+    // The last rule line, held back in case an `if_page_contains` follows it.
+    let mut pending: Option<(&str, Vec<String>)> = None;
+    let mut wrap_in = toml::Table::new();
+    let mut flags = toml::Table::new();
+    let mut fallback = toml::Table::new();
+    let mut skipped = Vec::new();
+    let mut skip = |name: &str| {
+        let name = name.split_once('(').map_or(name, |(name, _)| name).trim();
+        skipped.push(name.to_string());
+    };
+    for line in source.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.trim() == "if_page_contains" && !value.is_empty() {
+            if let Some((key, values)) = pending.take() {
+                let index = match conditionals.iter().position(|(condition, _)| *condition == value) {
+                    Some(index) => index,
+                    None => {
+                        conditionals.push((value, Vec::new()));
+                        conditionals.len() - 1
+                    }
+                };
+                for value in values {
+                    push_directive(&mut conditionals[index].1, key, value);
+                }
+            } else {
+                skip(name);
+            }
+            continue;
+        }
+        if let Some((key, values)) = pending.take() {
+            for value in values {
+                push_directive(&mut directives, key, value);
+            }
+        }
+        let key = match name.trim() {
+            "body" => "content_roots",
+            "strip" => "remove",
+            "strip_id_or_class" => "remove_id_or_class",
+            "next_page_link" => "next_page",
+            "title" | "author" | "date" | "dissolve" | "native_ad_clue" => name.trim(),
+            "test_url" => {
+                push_directive(&mut directives, "test_urls", value.to_string());
+                continue;
+            }
+            "strip_image_src" if !value.contains(['\'', '"']) => {
+                pending = Some(("remove", vec![format!("//img[contains(@src, '{value}')]")]));
+                continue;
+            }
+            "prune" => {
+                match parse_switch(value) {
+                    Some(prune) => {
+                        flags.insert("prune".into(), prune.into());
+                    }
+                    None => skip(name),
+                }
+                continue;
+            }
+            "autodetect_on_failure" => {
+                match parse_switch(value) {
+                    Some(generic) => {
+                        fallback.insert("generic_on_empty".into(), generic.into());
+                    }
+                    None => skip(name),
+                }
+                continue;
+            }
+            name => {
+                let tag = name.strip_prefix("wrap_in(").and_then(|tag| tag.strip_suffix(')'));
+                match tag.map(str::trim).filter(|tag| !tag.is_empty()) {
+                    Some(tag) => {
+                        let selectors = wrap_in.entry(tag).or_insert_with(|| toml::Value::Array(Vec::new()));
+                        if let Some(selectors) = selectors.as_array_mut() {
+                            selectors.extend(split_xpath_union(value).into_iter().map(toml::Value::from));
+                        }
+                    }
+                    None => skip(name),
+                }
+                continue;
+            }
+        };
+        match key {
+            "remove_id_or_class" => pending = Some((key, vec![value.to_string()])),
+            "title" | "author" | "date" | "dissolve" | "native_ad_clue" => {
+                for xpath in split_xpath_union(value) {
+                    push_directive(&mut directives, key, xpath);
+                }
+            }
+            _ => pending = Some((key, split_xpath_union(value))),
+        }
+    }
+    if let Some((key, values)) = pending {
+        for value in values {
+            push_directive(&mut directives, key, value);
+        }
+    }
+    if !directives.iter().any(|(key, _)| *key == "content_roots") {
+        return Err(Error::invalid_site_profile(
+            file_name,
+            "site config has no body directive",
+        ));
+    }
+
+    let mut profile = toml::Table::new();
+    let mut metadata = toml::Table::new();
+    profile.insert(
+        "name".into(),
+        format!("{host}{}", path_prefix.as_deref().unwrap_or_default()).into(),
+    );
+    profile.insert("hosts".into(), vec![host].into());
+    if subdomains {
+        profile.insert("subdomains".into(), true.into());
+    }
+    if let Some(path_prefix) = path_prefix {
+        profile.insert("path_prefixes".into(), vec![path_prefix].into());
+    }
+    for (key, values) in directives {
+        match key {
+            "title" | "author" | "date" => metadata.insert(key.into(), values.into()),
+            _ => profile.insert(key.into(), values.into()),
+        };
+    }
+    for (name, table) in [
+        ("wrap_in", wrap_in),
+        ("metadata", metadata),
+        ("cleanup", flags),
+        ("fallback", fallback),
+    ] {
+        if !table.is_empty() {
+            profile.insert(name.into(), table.into());
+        }
+    }
+    let conditionals: Vec<_> = conditionals
+        .into_iter()
+        .map(|(condition, directives)| {
+            let mut table = toml::Table::new();
+            table.insert("if_page_contains".into(), condition.into());
+            for (key, values) in directives {
+                table.insert(key.into(), values.into());
+            }
+            toml::Value::from(table)
+        })
+        .collect();
+    if !conditionals.is_empty() {
+        profile.insert("conditional".into(), conditionals.into());
+    }
+    let profile = toml::to_string(&profile).map_err(|error| Error::invalid_site_profile(file_name, error))?;
+    Ok(FtrConversion { profile, skipped })
+}
+
+/// Pages listed in a TOML site profile's `test_urls`, for
+/// [`check_site_profile`].
+pub fn site_profile_test_urls(source: &str) -> Result<Vec<String>> {
+    parse_toml_profile("user-profile", source, false).map(|profile| profile.test_urls)
+}
+
+/// Hosts a TOML site profile applies to, without `www.` or a leading `*.`,
+/// for looking profiles up by host. Profiles with `fingerprints` can match a
+/// page on any host and return `None`.
+pub fn site_profile_hosts(source: &str) -> Result<Option<Vec<String>>> {
+    let profile = parse_toml_profile("user-profile", source, false)?;
+    if !profile.fingerprints.is_empty() {
+        return Ok(None);
+    }
+    let hosts = profile
+        .hosts
+        .iter()
+        .map(|host| {
+            let host = host.trim().trim_start_matches("www.");
+            host.strip_prefix("*.").unwrap_or(host).to_string()
+        })
+        .collect();
+    Ok(Some(hosts))
+}
+
+/// Check a TOML site profile against a page fetched from `url`.
+///
+/// Runs the profile's content roots on the page, skipping the JSON-LD and
+/// known-container stages that can win before site rules, and runs full
+/// extraction without the profile for comparison. The check fails when
+/// another profile wins the URL, the roots select no text, the text is below
+/// `char_threshold`, or the profile has title selectors that match nothing.
+pub fn check_site_profile(
+    source: &str, url: &str, html: &str, options: &ReadabilityOptions,
+) -> Result<SiteProfileCheck> {
+    let profile = parse_toml_profile("user-profile", source, false)?;
+    let base_url = Url::parse(url).map_err(|_| Error::InvalidBaseUrl(url.to_string()))?;
+    let mut with_profile = options.clone();
+    with_profile.site_profiles.insert(0, source.to_string());
+
+    let doc = kuchiki::parse_html().one(html);
+    let matched = matching_page_profile(&base_url, &with_profile, &doc)?
+        .is_some_and(|profile_match| profile_match.matched_by.user_index == Some(0));
+    let text_len = if matched {
+        extract::site_rule_attempt(html, &base_url, &with_profile)?.map_or(0, |rule| rule.attempt.text_len)
+    } else {
+        0
+    };
+    let title = extract_string(&doc, &profile.metadata.title);
+    let baseline_text_len = extract::extract(html, Some(url), options)?.map_or(0, |article| article.length);
+
+    let mut failures = Vec::new();
+    if !matched {
+        failures.push(format!("profile does not match {url}"));
+    } else if text_len == 0 {
+        failures.push("content roots matched no text".to_string());
+    } else if text_len < options.char_threshold {
+        failures.push(format!(
+            "text length {text_len} below char_threshold {}",
+            options.char_threshold
+        ));
+    }
+    if !profile.metadata.title.is_empty() && title.is_none() {
+        failures.push("title selectors matched nothing".to_string());
+    }
+
+    Ok(SiteProfileCheck {
+        name: profile.name,
+        url: url.to_string(),
+        matched,
+        text_len,
+        baseline_text_len,
+        title,
+        failures,
+    })
+}
+
+/// Profile keys and their values, in the order a site config first used them.
+type FtrDirectives<'a> = Vec<(&'a str, Vec<String>)>;
+
+fn push_directive<'a>(directives: &mut FtrDirectives<'a>, key: &'a str, value: String) {
+    if value.is_empty() {
+        return;
+    }
+    match directives.iter_mut().find(|(existing, _)| *existing == key) {
+        Some((_, values)) => values.push(value),
+        None => directives.push((key, vec![value])),
+    }
+}
+
+/// Split an XPath union such as `//h1 | //h2` into its branches, leaving `|`
+/// inside predicates and string literals alone.
+fn split_xpath_union(xpath: &str) -> Vec<String> {
+    let mut branches = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (index, ch) in xpath.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, '[' | '(') => depth += 1,
+            (None, ']' | ')') => depth = depth.saturating_sub(1),
+            (None, '|') if depth == 0 => {
+                branches.push(xpath[start..index].trim().to_string());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    branches.push(xpath[start..].trim().to_string());
+    branches.retain(|branch| !branch.is_empty());
+    branches
+}
+
+/// `(file name, TOML source)` of every profile compiled into the crate.
+fn bundled_profiles() -> impl Iterator<Item = (&'static str, &'static str)> {
+    BUNDLED_PROFILES
+        .iter()
+        .chain(NEWS_PROFILES)
+        .chain(PLATFORM_PROFILES)
+        .copied()
+}
+
 fn parse_toml_profile(name: &str, source: &str, bundled: bool) -> Result<SiteProfile> {
     let profile: TomlSiteProfile = toml::from_str(source).map_err(|error| Error::invalid_site_profile(name, error))?;
-    if profile.hosts.is_empty() {
+    if profile.hosts.is_empty() && profile.fingerprints.is_empty() {
         return Err(Error::invalid_site_profile(
             name,
-            "profile must define at least one host",
+            "profile must define at least one host or fingerprint",
         ));
     }
     if profile.content_roots.is_empty() {
@@ -353,21 +1306,35 @@ fn parse_toml_profile(name: &str, source: &str, bundled: bool) -> Result<SitePro
 
     Ok(SiteProfile {
         name: profile.name.unwrap_or_else(|| name.to_string()),
-        specificity: profile.hosts.iter().map(|host| host.len()).max().unwrap_or_default()
-            + usize::from(!profile.subdomains) * 100
+        // Exact hosts beat subdomain matches, and wildcards in path prefixes
+        // do not count toward their length.
+        specificity: profile
+            .hosts
+            .iter()
+            .map(|host| match host.strip_prefix("*.") {
+                Some(host) => host.len(),
+                None => host.len() + usize::from(!profile.subdomains) * 100,
+            })
+            .max()
+            .unwrap_or_default()
             + profile
                 .path_prefixes
                 .iter()
-                .map(|path| path.len())
+                .map(|path| path.len() - path.matches('*').count())
                 .max()
                 .unwrap_or_default(),
         hosts: profile.hosts,
+        fingerprints: profile.fingerprints,
         subdomains: profile.subdomains,
         path_prefixes: profile.path_prefixes,
         exclude_path_prefixes: profile.exclude_path_prefixes,
         content_roots: profile.content_roots,
         remove: profile.remove,
         remove_id_or_class: profile.remove_id_or_class,
+        dissolve: profile.dissolve,
+        wrap_in: profile.wrap_in.into_iter().collect(),
+        next_page: profile.next_page,
+        native_ad_clue: profile.native_ad_clue,
         metadata: MetadataProfile {
             title: profile.metadata.title,
             author: profile.metadata.author,
@@ -375,16 +1342,68 @@ fn parse_toml_profile(name: &str, source: &str, bundled: bool) -> Result<SitePro
             image: profile.metadata.image,
             site_name: profile.metadata.site_name,
             title_suffixes: profile.metadata.title_suffixes,
+            prefer_config: profile.metadata.prefer_config.unwrap_or(true),
         },
         cleanup: CleanupProfile {
             enabled: profile.cleanup.enabled.unwrap_or(true),
             prune: profile.cleanup.prune.unwrap_or(true),
         },
         fallback: FallbackProfile { generic_on_empty: profile.fallback.generic_on_empty.unwrap_or(true) },
+        test_urls: profile.test_urls,
+        conditionals: profile
+            .conditional
+            .into_iter()
+            .map(|conditional| ConditionalRules {
+                if_page_contains: conditional.if_page_contains,
+                rules: SiteProfile {
+                    content_roots: conditional.content_roots,
+                    remove: conditional.remove,
+                    remove_id_or_class: conditional.remove_id_or_class,
+                    next_page: conditional.next_page,
+                    ..SiteProfile::default()
+                },
+            })
+            .collect(),
         bundled,
     })
 }
 
+/// Put the rules of each conditional block whose `if_page_contains` matches
+/// the page ahead of the profile's own, and return the matched expressions.
+fn apply_conditionals(doc: &NodeRef, profile: &mut SiteProfile) -> Vec<String> {
+    let mut matched = Vec::new();
+    let mut rules = SiteProfile::default();
+    for mut conditional in std::mem::take(&mut profile.conditionals) {
+        if !page_contains(doc, &conditional.if_page_contains) {
+            continue;
+        }
+        for directive in CONDITIONAL_DIRECTIVES {
+            if let (Some(list), Some(added)) = (directive.list(&mut rules), directive.list(&mut conditional.rules)) {
+                list.append(added);
+            }
+        }
+        matched.push(conditional.if_page_contains);
+    }
+    for directive in CONDITIONAL_DIRECTIVES {
+        if let (Some(list), Some(added)) = (directive.list(profile), directive.list(&mut rules)) {
+            list.splice(0..0, added.drain(..));
+        }
+    }
+    matched
+}
+
+/// Whether `selector` matches an element, or an attribute for `.../@attr`.
+fn page_contains(doc: &NodeRef, selector: &str) -> bool {
+    selector_to_query(selector).is_some_and(|query| {
+        dom::select_iter(doc, &query.selector).any(|node| {
+            query
+                .attr
+                .as_deref()
+                .is_none_or(|attr| dom::attr(&node, attr).is_some())
+        })
+    })
+}
+
 fn apply_removals(doc: &NodeRef, profile: &SiteProfile) -> usize {
     let mut removals = 0;
     for pattern in &profile.remove_id_or_class {
@@ -414,6 +1433,32 @@ fn apply_removals(doc: &NodeRef, profile: &SiteProfile) -> usize {
     removals
 }
 
+/// Run the FiveFilters `wrap_in` and `dissolve` directives: wrap each match
+/// in a new element, then replace each `dissolve` match with its children.
+fn apply_rewrites(doc: &NodeRef, profile: &SiteProfile) {
+    for (tag, selectors) in &profile.wrap_in {
+        for node in element_matches(doc, selectors) {
+            if let Some(wrapper) = dom::new_element(tag) {
+                node.insert_before(wrapper.clone());
+                wrapper.append(node);
+            }
+        }
+    }
+    for node in element_matches(doc, &profile.dissolve) {
+        dom::replace_with_children(&node);
+    }
+}
+
+/// Elements matched by any of `selectors`, skipping attribute selectors.
+fn element_matches(doc: &NodeRef, selectors: &[String]) -> Vec<NodeRef> {
+    selectors
+        .iter()
+        .filter_map(|selector| selector_to_query(selector))
+        .filter(|query| query.attr.is_none())
+        .flat_map(|query| dom::select_nodes(doc, &query.selector))
+        .collect()
+}
+
 fn select_first_non_empty(doc: &NodeRef, selectors: &[String]) -> Vec<NodeRef> {
     for selector in selectors {
         let Some(query) = selector_to_query(selector) else {
@@ -422,8 +1467,7 @@ fn select_first_non_empty(doc: &NodeRef, selectors: &[String]) -> Vec<NodeRef> {
         if query.attr.is_some() {
             continue;
         }
-        let nodes = dom::select_nodes(doc, &query.selector)
-            .into_iter()
+        let nodes = dom::select_iter(doc, &query.selector)
             .filter(|node| !dom::inner_text(node).is_empty())
             .collect::<Vec<_>>();
         if !nodes.is_empty() {
@@ -491,16 +1535,14 @@ fn xpath_to_query(xpath: &str) -> Option<SelectorQuery> {
 }
 
 fn xpath_segment_to_css(segment: &str) -> Option<String> {
-    let plain = Regex::new(r"^([A-Za-z][\w-]*|\*)$").ok()?;
-    if plain.is_match(segment) {
+    if RegexPattern::XPathTagStep.to_regex().is_match(segment) {
         return Some(css_tag(segment));
     }
 
-    let eq = Regex::new(r#"^([A-Za-z][\w-]*|\*)\[@([\w:-]+)='([^']+)'\]$"#).ok()?;
-    if let Some(caps) = eq.captures(segment) {
+    if let Some(caps) = RegexPattern::XPathAttrEqualsStep.to_regex().captures(segment) {
         let tag = caps.get(1)?.as_str();
         let attr = caps.get(2)?.as_str();
-        let value = caps.get(3)?.as_str();
+        let value = caps.get(3).or_else(|| caps.get(4))?.as_str();
         return match attr {
             "id" => Some(if tag == "*" { format!("#{value}") } else { format!("{tag}#{value}") }),
             "class" => {
@@ -515,11 +1557,10 @@ fn xpath_segment_to_css(segment: &str) -> Option<String> {
         };
     }
 
-    let contains = Regex::new(r#"^([A-Za-z][\w-]*|\*)\[contains\(@([\w:-]+), '([^']+)'\)\]$"#).ok()?;
-    if let Some(caps) = contains.captures(segment) {
+    if let Some(caps) = RegexPattern::XPathAttrContainsStep.to_regex().captures(segment) {
         let tag = caps.get(1)?.as_str();
         let attr = caps.get(2)?.as_str();
-        let value = caps.get(3)?.as_str();
+        let value = caps.get(3).or_else(|| caps.get(4))?.as_str();
         return Some(format!(r#"{}[{attr}*="{value}"]"#, css_tag(tag)));
     }
 
@@ -550,6 +1591,7 @@ fn serialize_profile_roots(
         }
     }
     normalize::normalize_article(&roots, metadata.title.as_deref());
+    super::typography::normalize_text(&roots, opts);
 
     let mut content = String::from(r#"<div id="readability-page-1" class="page">"#);
     for node in &roots {
@@ -593,8 +1635,399 @@ fn node_selector(node: &NodeRef) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostics::LintSeverity;
     use kuchiki::traits::TendrilSink;
 
+    #[test]
+    fn bundled_index_lists_every_bundled_profile() {
+        // `index.txt` is the published manifest that `lectito siteconfig` reads.
+        const BUNDLED_INDEX: &str = include_str!("./rules/conf/index.txt");
+        // It also lists the `bundled-configs` profiles, which the CLI compiles in.
+        let mut bundled: Vec<_> = bundled_profiles().map(|(name, _)| name).collect();
+        bundled.sort();
+        let index = site_rules_index(BUNDLED_INDEX);
+        if cfg!(feature = "bundled-configs") {
+            assert_eq!(index, bundled);
+        } else {
+            assert!(bundled.iter().all(|name| index.contains(name)));
+        }
+    }
+
+    #[test]
+    fn converts_fivefilters_site_configs() {
+        let source = "# example.com\n\
+            title: //h1[@class='headline']\n\
+            body: //div[@id='story'] | //article[contains(@class, 'post|entry')]\n\
+            strip: //aside\n\
+            strip_id_or_class: newsletter\n\
+            strip_image_src: /pixel.gif\n\
+            prune: no\n\
+            tidy: no\n\
+            test_url: https://example.com/news/1\n";
+        let profile = site_profile_from_ftr(".example.com.txt", source).unwrap();
+        let parsed = parse_toml_profile("example.com.txt", &profile, false).unwrap();
+
+        assert_eq!(parsed.name, "example.com");
+        assert_eq!(parsed.hosts, ["example.com"]);
+        assert!(parsed.subdomains);
+        assert_eq!(
+            parsed.content_roots,
+            ["//div[@id='story']", "//article[contains(@class, 'post|entry')]"]
+        );
+        assert_eq!(parsed.remove, ["//aside", "//img[contains(@src, '/pixel.gif')]"]);
+        assert_eq!(parsed.remove_id_or_class, ["newsletter"]);
+        assert_eq!(parsed.metadata.title, ["//h1[@class='headline']"]);
+        assert!(!parsed.cleanup.prune);
+        assert_eq!(parsed.test_urls, ["https://example.com/news/1"]);
+
+        assert!(site_profile_from_ftr("example.com.txt", "strip: //aside\n").is_err());
+        assert!(site_profile_from_ftr("README.txt", "body: //article\n").is_err());
+    }
+
+    #[test]
+    fn reports_skipped_ftr_directives() {
+        let source = "body: //article\n\
+            http_header(user-agent): Mozilla/5.0\n\
+            single_page_link: //a[@class='print']\n\
+            if_page_contains: //a[@class='print']\n\
+            strip_image_src: \"pixel\"\n\
+            prune: maybe\n\
+            strip: //aside\n";
+        let conversion = convert_ftr_site_config("example.com.txt", source).unwrap();
+        assert_eq!(
+            conversion.skipped,
+            [
+                "http_header",
+                "single_page_link",
+                "if_page_contains",
+                "strip_image_src",
+                "prune"
+            ]
+        );
+        assert_eq!(
+            conversion.profile,
+            site_profile_from_ftr("example.com.txt", source).unwrap()
+        );
+    }
+
+    #[test]
+    fn applies_if_page_contains_rules() {
+        let source = "body: //article\n\
+            next_page_link: //a[@rel='next']\n\
+            if_page_contains: //div[@class='pagination']\n\
+            strip: //div[@class='promo']\n\
+            if_page_contains: //body[@class='amp']\n\
+            single_page_link: //a[@class='print']\n\
+            if_page_contains: //a[@class='print']\n";
+        let profile = site_profile_from_ftr("example.com.txt", source).unwrap();
+        let parsed = parse_toml_profile("example.com.txt", &profile, false).unwrap();
+        assert!(parsed.next_page.is_empty() && parsed.remove.is_empty());
+        assert_eq!(parsed.conditionals.len(), 2);
+
+        let html = "<html><body class='amp'><div class='promo'>Subscribe</div>\
+                    <article><p>Body</p></article></body></html>";
+        let doc = kuchiki::parse_html().one(html);
+        let mut matched = parsed.clone();
+        assert_eq!(apply_conditionals(&doc, &mut matched), ["//body[@class='amp']"]);
+        assert_eq!(matched.remove, ["//div[@class='promo']"]);
+        assert!(matched.next_page.is_empty());
+
+        let options = ReadabilityOptions { site_profiles: vec![profile.clone()], ..Default::default() };
+        let url = Url::parse("https://example.com/story").unwrap();
+        assert_eq!(profile_next_page_links(&doc, &url, &options).unwrap(), None);
+        let paged = kuchiki::parse_html()
+            .one("<div class='pagination'><a rel='next' href='/story?page=2'>Next</a></div><article></article>");
+        assert_eq!(
+            profile_next_page_links(&paged, &url, &options).unwrap(),
+            Some(vec!["/story?page=2".to_string()])
+        );
+        let ignored = ReadabilityOptions { site_profile_ignores: vec!["next_page_link".to_string()], ..options };
+        assert_eq!(profile_next_page_links(&paged, &url, &ignored).unwrap(), None);
+
+        let paragraph = "<p>The harbour authority confirmed the new tide tables for the coming season.</p>";
+        let live = format!(
+            "<html><body class='liveblog'><article><p>Teaser only.</p></article>\
+             <div id='live'>{}</div></body></html>",
+            paragraph.repeat(8)
+        );
+        let profile = "hosts = [\"example.com\"]\ncontent_roots = [\"//article\"]\n\n\
+                       [[conditional]]\nif_page_contains = \"//body[@class='liveblog']\"\n\
+                       content_roots = [\"//div[@id='live']\"]\n";
+        let options = ReadabilityOptions { site_profiles: vec![profile.to_string()], ..Default::default() };
+        let report = extract::extract_with_diagnostics(&live, Some(url.as_str()), &options).unwrap();
+        let site_rule = report.diagnostics.site_rule.unwrap();
+        assert_eq!(site_rule.conditions, ["//body[@class='liveblog']"]);
+        assert!(report.article.unwrap().text_content.contains("tide tables"));
+    }
+
+    #[test]
+    fn wraps_and_dissolves_fivefilters_matches() {
+        let source = "body: //article\n\
+            wrap_in(blockquote): //div[@class='pullquote']\n\
+            dissolve: //font | //span[@class='dropcap']\n";
+        let profile = site_profile_from_ftr("example.com.txt", source).unwrap();
+        let parsed = parse_toml_profile("example.com.txt", &profile, false).unwrap();
+        assert_eq!(parsed.dissolve, ["//font", "//span[@class='dropcap']"]);
+        assert_eq!(
+            parsed.wrap_in,
+            [("blockquote".to_string(), vec!["//div[@class='pullquote']".to_string()])]
+        );
+
+        let doc = kuchiki::parse_html().one(
+            "<article><p><span class='dropcap'>O</span>nce <font color='red'>upon</font> a time.</p>\
+             <div class='pullquote'>Quoted</div></article>",
+        );
+        apply_rewrites(&doc, &parsed);
+        let article = dom::select_first(&doc, "article").unwrap();
+        assert_eq!(
+            serialize::serialize_children(&article).unwrap(),
+            "<p>Once upon a time.</p><blockquote><div class=\"pullquote\">Quoted</div></blockquote>"
+        );
+    }
+
+    #[test]
+    fn applies_platform_presets_by_generator_fingerprint() {
+        let paragraph =
+            "<p>Install the command line tool, then point it at a running server to sync the schema and seed data.</p>";
+        let page = |generator: &str| {
+            format!(
+                "<html><head><meta name=\"generator\" content=\"{generator}\"></head><body>\
+                 <nav class=\"theme-doc-breadcrumbs\">Home / Guides / Setup</nav>\
+                 <article><div class=\"theme-doc-markdown markdown\"><h1>Setup</h1>{}</div>\
+                 <nav class=\"pagination-nav\">Previous: Introduction</nav></article></body></html>",
+                paragraph.repeat(6)
+            )
+        };
+        let url = "https://docs.example.dev/setup";
+
+        let report =
+            extract::extract_with_diagnostics(&page("Docusaurus v3.5.2"), Some(url), &Default::default()).unwrap();
+        let site_rule = report.diagnostics.site_rule.unwrap();
+        assert_eq!(site_rule.name, "docusaurus");
+        assert_eq!(site_rule.matched_by.file.as_deref(), Some("docusaurus.toml"));
+        assert_eq!(
+            site_rule.matched_by.fingerprint.as_deref(),
+            Some("meta[name='generator'][content^='Docusaurus']")
+        );
+        let article = report.article.unwrap();
+        assert!(article.text_content.contains("sync the schema"));
+        assert!(!article.text_content.contains("Previous: Introduction"));
+
+        let options = ReadabilityOptions {
+            site_profiles: vec!["hosts = [\"docs.example.dev\"]\ncontent_roots = [\"div.markdown\"]\n".to_string()],
+            ..Default::default()
+        };
+        let report = extract::extract_with_diagnostics(&page("Docusaurus v3.5.2"), Some(url), &options).unwrap();
+        let site_rule = report.diagnostics.site_rule.unwrap();
+        assert_eq!(site_rule.matched_by.user_index, Some(0));
+        assert_eq!(site_rule.matched_by.fingerprint, None);
+
+        let report = extract::extract_with_diagnostics(&page("Jekyll"), Some(url), &Default::default()).unwrap();
+        assert!(report.diagnostics.site_rule.is_none());
+    }
+
+    #[test]
+    fn flags_pages_matching_native_ad_clues_as_sponsored() {
+        let source = "body: //article\nnative_ad_clue: //div[@class='sponsored-label'] | //meta[@name='sponsored']\n";
+        let profile = site_profile_from_ftr("example.com.txt", source).unwrap();
+        let paragraph = "<p>The harbour authority confirmed the new tide tables, and the ferry schedule will follow them from next month.</p>";
+        let page = |label: &str| {
+            format!(
+                "<html><head><title>Tides</title></head><body>{label}<article>{}</article></body></html>",
+                paragraph.repeat(6)
+            )
+        };
+        let url = "https://example.com/tides";
+        let options = ReadabilityOptions { site_profiles: vec![profile], ..Default::default() };
+
+        let sponsored = page("<div class='sponsored-label'>Paid post</div>");
+        let report = extract::extract_with_diagnostics(&sponsored, Some(url), &options).unwrap();
+        assert_eq!(report.article.unwrap().is_sponsored, Some(true));
+        assert!(
+            report
+                .diagnostics
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("sponsored"))
+        );
+
+        let article = extract::extract(&page(""), Some(url), &options).unwrap().unwrap();
+        assert_eq!(article.is_sponsored, Some(false));
+        let article = extract::extract(&sponsored, Some("https://example.org/tides"), &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(article.is_sponsored, None);
+    }
+
+    #[test]
+    fn parsed_profiles_are_shared_across_threads() {
+        let source = "name = \"shared\"\nhosts = [\"shared.example\"]\ncontent_roots = [\"article\"]\n";
+        let cache = ProfileCache::default();
+        let first = cache.user("user-profile-0", source).unwrap();
+        std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| cache.user("user-profile-0", source).unwrap()))
+                .collect();
+            for thread in threads {
+                assert!(Arc::ptr_eq(&first, &thread.join().unwrap()));
+            }
+        });
+
+        let url = Url::parse("https://shared.example/post").unwrap();
+        let options = ReadabilityOptions {
+            site_profiles: vec![source.to_string()],
+            site_profile_overrides: vec!["remove=.promo".to_string()],
+            ..Default::default()
+        };
+        let profile_match = matching_profile(&url, &options).unwrap().unwrap();
+        assert_eq!(profile_match.profile.remove, [".promo"]);
+        assert!(PROFILE_CACHE.user("user-profile-0", source).unwrap().remove.is_empty());
+    }
+
+    #[test]
+    fn profile_cache_drops_the_least_recently_used_profile() {
+        let source = |host: &str| format!("hosts = [\"{host}\"]\ncontent_roots = [\"article\"]\n");
+        let (a, b, c) = (source("a.example"), source("b.example"), source("c.example"));
+        let cache = ProfileCache::with_limit(2);
+        let first = cache.user("a", &a).unwrap();
+        cache.user("b", &b).unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.user("a", &a).unwrap()));
+
+        cache.user("c", &c).unwrap();
+        let user = cache.user.read().unwrap();
+        let mut names: Vec<_> = user.values().map(|cached| &*cached.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["a", "c"]);
+    }
+
+    #[test]
+    fn lists_profile_hosts_for_lookup() {
+        let hosts = "hosts = [\"www.example.com\", \"*.substack.com\"]\ncontent_roots = [\"article\"]\n";
+        assert_eq!(
+            site_profile_hosts(hosts).unwrap(),
+            Some(vec!["example.com".to_string(), "substack.com".to_string()])
+        );
+        let fingerprinted = "fingerprints = [\"meta[name=generator]\"]\ncontent_roots = [\"article\"]\n";
+        assert_eq!(site_profile_hosts(fingerprinted).unwrap(), None);
+    }
+
+    #[test]
+    fn checks_profiles_against_their_test_urls() {
+        let paragraph = "<p>The harbour authority confirmed the new tide tables, and the ferry schedule will follow them from next month.</p>";
+        let html = format!(
+            "<html><body><nav><a href='/'>Home</a></nav><h1>Tide tables</h1>\
+             <div itemprop='articleBody'>{}</div></body></html>",
+            paragraph.repeat(6)
+        );
+        let url = "https://www.example.com/news/tides";
+        let profile = format!(
+            "name = \"example\"\nhosts = [\"example.com\"]\ntest_urls = [\"{url}\"]\n\
+             content_roots = [\"//div[@itemprop='articleBody']\"]\n\n[metadata]\ntitle = [\"//h1\"]\n"
+        );
+        assert_eq!(site_profile_test_urls(&profile).unwrap(), [url]);
+
+        let options = ReadabilityOptions::default();
+        let check = check_site_profile(&profile, url, &html, &options).unwrap();
+        assert!(check.passed(), "{:?}", check.failures);
+        assert!(check.matched);
+        assert!(check.text_len >= options.char_threshold);
+        assert!(check.baseline_text_len > 0);
+        assert!(check.title.is_some());
+
+        let broken = profile.replace("articleBody", "missing").replace("//h1", "//h6");
+        let check = check_site_profile(&broken, url, &html, &options).unwrap();
+        assert_eq!(
+            check.failures,
+            ["content roots matched no text", "title selectors matched nothing"]
+        );
+
+        let check = check_site_profile(&profile, "https://example.org/news/tides", &html, &options).unwrap();
+        assert!(!check.matched);
+        assert!(!check.passed());
+    }
+
+    #[test]
+    fn lints_site_profiles() {
+        for (name, source) in bundled_profiles() {
+            assert_eq!(lint_site_profile(source), [], "{name}");
+        }
+
+        let profile = "name = \"example\"\nhosts = [\"example.com\", \"https://example.org\"]\n\
+                       exclude_path_prefixes = [\"/\"]\n\
+                       content_roots = [\"article\", \"//div[last()]\", \"article\", \"p >\"]\n\
+                       remove = [\"article\"]\ntest_urls = [\"https://example.net/a\"]\n\n\
+                       [metadata]\ntitle = [\"//h1\"]\nsubtitle = [\"h2\"]\n";
+        let lints: Vec<_> = lint_site_profile(profile)
+            .into_iter()
+            .map(|lint| (lint.severity, lint.line, lint.message))
+            .collect();
+        let expected = [
+            (
+                LintSeverity::Error,
+                2,
+                "host `https://example.org` has a scheme, port, or path and never matches",
+            ),
+            (
+                LintSeverity::Warning,
+                3,
+                "`exclude_path_prefixes` entry `/` excludes every path under `/`",
+            ),
+            (
+                LintSeverity::Error,
+                4,
+                "`content_roots` selector `//div[last()]` uses XPath outside the supported subset",
+            ),
+            (
+                LintSeverity::Warning,
+                4,
+                "`content_roots` lists `article` more than once",
+            ),
+            (
+                LintSeverity::Error,
+                4,
+                "`content_roots` selector `p >` does not compile",
+            ),
+            (
+                LintSeverity::Warning,
+                4,
+                "content root `article` is also in `remove`, which runs first",
+            ),
+            (
+                LintSeverity::Warning,
+                6,
+                "test URL `https://example.net/a` does not match the profile",
+            ),
+            (LintSeverity::Warning, 10, "unknown key `metadata.subtitle` is ignored"),
+        ];
+        assert_eq!(lints.len(), expected.len(), "{lints:#?}");
+        for (severity, line, message) in expected {
+            assert!(
+                lints.contains(&(severity, Some(line), message.to_string())),
+                "{message}: {lints:#?}"
+            );
+        }
+
+        let lints = lint_site_profile("hosts = [\"example.com\"]\ncontent_roots = \"article\"\n");
+        assert_eq!(lints.len(), 1);
+        assert_eq!((lints[0].severity, lints[0].line), (LintSeverity::Error, Some(2)));
+        let lints = lint_site_profile("hosts = [\"example.com\"\n");
+        assert_eq!(lints[0].severity, LintSeverity::Error);
+        assert!(lints[0].line.is_some());
+    }
+
+    #[test]
+    fn site_rules_version_ignores_order_but_not_content() {
+        let version = bundled_site_rules_version();
+        let mut reversed: Vec<_> = bundled_profiles().collect();
+        reversed.reverse();
+
+        assert_eq!(site_rules_version(reversed), version);
+        assert_ne!(
+            site_rules_version(bundled_profiles().map(|(name, _)| (name, ""))),
+            version
+        );
+    }
+
     #[test]
     fn converts_focused_xpath_subset_to_css() {
         assert_eq!(
@@ -611,6 +2044,12 @@ mod tests {
                 .selector,
             r#"article[class*="markdown-body"]"#
         );
+        assert_eq!(
+            xpath_to_query(r#"//div[@class="post-body"]//section[contains(@id,"story")]"#)
+                .unwrap()
+                .selector,
+            r#"div.post-body section[id*="story"]"#
+        );
         let query = xpath_to_query("//p[@class='entry-posted']//abbr[@class='published']/@title").unwrap();
         assert_eq!(query.selector, "p.entry-posted abbr.published");
         assert_eq!(query.attr.as_deref(), Some("title"));
@@ -636,16 +2075,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wildcard_hosts_and_path_patterns_match() {
+        assert!(host_matches("*.substack.com", false, "writer.substack.com"));
+        assert!(host_matches("*.substack.com", false, "substack.com"));
+        assert!(!host_matches("*.substack.com", false, "notsubstack.com"));
+        assert!(path_matches("/@*/p/", "/@writer/p/essay"));
+        assert!(path_matches("/blog/*", "/blog/"));
+        assert!(!path_matches("/@*/p/", "/@writer/notes/p/essay"));
+
+        let wildcard = site_profile_from_ftr("*.substack.com.txt", "body: //article\n").unwrap();
+        let parsed = parse_toml_profile("substack", &wildcard, false).unwrap();
+        assert_eq!(
+            (parsed.hosts.as_slice(), parsed.subdomains),
+            (["substack.com".to_string()].as_slice(), true)
+        );
+        let blog = site_profile_from_ftr("example.com/blog/*.txt", "body: //main\n").unwrap();
+        let parsed = parse_toml_profile("blog", &blog, false).unwrap();
+        assert_eq!(parsed.name, "example.com/blog/*");
+        assert_eq!(parsed.path_prefixes, ["/blog/*"]);
+
+        let options = ReadabilityOptions {
+            site_profiles: vec![
+                "name = \"platform\"\nhosts = [\"*.example.org\"]\ncontent_roots = [\"article\"]\n".to_string(),
+                "name = \"flagship\"\nhosts = [\"news.example.org\"]\ncontent_roots = [\"main\"]\n".to_string(),
+                "name = \"posts\"\nhosts = [\"*.example.org\"]\npath_prefixes = [\"/p/*/\"]\ncontent_roots = [\"main\"]\n"
+                    .to_string(),
+            ],
+            ..Default::default()
+        };
+        let name = |url: &str| {
+            matching_profile(&Url::parse(url).unwrap(), &options)
+                .unwrap()
+                .map(|profile_match| profile_match.profile.name)
+        };
+        assert_eq!(name("https://writer.example.org/about").as_deref(), Some("platform"));
+        assert_eq!(name("https://news.example.org/about").as_deref(), Some("flagship"));
+        assert_eq!(name("https://writer.example.org/p/essay/1").as_deref(), Some("posts"));
+        assert_eq!(name("https://example.net/about"), None);
+    }
+
     #[test]
     fn bundled_profiles_are_valid_toml_profiles() {
-        for (name, source) in BUNDLED_PROFILES {
+        for (name, source) in bundled_profiles() {
             let profile = parse_toml_profile(name, source, true).unwrap();
-            assert!(!profile.hosts.is_empty(), "{name} hosts");
+            assert!(
+                !profile.hosts.is_empty() || !profile.fingerprints.is_empty(),
+                "{name} hosts or fingerprints"
+            );
             assert!(!profile.content_roots.is_empty(), "{name} content roots");
             assert!(profile.bundled, "{name} bundled");
         }
     }
 
+    #[cfg(feature = "bundled-configs")]
+    #[test]
+    fn news_profiles_match_representative_pages() {
+        for (fixture, url, name) in [
+            ("bbc-1", "https://www.bbc.co.uk/news/world-us-canada-33646542", "bbc"),
+            (
+                "guardian-1",
+                "https://www.theguardian.com/business/2015/sep/20/story",
+                "guardian",
+            ),
+            ("nytimes-3", "https://www.nytimes.com/2019/01/21/story.html", "nytimes"),
+            ("nytimes-4", "https://www.nytimes.com/2019/03/01/story.html", "nytimes"),
+            (
+                "telegraph",
+                "https://www.telegraph.co.uk/news/2017/11/30/story/",
+                "telegraph",
+            ),
+            ("theverge", "https://www.theverge.com/2023/11/1/story", "theverge"),
+            (
+                "wapo-1",
+                "https://www.washingtonpost.com/world/story.html",
+                "washingtonpost",
+            ),
+            (
+                "wapo-2",
+                "https://www.washingtonpost.com/politics/story.html",
+                "washingtonpost",
+            ),
+        ] {
+            let source = lectito_fixtures::load_fixture(fixture).unwrap().source;
+            let doc = kuchiki::parse_html().one(source.as_str());
+            let url = Url::parse(url).unwrap();
+            let profile = matching_profile(&url, &ReadabilityOptions::default())
+                .unwrap()
+                .unwrap()
+                .profile;
+
+            assert_eq!(profile.name, name, "{fixture}");
+            assert!(
+                !select_first_non_empty(&doc, &profile.content_roots).is_empty(),
+                "{name} roots match {fixture}"
+            );
+        }
+    }
+
     #[test]
     fn user_profile_overrides_bundled_profile() {
         let options = ReadabilityOptions {
@@ -668,6 +2195,35 @@ mod tests {
         assert_eq!(rule.content_roots, vec!["#custom"]);
     }
 
+    #[test]
+    fn reports_where_the_matched_profile_came_from() {
+        let options = ReadabilityOptions {
+            site_profiles: vec![
+                "hosts = [\"example.com\"]\ncontent_roots = [\"main\"]".to_string(),
+                "name = \"wiki\"\nhosts = [\"wikipedia.org\"]\nsubdomains = true\ncontent_roots = [\"#custom\"]"
+                    .to_string(),
+            ],
+            site_profile_overrides: vec!["prune=no".to_string()],
+            site_profile_ignores: vec!["remove".to_string()],
+            ..Default::default()
+        };
+        let profile_match = matching_profile(&Url::parse("https://en.wikipedia.org/wiki/Rust").unwrap(), &options)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(profile_match.profile.name, "wiki");
+        assert_eq!(profile_match.matched_by.user_index, Some(1));
+        assert_eq!(profile_match.matched_by.file, None);
+        assert_eq!(profile_match.shadowed.len(), 1);
+        assert_eq!(profile_match.shadowed[0].name, "wikipedia");
+        assert_eq!(
+            profile_match.shadowed[0].matched_by.file.as_deref(),
+            Some("wikipedia.org.toml")
+        );
+        assert!(profile_match.shadowed[0].matched_by.bundled);
+        assert_eq!(profile_match.overrides, ["ignore remove", "set prune=no"]);
+    }
+
     #[test]
     fn path_specific_profile_beats_host_profile() {
         let options = ReadabilityOptions {
@@ -833,6 +2389,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn profile_metadata_precedence_is_configurable() {
+        let html = r#"<html><body><h1>Real Headline</h1><span class="by">Staff Writer</span>
+            <main><p>Article text long enough to keep as the profile root.</p></main></body></html>"#;
+        let url = Url::parse("https://example.com/post").unwrap();
+        let page = Metadata { title: Some("SEO Title | Example".to_string()), byline: None, ..Default::default() };
+        let extract = |prefer: &str| {
+            let options = ReadabilityOptions {
+                site_profiles: vec![format!(
+                    r#"
+                    hosts = ["example.com"]
+                    content_roots = ["main"]
+
+                    [metadata]
+                    title = ["h1"]
+                    author = ["span.by"]
+                    {prefer}
+                    "#
+                )],
+                ..Default::default()
+            };
+            let document = kuchiki::parse_html().one(html);
+            extract_with_site_rule(&document, Some(&url), &options, &page)
+                .unwrap()
+                .unwrap()
+                .attempt
+                .metadata
+        };
+
+        let metadata = extract("prefer_config_metadata = true");
+        assert_eq!(metadata.title.as_deref(), Some("Real Headline"));
+        assert_eq!(metadata.byline.as_deref(), Some("Staff Writer"));
+        assert_eq!(extract("").title.as_deref(), Some("Real Headline"));
+
+        let metadata = extract("prefer_config = false");
+        assert_eq!(metadata.title.as_deref(), Some("SEO Title | Example"));
+        assert_eq!(metadata.byline.as_deref(), Some("Staff Writer"));
+    }
+
+    #[test]
+    fn run_overrides_change_the_matched_profile() {
+        let url = Url::parse("https://example.com/post").unwrap();
+        let options = ReadabilityOptions {
+            site_profiles: vec![
+                r#"
+                hosts = ["example.com"]
+                content_roots = ["article"]
+                remove = [".ad"]
+                remove_id_or_class = ["share"]
+
+                [cleanup]
+                prune = false
+                "#
+                .to_string(),
+            ],
+            site_profile_overrides: vec![
+                "prune=yes".to_string(),
+                "strip=.promo".to_string(),
+                "metadata.title=h1".to_string(),
+                "strip = .footer".to_string(),
+            ],
+            site_profile_ignores: vec!["strip_id_or_class".to_string()],
+            ..Default::default()
+        };
+        let profile = matching_profile(&url, &options).unwrap().unwrap().profile;
+        assert!(profile.cleanup.prune);
+        assert_eq!(profile.remove, [".promo", ".footer"]);
+        assert!(profile.remove_id_or_class.is_empty());
+        assert_eq!(profile.metadata.title, ["h1"]);
+        assert_eq!(profile.content_roots, ["article"]);
+
+        for (overrides, ignores) in [
+            (vec!["prune=maybe".to_string()], Vec::new()),
+            (vec!["prune".to_string()], Vec::new()),
+            (Vec::new(), vec!["hosts".to_string()]),
+        ] {
+            let options = ReadabilityOptions {
+                site_profile_overrides: overrides,
+                site_profile_ignores: ignores,
+                ..options.clone()
+            };
+            assert!(matches!(
+                matching_profile(&url, &options),
+                Err(Error::InvalidSiteProfile { .. })
+            ));
+        }
+    }
+
     #[test]
     fn hacker_news_listing_uses_code_extractor() {
         let document = kuchiki::parse_html().one(
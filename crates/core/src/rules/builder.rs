@@ -0,0 +1,388 @@
+use super::{TomlConditionalRules, TomlSiteProfile, parse_toml_profile};
+use crate::diagnostics::LintSeverity;
+use crate::error::{Error, Result};
+
+/// Builder for TOML site profiles, for rules an application learns or edits
+/// at runtime.
+///
+/// Each setter names the profile field it sets, and list setters add one
+/// value per call. [`build`](Self::build) returns the profile as TOML for
+/// [`ReadabilityOptions::site_profiles`](crate::ReadabilityOptions::site_profiles)
+/// or a `.toml` file, and [`to_ftr_string`](Self::to_ftr_string) writes it as
+/// a FiveFilters site config.
+///
+/// ```
+/// use lectito::{ReadabilityOptions, SiteProfileBuilder};
+///
+/// let profile = SiteProfileBuilder::default()
+///     .host("example.com")
+///     .content_root("//article")
+///     .remove_id_or_class("ad")
+///     .build()?;
+/// let options = ReadabilityOptions::builder().site_profile(profile).build()?;
+/// # Ok::<(), lectito::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SiteProfileBuilder {
+    profile: TomlSiteProfile,
+}
+
+impl SiteProfileBuilder {
+    /// Continue building from a TOML profile, such as one loaded from disk.
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let profile = toml::from_str(source).map_err(|error| Error::invalid_site_profile("profile", error))?;
+        Ok(Self { profile })
+    }
+
+    /// Profile name used in diagnostics. Defaults to the first host.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.profile.name = Some(name.into());
+        self
+    }
+
+    /// Add a host the profile matches. `www.` is ignored during matching.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.profile.hosts.push(host.into());
+        self
+    }
+
+    /// Add a selector that identifies the publishing platform, so the profile
+    /// applies on any host whose pages contain a match.
+    pub fn fingerprint(mut self, selector: impl Into<String>) -> Self {
+        self.profile.fingerprints.push(selector.into());
+        self
+    }
+
+    /// Also match subdomains of each host.
+    pub fn subdomains(mut self, subdomains: bool) -> Self {
+        self.profile.subdomains = subdomains;
+        self
+    }
+
+    /// Add a path prefix. Without one the profile matches every path.
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.profile.path_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Add a path prefix that suppresses the profile.
+    pub fn exclude_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.profile.exclude_path_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Add a CSS or XPath selector for the article root. The first selector
+    /// that matches text wins.
+    pub fn content_root(mut self, selector: impl Into<String>) -> Self {
+        self.profile.content_roots.push(selector.into());
+        self
+    }
+
+    /// Add a selector for elements removed before extraction.
+    pub fn remove(mut self, selector: impl Into<String>) -> Self {
+        self.profile.remove.push(selector.into());
+        self
+    }
+
+    /// Add an exact id or class token whose elements are removed.
+    pub fn remove_id_or_class(mut self, token: impl Into<String>) -> Self {
+        self.profile.remove_id_or_class.push(token.into());
+        self
+    }
+
+    /// Add a selector for elements replaced by their children.
+    pub fn dissolve(mut self, selector: impl Into<String>) -> Self {
+        self.profile.dissolve.push(selector.into());
+        self
+    }
+
+    /// Add a selector for elements wrapped in a new `tag` element.
+    pub fn wrap_in(mut self, tag: impl Into<String>, selector: impl Into<String>) -> Self {
+        self.profile
+            .wrap_in
+            .entry(tag.into())
+            .or_default()
+            .push(selector.into());
+        self
+    }
+
+    /// Add a selector for the next-page link.
+    pub fn next_page(mut self, selector: impl Into<String>) -> Self {
+        self.profile.next_page.push(selector.into());
+        self
+    }
+
+    /// Add a selector that marks the page as sponsored content.
+    pub fn native_ad_clue(mut self, selector: impl Into<String>) -> Self {
+        self.profile.native_ad_clue.push(selector.into());
+        self
+    }
+
+    /// Add a page to check the profile against with
+    /// [`check_site_profile`](crate::check_site_profile).
+    pub fn test_url(mut self, url: impl Into<String>) -> Self {
+        self.profile.test_urls.push(url.into());
+        self
+    }
+
+    /// Add the content roots, removals, and next-page selectors of `rules`
+    /// as a block that applies only when the page contains a match for the
+    /// `if_page_contains` selector.
+    pub fn conditional(mut self, if_page_contains: impl Into<String>, rules: SiteProfileBuilder) -> Self {
+        let rules = rules.profile;
+        self.profile.conditional.push(TomlConditionalRules {
+            if_page_contains: if_page_contains.into(),
+            content_roots: rules.content_roots,
+            remove: rules.remove,
+            remove_id_or_class: rules.remove_id_or_class,
+            next_page: rules.next_page,
+        });
+        self
+    }
+
+    /// Add a selector for the title.
+    pub fn title(mut self, selector: impl Into<String>) -> Self {
+        self.profile.metadata.title.push(selector.into());
+        self
+    }
+
+    /// Add a selector for the author.
+    pub fn author(mut self, selector: impl Into<String>) -> Self {
+        self.profile.metadata.author.push(selector.into());
+        self
+    }
+
+    /// Add a selector for the published date.
+    pub fn date(mut self, selector: impl Into<String>) -> Self {
+        self.profile.metadata.date.push(selector.into());
+        self
+    }
+
+    /// Add a selector for the lead image.
+    pub fn image(mut self, selector: impl Into<String>) -> Self {
+        self.profile.metadata.image.push(selector.into());
+        self
+    }
+
+    /// Constant site name reported for every matching page.
+    pub fn site_name(mut self, name: impl Into<String>) -> Self {
+        self.profile.metadata.site_name = Some(name.into());
+        self
+    }
+
+    /// Add a suffix stripped from the end of titles.
+    pub fn title_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.profile.metadata.title_suffixes.push(suffix.into());
+        self
+    }
+
+    /// Whether profile metadata wins over the page's own. Defaults to true.
+    pub fn prefer_config(mut self, prefer: bool) -> Self {
+        self.profile.metadata.prefer_config = Some(prefer);
+        self
+    }
+
+    /// Whether generic cleanup runs on the content roots. Defaults to true.
+    pub fn cleanup(mut self, enabled: bool) -> Self {
+        self.profile.cleanup.enabled = Some(enabled);
+        self
+    }
+
+    /// Whether conditional cleanup runs. Defaults to true.
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.profile.cleanup.prune = Some(prune);
+        self
+    }
+
+    /// Whether generic extraction runs when no content root matches.
+    /// Defaults to true.
+    pub fn generic_on_empty(mut self, fallback: bool) -> Self {
+        self.profile.fallback.generic_on_empty = Some(fallback);
+        self
+    }
+
+    /// Finish building and return the profile as TOML.
+    ///
+    /// Fails with [`Error::InvalidSiteProfile`] when the profile has no host
+    /// or fingerprint, or no content root, or [`lint_site_profile`](crate::lint_site_profile)
+    /// finds an error, such as a selector that does not compile.
+    pub fn build(&self) -> Result<String> {
+        let name = self.display_name();
+        let mut profile = self.profile.clone();
+        if profile.name.is_none() {
+            profile.name = profile.hosts.first().cloned();
+        }
+        let source = toml::to_string(&profile).map_err(|error| Error::invalid_site_profile(&name, error))?;
+        parse_toml_profile(&name, &source, false)?;
+        if let Some(lint) = super::lint_site_profile(&source)
+            .into_iter()
+            .find(|lint| lint.severity == LintSeverity::Error)
+        {
+            return Err(Error::invalid_site_profile(name, lint.message));
+        }
+        Ok(source)
+    }
+
+    /// Write the profile as a FiveFilters site config.
+    ///
+    /// Save it under [`ftr_file_name`](Self::ftr_file_name), which carries
+    /// the host and path prefix. FiveFilters configs name one host and take
+    /// only XPath, so CSS selectors fail with [`Error::InvalidSiteProfile`],
+    /// and other hosts and path prefixes, excluded path prefixes,
+    /// `fingerprints`, `image`, `site_name`, `title_suffixes`,
+    /// `prefer_config`, and `cleanup` are left out.
+    pub fn to_ftr_string(&self) -> Result<String> {
+        self.build()?;
+        let profile = &self.profile;
+        let name = self.display_name();
+        let mut lines = Vec::new();
+        let directives = [
+            ("title", &profile.metadata.title),
+            ("author", &profile.metadata.author),
+            ("date", &profile.metadata.date),
+            ("body", &profile.content_roots),
+            ("strip", &profile.remove),
+            ("next_page_link", &profile.next_page),
+            ("strip_id_or_class", &profile.remove_id_or_class),
+            ("dissolve", &profile.dissolve),
+            ("native_ad_clue", &profile.native_ad_clue),
+        ];
+        for (directive, values) in directives {
+            for value in values {
+                lines.push(ftr_line(&name, directive, value)?);
+            }
+        }
+        for (tag, selectors) in &profile.wrap_in {
+            for selector in selectors {
+                lines.push(ftr_line(&name, &format!("wrap_in({tag})"), selector)?);
+            }
+        }
+        // Each conditional rule is followed by the condition it depends on.
+        for conditional in &profile.conditional {
+            let condition = ftr_line(&name, "if_page_contains", &conditional.if_page_contains)?;
+            let directives = [
+                ("body", &conditional.content_roots),
+                ("strip", &conditional.remove),
+                ("strip_id_or_class", &conditional.remove_id_or_class),
+                ("next_page_link", &conditional.next_page),
+            ];
+            for (directive, values) in directives {
+                for value in values {
+                    lines.push(ftr_line(&name, directive, value)?);
+                    lines.push(condition.clone());
+                }
+            }
+        }
+        if let Some(prune) = profile.cleanup.prune {
+            lines.push(format!("prune: {}", if prune { "yes" } else { "no" }));
+        }
+        if let Some(fallback) = profile.fallback.generic_on_empty {
+            lines.push(format!(
+                "autodetect_on_failure: {}",
+                if fallback { "yes" } else { "no" }
+            ));
+        }
+        for url in &profile.test_urls {
+            lines.push(format!("test_url: {url}"));
+        }
+        lines.push(String::new());
+        Ok(lines.join("\n"))
+    }
+
+    /// File name for [`to_ftr_string`](Self::to_ftr_string) output: the
+    /// first host and path prefix plus `.txt`, with a leading dot when
+    /// subdomains match, as in `.example.com/blog/*.txt`.
+    pub fn ftr_file_name(&self) -> Option<String> {
+        let host = self.profile.hosts.first()?.trim().trim_start_matches("www.");
+        let (host, subdomains) = match host.strip_prefix("*.") {
+            Some(host) => (host, true),
+            None => (host, self.profile.subdomains),
+        };
+        let mut path = self.profile.path_prefixes.first().cloned().unwrap_or_default();
+        if path.ends_with('/') {
+            path.push('*');
+        }
+        Some(format!("{}{host}{path}.txt", if subdomains { "." } else { "" }))
+    }
+
+    fn display_name(&self) -> String {
+        self.profile
+            .name
+            .clone()
+            .or_else(|| self.profile.hosts.first().cloned())
+            .unwrap_or_else(|| "profile".to_string())
+    }
+}
+
+/// One `directive: value` line. Every directive but `strip_id_or_class`
+/// takes XPath in a FiveFilters config.
+fn ftr_line(name: &str, directive: &str, value: &str) -> Result<String> {
+    let value = value.trim();
+    if directive != "strip_id_or_class" && !value.starts_with('/') {
+        return Err(Error::invalid_site_profile(
+            name,
+            format!("`{directive}` takes XPath in a FiveFilters config, got `{value}`"),
+        ));
+    }
+    Ok(format!("{directive}: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{site_profile_from_ftr, validate_site_profile};
+
+    #[test]
+    fn builds_profiles_and_writes_them_as_fivefilters_configs() {
+        let builder = SiteProfileBuilder::default()
+            .host("example.com")
+            .subdomains(true)
+            .content_root("//article")
+            .remove("//aside")
+            .remove_id_or_class("ad")
+            .dissolve("//font")
+            .native_ad_clue("//div[@class='sponsored']")
+            .title("//h1")
+            .prune(false)
+            .conditional(
+                "//div[@class='pagination']",
+                SiteProfileBuilder::default().next_page("//a[@rel='next']"),
+            )
+            .test_url("https://example.com/post");
+        let source = builder.build().unwrap();
+        assert_eq!(validate_site_profile(&source).unwrap(), "example.com");
+        let rebuilt = SiteProfileBuilder::from_toml(&source).unwrap().build().unwrap();
+        assert_eq!(rebuilt, source);
+
+        let ftr = builder.to_ftr_string().unwrap();
+        assert_eq!(
+            ftr,
+            "title: //h1\nbody: //article\nstrip: //aside\nstrip_id_or_class: ad\n\
+             dissolve: //font\nnative_ad_clue: //div[@class='sponsored']\nnext_page_link: //a[@rel='next']\nif_page_contains: //div[@class='pagination']\n\
+             prune: no\ntest_url: https://example.com/post\n"
+        );
+        let file_name = builder.ftr_file_name().unwrap();
+        assert_eq!(file_name, ".example.com.txt");
+        let converted = site_profile_from_ftr(&file_name, &ftr).unwrap();
+        assert_eq!(
+            SiteProfileBuilder::from_toml(&converted).unwrap().build().unwrap(),
+            source
+        );
+    }
+
+    #[test]
+    fn build_rejects_incomplete_or_broken_profiles() {
+        let message = |builder: SiteProfileBuilder| builder.build().unwrap_err().to_string();
+
+        assert!(message(SiteProfileBuilder::default().host("example.com")).contains("content root"));
+        assert!(message(SiteProfileBuilder::default().content_root("article")).contains("host"));
+        assert!(
+            message(SiteProfileBuilder::default().host("example.com").content_root("p >")).contains("does not compile")
+        );
+        let css = SiteProfileBuilder::default()
+            .host("example.com")
+            .content_root("article");
+        assert!(css.build().is_ok());
+        assert!(css.to_ftr_string().unwrap_err().to_string().contains("takes XPath"));
+    }
+}
@@ -60,7 +60,7 @@ impl SiteExtractor for HackerNewsExtractor {
 
 impl HackerNewsExtractor {
     fn try_thread(document: &NodeRef, url: &Url, metadata: &Metadata) -> Result<Option<ExtractAttempt>> {
-        let Some(main_post) = dom::select_nodes(document, ".fatitem").into_iter().next() else {
+        let Some(main_post) = dom::select_first(document, ".fatitem") else {
             return Self::try_listing(document, url, metadata);
         };
 
@@ -98,7 +98,7 @@ impl HackerNewsExtractor {
             content.push_str(&format!("<p><small>{}</small></p>", escape_html(&meta)));
         }
 
-        if let Some(toptext) = dom::select_nodes(&main_post, ".toptext").into_iter().next() {
+        if let Some(toptext) = dom::select_first(&main_post, ".toptext") {
             let html = serialize::serialize_children(&toptext)?;
             if !dom::inner_text(&toptext).is_empty() {
                 content.push_str(r#"<div class="post-text">"#);
@@ -145,7 +145,7 @@ impl HackerNewsExtractor {
         content.push_str(&format!("<h1>{}</h1><ol>", escape_html(&title)));
 
         for row in stories {
-            let Some(title_link) = dom::select_nodes(&row, ".titleline a[href]").into_iter().next() else {
+            let Some(title_link) = dom::select_first(&row, ".titleline a[href]") else {
                 continue;
             };
             let story_title = dom::inner_text(&title_link);
@@ -155,20 +155,18 @@ impl HackerNewsExtractor {
             let story_url = dom::attr(&title_link, "href")
                 .and_then(|href| Self::absolute_or_original(url, &href))
                 .unwrap_or_default();
-            let site = dom::select_nodes(&row, ".sitestr")
-                .into_iter()
-                .next()
+            let site = dom::select_first(&row, ".sitestr")
                 .map(|node| dom::inner_text(&node))
                 .filter(|site| !site.is_empty());
             let subtext = Self::next_element_sibling(&row, "tr");
             let score = subtext
                 .as_ref()
-                .and_then(|node| dom::select_nodes(node, ".score").into_iter().next())
+                .and_then(|node| dom::select_first(node, ".score"))
                 .map(|node| dom::inner_text(&node))
                 .filter(|score| !score.is_empty());
             let author = subtext
                 .as_ref()
-                .and_then(|node| dom::select_nodes(node, ".hnuser").into_iter().next())
+                .and_then(|node| dom::select_first(node, ".hnuser"))
                 .map(|node| dom::inner_text(&node))
                 .filter(|author| !author.is_empty());
             let comment_url = dom::attr(&row, "id")
@@ -212,7 +210,7 @@ impl HackerNewsExtractor {
         }
 
         content.push_str("</ol>");
-        if let Some(more) = dom::select_nodes(document, ".morelink[href]").into_iter().next()
+        if let Some(more) = dom::select_first(document, ".morelink[href]")
             && let Some(href) = dom::attr(&more, "href").and_then(|href| Self::absolute_or_original(url, &href))
         {
             let label = dom::inner_text(&more);
@@ -232,42 +230,32 @@ impl HackerNewsExtractor {
     }
 
     fn hn_post_title(main_post: &NodeRef) -> Option<String> {
-        dom::select_nodes(main_post, ".titleline")
-            .into_iter()
-            .next()
+        dom::select_first(main_post, ".titleline")
             .map(|node| decode_html_entities(&dom::inner_text(&node)))
             .filter(|title| !title.is_empty())
     }
 
     fn hn_story_url(main_post: &NodeRef) -> Option<String> {
-        dom::select_nodes(main_post, ".titleline a[href]")
-            .into_iter()
-            .next()
+        dom::select_first(main_post, ".titleline a[href]")
             .and_then(|node| dom::attr(&node, "href"))
             .filter(|href| !href.is_empty())
     }
 
     fn hn_post_author(main_post: &NodeRef) -> Option<String> {
-        dom::select_nodes(main_post, ".hnuser")
-            .into_iter()
-            .next()
+        dom::select_first(main_post, ".hnuser")
             .map(|node| dom::inner_text(&node))
             .filter(|author| !author.is_empty())
     }
 
     fn hn_post_date(main_post: &NodeRef) -> Option<String> {
-        dom::select_nodes(main_post, ".age")
-            .into_iter()
-            .next()
+        dom::select_first(main_post, ".age")
             .and_then(|node| dom::attr(&node, "title"))
             .and_then(|value| value.split_whitespace().next().map(str::to_string))
             .filter(|date| !date.is_empty())
     }
 
     fn hn_meta_line(main_post: &NodeRef, author: Option<&str>, published: Option<&str>) -> String {
-        let score = dom::select_nodes(main_post, ".score")
-            .into_iter()
-            .next()
+        let score = dom::select_first(main_post, ".score")
             .map(|node| dom::inner_text(&node))
             .unwrap_or_default();
         let mut parts = Vec::new();
@@ -315,8 +303,7 @@ impl HackerNewsExtractor {
     }
 
     fn hn_listing_comment_text(subtext: &NodeRef) -> String {
-        dom::select_nodes(subtext, "a")
-            .into_iter()
+        dom::select_iter(subtext, "a")
             .last()
             .map(|node| dom::inner_text(&node).replace('\u{a0}', " "))
             .unwrap_or_default()
@@ -341,19 +328,15 @@ impl HackerNewsExtractor {
     }
 
     fn hn_comment_from_row(row: &NodeRef) -> Result<Option<HnComment>> {
-        let Some(comment_text) = dom::select_nodes(row, ".commtext").into_iter().next() else {
+        let Some(comment_text) = dom::select_first(row, ".commtext") else {
             return Ok(None);
         };
         let id = dom::attr(row, "id").unwrap_or_default();
-        let author = dom::select_nodes(row, ".hnuser")
-            .into_iter()
-            .next()
+        let author = dom::select_first(row, ".hnuser")
             .map(|node| dom::inner_text(&node))
             .filter(|author| !author.is_empty())
             .unwrap_or_else(|| "[deleted]".to_string());
-        let date = dom::select_nodes(row, ".age")
-            .into_iter()
-            .next()
+        let date = dom::select_first(row, ".age")
             .and_then(|node| dom::attr(&node, "title"))
             .and_then(|value| value.split_whitespace().next().map(str::to_string));
         let content_html = serialize::serialize_node(&comment_text)?;
@@ -368,9 +351,7 @@ impl HackerNewsExtractor {
     }
 
     fn hn_comment_depth(row: &NodeRef) -> usize {
-        dom::select_nodes(row, ".ind img")
-            .into_iter()
-            .next()
+        dom::select_first(row, ".ind img")
             .and_then(|node| dom::attr(&node, "width"))
             .and_then(|width| width.parse::<usize>().ok())
             .unwrap_or(0)
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+
+use kuchiki::Selectors;
+use url::Url;
+
+use super::{
+    SiteProfile, TomlSiteProfile, matching_path, matching_profile_path, parse_toml_profile, path_matches,
+    selector_to_query,
+};
+use crate::diagnostics::{LintSeverity, SiteProfileLint};
+use crate::error::Error;
+
+const PROFILE_KEYS: &[&str] = &[
+    "name",
+    "hosts",
+    "fingerprints",
+    "subdomains",
+    "path_prefixes",
+    "exclude_path_prefixes",
+    "content_roots",
+    "remove",
+    "remove_id_or_class",
+    "dissolve",
+    "wrap_in",
+    "next_page",
+    "next_page_link",
+    "native_ad_clue",
+    "metadata",
+    "cleanup",
+    "fallback",
+    "test_urls",
+    "test_url",
+    "conditional",
+];
+
+const TABLE_KEYS: &[(&str, &[&str])] = &[
+    (
+        "metadata",
+        &[
+            "title",
+            "author",
+            "byline",
+            "date",
+            "published_time",
+            "image",
+            "site_name",
+            "title_suffixes",
+            "prefer_config",
+            "prefer_config_metadata",
+        ],
+    ),
+    ("cleanup", &["enabled", "prune"]),
+    ("fallback", &["generic_on_empty"]),
+    (
+        "conditional",
+        &[
+            "if_page_contains",
+            "content_roots",
+            "remove",
+            "remove_id_or_class",
+            "next_page",
+            "next_page_link",
+        ],
+    ),
+];
+
+/// Check a TOML site profile without running it.
+///
+/// Reports syntax and type errors, keys the loader would silently ignore,
+/// selectors that do not compile or use XPath outside the supported subset,
+/// and rules that can never apply, such as repeated selectors, content roots
+/// that `remove` deletes first, excluded path prefixes, and test URLs the
+/// profile does not match. Lints come back in line order; an empty list means
+/// the profile is clean.
+pub fn lint_site_profile(source: &str) -> Vec<SiteProfileLint> {
+    let table: toml::Table = match toml::from_str(source) {
+        Ok(table) => table,
+        Err(error) => return vec![toml_error(source, &error)],
+    };
+    let mut lints = unknown_keys(source, &table);
+    if let Err(error) = toml::from_str::<TomlSiteProfile>(source) {
+        lints.push(toml_error(source, &error));
+        return lints;
+    }
+    let profile = match parse_toml_profile("profile", source, false) {
+        Ok(profile) => profile,
+        Err(Error::InvalidSiteProfile { message, .. }) => {
+            lints.push(lint(LintSeverity::Error, None, message));
+            return lints;
+        }
+        Err(error) => {
+            lints.push(lint(LintSeverity::Error, None, error.to_string()));
+            return lints;
+        }
+    };
+
+    let selector_lists = [
+        ("fingerprints", &profile.fingerprints),
+        ("content_roots", &profile.content_roots),
+        ("remove", &profile.remove),
+        ("dissolve", &profile.dissolve),
+        ("next_page", &profile.next_page),
+        ("native_ad_clue", &profile.native_ad_clue),
+        ("metadata.title", &profile.metadata.title),
+        ("metadata.author", &profile.metadata.author),
+        ("metadata.date", &profile.metadata.date),
+        ("metadata.image", &profile.metadata.image),
+    ];
+    for (field, selectors) in selector_lists {
+        lints.extend(selector_lints(source, field, selectors));
+    }
+    for (tag, selectors) in &profile.wrap_in {
+        if !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            || !tag.starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            lints.push(lint(
+                LintSeverity::Error,
+                key_line(source, Some("wrap_in"), tag),
+                format!("`wrap_in` tag `{tag}` is not an element name"),
+            ));
+        }
+        lints.extend(selector_lints(source, &format!("wrap_in.{tag}"), selectors));
+    }
+    for conditional in &profile.conditionals {
+        let rules = &conditional.rules;
+        let selector_lists = [
+            ("if_page_contains", &vec![conditional.if_page_contains.clone()]),
+            ("conditional.content_roots", &rules.content_roots),
+            ("conditional.remove", &rules.remove),
+            ("conditional.next_page", &rules.next_page),
+        ];
+        for (field, selectors) in selector_lists {
+            lints.extend(selector_lints(source, field, selectors));
+        }
+    }
+    lints.extend(dead_rule_lints(source, &profile));
+    lints.sort_by_key(|lint| lint.line.unwrap_or(usize::MAX));
+    lints
+}
+
+fn unknown_keys(source: &str, table: &toml::Table) -> Vec<SiteProfileLint> {
+    let mut lints = Vec::new();
+    for (key, value) in table {
+        if !PROFILE_KEYS.contains(&key.as_str()) {
+            lints.push(lint(
+                LintSeverity::Warning,
+                key_line(source, None, key),
+                format!("unknown key `{key}` is ignored"),
+            ));
+            continue;
+        }
+        let Some((_, known)) = TABLE_KEYS.iter().find(|(name, _)| name == key) else {
+            continue;
+        };
+        // `[[conditional]]` is an array of tables; the others are tables.
+        let tables = match value {
+            toml::Value::Array(values) => values.iter().filter_map(toml::Value::as_table).collect(),
+            value => value.as_table().into_iter().collect::<Vec<_>>(),
+        };
+        for inner_key in tables
+            .iter()
+            .flat_map(|table| table.keys())
+            .filter(|inner_key| !known.contains(&inner_key.as_str()))
+        {
+            lints.push(lint(
+                LintSeverity::Warning,
+                key_line(source, Some(key), inner_key),
+                format!("unknown key `{key}.{inner_key}` is ignored"),
+            ));
+        }
+    }
+    lints
+}
+
+fn selector_lints(source: &str, field: &str, selectors: &[String]) -> Vec<SiteProfileLint> {
+    let mut lints = Vec::new();
+    let mut seen = HashSet::new();
+    for selector in selectors {
+        let line = value_line(source, selector, 0);
+        if !seen.insert(selector.trim()) {
+            lints.push(lint(
+                LintSeverity::Warning,
+                value_line(source, selector, 1),
+                format!("`{field}` lists `{selector}` more than once"),
+            ));
+            continue;
+        }
+        let Some(query) = selector_to_query(selector) else {
+            lints.push(lint(
+                LintSeverity::Error,
+                line,
+                format!("`{field}` selector `{selector}` uses XPath outside the supported subset"),
+            ));
+            continue;
+        };
+        if Selectors::compile(&query.selector).is_err() {
+            lints.push(lint(
+                LintSeverity::Error,
+                line,
+                format!("`{field}` selector `{selector}` does not compile"),
+            ));
+        } else if field == "content_roots" && query.attr.is_some() {
+            lints.push(lint(
+                LintSeverity::Warning,
+                line,
+                format!("content root `{selector}` selects an attribute and is skipped"),
+            ));
+        }
+    }
+    lints
+}
+
+fn dead_rule_lints(source: &str, profile: &SiteProfile) -> Vec<SiteProfileLint> {
+    let mut lints = Vec::new();
+    let removed: Vec<_> = profile
+        .remove
+        .iter()
+        .filter_map(|selector| selector_to_query(selector))
+        .filter(|query| query.attr.is_none())
+        .map(|query| query.selector)
+        .collect();
+    let mut roots = HashSet::new();
+    for root in &profile.content_roots {
+        if roots.insert(root.trim()) && selector_to_query(root).is_some_and(|query| removed.contains(&query.selector)) {
+            lints.push(lint(
+                LintSeverity::Warning,
+                value_line(source, root, 0),
+                format!("content root `{root}` is also in `remove`, which runs first"),
+            ));
+        }
+    }
+
+    let mut hosts = HashSet::new();
+    for host in &profile.hosts {
+        let line = value_line(source, host, 0);
+        if !hosts.insert(host.trim().trim_start_matches("www.")) {
+            lints.push(lint(
+                LintSeverity::Warning,
+                value_line(source, host, 1),
+                format!("`hosts` lists `{host}` more than once"),
+            ));
+        } else if host.contains(['/', ':']) {
+            lints.push(lint(
+                LintSeverity::Error,
+                line,
+                format!("host `{host}` has a scheme, port, or path and never matches"),
+            ));
+        } else if host.trim().strip_prefix("*.").unwrap_or(host).contains('*') {
+            lints.push(lint(
+                LintSeverity::Error,
+                line,
+                format!("host `{host}` uses `*` other than as a leading `*.` and never matches"),
+            ));
+        }
+    }
+
+    let prefixes = match profile.path_prefixes.is_empty() {
+        true => vec!["/".to_string()],
+        false => profile.path_prefixes.clone(),
+    };
+    for prefix in &prefixes {
+        if let Some(exclude) = profile
+            .exclude_path_prefixes
+            .iter()
+            .find(|exclude| path_matches(exclude, prefix))
+        {
+            lints.push(lint(
+                LintSeverity::Warning,
+                value_line(source, exclude, 0),
+                format!("`exclude_path_prefixes` entry `{exclude}` excludes every path under `{prefix}`"),
+            ));
+        }
+    }
+
+    for test_url in &profile.test_urls {
+        let line = value_line(source, test_url, 0);
+        let Ok(url) = Url::parse(test_url) else {
+            lints.push(lint(
+                LintSeverity::Error,
+                line,
+                format!("test URL `{test_url}` is not an absolute URL"),
+            ));
+            continue;
+        };
+        let host = url.host_str().unwrap_or_default().trim_start_matches("www.");
+        // Profiles without hosts match by fingerprint, which needs the page.
+        let matches = match profile.hosts.is_empty() {
+            true => matching_path(profile, url.path()).is_some(),
+            false => matching_profile_path(profile, host, url.path()).is_some(),
+        };
+        if !matches {
+            lints.push(lint(
+                LintSeverity::Warning,
+                line,
+                format!("test URL `{test_url}` does not match the profile"),
+            ));
+        }
+    }
+    lints
+}
+
+fn lint(severity: LintSeverity, line: Option<usize>, message: String) -> SiteProfileLint {
+    SiteProfileLint { severity, line, message }
+}
+
+fn toml_error(source: &str, error: &toml::de::Error) -> SiteProfileLint {
+    let line = error
+        .span()
+        .map(|span| source[..span.start.min(source.len())].matches('\n').count() + 1);
+    lint(LintSeverity::Error, line, error.message().trim().to_string())
+}
+
+/// Line of `key` inside the `[table]` section, or at the top level as
+/// `key` or a dotted `table.key`.
+fn key_line(source: &str, table: Option<&str>, key: &str) -> Option<usize> {
+    let wanted = match table {
+        Some(table) => format!("{table}.{key}"),
+        None => key.to_string(),
+    };
+    let mut section = None;
+    source.lines().enumerate().find_map(|(index, line)| {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            section = header.trim_start_matches('[').split(']').next().map(str::trim);
+            return None;
+        }
+        let name = line.split_once('=')?.0.trim().trim_matches('"');
+        let qualified = match section {
+            Some(section) => format!("{section}.{name}"),
+            None => name.to_string(),
+        };
+        (qualified == wanted).then_some(index + 1)
+    })
+}
+
+/// Line of the `nth` quoted occurrence of `value`, counting from zero, or
+/// of the first bare occurrence when the quoted form is escaped.
+fn value_line(source: &str, value: &str, nth: usize) -> Option<usize> {
+    let value = value.trim();
+    let quoted = [format!("\"{value}\""), format!("'{value}'")];
+    let mut seen = 0;
+    source
+        .lines()
+        .position(|line| {
+            seen += quoted
+                .iter()
+                .map(|quoted| line.matches(quoted.as_str()).count())
+                .sum::<usize>();
+            seen > nth
+        })
+        .or_else(|| source.lines().position(|line| line.contains(value)))
+        .map(|index| index + 1)
+}
@@ -0,0 +1,271 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::config::Article;
+use super::epub;
+use super::error::{Error, Result};
+use super::markdown::toml_frontmatter;
+use super::options::{OutputOptions, render};
+
+/// File extensions [`Article::save`] understands and the format each names.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("html", "html"),
+    ("htm", "html"),
+    ("txt", "text"),
+    ("json", "json"),
+    ("epub", "epub"),
+];
+
+impl Article {
+    /// Write the article to `path` in the format its extension names.
+    ///
+    /// `.md` and `.markdown` write Markdown with TOML frontmatter, `.html` and
+    /// `.htm` the cleaned HTML, `.txt` plain text, `.json` the article as JSON,
+    /// and `.epub` an EPUB book. Other extensions fail with [`Error::Config`].
+    ///
+    /// ```no_run
+    /// # let article: lectito::Article = unimplemented!();
+    /// article.save("tides.epub")?;
+    /// # Ok::<(), lectito::Error>(())
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| {
+                EXTENSIONS
+                    .iter()
+                    .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+            })
+            .map(|(_, format)| *format)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "cannot tell the output format of {} (expected .md, .html, .txt, .json, or .epub)",
+                    path.display()
+                ))
+            })?;
+        self.save_with(
+            path,
+            &OutputOptions { format: format.to_string(), ..Default::default() },
+        )
+    }
+
+    /// Write the article to `path` in `output.format`, whatever the extension.
+    ///
+    /// Takes the formats [`crate::LectitoOptions::render`] writes plus `epub`.
+    /// Markdown frontmatter and the EPUB identifier use the canonical URL. The
+    /// file is written next to `path` under a temporary name and renamed into
+    /// place, so readers never see a partial article.
+    pub fn save_with(&self, path: impl AsRef<Path>, output: &OutputOptions) -> Result<()> {
+        self.save_from(path, output, self.canonical_url.as_deref())
+    }
+
+    /// [`Article::save_with`] with `source` as the page URL for Markdown
+    /// frontmatter and the EPUB identifier, such as the URL the page was
+    /// fetched from when it has no canonical link.
+    pub fn save_from(&self, path: impl AsRef<Path>, output: &OutputOptions, source: Option<&str>) -> Result<()> {
+        let path = path.as_ref();
+        write_atomically(path, |writer| self.write_to(writer, output, source))
+    }
+
+    /// Write the article to `writer` in `output.format`, as
+    /// [`Article::save_with`] would write it. `source` is the page URL for
+    /// Markdown frontmatter and the EPUB identifier. Write failures return
+    /// [`Error::Write`].
+    ///
+    /// Markdown, HTML, and text are written from the article's own fields and
+    /// JSON is serialized straight into `writer`. EPUB builds its entries,
+    /// including the chapter and images, before the first write, then writes
+    /// them one zip entry at a time rather than as one finished archive.
+    pub fn write_to(&self, mut writer: impl Write, output: &OutputOptions, source: Option<&str>) -> Result<()> {
+        if output.format == "json" {
+            match output.pretty {
+                true => serde_json::to_writer_pretty(&mut writer, self),
+                false => serde_json::to_writer(&mut writer, self),
+            }
+            .map_err(|error| match error.is_io() {
+                true => Error::Write(error.into()),
+                false => Error::Config(error.to_string()),
+            })?;
+        } else {
+            for chunk in chunks(self, output, source)? {
+                writer.write_all(&chunk).map_err(Error::Write)?;
+            }
+        }
+        writer.flush().map_err(Error::Write)
+    }
+
+    /// [`Article::write_to`] for an async sink, such as a socket or a
+    /// response body in a server. Requires the `tokio` feature.
+    ///
+    /// EPUB entries are built up front as for [`Article::write_to`]. JSON has
+    /// no async serializer, so it is formatted in memory and then written.
+    #[cfg(feature = "tokio")]
+    pub async fn write_to_async(
+        &self, writer: &mut (impl tokio::io::AsyncWrite + Unpin), output: &OutputOptions, source: Option<&str>,
+    ) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        for chunk in chunks(self, output, source)? {
+            writer.write_all(&chunk).await.map_err(Error::Write)?;
+        }
+        writer.flush().await.map_err(Error::Write)
+    }
+}
+
+/// The formatted article as consecutive byte runs. Text formats borrow the
+/// article's own strings and EPUB yields one prebuilt zip entry per run;
+/// frontmatter and JSON are formatted in memory.
+fn chunks<'a>(
+    article: &'a Article, output: &OutputOptions, source: Option<&str>,
+) -> Result<Box<dyn Iterator<Item = Cow<'a, [u8]>> + Send + 'a>> {
+    let borrowed = |text: &'a str| Cow::Borrowed(text.as_bytes());
+    Ok(match output.format.as_str() {
+        "markdown" if output.frontmatter => Box::new(
+            [
+                Cow::Owned(toml_frontmatter(article, source)?.into_bytes()),
+                borrowed(&article.markdown),
+            ]
+            .into_iter(),
+        ),
+        "markdown" => Box::new(std::iter::once(borrowed(&article.markdown))),
+        "html" => Box::new(std::iter::once(borrowed(&article.content))),
+        "text" => Box::new(std::iter::once(borrowed(&article.text_content))),
+        "epub" => Box::new(epub::parts(article, source).map(Cow::Owned)),
+        _ => Box::new(std::iter::once(Cow::Owned(
+            render(article, output, source)?.into_bytes(),
+        ))),
+    })
+}
+
+/// Write to a temporary file next to `path` and rename it into place. I/O
+/// failures, including those of `write`, return [`Error::Save`].
+fn write_atomically(path: &Path, write: impl FnOnce(&mut BufWriter<fs::File>) -> Result<()>) -> Result<()> {
+    let save_error = |source| Error::Save { path: path.to_path_buf(), source };
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("article");
+    let temporary = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    let written = fs::File::create(&temporary).map_err(save_error).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer).map_err(|error| match error {
+            Error::Write(source) => save_error(source),
+            error => error,
+        })?;
+        writer
+            .into_inner()
+            .map_err(|error| error.into_error())
+            .and_then(|file| file.sync_all())
+            .map_err(save_error)
+    });
+    match written.and_then(|()| fs::rename(&temporary, path).map_err(save_error)) {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            let _ = fs::remove_file(&temporary);
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadabilityOptions, extract};
+
+    #[test]
+    fn save_infers_format_from_extension() {
+        let html = "<html><head><title>On Tides</title></head><body><article><p>The tide comes in twice a day.</p></article></body></html>";
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(html, Some("https://example.com/tides"), &options)
+            .unwrap()
+            .unwrap();
+        let directory = std::env::temp_dir().join(format!("lectito-save-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        article.save(directory.join("tides.md")).unwrap();
+        article.save(directory.join("tides.TXT")).unwrap();
+        article.save(directory.join("tides.epub")).unwrap();
+        let markdown = fs::read_to_string(directory.join("tides.md")).unwrap();
+        assert!(markdown.starts_with("+++\n"));
+        assert!(markdown.contains("The tide comes in"));
+        assert_eq!(
+            fs::read_to_string(directory.join("tides.TXT")).unwrap(),
+            article.text_content
+        );
+        assert!(
+            fs::read(directory.join("tides.epub"))
+                .unwrap()
+                .starts_with(b"PK\x03\x04")
+        );
+
+        let json = OutputOptions { format: "json".to_string(), ..Default::default() };
+        article.save_with(directory.join("tides.data"), &json).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_slice(&fs::read(directory.join("tides.data")).unwrap()).unwrap();
+        assert_eq!(saved["title"], "On Tides");
+        article
+            .save_from(
+                directory.join("tides.md"),
+                &OutputOptions::default(),
+                Some("https://example.com/fetched"),
+            )
+            .unwrap();
+        assert!(
+            fs::read_to_string(directory.join("tides.md"))
+                .unwrap()
+                .contains("source = \"https://example.com/fetched\"")
+        );
+
+        let mut streamed = Vec::new();
+        article
+            .write_to(
+                &mut streamed,
+                &OutputOptions::default(),
+                article.canonical_url.as_deref(),
+            )
+            .unwrap();
+        assert_eq!(streamed, markdown.as_bytes());
+        let epub = OutputOptions { format: "epub".to_string(), ..Default::default() };
+        streamed.clear();
+        article.write_to(&mut streamed, &epub, None).unwrap();
+        assert_eq!(streamed, article.to_epub(None));
+
+        assert!(matches!(
+            article.save(directory.join("tides.docx")),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(
+            article.save(directory.join("missing").join("tides.md")),
+            Err(Error::Save { .. })
+        ));
+        let mut names: Vec<_> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["tides.TXT", "tides.data", "tides.epub", "tides.md"]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn write_to_async_matches_write_to() {
+        let html = "<html><head><title>On Tides</title></head><body><article><h1>On Tides</h1><p>The tide comes in twice a day.</p><h2>Spring</h2><p>Spring tides are the largest.</p></article></body></html>";
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(html, Some("https://example.com/tides"), &options)
+            .unwrap()
+            .unwrap();
+
+        for format in ["markdown", "text", "json", "epub"] {
+            let output = OutputOptions { format: format.to_string(), ..Default::default() };
+            let mut expected = Vec::new();
+            article.write_to(&mut expected, &output, None).unwrap();
+            let mut streamed = Vec::new();
+            article.write_to_async(&mut streamed, &output, None).await.unwrap();
+            assert_eq!(streamed, expected, "{format}");
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use serde_json::Value;
+
+use super::config::Article;
+
+impl Article {
+    /// JSON Schema (draft 2020-12) describing an `Article` serialized with
+    /// serde, as the CLI's `--format json` prints it.
+    ///
+    /// Field descriptions come from the field docs. Dates are RFC 3339
+    /// strings. Requires the `schema` feature.
+    pub fn json_schema() -> Value {
+        schemars::schema_for!(Article).to_value()
+    }
+}
+
+#[cfg(feature = "textstats")]
+impl super::textstats::ReadabilityMetrics {
+    /// JSON Schema (draft 2020-12) for serialized readability metrics.
+    pub fn json_schema() -> Value {
+        schemars::schema_for!(super::textstats::ReadabilityMetrics).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn article_schema_lists_serialized_fields() {
+        let schema = Article::json_schema();
+        assert_eq!(schema["title"], "Article");
+        assert_eq!(schema["properties"]["content"]["type"], "string");
+        assert_eq!(schema["properties"]["published"]["format"], "date-time");
+
+        let options = crate::ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = crate::extract("<article><p>Text.</p></article>", None, &options)
+            .unwrap()
+            .unwrap();
+        let article = serde_json::to_value(article).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(
+            article
+                .as_object()
+                .unwrap()
+                .keys()
+                .all(|key| properties.contains_key(key))
+        );
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert!(required.contains(&"markdown"));
+        assert!(!required.contains(&"keywords"));
+    }
+}
@@ -1,18 +1,62 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use kuchiki::NodeRef;
 
-use super::config::ExtractFlags;
-use super::dom;
+use super::config::{ExtractFlags, PatternGroup, ReadabilityOptions};
+use super::diagnostics::Deadline;
+use super::error::{Error, Result};
 use super::patterns::TAGS_TO_SCORE;
-use super::regexes::RegexPattern;
+use super::regexes::{RegexPattern, RegexSetPattern};
+use super::{dom, patterns};
 
 pub struct Candidate {
     pub node: NodeRef,
     pub score: f64,
 }
 
-pub fn score_candidates(document: &NodeRef, flags: ExtractFlags) -> Vec<Candidate> {
+/// Node scores and class weights for one extraction attempt, keyed by node
+/// identity so the candidate, entry-point and sibling passes score each
+/// element once.
+#[derive(Default)]
+pub struct ScoreCache {
+    scores: HashMap<usize, f64>,
+    class_weights: HashMap<usize, i32>,
+}
+
+impl ScoreCache {
+    /// The content score recorded for `node`, if it was scored as a candidate.
+    pub fn score(&self, node: &NodeRef) -> Option<f64> {
+        self.scores.get(&dom::node_id(node)).copied()
+    }
+
+    /// Record the current scores of `candidates`.
+    pub fn record(&mut self, candidates: &[Candidate]) {
+        self.scores.extend(
+            candidates
+                .iter()
+                .map(|candidate| (dom::node_id(&candidate.node), candidate.score)),
+        );
+    }
+
+    /// [`class_weight`], computed once per node.
+    pub fn class_weight(&mut self, node: &NodeRef, flags: ExtractFlags, options: &ReadabilityOptions) -> i32 {
+        if !flags.weight_classes {
+            return 0;
+        }
+        *self
+            .class_weights
+            .entry(dom::node_id(node))
+            .or_insert_with(|| class_weight(node, flags, options))
+    }
+}
+
+/// Elements scored between checks of the extraction deadline.
+const DEADLINE_BATCH: usize = 256;
+
+/// Score candidate roots and return them with the number of elements examined.
+pub fn score_candidates(
+    document: &NodeRef, flags: ExtractFlags, options: &ReadabilityOptions, deadline: &Deadline, cache: &mut ScoreCache,
+) -> Result<(Vec<Candidate>, usize)> {
     let selector = TAGS_TO_SCORE.join(",");
     let mut nodes = dom::select_nodes(document, &selector);
     let mut seen: HashSet<_> = nodes.iter().map(dom::node_id).collect();
@@ -23,9 +67,15 @@ pub fn score_candidates(document: &NodeRef, flags: ExtractFlags) -> Vec<Candidat
             nodes.push(parent);
         }
     }
+    let scanned = nodes.len();
     let mut candidates = Vec::<Candidate>::new();
+    // Candidate index by node identity, so each ancestor is initialized once.
+    let mut index_by_id = HashMap::<usize, usize>::new();
 
-    for node in nodes {
+    for (position, node) in nodes.into_iter().enumerate() {
+        if position % DEADLINE_BATCH == 0 {
+            deadline.check("scoring")?;
+        }
         let text = dom::inner_text(&node);
         if text.chars().count() < 25 {
             continue;
@@ -50,42 +100,45 @@ pub fn score_candidates(document: &NodeRef, flags: ExtractFlags) -> Vec<Candidat
                 2 => 2.0,
                 _ => (level - 1) as f64 * 3.0,
             };
-            let base = initialize_node_score(&ancestor, flags);
-            let id = dom::node_id(&ancestor);
-            if let Some(candidate) = candidates
-                .iter_mut()
-                .find(|candidate| dom::node_id(&candidate.node) == id)
-            {
-                candidate.score += content_score / divider;
-            } else {
-                candidates.push(Candidate { node: ancestor, score: base + content_score / divider });
+            match index_by_id.get(&dom::node_id(&ancestor)) {
+                Some(&index) => candidates[index].score += content_score / divider,
+                None => {
+                    if let Some(limit) = options.max_candidates
+                        && candidates.len() >= limit
+                    {
+                        return Err(Error::MaxCandidatesExceeded { limit });
+                    }
+                    index_by_id.insert(dom::node_id(&ancestor), candidates.len());
+                    let base = initialize_node_score(&ancestor, flags, options, cache);
+                    candidates.push(Candidate { node: ancestor, score: base + content_score / divider });
+                }
             }
         }
     }
 
-    candidates
+    candidates.retain(|candidate| candidate.score >= f64::from(options.min_candidate_score));
+    cache.record(&candidates);
+    Ok((candidates, scanned))
 }
 
-pub fn class_weight(node: &NodeRef, flags: ExtractFlags) -> i32 {
+pub fn class_weight(node: &NodeRef, flags: ExtractFlags, options: &ReadabilityOptions) -> i32 {
     if !flags.weight_classes {
         return 0;
     }
 
+    let builtin = |group| !options.replace_pattern_groups.contains(&group);
     let mut weight = 0;
-    if let Some(class) = dom::attr(node, "class") {
-        if RegexPattern::Negative.to_regex().is_match(&class) {
-            weight -= 25;
-        }
-        if RegexPattern::Positive.to_regex().is_match(&class) {
-            weight += 25;
-        }
-    }
-    if let Some(id) = dom::attr(node, "id") {
-        if RegexPattern::Negative.to_regex().is_match(&id) {
-            weight -= 25;
+    for value in [dom::attr(node, "class"), dom::attr(node, "id")].into_iter().flatten() {
+        let matches = RegexSetPattern::ClassWeight.to_regex_set().matches(&value);
+        if (matches.matched(0) && builtin(PatternGroup::Negative))
+            || patterns::matches_extra(PatternGroup::Negative, &value, options)
+        {
+            weight -= options.class_weight;
         }
-        if RegexPattern::Positive.to_regex().is_match(&id) {
-            weight += 25;
+        if (matches.matched(1) && builtin(PatternGroup::Positive))
+            || patterns::matches_extra(PatternGroup::Positive, &value, options)
+        {
+            weight += options.class_weight;
         }
     }
     weight
@@ -97,8 +150,7 @@ pub fn link_density(node: &NodeRef) -> f64 {
         return 0.0;
     }
 
-    let link_len: f64 = dom::select_nodes(node, "a")
-        .into_iter()
+    let link_len: f64 = dom::select_iter(node, "a")
         .map(|link| {
             let coefficient =
                 if dom::attr(&link, "href").is_some_and(|href| href.starts_with('#')) { 0.3 } else { 1.0 };
@@ -109,10 +161,13 @@ pub fn link_density(node: &NodeRef) -> f64 {
     link_len / text_len as f64
 }
 
-fn initialize_node_score(node: &NodeRef, flags: ExtractFlags) -> f64 {
-    let mut score = class_weight(node, flags) as f64;
+fn initialize_node_score(
+    node: &NodeRef, flags: ExtractFlags, options: &ReadabilityOptions, cache: &mut ScoreCache,
+) -> f64 {
+    let mut score = cache.class_weight(node, flags, options) as f64;
     score += match dom::node_name(node).as_str() {
         "div" | "article" => 5.0,
+        _ if dom::is_custom_element(node) => 5.0,
         "pre" | "td" | "blockquote" => 3.0,
         "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
         "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
@@ -120,3 +175,114 @@ fn initialize_node_score(node: &NodeRef, flags: ExtractFlags) -> f64 {
     };
     score
 }
+
+#[cfg(test)]
+mod tests {
+    use kuchiki::traits::TendrilSink;
+
+    use super::*;
+
+    #[test]
+    fn accumulates_shared_ancestor_scores_once_per_node() {
+        let document = kuchiki::parse_html().one(
+            r#"<html><body><div id="root">
+                <p>First paragraph with enough text, and one comma.</p>
+                <p>Second paragraph with enough text, and one comma.</p>
+            </div></body></html>"#,
+        );
+
+        let (candidates, _) = score_candidates(
+            &document,
+            ExtractFlags::all(),
+            &ReadabilityOptions::default(),
+            &Deadline::start(None),
+            &mut ScoreCache::default(),
+        )
+        .unwrap();
+        let root: Vec<_> = candidates
+            .iter()
+            .filter(|candidate| dom::attr(&candidate.node, "id").as_deref() == Some("root"))
+            .collect();
+
+        assert_eq!(root.len(), 1);
+        // Div base score plus two paragraphs worth one point and one comma each.
+        assert_eq!(root[0].score, 5.0 + 2.0 * 2.0);
+    }
+
+    #[test]
+    fn caches_scores_and_class_weights_per_node() {
+        let document = kuchiki::parse_html().one(
+            r#"<html><body><div id="root" class="content">
+                <p>First paragraph with enough text, and one comma.</p>
+            </div></body></html>"#,
+        );
+        let options = ReadabilityOptions::default();
+        let mut cache = ScoreCache::default();
+
+        score_candidates(
+            &document,
+            ExtractFlags::all(),
+            &options,
+            &Deadline::start(None),
+            &mut cache,
+        )
+        .unwrap();
+        let root = dom::select_first(&document, "#root").unwrap();
+        let weight = cache.class_weight(&root, ExtractFlags::all(), &options);
+        root.as_element()
+            .unwrap()
+            .attributes
+            .borrow_mut()
+            .insert("class", "sidebar".to_string());
+
+        assert_eq!(cache.score(&root), Some(5.0 + 25.0 + 2.0));
+        assert_eq!(weight, 25);
+        // The weight is read once; later passes reuse it.
+        assert_eq!(cache.class_weight(&root, ExtractFlags::all(), &options), 25);
+        assert_eq!(
+            cache.class_weight(
+                &root,
+                ExtractFlags { weight_classes: false, ..ExtractFlags::all() },
+                &options
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn applies_custom_class_patterns_weight_and_min_score() {
+        let document = kuchiki::parse_html().one(
+            r#"<html><body>
+                <div id="root" class="story-copy"><p>First paragraph with enough text, and one comma.</p></div>
+                <div id="other" class="rail-extra"><p>Second paragraph with enough text, and one comma.</p></div>
+            </body></html>"#,
+        );
+        let options = ReadabilityOptions {
+            class_weight: 10,
+            positive_patterns: vec!["Story-Copy".to_string()],
+            negative_patterns: vec!["rail".to_string()],
+            min_candidate_score: 1.0,
+            ..Default::default()
+        };
+
+        let (candidates, _) = score_candidates(
+            &document,
+            ExtractFlags::all(),
+            &options,
+            &Deadline::start(None),
+            &mut ScoreCache::default(),
+        )
+        .unwrap();
+        let score = |id: &str| {
+            candidates
+                .iter()
+                .find(|candidate| dom::attr(&candidate.node, "id").as_deref() == Some(id))
+                .map(|candidate| candidate.score)
+        };
+
+        // Div base score, custom positive weight, and one paragraph with one comma.
+        assert_eq!(score("root"), Some(5.0 + 10.0 + 2.0));
+        // Div base score minus the custom negative weight falls below the minimum.
+        assert_eq!(score("other"), None);
+    }
+}
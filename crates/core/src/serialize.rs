@@ -36,6 +36,9 @@ fn append_text_content(node: &NodeRef, output: &mut String) {
     }
 
     let tag = dom::node_name(node);
+    if matches!(tag.as_str(), "annotation" | "annotation-xml") {
+        return;
+    }
     let block = is_block_boundary(&tag);
     if block {
         ensure_line_break(output);
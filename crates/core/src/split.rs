@@ -0,0 +1,141 @@
+use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+
+use super::config::Article;
+use super::{dom, markdown, serialize};
+
+/// Opening tag of the page div around article content, as extraction writes it.
+const PAGE_OPEN: &str = r#"<div id="readability-page-1" class="page">"#;
+
+impl Article {
+    /// Split the article into one article per section, cutting at each
+    /// heading of `level` (1 to 6).
+    ///
+    /// Content before the first cut becomes the first part and keeps the
+    /// article title; each later part is titled by its heading. Every part
+    /// keeps the article's metadata. Markdown and text are rebuilt from each
+    /// part's HTML, so Markdown options such as `link_style` are not applied
+    /// again. An article without headings at `level` comes back whole.
+    pub fn split_at(&self, level: u8) -> Vec<Article> {
+        let tag = format!("h{}", level.clamp(1, 6));
+        let count = dom::select_iter(&parse(&self.content), &tag).count();
+        if count == 0 {
+            return vec![self.clone()];
+        }
+
+        let mut parts = Vec::new();
+        for index in 0..=count {
+            let document = parse(&self.content);
+            let headings = dom::select_nodes(&document, &tag);
+            let start = index.checked_sub(1).and_then(|start| headings.get(start));
+            if let Some(start) = start {
+                cut_before(start);
+            }
+            if let Some(end) = headings.get(index) {
+                cut_from(end);
+            }
+
+            let Some(body) = dom::select_first(&document, "body") else {
+                continue;
+            };
+            let text_content = serialize::text_content(std::slice::from_ref(&body));
+            if start.is_none() && text_content.trim().is_empty() && !dom::exists(&body, "img") {
+                continue;
+            }
+            let content = match dom::select_first(&body, "body > #readability-page-1") {
+                Some(page) => format!(
+                    "{PAGE_OPEN}{}</div>",
+                    serialize::serialize_children(&page).unwrap_or_default()
+                ),
+                None => serialize::serialize_children(&body).unwrap_or_default(),
+            };
+            let title = start
+                .map(|heading| {
+                    dom::inner_text(heading)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .filter(|title| !title.is_empty())
+                .or_else(|| self.title.clone());
+            parts.push(Article {
+                title,
+                markdown: markdown::html_to_markdown(&content),
+                length: text_content.encode_utf16().count(),
+                text_content,
+                content,
+                ..self.clone()
+            });
+        }
+        parts
+    }
+}
+
+fn parse(content: &str) -> NodeRef {
+    kuchiki::parse_html().one(format!("<html><body>{content}</body></html>"))
+}
+
+/// Remove everything before `node` in document order, keeping its ancestors.
+fn cut_before(node: &NodeRef) {
+    let mut current = node.clone();
+    loop {
+        while let Some(previous) = current.previous_sibling() {
+            previous.detach();
+        }
+        match current.parent() {
+            Some(parent) if dom::node_name(&parent) != "body" => current = parent,
+            _ => break,
+        }
+    }
+}
+
+/// Remove `node` and everything after it in document order, keeping its
+/// ancestors.
+fn cut_from(node: &NodeRef) {
+    let mut current = node.clone();
+    loop {
+        while let Some(next) = current.next_sibling() {
+            next.detach();
+        }
+        match current.parent() {
+            Some(parent) if dom::node_name(&parent) != "body" => current = parent,
+            _ => break,
+        }
+    }
+    node.detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ReadabilityOptions, extract};
+
+    #[test]
+    fn splits_sections_at_heading_level() {
+        let paragraph = "The tide rises and falls twice a day along the whole of the coast.";
+        let html = format!(
+            "<html><head><title>On Tides</title></head><body><article><h1>On Tides</h1><p>{paragraph}</p>\
+             <section><h2>Spring</h2><p>{paragraph}</p><h3>Details</h3><p>Full moon.</p></section>\
+             <section><h2>Neap</h2><p>{paragraph}</p></section></article></body></html>"
+        );
+        let options = ReadabilityOptions { char_threshold: 0, ..Default::default() };
+        let article = extract(&html, Some("https://example.com/tides"), &options)
+            .unwrap()
+            .unwrap();
+
+        let parts = article.split_at(2);
+
+        let titles: Vec<_> = parts.iter().map(|part| part.title.as_deref()).collect();
+        assert_eq!(titles, [Some("On Tides"), Some("Spring"), Some("Neap")]);
+        assert!(!parts[0].text_content.contains("Spring"));
+        assert!(parts[1].markdown.starts_with("## Spring"));
+        assert!(parts[1].markdown.contains("### Details\n\nFull moon."));
+        assert!(!parts[1].text_content.contains("Neap"));
+        assert!(
+            parts[2]
+                .content
+                .starts_with(r#"<div id="readability-page-1" class="page"><article><section><h2>Neap</h2>"#)
+        );
+        assert_eq!(parts[2].domain, article.domain);
+        assert_eq!(article.split_at(4), vec![article.clone()]);
+    }
+}
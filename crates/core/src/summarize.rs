@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+
+use super::config::Article;
+use super::patterns::STOP_WORDS;
+
+/// PageRank damping factor from the TextRank paper.
+const DAMPING: f64 = 0.85;
+const ITERATIONS: usize = 50;
+const CONVERGENCE: f64 = 1e-6;
+
+impl Article {
+    /// Pick the `n_sentences` most central sentences of `text_content` and
+    /// join them in their original order.
+    ///
+    /// Returns an empty string when the article has no sentences or
+    /// `n_sentences` is zero.
+    pub fn summary(&self, n_sentences: usize) -> String {
+        summarize(&self.text_content, n_sentences).join(" ")
+    }
+}
+
+/// Extractive summary of plain text using TextRank over sentences.
+///
+/// Sentences are ranked by PageRank on a graph whose edges weigh shared
+/// content words, then the top `n_sentences` are returned in document order.
+pub fn summarize(text: &str, n_sentences: usize) -> Vec<String> {
+    let sentences = split_sentences(text);
+    if n_sentences == 0 || sentences.is_empty() {
+        return Vec::new();
+    }
+    if sentences.len() <= n_sentences {
+        return sentences.into_iter().map(str::to_string).collect();
+    }
+
+    let words: Vec<HashSet<String>> = sentences.iter().map(|sentence| content_words(sentence)).collect();
+    let weights: Vec<Vec<f64>> = words
+        .iter()
+        .enumerate()
+        .map(|(i, left)| {
+            words
+                .iter()
+                .enumerate()
+                .map(|(j, right)| if i == j { 0.0 } else { similarity(left, right) })
+                .collect()
+        })
+        .collect();
+    let scores = rank(&weights);
+
+    let mut selected: Vec<usize> = (0..sentences.len()).collect();
+    // Stable sort keeps earlier sentences ahead on ties.
+    selected.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    selected.truncate(n_sentences);
+    selected.sort_unstable();
+    selected.into_iter().map(|index| sentences[index].to_string()).collect()
+}
+
+/// Split on paragraph breaks and on `.`, `!`, or `?` followed by whitespace.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    for block in text.lines() {
+        let mut start = 0;
+        let mut chars = block.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            if matches!(c, '.' | '!' | '?')
+                && chars.peek().is_none_or(|(_, next)| next.is_whitespace())
+                && !(c == '.' && is_abbreviation(&block[start..index], &block[index + 1..]))
+            {
+                let end = index + c.len_utf8();
+                sentences.push(block[start..end].trim());
+                start = end;
+            }
+        }
+        sentences.push(block[start..].trim());
+    }
+    sentences.retain(|sentence| sentence.chars().any(char::is_alphanumeric));
+    sentences
+}
+
+/// Initialisms such as `U.S.` or `J.` and periods followed by lowercase text
+/// do not end a sentence.
+fn is_abbreviation(before: &str, after: &str) -> bool {
+    let word = before.rsplit(char::is_whitespace).next().unwrap_or_default();
+    word.chars().count() <= 1 || word.contains('.') || after.trim_start().starts_with(char::is_lowercase)
+}
+
+fn content_words(sentence: &str) -> HashSet<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// TextRank similarity: shared words normalized by sentence length.
+fn similarity(left: &HashSet<String>, right: &HashSet<String>) -> f64 {
+    let shared = left.intersection(right).count();
+    let norm = (left.len() as f64).ln() + (right.len() as f64).ln();
+    if shared == 0 || norm <= 0.0 { 0.0 } else { shared as f64 / norm }
+}
+
+fn rank(weights: &[Vec<f64>]) -> Vec<f64> {
+    let out_weight: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+    let mut scores = vec![1.0; weights.len()];
+    for _ in 0..ITERATIONS {
+        let next: Vec<f64> = (0..weights.len())
+            .map(|i| {
+                let incoming: f64 = (0..weights.len())
+                    .filter(|&j| out_weight[j] > 0.0)
+                    .map(|j| weights[j][i] / out_weight[j] * scores[j])
+                    .sum();
+                (1.0 - DAMPING) + DAMPING * incoming
+            })
+            .collect();
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < CONVERGENCE {
+            break;
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_sentences, summarize};
+
+    #[test]
+    fn splits_sentences_on_punctuation_and_paragraphs() {
+        assert_eq!(
+            split_sentences("First one. Second one? Made in the U.S. by J. Doe, etc. and more.\nNew paragraph!"),
+            [
+                "First one.",
+                "Second one?",
+                "Made in the U.S. by J. Doe, etc. and more.",
+                "New paragraph!"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_central_sentences_in_document_order() {
+        let text = "The river flooded the valley town overnight. \
+                    Residents of the valley town moved to higher ground as the river rose. \
+                    A bakery on Main Street sold out of bread. \
+                    Officials said the river should recede from the town by Friday.";
+
+        let summary = summarize(text, 2);
+
+        assert_eq!(summary.len(), 2);
+        assert!(
+            !summary.iter().any(|sentence| sentence.contains("bakery")),
+            "{summary:?}"
+        );
+        assert!(summary[0].starts_with("The river") || summary[0].starts_with("Residents"));
+        assert!(summarize(text, 0).is_empty());
+        assert_eq!(summarize(text, 10).len(), 4);
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use super::config::Article;
+
+/// Reading-difficulty statistics for extracted article text.
+///
+/// Counts use English heuristics: sentences end at `.`, `!`, or `?`, and
+/// syllables are runs of vowels with a silent final `e` dropped.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ReadabilityMetrics {
+    /// Flesch reading ease. Higher is easier; 60–70 is plain English.
+    pub flesch_reading_ease: f64,
+    /// Flesch-Kincaid US school grade level.
+    pub flesch_kincaid_grade: f64,
+    /// Mean words per sentence.
+    pub average_sentence_length: f64,
+    /// Number of sentences.
+    pub sentence_count: usize,
+    /// Number of words.
+    pub word_count: usize,
+    /// Number of syllables.
+    pub syllable_count: usize,
+}
+
+impl Article {
+    /// Compute reading-difficulty statistics for `text_content`.
+    pub fn readability_metrics(&self) -> ReadabilityMetrics {
+        readability_metrics(&self.text_content)
+    }
+}
+
+/// Compute reading-difficulty statistics for plain text.
+pub fn readability_metrics(text: &str) -> ReadabilityMetrics {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .collect();
+    if words.is_empty() {
+        return ReadabilityMetrics::default();
+    }
+
+    let sentence_count = text
+        .split(['.', '!', '?'])
+        .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+        .count()
+        .max(1);
+    let word_count = words.len();
+    let syllable_count: usize = words.iter().map(|word| syllables(word)).sum();
+
+    let words_per_sentence = word_count as f64 / sentence_count as f64;
+    let syllables_per_word = syllable_count as f64 / word_count as f64;
+
+    ReadabilityMetrics {
+        flesch_reading_ease: 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+        flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+        average_sentence_length: words_per_sentence,
+        sentence_count,
+        word_count,
+        syllable_count,
+    }
+}
+
+fn syllables(word: &str) -> usize {
+    let word: Vec<char> = word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if word.is_empty() {
+        return 1;
+    }
+
+    let is_vowel = |c: &char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in &word {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    // A trailing `e` is usually silent ("make"), except in "-le" endings ("table").
+    let len = word.len();
+    if count > 1 && word[len - 1] == 'e' && !(len > 2 && word[len - 2] == 'l' && !is_vowel(&word[len - 3])) {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{readability_metrics, syllables};
+
+    #[test]
+    fn counts_syllables_with_silent_e() {
+        assert_eq!(syllables("cat"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("readability"), 5);
+    }
+
+    #[test]
+    fn scores_simple_text_as_easy() {
+        let metrics = readability_metrics("The cat sat on the mat. The dog ran to the park!");
+
+        assert_eq!(metrics.sentence_count, 2);
+        assert_eq!(metrics.word_count, 12);
+        assert_eq!(metrics.syllable_count, 12);
+        assert_eq!(metrics.average_sentence_length, 6.0);
+        assert!(metrics.flesch_reading_ease > 100.0, "{metrics:?}");
+        assert!(metrics.flesch_kincaid_grade < 1.0, "{metrics:?}");
+        assert_eq!(readability_metrics("  ").word_count, 0);
+    }
+}
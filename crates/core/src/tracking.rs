@@ -0,0 +1,189 @@
+use kuchiki::NodeRef;
+use url::Url;
+
+use super::config::LinkCleanup;
+use super::dom;
+
+/// Query parameters that only identify a campaign, click, or share, matched
+/// case-insensitively. Any parameter starting with `utm_` is also removed.
+const TRACKING_PARAMETERS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "ttclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+    "ref_src",
+];
+
+/// Hosts that bounce a click to another URL: the host, the path (empty for
+/// any), and the query parameter holding the destination.
+const REDIRECTORS: &[(&str, &str, &str)] = &[
+    ("l.facebook.com", "/l.php", "u"),
+    ("lm.facebook.com", "/l.php", "u"),
+    ("l.messenger.com", "/l.php", "u"),
+    ("l.instagram.com", "/", "u"),
+    ("www.google.com", "/url", "q"),
+    ("www.google.com", "/url", "url"),
+    ("news.google.com", "", "url"),
+    ("www.youtube.com", "/redirect", "q"),
+    ("out.reddit.com", "", "url"),
+    ("t.umblr.com", "/redirect", "z"),
+    ("away.vk.com", "/away.php", "to"),
+    ("slack-redir.net", "/link", "url"),
+];
+
+/// Redirectors wrapping redirectors are followed this many levels deep.
+const MAX_REDIRECTS: usize = 3;
+
+/// Rewrite the `http` and `https` links under `root` as `cleanup` asks. A
+/// link whose text is its own URL gets the new URL as text too.
+pub fn clean_links(root: &NodeRef, cleanup: LinkCleanup) {
+    if cleanup == LinkCleanup::None {
+        return;
+    }
+    for node in dom::select_nodes(root, "a[href], area[href]") {
+        let Some(href) = dom::attr(&node, "href") else {
+            continue;
+        };
+        let Some(cleaned) = clean_url(&href, cleanup) else {
+            continue;
+        };
+        if let Some(text) = node
+            .first_child()
+            .filter(|child| child.next_sibling().is_none())
+            .and_then(|child| child.into_text_ref())
+            && text.borrow().trim() == href
+        {
+            *text.borrow_mut() = cleaned.clone();
+        }
+        dom::set_attr(&node, "href", &cleaned);
+    }
+}
+
+/// The cleaned form of `url`, or `None` when it is not an `http` or `https`
+/// URL or nothing changed.
+fn clean_url(url: &str, cleanup: LinkCleanup) -> Option<String> {
+    let mut url = Url::parse(url).ok().filter(is_web)?;
+    let mut changed = false;
+    if cleanup == LinkCleanup::UnwrapRedirects {
+        for _ in 0..MAX_REDIRECTS {
+            let Some(target) = redirect_target(&url) else {
+                break;
+            };
+            url = target;
+            changed = true;
+        }
+    }
+    changed |= strip_tracking(&mut url);
+    changed.then(|| url.into())
+}
+
+fn is_web(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+fn redirect_target(url: &Url) -> Option<Url> {
+    let host = url.host_str()?;
+    REDIRECTORS
+        .iter()
+        .filter(|(redirector, path, _)| *redirector == host && (path.is_empty() || url.path() == *path))
+        .find_map(|(_, _, parameter)| {
+            url.query_pairs()
+                .find(|(name, _)| name == parameter)
+                .map(|(_, value)| value.into_owned())
+        })
+        .and_then(|target| Url::parse(&target).ok())
+        .filter(is_web)
+}
+
+/// Drop tracking parameters from the query, leaving the encoding of the
+/// others untouched. Returns whether any were removed.
+fn strip_tracking(url: &mut Url) -> bool {
+    let Some(query) = url.query().map(str::to_string) else {
+        return false;
+    };
+    let pairs: Vec<&str> = query.split('&').filter(|pair| !pair.is_empty()).collect();
+    let kept: Vec<&str> = pairs
+        .iter()
+        .copied()
+        .filter(|pair| !is_tracking(pair.split('=').next().unwrap_or_default()))
+        .collect();
+    if kept.len() == pairs.len() {
+        return false;
+    }
+    let kept = kept.join("&");
+    url.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    true
+}
+
+fn is_tracking(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMETERS.contains(&name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_tracking_parameters_and_unwraps_redirectors() {
+        let strip = |url: &str| clean_url(url, LinkCleanup::StripTracking);
+        let unwrap = |url: &str| clean_url(url, LinkCleanup::UnwrapRedirects);
+
+        assert_eq!(
+            strip("https://example.com/tides?id=7&utm_source=feed&UTM_Medium=rss&fbclid=abc#notes").as_deref(),
+            Some("https://example.com/tides?id=7#notes")
+        );
+        assert_eq!(
+            strip("https://example.com/tides?gclid=1").as_deref(),
+            Some("https://example.com/tides")
+        );
+        assert_eq!(strip("https://example.com/tides?q=a%20b&page=2"), None);
+        assert_eq!(strip("mailto:harbour@example.com?utm_source=x"), None);
+        assert_eq!(
+            strip("https://l.facebook.com/l.php?u=https%3A%2F%2Fexample.com%2Ftides&h=AT0").as_deref(),
+            None
+        );
+
+        assert_eq!(
+            unwrap("https://l.facebook.com/l.php?u=https%3A%2F%2Fexample.com%2Ftides%3Futm_campaign%3Dx&h=AT0")
+                .as_deref(),
+            Some("https://example.com/tides")
+        );
+        assert_eq!(
+            unwrap("https://www.google.com/url?sa=t&url=https%3A%2F%2Fexample.com%2Fa").as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(unwrap("https://www.google.com/url?q=javascript%3Aalert(1)"), None);
+    }
+
+    #[test]
+    fn rewrites_bare_url_link_text() {
+        use kuchiki::traits::TendrilSink;
+
+        let document = kuchiki::parse_html().one(
+            r#"<p><a href="https://example.com/a?utm_source=x">https://example.com/a?utm_source=x</a>
+            <a href="https://example.com/b?fbclid=1">the harbour report</a></p>"#,
+        );
+        clean_links(&document, LinkCleanup::StripTracking);
+
+        let links = dom::select_nodes(&document, "a");
+        assert_eq!(dom::attr(&links[0], "href").as_deref(), Some("https://example.com/a"));
+        assert_eq!(dom::inner_text(&links[0]), "https://example.com/a");
+        assert_eq!(dom::attr(&links[1], "href").as_deref(), Some("https://example.com/b"));
+        assert_eq!(dom::inner_text(&links[1]), "the harbour report");
+    }
+}
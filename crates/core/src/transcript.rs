@@ -0,0 +1,311 @@
+use kuchiki::NodeRef;
+use kuchiki::traits::TendrilSink;
+use serde_json::Value;
+
+use super::config::ExtractionStrategy;
+use super::regexes::RegexPattern;
+use super::{cleanup, dom, json_schema, shared};
+
+/// Shortest transcript text worth extracting.
+const MIN_TRANSCRIPT_TEXT: usize = 200;
+
+/// A nested marked element replaces its container when it holds this share
+/// of the container's text.
+const INNER_TEXT_SHARE: f64 = 0.9;
+
+/// Schema.org types whose `transcript` belongs to a recording.
+const MEDIA_TYPES: &[&str] = &[
+    "VideoObject",
+    "AudioObject",
+    "MediaObject",
+    "Clip",
+    "Episode",
+    "PodcastEpisode",
+    "RadioEpisode",
+    "TVEpisode",
+];
+
+/// Hosts of embedded podcast and audio players.
+const AUDIO_HOSTS: &[&str] = &[
+    "soundcloud.com",
+    "open.spotify.com",
+    "megaphone.fm",
+    "libsyn.com",
+    "simplecast.com",
+    "buzzsprout.com",
+    "podbean.com",
+    "art19.com",
+    "omny.fm",
+    "transistor.fm",
+];
+
+/// Page chrome left out when measuring the prose around a transcript.
+const CHROME: &str = "nav p, header p, footer p, aside p";
+
+/// An `<article>` holding the page's transcript.
+///
+/// The transcript is the element whose class, id, or `aria-label` names a
+/// transcript, else the section under a "Transcript" heading or `<summary>`,
+/// else the schema.org `transcript` of a video, audio clip, or episode.
+/// `document` should keep hidden elements, since transcripts are often
+/// collapsed. With the auto strategy the transcript is only used on player
+/// pages whose other prose is shorter than `char_threshold`.
+pub fn transcript_root(
+    html: &str, document: &NodeRef, strategy: ExtractionStrategy, char_threshold: usize,
+) -> Option<NodeRef> {
+    let transcript = marked_transcript(document)
+        .or_else(|| headed_transcript(document))
+        .or_else(|| schema_transcript(html))?;
+    strip_transcript_chrome(&transcript);
+    if dom::inner_text(&transcript).chars().count() < MIN_TRANSCRIPT_TEXT {
+        return None;
+    }
+    if strategy == ExtractionStrategy::Auto
+        && !(is_player_page(html, document)
+            && prose_len(document).saturating_sub(prose_len(&transcript)) < char_threshold)
+    {
+        return None;
+    }
+
+    let page = kuchiki::parse_html().one("<html><body><article></article></body></html>");
+    let root = dom::select_first(&page, "article")?;
+    for child in transcript.children().collect::<Vec<_>>() {
+        root.append(child);
+    }
+    Some(root)
+}
+
+/// A copy of the innermost element marked as a transcript that still holds
+/// nearly all of the longest one's text.
+fn marked_transcript(document: &NodeRef) -> Option<NodeRef> {
+    let marked: Vec<(NodeRef, usize)> = dom::select_iter(document, "[class], [id], [aria-label]")
+        .filter(|node| {
+            !matches!(
+                dom::node_name(node).as_str(),
+                "a" | "button" | "script" | "style" | "template"
+            )
+        })
+        .filter(|node| {
+            let label = dom::attr(node, "aria-label").unwrap_or_default();
+            let marker = RegexPattern::TranscriptMarker.to_regex();
+            marker.is_match(&dom::class_id_string(node)) || marker.is_match(&label)
+        })
+        .map(|node| {
+            let len = dom::inner_text(&node).chars().count();
+            (node, len)
+        })
+        .filter(|(_, len)| *len >= MIN_TRANSCRIPT_TEXT)
+        .collect();
+
+    let mut best = marked
+        .iter()
+        .fold(None::<&(NodeRef, usize)>, |best, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })?;
+    while let Some(inner) = marked.iter().find(|(node, len)| {
+        node != &best.0
+            && node.ancestors().any(|ancestor| ancestor == best.0)
+            && *len as f64 >= best.1 as f64 * INNER_TEXT_SHARE
+    }) {
+        best = inner;
+    }
+    Some(dom::deep_clone(&best.0))
+}
+
+/// The content under a heading or `<summary>` reading "Transcript".
+fn headed_transcript(document: &NodeRef) -> Option<NodeRef> {
+    let heading = dom::select_iter(document, "h2, h3, h4, h5, h6, summary, strong, b").find(|node| {
+        RegexPattern::TranscriptHeading
+            .to_regex()
+            .is_match(&dom::inner_text(node))
+    })?;
+    let name = dom::node_name(&heading);
+    if name == "summary" {
+        return heading.parent().map(|details| dom::deep_clone(&details));
+    }
+
+    // Bold labels sit inside a paragraph; the transcript follows that block.
+    let start = match name.as_str() {
+        "strong" | "b" => heading
+            .parent()
+            .filter(|parent| dom::inner_text(parent) == dom::inner_text(&heading))?,
+        _ => heading,
+    };
+    let level = heading_level(&start);
+    let section = dom::new_element("div")?;
+    let mut sibling = start.next_sibling();
+    while let Some(node) = sibling {
+        sibling = node.next_sibling();
+        if heading_level(&node).is_some_and(|next| level.is_none_or(|level| next <= level)) {
+            break;
+        }
+        section.append(dom::deep_clone(&node));
+    }
+    Some(section)
+}
+
+fn heading_level(node: &NodeRef) -> Option<u8> {
+    match dom::node_name(node).as_str() {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// The `transcript` of a schema.org recording, as paragraphs.
+fn schema_transcript(html: &str) -> Option<NodeRef> {
+    if !html.contains("transcript") {
+        return None;
+    }
+    let blocks = json_schema::json_ld_blocks(html);
+    let text = blocks.iter().find_map(media_transcript)?;
+    let separator = if text.contains("\n\n") { "\n\n" } else { "\n" };
+    let markup: String = text
+        .split(separator)
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", shared::escape_html(paragraph)))
+        .collect();
+    let fragment = kuchiki::parse_html().one(format!("<html><body><div>{markup}</div></body></html>"));
+    dom::select_first(&fragment, "body > div")
+}
+
+fn media_transcript(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(items) => items.iter().find_map(media_transcript),
+        Value::Object(map) => {
+            let types = json_schema::json_ld_types(map.get("@type"));
+            if types.iter().any(|kind| MEDIA_TYPES.contains(kind))
+                && let Some(transcript) = json_schema::string_field(value, "transcript")
+            {
+                return Some(transcript);
+            }
+            ["@graph", "mainEntity", "video", "audio", "associatedMedia"]
+                .iter()
+                .filter_map(|field| map.get(*field))
+                .find_map(media_transcript)
+        }
+        _ => None,
+    }
+}
+
+/// Remove the transcript's own title and its show/hide controls.
+fn strip_transcript_chrome(transcript: &NodeRef) {
+    for node in dom::select_nodes(transcript, "summary, button, [role=\"button\"], h2, h3, h4, h5, h6") {
+        let text = dom::inner_text(&node);
+        if matches!(dom::node_name(&node).as_str(), "summary" | "button")
+            || dom::attr(&node, "role").is_some()
+            || RegexPattern::TranscriptHeading.to_regex().is_match(&text)
+        {
+            node.detach();
+        }
+    }
+}
+
+/// Whether the page is built around a video or audio player.
+fn is_player_page(html: &str, document: &NodeRef) -> bool {
+    let embedded = dom::select_iter(document, "iframe[src]").any(|iframe| {
+        let src = dom::attr(&iframe, "src").unwrap_or_default();
+        cleanup::allowed_video(&src) || AUDIO_HOSTS.iter().any(|host| src.contains(host))
+    });
+    let schema = MEDIA_TYPES.iter().any(|kind| html.contains(&format!("\"{kind}\"")));
+    embedded || schema || dom::exists(document, "video, audio")
+}
+
+/// Characters of paragraph text outside page chrome.
+fn prose_len(root: &NodeRef) -> usize {
+    let chrome = dom::select_nodes(root, CHROME);
+    dom::select_iter(root, "p")
+        .filter(|paragraph| !chrome.contains(paragraph))
+        .map(|paragraph| dom::inner_text(&paragraph).chars().count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadabilityOptions, extract};
+
+    const LINES: &[&str] = &[
+        "Welcome back to the show. Today we are talking about tides and the moon.",
+        "Thanks for having me. I have studied coastal flooding for twenty years now.",
+        "So what actually causes a king tide, and why are they getting worse?",
+        "The moon and sun line up, and rising seas raise the baseline every year.",
+    ];
+
+    fn lines() -> String {
+        LINES.iter().map(|line| format!("<p>{line}</p>")).collect()
+    }
+
+    fn options(strategy: ExtractionStrategy) -> ReadabilityOptions {
+        ReadabilityOptions { strategy, char_threshold: 200, ..Default::default() }
+    }
+
+    #[test]
+    fn extracts_collapsed_transcript_from_player_page() {
+        let html = format!(
+            r#"<html><body><nav><a href="/">Home</a></nav><h1>Episode 12: King tides</h1>
+            <audio controls src="/ep12.mp3"></audio><p>Listen now.</p>
+            <section aria-label="Episode transcript"><button>Show transcript</button>
+            <div class="transcript-body" hidden>{}</div></section></body></html>"#,
+            lines()
+        );
+
+        let article = extract(&html, None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+
+        assert!(article.text_content.contains("why are they getting worse?"));
+        assert!(article.markdown.starts_with("Welcome back to the show."));
+        assert!(!article.text_content.contains("Show transcript"));
+        assert!(!article.text_content.contains("Listen now."));
+    }
+
+    #[test]
+    fn auto_strategy_keeps_articles_with_a_transcript_appendix() {
+        let story: String = (0..6)
+            .map(|index| format!("<p>Paragraph {index} of the written story explains the flooding in some detail.</p>"))
+            .collect();
+        let html = format!(
+            r#"<html><body><article><h1>King tides</h1><video src="/clip.mp4"></video>{story}
+            <h2>Transcript</h2>{}</article></body></html>"#,
+            lines()
+        );
+
+        let auto = extract(&html, None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+        let transcript = extract(&html, None, &options(ExtractionStrategy::Transcript))
+            .unwrap()
+            .unwrap();
+
+        assert!(auto.text_content.contains("Paragraph 0 of the written story"));
+        assert!(transcript.markdown.starts_with("Welcome back to the show."));
+        assert!(!transcript.text_content.contains("Paragraph 0"));
+    }
+
+    #[test]
+    fn reads_schema_org_transcript() {
+        let html = format!(
+            r#"<html><head><script type="application/ld+json">{{
+                "@context": "https://schema.org",
+                "@type": "VideoObject",
+                "name": "King tides explained",
+                "transcript": "{}"
+            }}</script></head><body><h1>King tides explained</h1><div id="player"></div></body></html>"#,
+            LINES.join("\\n\\n")
+        );
+
+        let article = extract(&html, None, &options(ExtractionStrategy::Auto))
+            .unwrap()
+            .unwrap();
+
+        assert!(article.text_content.contains("I have studied coastal flooding"));
+        assert_eq!(article.markdown.matches("\n\n").count(), LINES.len() - 1);
+    }
+}
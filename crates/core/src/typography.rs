@@ -0,0 +1,140 @@
+use kuchiki::NodeRef;
+use unicode_normalization::UnicodeNormalization;
+
+use super::config::{ReadabilityOptions, Typography};
+use super::{dom, metadata};
+
+/// Elements whose text is code or markup and must keep its characters.
+const LITERAL_TAGS: &[&str] = &["pre", "code", "kbd", "samp", "var", "tt", "script", "style", "textarea"];
+
+/// Invisible characters that split words without showing anything: zero-width
+/// space, word joiner, byte-order mark, and soft hyphen.
+const INVISIBLE: &[char] = &['\u{200b}', '\u{2060}', '\u{feff}', '\u{ad}'];
+
+/// Characters after which a quote opens rather than closes.
+const OPENERS: &str = "([{<—–-/";
+
+/// Apply `normalize_text` and `typography` to the text of article roots.
+pub fn normalize_text(roots: &[NodeRef], options: &ReadabilityOptions) {
+    if !options.normalize_text && options.typography == Typography::Preserve {
+        return;
+    }
+
+    for root in roots {
+        // Quotes open or close depending on the text before them, which may
+        // end in an earlier node, as in `"<em>Dune</em>"`.
+        let mut previous = None;
+        for node in root.descendants() {
+            let Some(text) = node.as_text() else {
+                continue;
+            };
+            if is_literal(&node) {
+                previous = text.borrow().chars().last();
+                continue;
+            }
+            let mut value = text.borrow().clone();
+            if options.normalize_text {
+                value = clean(&value);
+            }
+            value = match options.typography {
+                Typography::Preserve => value,
+                Typography::Smart => smarten(&value, previous),
+                Typography::Plain => plain(&value),
+            };
+            if let Some(last) = value.chars().last() {
+                previous = Some(last);
+            }
+            *text.borrow_mut() = value;
+        }
+    }
+}
+
+fn is_literal(node: &NodeRef) -> bool {
+    node.ancestors()
+        .any(|ancestor| LITERAL_TAGS.contains(&dom::node_name(&ancestor).as_str()))
+}
+
+/// Decode entities left in the text by double-escaped markup, compose
+/// characters to NFC, and drop invisible word breakers.
+fn clean(text: &str) -> String {
+    let decoded = if text.contains('&') { metadata::decode_html_entities(text) } else { text.to_string() };
+    decoded.nfc().filter(|c| !INVISIBLE.contains(c)).collect()
+}
+
+fn smarten(text: &str, previous: Option<char>) -> String {
+    let text = text.replace("...", "…").replace("---", "—").replace("--", "—");
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut before = previous;
+    for (index, &c) in chars.iter().enumerate() {
+        let after = chars.get(index + 1).copied();
+        let opens = before.is_none_or(|before| before.is_whitespace() || OPENERS.contains(before))
+            && after.is_some_and(|after| !after.is_whitespace());
+        let curled = match c {
+            '"' if opens => '“',
+            '"' => '”',
+            // A leading apostrophe before a digit is an elision, as in '90s.
+            '\'' if opens && !after.is_some_and(|after| after.is_ascii_digit()) => '‘',
+            '\'' => '’',
+            _ => c,
+        };
+        output.push(curled);
+        before = Some(c);
+    }
+    output
+}
+
+fn plain(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '‘' | '’' | '‚' | '‛' | '′' => output.push('\''),
+            '“' | '”' | '„' | '‟' | '″' => output.push('"'),
+            '—' | '―' => output.push_str("--"),
+            '–' | '‐' | '‑' | '−' => output.push('-'),
+            '…' => output.push_str("..."),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract;
+
+    fn article(html: &str, normalize_text: bool, typography: Typography) -> crate::Article {
+        let options = ReadabilityOptions { char_threshold: 0, normalize_text, typography, ..Default::default() };
+        extract(html, None, &options).unwrap().unwrap()
+    }
+
+    #[test]
+    fn cleans_entities_combining_marks_and_invisible_characters() {
+        let html = "<article><p>Caf\u{65}\u{301} co\u{ad}op\u{200b}erative &amp;amp; friends</p></article>";
+
+        let article = article(html, true, Typography::Preserve);
+
+        assert_eq!(article.text_content.trim(), "Café cooperative & friends");
+    }
+
+    #[test]
+    fn curls_quotes_and_dashes_outside_code() {
+        let html = r#"<article><p>She said "it's <em>fine</em>" -- in the '90s... 'really'.</p><pre><code>let s = "x--y";</code></pre></article>"#;
+
+        let smart = article(html, false, Typography::Smart);
+        let plain = article(
+            "<article><p>“It’s fine” — in the ’90s… – ok</p></article>",
+            false,
+            Typography::Plain,
+        );
+
+        assert!(
+            smart
+                .text_content
+                .contains("She said “it’s fine” — in the ’90s… ‘really’.")
+        );
+        assert!(smart.text_content.contains(r#"let s = "x--y";"#));
+        assert!(plain.text_content.contains(r#""It's fine" -- in the '90s... - ok"#));
+    }
+}
@@ -146,7 +146,7 @@ struct ToolContent {
 #[serde(untagged)]
 enum ToolStructuredContent {
     SearchResults { results: Vec<SearchResult> },
-    Article { article: ReadArticleOutput },
+    Article { article: Box<ReadArticleOutput> },
     Error { error: String },
 }
 
@@ -216,7 +216,7 @@ struct RpcError {
 }
 
 impl RpcError {
-    fn method_not_found(method: String) -> Self {
+    fn method_not_found(method: &str) -> Self {
         Self { code: -32601, message: format!("method not found: {method}") }
     }
 
@@ -326,7 +326,7 @@ impl Server {
                 tools: vec![self.search_articles_tool(), self.read_article_tool()],
             })),
             "tools/call" => self.call_tool(request.params).await.map(McpResult::Tool),
-            _ => Err(RpcError::method_not_found(request.method)),
+            _ => Err(RpcError::method_not_found(&request.method)),
         }
     }
 
@@ -437,7 +437,7 @@ impl Server {
         match self.read_article_output(&arguments, max_chars).await {
             Ok(article) => Ok(ToolResult::new(
                 article.content.clone(),
-                ToolStructuredContent::Article { article },
+                ToolStructuredContent::Article { article: Box::new(article) },
                 false,
             )),
             Err(error) => Ok(ToolResult::new(
@@ -21,7 +21,7 @@ impl TestServer {
         thread::spawn(move || {
             for stream in listener.incoming().flatten() {
                 let handler = Arc::clone(&handler);
-                thread::spawn(move || handle_connection(stream, handler));
+                thread::spawn(move || handle_connection(stream, handler.as_ref()));
             }
         });
 
@@ -101,17 +101,19 @@ fn run_mcp(lines: &[String], envs: &[(&str, &str)]) -> Vec<Value> {
 }
 
 fn request(id: impl Into<Value>, method: &str, params: Value) -> String {
-    json!({
+    let mut request = json!({
         "jsonrpc": "2.0",
         "id": id.into(),
         "method": method,
-        "params": params,
-    })
-    .to_string()
+    });
+    request["params"] = params;
+    request.to_string()
 }
 
 fn tool_call(id: impl Into<Value>, name: &str, arguments: Value) -> String {
-    request(id, "tools/call", json!({ "name": name, "arguments": arguments }))
+    let mut params = json!({ "name": name });
+    params["arguments"] = arguments;
+    request(id, "tools/call", params)
 }
 
 fn article(response: &Value) -> &Value {
@@ -152,7 +154,7 @@ fn article_html() -> String {
     )
 }
 
-fn handle_connection(stream: TcpStream, handler: Arc<dyn Fn(String) -> TestResponse + Send + Sync>) {
+fn handle_connection(stream: TcpStream, handler: &(dyn Fn(String) -> TestResponse + Send + Sync)) {
     let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
     let mut request_line = String::new();
     if reader.read_line(&mut request_line).is_err() {
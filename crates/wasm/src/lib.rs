@@ -2,7 +2,10 @@
 
 use std::sync::Once;
 
-use lectito::{MarkdownOptions, MediaRetention, ReadabilityOptions, ReadableOptions};
+use lectito::{
+    ExtractionStrategy, FootnoteMode, LinkCleanup, LinkStyle, MarkdownOptions, MediaRetention, PatternGroup,
+    ReadabilityOptions, ReadableOptions, Typography,
+};
 use serde::Deserialize;
 use wasm_bindgen::prelude::*;
 
@@ -69,6 +72,13 @@ pub fn markdown_to_html_js(markdown: &str, options: Option<JsValue>) -> Result<S
     Ok(lectito::markdown_to_html(markdown, &options))
 }
 
+/// Reports the optional features and output formats compiled into Lectito.
+#[wasm_bindgen(js_name = capabilities, skip_typescript)]
+pub fn capabilities_js() -> Result<JsValue, JsValue> {
+    init();
+    serde_wasm_bindgen::to_value(&lectito::capabilities()).map_err(js_error)
+}
+
 fn init() {
     INIT.call_once(console_error_panic_hook::set_once);
 }
@@ -113,12 +123,39 @@ struct ReadabilityOptionsDto {
     char_threshold: Option<usize>,
     content_selector: Option<Option<String>>,
     site_profiles: Option<Vec<String>>,
+    site_profile_overrides: Option<Vec<String>>,
+    site_profile_ignores: Option<Vec<String>>,
     mobile_viewport_width: Option<Option<usize>>,
     classes_to_preserve: Option<Vec<String>>,
     keep_classes: Option<bool>,
     disable_json_ld: Option<bool>,
     link_density_modifier: Option<f32>,
     media_retention: Option<MediaRetention>,
+    iframe_allowlist: Option<Vec<String>>,
+    footnotes: Option<FootnoteMode>,
+    link_style: Option<LinkStyle>,
+    link_cleanup: Option<LinkCleanup>,
+    heading_offset: Option<Option<u8>>,
+    normalize_heading_levels: Option<bool>,
+    reading_time_markers: Option<bool>,
+    normalize_text: Option<bool>,
+    typography: Option<Typography>,
+    strategy: Option<ExtractionStrategy>,
+    cleanup_dry_run: Option<bool>,
+    keep_hidden: Option<bool>,
+    keep_unlikely_candidates: Option<bool>,
+    keep_iframes: Option<bool>,
+    sibling_threshold: Option<f32>,
+    min_candidate_score: Option<f32>,
+    class_weight: Option<i32>,
+    positive_patterns: Option<Vec<String>>,
+    negative_patterns: Option<Vec<String>>,
+    unlikely_patterns: Option<Vec<String>>,
+    trailing_chrome_patterns: Option<Vec<String>>,
+    doc_chrome_labels: Option<Vec<String>>,
+    replace_pattern_groups: Option<Vec<PatternGroup>>,
+    image_target_width: Option<Option<u32>>,
+    recover_noscript: Option<bool>,
 }
 
 impl ReadabilityOptionsDto {
@@ -139,6 +176,12 @@ impl ReadabilityOptionsDto {
         if let Some(value) = self.site_profiles {
             options.site_profiles = value;
         }
+        if let Some(value) = self.site_profile_overrides {
+            options.site_profile_overrides = value;
+        }
+        if let Some(value) = self.site_profile_ignores {
+            options.site_profile_ignores = value;
+        }
         if let Some(value) = self.mobile_viewport_width {
             options.mobile_viewport_width = value;
         }
@@ -157,6 +200,81 @@ impl ReadabilityOptionsDto {
         if let Some(value) = self.media_retention {
             options.media_retention = value;
         }
+        if let Some(value) = self.iframe_allowlist {
+            options.iframe_allowlist = value;
+        }
+        if let Some(value) = self.footnotes {
+            options.footnotes = value;
+        }
+        if let Some(value) = self.link_style {
+            options.link_style = value;
+        }
+        if let Some(value) = self.link_cleanup {
+            options.link_cleanup = value;
+        }
+        if let Some(value) = self.heading_offset {
+            options.heading_offset = value;
+        }
+        if let Some(value) = self.normalize_heading_levels {
+            options.normalize_heading_levels = value;
+        }
+        if let Some(value) = self.reading_time_markers {
+            options.reading_time_markers = value;
+        }
+        if let Some(value) = self.normalize_text {
+            options.normalize_text = value;
+        }
+        if let Some(value) = self.typography {
+            options.typography = value;
+        }
+        if let Some(value) = self.strategy {
+            options.strategy = value;
+        }
+        if let Some(value) = self.cleanup_dry_run {
+            options.cleanup_dry_run = value;
+        }
+        if let Some(value) = self.keep_hidden {
+            options.keep_hidden = value;
+        }
+        if let Some(value) = self.keep_unlikely_candidates {
+            options.keep_unlikely_candidates = value;
+        }
+        if let Some(value) = self.keep_iframes {
+            options.keep_iframes = value;
+        }
+        if let Some(value) = self.sibling_threshold {
+            options.sibling_threshold = value;
+        }
+        if let Some(value) = self.min_candidate_score {
+            options.min_candidate_score = value;
+        }
+        if let Some(value) = self.class_weight {
+            options.class_weight = value;
+        }
+        if let Some(value) = self.positive_patterns {
+            options.positive_patterns = value;
+        }
+        if let Some(value) = self.negative_patterns {
+            options.negative_patterns = value;
+        }
+        if let Some(value) = self.unlikely_patterns {
+            options.unlikely_patterns = value;
+        }
+        if let Some(value) = self.trailing_chrome_patterns {
+            options.trailing_chrome_patterns = value;
+        }
+        if let Some(value) = self.doc_chrome_labels {
+            options.doc_chrome_labels = value;
+        }
+        if let Some(value) = self.replace_pattern_groups {
+            options.replace_pattern_groups = value;
+        }
+        if let Some(value) = self.image_target_width {
+            options.image_target_width = value;
+        }
+        if let Some(value) = self.recover_noscript {
+            options.recover_noscript = value;
+        }
         options
     }
 }
@@ -1,4 +1,10 @@
 export type MediaRetention = "none" | "conservative" | "article" | "all";
+export type FootnoteMode = "markdown" | "strip";
+export type LinkStyle = "inline" | "reference" | "footnotes";
+export type LinkCleanup = "none" | "strip-tracking" | "unwrap-redirects";
+export type Typography = "preserve" | "smart" | "plain";
+export type ExtractionStrategy = "auto" | "full-body-cleanup" | "live-blog" | "transcript" | "density";
+export type PatternGroup = "positive" | "negative" | "unlikely" | "trailing-chrome" | "doc-chrome";
 
 export interface ReadabilityOptions {
   maxElemsToParse?: number | null;
@@ -6,12 +12,39 @@ export interface ReadabilityOptions {
   charThreshold?: number;
   contentSelector?: string | null;
   siteProfiles?: string[];
+  siteProfileOverrides?: string[];
+  siteProfileIgnores?: string[];
   mobileViewportWidth?: number | null;
   classesToPreserve?: string[];
   keepClasses?: boolean;
   disableJsonLd?: boolean;
   linkDensityModifier?: number;
   mediaRetention?: MediaRetention;
+  iframeAllowlist?: string[];
+  footnotes?: FootnoteMode;
+  linkStyle?: LinkStyle;
+  linkCleanup?: LinkCleanup;
+  headingOffset?: number | null;
+  normalizeHeadingLevels?: boolean;
+  readingTimeMarkers?: boolean;
+  normalizeText?: boolean;
+  typography?: Typography;
+  strategy?: ExtractionStrategy;
+  cleanupDryRun?: boolean;
+  keepHidden?: boolean;
+  keepUnlikelyCandidates?: boolean;
+  keepIframes?: boolean;
+  siblingThreshold?: number;
+  minCandidateScore?: number;
+  classWeight?: number;
+  positivePatterns?: string[];
+  negativePatterns?: string[];
+  unlikelyPatterns?: string[];
+  trailingChromePatterns?: string[];
+  docChromeLabels?: string[];
+  replacePatternGroups?: PatternGroup[];
+  imageTargetWidth?: number | null;
+  recoverNoscript?: boolean;
 }
 
 export interface ReadableOptions {
@@ -30,7 +63,9 @@ export type CleanHtmlOptions = ReadabilityOptions;
 
 export interface Article {
   title?: string | null;
+  raw_title?: string | null;
   byline?: string | null;
+  author_bio?: string | null;
   dir?: string | null;
   lang?: string | null;
   content: string;
@@ -40,9 +75,36 @@ export interface Article {
   excerpt?: string | null;
   site_name?: string | null;
   published_time?: string | null;
+  modified_time?: string | null;
+  published?: string | null;
+  modified?: string | null;
   image?: string | null;
   domain?: string | null;
   favicon?: string | null;
+  canonical_url?: string | null;
+  amp_url?: string | null;
+  keywords: string[];
+  section?: string | null;
+  is_paywalled?: boolean | null;
+  is_sponsored?: boolean | null;
+  links: ArticleLink[];
+  stats: ExtractionStats;
+}
+
+export interface ArticleLink {
+  text: string;
+  url: string;
+  rel: string[];
+  kind: "content" | "related";
+}
+
+export interface ExtractionStats {
+  input_bytes: number;
+  preprocessed_bytes: number;
+  candidates_scanned: number;
+  candidates_scored: number;
+  removed_elements: number;
+  timings: { parse_ms: number; extract_ms: number; postprocess_ms: number };
 }
 
 export interface ExtractionReport {
@@ -50,6 +112,17 @@ export interface ExtractionReport {
   diagnostics: unknown;
 }
 
+export interface Capabilities {
+  bundled_configs: boolean;
+  schema: boolean;
+  textstats: boolean;
+  tokio: boolean;
+  webarchive: boolean;
+  output_formats: string[];
+}
+
+export function capabilities(): Capabilities;
+
 export function cleanHtml(html: string, baseUrl?: string | null, options?: CleanHtmlOptions | null): string | null;
 
 export function extract(html: string, baseUrl?: string | null, options?: ReadabilityOptions | null): Article | null;